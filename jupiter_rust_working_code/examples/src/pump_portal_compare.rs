@@ -7,11 +7,13 @@
 //! - Jito vs non-Jito routing
 //! - Different priority fees
 //!
-//! Tracks: Speed, success rate, actual slippage
+//! - Auto pool selection
+//!
+//! Tracks: Speed, success rate, actual slippage, pool that filled
 
 use pump_portal_sdk::{PumpPortalClient, Pool, TradeRequest};
 use std::env;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 #[derive(Debug)]
 struct TestResult {
@@ -19,6 +21,7 @@ struct TestResult {
     success: bool,
     duration_ms: u128,
     signature: Option<String>,
+    pool: Option<Pool>,
     error: Option<String>,
 }
 
@@ -35,6 +38,10 @@ impl TestResult {
                 println!("📝 Signature: {}", sig);
                 println!("🔗 Explorer: https://solscan.io/tx/{}", sig);
             }
+            match &self.pool {
+                Some(pool) => println!("🏊 Filled on: {:?}", pool),
+                None => println!("🏊 Filled on: unknown"),
+            }
         } else {
             println!("❌ FAILED");
             println!("⏱️  Duration: {} ms", self.duration_ms);
@@ -47,6 +54,7 @@ impl TestResult {
 
 async fn run_test(
     client: &PumpPortalClient,
+    rpc_url: &str,
     name: &str,
     request: TradeRequest,
 ) -> TestResult {
@@ -55,11 +63,23 @@ async fn run_test(
     match client.trade(request).await {
         Ok(response) => {
             let duration = start.elapsed().as_millis();
+
+            // The API rarely says where it routed; read it from the transaction
+            let pool = match (response.pool, &response.signature) {
+                (Some(pool), _) => Some(pool),
+                (None, Some(sig)) => client
+                    .pool_used(sig, rpc_url, Duration::from_secs(30))
+                    .await
+                    .unwrap_or(None),
+                (None, None) => None,
+            };
+
             TestResult {
                 name: name.to_string(),
                 success: response.signature.is_some(),
                 duration_ms: duration,
                 signature: response.signature,
+                pool,
                 error: response.error,
             }
         }
@@ -70,6 +90,7 @@ async fn run_test(
                 success: false,
                 duration_ms: duration,
                 signature: None,
+                pool: None,
                 error: Some(e.to_string()),
             }
         }
@@ -87,6 +108,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let token_mint = env::var("TOKEN_MINT")
         .expect("TOKEN_MINT must be set in .env file");
 
+    let rpc_url = env::var("SOLANA_RPC_URL")
+        .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string());
+
     let client = PumpPortalClient::new(api_key);
 
     println!("╔═══════════════════════════════════════════════╗");
@@ -96,49 +120,56 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("📊 Test Configuration:");
     println!("   Token: {}", token_mint);
     println!("   Test Amount: 0.001 SOL per test");
-    println!("   Total Tests: 6 different configurations");
+    println!("   Total Tests: 7 different configurations");
     println!();
 
     let test_amount = 0.001;
     let mut results = Vec::new();
 
     // Test 1: Standard buy (default pool, skip preflight)
-    println!("\n🧪 Running Test 1/6: Standard Buy (Default)...");
+    println!("\n🧪 Running Test 1/7: Standard Buy (Default)...");
     let test1 = TradeRequest::buy(token_mint.clone(), test_amount, 10, 0.0001);
-    results.push(run_test(&client, "Standard Buy - Default Pool", test1).await);
+    results.push(run_test(&client, &rpc_url, "Standard Buy - Default Pool", test1).await);
     tokio::time::sleep(std::time::Duration::from_secs(2)).await;
 
     // Test 2: High slippage (20%)
-    println!("\n🧪 Running Test 2/6: High Slippage (20%)...");
+    println!("\n🧪 Running Test 2/7: High Slippage (20%)...");
     let test2 = TradeRequest::buy(token_mint.clone(), test_amount, 20, 0.0001);
-    results.push(run_test(&client, "High Slippage (20%)", test2).await);
+    results.push(run_test(&client, &rpc_url, "High Slippage (20%)", test2).await);
     tokio::time::sleep(std::time::Duration::from_secs(2)).await;
 
     // Test 3: Raydium pool
-    println!("\n🧪 Running Test 3/6: Raydium Pool...");
+    println!("\n🧪 Running Test 3/7: Raydium Pool...");
     let test3 = TradeRequest::buy(token_mint.clone(), test_amount, 10, 0.0001)
         .with_pool(Pool::Raydium);
-    results.push(run_test(&client, "Raydium Pool", test3).await);
+    results.push(run_test(&client, &rpc_url, "Raydium Pool", test3).await);
     tokio::time::sleep(std::time::Duration::from_secs(2)).await;
 
     // Test 4: With preflight simulation
-    println!("\n🧪 Running Test 4/6: With Preflight Simulation...");
+    println!("\n🧪 Running Test 4/7: With Preflight Simulation...");
     let test4 = TradeRequest::buy(token_mint.clone(), test_amount, 10, 0.0001)
         .with_skip_preflight(false);
-    results.push(run_test(&client, "With Preflight Simulation", test4).await);
+    results.push(run_test(&client, &rpc_url, "With Preflight Simulation", test4).await);
     tokio::time::sleep(std::time::Duration::from_secs(2)).await;
 
     // Test 5: Jito-only routing
-    println!("\n🧪 Running Test 5/6: Jito-Only Routing...");
+    println!("\n🧪 Running Test 5/7: Jito-Only Routing...");
     let test5 = TradeRequest::buy(token_mint.clone(), test_amount, 10, 0.0001)
         .with_jito_only(true);
-    results.push(run_test(&client, "Jito-Only Routing", test5).await);
+    results.push(run_test(&client, &rpc_url, "Jito-Only Routing", test5).await);
     tokio::time::sleep(std::time::Duration::from_secs(2)).await;
 
     // Test 6: High priority fee
-    println!("\n🧪 Running Test 6/6: High Priority Fee (0.001 SOL)...");
+    println!("\n🧪 Running Test 6/7: High Priority Fee (0.001 SOL)...");
     let test6 = TradeRequest::buy(token_mint.clone(), test_amount, 10, 0.001);
-    results.push(run_test(&client, "High Priority Fee (0.001 SOL)", test6).await);
+    results.push(run_test(&client, &rpc_url, "High Priority Fee (0.001 SOL)", test6).await);
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+
+    // Test 7: Let PumpPortal pick the pool
+    println!("\n🧪 Running Test 7/7: Auto Pool...");
+    let test7 = TradeRequest::buy(token_mint.clone(), test_amount, 10, 0.0001)
+        .with_pool(Pool::Auto);
+    results.push(run_test(&client, &rpc_url, "Auto Pool", test7).await);
 
     // Display all results
     println!("\n\n");
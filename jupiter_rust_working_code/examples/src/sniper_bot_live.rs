@@ -33,8 +33,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .parse::<f64>()
         .expect("Invalid SNIPE_AMOUNT_SOL");
 
+    let min_sol_reserves = env::var("MIN_SOL_RESERVES")
+        .unwrap_or_else(|_| "0".to_string())
+        .parse::<f64>()
+        .expect("Invalid MIN_SOL_RESERVES");
+
     println!("⚙️  Configuration:");
     println!("   Trade Size: {} SOL (~${:.2} at $200/SOL)", trade_amount, trade_amount * 200.0);
+    println!("   Min Liquidity: {} SOL in curve", min_sol_reserves);
     println!("   Strategy: Fast in, smart exit");
     println!("   Exit Rules:");
     println!("      • No momentum (60s) → Fast exit 100%");
@@ -62,18 +68,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // For now, just show that launch detection works
     use pump_sniper_bot::launch_detector::{LaunchDetector, LaunchDetectorConfig};
 
-    let detector = LaunchDetector::new(LaunchDetectorConfig::default());
-    let mut launch_rx = detector.start_monitoring().await?;
+    let detector = LaunchDetector::new(LaunchDetectorConfig {
+        min_sol_reserves,
+        ..LaunchDetectorConfig::default()
+    });
+    let mut monitor = detector.start_monitoring().await?;
 
     info!("✅ Launch detector running");
     info!("👀 Monitoring for new pump.fun launches...\n");
 
     // Process launches
-    while let Some(launch) = launch_rx.recv().await {
+    while let Some(launch) = monitor.launches.recv().await {
         println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
         launch.display();
 
-        if launch.is_snipeable() {
+        if launch.is_snipeable(min_sol_reserves) {
             println!("\n💡 This token would be sniped automatically");
             println!("   Entry: {} SOL", trade_amount);
             println!("   Expected execution: ~700ms via PumpPortal");
@@ -84,5 +93,5 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         info!("👀 Monitoring for next launch...\n");
     }
 
-    Ok(())
+    monitor.join().await
 }
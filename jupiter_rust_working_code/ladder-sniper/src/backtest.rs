@@ -0,0 +1,400 @@
+use anyhow::{Context, Result};
+use duckdb::Connection;
+use log::{debug, info};
+use std::collections::BTreeMap;
+
+use pump_portal_sdk::exit::{self, VwapMomentum, STRATEGY_NAMES};
+use pump_portal_sdk::{ExitDecision, ExitReason, ExitStrategy, PositionState};
+
+/// One recorded `position_metrics` snapshot
+#[derive(Debug, Clone)]
+pub struct MetricPoint {
+    pub elapsed_secs: u64,
+    pub pnl_multiplier: f64,
+    pub vwap_distance_percent: f64,
+    pub momentum_score: f64,
+}
+
+/// Parameter set to replay the exit rules with
+#[derive(Debug, Clone)]
+pub struct BacktestParams {
+    pub momentum_threshold: f64,  // Momentum required at the first checkpoint
+    pub vwap_exit_deviation: f64, // Exit when price is this far below VWAP (0.05 = 5%)
+    pub checkpoint_secs: Vec<u64>, // Checkpoint times; each requires +10% more momentum
+}
+
+impl BacktestParams {
+    /// Build the exit strategy these parameters describe
+    pub fn strategy(&self) -> VwapMomentum {
+        let checkpoints = self
+            .checkpoint_secs
+            .iter()
+            .enumerate()
+            .map(|(i, secs)| (*secs, self.momentum_threshold + 0.1 * i as f64))
+            .collect();
+
+        VwapMomentum::default()
+            .with_vwap_exit(self.vwap_exit_deviation)
+            .with_checkpoints(checkpoints)
+    }
+}
+
+impl Default for BacktestParams {
+    fn default() -> Self {
+        Self {
+            momentum_threshold: 0.2,
+            vwap_exit_deviation: 0.05,
+            checkpoint_secs: vec![10, 20, 30, 45, 60],
+        }
+    }
+}
+
+/// Outcome of replaying one position
+#[derive(Debug, Clone)]
+pub struct TradeOutcome {
+    pub position_id: String,
+    pub pnl_percent: f64,
+    pub exit_secs: u64,
+    /// `None` when the data ran out before the strategy sold out
+    pub exit_reason: Option<ExitReason>,
+    pub exit_detail: String,
+}
+
+/// Aggregate result for one parameter set
+#[derive(Debug, Clone)]
+pub struct BacktestReport {
+    pub params: BacktestParams,
+    pub trades: usize,
+    pub wins: usize,
+    pub avg_pnl_percent: f64,
+    pub total_pnl_percent: f64,
+    pub outcomes: Vec<TradeOutcome>,
+}
+
+impl BacktestReport {
+    pub fn win_rate(&self) -> f64 {
+        if self.trades == 0 {
+            0.0
+        } else {
+            self.wins as f64 / self.trades as f64
+        }
+    }
+}
+
+/// Load `position_metrics` grouped by position, ordered by time
+pub fn load_duckdb(db_path: &str) -> Result<BTreeMap<String, Vec<MetricPoint>>> {
+    let conn = Connection::open(db_path)?;
+    let mut stmt = conn.prepare(
+        "SELECT position_id, elapsed_secs, pnl_multiplier, vwap_distance_percent, momentum_score
+         FROM position_metrics
+         ORDER BY position_id, timestamp_micros"
+    )?;
+
+    let mut series: BTreeMap<String, Vec<MetricPoint>> = BTreeMap::new();
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let position_id: String = row.get(0)?;
+        series.entry(position_id).or_default().push(MetricPoint {
+            elapsed_secs: row.get::<_, i64>(1)?.max(0) as u64,
+            pnl_multiplier: row.get(2)?,
+            vwap_distance_percent: row.get(3)?,
+            momentum_score: row.get(4)?,
+        });
+    }
+
+    Ok(series)
+}
+
+/// Load a CSV export of `position_metrics` (header row required)
+/// Rows are expected in time order within each position
+pub fn load_csv(path: &str) -> Result<BTreeMap<String, Vec<MetricPoint>>> {
+    let content = std::fs::read_to_string(path)?;
+    let mut lines = content.lines();
+    let header: Vec<&str> = lines
+        .next()
+        .context("CSV file is empty")?
+        .split(',')
+        .map(|h| h.trim())
+        .collect();
+
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|h| *h == name)
+            .with_context(|| format!("CSV missing column: {}", name))
+    };
+    let position_col = column("position_id")?;
+    let elapsed_col = column("elapsed_secs")?;
+    let pnl_col = column("pnl_multiplier")?;
+    let vwap_col = column("vwap_distance_percent")?;
+    let momentum_col = column("momentum_score")?;
+
+    let mut series: BTreeMap<String, Vec<MetricPoint>> = BTreeMap::new();
+    for (line_no, line) in lines.enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+        let field = |idx: usize| {
+            fields
+                .get(idx)
+                .copied()
+                .with_context(|| format!("CSV line {} is missing fields", line_no + 2))
+        };
+
+        series.entry(field(position_col)?.to_string()).or_default().push(MetricPoint {
+            elapsed_secs: field(elapsed_col)?.parse()?,
+            pnl_multiplier: field(pnl_col)?.parse()?,
+            vwap_distance_percent: field(vwap_col)?.parse()?,
+            momentum_score: field(momentum_col)?.parse()?,
+        });
+    }
+
+    Ok(series)
+}
+
+/// Replay one position's metric series through an exit strategy
+/// Partial sells come off the remaining bag; any remainder is marked to the
+/// last snapshot if the strategy never fully exits
+pub fn replay_position(
+    position_id: &str,
+    points: &[MetricPoint],
+    strategy: &dyn ExitStrategy,
+) -> Option<TradeOutcome> {
+    let last = points.last()?;
+
+    let mut remaining = 1.0;
+    let mut realized = 0.0;
+    let mut exits_taken = 0;
+    let mut peak_multiplier: f64 = 1.0;
+
+    for point in points {
+        peak_multiplier = peak_multiplier.max(point.pnl_multiplier);
+        let state = PositionState {
+            elapsed_secs: point.elapsed_secs,
+            pnl_multiplier: point.pnl_multiplier,
+            peak_multiplier,
+            momentum: point.momentum_score,
+            vwap_deviation: point.vwap_distance_percent / 100.0,
+            exits_taken,
+        };
+
+        if let ExitDecision::Sell { percent, reason, detail } = strategy.evaluate(&state) {
+            let sold = remaining * percent as f64 / 100.0;
+            realized += sold * point.pnl_multiplier;
+            remaining -= sold;
+            exits_taken += 1;
+
+            if percent >= 100 {
+                return Some(TradeOutcome {
+                    position_id: position_id.to_string(),
+                    pnl_percent: (realized - 1.0) * 100.0,
+                    exit_secs: point.elapsed_secs,
+                    exit_reason: Some(reason),
+                    exit_detail: detail,
+                });
+            }
+        }
+    }
+
+    realized += remaining * last.pnl_multiplier;
+    Some(TradeOutcome {
+        position_id: position_id.to_string(),
+        pnl_percent: (realized - 1.0) * 100.0,
+        exit_secs: last.elapsed_secs,
+        exit_reason: None,
+        exit_detail: "End of data".to_string(),
+    })
+}
+
+/// Replay every position through one exit strategy
+pub fn replay_all(
+    series: &BTreeMap<String, Vec<MetricPoint>>,
+    strategy: &dyn ExitStrategy,
+) -> Vec<TradeOutcome> {
+    series
+        .iter()
+        .filter_map(|(id, points)| replay_position(id, points, strategy))
+        .collect()
+}
+
+/// (trades, wins, total P&L %) for a set of outcomes
+fn summarize(outcomes: &[TradeOutcome]) -> (usize, usize, f64) {
+    let wins = outcomes.iter().filter(|o| o.pnl_percent > 0.0).count();
+    let total_pnl_percent = outcomes.iter().map(|o| o.pnl_percent).sum();
+    (outcomes.len(), wins, total_pnl_percent)
+}
+
+/// Replay every position with one parameter set
+pub fn run(series: &BTreeMap<String, Vec<MetricPoint>>, params: &BacktestParams) -> BacktestReport {
+    let outcomes = replay_all(series, &params.strategy());
+    let (trades, wins, total_pnl_percent) = summarize(&outcomes);
+    let avg_pnl_percent = if trades > 0 {
+        total_pnl_percent / trades as f64
+    } else {
+        0.0
+    };
+
+    BacktestReport {
+        params: params.clone(),
+        trades,
+        wins,
+        avg_pnl_percent,
+        total_pnl_percent,
+        outcomes,
+    }
+}
+
+/// Default parameter grid for sweeps
+pub fn default_grid() -> Vec<BacktestParams> {
+    let thresholds = [0.1, 0.15, 0.2, 0.25, 0.3];
+    let deviations = [0.03, 0.05, 0.08, 0.1];
+    let checkpoint_sets = [
+        vec![5, 10, 20, 30, 45],
+        vec![10, 20, 30, 45, 60],
+        vec![15, 30, 45, 60, 90],
+    ];
+
+    let mut grid = Vec::new();
+    for &momentum_threshold in &thresholds {
+        for &vwap_exit_deviation in &deviations {
+            for checkpoint_secs in &checkpoint_sets {
+                grid.push(BacktestParams {
+                    momentum_threshold,
+                    vwap_exit_deviation,
+                    checkpoint_secs: checkpoint_secs.clone(),
+                });
+            }
+        }
+    }
+    grid
+}
+
+/// Sweep a parameter grid, returning reports sorted best-first by total P&L
+pub fn sweep(
+    series: &BTreeMap<String, Vec<MetricPoint>>,
+    grid: &[BacktestParams],
+) -> Vec<BacktestReport> {
+    let mut reports: Vec<BacktestReport> = grid.iter().map(|p| run(series, p)).collect();
+    reports.sort_by(|a, b| b.total_pnl_percent.total_cmp(&a.total_pnl_percent));
+    reports
+}
+
+/// Entry point for `ladder-sniper backtest [path]` (DuckDB or .csv)
+pub fn run_cli(path: &str) -> Result<()> {
+    let series = if path.ends_with(".csv") {
+        load_csv(path)?
+    } else {
+        load_duckdb(path)?
+    };
+
+    info!("📼 BACKTEST: {} positions from {}", series.len(), path);
+    if series.is_empty() {
+        info!("   No position_metrics recorded, nothing to replay");
+        return Ok(());
+    }
+
+    let baseline = run(&series, &BacktestParams::default());
+    let reports = sweep(&series, &default_grid());
+
+    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    info!("   Exit strategies (default params):");
+    for name in STRATEGY_NAMES {
+        let strategy = exit::by_name(name)?;
+        let (trades, wins, total) = summarize(&replay_all(&series, strategy.as_ref()));
+        info!(
+            "   {:<14} {} trades | {} wins | total {:+.1}%",
+            name, trades, wins, total
+        );
+    }
+    info!("   Current params: {}", format_report(&baseline));
+    info!("   Top parameter sets:");
+    for report in reports.iter().take(5) {
+        info!("   {}", format_report(report));
+    }
+    if let Some(best) = reports.first() {
+        info!("🏆 BEST: {}", format_report(best));
+        for outcome in &best.outcomes {
+            debug!(
+                "   {} exited at {}s ({:+.1}%) [{}]: {}",
+                outcome.position_id,
+                outcome.exit_secs,
+                outcome.pnl_percent,
+                outcome.exit_reason.map_or("end_of_data", |reason| reason.as_str()),
+                outcome.exit_detail
+            );
+        }
+    }
+    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    Ok(())
+}
+
+fn format_report(report: &BacktestReport) -> String {
+    format!(
+        "mom={:.2} vwap={:.0}% checkpoints={:?} | {} trades | {:.1}% win | avg {:+.1}% | total {:+.1}%",
+        report.params.momentum_threshold,
+        report.params.vwap_exit_deviation * 100.0,
+        report.params.checkpoint_secs,
+        report.trades,
+        report.win_rate() * 100.0,
+        report.avg_pnl_percent,
+        report.total_pnl_percent,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(elapsed_secs: u64, pnl_multiplier: f64, momentum_score: f64) -> MetricPoint {
+        MetricPoint {
+            elapsed_secs,
+            pnl_multiplier,
+            vwap_distance_percent: 0.0,
+            momentum_score,
+        }
+    }
+
+    #[test]
+    fn test_exits_at_first_failed_checkpoint() {
+        let strategy = BacktestParams::default().strategy();
+        let points = vec![point(5, 1.1, 0.1), point(10, 0.9, 0.1), point(20, 2.5, 0.9)];
+
+        let outcome = replay_position("a", &points, &strategy).unwrap();
+        assert_eq!(outcome.exit_secs, 10);
+        assert!((outcome.pnl_percent - -10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_takes_half_at_2x() {
+        let strategy = BacktestParams::default().strategy();
+        let points = vec![point(5, 2.0, 0.9), point(10, 1.0, 0.1)];
+
+        // Half sold at 2x, half at 1x: 1.0 + 0.5 = 1.5 => +50%
+        let outcome = replay_position("a", &points, &strategy).unwrap();
+        assert!((outcome.pnl_percent - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_vwap_deviation_exit() {
+        let strategy = BacktestParams::default().strategy();
+        let mut below_vwap = point(15, 0.95, 0.9);
+        below_vwap.vwap_distance_percent = -8.0;
+
+        let outcome = replay_position("a", &[below_vwap], &strategy).unwrap();
+        assert_eq!(outcome.exit_reason, Some(ExitReason::BelowVwap));
+        assert!(outcome.exit_detail.contains("below VWAP"));
+    }
+
+    #[test]
+    fn test_sweep_sorted_best_first() {
+        let mut series = BTreeMap::new();
+        series.insert("a".to_string(), vec![point(10, 1.5, 0.15), point(20, 3.0, 0.9)]);
+
+        let reports = sweep(&series, &default_grid());
+        assert!(reports
+            .windows(2)
+            .all(|w| w[0].total_pnl_percent >= w[1].total_pnl_percent));
+    }
+}
@@ -16,6 +16,7 @@ struct LiveStats {
     losses: i64,
     win_rate_pct: f64,
     total_pnl_sol: f64,
+    total_pnl_usd: Option<f64>,
     avg_pnl_pct: f64,
     avg_hold_secs: f64,
     avg_entry_holders: f64,
@@ -64,6 +65,13 @@ fn get_stats(conn: &Connection) -> Result<LiveStats> {
         (0, 0, 0.0, 0.0, 0.0, 0.0, 0.0)
     };
 
+    // NULL until a position closes with a known SOL price
+    let total_pnl_usd: Option<f64> = conn.query_row(
+        "SELECT SUM(pnl_usd) FROM positions WHERE exit_time_micros IS NOT NULL",
+        [],
+        |row| row.get(0),
+    )?;
+
     let win_rate_pct = if closed_positions > 0 {
         (wins as f64 / closed_positions as f64) * 100.0
     } else {
@@ -91,6 +99,7 @@ fn get_stats(conn: &Connection) -> Result<LiveStats> {
         losses,
         win_rate_pct,
         total_pnl_sol,
+        total_pnl_usd,
         avg_pnl_pct,
         avg_hold_secs,
         avg_entry_holders,
@@ -118,7 +127,10 @@ fn print_stats_human(stats: &LiveStats) {
         println!("");
         println!("💰 PERFORMANCE:");
         println!("   Win Rate: {:.1}% ({} W / {} L)", stats.win_rate_pct, stats.wins, stats.losses);
-        println!("   Total P&L: {:+.4} SOL", stats.total_pnl_sol);
+        match stats.total_pnl_usd {
+            Some(usd) => println!("   Total P&L: {:+.4} SOL (${:+.2})", stats.total_pnl_sol, usd),
+            None => println!("   Total P&L: {:+.4} SOL", stats.total_pnl_sol),
+        }
         println!("   Avg P&L: {:+.2}%", stats.avg_pnl_pct);
         println!("   Avg Hold Time: {:.1}s", stats.avg_hold_secs);
 
@@ -24,7 +24,7 @@ const MINTS_TO_SELL: &[&str] = &[
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    env_logger::init();
+    pump_portal_sdk::logging::init();
     dotenv().ok();
 
     info!("🔥 EMERGENCY SELL ALL POSITIONS");
@@ -1,5 +1,5 @@
 use crate::trade_events::TradeEvent;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone)]
 pub struct Candle {
@@ -82,6 +82,24 @@ impl Candle {
     }
 }
 
+/// Bollinger Bands over candle closes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BollingerBands {
+    pub middle: f64, // SMA of closes
+    pub upper: f64,
+    pub lower: f64,
+}
+
+impl BollingerBands {
+    /// Band width relative to the middle band, low values mean a squeeze
+    pub fn bandwidth(&self) -> f64 {
+        if self.middle == 0.0 {
+            return 0.0;
+        }
+        (self.upper - self.lower) / self.middle
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CandleBuilder {
     interval_ms: u64,
@@ -107,8 +125,13 @@ impl CandleBuilder {
                 self.current_candle = Some(Candle::new(trade));
             }
             Some(candle) => {
-                let elapsed_ms = candle.timestamp.elapsed().as_millis() as u64;
+                // Bucket by trade time so replayed or delayed trades land in the right candle
+                let elapsed_ms = trade
+                    .timestamp
+                    .saturating_duration_since(candle.timestamp)
+                    .as_millis() as u64;
 
+                // Gaps with no trades don't produce empty candles, the next trade just opens a new one
                 if elapsed_ms >= self.interval_ms {
                     // Complete current candle and start new one
                     let completed = candle.clone();
@@ -133,6 +156,16 @@ impl CandleBuilder {
         self.current_candle.as_ref()
     }
 
+    /// Time since the current candle opened
+    /// Past `interval_ms` means no trades have arrived since the interval closed
+    pub fn current_candle_age(&self) -> Option<Duration> {
+        self.current_candle.as_ref().map(|c| c.timestamp.elapsed())
+    }
+
+    pub fn interval_ms(&self) -> u64 {
+        self.interval_ms
+    }
+
     pub fn completed_candles(&self) -> &[Candle] {
         &self.completed_candles
     }
@@ -181,6 +214,36 @@ impl CandleBuilder {
         sum / (self.completed_candles.len() - start) as f64
     }
 
+    /// Bollinger Bands over the last `period` completed closes
+    /// None until `period` candles have completed
+    pub fn bollinger(&self, period: usize, std_mult: f64) -> Option<BollingerBands> {
+        if period == 0 || self.completed_candles.len() < period {
+            return None;
+        }
+
+        let closes = &self.completed_candles[self.completed_candles.len() - period..];
+        let mean = closes.iter().map(|c| c.close).sum::<f64>() / period as f64;
+        let variance = closes
+            .iter()
+            .map(|c| (c.close - mean).powi(2))
+            .sum::<f64>()
+            / period as f64;
+        let band = variance.sqrt() * std_mult;
+
+        Some(BollingerBands {
+            middle: mean,
+            upper: mean + band,
+            lower: mean - band,
+        })
+    }
+
+    /// Bandwidth of `bollinger(period, std_mult)`, 0.0 while there isn't enough data
+    pub fn bollinger_bandwidth(&self, period: usize, std_mult: f64) -> f64 {
+        self.bollinger(period, std_mult)
+            .map(|bands| bands.bandwidth())
+            .unwrap_or(0.0)
+    }
+
     /// Check if momentum is increasing
     pub fn is_accelerating(&self) -> bool {
         if self.completed_candles.len() < 3 {
@@ -249,4 +312,132 @@ mod tests {
         // Buy ratio: 0.04 / 0.06 = 0.666...
         assert!((candle.buy_ratio() - 0.6666).abs() < 0.001);
     }
+
+    fn trade_at(start: Instant, offset_ms: u64, price: f64, volume: f64, is_buy: bool) -> TradeEvent {
+        TradeEvent::at(start + Duration::from_millis(offset_ms), price, volume, is_buy)
+    }
+
+    #[test]
+    fn test_interval_rollover_ohlc() {
+        let start = Instant::now();
+        let mut builder = CandleBuilder::new(500, 100);
+
+        builder.add_trade(&trade_at(start, 0, 0.0001, 0.02, true));
+        builder.add_trade(&trade_at(start, 100, 0.00015, 0.03, true));
+        builder.add_trade(&trade_at(start, 200, 0.00008, 0.01, false));
+        builder.add_trade(&trade_at(start, 499, 0.00012, 0.04, true));
+        assert!(builder.completed_candles().is_empty());
+
+        // Exactly one interval later rolls into a new candle
+        builder.add_trade(&trade_at(start, 500, 0.0002, 0.05, false));
+
+        let completed = builder.latest_candle().unwrap();
+        assert_eq!(builder.completed_candles().len(), 1);
+        assert_eq!(completed.open, 0.0001);
+        assert_eq!(completed.high, 0.00015);
+        assert_eq!(completed.low, 0.00008);
+        assert_eq!(completed.close, 0.00012);
+        assert_eq!(completed.trade_count, 4);
+        assert_eq!((completed.buy_count, completed.sell_count), (3, 1));
+        // 0.09 bought of 0.10 total
+        assert!((completed.buy_ratio() - 0.9).abs() < 1e-9);
+
+        let current = builder.current_candle().unwrap();
+        assert_eq!(current.open, 0.0002);
+        assert_eq!(current.trade_count, 1);
+        assert_eq!(current.buy_ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_gap_does_not_create_empty_candles() {
+        let start = Instant::now();
+        let mut builder = CandleBuilder::new(500, 100);
+
+        builder.add_trade(&trade_at(start, 0, 0.0001, 0.02, true));
+        // Ten intervals with no trades
+        builder.add_trade(&trade_at(start, 5_000, 0.0001, 0.02, true));
+
+        assert_eq!(builder.completed_candles().len(), 1);
+        assert!(builder.completed_candles().iter().all(|c| c.trade_count > 0));
+        assert_eq!(builder.avg_volume(5), 0.02);
+    }
+
+    #[test]
+    fn test_empty_builder() {
+        let builder = CandleBuilder::new(500, 100);
+
+        assert!(builder.current_candle().is_none());
+        assert!(builder.current_candle_age().is_none());
+        assert!(builder.latest_candle().is_none());
+        assert_eq!(builder.avg_volume(5), 0.0);
+        assert_eq!(builder.avg_buy_ratio(5), 0.5);
+        assert!(!builder.is_accelerating());
+    }
+
+    #[test]
+    fn test_capacity_evicts_oldest() {
+        let start = Instant::now();
+        let mut builder = CandleBuilder::new(100, 3);
+
+        for i in 0..10u64 {
+            builder.add_trade(&trade_at(start, i * 100, 0.0001 * (i + 1) as f64, 0.01, true));
+            assert!(builder.completed_candles().len() <= 3);
+        }
+
+        // Candles 0..=8 completed, only 6..=8 kept
+        let opens: Vec<f64> = builder.completed_candles().iter().map(|c| c.open).collect();
+        assert_eq!(opens.len(), 3);
+        assert!((opens[0] - 0.0007).abs() < 1e-12);
+        assert!((opens[2] - 0.0009).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_bollinger_flat_series() {
+        let start = Instant::now();
+        let mut builder = CandleBuilder::new(100, 100);
+        for i in 0..21u64 {
+            builder.add_trade(&trade_at(start, i * 100, 0.0001, 0.01, true));
+        }
+
+        let bands = builder.bollinger(20, 2.0).unwrap();
+        assert!((bands.middle - 0.0001).abs() < 1e-15);
+        assert!((bands.upper - bands.lower).abs() < 1e-15);
+        assert!(builder.bollinger_bandwidth(20, 2.0) < 1e-9);
+
+        // Not enough candles yet
+        assert!(builder.bollinger(50, 2.0).is_none());
+    }
+
+    #[test]
+    fn test_bollinger_trending_series() {
+        let start = Instant::now();
+        let mut builder = CandleBuilder::new(100, 100);
+        // Closes 1..=5 (plus an open candle that isn't counted)
+        for i in 0..6u64 {
+            builder.add_trade(&trade_at(start, i * 100, (i + 1) as f64, 0.01, true));
+        }
+
+        // Mean 3, population std dev sqrt(2)
+        let bands = builder.bollinger(5, 2.0).unwrap();
+        assert!((bands.middle - 3.0).abs() < 1e-9);
+        assert!((bands.upper - (3.0 + 2.0 * 2f64.sqrt())).abs() < 1e-9);
+        assert!((bands.lower - (3.0 - 2.0 * 2f64.sqrt())).abs() < 1e-9);
+        assert!(builder.bollinger_bandwidth(5, 2.0) > 1.0);
+
+        // Shorter window only sees the latest closes 4 and 5
+        let recent = builder.bollinger(2, 2.0).unwrap();
+        assert!((recent.middle - 4.5).abs() < 1e-9);
+        assert!((recent.upper - 5.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_current_candle_age() {
+        let mut builder = CandleBuilder::new(500, 100);
+        let opened = Instant::now() - Duration::from_secs(2);
+        builder.add_trade(&TradeEvent::at(opened, 0.0001, 0.02, true));
+
+        let age = builder.current_candle_age().unwrap();
+        assert!(age >= Duration::from_secs(2));
+        assert!(age.as_millis() as u64 > builder.interval_ms());
+    }
 }
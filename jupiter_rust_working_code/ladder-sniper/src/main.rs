@@ -1,233 +1,244 @@
 use anyhow::Result;
 use dotenv::dotenv;
-use futures_util::{SinkExt, StreamExt};
-use log::{info, warn, error};
-use pump_portal_sdk::{PumpPortalClient, TradeRequest};
-use serde::Deserialize;
+use log::{info, warn};
+use pump_portal_sdk::config::BotConfig;
+use pump_portal_sdk::dedup::DEFAULT_BUY_DEDUP_WINDOW;
+use pump_portal_sdk::{exit, paper, AdaptiveSlippage, BondingCurve, ExitDecision, ExitReason, ExitStrategy, NewTokenEvent, Notification, Notifier, PaperTradingSimulator, PnlTracker, PositionState, PumpPortalClient, PumpPortalEvent, PumpPortalStream, SolBudget, StreamHandle, Subscription, TradeRequest, PUMP_TOKEN_DECIMALS};
 use std::collections::HashMap;
 use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Semaphore};
 use tokio::time::{sleep, Duration};
-use tokio_tungstenite::{connect_async, tungstenite::Message};
 
 mod trade_events;
 mod candle_builder;
 mod vwap;
 mod momentum;
-mod paper_trading;
 mod holder_count;
-mod data_export;
+mod backtest;
 
 use candle_builder::CandleBuilder;
-use momentum::MomentumDetector;
+use momentum::{MomentumDetector, MomentumSignal};
 use trade_events::TradeEvent;
 use vwap::VWAPTracker;
-use paper_trading::{PaperTradingConfig, PaperTradingSimulator, SharedExporter};
 use holder_count::HolderCountClient;
-use data_export::DataExporter;
 
 #[derive(Debug, Clone)]
 struct Position {
     mint: String,
     entry_time: Instant,
-    entry_price: f64,
-    total_sol_invested: f64,
+    /// Entry price in SOL per token (from the buy fill, or the first candle
+    /// until then) and SOL still at stake
+    pnl: PnlTracker,
     candle_builder: CandleBuilder,
+    /// Slower candles for exit confirmation, when `trend_candle_interval_ms` is set
+    trend_candles: Option<CandleBuilder>,
     vwap_tracker: VWAPTracker,
-    profits_taken: bool,
+    exits_taken: u32,
+    /// Take-profit tiers already sold; they fire lowest first
+    take_profits_fired: usize,
+    /// Start of the candle last scaled out into, so one spike sells once
+    scaled_out_candle: Option<Instant>,
     holder_count: u64,
 }
 
-#[derive(Debug, Deserialize)]
-struct TokenCreatedEvent {
-    mint: Option<String>,
-    name: Option<String>,
-    symbol: Option<String>,
+impl Position {
+    /// Feed a trade to VWAP and every timeframe
+    fn add_trade(&mut self, trade: &TradeEvent) {
+        self.vwap_tracker.add_trade(trade);
+        self.candle_builder.add_trade(trade);
+        if let Some(trend) = &mut self.trend_candles {
+            trend.add_trade(trade);
+        }
+    }
 }
 
 type Positions = Arc<Mutex<HashMap<String, Position>>>;
 
+/// SOL at cost still in open positions, what `max_total_sol_at_risk` caps
+fn sol_at_risk(positions: &HashMap<String, Position>) -> f64 {
+    positions.values().map(|pos| pos.pnl.size_sol()).sum()
+}
+
+/// How long to wait for a live buy to show up on-chain when reading its fill
+const FILL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long each sell gets on Ctrl+C before the bot exits anyway
+const EXIT_SELL_TIMEOUT: Duration = Duration::from_secs(10);
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    env_logger::init();
+    pump_portal_sdk::logging::init();
     dotenv().ok();
 
+    // Offline mode: replay recorded metrics through the exit rules
+    let args: Vec<String> = env::args().collect();
+    if args.get(1).map(String::as_str) == Some("backtest") {
+        let path = args.get(2).map(String::as_str).unwrap_or("./data/research.duckdb");
+        return backtest::run_cli(path);
+    }
+
     info!("⚡ VWAP MOMENTUM SNIPER - Sub-Millisecond Indicators");
     info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
 
     let api_key = env::var("PUMPPORTAL_API_KEY").expect("PUMPPORTAL_API_KEY required");
 
-    let base_amount = env::var("BASE_AMOUNT_SOL")
-        .unwrap_or_else(|_| "0.02".to_string())
-        .parse::<f64>()?;
-
-    let candle_interval_ms = env::var("CANDLE_INTERVAL_MS")
-        .unwrap_or_else(|_| "500".to_string())
-        .parse::<u64>()?;
-
-    let momentum_threshold = env::var("MOMENTUM_EXIT_THRESHOLD")
-        .unwrap_or_else(|_| "0.2".to_string())
-        .parse::<f64>()?;
-
-    let vwap_deviation = env::var("VWAP_EXIT_DEVIATION")
-        .unwrap_or_else(|_| "0.95".to_string())
-        .parse::<f64>()?;
-
-    // Burst mode configuration
-    let max_trades = env::var("MAX_TRADES")
-        .ok()
-        .and_then(|v| v.parse::<usize>().ok());
-
-    if let Some(max) = max_trades {
-        info!("🚀 BURST MODE: Will stop after {} trades", max);
+    // config.toml / config.json if present, else env vars
+    let config = Arc::new(BotConfig::load(BotConfig {
+        snipe_amount_sol: 0.02,
+        buy_slippage: 15,
+        sell_slippage: 20,
+        max_requests_per_sec: Some(5.0),
+        ..BotConfig::default()
+    })?);
+
+    let mut momentum_detector = MomentumDetector::new(config.momentum_exit_threshold)
+        .with_vwap_exit(1.0 - config.vwap_exit_deviation)
+        .with_bollinger(config.bollinger_period, config.bollinger_std_mult)
+        .with_squeeze(config.bollinger_squeeze);
+    if let Some(k) = config.vwap_band_std_mult {
+        momentum_detector = momentum_detector.with_vwap_bands(k);
     }
-
-    // Paper trading setup
-    let paper_config = PaperTradingConfig::from_env();
-
-    // Initialize DuckDB exporter for research
-    let exporter = if paper_config.enabled {
-        let db_path = env::var("DUCKDB_PATH")
-            .unwrap_or_else(|_| "./data/research.duckdb".to_string());
-
-        match DataExporter::new(&db_path, true) {
-            Ok(exp) => {
-                info!("📊 DuckDB Analytics: ENABLED");
-                info!("   Database: {}", db_path);
-                Some(Arc::new(Mutex::new(exp)))
-            }
-            Err(e) => {
-                warn!("⚠️  Failed to initialize DuckDB: {}", e);
-                None
-            }
-        }
-    } else {
-        None
+    // Named strategies bring their own profit taking
+    momentum_detector = match config.exit_strategy {
+        Some(_) => momentum_detector.with_take_profit(Vec::new()),
+        None => momentum_detector.with_take_profit(config.take_profit.clone()),
     };
+    if let Some(spike) = config.scale_out_spike_percent {
+        momentum_detector =
+            momentum_detector.with_scale_out(spike, config.scale_out_min_multiple, config.scale_out_fraction);
+    }
+    let momentum_detector = Arc::new(momentum_detector);
 
-    let paper_sim = if paper_config.enabled {
-        Some(Arc::new(PaperTradingSimulator::new(paper_config.clone(), exporter)))
-    } else {
-        None
+    // Exit strategy: picked by name, default is the VWAP/momentum rules above
+    let exit_strategy: Arc<dyn ExitStrategy> = match &config.exit_strategy {
+        Some(name) => Arc::from(exit::by_name(name)?),
+        None => Arc::new(momentum_detector.exit_strategy()),
     };
 
-    info!("💰 Config:");
-    if paper_config.enabled {
-        info!("   🧪 PAPER MODE: ENABLED");
-        info!("   Starting Balance: {} SOL", paper_config.starting_balance);
-        info!("   Buy Latency: {}ms", paper_config.buy_latency_ms);
-        info!("   Sell Latency: {}ms", paper_config.sell_latency_ms);
-        info!("   Trade Fee: {:.1}%", paper_config.trade_fee_percent);
-        info!("   Priority Fee: {} SOL", paper_config.priority_fee_sol);
-        info!("");
+    // Paper trading with DuckDB export for research (PAPER_MODE or --paper)
+    let paper_sim = config.paper_simulator();
+
+    config.display();
+    info!("   Resolved Exit Strategy: {}", exit_strategy.name());
+    // Buys run off the read loop; max_concurrent_buys caps how many wait on confirmation at once
+    info!("   Max Concurrent Buys: {}", config.max_concurrent_buys);
+    if let Some(max) = config.max_trades {
+        info!("🚀 BURST MODE: Will stop after {} trades", max);
     }
-    info!("   Buy Amount: {} SOL", base_amount);
-    info!("   Candle Interval: {}ms", candle_interval_ms);
-    info!("   Momentum Threshold: {:.0}%", momentum_threshold * 100.0);
-    info!("   VWAP Exit: {:.0}% deviation", (1.0 - vwap_deviation) * 100.0);
-    info!("   Time Exits: 10s, 20s, 30s, 45s, 60s");
     info!("");
 
-    let client = Arc::new(PumpPortalClient::new(api_key));
+    // A buy resent after an ambiguous failure must not double the position
+    let mut client = PumpPortalClient::new(api_key).with_buy_dedup(DEFAULT_BUY_DEDUP_WINDOW);
+    // Throttle PumpPortal requests so launch bursts don't get rejected
+    if let Some(rate) = config.max_requests_per_sec {
+        client = client.with_rate_limit(rate);
+    }
+    if let Some(max) = config.max_slippage {
+        client = client.with_slippage_retry(max);
+    }
+    let client = Arc::new(client);
     let positions: Positions = Arc::new(Mutex::new(HashMap::new()));
 
     // Solana RPC for holder counts
-    let rpc_url = env::var("SOLANA_RPC_URL")
-        .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string());
-    let holder_client = Arc::new(HolderCountClient::new(rpc_url));
+    let holder_client = Arc::new(HolderCountClient::new(config.rpc_url.clone()));
 
-    // Start position monitor
-    let monitor_client = client.clone();
-    let monitor_positions = positions.clone();
-    let momentum_detector = Arc::new(MomentumDetector::new(momentum_threshold));
-    let monitor_paper_sim = paper_sim.clone();
+    // Connect to PumpPortal WebSocket; trade subscriptions from the monitor go through its handle
+    info!("📡 Connecting to PumpPortal WebSocket...");
+    let mut stream = PumpPortalStream::connect(config.stream_config()).await?;
+    let ws_tx = stream.handle();
+
+    let ctx = BotContext {
+        client: client.clone(),
+        positions: positions.clone(),
+        holder_client,
+        paper_sim: paper_sim.clone(),
+        ws_tx,
+        notifier: config.notifier("ladder-sniper"),
+        budget: config.sol_budget(),
+        // Paper buys never fail on slippage, so there is nothing to adapt to
+        adaptive_slippage: config.adaptive_slippage().filter(|_| paper_sim.is_none()),
+        config: config.clone(),
+    };
 
+    // Start position monitor
+    let monitor_ctx = ctx.clone();
     tokio::spawn(async move {
-        monitor_positions_loop(
-            monitor_client,
-            monitor_positions,
-            momentum_detector,
-            candle_interval_ms,
-            monitor_paper_sim,
-        )
-        .await;
+        monitor_positions_loop(monitor_ctx, momentum_detector, exit_strategy).await;
     });
 
-    // Connect to PumpPortal WebSocket
-    info!("📡 Connecting to PumpPortal WebSocket...");
-    let ws_url = "wss://pumpportal.fun/api/data";
-    let (ws_stream, _) = connect_async(ws_url).await?;
-    let (mut write, mut read) = ws_stream.split();
-
-    let subscribe_msg = serde_json::json!({
-        "method": "subscribeNewToken"
-    });
-    write.send(Message::Text(subscribe_msg.to_string())).await?;
+    stream.subscribe(&Subscription::NewTokens);
     info!("✅ Subscribed to new token launches\n");
     info!("🎯 BUYING ALL LAUNCHES... Press Ctrl+C to stop\n");
 
-    while let Some(msg) = read.next().await {
-        match msg {
-            Ok(Message::Text(text)) => {
-                if let Ok(event) = serde_json::from_str::<TokenCreatedEvent>(&text) {
-                    if let (Some(mint), Some(name), Some(symbol)) = (event.mint, event.name, event.symbol) {
-                        info!("🔔 NEW LAUNCH: {} ({})", name, symbol);
-                        info!("   Mint: {}", mint);
-
-                        // Execute initial buy
-                        match execute_buy(&client, &mint, base_amount, &paper_sim).await {
-                            Ok(_sig) => {
-                                info!("✅ BOUGHT: {} SOL", base_amount);
-
-                                // Fetch holder count
-                                let holder_count = holder_client.get_holder_count(&mint).await.unwrap_or(0);
-                                if holder_count > 0 {
-                                    info!("👥 HOLDERS: {}", holder_count);
-                                }
-
-                                // Initialize position with VWAP + momentum tracking
-                                let position = Position {
-                                    mint: mint.clone(),
-                                    entry_time: Instant::now(),
-                                    entry_price: 0.0, // Will be set from first candle
-                                    total_sol_invested: base_amount,
-                                    candle_builder: CandleBuilder::new(candle_interval_ms, 100),
-                                    vwap_tracker: VWAPTracker::new(),
-                                    profits_taken: false,
-                                    holder_count,
-                                };
-
-                                // Record entry trade
-                                let entry_trade = TradeEvent::new_buy(0.0, base_amount);
-                                positions.lock().await.insert(mint.clone(), position);
-
-                                // Add trade to position trackers
-                                if let Some(pos) = positions.lock().await.get_mut(&mint) {
-                                    pos.vwap_tracker.add_trade(&entry_trade);
-                                    pos.candle_builder.add_trade(&entry_trade);
-                                }
-
-                                let pos_count = positions.lock().await.len();
-                                info!("💼 Open Positions: {}\n", pos_count);
-                            }
-                            Err(e) => {
-                                error!("❌ Buy failed: {}\n", e);
-                            }
-                        }
-                    }
-                }
-            }
-            Ok(Message::Close(_)) => {
-                warn!("WebSocket closed");
+    let buy_slots = Arc::new(Semaphore::new(config.max_concurrent_buys));
+    let trades_made = Arc::new(AtomicUsize::new(0));
+
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    loop {
+        // The stream reconnects itself on drops and stalls
+        let text = tokio::select! {
+            text = stream.next() => text,
+            _ = &mut ctrl_c => {
+                warn!("🛑 Ctrl+C received, shutting down");
                 break;
             }
-            Err(e) => {
-                error!("WebSocket error: {}", e);
+        };
+
+        let Ok(event) = serde_json::from_str::<PumpPortalEvent>(&text) else {
+            continue;
+        };
+
+        // Live trade on an open position: feed candles and VWAP
+        if let Some(trade) = TradeEvent::from_pumpportal(&event) {
+            if let Some(pos) = positions.lock().await.get_mut(event.mint()) {
+                pos.add_trade(&trade);
             }
-            _ => {}
+            continue;
         }
+
+        if let PumpPortalEvent::NewToken(event) = event {
+            let mint = &event.mint;
+            info!("🔔 NEW LAUNCH: {} ({})", event.name, event.symbol);
+            info!("   Mint: {}", mint);
+
+            // Skip rather than queue: a buy that waits for a slot lands too late
+            let Ok(permit) = buy_slots.clone().try_acquire_owned() else {
+                tracing::warn!(event = "skip", mint = %mint, reason = "buys_in_flight",
+                               "⏸️  {} buys already in flight, skipping launch", config.max_concurrent_buys);
+                continue;
+            };
+            if !reserve_trade(&trades_made, config.max_trades) {
+                tracing::info!(event = "skip", mint = %mint, reason = "max_trades",
+                               "🏁 Burst complete ({} trades), skipping launch", config.max_trades.unwrap_or_default());
+                continue;
+            }
+
+            // Buy off the read loop so the next launch isn't missed while this one confirms
+            let mint = mint.clone();
+            let ctx = ctx.clone();
+            let trades_made = trades_made.clone();
+            tokio::spawn(async move {
+                let bought = handle_launch(&ctx, &mint, &event).await;
+                if !bought {
+                    // Give the reserved slot back so a failed buy doesn't count toward MAX_TRADES
+                    trades_made.fetch_sub(1, Ordering::SeqCst);
+                }
+                drop(permit);
+            });
+        }
+    }
+
+    // Only Ctrl+C ends the loop
+    if config.sell_on_exit {
+        sell_all_on_exit(&ctx).await;
+    } else {
+        let held = positions.lock().await.len();
+        warn!("💼 sell_on_exit is off, leaving {} positions open", held);
     }
 
     // Print paper trading summary
@@ -238,43 +249,210 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Shared state the launch buys and the position monitor need, cloned into each task
+#[derive(Clone)]
+struct BotContext {
+    client: Arc<PumpPortalClient>,
+    positions: Positions,
+    holder_client: Arc<HolderCountClient>,
+    paper_sim: Option<Arc<PaperTradingSimulator>>,
+    ws_tx: StreamHandle,
+    notifier: Arc<dyn Notifier>,
+    /// Shared by every launch buy, so buys in flight count toward the cap
+    budget: SolBudget,
+    /// Base buy slippage when ADAPTIVE_SLIPPAGE is on (live only)
+    adaptive_slippage: Option<AdaptiveSlippage>,
+    config: Arc<BotConfig>,
+}
+
+/// Claim one of `max_trades` buys; always succeeds with no limit
+fn reserve_trade(trades_made: &AtomicUsize, max_trades: Option<usize>) -> bool {
+    match max_trades {
+        Some(max) => trades_made
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| (n < max).then_some(n + 1))
+            .is_ok(),
+        None => {
+            trades_made.fetch_add(1, Ordering::SeqCst);
+            true
+        }
+    }
+}
+
+/// Buy a new launch and start tracking it; returns whether the buy went through
+async fn handle_launch(ctx: &BotContext, mint: &str, event: &NewTokenEvent) -> bool {
+    let BotContext { client, positions, holder_client, paper_sim, ws_tx, notifier, budget, adaptive_slippage, config } = ctx;
+    let base_amount = config.snipe_amount_sol;
+    if let Some(reserves) = event.thin_reserves(config.min_sol_reserves) {
+        tracing::info!(event = "skip", mint, reason = "low_liquidity", sol_reserves = reserves,
+                       "💧 Only {:.2} SOL in the curve (min {:.2}), skipping launch\n", reserves, config.min_sol_reserves);
+        return false;
+    }
+
+    // Held until the position is tracked, so concurrent buys can't overshoot the cap
+    let reservation = match budget.reserve(sol_at_risk(&*positions.lock().await), base_amount) {
+        Ok(reservation) => reservation,
+        Err(left) => {
+            tracing::warn!(event = "skip", mint, reason = "max_sol_at_risk", budget_left_sol = left,
+                           "💰 {} SOL left under the {} SOL cap, skipping launch\n",
+                           left, budget.max_sol().unwrap_or_default());
+            return false;
+        }
+    };
+
+    let curve = event.curve();
+    let slippage = adaptive_slippage.as_ref().map_or(config.buy_slippage, AdaptiveSlippage::current);
+    let result = execute_buy(client, mint, base_amount, slippage, config.max_price_impact_bps, curve, paper_sim).await;
+    if let Some(adaptive) = adaptive_slippage {
+        // A sent buy can still fail on slippage on-chain, so it's counted once it settles
+        match &result {
+            Ok(signature) => adaptive.record_when_confirmed(client, signature, &config.rpc_url),
+            Err(e) => {
+                adaptive.record_error(e.as_ref());
+            }
+        }
+    }
+    let sig = match result {
+        Ok(sig) => sig,
+        Err(e) => {
+            tracing::error!(event = "buy_failed", mint, sol = base_amount, error = %e, "❌ Buy failed\n");
+            return false;
+        }
+    };
+    tracing::info!(event = "buy", mint, sol = base_amount, slippage,
+                   signature = %sig, "✅ BOUGHT");
+    notifier.notify(Notification::buy(mint, base_amount, &sig));
+
+    // Fetch holder count
+    let holder_count = holder_client.get_holder_count(mint).await.unwrap_or(0);
+    if holder_count > 0 {
+        info!("👥 HOLDERS: {}", holder_count);
+    }
+
+    // Initialize position with VWAP + momentum tracking
+    let position = Position {
+        mint: mint.to_string(),
+        entry_time: Instant::now(),
+        pnl: PnlTracker::new(0.0, base_amount),
+        candle_builder: CandleBuilder::new(config.candle_interval_ms, config.candle_history),
+        trend_candles: config
+            .trend_candle_interval_ms
+            .map(|interval_ms| CandleBuilder::new(interval_ms, config.candle_history)),
+        vwap_tracker: VWAPTracker::new(),
+        exits_taken: 0,
+        take_profits_fired: 0,
+        scaled_out_candle: None,
+        holder_count,
+    };
+
+    let at_risk = {
+        let mut locked = positions.lock().await;
+        locked.insert(mint.to_string(), position);
+        drop(reservation);
+        sol_at_risk(&locked)
+    };
+
+    // Seed trackers with our entry at the launch curve price
+    let launch_price = curve.map(|curve| curve.price()).unwrap_or(0.0);
+    if launch_price > 0.0 {
+        let entry_trade = TradeEvent::new_buy(launch_price, base_amount);
+        if let Some(pos) = positions.lock().await.get_mut(mint) {
+            pos.add_trade(&entry_trade);
+        }
+    }
+
+    // Replace the provisional entry with what the buy actually paid
+    if paper_sim.is_none() {
+        tokio::spawn(capture_entry_price(
+            client.clone(),
+            positions.clone(),
+            sig,
+            mint.to_string(),
+            config.rpc_url.clone(),
+        ));
+    }
+
+    // Stream live trades for this mint into the candle builder
+    ws_tx.subscribe(&Subscription::TokenTrades(vec![mint.to_string()]));
+
+    let pos_count = positions.lock().await.len();
+    match budget.remaining(at_risk) {
+        Some(left) => info!("💼 Open Positions: {} | At risk: {:.4} SOL ({:.4} SOL budget left)\n", pos_count, at_risk, left),
+        None => info!("💼 Open Positions: {}\n", pos_count),
+    }
+
+    true
+}
+
+/// Set a position's entry price from its confirmed buy transaction
+async fn capture_entry_price(
+    client: Arc<PumpPortalClient>,
+    positions: Positions,
+    signature: String,
+    mint: String,
+    rpc_url: String,
+) {
+    match client.fill(&signature, &mint, &rpc_url, FILL_TIMEOUT).await {
+        Ok(fill) => {
+            info!("   📌 {} filled: {:.4} SOL for {:.0} tokens ({:.10} SOL/token)",
+                  &mint[..8], fill.sol_spent, fill.tokens_received, fill.price());
+            if let Some(pos) = positions.lock().await.get_mut(&mint) {
+                pos.pnl.set_entry_price(fill.price());
+            }
+        }
+        Err(e) => warn!("   ⚠️  No fill for {} ({}), keeping candle entry price", &mint[..8], e),
+    }
+}
+
 async fn execute_buy(
     client: &PumpPortalClient,
     mint: &str,
     amount_sol: f64,
+    slippage: u32,
+    max_price_impact_bps: Option<u32>,
+    curve: Option<BondingCurve>,
     paper_sim: &Option<Arc<PaperTradingSimulator>>,
 ) -> Result<String> {
-    // Paper trading mode
-    if let Some(sim) = paper_sim {
-        // Use estimated entry price for paper trading (bonding curve start ~0.0000001 SOL/token)
-        let estimated_price = 0.0000001;
-        return sim.simulate_buy(mint.to_string(), amount_sol, estimated_price).await;
-    }
-
-    // Real trading
-    let request = TradeRequest::buy(
+    let mut request = TradeRequest::buy(
         mint.to_string(),
         amount_sol,
-        15,
+        slippage,
         0.0001,
     ).with_jito_only(true);
+    // Only guard launches that reported their curve; the rest buy as before
+    if let (Some(bps), Some(curve)) = (max_price_impact_bps, curve) {
+        request = request
+            .with_curve_reserves(curve.virtual_sol_reserves, curve.virtual_token_reserves)
+            .with_max_price_impact_bps(bps);
+    }
+
+    // Paper trading mode: fill at the launch curve price
+    if let Some(sim) = paper_sim {
+        request.validate()?;
+        let price = curve.map(|curve| curve.price()).unwrap_or(paper::ESTIMATED_LAUNCH_PRICE);
+        return Ok(sim.simulate_buy(mint.to_string(), amount_sol, price, PUMP_TOKEN_DECIMALS).await?);
+    }
+
+    // Real trading
 
     let response = client.trade(request).await?;
     Ok(response.signature.unwrap_or_else(|| "unknown".to_string()))
 }
 
+/// Sell `percent` of a position; `price` is the last SOL/token mark, which
+/// only paper trading uses
 async fn execute_sell(
     client: &PumpPortalClient,
     mint: &str,
     percent: u32,
+    slippage: u32,
+    price: Option<f64>,
     paper_sim: &Option<Arc<PaperTradingSimulator>>,
-    exit_reason: Option<String>,
+    exit_reason: ExitReason,
 ) -> Result<String> {
     // Paper trading mode
     if let Some(sim) = paper_sim {
-        // Use estimated exit price for paper trading (assume similar to entry for now)
-        let estimated_price = 0.0000001;
-        return sim.simulate_sell(mint, estimated_price, exit_reason).await;
+        let (signature, _) = sim.simulate_sell(mint, percent, price, Some(exit_reason)).await?;
+        return Ok(signature);
     }
 
     // Real trading
@@ -282,7 +460,7 @@ async fn execute_sell(
     let request = TradeRequest::sell(
         mint.to_string(),
         amount,
-        20,
+        slippage,
         0.0001,
     ).with_jito_only(true);
 
@@ -290,13 +468,53 @@ async fn execute_sell(
     Ok(response.signature.unwrap_or_else(|| "unknown".to_string()))
 }
 
-async fn monitor_positions_loop(
-    client: Arc<PumpPortalClient>,
-    positions: Positions,
-    momentum_detector: Arc<MomentumDetector>,
-    _candle_interval_ms: u64,
-    paper_sim: Option<Arc<PaperTradingSimulator>>,
-) {
+/// Sell every open position in full, giving each sell `EXIT_SELL_TIMEOUT`
+async fn sell_all_on_exit(ctx: &BotContext) {
+    let BotContext { client, positions, paper_sim, notifier, config, .. } = ctx;
+    let slippage = config.sell_slippage;
+    let mints: Vec<String> = positions.lock().await.keys().cloned().collect();
+    if mints.is_empty() {
+        info!("💼 No open positions to sell");
+        return;
+    }
+
+    warn!("🚨 Selling {} open positions before exit...", mints.len());
+    let sells = mints.iter().map(|mint| async move {
+        let price = positions.lock().await.get(mint).map(|pos| pos.pnl.price());
+        let sell = execute_sell(client, mint, 100, slippage, price, paper_sim, ExitReason::Shutdown);
+        let result = match tokio::time::timeout(EXIT_SELL_TIMEOUT, sell).await {
+            Ok(result) => result,
+            Err(_) => Err(anyhow::anyhow!("timed out after {}s", EXIT_SELL_TIMEOUT.as_secs())),
+        };
+        (mint, result)
+    });
+
+    let mut sold = 0;
+    for (mint, result) in futures_util::future::join_all(sells).await {
+        match result {
+            Ok(sig) => {
+                tracing::info!(event = "sell", mint = %mint, percent = 100, slippage,
+                               signature = %sig, reason = %ExitReason::Shutdown, "✅ SOLD");
+                if let Some(position) = positions.lock().await.remove(mint) {
+                    notifier.notify(Notification::sell(mint, &sig, ExitReason::Shutdown).with_pnl(position.pnl.percent()));
+                }
+                sold += 1;
+            }
+            Err(e) => tracing::error!(event = "sell_failed", mint = %mint, percent = 100,
+                                      error = %e, "❌ Sell failed"),
+        }
+    }
+
+    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    info!("🏁 Exit sell-off: {}/{} positions sold", sold, mints.len());
+    for mint in positions.lock().await.keys() {
+        warn!("   ⚠️  Still holding {}", mint);
+    }
+}
+
+async fn monitor_positions_loop(ctx: BotContext, momentum_detector: Arc<MomentumDetector>, exit_strategy: Arc<dyn ExitStrategy>) {
+    let positions = &ctx.positions;
+    let max_hold_secs = ctx.config.max_hold_secs;
     info!("👀 VWAP + Momentum Monitor Started\n");
 
     // Check every 1 second for time-based exits
@@ -318,6 +536,13 @@ async fn monitor_positions_loop(
             let elapsed = position.entry_time.elapsed().as_secs();
             let mint_short = &position.mint[..8];
 
+            // Hard stop before anything that can stall, like waiting for candles
+            if let Some(ExitDecision::Sell { percent, reason, .. }) = exit::hard_time_stop(elapsed, max_hold_secs) {
+                warn!("   🛑 Hard time stop: {} held {}s (max {}s)", mint_short, elapsed, max_hold_secs);
+                exit_position(&ctx, &position.mint, percent, reason).await;
+                continue;
+            }
+
             // Get current candle if exists
             let current_candle = position.candle_builder.current_candle();
             if current_candle.is_none() {
@@ -328,81 +553,123 @@ async fn monitor_positions_loop(
             let candle = current_candle.unwrap();
             let current_price = candle.close;
 
-            // Set entry price on first candle
-            if position.entry_price == 0.0 {
-                if let Some(pos) = positions.lock().await.get_mut(&position.mint) {
-                    pos.entry_price = current_price;
+            // Candle older than its interval means trading has stalled
+            if let Some(age) = position.candle_builder.current_candle_age() {
+                if age.as_millis() as u64 > position.candle_builder.interval_ms() {
+                    info!("   {} ({}s) - ⚠️ No trades for {:.1}s", mint_short, elapsed, age.as_secs_f64());
                 }
             }
 
-            // Calculate P&L
-            let entry_price = if position.entry_price > 0.0 {
-                position.entry_price
-            } else {
-                current_price
-            };
-
-            let pnl_multiplier = if entry_price > 0.0 {
-                current_price / entry_price
-            } else {
-                1.0
+            // Mark P&L in place, so a fill landing since the snapshot keeps its
+            // entry; the first candle sets the entry if the fill hasn't
+            let pnl = match positions.lock().await.get_mut(&position.mint) {
+                Some(pos) => {
+                    pos.pnl.update(current_price);
+                    pos.pnl.clone()
+                }
+                None => continue,
             };
-            let pnl_percent = (pnl_multiplier - 1.0) * 100.0;
 
             // Get VWAP info
             let vwap = position.vwap_tracker.vwap();
             let vwap_distance = position.vwap_tracker.vwap_distance_percent();
 
-            // Get momentum
-            let momentum = momentum_detector.calculate_momentum(
+            // Get momentum on each timeframe; exits need them to agree
+            let timeframes = momentum_detector.calculate_timeframes(
                 &position.candle_builder,
+                position.trend_candles.as_ref(),
                 &position.vwap_tracker,
                 elapsed,
             );
+            let momentum = timeframes.fast;
+            let trend = match timeframes.trend {
+                Some(trend) => format!(" / trend {:.0}%", trend * 100.0),
+                None => String::new(),
+            };
 
             info!(
-                "   {} ({}s) - P&L: {:.1}x ({:+.0}%) | VWAP: {:.8} ({:+.0}%) | Mom: {:.0}% | Buy: {:.0}% | Holders: {}",
+                "   {} ({}s) - P&L: {:.1}x ({:+.0}%, {:+.4} SOL) | VWAP: {:.8} ({:+.0}%) | Mom: {:.0}%{} | Buy: {:.0}% | Holders: {}",
                 mint_short,
                 elapsed,
-                pnl_multiplier,
-                pnl_percent,
+                pnl.multiplier(),
+                pnl.percent(),
+                pnl.unrealized_sol(),
                 vwap,
                 vwap_distance,
                 momentum * 100.0,
+                trend,
                 candle.buy_ratio() * 100.0,
                 position.holder_count
             );
 
-            // TAKE PROFIT AT 2X
-            if !position.profits_taken && momentum_detector.should_take_profit(entry_price, current_price) {
-                info!("   🎯 2X PROFIT! Taking 50%");
-                match execute_sell(&client, &position.mint, 50, &paper_sim, Some("2X_PROFIT".to_string())).await {
-                    Ok(sig) => {
-                        info!("   ✅ SOLD 50%: {}", sig);
-                        if let Some(pos) = positions.lock().await.get_mut(&position.mint) {
-                            pos.profits_taken = true;
-                        }
-                    }
-                    Err(e) => error!("   ❌ Sell failed: {}", e),
-                }
-                continue;
-            }
+            let state = PositionState {
+                elapsed_secs: elapsed,
+                pnl_multiplier: pnl.multiplier(),
+                peak_multiplier: pnl.peak_multiplier(),
+                momentum: timeframes.confirmed(),
+                vwap_deviation: position.vwap_tracker.price_deviation(),
+                exits_taken: position.exits_taken,
+            };
 
-            // TIME-BASED MOMENTUM EXIT
-            let (should_exit, reason) = momentum_detector.check_time_exit(
+            let entry_price = pnl.entry_price().unwrap_or(0.0);
+            let take_profit = momentum_detector.take_profit_due(entry_price, pnl.price(), position.take_profits_fired);
+            let signal = momentum_detector.get_position_signal(
                 &position.candle_builder,
                 &position.vwap_tracker,
                 elapsed,
+                pnl.multiplier(),
             );
+            let scale_out = match signal {
+                MomentumSignal::ScaleOut { fraction } if position.scaled_out_candle != Some(candle.timestamp) => {
+                    Some(fraction)
+                }
+                _ => None,
+            };
+
+            let decision = if let Some((tiers, percent)) = take_profit {
+                let reached = position.take_profits_fired + tiers;
+                ExitDecision::sell(
+                    percent,
+                    ExitReason::TakeProfit,
+                    format!("Take profit tier {} at {:.1}x", reached, pnl.multiplier()),
+                )
+            } else if let Some(fraction) = scale_out {
+                ExitDecision::sell(
+                    ((fraction * 100.0).round() as u32).clamp(1, 100),
+                    ExitReason::ScaleOut,
+                    format!("Scaling out into a {:+.0}% candle at {:.1}x", candle.price_change_percent(), pnl.multiplier()),
+                )
+            } else if position.exits_taken == 0
+                // Upper band breakout at peak momentum takes the first profit on choppy tokens
+                && momentum_detector.should_take_band_profit(&position.candle_builder, momentum)
+            {
+                ExitDecision::sell(50, ExitReason::TakeProfit, "Close above upper Bollinger band")
+            } else if let Some(detail) = momentum_detector.vwap_band_exit(&position.vwap_tracker, elapsed) {
+                ExitDecision::sell(100, ExitReason::BelowVwap, detail)
+            } else {
+                exit_strategy.evaluate(&state)
+            };
+
+            let (percent, reason, detail) = match decision {
+                ExitDecision::Hold => continue,
+                ExitDecision::Sell { percent, reason, detail } => (percent, reason, detail),
+            };
+
+            if percent >= 100 {
+                info!("   ❌ EXIT - {}", detail);
+            } else {
+                info!("   🎯 {} - Selling {}%", detail, percent);
+            }
 
-            if should_exit {
-                info!("   ❌ EXIT - {}", reason);
-                match execute_sell(&client, &position.mint, 100, &paper_sim, Some(reason.clone())).await {
-                    Ok(sig) => {
-                        info!("   ✅ SOLD 100%: {}", sig);
-                        positions.lock().await.remove(&position.mint);
+            let sold = exit_position(&ctx, &position.mint, percent, reason).await;
+            // Only a sell that went through uses up its tiers or spike; a failed one retries next tick
+            if sold {
+                if let Some(pos) = positions.lock().await.get_mut(&position.mint) {
+                    match (take_profit, reason) {
+                        (Some((tiers, _)), ExitReason::TakeProfit) => pos.take_profits_fired += tiers,
+                        (_, ExitReason::ScaleOut) => pos.scaled_out_candle = Some(candle.timestamp),
+                        _ => {}
                     }
-                    Err(e) => error!("   ❌ Sell failed: {}", e),
                 }
             }
         }
@@ -410,3 +677,35 @@ async fn monitor_positions_loop(
         info!(""); // Blank line
     }
 }
+
+/// Sell `percent` of a position and update it; a full exit drops it and its trade stream.
+/// Returns whether the sell went through.
+async fn exit_position(ctx: &BotContext, mint: &str, percent: u32, reason: ExitReason) -> bool {
+    let BotContext { client, positions, paper_sim, ws_tx, notifier, config, .. } = ctx;
+    let slippage = config.sell_slippage;
+    let price = positions.lock().await.get(mint).map(|pos| pos.pnl.price());
+    match execute_sell(client, mint, percent, slippage, price, paper_sim, reason).await {
+        Ok(sig) => {
+            tracing::info!(event = "sell", mint, percent, slippage,
+                           signature = %sig, reason = %reason, "✅ SOLD");
+            let mut sell = Notification::sell(mint, &sig, reason);
+            let mut locked = positions.lock().await;
+            if percent >= 100 {
+                if let Some(position) = locked.remove(mint) {
+                    sell = sell.with_pnl(position.pnl.percent());
+                }
+                ws_tx.unsubscribe(&Subscription::TokenTrades(vec![mint.to_string()]));
+            } else if let Some(pos) = locked.get_mut(mint) {
+                sell = sell.with_pnl(pos.pnl.percent());
+                pos.exits_taken += 1;
+                pos.pnl.reduce(percent);
+            }
+            notifier.notify(sell);
+            true
+        }
+        Err(e) => {
+            tracing::error!(event = "sell_failed", mint, percent, error = %e, "❌ Sell failed");
+            false
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use crate::candle_builder::CandleBuilder;
 use crate::vwap::VWAPTracker;
+use pump_portal_sdk::exit::{TakeProfitTier, VwapMomentum, DEFAULT_TAKE_PROFIT};
+use pump_portal_sdk::{ExitDecision, ExitStrategy, PositionState};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum MomentumSignal {
@@ -7,20 +9,114 @@ pub enum MomentumSignal {
     Hold,         // Neutral momentum, keep holding
     WeakExit,     // Weak momentum, consider exit
     Dump,         // No momentum, exit immediately
+    ScaleOut { fraction: f64 },  // Parabolic spike in profit, sell this fraction of what's left
 }
 
-#[derive(Debug)]
+/// When to sell into a momentum spike (see `MomentumDetector::with_scale_out`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScaleOutRule {
+    pub spike_percent: f64,  // Candle price change that counts as a spike
+    pub min_multiple: f64,   // Position has to be up at least this much
+    pub fraction: f64,       // Share of the remaining position to sell
+}
+
+/// Momentum on the fast timeframe and, when one is tracked, the trend timeframe
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeframeMomentum {
+    pub fast: f64,
+    pub trend: Option<f64>,
+}
+
+impl TimeframeMomentum {
+    /// Momentum for exit decisions: both timeframes have to be weak to exit,
+    /// so one bad tick on the fast candles alone doesn't dump the position
+    pub fn confirmed(&self) -> f64 {
+        match self.trend {
+            Some(trend) => self.fast.max(trend),
+            None => self.fast,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct MomentumDetector {
     min_threshold: f64,  // Minimum momentum to hold (0.0-1.0)
+    exit_rules: VwapMomentum,  // Checkpoint + VWAP exit rules (no take-profit)
+    band_period: usize,  // Completed candles in the Bollinger window
+    band_std_mult: f64,  // Std devs from the middle band
+    squeeze_bandwidth: f64,  // Bands this narrow or narrower are a squeeze
+    vwap_band_std_mult: Option<f64>,  // Exit below VWAP - k·σ instead of the fixed deviation
+    take_profit: Vec<TakeProfitTier>,  // Partial sells by multiple, ascending
+    scale_out: Option<ScaleOutRule>,  // Sell into parabolic candles (off by default)
 }
 
 impl MomentumDetector {
     pub fn new(min_threshold: f64) -> Self {
         Self {
             min_threshold: min_threshold.clamp(0.0, 1.0),
+            exit_rules: VwapMomentum::default().with_take_profit(None),
+            band_period: 20,
+            band_std_mult: 2.0,
+            squeeze_bandwidth: 0.0,
+            vwap_band_std_mult: None,
+            take_profit: vec![DEFAULT_TAKE_PROFIT],
+            scale_out: None,
         }
     }
 
+    /// Set the Bollinger window used by `should_take_band_profit`
+    pub fn with_bollinger(mut self, period: usize, std_mult: f64) -> Self {
+        self.band_period = period;
+        self.band_std_mult = std_mult;
+        self
+    }
+
+    /// Treat bands with a bandwidth at or below `bandwidth` as a squeeze, where
+    /// an upper-band close is noise rather than a local top (0.0 = flat only)
+    pub fn with_squeeze(mut self, bandwidth: f64) -> Self {
+        self.squeeze_bandwidth = bandwidth.max(0.0);
+        self
+    }
+
+    /// Set the VWAP deviation that triggers an exit (0.05 = 5% below VWAP)
+    pub fn with_vwap_exit(mut self, deviation: f64) -> Self {
+        self.exit_rules = self.exit_rules.with_vwap_exit(deviation);
+        self
+    }
+
+    /// Exit when price breaks VWAP - `k` standard deviations, replacing the fixed
+    /// `with_vwap_exit` deviation so the exit scales with each token's volatility
+    pub fn with_vwap_bands(mut self, k: f64) -> Self {
+        self.vwap_band_std_mult = Some(k);
+        self.exit_rules = self.exit_rules.with_vwap_exit(f64::INFINITY);
+        self
+    }
+
+    /// Replace the take-profit tiers used by `take_profit_due` (empty = none)
+    pub fn with_take_profit(mut self, mut tiers: Vec<TakeProfitTier>) -> Self {
+        tiers.sort_by(|a, b| a.multiple.total_cmp(&b.multiple));
+        self.take_profit = tiers;
+        self
+    }
+
+    /// Sell `fraction` of the remaining position into any candle that rises
+    /// `spike_percent` or more while the position is up `min_multiple` or more
+    pub fn with_scale_out(mut self, spike_percent: f64, min_multiple: f64, fraction: f64) -> Self {
+        self.scale_out = Some(ScaleOutRule {
+            spike_percent,
+            min_multiple,
+            fraction: fraction.clamp(0.0, 1.0),
+        });
+        self
+    }
+
+    /// Full exit strategy for these rules: checkpoint/VWAP exits. Profit taking
+    /// is left to `take_profit_due`, and with `with_vwap_bands` the band exit
+    /// to `vwap_band_exit`.
+    pub fn exit_strategy(&self) -> VwapMomentum {
+        self.exit_rules.clone()
+    }
+
     /// Calculate momentum score (0.0-1.0)
     /// Uses: price change, VWAP position, volume acceleration, buy ratio
     pub fn calculate_momentum(
@@ -78,6 +174,20 @@ impl MomentumDetector {
         }
     }
 
+    /// Momentum on both timeframes; `trend` is `None` when only fast candles are built
+    pub fn calculate_timeframes(
+        &self,
+        fast: &CandleBuilder,
+        trend: Option<&CandleBuilder>,
+        vwap_tracker: &VWAPTracker,
+        elapsed_secs: u64,
+    ) -> TimeframeMomentum {
+        TimeframeMomentum {
+            fast: self.calculate_momentum(fast, vwap_tracker, elapsed_secs),
+            trend: trend.map(|candles| self.calculate_momentum(candles, vwap_tracker, elapsed_secs)),
+        }
+    }
+
     /// Check if we should exit at specific time checkpoints
     /// Returns (should_exit, reason)
     pub fn check_time_exit(
@@ -87,40 +197,51 @@ impl MomentumDetector {
         elapsed_secs: u64,
     ) -> (bool, String) {
         let momentum = self.calculate_momentum(candle_builder, vwap_tracker, elapsed_secs);
+        let (should_exit, reason) = self.evaluate_exit(momentum, vwap_tracker.price_deviation(), elapsed_secs);
+        if should_exit {
+            return (should_exit, reason);
+        }
 
-        // Time-based thresholds (increasing requirements over time)
-        let checkpoint = if elapsed_secs >= 60 {
-            (0.6, "60s checkpoint")
-        } else if elapsed_secs >= 45 {
-            (0.5, "45s checkpoint")
-        } else if elapsed_secs >= 30 {
-            (0.4, "30s checkpoint")
-        } else if elapsed_secs >= 20 {
-            (0.3, "20s checkpoint")
-        } else if elapsed_secs >= 10 {
-            (0.2, "10s checkpoint")
-        } else {
-            return (false, String::new()); // Too early to exit
-        };
-
-        let (threshold, checkpoint_name) = checkpoint;
-
-        if momentum < threshold {
-            return (
-                true,
-                format!("{} - momentum {:.1}% < {:.0}%", checkpoint_name, momentum * 100.0, threshold * 100.0)
-            );
+        match self.vwap_band_exit(vwap_tracker, elapsed_secs) {
+            Some(reason) => (true, reason),
+            None => (false, String::new()),
         }
+    }
 
-        // Additional VWAP-based exit (if price drops 5% below VWAP)
-        if vwap_tracker.should_exit_on_vwap(0.05) {
-            return (
-                true,
-                format!("Price {:.1}% below VWAP", vwap_tracker.vwap_distance_percent().abs())
-            );
+    /// Exit reason when `with_vwap_bands` is set and price broke the lower band
+    /// Like the fixed VWAP exit, never fires before the first checkpoint
+    pub fn vwap_band_exit(&self, vwap_tracker: &VWAPTracker, elapsed_secs: u64) -> Option<String> {
+        let k = self.vwap_band_std_mult?;
+        let first_checkpoint = self.exit_rules.checkpoints.first()?.0;
+        if elapsed_secs < first_checkpoint || !vwap_tracker.below_lower_band(k) {
+            return None;
         }
+        Some(format!(
+            "Price below VWAP - {}σ band ({:.1}% below VWAP)",
+            k,
+            (vwap_tracker.price_deviation() * 100.0).abs()
+        ))
+    }
+
+    /// Apply the checkpoint and VWAP exit rules to precomputed values
+    /// `vwap_deviation` is a fraction (-0.05 = 5% below VWAP)
+    /// Returns (should_exit, reason)
+    pub fn evaluate_exit(
+        &self,
+        momentum: f64,
+        vwap_deviation: f64,
+        elapsed_secs: u64,
+    ) -> (bool, String) {
+        let state = PositionState {
+            momentum,
+            vwap_deviation,
+            ..PositionState::at(elapsed_secs)
+        };
 
-        (false, String::new())
+        match self.exit_rules.evaluate(&state) {
+            ExitDecision::Sell { detail, .. } => (true, detail),
+            ExitDecision::Hold => (false, String::new()),
+        }
     }
 
     /// Get momentum signal for display
@@ -143,17 +264,83 @@ impl MomentumDetector {
         }
     }
 
-    /// Check if we should take profit at 2x
+    /// Signal for a held position: `ScaleOut` when the current candle is a
+    /// spike and the position is far enough in profit, else `get_signal`
+    pub fn get_position_signal(
+        &self,
+        candle_builder: &CandleBuilder,
+        vwap_tracker: &VWAPTracker,
+        elapsed_secs: u64,
+        pnl_multiplier: f64,
+    ) -> MomentumSignal {
+        if let (Some(rule), Some(candle)) = (self.scale_out, candle_builder.current_candle()) {
+            if pnl_multiplier >= rule.min_multiple && candle.price_change_percent() >= rule.spike_percent {
+                return MomentumSignal::ScaleOut { fraction: rule.fraction };
+            }
+        }
+        self.get_signal(candle_builder, vwap_tracker, elapsed_secs)
+    }
+
+    /// Bollinger bandwidth at or below the squeeze threshold, or too few candles to tell
+    pub fn is_squeezed(&self, candle_builder: &CandleBuilder) -> bool {
+        candle_builder.bollinger_bandwidth(self.band_period, self.band_std_mult) <= self.squeeze_bandwidth.max(1e-9)
+    }
+
+    /// Take profit when the last candle closed above the upper Bollinger band
+    /// while momentum is at StrongBuy levels (likely near the local top)
+    pub fn should_take_band_profit(&self, candle_builder: &CandleBuilder, momentum: f64) -> bool {
+        if momentum < 0.7 || self.is_squeezed(candle_builder) {
+            return false;
+        }
+
+        match (
+            candle_builder.bollinger(self.band_period, self.band_std_mult),
+            candle_builder.latest_candle(),
+        ) {
+            (Some(bands), Some(candle)) => candle.close > bands.upper,
+            _ => false,
+        }
+    }
+
+    /// Check if price has reached `target` times the entry
     pub fn should_take_profit(
         &self,
         entry_price: f64,
         current_price: f64,
+        target: f64,
     ) -> bool {
-        if entry_price == 0.0 {
+        if entry_price <= 0.0 {
             return false;
         }
         let multiplier = current_price / entry_price;
-        multiplier >= 2.0
+        multiplier >= target
+    }
+
+    /// Take-profit tiers reached that this position hasn't sold yet, as
+    /// (tiers now fired, percent of the remaining tokens to sell)
+    ///
+    /// Tiers fire in order, so `fired` (tiers already sold) says which ones are
+    /// done. A price that jumps past several tiers sells them together.
+    pub fn take_profit_due(&self, entry_price: f64, current_price: f64, fired: usize) -> Option<(usize, u32)> {
+        let done = fired.min(self.take_profit.len());
+        let due = self.take_profit[done..]
+            .iter()
+            .take_while(|tier| self.should_take_profit(entry_price, current_price, tier.multiple))
+            .count();
+        if due == 0 {
+            return None;
+        }
+
+        // Fractions are of the original position; sells are of what's left
+        let sold: f64 = self.take_profit[..done].iter().map(|tier| tier.fraction).sum();
+        let selling: f64 = self.take_profit[done..done + due].iter().map(|tier| tier.fraction).sum();
+        let remaining = 1.0 - sold;
+        let percent = if remaining > 1e-9 {
+            (selling / remaining * 100.0).round().min(100.0) as u32
+        } else {
+            100
+        };
+        Some((due, percent))
     }
 }
 
@@ -167,6 +354,57 @@ impl Default for MomentumDetector {
 mod tests {
     use super::*;
     use crate::trade_events::TradeEvent;
+    use std::time::{Duration, Instant};
+
+    const CHECKPOINTS: [u64; 5] = [10, 20, 30, 45, 60];
+
+    /// Replay `count` trades 250ms apart into 1s candles and a VWAP tracker
+    fn replay(count: u64, trade: impl Fn(u64) -> (f64, bool)) -> (CandleBuilder, VWAPTracker) {
+        let start = Instant::now();
+        let mut candle_builder = CandleBuilder::new(1000, 100);
+        let mut vwap_tracker = VWAPTracker::new();
+        for i in 0..count {
+            let (price, is_buy) = trade(i);
+            let event = TradeEvent::at(start + Duration::from_millis(i * 250), price, 0.02, is_buy);
+            candle_builder.add_trade(&event);
+            vwap_tracker.add_trade(&event);
+        }
+        (candle_builder, vwap_tracker)
+    }
+
+    /// Steady buys, +2% per trade (~2.2x over 10s)
+    fn pumping() -> (CandleBuilder, VWAPTracker) {
+        replay(40, |i| (0.0001 * 1.02f64.powi(i as i32), true))
+    }
+
+    /// Steady sells, -3% per trade
+    fn dumping() -> (CandleBuilder, VWAPTracker) {
+        replay(40, |i| (0.0001 * 0.97f64.powi(i as i32), false))
+    }
+
+    /// Buys tick up 2%, sells knock it back, ending on a sell
+    fn choppy() -> (CandleBuilder, VWAPTracker) {
+        replay(39, |i| if i % 2 == 0 { (0.0001, false) } else { (0.000102, true) })
+    }
+
+    /// Same replay into 500ms fast and 5s trend candles
+    fn replay_timeframes(
+        count: u64,
+        trade: impl Fn(u64) -> (f64, bool),
+    ) -> (CandleBuilder, CandleBuilder, VWAPTracker) {
+        let start = Instant::now();
+        let mut fast = CandleBuilder::new(500, 100);
+        let mut trend = CandleBuilder::new(5000, 100);
+        let mut vwap_tracker = VWAPTracker::new();
+        for i in 0..count {
+            let (price, is_buy) = trade(i);
+            let event = TradeEvent::at(start + Duration::from_millis(i * 250), price, 0.02, is_buy);
+            fast.add_trade(&event);
+            trend.add_trade(&event);
+            vwap_tracker.add_trade(&event);
+        }
+        (fast, trend, vwap_tracker)
+    }
 
     #[test]
     fn test_momentum_calculation() {
@@ -218,14 +456,262 @@ mod tests {
         println!("Exit decision: {}, reason: {}", should_exit, reason);
     }
 
+    #[test]
+    fn test_band_profit_needs_breakout_and_momentum() {
+        let detector = MomentumDetector::new(0.2).with_bollinger(5, 1.0);
+        let start = std::time::Instant::now();
+        let mut candle_builder = CandleBuilder::new(100, 100);
+
+        // Flat closes, then a spike that completes as the latest candle
+        let closes = [0.0001, 0.0001, 0.0001, 0.0001, 0.0002, 0.0002];
+        for (i, price) in closes.iter().enumerate() {
+            let at = start + std::time::Duration::from_millis(i as u64 * 100);
+            candle_builder.add_trade(&TradeEvent::at(at, *price, 0.02, true));
+        }
+
+        assert!(detector.should_take_band_profit(&candle_builder, 0.8));
+        assert!(!detector.should_take_band_profit(&candle_builder, 0.5));
+
+        // Flat series has zero width, never triggers
+        let flat = CandleBuilder::new(100, 100);
+        assert!(detector.is_squeezed(&flat));
+        assert!(!detector.should_take_band_profit(&flat, 0.9));
+
+        // The breakout window is ~0.67 wide; a wider squeeze threshold ignores it
+        assert!(!detector.is_squeezed(&candle_builder));
+        let squeezed = detector.clone().with_squeeze(1.0);
+        assert!(squeezed.is_squeezed(&candle_builder));
+        assert!(!squeezed.should_take_band_profit(&candle_builder, 0.8));
+    }
+
+    #[test]
+    fn test_pumping_token_holds_every_checkpoint() {
+        let detector = MomentumDetector::default();
+        let (candle_builder, vwap_tracker) = pumping();
+
+        let momentum = detector.calculate_momentum(&candle_builder, &vwap_tracker, 10);
+        assert!(momentum > 0.7, "momentum {}", momentum);
+        assert_eq!(detector.get_signal(&candle_builder, &vwap_tracker, 10), MomentumSignal::StrongBuy);
+
+        for secs in CHECKPOINTS {
+            let (should_exit, reason) = detector.check_time_exit(&candle_builder, &vwap_tracker, secs);
+            assert!(!should_exit, "exited at {}s: {}", secs, reason);
+        }
+    }
+
+    #[test]
+    fn test_dumping_token_exits_at_first_checkpoint() {
+        let detector = MomentumDetector::default();
+        let (candle_builder, vwap_tracker) = dumping();
+
+        let momentum = detector.calculate_momentum(&candle_builder, &vwap_tracker, 10);
+        assert!(momentum < 0.2, "momentum {}", momentum);
+        assert_eq!(detector.get_signal(&candle_builder, &vwap_tracker, 10), MomentumSignal::Dump);
+
+        // Too early to exit, even far below VWAP
+        assert!(vwap_tracker.should_exit_on_vwap(0.05));
+        let (should_exit, _) = detector.check_time_exit(&candle_builder, &vwap_tracker, 9);
+        assert!(!should_exit);
+
+        let (should_exit, reason) = detector.check_time_exit(&candle_builder, &vwap_tracker, 10);
+        assert!(should_exit);
+        assert!(reason.starts_with("10s checkpoint"), "reason: {}", reason);
+    }
+
+    #[test]
+    fn test_choppy_token_fades_out_at_later_checkpoint() {
+        let detector = MomentumDetector::default();
+        let (candle_builder, vwap_tracker) = choppy();
+
+        // Just under neutral, and less than 1% off VWAP
+        let momentum = detector.calculate_momentum(&candle_builder, &vwap_tracker, 10);
+        assert!(momentum > 0.4 && momentum < 0.5, "momentum {}", momentum);
+        assert!(!vwap_tracker.should_exit_on_vwap(0.05));
+        assert_eq!(detector.get_signal(&candle_builder, &vwap_tracker, 10), MomentumSignal::WeakExit);
+
+        for secs in [10, 20, 30] {
+            let (should_exit, reason) = detector.check_time_exit(&candle_builder, &vwap_tracker, secs);
+            assert!(!should_exit, "exited at {}s: {}", secs, reason);
+        }
+
+        let (should_exit, reason) = detector.check_time_exit(&candle_builder, &vwap_tracker, 45);
+        assert!(should_exit);
+        assert!(reason.starts_with("45s checkpoint"), "reason: {}", reason);
+    }
+
+    #[test]
+    fn test_exit_below_vwap_despite_momentum() {
+        let detector = MomentumDetector::default();
+
+        // Flat buying at 0.0001, then a buy fills 6% lower
+        let (candle_builder, vwap_tracker) =
+            replay(11, |i| if i < 10 { (0.0001, true) } else { (0.000094, true) });
+        assert!(detector.calculate_momentum(&candle_builder, &vwap_tracker, 10) >= 0.2);
+
+        let (should_exit, reason) = detector.check_time_exit(&candle_builder, &vwap_tracker, 10);
+        assert!(should_exit);
+        assert!(reason.contains("below VWAP"), "reason: {}", reason);
+
+        // Exactly 5% below holds, anything further exits
+        assert!(!detector.evaluate_exit(0.9, -0.05, 30).0);
+        assert!(detector.evaluate_exit(0.9, -0.0501, 30).0);
+        assert!(!detector.with_vwap_exit(0.1).evaluate_exit(0.9, -0.0501, 30).0);
+    }
+
+    #[test]
+    fn test_trend_overrules_one_bad_tick() {
+        let detector = MomentumDetector::default();
+
+        // Pump, then one sell 30% down opens a fresh fast candle
+        let (fast, trend, vwap_tracker) = replay_timeframes(39, |i| {
+            let price = 0.0001 * 1.02f64.powi(i.min(37) as i32);
+            if i < 38 { (price, true) } else { (price * 0.7, false) }
+        });
+        let momentum = detector.calculate_timeframes(&fast, Some(&trend), &vwap_tracker, 20);
+        assert!(momentum.fast < 0.3, "fast {}", momentum.fast);
+        assert!(momentum.trend.unwrap() > 0.5, "trend {:?}", momentum.trend);
+
+        // Fast alone exits at the 20s checkpoint, the trend keeps the position through 45s
+        let deviation = vwap_tracker.price_deviation();
+        assert!(detector.evaluate_exit(momentum.fast, deviation, 20).0);
+        for secs in [20, 30, 45] {
+            let (should_exit, reason) = detector.evaluate_exit(momentum.confirmed(), deviation, secs);
+            assert!(!should_exit, "exited at {}s: {}", secs, reason);
+        }
+    }
+
+    #[test]
+    fn test_timeframes_agree_on_dump() {
+        let detector = MomentumDetector::default();
+        let (fast, trend, vwap_tracker) = replay_timeframes(40, |i| (0.0001 * 0.97f64.powi(i as i32), false));
+
+        let momentum = detector.calculate_timeframes(&fast, Some(&trend), &vwap_tracker, 10);
+        assert!(momentum.confirmed() < 0.2, "{:?}", momentum);
+        assert!(detector.evaluate_exit(momentum.confirmed(), vwap_tracker.price_deviation(), 10).0);
+
+        // Without a trend timeframe the fast momentum is used as is
+        let fast_only = detector.calculate_timeframes(&fast, None, &vwap_tracker, 10);
+        assert_eq!(fast_only.confirmed(), momentum.fast);
+    }
+
+    #[test]
+    fn test_vwap_band_exit_on_noisy_series() {
+        let fixed = MomentumDetector::default();
+        let banded = MomentumDetector::default().with_vwap_bands(2.0);
+
+        // Noisy buying around 0.0001 (swings of up to 8%), then a 7% dip
+        let swings = [1.00, 1.06, 0.95, 1.04, 0.97, 1.08, 0.94, 1.02];
+        let (candle_builder, mut vwap_tracker) = replay(41, |i| {
+            if i < 40 { (0.0001 * swings[i as usize % 8], true) } else { (0.000094, true) }
+        });
+        assert!(fixed.calculate_momentum(&candle_builder, &vwap_tracker, 10) >= 0.2);
+
+        // Fixed 5% exits on noise the bands absorb
+        let (should_exit, reason) = fixed.check_time_exit(&candle_builder, &vwap_tracker, 10);
+        assert!(should_exit && reason.contains("below VWAP"), "reason: {}", reason);
+        let (should_exit, reason) = banded.check_time_exit(&candle_builder, &vwap_tracker, 10);
+        assert!(!should_exit, "exited: {}", reason);
+
+        // Breaking the band exits, but not before the first checkpoint
+        vwap_tracker.add_trade(&TradeEvent::new_sell(0.000085, 0.02));
+        assert!(banded.vwap_band_exit(&vwap_tracker, 9).is_none());
+        let (should_exit, reason) = banded.check_time_exit(&candle_builder, &vwap_tracker, 10);
+        assert!(should_exit);
+        assert!(reason.starts_with("Price below VWAP - 2σ band"), "reason: {}", reason);
+
+        // Momentum checkpoints still apply with bands on
+        assert!(banded.evaluate_exit(0.1, 0.0, 10).0);
+        assert!(!banded.evaluate_exit(0.9, -0.5, 10).0);
+    }
+
+    #[test]
+    fn test_take_profit_boundary() {
+        let detector = MomentumDetector::default();
+
+        // Pump runs past 2x from its first trade
+        let (_, vwap_tracker) = pumping();
+        assert!(detector.should_take_profit(0.0001, vwap_tracker.last_price(), 2.0));
+
+        assert!(!detector.should_take_profit(0.0001, 0.000199999, 2.0));
+        assert!(!detector.should_take_profit(0.0, 0.0002, 2.0));
+
+        // Default tier sells half at exactly 2x, once
+        assert_eq!(detector.take_profit_due(0.0001, 0.0002, 0), Some((1, 50)));
+        assert_eq!(detector.take_profit_due(0.0001, 0.0002, 1), None);
+        assert_eq!(detector.take_profit_due(0.0001, 0.000199, 0), None);
+
+        // Profit taking is no longer part of the strategy: a dead 2x just exits
+        let strategy = detector.exit_strategy();
+        let at_two_x = PositionState {
+            pnl_multiplier: 2.0,
+            momentum: 0.0,
+            ..PositionState::at(30)
+        };
+        assert!(strategy.evaluate(&at_two_x).is_full_exit());
+        let below = PositionState {
+            pnl_multiplier: 1.99,
+            ..PositionState::at(5)
+        };
+        assert_eq!(strategy.evaluate(&below), ExitDecision::Hold);
+    }
+
+    #[test]
+    fn test_take_profit_tiers() {
+        let detector = MomentumDetector::default().with_take_profit(vec![
+            TakeProfitTier { multiple: 3.0, fraction: 0.33 },
+            TakeProfitTier { multiple: 1.8, fraction: 0.33 },
+        ]);
+
+        assert_eq!(detector.take_profit_due(1.0, 1.7, 0), None);
+        assert_eq!(detector.take_profit_due(1.0, 1.8, 0), Some((1, 33)));
+        // A third of the original is about half of the two thirds left
+        assert_eq!(detector.take_profit_due(1.0, 2.5, 1), None);
+        assert_eq!(detector.take_profit_due(1.0, 3.0, 1), Some((1, 49)));
+        assert_eq!(detector.take_profit_due(1.0, 10.0, 2), None);
+
+        // Gapping past both tiers sells both at once
+        assert_eq!(detector.take_profit_due(1.0, 3.5, 0), Some((2, 66)));
+
+        let off = MomentumDetector::default().with_take_profit(Vec::new());
+        assert_eq!(off.take_profit_due(1.0, 10.0, 0), None);
+    }
+
+    #[test]
+    fn test_scale_out_into_spike() {
+        let detector = MomentumDetector::default().with_scale_out(30.0, 2.0, 0.25);
+
+        // One 1s candle running from 0.0001 to 0.00014 (+40%)
+        let (spike, vwap_tracker) = replay(4, |i| (0.0001 + 0.0000133 * i as f64, true));
+        let candle = spike.current_candle().unwrap();
+        assert!(candle.price_change_percent() > 39.0, "change {}", candle.price_change_percent());
+
+        assert_eq!(
+            detector.get_position_signal(&spike, &vwap_tracker, 20, 2.5),
+            MomentumSignal::ScaleOut { fraction: 0.25 }
+        );
+        // Not up enough yet: the spike is just strong momentum
+        assert_eq!(detector.get_position_signal(&spike, &vwap_tracker, 20, 1.5), MomentumSignal::StrongBuy);
+
+        // A steady pump never spikes 30% inside one candle
+        let (candle_builder, vwap_tracker) = pumping();
+        assert_eq!(detector.get_position_signal(&candle_builder, &vwap_tracker, 20, 3.0), MomentumSignal::StrongBuy);
+
+        // Off by default
+        let (spike, vwap_tracker) = replay(4, |i| (0.0001 + 0.0000133 * i as f64, true));
+        assert_ne!(
+            MomentumDetector::default().get_position_signal(&spike, &vwap_tracker, 20, 2.5),
+            MomentumSignal::ScaleOut { fraction: 0.25 }
+        );
+    }
+
     #[test]
     fn test_profit_taking() {
         let detector = MomentumDetector::default();
 
         // Should take profit at 2x
-        assert!(detector.should_take_profit(0.0001, 0.0002));
+        assert!(detector.should_take_profit(0.0001, 0.0002, 2.0));
 
         // Should not take profit below 2x
-        assert!(!detector.should_take_profit(0.0001, 0.00015));
+        assert!(!detector.should_take_profit(0.0001, 0.00015, 2.0));
     }
 }
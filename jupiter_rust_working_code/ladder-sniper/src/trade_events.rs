@@ -1,3 +1,4 @@
+use pump_portal_sdk::{PumpPortalEvent, TradeSide};
 use std::time::Instant;
 
 #[derive(Debug, Clone)]
@@ -27,6 +28,16 @@ impl TradeEvent {
         }
     }
 
+    /// Trade at an explicit time, for replaying history
+    pub fn at(timestamp: Instant, price: f64, volume_sol: f64, is_buy: bool) -> Self {
+        Self {
+            timestamp,
+            price,
+            volume_sol,
+            is_buy,
+        }
+    }
+
     /// Calculate price from bonding curve reserves
     /// Formula: price = sol_reserves / token_reserves
     pub fn calculate_price(sol_reserves: f64, token_reserves: f64) -> f64 {
@@ -59,6 +70,22 @@ impl TradeEvent {
         }
     }
 
+    /// Convert a PumpPortal buy or sell, pricing off the post-trade bonding curve
+    /// Falls back to the trade's own SOL/token ratio when reserves are missing
+    /// Returns None for non-trade events (e.g. "create") or zero-size trades
+    pub fn from_pumpportal(event: &PumpPortalEvent) -> Option<Self> {
+        let (side, trade) = event.trade()?;
+        let price = trade.price()?;
+        if trade.sol_amount <= 0.0 {
+            return None;
+        }
+
+        Some(match side {
+            TradeSide::Buy => Self::new_buy(price, trade.sol_amount),
+            TradeSide::Sell => Self::new_sell(price, trade.sol_amount),
+        })
+    }
+
     /// Get elapsed milliseconds since event
     pub fn elapsed_ms(&self) -> u128 {
         self.timestamp.elapsed().as_millis()
@@ -97,4 +124,22 @@ mod tests {
         // Should receive some tokens
         assert!(tokens > 0.0);
     }
+
+    #[test]
+    fn test_parse_token_trade() {
+        let json = r#"{"signature":"sig","mint":"Mint111","traderPublicKey":"Trader","txType":"sell","tokenAmount":1000000.0,"solAmount":0.5,"vTokensInBondingCurve":1000000000.0,"vSolInBondingCurve":30.0,"marketCapSol":30.0,"pool":"pump"}"#;
+        let event: PumpPortalEvent = serde_json::from_str(json).unwrap();
+        let trade = TradeEvent::from_pumpportal(&event).unwrap();
+
+        assert!(!trade.is_buy);
+        assert_eq!(trade.volume_sol, 0.5);
+        assert!((trade.price - 3e-8).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_create_message_is_not_a_trade() {
+        let json = r#"{"mint":"Mint111","txType":"create","solAmount":1.0,"tokenAmount":1000.0}"#;
+        let event: PumpPortalEvent = serde_json::from_str(json).unwrap();
+        assert!(TradeEvent::from_pumpportal(&event).is_none());
+    }
 }
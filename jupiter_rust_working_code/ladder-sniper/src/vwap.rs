@@ -6,6 +6,7 @@ pub struct VWAPTracker {
     start_time: Instant,
     cumulative_pv: f64,      // Sum of price * volume
     cumulative_volume: f64,  // Sum of volume
+    cumulative_p2v: f64,     // Sum of price^2 * volume, for variance around VWAP
     vwap: f64,
     last_price: f64,
     trade_count: u32,
@@ -17,6 +18,7 @@ impl VWAPTracker {
             start_time: Instant::now(),
             cumulative_pv: 0.0,
             cumulative_volume: 0.0,
+            cumulative_p2v: 0.0,
             vwap: 0.0,
             last_price: 0.0,
             trade_count: 0,
@@ -28,6 +30,7 @@ impl VWAPTracker {
         let pv = trade.price * trade.volume_sol;
         self.cumulative_pv += pv;
         self.cumulative_volume += trade.volume_sol;
+        self.cumulative_p2v += trade.price * pv;
         self.last_price = trade.price;
         self.trade_count += 1;
 
@@ -47,6 +50,31 @@ impl VWAPTracker {
         self.last_price
     }
 
+    /// Volume-weighted standard deviation of trade prices around VWAP
+    pub fn std_dev(&self) -> f64 {
+        if self.cumulative_volume == 0.0 {
+            return 0.0;
+        }
+        let variance = self.cumulative_p2v / self.cumulative_volume - self.vwap * self.vwap;
+        variance.max(0.0).sqrt() // Rounding can leave a tiny negative
+    }
+
+    /// VWAP + k standard deviations
+    pub fn vwap_upper_band(&self, k: f64) -> f64 {
+        self.vwap + k * self.std_dev()
+    }
+
+    /// VWAP - k standard deviations
+    pub fn vwap_lower_band(&self, k: f64) -> f64 {
+        self.vwap - k * self.std_dev()
+    }
+
+    /// Has the last trade broken below the lower band?
+    /// Never true before prices have any spread, when the band sits on VWAP itself
+    pub fn below_lower_band(&self, k: f64) -> bool {
+        self.std_dev() > 0.0 && self.last_price < self.vwap_lower_band(k)
+    }
+
     /// Get price deviation from VWAP (-1.0 to +1.0, where 0 = at VWAP)
     pub fn price_deviation(&self) -> f64 {
         if self.vwap == 0.0 {
@@ -119,6 +147,7 @@ impl VWAPTracker {
         self.start_time = Instant::now();
         self.cumulative_pv = 0.0;
         self.cumulative_volume = 0.0;
+        self.cumulative_p2v = 0.0;
         self.vwap = 0.0;
         self.last_price = 0.0;
         self.trade_count = 0;
@@ -184,6 +213,57 @@ mod tests {
         assert!(!tracker.should_exit_on_vwap(0.15)); // 15% threshold
     }
 
+    /// Trade the same `cycle` of prices (x 0.0001) `repeats` times
+    fn cycled(cycle: &[f64], repeats: usize) -> VWAPTracker {
+        let mut tracker = VWAPTracker::new();
+        for _ in 0..repeats {
+            for price in cycle {
+                tracker.add_trade(&TradeEvent::new_buy(price * 0.0001, 0.02));
+            }
+        }
+        tracker
+    }
+
+    #[test]
+    fn test_vwap_bands() {
+        let mut tracker = VWAPTracker::new();
+        assert_eq!(tracker.std_dev(), 0.0);
+
+        // Two prices, equal volume: σ is half the spread
+        tracker.add_trade(&TradeEvent::new_buy(0.0001, 0.02));
+        tracker.add_trade(&TradeEvent::new_buy(0.0003, 0.02));
+        assert!((tracker.std_dev() - 0.0001).abs() < 1e-12);
+        assert!((tracker.vwap_upper_band(2.0) - 0.0004).abs() < 1e-12);
+        assert!((tracker.vwap_lower_band(1.0) - 0.0001).abs() < 1e-12);
+
+        // Flat prices have no spread and never break the band
+        let flat = cycled(&[1.0], 10);
+        assert!(flat.std_dev() < 1e-12);
+        assert!(!flat.below_lower_band(2.0));
+    }
+
+    #[test]
+    fn test_lower_band_adapts_to_volatility() {
+        let noisy_cycle = [1.00, 1.06, 0.95, 1.04, 0.97, 1.08, 0.94, 1.02];
+        let calm_cycle = [1.000, 1.004];
+
+        // Noisy token: a ~7% dip is inside its 2σ band, though past the fixed 5%
+        let mut noisy = cycled(&noisy_cycle, 5);
+        noisy.add_trade(&TradeEvent::new_sell(0.000094, 0.02));
+        assert!(noisy.should_exit_on_vwap(0.05));
+        assert!(!noisy.below_lower_band(2.0));
+
+        // A real break clears the band too
+        noisy.add_trade(&TradeEvent::new_sell(0.000085, 0.02));
+        assert!(noisy.below_lower_band(2.0));
+
+        // Calm token: a 3% dip is well outside its band, though inside the fixed 5%
+        let mut calm = cycled(&calm_cycle, 20);
+        calm.add_trade(&TradeEvent::new_sell(0.000097, 0.02));
+        assert!(!calm.should_exit_on_vwap(0.05));
+        assert!(calm.below_lower_band(2.0));
+    }
+
     #[test]
     fn test_vwap_strength() {
         let mut tracker = VWAPTracker::new();
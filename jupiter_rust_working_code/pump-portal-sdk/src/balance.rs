@@ -0,0 +1,196 @@
+//! Pre-flight SOL balance check for wallets the bot signs for itself
+//!
+//! A buy from a wallet without enough SOL still takes a position slot and then
+//! fails with an opaque simulation error, after the priority fee has been spent
+//! building it. [`BalanceCheck`] reads the wallet's balance (`getBalance`) and
+//! refuses the trade with `PumpPortalError::InsufficientFunds` before anything
+//! is built or signed.
+//!
+//! The balance is cached for a short TTL and debited as trades pass the check,
+//! so a burst of buys costs one RPC call rather than one each, and doesn't all
+//! pass on the same stale balance.
+
+use crate::{PumpPortalClient, PumpPortalError, Result, TradeAction, TradeRequest};
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// How long a fetched balance is trusted
+pub const DEFAULT_BALANCE_TTL: Duration = Duration::from_secs(2);
+
+/// SOL kept back on top of every trade: rent for a new token account
+/// (~0.00204 SOL) plus network fees
+pub const DEFAULT_FEE_BUFFER_SOL: f64 = 0.003;
+
+impl PumpPortalClient {
+    /// SOL balance of `wallet` (a base58 public key) at `confirmed` commitment
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use pump_portal_sdk::PumpPortalClient;
+    /// # async fn example() -> pump_portal_sdk::Result<()> {
+    /// let client = PumpPortalClient::new("your-api-key".to_string());
+    /// let sol = client.get_balance("WalletPublicKey", "https://api.mainnet-beta.solana.com").await?;
+    /// println!("{} SOL", sol);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_balance(&self, wallet: &str, rpc_url: &str) -> Result<f64> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getBalance",
+            "params": [wallet, { "commitment": "confirmed" }],
+        });
+
+        let response: Value = self
+            .http()
+            .post(rpc_url)
+            .timeout(self.timeout())
+            .json(&body)
+            .send()
+            .await
+            .map_err(PumpPortalError::from_request)?
+            .json()
+            .await
+            .map_err(PumpPortalError::from_request)?;
+
+        if let Some(error) = response.get("error") {
+            return Err(PumpPortalError::ApiError(format!("RPC error: {}", error)));
+        }
+        let lamports = response
+            .pointer("/result/value")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| PumpPortalError::ApiError(format!("getBalance returned no balance: {}", response)))?;
+        Ok(lamports as f64 / LAMPORTS_PER_SOL)
+    }
+}
+
+/// Refuses trades the wallet can't pay for
+///
+/// # Example
+///
+/// ```no_run
+/// # use pump_portal_sdk::{balance::BalanceCheck, PumpPortalClient, TradeRequest};
+/// # async fn example() -> pump_portal_sdk::Result<()> {
+/// let client = PumpPortalClient::new("your-api-key".to_string());
+/// let balance = BalanceCheck::new("WalletPublicKey", "https://api.mainnet-beta.solana.com");
+///
+/// let request = TradeRequest::buy("TokenMintAddress".to_string(), 0.05, 10, 0.0001);
+/// balance.ensure(&client, &request).await?; // InsufficientFunds below ~0.0531 SOL
+/// // ... build, sign and send the transaction
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct BalanceCheck {
+    wallet: String,
+    rpc_url: String,
+    ttl: Duration,
+    fee_buffer_sol: f64,
+    /// When the balance was fetched, and what's left of it after the trades since
+    cached: Mutex<Option<(Instant, f64)>>,
+}
+
+impl BalanceCheck {
+    pub fn new(wallet: &str, rpc_url: &str) -> Self {
+        Self {
+            wallet: wallet.to_string(),
+            rpc_url: rpc_url.to_string(),
+            ttl: DEFAULT_BALANCE_TTL,
+            fee_buffer_sol: DEFAULT_FEE_BUFFER_SOL,
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// Trust a fetched balance this long (default 2s; zero fetches every time)
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// SOL kept back on top of each trade (default [`DEFAULT_FEE_BUFFER_SOL`])
+    pub fn with_fee_buffer(mut self, sol: f64) -> Self {
+        self.fee_buffer_sol = sol.max(0.0);
+        self
+    }
+
+    /// SOL the wallet needs for `request`: a SOL-denominated buy's amount,
+    /// plus the priority fee and fee buffer for any trade
+    pub fn required_sol(&self, request: &TradeRequest) -> f64 {
+        self.spend_sol(request) + self.fee_buffer_sol
+    }
+
+    /// SOL `request` takes out of the wallet, leaving the buffer aside
+    fn spend_sol(&self, request: &TradeRequest) -> f64 {
+        let amount = match request.action {
+            TradeAction::Buy if request.denominated_in_sol => request.amount.parse::<f64>().unwrap_or(0.0),
+            _ => 0.0,
+        };
+        amount + request.priority_fee
+    }
+
+    /// Check the wallet can pay for `request`, debiting the cached balance if so
+    ///
+    /// Dry-run clients skip the check; nothing is spent.
+    ///
+    /// # Errors
+    ///
+    /// `InsufficientFunds` if the balance is short, or the RPC error if it
+    /// couldn't be read.
+    pub async fn ensure(&self, client: &PumpPortalClient, request: &TradeRequest) -> Result<()> {
+        if client.is_dry_run() {
+            return Ok(());
+        }
+
+        // Held across the fetch, so a burst waits on one RPC call
+        let mut cached = self.cached.lock().await;
+        let (fetched_at, balance) = match *cached {
+            Some((at, balance)) if at.elapsed() < self.ttl => (at, balance),
+            _ => (Instant::now(), client.get_balance(&self.wallet, &self.rpc_url).await?),
+        };
+
+        let required = self.required_sol(request);
+        if balance < required {
+            *cached = Some((fetched_at, balance));
+            log::warn!(
+                "Wallet has {:.4} SOL, {:?} {} needs {:.4} SOL",
+                balance,
+                request.action,
+                request.mint,
+                required
+            );
+            return Err(PumpPortalError::InsufficientFunds);
+        }
+
+        *cached = Some((fetched_at, balance - self.spend_sol(request)));
+        Ok(())
+    }
+
+    /// Forget the cached balance, e.g. after a trade failed or SOL was deposited
+    pub async fn invalidate(&self) {
+        *self.cached.lock().await = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_sol() {
+        let check = BalanceCheck::new("wallet", "http://localhost").with_fee_buffer(0.002);
+
+        let buy = TradeRequest::buy("mint".to_string(), 0.05, 10, 0.0001);
+        assert!((check.required_sol(&buy) - 0.0521).abs() < 1e-12);
+
+        // Sells and token-denominated buys only pay fees
+        let sell = TradeRequest::sell("mint".to_string(), "100%".to_string(), 10, 0.0001);
+        assert!((check.required_sol(&sell) - 0.0021).abs() < 1e-12);
+        let by_tokens = TradeRequest::buy_tokens("mint".to_string(), 1_000_000.0, 10, 0.0001);
+        assert!((check.required_sol(&by_tokens) - 0.0021).abs() < 1e-12);
+    }
+}
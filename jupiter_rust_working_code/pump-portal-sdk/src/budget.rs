@@ -0,0 +1,132 @@
+//! Cap on the total SOL a bot has at risk
+//!
+//! `max_positions` alone doesn't bound exposure: 50 positions at 0.05 SOL
+//! quietly commits 2.5 SOL. [`SolBudget`] caps the SOL at cost across open
+//! positions plus buys still in flight. Each buy reserves its amount first and
+//! holds the [`SolReservation`] until the position is tracked, so concurrent
+//! buys can't all squeeze under the cap at once.
+//!
+//! The bots keep their own position maps, so the caller passes in what its
+//! open positions have at risk. Reserve and release while holding the lock on
+//! those positions, or a buy that lands in between is counted twice or not at all.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+fn lamports(sol: f64) -> u64 {
+    (sol.max(0.0) * LAMPORTS_PER_SOL).round() as u64
+}
+
+/// Limits SOL at risk to `max_sol`; without one every buy fits
+#[derive(Debug, Clone, Default)]
+pub struct SolBudget {
+    max_sol: Option<f64>,
+    /// Lamports reserved by buys in flight
+    reserved: Arc<AtomicU64>,
+}
+
+impl SolBudget {
+    pub fn new(max_sol: Option<f64>) -> Self {
+        Self {
+            max_sol,
+            reserved: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub fn max_sol(&self) -> Option<f64> {
+        self.max_sol
+    }
+
+    /// SOL reserved by buys that haven't been tracked as positions yet
+    pub fn reserved_sol(&self) -> f64 {
+        self.reserved.load(Ordering::SeqCst) as f64 / LAMPORTS_PER_SOL
+    }
+
+    /// SOL still free with `at_risk` in open positions, None when uncapped
+    pub fn remaining(&self, at_risk: f64) -> Option<f64> {
+        let max = lamports(self.max_sol?);
+        let used = lamports(at_risk) + self.reserved.load(Ordering::SeqCst);
+        Some(max.saturating_sub(used) as f64 / LAMPORTS_PER_SOL)
+    }
+
+    /// Reserve `amount` SOL for a buy with `at_risk` already in open positions,
+    /// or the SOL still free when it doesn't fit
+    ///
+    /// Compared in lamports, so 3 x 0.05 SOL fits a 0.15 SOL cap.
+    pub fn reserve(&self, at_risk: f64, amount: f64) -> Result<SolReservation, f64> {
+        let wanted = lamports(amount);
+        match self.max_sol {
+            Some(max) => {
+                let free = lamports(max).saturating_sub(lamports(at_risk));
+                self.reserved
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |reserved| {
+                        (reserved + wanted <= free).then_some(reserved + wanted)
+                    })
+                    .map_err(|reserved| free.saturating_sub(reserved) as f64 / LAMPORTS_PER_SOL)?;
+            }
+            None => {
+                self.reserved.fetch_add(wanted, Ordering::SeqCst);
+            }
+        }
+        Ok(SolReservation {
+            reserved: self.reserved.clone(),
+            lamports: wanted,
+        })
+    }
+}
+
+/// SOL held back for one buy; released on drop
+#[derive(Debug)]
+pub struct SolReservation {
+    reserved: Arc<AtomicU64>,
+    lamports: u64,
+}
+
+impl SolReservation {
+    pub fn sol(&self) -> f64 {
+        self.lamports as f64 / LAMPORTS_PER_SOL
+    }
+}
+
+impl Drop for SolReservation {
+    fn drop(&mut self) {
+        self.reserved.fetch_sub(self.lamports, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reservations_share_the_cap() {
+        let budget = SolBudget::new(Some(0.15));
+        assert_eq!(budget.remaining(0.0), Some(0.15));
+
+        // Two positions open, one buy in flight: the cap is full
+        let in_flight = budget.reserve(0.1, 0.05).unwrap();
+        assert_eq!(budget.remaining(0.1), Some(0.0));
+        assert_eq!(budget.reserve(0.1, 0.05).unwrap_err(), 0.0);
+
+        // A failed buy hands its SOL back
+        drop(in_flight);
+        assert_eq!(budget.reserved_sol(), 0.0);
+        let reservation = budget.reserve(0.1, 0.05).unwrap();
+        assert_eq!(reservation.sol(), 0.05);
+
+        // Over the cap reports what's left
+        assert_eq!(budget.reserve(0.0, 0.2).unwrap_err(), 0.1);
+    }
+
+    #[test]
+    fn test_uncapped() {
+        let budget = SolBudget::new(None);
+        assert_eq!(budget.remaining(100.0), None);
+        let reservation = budget.reserve(100.0, 5.0).unwrap();
+        assert_eq!(budget.reserved_sol(), 5.0);
+        drop(reservation);
+        assert_eq!(budget.reserved_sol(), 0.0);
+    }
+}
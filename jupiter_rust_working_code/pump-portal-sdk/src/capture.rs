@@ -0,0 +1,205 @@
+//! Record and replay the raw PumpPortal WebSocket feed
+//!
+//! When a snipe did or didn't fire for no obvious reason, the launch that
+//! caused it is gone by the time anyone looks. [`Capture`] appends every text
+//! message a [`PumpPortalStream`](crate::PumpPortalStream) receives to a file,
+//! one JSON line each with its receive time:
+//!
+//! ```text
+//! {"received_at_ms":1718000000123,"text":"{\"mint\":\"...\",\"txType\":\"create\",...}"}
+//! ```
+//!
+//! [`Replay`] reads such a file back at the pace it was recorded, so a stream
+//! built with [`StreamConfig::with_replay`](crate::StreamConfig::with_replay)
+//! feeds the bot's own loop exactly what it saw live.
+
+use crate::{PumpPortalError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncBufReadExt, BufReader, Lines};
+use tokio::time::Instant;
+
+/// One captured message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapturedMessage {
+    /// Unix time the message arrived, in milliseconds
+    pub received_at_ms: u64,
+    /// The message exactly as the server sent it
+    pub text: String,
+}
+
+/// Appends received messages to a capture file
+#[derive(Debug)]
+pub struct Capture {
+    path: PathBuf,
+    file: File,
+    failed: bool,
+}
+
+impl Capture {
+    /// Open `path` for appending, creating it if needed
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| PumpPortalError::Capture(format!("Failed to open {}: {}", path.display(), e)))?;
+        log::info!("📼 Capturing PumpPortal messages to {}", path.display());
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            failed: false,
+        })
+    }
+
+    /// Append `text`, stamped with the current time
+    ///
+    /// A failed write is logged once and never stops the bot.
+    pub fn record(&mut self, text: &str) {
+        let received_at_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        let message = CapturedMessage {
+            received_at_ms,
+            text: text.to_string(),
+        };
+        // One write per line, so a crash leaves at most the last line cut short
+        let mut line = serde_json::to_string(&message).unwrap_or_default();
+        line.push('\n');
+        if let Err(e) = self.file.write_all(line.as_bytes()) {
+            if !self.failed {
+                log::warn!("📼 Writing to capture file {} failed: {}", self.path.display(), e);
+                self.failed = true;
+            }
+        }
+    }
+}
+
+/// Plays a capture file back at its recorded pace
+#[derive(Debug)]
+pub struct Replay {
+    path: PathBuf,
+    lines: Lines<BufReader<tokio::fs::File>>,
+    /// First message's receive time, and when it was replayed
+    started: Option<(u64, Instant)>,
+    /// Read but still waiting for its time to come
+    pending: Option<CapturedMessage>,
+    replayed: u64,
+    finished: bool,
+}
+
+impl Replay {
+    pub async fn open(path: &Path) -> Result<Self> {
+        let file = tokio::fs::File::open(path)
+            .await
+            .map_err(|e| PumpPortalError::Capture(format!("Failed to open {}: {}", path.display(), e)))?;
+        log::info!("📼 Replaying PumpPortal messages from {}", path.display());
+        Ok(Self {
+            path: path.to_path_buf(),
+            lines: BufReader::new(file).lines(),
+            started: None,
+            pending: None,
+            replayed: 0,
+            finished: false,
+        })
+    }
+
+    /// Next message, once as much time has passed since the first as did
+    /// when it was captured; None at the end of the file
+    ///
+    /// Lines that don't parse (like one cut short by a crash) are skipped.
+    /// Cancel-safe: a message whose wait was cut short is returned next time.
+    pub async fn next(&mut self) -> Option<String> {
+        if self.pending.is_none() {
+            self.pending = self.read().await;
+        }
+        let message = self.pending.as_ref()?;
+
+        let (first_at, started) = *self.started.get_or_insert((message.received_at_ms, Instant::now()));
+        let offset = Duration::from_millis(message.received_at_ms.saturating_sub(first_at));
+        tokio::time::sleep_until(started + offset).await;
+
+        self.replayed += 1;
+        self.pending.take().map(|message| message.text)
+    }
+
+    /// Next readable line, None at the end of the file
+    async fn read(&mut self) -> Option<CapturedMessage> {
+        if self.finished {
+            return None;
+        }
+        loop {
+            let line = match self.lines.next_line().await {
+                Ok(Some(line)) => line,
+                Ok(None) => break,
+                Err(e) => {
+                    log::warn!("📼 Reading replay file {} failed: {}", self.path.display(), e);
+                    break;
+                }
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<CapturedMessage>(&line) {
+                Ok(message) => return Some(message),
+                Err(e) => log::warn!("📼 Skipping unreadable capture line: {}", e),
+            }
+        }
+        self.finished = true;
+        None
+    }
+
+    /// Whether every message has been replayed
+    pub fn is_finished(&self) -> bool {
+        self.finished && self.pending.is_none()
+    }
+
+    /// Messages replayed so far
+    pub fn replayed(&self) -> u64 {
+        self.replayed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_capture_replays_in_order_at_recorded_pace() {
+        let path = std::env::temp_dir().join(format!("pump-capture-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut capture = Capture::open(&path).unwrap();
+        capture.record(r#"{"message":"Successfully subscribed"}"#);
+        drop(capture);
+        // Appending keeps earlier captures; a torn line is skipped on replay
+        let lines = [
+            r#"{"received_at_ms":1000,"text":"{\"mint\":\"A\",\"txType\":\"create\"}"}"#,
+            r#"{"received_at_ms":1250,"text":"#,
+            r#"{"received_at_ms":1300,"text":"{\"mint\":\"A\",\"txType\":\"buy\"}"}"#,
+        ];
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "{}", lines.join("\n")).unwrap();
+
+        let mut replay = Replay::open(&path).await.unwrap();
+        assert_eq!(replay.next().await.as_deref(), Some(r#"{"message":"Successfully subscribed"}"#));
+
+        // Anchor on the hand-written lines: the buy comes 300ms after the create
+        let mut replay = Replay::open(&path).await.unwrap();
+        replay.next().await.unwrap();
+        replay.started = Some((1000, Instant::now()));
+        let start = Instant::now();
+        assert_eq!(replay.next().await.as_deref(), Some(r#"{"mint":"A","txType":"create"}"#));
+        assert_eq!(replay.next().await.as_deref(), Some(r#"{"mint":"A","txType":"buy"}"#));
+        assert!(start.elapsed() >= Duration::from_millis(300));
+        assert_eq!(replay.next().await, None);
+        assert!(replay.is_finished());
+        assert_eq!(replay.replayed(), 3);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
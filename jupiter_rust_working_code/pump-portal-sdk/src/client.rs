@@ -1,17 +1,58 @@
 //! PumpPortal API client implementation
 
-use crate::{PumpPortalError, Result, TradeRequest, TradeResponse};
+use crate::compute_budget;
+use crate::dedup::BuyGuard;
+use crate::rate_limit::RateLimiter;
+use crate::{PumpPortalError, Result, TradeAction, TradeRequest, TradeResponse};
 use reqwest::Client;
 use serde_json::json;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 const BASE_URL: &str = "https://pumpportal.fun/api/trade";
 
+/// Default connect and request timeout; a launch window doesn't wait for slow requests
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Back-off applied to the rate limiter when a 429 comes without `Retry-After`
+pub const DEFAULT_RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Prefix for synthetic signatures returned in dry-run mode
+pub const DRY_RUN_SIGNATURE_PREFIX: &str = "PAPER_";
+
+/// Counter keeping dry-run signatures unique within a process
+static DRY_RUN_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// A unique synthetic signature for a trade that never goes on-chain
+pub(crate) fn dry_run_signature() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let seq = DRY_RUN_COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("{}{:x}{:04x}", DRY_RUN_SIGNATURE_PREFIX, nanos, seq)
+}
+
 /// PumpPortal API client
 ///
 /// Handles authentication and communication with the PumpPortal Trading API.
+/// Clones share the HTTP connection pool and rate limiter.
+#[derive(Clone)]
 pub struct PumpPortalClient {
     client: Client,
     api_key: String,
+    base_url: String,
+    dry_run: bool,
+    timeout: Duration,
+    /// HTTP client was supplied by the caller, so `with_timeout` mustn't replace it
+    custom_http: bool,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    buy_guard: Option<Arc<BuyGuard>>,
+    /// Ceiling a trade that failed on slippage is retried up to
+    max_slippage: Option<u32>,
+    /// Compute-unit limit written into locally signed transactions
+    compute_unit_limit: Option<u32>,
 }
 
 impl PumpPortalClient {
@@ -30,11 +71,234 @@ impl PumpPortalClient {
     /// ```
     pub fn new(api_key: String) -> Self {
         Self {
-            client: Client::new(),
+            client: build_http_client(DEFAULT_TIMEOUT),
             api_key,
+            base_url: BASE_URL.to_string(),
+            dry_run: false,
+            timeout: DEFAULT_TIMEOUT,
+            custom_http: false,
+            rate_limiter: None,
+            buy_guard: None,
+            max_slippage: None,
+            compute_unit_limit: None,
         }
     }
 
+    /// Send requests through a preconfigured HTTP client, e.g. one with a proxy,
+    /// pinned TLS roots, custom DNS, or a connection pool shared with other code
+    ///
+    /// The SDK's own behavior layers on top: the client timeout still bounds
+    /// every trade and RPC request, and rate limiting and retries work as usual.
+    /// Connect timeouts and everything else come from `client`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use pump_portal_sdk::PumpPortalClient;
+    ///
+    /// let http = reqwest::Client::builder()
+    ///     .proxy(reqwest::Proxy::https("http://proxy.internal:3128").unwrap())
+    ///     .build()
+    ///     .unwrap();
+    /// let client = PumpPortalClient::new("your-api-key".to_string()).with_http_client(http);
+    /// ```
+    pub fn with_http_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self.custom_http = true;
+        self
+    }
+
+    /// Send trades to another endpoint instead of PumpPortal, e.g. a mock server
+    ///
+    /// The API key is still appended as the `api-key` query parameter.
+    pub fn with_base_url(mut self, url: &str) -> Self {
+        self.base_url = url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Set the connect and request timeout (default 5s)
+    ///
+    /// Requests exceeding it fail with `PumpPortalError::Timeout`. With a client
+    /// from [`with_http_client`](Self::with_http_client) this sets the request timeout only.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use pump_portal_sdk::PumpPortalClient;
+    /// use std::time::Duration;
+    ///
+    /// let client = PumpPortalClient::new("your-api-key".to_string())
+    ///     .with_timeout(Duration::from_secs(3));
+    /// ```
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        if !self.custom_http {
+            self.client = build_http_client(timeout);
+        }
+        self.timeout = timeout;
+        self
+    }
+
+    /// Configured connect and request timeout
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Limit trades to `max_per_sec`, delaying requests over the limit
+    ///
+    /// Bursts up to `max_per_sec` go out immediately; beyond that `trade()`
+    /// waits for a token instead of failing. The limiter is shared by every
+    /// clone of this client.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_per_sec` is not positive.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use pump_portal_sdk::PumpPortalClient;
+    ///
+    /// let client = PumpPortalClient::new("your-api-key".to_string())
+    ///     .with_rate_limit(5.0);
+    /// ```
+    pub fn with_rate_limit(mut self, max_per_sec: f64) -> Self {
+        self.rate_limiter = Some(Arc::new(RateLimiter::new(max_per_sec)));
+        self
+    }
+
+    /// Configured rate limit in requests per second, if any
+    pub fn rate_limit(&self) -> Option<f64> {
+        self.rate_limiter.as_ref().map(|limiter| limiter.max_per_sec())
+    }
+
+    /// Keep a retried buy from being sent twice
+    ///
+    /// Each buy is remembered by mint and amount for `window`. An identical
+    /// buy in that time gets the first one's response instead of being sent
+    /// again, and if the first failed in a way that doesn't say whether it
+    /// executed (timeout, connection error, 5xx) it is refused with
+    /// `PumpPortalError::DuplicateBuy`. Shared by every clone of this client.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use pump_portal_sdk::PumpPortalClient;
+    /// use std::time::Duration;
+    ///
+    /// let client = PumpPortalClient::new("your-api-key".to_string())
+    ///     .with_buy_dedup(Duration::from_secs(30));
+    /// ```
+    pub fn with_buy_dedup(mut self, window: Duration) -> Self {
+        self.buy_guard = Some(Arc::new(BuyGuard::new(window)));
+        self
+    }
+
+    /// Retry a trade that fails on slippage once, at a higher slippage
+    ///
+    /// When the price moves past a trade's tolerance before it executes
+    /// (`PumpPortalError::SlippageExceeded`), the same request would just fail
+    /// again. With this set the trade is resent once with its slippage
+    /// doubled, capped at `max_slippage` percent; a trade already at the
+    /// ceiling, and every other error, fails as before.
+    ///
+    /// Only a slip PumpPortal reports on the request itself is retried.
+    /// [`TradeRequest::buy`] and [`TradeRequest::sell`] skip preflight, so
+    /// PumpPortal sends them unsimulated and a slip shows up on-chain instead,
+    /// as a [`ConfirmationStatus::Failed`](crate::ConfirmationStatus::Failed)
+    /// from [`confirm`](Self::confirm) (see
+    /// [`is_slippage_failure`](crate::ConfirmationStatus::is_slippage_failure)).
+    /// Those aren't retried, so for this to fire, send with
+    /// [`with_skip_preflight(false)`](TradeRequest::with_skip_preflight).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use pump_portal_sdk::PumpPortalClient;
+    ///
+    /// // A 10% buy that slips is retried at 20%, a 15% one at 25%
+    /// let client = PumpPortalClient::new("your-api-key".to_string())
+    ///     .with_slippage_retry(25);
+    /// ```
+    pub fn with_slippage_retry(mut self, max_slippage: u32) -> Self {
+        self.max_slippage = Some(max_slippage.min(100));
+        self
+    }
+
+    /// Highest slippage a failed trade is retried at, if escalation is on
+    pub fn max_slippage(&self) -> Option<u32> {
+        self.max_slippage
+    }
+
+    /// Give locally signed transactions this compute-unit limit
+    ///
+    /// The limit PumpPortal sets can be too low for swaps through busy pools,
+    /// which then fail on-chain with the fee spent.
+    /// [`prepare_local_transaction`](Self::prepare_local_transaction) rewrites
+    /// it (or adds one) before the transaction is signed. Capped at
+    /// [`MAX_COMPUTE_UNIT_LIMIT`](crate::compute_budget::MAX_COMPUTE_UNIT_LIMIT).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use pump_portal_sdk::PumpPortalClient;
+    ///
+    /// let client = PumpPortalClient::new("your-api-key".to_string())
+    ///     .with_compute_unit_limit(250_000);
+    /// ```
+    pub fn with_compute_unit_limit(mut self, limit: u32) -> Self {
+        self.compute_unit_limit = Some(limit.clamp(1, compute_budget::MAX_COMPUTE_UNIT_LIMIT));
+        self
+    }
+
+    /// Compute-unit limit set by [`with_compute_unit_limit`](Self::with_compute_unit_limit)
+    pub fn compute_unit_limit(&self) -> Option<u32> {
+        self.compute_unit_limit
+    }
+
+    /// Apply the client's transaction settings to an unsigned transaction
+    /// PumpPortal returned for local signing; sign the result
+    ///
+    /// Without [`with_compute_unit_limit`](Self::with_compute_unit_limit) the
+    /// bytes come back unchanged.
+    ///
+    /// # Errors
+    ///
+    /// `InvalidParameter` if the bytes aren't a legacy or v0 transaction.
+    pub fn prepare_local_transaction(&self, transaction: &[u8]) -> Result<Vec<u8>> {
+        match self.compute_unit_limit {
+            Some(limit) => compute_budget::set_compute_unit_limit(transaction, limit),
+            None => Ok(transaction.to_vec()),
+        }
+    }
+
+    /// Enable/disable dry-run mode
+    ///
+    /// In dry-run mode `trade()` validates and logs the request, then returns a
+    /// synthetic response with a `PAPER_`-prefixed signature without touching
+    /// the network. Nothing is sent, so there is nothing to retry.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use pump_portal_sdk::PumpPortalClient;
+    ///
+    /// let client = PumpPortalClient::new("your-api-key".to_string()).dry_run(true);
+    /// ```
+    pub fn dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = enabled;
+        self
+    }
+
+    /// Whether this client is in dry-run mode
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Underlying HTTP client, shared with the RPC helpers
+    pub(crate) fn http(&self) -> &Client {
+        &self.client
+    }
+
     /// Execute a trade request
     ///
     /// # Arguments
@@ -47,25 +311,88 @@ impl PumpPortalClient {
     ///
     /// # Errors
     ///
-    /// Returns `PumpPortalError` if the request fails or the API returns an error
+    /// Returns `PumpPortalError` if the request fails or the API returns an error.
+    /// API failures are classified as `RateLimited`, `InsufficientFunds` or
+    /// `ServerError` where possible, falling back to `ApiError`. A rate-limited
+    /// response also backs off the client's rate limiter, if one is set. With
+    /// [`with_buy_dedup`](Self::with_buy_dedup), a repeated buy may fail with `DuplicateBuy`.
+    /// With [`with_slippage_retry`](Self::with_slippage_retry), a `SlippageExceeded`
+    /// failure is retried once at a higher slippage.
     pub async fn trade(&self, request: TradeRequest) -> Result<TradeResponse> {
-        let url = format!("{}?api-key={}", BASE_URL, self.api_key);
+        request.validate()?;
+
+        match self.submit(&request).await {
+            Err(PumpPortalError::SlippageExceeded(reason)) => {
+                let Some(slippage) = self
+                    .max_slippage
+                    .and_then(|ceiling| escalate_slippage(request.slippage, ceiling))
+                else {
+                    return Err(PumpPortalError::SlippageExceeded(reason));
+                };
+                log::warn!(
+                    "Slippage exceeded at {}% for {} ({}), retrying at {}%",
+                    request.slippage,
+                    request.mint,
+                    reason,
+                    slippage
+                );
+                let mut retry = request;
+                retry.slippage = slippage;
+                self.submit(&retry).await
+            }
+            result => result,
+        }
+    }
+
+    /// Send a validated request, through the buy guard if one is set
+    async fn submit(&self, request: &TradeRequest) -> Result<TradeResponse> {
+        match &self.buy_guard {
+            Some(guard) if !self.dry_run && matches!(request.action, TradeAction::Buy) => {
+                guard.run(request, || self.send(request)).await
+            }
+            _ => self.send(request).await,
+        }
+    }
+
+    /// Send a validated request, or fake it in dry-run mode
+    async fn send(&self, request: &TradeRequest) -> Result<TradeResponse> {
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire().await;
+        }
+
+        if self.dry_run {
+            return self.dry_run_trade(request);
+        }
+
+        let url = format!("{}?api-key={}", self.base_url, self.api_key);
 
         let response = self
             .client
             .post(&url)
-            .json(&request)
+            .timeout(self.timeout)
+            .json(request)
             .send()
-            .await?;
+            .await
+            .map_err(PumpPortalError::from_request)?;
 
         let status = response.status();
-        let body = response.text().await?;
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = response
+            .text()
+            .await
+            .map_err(PumpPortalError::from_request)?;
 
         // Try to parse as JSON first
         let trade_response: TradeResponse = serde_json::from_str(&body)
             .unwrap_or_else(|_| TradeResponse {
                 signature: None,
                 error: Some(body.clone()),
+                pool: None,
+                input_amount: None,
                 extra: json!({}),
             });
 
@@ -74,12 +401,34 @@ impl PumpPortalClient {
             let error_msg = trade_response
                 .error
                 .unwrap_or_else(|| format!("HTTP {}: {}", status, body));
-            return Err(PumpPortalError::ApiError(error_msg));
+            let err = PumpPortalError::from_response(status.as_u16(), retry_after.as_deref(), error_msg);
+
+            // Slow every clone down, not just this caller
+            if let (PumpPortalError::RateLimited { retry_after }, Some(limiter)) = (&err, &self.rate_limiter) {
+                limiter
+                    .back_off(retry_after.unwrap_or(DEFAULT_RATE_LIMIT_BACKOFF))
+                    .await;
+            }
+            return Err(err);
         }
 
         Ok(trade_response)
     }
 
+    /// Build the synthetic response for a dry-run trade
+    fn dry_run_trade(&self, request: &TradeRequest) -> Result<TradeResponse> {
+        let body = request.to_json()?;
+        log::info!("[dry-run] would POST {}: {}", self.base_url, body);
+
+        Ok(TradeResponse {
+            signature: Some(dry_run_signature()),
+            error: None,
+            pool: request.pool,
+            input_amount: Some(request.amount.clone()),
+            extra: json!({ "dryRun": true }),
+        })
+    }
+
     /// Execute a buy order
     ///
     /// # Arguments
@@ -151,6 +500,21 @@ impl PumpPortalClient {
     }
 }
 
+/// Build the underlying HTTP client with connect and request timeouts
+fn build_http_client(timeout: Duration) -> Client {
+    Client::builder()
+        .connect_timeout(timeout)
+        .timeout(timeout)
+        .build()
+        .expect("failed to build HTTP client")
+}
+
+/// Slippage for the one retry after a slippage failure: double `slippage`,
+/// capped at `ceiling`; None when it's already at the ceiling
+fn escalate_slippage(slippage: u32, ceiling: u32) -> Option<u32> {
+    (slippage < ceiling).then(|| slippage.saturating_mul(2).min(ceiling))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -160,4 +524,78 @@ mod tests {
         let client = PumpPortalClient::new("test-key".to_string());
         assert_eq!(client.api_key, "test-key");
     }
+
+    #[test]
+    fn test_timeout_configuration() {
+        let client = PumpPortalClient::new("test-key".to_string());
+        assert_eq!(client.timeout(), DEFAULT_TIMEOUT);
+
+        let client = client.with_timeout(Duration::from_millis(1500));
+        assert_eq!(client.timeout(), Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn test_escalate_slippage() {
+        assert_eq!(escalate_slippage(10, 25), Some(20));
+        assert_eq!(escalate_slippage(15, 25), Some(25));
+        assert_eq!(escalate_slippage(25, 25), None);
+        assert_eq!(escalate_slippage(30, 25), None);
+
+        let client = PumpPortalClient::new("test-key".to_string()).with_slippage_retry(150);
+        assert_eq!(client.max_slippage(), Some(100));
+    }
+
+    #[test]
+    fn test_prepare_local_transaction_only_with_limit() {
+        // Legacy transaction: one empty signature slot, one key, no instructions
+        let tx = [&[1u8][..], &[0; 64], &[1, 0, 0, 1], &[7; 32], &[9; 32], &[0]].concat();
+        let client = PumpPortalClient::new("test-key".to_string());
+        assert_eq!(client.prepare_local_transaction(&tx).unwrap(), tx);
+
+        let client = client.with_compute_unit_limit(2_000_000);
+        assert_eq!(client.compute_unit_limit(), Some(compute_budget::MAX_COMPUTE_UNIT_LIMIT));
+        let prepared = client.prepare_local_transaction(&tx).unwrap();
+        assert_eq!(compute_budget::compute_unit_limit(&prepared), Some(compute_budget::MAX_COMPUTE_UNIT_LIMIT));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_shared_across_clones() {
+        let client = PumpPortalClient::new("test-key".to_string())
+            .dry_run(true)
+            .with_rate_limit(2.0);
+        let clone = client.clone();
+        assert_eq!(clone.rate_limit(), Some(2.0));
+
+        let start = std::time::Instant::now();
+        client.buy("TokenMint".to_string(), 0.01, 10, 0.0001).await.unwrap();
+        clone.buy("TokenMint".to_string(), 0.01, 10, 0.0001).await.unwrap();
+        assert!(start.elapsed() < Duration::from_millis(100));
+
+        // Burst spent by the two clients together, the third request waits
+        clone.buy("TokenMint".to_string(), 0.01, 10, 0.0001).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(400));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_returns_paper_signature() {
+        let client = PumpPortalClient::new("test-key".to_string()).dry_run(true);
+        let response = client
+            .buy("TokenMint".to_string(), 0.01, 10, 0.0001)
+            .await
+            .unwrap();
+
+        let signature = response.signature.unwrap();
+        assert!(signature.starts_with(DRY_RUN_SIGNATURE_PREFIX));
+        assert!(response.error.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_still_validates() {
+        let client = PumpPortalClient::new("test-key".to_string()).dry_run(true);
+        let result = client
+            .sell("TokenMint".to_string(), "150%".to_string(), 10, 0.0001)
+            .await;
+
+        assert!(matches!(result, Err(PumpPortalError::InvalidParameter(_))));
+    }
 }
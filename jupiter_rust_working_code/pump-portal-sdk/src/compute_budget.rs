@@ -0,0 +1,307 @@
+//! Compute-unit limit of transactions the bot signs itself
+//!
+//! PumpPortal's local-trade transactions carry their own `SetComputeUnitLimit`,
+//! and for swaps through some pools it is too low: the transaction lands, runs
+//! out of compute and fails on-chain with the fee spent. [`set_compute_unit_limit`]
+//! rewrites that instruction in the serialized transaction (legacy or v0), or
+//! adds one if there is none, so the limit can be raised before signing.
+//!
+//! Only the message changes; signature slots are kept as they are and must be
+//! filled in afterwards.
+
+use crate::{PumpPortalError, Result};
+
+/// `ComputeBudget111111111111111111111111111111`
+const COMPUTE_BUDGET_PROGRAM: [u8; 32] = [
+    3, 6, 70, 111, 229, 33, 23, 50, 255, 236, 173, 186, 114, 195, 155, 231, 188, 140, 229, 187, 197, 247, 18, 107,
+    44, 67, 155, 58, 64, 0, 0, 0,
+];
+
+/// Instruction discriminator of `SetComputeUnitLimit(u32)`
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+
+/// Highest limit the runtime accepts per transaction
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Return `transaction` with its compute-unit limit set to `limit`
+///
+/// # Errors
+///
+/// `InvalidParameter` if `limit` is 0 or above [`MAX_COMPUTE_UNIT_LIMIT`], or
+/// the bytes aren't a legacy or v0 transaction.
+pub fn set_compute_unit_limit(transaction: &[u8], limit: u32) -> Result<Vec<u8>> {
+    if limit == 0 || limit > MAX_COMPUTE_UNIT_LIMIT {
+        return Err(PumpPortalError::InvalidParameter(format!(
+            "Compute-unit limit {} is outside 1-{}",
+            limit, MAX_COMPUTE_UNIT_LIMIT
+        )));
+    }
+
+    let mut tx = Transaction::parse(transaction).ok_or_else(|| {
+        PumpPortalError::InvalidParameter("Not a legacy or v0 transaction".to_string())
+    })?;
+    let data = [&[SET_COMPUTE_UNIT_LIMIT][..], &limit.to_le_bytes()].concat();
+
+    let program = match tx.keys.iter().position(|key| *key == COMPUTE_BUDGET_PROGRAM) {
+        Some(index) => index as u8,
+        None => tx.add_readonly_key(COMPUTE_BUDGET_PROGRAM)?,
+    };
+    let existing = tx
+        .instructions
+        .iter_mut()
+        .find(|ix| ix.program == program && ix.data.first() == Some(&SET_COMPUTE_UNIT_LIMIT));
+    match existing {
+        Some(ix) => ix.data = data,
+        None => tx.instructions.insert(
+            0,
+            Instruction {
+                program,
+                accounts: Vec::new(),
+                data,
+            },
+        ),
+    }
+    Ok(tx.serialize())
+}
+
+/// Compute-unit limit a transaction sets, None if it sets none
+pub fn compute_unit_limit(transaction: &[u8]) -> Option<u32> {
+    let tx = Transaction::parse(transaction)?;
+    let program = tx.keys.iter().position(|key| *key == COMPUTE_BUDGET_PROGRAM)? as u8;
+    tx.instructions
+        .iter()
+        .filter(|ix| ix.program == program)
+        .find_map(|ix| match ix.data.as_slice() {
+            [SET_COMPUTE_UNIT_LIMIT, a, b, c, d] => Some(u32::from_le_bytes([*a, *b, *c, *d])),
+            _ => None,
+        })
+}
+
+struct Instruction {
+    program: u8,
+    accounts: Vec<u8>,
+    data: Vec<u8>,
+}
+
+/// The parts of a transaction the limit touches; the rest is kept as bytes
+struct Transaction {
+    /// Signature count and slots
+    signatures: Vec<u8>,
+    /// `0x80` for v0, None for legacy
+    version: Option<u8>,
+    header: [u8; 3],
+    keys: Vec<[u8; 32]>,
+    blockhash: [u8; 32],
+    instructions: Vec<Instruction>,
+    /// v0 address table lookups
+    lookups: Vec<u8>,
+}
+
+impl Transaction {
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        let mut r = Reader { bytes, pos: 0 };
+
+        let signature_count = r.short_vec()?;
+        r.take(signature_count * 64)?;
+        let signatures = bytes[..r.pos].to_vec();
+
+        let version = match r.peek()? {
+            0x80 => Some(r.byte()?),
+            prefix if prefix & 0x80 != 0 => return None, // v1+ doesn't exist yet
+            _ => None,
+        };
+        let header = [r.byte()?, r.byte()?, r.byte()?];
+        let keys = (0..r.short_vec()?).map(|_| r.array()).collect::<Option<Vec<_>>>()?;
+        let blockhash = r.array()?;
+        let instructions = (0..r.short_vec()?)
+            .map(|_| {
+                let program = r.byte()?;
+                let count = r.short_vec()?;
+                let accounts = r.take(count)?.to_vec();
+                let len = r.short_vec()?;
+                let data = r.take(len)?.to_vec();
+                Some(Instruction { program, accounts, data })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let lookups = bytes[r.pos..].to_vec();
+        if version.is_none() && !lookups.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            signatures,
+            version,
+            header,
+            keys,
+            blockhash,
+            instructions,
+            lookups,
+        })
+    }
+
+    /// Append a read-only, unsigned account key and return its index
+    ///
+    /// Indices at or past the end of the static keys point into v0 lookup
+    /// tables, so they move up by one.
+    fn add_readonly_key(&mut self, key: [u8; 32]) -> Result<u8> {
+        let index = self.keys.len();
+        if index >= u8::MAX as usize {
+            return Err(PumpPortalError::InvalidParameter("Transaction has no room for another account".to_string()));
+        }
+        let index = index as u8;
+        for ix in &mut self.instructions {
+            for account in &mut ix.accounts {
+                if *account >= index {
+                    *account += 1;
+                }
+            }
+        }
+        self.keys.push(key);
+        self.header[2] += 1;
+        Ok(index)
+    }
+
+    fn serialize(&self) -> Vec<u8> {
+        let mut out = self.signatures.clone();
+        out.extend(self.version);
+        out.extend(self.header);
+        write_short_vec(&mut out, self.keys.len());
+        for key in &self.keys {
+            out.extend(key);
+        }
+        out.extend(self.blockhash);
+        write_short_vec(&mut out, self.instructions.len());
+        for ix in &self.instructions {
+            out.push(ix.program);
+            write_short_vec(&mut out, ix.accounts.len());
+            out.extend(&ix.accounts);
+            write_short_vec(&mut out, ix.data.len());
+            out.extend(&ix.data);
+        }
+        out.extend(&self.lookups);
+        out
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        let b = self.peek()?;
+        self.pos += 1;
+        Some(b)
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let slice = self.bytes.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(slice)
+    }
+
+    fn array(&mut self) -> Option<[u8; 32]> {
+        self.take(32)?.try_into().ok()
+    }
+
+    /// Solana's compact-u16 length prefix
+    fn short_vec(&mut self) -> Option<usize> {
+        let mut value = 0usize;
+        for shift in [0, 7, 14] {
+            let b = self.byte()?;
+            value |= ((b & 0x7f) as usize) << shift;
+            if b & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+fn write_short_vec(out: &mut Vec<u8>, mut len: usize) {
+    loop {
+        let b = (len & 0x7f) as u8;
+        len >>= 7;
+        if len == 0 {
+            out.push(b);
+            return;
+        }
+        out.push(b | 0x80);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(n: u8) -> [u8; 32] {
+        [n; 32]
+    }
+
+    /// One signer, `keys` static accounts, and the given instructions
+    fn transaction(v0: bool, keys: &[[u8; 32]], instructions: &[(u8, &[u8], &[u8])], lookups: &[u8]) -> Vec<u8> {
+        let mut out = vec![1];
+        out.extend([0u8; 64]);
+        if v0 {
+            out.push(0x80);
+        }
+        out.extend([1, 0, 1]);
+        write_short_vec(&mut out, keys.len());
+        keys.iter().for_each(|k| out.extend(k));
+        out.extend(key(9));
+        write_short_vec(&mut out, instructions.len());
+        for (program, accounts, data) in instructions {
+            out.push(*program);
+            write_short_vec(&mut out, accounts.len());
+            out.extend(*accounts);
+            write_short_vec(&mut out, data.len());
+            out.extend(*data);
+        }
+        out.extend(lookups);
+        out
+    }
+
+    #[test]
+    fn test_existing_limit_is_rewritten() {
+        let limit = [&[SET_COMPUTE_UNIT_LIMIT][..], &100_000u32.to_le_bytes()].concat();
+        let tx = transaction(false, &[key(1), COMPUTE_BUDGET_PROGRAM, key(2)], &[(1, &[], &limit), (2, &[0], &[7])], &[]);
+        assert_eq!(compute_unit_limit(&tx), Some(100_000));
+
+        let raised = set_compute_unit_limit(&tx, 300_000).unwrap();
+        assert_eq!(compute_unit_limit(&raised), Some(300_000));
+        assert_eq!(raised.len(), tx.len());
+        assert_eq!(raised[..65], tx[..65]); // Signature slots untouched
+    }
+
+    #[test]
+    fn test_missing_limit_is_added_and_lookup_indices_shift() {
+        // Instruction touches static key 1 and looked-up account 2 (the first past the statics)
+        let lookups = [&[1u8][..], &key(5), &[1, 0], &[0]].concat();
+        let tx = transaction(true, &[key(1), key(2)], &[(1, &[0, 2], &[7])], &lookups);
+        assert_eq!(compute_unit_limit(&tx), None);
+
+        let raised = set_compute_unit_limit(&tx, 250_000).unwrap();
+        assert_eq!(compute_unit_limit(&raised), Some(250_000));
+
+        let parsed = Transaction::parse(&raised).unwrap();
+        assert_eq!(parsed.header, [1, 0, 2]);
+        assert_eq!(parsed.keys, vec![key(1), key(2), COMPUTE_BUDGET_PROGRAM]);
+        assert_eq!(parsed.instructions[0].program, 2);
+        assert_eq!(parsed.instructions[1].accounts, vec![0, 3]);
+        assert_eq!(parsed.lookups, lookups);
+    }
+
+    #[test]
+    fn test_rejects_bad_limit_and_garbage() {
+        let tx = transaction(false, &[key(1)], &[], &[]);
+        assert!(set_compute_unit_limit(&tx, 0).is_err());
+        assert!(set_compute_unit_limit(&tx, MAX_COMPUTE_UNIT_LIMIT + 1).is_err());
+        assert!(set_compute_unit_limit(&tx[..40], 200_000).is_err());
+        assert!(set_compute_unit_limit(&[&tx[..], &[0]].concat(), 200_000).is_err());
+    }
+}
@@ -0,0 +1,974 @@
+//! Bot settings from a config file or the environment (`config` feature)
+//!
+//! [`BotConfig::load`] reads the file named by `BOT_CONFIG`, or `config.toml`
+//! / `config.json` in the working directory. Fields the file leaves out keep
+//! the bot's defaults, and unknown fields are rejected so a typo doesn't
+//! quietly fall back to a default. Without a file, each field is read from its
+//! environment variable instead:
+//!
+//! | field                     | env var                   |
+//! |---------------------------|---------------------------|
+//! | `snipe_amount_sol`        | `SNIPE_AMOUNT_SOL`        |
+//! | `min_snipe_sol`           | `MIN_SNIPE_SOL`           |
+//! | `max_snipe_sol`           | `MAX_SNIPE_SOL`           |
+//! | `max_positions`           | `MAX_POSITIONS`           |
+//! | `max_total_sol_at_risk`   | `MAX_TOTAL_SOL_AT_RISK`   |
+//! | `buy_slippage`            | `BUY_SLIPPAGE`            |
+//! | `sell_slippage`           | `SELL_SLIPPAGE`           |
+//! | `max_slippage`            | `MAX_SLIPPAGE`            |
+//! | `adaptive_slippage`       | `ADAPTIVE_SLIPPAGE`       |
+//! | `priority_fee_sol`        | `PRIORITY_FEE_SOL`        |
+//! | `jito_only`               | `JITO_ONLY`               |
+//! | `max_hold_secs`           | `MAX_HOLD_SECS`           |
+//! | `mint_cooldown_secs`      | `MINT_COOLDOWN_SECS`      |
+//! | `loss_streak_limit`       | `LOSS_STREAK_LIMIT`       |
+//! | `loss_cooldown_secs`      | `LOSS_COOLDOWN_SECS`      |
+//! | `candle_interval_ms`      | `CANDLE_INTERVAL_MS`      |
+//! | `candle_history`          | `CANDLE_HISTORY`          |
+//! | `trend_candle_interval_ms` | `TREND_CANDLE_INTERVAL_MS` |
+//! | `momentum_exit_threshold` | `MOMENTUM_EXIT_THRESHOLD` |
+//! | `vwap_exit_deviation`     | `VWAP_EXIT_DEVIATION`     |
+//! | `vwap_band_std_mult`      | `VWAP_BAND_STD_MULT`      |
+//! | `bollinger_period`        | `BOLLINGER_PERIOD`        |
+//! | `bollinger_std_mult`      | `BOLLINGER_STD_MULT`      |
+//! | `bollinger_squeeze`       | `BOLLINGER_SQUEEZE`       |
+//! | `take_profit`             | `TP<n>_MULT`, `TP<n>_FRACTION` (n = 1-5) |
+//! | `scale_out_spike_percent` | `SCALE_OUT_SPIKE_PERCENT` |
+//! | `scale_out_min_multiple`  | `SCALE_OUT_MIN_MULT`      |
+//! | `scale_out_fraction`      | `SCALE_OUT_FRACTION`      |
+//! | `exit_strategy`           | `EXIT_STRATEGY`           |
+//! | `momentum_exits`          | `MOMENTUM_EXITS`          |
+//! | `no_price_grace_secs`     | `NO_PRICE_GRACE_SECS`     |
+//! | `sell_on_exit`            | `SELL_ON_EXIT`            |
+//! | `max_trades`              | `MAX_TRADES`              |
+//! | `max_concurrent_buys`     | `MAX_CONCURRENT_BUYS`     |
+//! | `max_requests_per_sec`    | `MAX_REQUESTS_PER_SEC`    |
+//! | `max_price_impact_bps`    | `MAX_PRICE_IMPACT_BPS`    |
+//! | `ws_stall_timeout_secs`   | `WS_STALL_TIMEOUT_SECS`   |
+//! | `ws_capture_path`         | `WS_CAPTURE_PATH`         |
+//! | `latency_benchmark`       | `LATENCY_BENCHMARK`       |
+//! | `min_sol_reserves`        | `MIN_SOL_RESERVES`        |
+//! | `dev_max_rugs`            | `DEV_MAX_RUGS`            |
+//! | `dev_blocklist_file`      | `DEV_BLOCKLIST_FILE`      |
+//! | `database_path`           | `DATABASE_PATH`           |
+//! | `metrics_port`            | `METRICS_PORT`            |
+//! | `rpc_url`                 | `SOLANA_RPC_URL`          |
+//! | `webhook_url`             | `WEBHOOK_URL`             |
+//! | `webhook_events`          | `WEBHOOK_EVENTS` (comma-separated) |
+//! | `paper.*`                 | `PAPER_MODE`, `PAPER_<FIELD>` |
+//!
+//! Starting a bot with `--paper` turns paper mode on whatever the file or
+//! environment says. `--replay <file>` plays a `ws_capture_path` capture
+//! through the bot instead of the live feed, and always trades on paper.
+//!
+//! A value that doesn't parse is an error rather than the default. Secrets
+//! like `PUMPPORTAL_API_KEY` and `HELIUS_RPC_URL` stay in the environment.
+//!
+//! ```toml
+//! snipe_amount_sol = 0.02
+//! sell_slippage = 25
+//! exit_strategy = "ladder"
+//!
+//! [[take_profit]]
+//! multiple = 1.8
+//! fraction = 0.33
+//!
+//! [paper]
+//! enabled = true
+//! starting_balance = 2.0
+//! ```
+
+use crate::notify::{self, Notifier, NotifyEvent};
+use crate::{cooldown, exit, AdaptiveSlippage, SolBudget};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Env vars for each take-profit tier, lowest first
+const TAKE_PROFIT_VARS: [(&str, &str); 5] = [
+    ("TP1_MULT", "TP1_FRACTION"),
+    ("TP2_MULT", "TP2_FRACTION"),
+    ("TP3_MULT", "TP3_FRACTION"),
+    ("TP4_MULT", "TP4_FRACTION"),
+    ("TP5_MULT", "TP5_FRACTION"),
+];
+
+/// Env var naming the config file to load
+pub const CONFIG_PATH_VAR: &str = "BOT_CONFIG";
+
+/// Files looked for in the working directory when `BOT_CONFIG` is unset
+const DEFAULT_PATHS: [&str; 2] = ["config.toml", "config.json"];
+
+/// Errors loading or validating a [`BotConfig`]
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Failed to read {}: {source}", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+
+    #[error("Failed to parse {}: {message}", path.display())]
+    Parse { path: PathBuf, message: String },
+
+    #[error("Invalid {var}={value:?}")]
+    Env { var: &'static str, value: String },
+
+    #[error("Invalid {field}: {message}")]
+    Invalid { field: &'static str, message: String },
+}
+
+/// Where a loaded config came from
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum ConfigSource {
+    File(PathBuf),
+    #[default]
+    Env,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigSource::File(path) => write!(f, "{}", path.display()),
+            ConfigSource::Env => write!(f, "environment"),
+        }
+    }
+}
+
+/// Command-line flag that turns paper trading on
+pub const PAPER_FLAG: &str = "--paper";
+
+/// Command-line flag naming a capture file to replay instead of the live feed
+pub const REPLAY_FLAG: &str = "--replay";
+
+/// Paper trading simulation settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PaperConfig {
+    pub enabled: bool,
+    pub starting_balance: f64,
+    pub buy_latency_ms: u64,
+    pub sell_latency_ms: u64,
+    pub trade_fee_percent: f64,
+    pub priority_fee_sol: f64,
+}
+
+impl Default for PaperConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            starting_balance: 1.0,
+            buy_latency_ms: 700,
+            sell_latency_ms: 500,
+            trade_fee_percent: 1.0,
+            priority_fee_sol: 0.0001,
+        }
+    }
+}
+
+impl PaperConfig {
+    /// Read each field from `PAPER_MODE` / `PAPER_<FIELD>`, keeping `defaults` for unset ones
+    pub fn from_env(defaults: PaperConfig) -> Result<Self, ConfigError> {
+        let d = defaults;
+        Ok(Self {
+            enabled: env_flag("PAPER_MODE", d.enabled)?,
+            starting_balance: env_or("PAPER_STARTING_BALANCE", d.starting_balance)?,
+            buy_latency_ms: env_or("PAPER_BUY_LATENCY_MS", d.buy_latency_ms)?,
+            sell_latency_ms: env_or("PAPER_SELL_LATENCY_MS", d.sell_latency_ms)?,
+            trade_fee_percent: env_or("PAPER_TRADE_FEE_PERCENT", d.trade_fee_percent)?,
+            priority_fee_sol: env_or("PAPER_PRIORITY_FEE_SOL", d.priority_fee_sol)?,
+        })
+    }
+
+    /// Turn paper trading on if the bot was started with [`PAPER_FLAG`]
+    pub fn apply_flag(&mut self) {
+        if env::args().skip(1).any(|arg| arg == PAPER_FLAG) {
+            self.enabled = true;
+        }
+    }
+}
+
+/// Settings shared by the sniper bots
+///
+/// Each bot starts from its own defaults and reads only the fields it uses.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BotConfig {
+    /// SOL per buy
+    pub snipe_amount_sol: f64,
+    /// Buy size range for bots that scale by confidence; unset means `snipe_amount_sol`
+    pub min_snipe_sol: Option<f64>,
+    pub max_snipe_sol: Option<f64>,
+    pub max_positions: usize,
+    /// Cap on SOL at cost across open positions and buys in flight; unset means no cap
+    pub max_total_sol_at_risk: Option<f64>,
+    /// Slippage tolerance in percent
+    pub buy_slippage: u32,
+    pub sell_slippage: u32,
+    /// Retry a trade that failed on slippage once, at up to this slippage; unset means no retry.
+    /// Preflight is skipped, so slips that only show up on-chain aren't retried
+    /// (see `PumpPortalClient::with_slippage_retry`)
+    pub max_slippage: Option<u32>,
+    /// Move buy slippage between `buy_slippage` and `max_slippage` with recent fills
+    pub adaptive_slippage: bool,
+    /// Priority fee per trade, in SOL
+    pub priority_fee_sol: f64,
+    /// Route trades through Jito only
+    pub jito_only: bool,
+    /// Hard stop: force-sell anything held this long (0 = off)
+    pub max_hold_secs: u64,
+    /// Wait before re-buying a mint after exiting it
+    pub mint_cooldown_secs: u64,
+    /// Pause buying after this many losing exits in a row (0 = off)
+    pub loss_streak_limit: u32,
+    /// How long that pause lasts
+    pub loss_cooldown_secs: u64,
+    pub candle_interval_ms: u64,
+    /// Completed candles kept per timeframe
+    pub candle_history: usize,
+    /// Slower second timeframe that has to agree before a momentum exit; unset means fast only
+    pub trend_candle_interval_ms: Option<u64>,
+    /// Momentum below this fraction triggers an exit
+    pub momentum_exit_threshold: f64,
+    /// Exit when price falls below this fraction of VWAP
+    pub vwap_exit_deviation: f64,
+    /// Exit below VWAP minus this many standard deviations instead of `vwap_exit_deviation`
+    pub vwap_band_std_mult: Option<f64>,
+    /// Completed candles in the Bollinger window for upper-band profit taking
+    pub bollinger_period: usize,
+    /// Bollinger band distance from the middle, in standard deviations
+    pub bollinger_std_mult: f64,
+    /// Bandwidth at or below which the bands count as a squeeze and breakouts are ignored
+    pub bollinger_squeeze: f64,
+    /// Partial sells as price multiples are reached, ascending (empty = none)
+    pub take_profit: Vec<exit::TakeProfitTier>,
+    /// Sell into a candle whose price rises at least this many percent; unset means off
+    pub scale_out_spike_percent: Option<f64>,
+    /// ...but only once the position is up at least this multiple
+    pub scale_out_min_multiple: f64,
+    /// Fraction of the remaining position each spike sells
+    pub scale_out_fraction: f64,
+    /// Exit strategy by name (see `exit::by_name`); unset means the bot's default
+    pub exit_strategy: Option<String>,
+    pub momentum_exits: bool,
+    /// How long a new position may go without any price data before it's sold
+    pub no_price_grace_secs: u64,
+    /// Sell every open position on Ctrl+C
+    pub sell_on_exit: bool,
+    /// Stop buying after this many trades
+    pub max_trades: Option<usize>,
+    pub max_concurrent_buys: usize,
+    pub max_requests_per_sec: Option<f64>,
+    /// Skip buys estimated to move the bonding curve more than this
+    pub max_price_impact_bps: Option<u32>,
+    /// Reconnect the PumpPortal WebSocket after this long without a message or pong
+    pub ws_stall_timeout_secs: u64,
+    /// Append every PumpPortal WebSocket message to this file, for `--replay`
+    pub ws_capture_path: Option<PathBuf>,
+    /// Time each snipe from launch to confirmed buy and store it in DuckDB,
+    /// labelled with [`latency_label`](Self::latency_label)
+    pub latency_benchmark: bool,
+    /// Skip launches with less SOL than this in the bonding curve (0 = off)
+    pub min_sol_reserves: f64,
+    /// Skip devs with this many rugs on record (0 = only the blocklist)
+    pub dev_max_rugs: u32,
+    /// Creator wallets never to buy from, one per line
+    pub dev_blocklist_file: Option<PathBuf>,
+    /// SQLite database positions and trades are kept in
+    pub database_path: PathBuf,
+    /// Serve `/metrics` and `/healthz` on this port
+    pub metrics_port: u16,
+    pub rpc_url: String,
+    /// POST trade notifications here (Discord, Slack, Telegram or any JSON webhook); unset means off
+    pub webhook_url: Option<String>,
+    /// Which events the webhook gets
+    pub webhook_events: Vec<NotifyEvent>,
+    pub paper: PaperConfig,
+
+    #[serde(skip)]
+    pub source: ConfigSource,
+    /// Capture file given with [`REPLAY_FLAG`]
+    #[serde(skip)]
+    pub replay_path: Option<PathBuf>,
+}
+
+impl Default for BotConfig {
+    fn default() -> Self {
+        Self {
+            snipe_amount_sol: 0.05,
+            min_snipe_sol: None,
+            max_snipe_sol: None,
+            max_positions: 3,
+            max_total_sol_at_risk: None,
+            buy_slippage: 10,
+            sell_slippage: 20,
+            max_slippage: None,
+            adaptive_slippage: false,
+            priority_fee_sol: 0.0001,
+            jito_only: true,
+            max_hold_secs: exit::DEFAULT_MAX_HOLD_SECS,
+            mint_cooldown_secs: 300,
+            loss_streak_limit: cooldown::DEFAULT_LOSS_STREAK_LIMIT,
+            loss_cooldown_secs: cooldown::DEFAULT_LOSS_COOLDOWN.as_secs(),
+            candle_interval_ms: 500,
+            candle_history: 100,
+            trend_candle_interval_ms: None,
+            momentum_exit_threshold: 0.2,
+            vwap_exit_deviation: 0.95,
+            vwap_band_std_mult: None,
+            bollinger_period: 20,
+            bollinger_std_mult: 2.0,
+            bollinger_squeeze: 0.0,
+            take_profit: vec![exit::DEFAULT_TAKE_PROFIT],
+            scale_out_spike_percent: None,
+            scale_out_min_multiple: 2.0,
+            scale_out_fraction: 0.25,
+            exit_strategy: None,
+            momentum_exits: true,
+            no_price_grace_secs: 60,
+            sell_on_exit: true,
+            max_trades: None,
+            max_concurrent_buys: 4,
+            max_requests_per_sec: None,
+            max_price_impact_bps: None,
+            ws_stall_timeout_secs: 30,
+            ws_capture_path: None,
+            latency_benchmark: false,
+            min_sol_reserves: 0.0,
+            dev_max_rugs: 1,
+            dev_blocklist_file: None,
+            database_path: PathBuf::from("sniper_bot.db"),
+            metrics_port: 9090,
+            rpc_url: "https://api.mainnet-beta.solana.com".to_string(),
+            webhook_url: None,
+            webhook_events: NotifyEvent::ALL.to_vec(),
+            paper: PaperConfig::default(),
+            source: ConfigSource::Env,
+            replay_path: None,
+        }
+    }
+}
+
+impl BotConfig {
+    /// Load from the config file if there is one, else from the environment,
+    /// apply [`PAPER_FLAG`] and [`REPLAY_FLAG`], then validate
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use pump_portal_sdk::config::BotConfig;
+    ///
+    /// let defaults = BotConfig { snipe_amount_sol: 0.02, ..BotConfig::default() };
+    /// let config = BotConfig::load(defaults)?;
+    /// config.display();
+    /// # Ok::<(), pump_portal_sdk::config::ConfigError>(())
+    /// ```
+    pub fn load(defaults: BotConfig) -> Result<Self, ConfigError> {
+        let mut config = match config_path() {
+            Some(path) => Self::from_file(&path, defaults)?,
+            None => Self::from_env(defaults)?,
+        };
+        config.paper.apply_flag();
+        config.apply_replay_flag()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Read a `.toml` or `.json` file over `defaults`
+    pub fn from_file(path: &Path, defaults: BotConfig) -> Result<Self, ConfigError> {
+        let text = std::fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let parse_error = |message: String| ConfigError::Parse { path: path.to_path_buf(), message };
+
+        let file: Value = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&text).map_err(|e| parse_error(e.to_string()))?
+        } else {
+            toml::from_str(&text).map_err(|e| parse_error(e.to_string()))?
+        };
+
+        let mut merged = serde_json::to_value(&defaults).map_err(|e| parse_error(e.to_string()))?;
+        merge(&mut merged, file);
+        let mut config: BotConfig = serde_json::from_value(merged).map_err(|e| parse_error(e.to_string()))?;
+        config.source = ConfigSource::File(path.to_path_buf());
+        Ok(config)
+    }
+
+    /// Read each field from its env var, keeping `defaults` for unset ones
+    pub fn from_env(defaults: BotConfig) -> Result<Self, ConfigError> {
+        let d = defaults;
+        Ok(Self {
+            // BASE_AMOUNT_SOL is ladder-sniper's older name for the same setting
+            snipe_amount_sol: match env_opt("SNIPE_AMOUNT_SOL", None)? {
+                Some(amount) => amount,
+                None => env_or("BASE_AMOUNT_SOL", d.snipe_amount_sol)?,
+            },
+            min_snipe_sol: env_opt("MIN_SNIPE_SOL", d.min_snipe_sol)?,
+            max_snipe_sol: env_opt("MAX_SNIPE_SOL", d.max_snipe_sol)?,
+            max_positions: env_or("MAX_POSITIONS", d.max_positions)?,
+            max_total_sol_at_risk: env_opt("MAX_TOTAL_SOL_AT_RISK", d.max_total_sol_at_risk)?,
+            buy_slippage: env_or("BUY_SLIPPAGE", d.buy_slippage)?,
+            sell_slippage: env_or("SELL_SLIPPAGE", d.sell_slippage)?,
+            max_slippage: env_opt("MAX_SLIPPAGE", d.max_slippage)?,
+            adaptive_slippage: env_flag("ADAPTIVE_SLIPPAGE", d.adaptive_slippage)?,
+            priority_fee_sol: env_or("PRIORITY_FEE_SOL", d.priority_fee_sol)?,
+            jito_only: env_flag("JITO_ONLY", d.jito_only)?,
+            max_hold_secs: env_or("MAX_HOLD_SECS", d.max_hold_secs)?,
+            mint_cooldown_secs: env_or("MINT_COOLDOWN_SECS", d.mint_cooldown_secs)?,
+            loss_streak_limit: env_or("LOSS_STREAK_LIMIT", d.loss_streak_limit)?,
+            loss_cooldown_secs: env_or("LOSS_COOLDOWN_SECS", d.loss_cooldown_secs)?,
+            candle_interval_ms: env_or("CANDLE_INTERVAL_MS", d.candle_interval_ms)?,
+            candle_history: env_or("CANDLE_HISTORY", d.candle_history)?,
+            trend_candle_interval_ms: env_opt("TREND_CANDLE_INTERVAL_MS", d.trend_candle_interval_ms)?,
+            momentum_exit_threshold: env_or("MOMENTUM_EXIT_THRESHOLD", d.momentum_exit_threshold)?,
+            vwap_exit_deviation: env_or("VWAP_EXIT_DEVIATION", d.vwap_exit_deviation)?,
+            vwap_band_std_mult: env_opt("VWAP_BAND_STD_MULT", d.vwap_band_std_mult)?,
+            bollinger_period: env_or("BOLLINGER_PERIOD", d.bollinger_period)?,
+            bollinger_std_mult: env_or("BOLLINGER_STD_MULT", d.bollinger_std_mult)?,
+            bollinger_squeeze: env_or("BOLLINGER_SQUEEZE", d.bollinger_squeeze)?,
+            take_profit: env_tiers(d.take_profit)?,
+            scale_out_spike_percent: env_opt("SCALE_OUT_SPIKE_PERCENT", d.scale_out_spike_percent)?,
+            scale_out_min_multiple: env_or("SCALE_OUT_MIN_MULT", d.scale_out_min_multiple)?,
+            scale_out_fraction: env_or("SCALE_OUT_FRACTION", d.scale_out_fraction)?,
+            exit_strategy: env::var("EXIT_STRATEGY").ok().or(d.exit_strategy),
+            momentum_exits: env_flag("MOMENTUM_EXITS", d.momentum_exits)?,
+            no_price_grace_secs: env_or("NO_PRICE_GRACE_SECS", d.no_price_grace_secs)?,
+            sell_on_exit: env_flag("SELL_ON_EXIT", d.sell_on_exit)?,
+            max_trades: env_opt("MAX_TRADES", d.max_trades)?,
+            max_concurrent_buys: env_or("MAX_CONCURRENT_BUYS", d.max_concurrent_buys)?,
+            max_requests_per_sec: env_opt("MAX_REQUESTS_PER_SEC", d.max_requests_per_sec)?,
+            max_price_impact_bps: env_opt("MAX_PRICE_IMPACT_BPS", d.max_price_impact_bps)?,
+            ws_stall_timeout_secs: env_or("WS_STALL_TIMEOUT_SECS", d.ws_stall_timeout_secs)?,
+            ws_capture_path: env::var("WS_CAPTURE_PATH").ok().map(PathBuf::from).or(d.ws_capture_path),
+            latency_benchmark: env_flag("LATENCY_BENCHMARK", d.latency_benchmark)?,
+            min_sol_reserves: env_or("MIN_SOL_RESERVES", d.min_sol_reserves)?,
+            dev_max_rugs: env_or("DEV_MAX_RUGS", d.dev_max_rugs)?,
+            dev_blocklist_file: env::var("DEV_BLOCKLIST_FILE").ok().map(PathBuf::from).or(d.dev_blocklist_file),
+            database_path: env::var("DATABASE_PATH").map(PathBuf::from).unwrap_or(d.database_path),
+            metrics_port: env_or("METRICS_PORT", d.metrics_port)?,
+            rpc_url: env::var("SOLANA_RPC_URL").unwrap_or(d.rpc_url),
+            webhook_url: env::var("WEBHOOK_URL").ok().or(d.webhook_url),
+            webhook_events: env_list("WEBHOOK_EVENTS", d.webhook_events)?,
+            paper: PaperConfig::from_env(d.paper)?,
+            source: ConfigSource::Env,
+            replay_path: d.replay_path,
+        })
+    }
+
+    /// Check every value is in a usable range
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        positive("snipe_amount_sol", self.snipe_amount_sol)?;
+        if let Some(min) = self.min_snipe_sol {
+            positive("min_snipe_sol", min)?;
+        }
+        if let Some(max) = self.max_snipe_sol {
+            positive("max_snipe_sol", max)?;
+        }
+        if let (Some(min), Some(max)) = (self.min_snipe_sol, self.max_snipe_sol) {
+            if min > max {
+                return Err(invalid("min_snipe_sol", format!("{} is above max_snipe_sol {}", min, max)));
+            }
+        }
+        if self.max_positions == 0 {
+            return Err(invalid("max_positions", "must be at least 1"));
+        }
+        if let Some(max) = self.max_total_sol_at_risk {
+            positive("max_total_sol_at_risk", max)?;
+            let smallest_buy = self.min_snipe_sol.unwrap_or(self.snipe_amount_sol);
+            if max < smallest_buy {
+                return Err(invalid(
+                    "max_total_sol_at_risk",
+                    format!("{} SOL is below the smallest buy of {} SOL", max, smallest_buy),
+                ));
+            }
+        }
+        for (field, slippage) in [("buy_slippage", self.buy_slippage), ("sell_slippage", self.sell_slippage)] {
+            if !(1..=100).contains(&slippage) {
+                return Err(invalid(field, format!("{}% is outside 1-100%", slippage)));
+            }
+        }
+        if let Some(max) = self.max_slippage {
+            let floor = self.buy_slippage.min(self.sell_slippage);
+            if !(floor..=100).contains(&max) {
+                return Err(invalid("max_slippage", format!("{}% is outside {}-100%", max, floor)));
+            }
+        }
+        if self.adaptive_slippage && self.max_slippage.is_none() {
+            return Err(invalid("adaptive_slippage", "needs max_slippage as its ceiling"));
+        }
+        if !self.priority_fee_sol.is_finite() || self.priority_fee_sol < 0.0 {
+            return Err(invalid("priority_fee_sol", "must not be negative"));
+        }
+        if self.loss_streak_limit > 0 && self.loss_cooldown_secs == 0 {
+            return Err(invalid("loss_cooldown_secs", "must be at least 1s while loss_streak_limit is on"));
+        }
+        if self.candle_interval_ms == 0 {
+            return Err(invalid("candle_interval_ms", "must be at least 1ms"));
+        }
+        if self.candle_history == 0 {
+            return Err(invalid("candle_history", "must be at least 1"));
+        }
+        if let Some(trend) = self.trend_candle_interval_ms {
+            if trend <= self.candle_interval_ms {
+                return Err(invalid(
+                    "trend_candle_interval_ms",
+                    format!("{}ms is not above candle_interval_ms {}ms", trend, self.candle_interval_ms),
+                ));
+            }
+        }
+        fraction("momentum_exit_threshold", self.momentum_exit_threshold)?;
+        fraction("vwap_exit_deviation", self.vwap_exit_deviation)?;
+        if let Some(k) = self.vwap_band_std_mult {
+            positive("vwap_band_std_mult", k)?;
+        }
+        if self.bollinger_period < 2 {
+            return Err(invalid("bollinger_period", "must be at least 2"));
+        }
+        positive("bollinger_std_mult", self.bollinger_std_mult)?;
+        if !(self.bollinger_squeeze.is_finite() && self.bollinger_squeeze >= 0.0) {
+            return Err(invalid("bollinger_squeeze", format!("{} is below 0", self.bollinger_squeeze)));
+        }
+        let mut last_multiple = 1.0;
+        for tier in &self.take_profit {
+            if !tier.multiple.is_finite() || tier.multiple <= last_multiple {
+                return Err(invalid(
+                    "take_profit",
+                    format!("{}x is not above {}x; tiers must be ascending multiples over 1x", tier.multiple, last_multiple),
+                ));
+            }
+            if !(tier.fraction > 0.0 && tier.fraction <= 1.0) {
+                return Err(invalid("take_profit", format!("fraction {} at {}x is outside 0.0-1.0", tier.fraction, tier.multiple)));
+            }
+            last_multiple = tier.multiple;
+        }
+        let total: f64 = self.take_profit.iter().map(|tier| tier.fraction).sum();
+        if total > 1.0 + 1e-9 {
+            return Err(invalid("take_profit", format!("fractions add up to {:.2}, more than the whole position", total)));
+        }
+        if let Some(spike) = self.scale_out_spike_percent {
+            positive("scale_out_spike_percent", spike)?;
+            if !self.scale_out_min_multiple.is_finite() || self.scale_out_min_multiple < 1.0 {
+                return Err(invalid("scale_out_min_multiple", format!("{}x is below 1x", self.scale_out_min_multiple)));
+            }
+            positive("scale_out_fraction", self.scale_out_fraction)?;
+            fraction("scale_out_fraction", self.scale_out_fraction)?;
+        }
+        if let Some(name) = &self.exit_strategy {
+            exit::by_name(name).map_err(|e| invalid("exit_strategy", e.to_string()))?;
+        }
+        if self.max_trades == Some(0) {
+            return Err(invalid("max_trades", "must be at least 1"));
+        }
+        if self.max_concurrent_buys == 0 {
+            return Err(invalid("max_concurrent_buys", "must be at least 1"));
+        }
+        if let Some(rate) = self.max_requests_per_sec {
+            positive("max_requests_per_sec", rate)?;
+        }
+        if self.ws_stall_timeout_secs == 0 {
+            return Err(invalid("ws_stall_timeout_secs", "must be at least 1s"));
+        }
+        if !(self.min_sol_reserves.is_finite() && self.min_sol_reserves >= 0.0) {
+            return Err(invalid("min_sol_reserves", "must not be negative"));
+        }
+        if !self.rpc_url.starts_with("http://") && !self.rpc_url.starts_with("https://") {
+            return Err(invalid("rpc_url", format!("{:?} is not an http(s) URL", self.rpc_url)));
+        }
+        if let Some(url) = &self.webhook_url {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(invalid("webhook_url", "is not an http(s) URL"));
+            }
+        }
+
+        positive("paper.starting_balance", self.paper.starting_balance)?;
+        if !(0.0..100.0).contains(&self.paper.trade_fee_percent) {
+            return Err(invalid("paper.trade_fee_percent", format!("{}% is outside 0-100%", self.paper.trade_fee_percent)));
+        }
+        if !self.paper.priority_fee_sol.is_finite() || self.paper.priority_fee_sol < 0.0 {
+            return Err(invalid("paper.priority_fee_sol", "must not be negative"));
+        }
+        Ok(())
+    }
+
+    /// Log the resolved settings
+    pub fn display(&self) {
+        log::info!("⚙️  Config ({}):", self.source);
+        match (self.min_snipe_sol, self.max_snipe_sol) {
+            (None, None) => log::info!("   Snipe Amount: {} SOL", self.snipe_amount_sol),
+            (min, max) => log::info!(
+                "   Snipe Amount: {}-{} SOL",
+                min.unwrap_or(self.snipe_amount_sol),
+                max.unwrap_or(self.snipe_amount_sol)
+            ),
+        }
+        match self.max_total_sol_at_risk {
+            Some(max) => log::info!("   Max Positions: {} | Max SOL At Risk: {} SOL", self.max_positions, max),
+            None => log::info!("   Max Positions: {}", self.max_positions),
+        }
+        match self.max_slippage {
+            Some(max) => log::info!(
+                "   Slippage: {}% buy / {}% sell, one retry up to {}%",
+                self.buy_slippage,
+                self.sell_slippage,
+                max
+            ),
+            None => log::info!("   Slippage: {}% buy / {}% sell", self.buy_slippage, self.sell_slippage),
+        }
+        if let (true, Some(max)) = (self.adaptive_slippage, self.max_slippage) {
+            log::info!("   🎚️  Adaptive Buy Slippage: {}-{}%", self.buy_slippage, max);
+        }
+        log::info!(
+            "   Priority Fee: {} SOL | Jito Only: {}",
+            self.priority_fee_sol,
+            if self.jito_only { "ON" } else { "OFF" }
+        );
+        log::info!("   Max Hold: {}s | Mint Cooldown: {}s", self.max_hold_secs, self.mint_cooldown_secs);
+        match self.loss_streak_limit {
+            0 => log::info!("   Loss Streak Pause: OFF"),
+            limit => log::info!("   Loss Streak Pause: {}s after {} losses in a row", self.loss_cooldown_secs, limit),
+        }
+        match self.trend_candle_interval_ms {
+            Some(trend) => log::info!(
+                "   Candles: {}ms + {}ms trend, {} kept",
+                self.candle_interval_ms,
+                trend,
+                self.candle_history
+            ),
+            None => log::info!("   Candles: {}ms, {} kept", self.candle_interval_ms, self.candle_history),
+        }
+        log::info!(
+            "   Momentum Exit: {:.0}% | VWAP Exit: {:.0}% deviation",
+            self.momentum_exit_threshold * 100.0,
+            (1.0 - self.vwap_exit_deviation) * 100.0
+        );
+        if let Some(k) = self.vwap_band_std_mult {
+            log::info!("   VWAP Band Exit: VWAP - {}σ", k);
+        }
+        log::info!(
+            "   Bollinger: {} candles, {}σ (squeeze at width {})",
+            self.bollinger_period,
+            self.bollinger_std_mult,
+            self.bollinger_squeeze
+        );
+        if self.take_profit.is_empty() {
+            log::info!("   Take Profit: OFF");
+        } else {
+            let tiers: Vec<String> = self
+                .take_profit
+                .iter()
+                .map(|tier| format!("{:.0}% @ {}x", tier.fraction * 100.0, tier.multiple))
+                .collect();
+            log::info!("   Take Profit: {}", tiers.join(", "));
+        }
+        if let Some(spike) = self.scale_out_spike_percent {
+            log::info!(
+                "   Scale Out: {:.0}% on +{}% candles above {}x",
+                self.scale_out_fraction * 100.0,
+                spike,
+                self.scale_out_min_multiple
+            );
+        }
+        log::info!("   Exit Strategy: {}", self.exit_strategy.as_deref().unwrap_or("bot default"));
+        log::info!("   Sell On Exit: {}", if self.sell_on_exit { "ON" } else { "OFF (keeping bags)" });
+        if let Some(max) = self.max_trades {
+            log::info!("   Max Trades: {}", max);
+        }
+        if let Some(rate) = self.max_requests_per_sec {
+            log::info!("   Rate Limit: {} req/s", rate);
+        }
+        if let Some(bps) = self.max_price_impact_bps {
+            log::info!("   Max Price Impact: {} bps", bps);
+        }
+        log::info!("   WebSocket Stall Timeout: {}s", self.ws_stall_timeout_secs);
+        if let Some(path) = &self.replay_path {
+            log::info!("   📼 Replaying: {}", path.display());
+        } else if let Some(path) = &self.ws_capture_path {
+            log::info!("   📼 Capturing to: {}", path.display());
+        }
+        if self.latency_benchmark {
+            log::info!("   ⏱️  Latency Benchmark: ON ({})", self.latency_label());
+        }
+        log::info!("   RPC: {}", self.rpc_url);
+        // The URL itself is left out; Discord and Telegram ones carry a token
+        if self.webhook_url.is_some() {
+            let events: Vec<&str> = self.webhook_events.iter().map(NotifyEvent::as_str).collect();
+            log::info!("   Webhook: ON ({})", events.join(", "));
+        }
+        if self.paper.enabled {
+            log::info!(
+                "   🧪 Paper: {} SOL start | {}ms/{}ms latency | {:.1}% fee | {} SOL priority fee",
+                self.paper.starting_balance,
+                self.paper.buy_latency_ms,
+                self.paper.sell_latency_ms,
+                self.paper.trade_fee_percent,
+                self.paper.priority_fee_sol
+            );
+        }
+    }
+
+    /// Replay the file after [`REPLAY_FLAG`] if the bot was started with it,
+    /// with paper trading forced on so nothing replayed buys for real
+    fn apply_replay_flag(&mut self) -> Result<(), ConfigError> {
+        let mut args = env::args().skip(1);
+        if args.any(|arg| arg == REPLAY_FLAG) {
+            let path = args.next().ok_or_else(|| invalid("replay", format!("{} needs a capture file", REPLAY_FLAG)))?;
+            self.replay_path = Some(PathBuf::from(path));
+            self.paper.enabled = true;
+        }
+        Ok(())
+    }
+
+    /// Notifier for `webhook_url`, a no-op when it's unset. `source` names the
+    /// bot in each message.
+    pub fn notifier(&self, source: &str) -> Arc<dyn Notifier> {
+        notify::notifier(self.webhook_url.as_deref(), source, &self.webhook_events)
+    }
+
+    /// The trade settings snipe latency is compared by, e.g. `jito=on priority_fee=0.0001`
+    pub fn latency_label(&self) -> String {
+        format!("jito={} priority_fee={}", if self.jito_only { "on" } else { "off" }, self.priority_fee_sol)
+    }
+
+    /// Buy slippage tracker between `buy_slippage` and `max_slippage` when
+    /// `adaptive_slippage` is on
+    pub fn adaptive_slippage(&self) -> Option<AdaptiveSlippage> {
+        let max = self.max_slippage.filter(|_| self.adaptive_slippage)?;
+        Some(AdaptiveSlippage::new(self.buy_slippage, max))
+    }
+
+    /// Budget holding buys to `max_total_sol_at_risk`, uncapped when it's unset
+    pub fn sol_budget(&self) -> SolBudget {
+        SolBudget::new(self.max_total_sol_at_risk)
+    }
+
+    /// Data stream settings: the stall timeout, `ws_capture_path`, and the
+    /// replay file in place of the live feed (`stream` feature)
+    #[cfg(feature = "stream")]
+    pub fn stream_config(&self) -> crate::StreamConfig {
+        let mut config = crate::StreamConfig::default()
+            .with_stall_timeout(std::time::Duration::from_secs(self.ws_stall_timeout_secs));
+        if let Some(path) = &self.ws_capture_path {
+            config = config.with_capture(path);
+        }
+        if let Some(path) = &self.replay_path {
+            config = config.with_replay(path);
+        }
+        config
+    }
+
+    /// Paper trading simulator with DuckDB export when `paper.enabled`, else
+    /// None and the bot trades for real (`paper` feature)
+    #[cfg(feature = "paper")]
+    pub fn paper_simulator(&self) -> Option<Arc<crate::PaperTradingSimulator>> {
+        self.paper
+            .enabled
+            .then(|| Arc::new(crate::PaperTradingSimulator::with_duckdb(self.paper.clone())))
+    }
+}
+
+/// `BOT_CONFIG` if set, else the first default file that exists
+fn config_path() -> Option<PathBuf> {
+    if let Ok(path) = env::var(CONFIG_PATH_VAR) {
+        return Some(PathBuf::from(path));
+    }
+    DEFAULT_PATHS.iter().map(PathBuf::from).find(|path| path.exists())
+}
+
+/// Overlay `overlay` onto `base`, recursing into tables
+fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Parse `var` if set, `default` if unset; a value that doesn't parse is an error
+fn env_or<T: FromStr>(var: &'static str, default: T) -> Result<T, ConfigError> {
+    Ok(env_opt(var, None)?.unwrap_or(default))
+}
+
+/// Like [`env_or`] for optional settings
+fn env_opt<T: FromStr>(var: &'static str, default: Option<T>) -> Result<Option<T>, ConfigError> {
+    match env::var(var) {
+        Ok(value) => match value.trim().parse() {
+            Ok(parsed) => Ok(Some(parsed)),
+            Err(_) => Err(ConfigError::Env { var, value }),
+        },
+        Err(_) => Ok(default),
+    }
+}
+
+/// Take-profit tiers from `TP1_MULT`/`TP1_FRACTION` up, stopping at the first unset
+/// `TP<n>_MULT`; `default` if `TP1_MULT` is unset
+fn env_tiers(default: Vec<exit::TakeProfitTier>) -> Result<Vec<exit::TakeProfitTier>, ConfigError> {
+    let mut tiers = Vec::new();
+    for (mult_var, fraction_var) in TAKE_PROFIT_VARS {
+        let multiple = match env_opt(mult_var, None)? {
+            Some(multiple) => multiple,
+            None => break,
+        };
+        let fraction = env_opt(fraction_var, None)?
+            .ok_or_else(|| invalid("take_profit", format!("{} is set without {}", mult_var, fraction_var)))?;
+        tiers.push(exit::TakeProfitTier { multiple, fraction });
+    }
+    Ok(if tiers.is_empty() { default } else { tiers })
+}
+
+/// Comma-separated list, `default` if unset; an empty value is an empty list
+fn env_list<T: FromStr>(var: &'static str, default: Vec<T>) -> Result<Vec<T>, ConfigError> {
+    match env::var(var) {
+        Ok(value) => value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| item.parse().map_err(|_| ConfigError::Env { var, value: value.clone() }))
+            .collect(),
+        Err(_) => Ok(default),
+    }
+}
+
+/// `true`/`false`/`1`/`0` flag, `default` if unset
+fn env_flag(var: &'static str, default: bool) -> Result<bool, ConfigError> {
+    match env::var(var) {
+        Ok(value) => match value.trim().to_lowercase().as_str() {
+            "true" | "1" | "yes" => Ok(true),
+            "false" | "0" | "no" => Ok(false),
+            _ => Err(ConfigError::Env { var, value }),
+        },
+        Err(_) => Ok(default),
+    }
+}
+
+fn invalid(field: &'static str, message: impl Into<String>) -> ConfigError {
+    ConfigError::Invalid { field, message: message.into() }
+}
+
+fn positive(field: &'static str, value: f64) -> Result<(), ConfigError> {
+    if value.is_finite() && value > 0.0 {
+        Ok(())
+    } else {
+        Err(invalid(field, format!("{} must be above 0", value)))
+    }
+}
+
+fn fraction(field: &'static str, value: f64) -> Result<(), ConfigError> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(())
+    } else {
+        Err(invalid(field, format!("{} is outside 0.0-1.0", value)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_temp(name: &str, contents: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("pump-portal-sdk-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_file_overrides_defaults() {
+        let defaults = BotConfig { snipe_amount_sol: 0.02, buy_slippage: 15, ..BotConfig::default() };
+        let path = write_temp(
+            "config.toml",
+            "sell_slippage = 25\nexit_strategy = \"ladder\"\n\n[paper]\nenabled = true\nstarting_balance = 2\n",
+        );
+        let config = BotConfig::from_file(&path, defaults).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(config.snipe_amount_sol, 0.02);
+        assert_eq!(config.buy_slippage, 15);
+        assert_eq!(config.sell_slippage, 25);
+        assert_eq!(config.exit_strategy.as_deref(), Some("ladder"));
+        assert!(config.paper.enabled);
+        assert_eq!(config.paper.starting_balance, 2.0);
+        assert_eq!(config.paper.buy_latency_ms, 700);
+        assert_eq!(config.source, ConfigSource::File(path));
+        config.validate().unwrap();
+    }
+
+    #[test]
+    fn test_json_file_and_unknown_fields() {
+        let path = write_temp(
+            "config.json",
+            r#"{"max_positions": 5, "max_trades": 10, "take_profit": [{"multiple": 1.8, "fraction": 0.33}, {"multiple": 3, "fraction": 0.33}], "webhook_events": ["sell", "rug"]}"#,
+        );
+        let config = BotConfig::from_file(&path, BotConfig::default()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(config.max_positions, 5);
+        assert_eq!(config.max_trades, Some(10));
+        assert_eq!(config.take_profit.len(), 2);
+        assert_eq!(config.take_profit[1], exit::TakeProfitTier { multiple: 3.0, fraction: 0.33 });
+        assert_eq!(config.webhook_events, vec![NotifyEvent::Sell, NotifyEvent::Rug]);
+        config.validate().unwrap();
+
+        let path = write_temp("typo.toml", "snipe_amount = 0.1\n");
+        let result = BotConfig::from_file(&path, BotConfig::default());
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(ConfigError::Parse { .. })));
+    }
+
+    #[test]
+    fn test_validate_ranges() {
+        BotConfig::default().validate().unwrap();
+
+        let cases = [
+            BotConfig { snipe_amount_sol: 0.0, ..BotConfig::default() },
+            BotConfig { min_snipe_sol: Some(0.1), max_snipe_sol: Some(0.05), ..BotConfig::default() },
+            BotConfig { max_total_sol_at_risk: Some(0.0), ..BotConfig::default() },
+            BotConfig { max_total_sol_at_risk: Some(0.01), ..BotConfig::default() },
+            BotConfig { sell_slippage: 150, ..BotConfig::default() },
+            BotConfig { max_slippage: Some(5), ..BotConfig::default() },
+            BotConfig { adaptive_slippage: true, ..BotConfig::default() },
+            BotConfig { priority_fee_sol: -0.001, ..BotConfig::default() },
+            BotConfig { scale_out_spike_percent: Some(30.0), scale_out_fraction: 1.5, ..BotConfig::default() },
+            BotConfig { scale_out_spike_percent: Some(30.0), scale_out_min_multiple: 0.5, ..BotConfig::default() },
+            BotConfig { loss_cooldown_secs: 0, ..BotConfig::default() },
+            BotConfig { candle_history: 0, ..BotConfig::default() },
+            BotConfig { trend_candle_interval_ms: Some(500), ..BotConfig::default() },
+            BotConfig { vwap_exit_deviation: 1.5, ..BotConfig::default() },
+            BotConfig { vwap_band_std_mult: Some(0.0), ..BotConfig::default() },
+            BotConfig { bollinger_period: 1, ..BotConfig::default() },
+            BotConfig { bollinger_std_mult: 0.0, ..BotConfig::default() },
+            BotConfig { bollinger_squeeze: -0.1, ..BotConfig::default() },
+            BotConfig {
+                take_profit: vec![
+                    exit::TakeProfitTier { multiple: 3.0, fraction: 0.3 },
+                    exit::TakeProfitTier { multiple: 1.8, fraction: 0.3 },
+                ],
+                ..BotConfig::default()
+            },
+            BotConfig {
+                take_profit: vec![
+                    exit::TakeProfitTier { multiple: 2.0, fraction: 0.6 },
+                    exit::TakeProfitTier { multiple: 4.0, fraction: 0.6 },
+                ],
+                ..BotConfig::default()
+            },
+            BotConfig { take_profit: vec![exit::TakeProfitTier { multiple: 0.5, fraction: 0.5 }], ..BotConfig::default() },
+            BotConfig { exit_strategy: Some("moon".to_string()), ..BotConfig::default() },
+            BotConfig { max_concurrent_buys: 0, ..BotConfig::default() },
+            BotConfig { min_sol_reserves: -1.0, ..BotConfig::default() },
+            BotConfig { rpc_url: "api.mainnet-beta.solana.com".to_string(), ..BotConfig::default() },
+            BotConfig { webhook_url: Some("discord.com/api/webhooks/1".to_string()), ..BotConfig::default() },
+        ];
+        for config in cases {
+            assert!(matches!(config.validate(), Err(ConfigError::Invalid { .. })), "{:?}", config);
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! On-chain confirmation of submitted trades
+//!
+//! PumpPortal returning a signature only means the transaction was accepted
+//! for submission. [`PumpPortalClient::confirm`] polls a Solana RPC node until
+//! the transaction lands, fails, or the timeout runs out.
+
+use crate::client::DRY_RUN_SIGNATURE_PREFIX;
+use crate::{PumpPortalClient, PumpPortalError, Result};
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+
+/// Delay between `getSignatureStatuses` polls
+pub const CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Outcome of waiting for a transaction
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfirmationStatus {
+    /// Reached `confirmed` or `finalized` without error
+    Confirmed,
+    /// Landed on-chain but the transaction failed
+    Failed(String),
+    /// Not confirmed before the timeout elapsed (likely dropped)
+    Timeout,
+}
+
+impl ConfirmationStatus {
+    pub fn is_confirmed(&self) -> bool {
+        matches!(self, ConfirmationStatus::Confirmed)
+    }
+
+    /// Whether the transaction failed on-chain because the price moved past
+    /// its slippage (the pump.fun program's custom errors 6002/6003)
+    pub fn is_slippage_failure(&self) -> bool {
+        let ConfirmationStatus::Failed(reason) = self else {
+            return false;
+        };
+        let lower = reason.to_lowercase().replace(' ', "");
+        lower.contains("\"custom\":6002") || lower.contains("\"custom\":6003") || crate::is_slippage_message(&lower)
+    }
+}
+
+impl PumpPortalClient {
+    /// Wait for a trade signature to be confirmed on-chain
+    ///
+    /// Polls `getSignatureStatuses` on `rpc_url` every 500ms until the
+    /// transaction is confirmed or finalized, fails, or `timeout` elapses.
+    /// RPC requests that fail are retried on the next poll. Dry-run
+    /// signatures confirm immediately.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PumpPortalError::ApiError` if the RPC node rejects the request
+    /// (e.g. a malformed signature).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use pump_portal_sdk::{ConfirmationStatus, PumpPortalClient};
+    /// # use std::time::Duration;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = PumpPortalClient::new("your-api-key".to_string());
+    /// let response = client.buy("TokenMintAddress".to_string(), 0.1, 10, 0.0001).await?;
+    ///
+    /// if let Some(signature) = response.signature {
+    ///     let status = client
+    ///         .confirm(&signature, "https://api.mainnet-beta.solana.com", Duration::from_secs(30))
+    ///         .await?;
+    ///     assert_eq!(status, ConfirmationStatus::Confirmed);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn confirm(
+        &self,
+        signature: &str,
+        rpc_url: &str,
+        timeout: Duration,
+    ) -> Result<ConfirmationStatus> {
+        if self.is_dry_run() || signature.starts_with(DRY_RUN_SIGNATURE_PREFIX) {
+            return Ok(ConfirmationStatus::Confirmed);
+        }
+
+        let body = status_request(signature);
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.http().post(rpc_url).timeout(self.timeout()).json(&body).send().await {
+                Ok(response) => match response.json::<Value>().await {
+                    Ok(value) => {
+                        if let Some(status) = parse_signature_status(&value)? {
+                            return Ok(status);
+                        }
+                    }
+                    Err(e) => log::debug!("Unreadable RPC response for {}: {}", signature, e),
+                },
+                Err(e) => log::debug!("RPC request failed for {}: {}", signature, e),
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                log::warn!("Transaction not confirmed after {:?}: {}", timeout, signature);
+                return Ok(ConfirmationStatus::Timeout);
+            }
+            tokio::time::sleep(CONFIRM_POLL_INTERVAL.min(deadline - now)).await;
+        }
+    }
+
+    /// Look a signature up once, None if the node hasn't seen it confirmed
+    /// (never seen, or only processed so far)
+    ///
+    /// Unlike [`confirm`](Self::confirm), a failed request is an error, so a
+    /// None means the node answered and didn't have the transaction.
+    pub async fn signature_status(&self, signature: &str, rpc_url: &str) -> Result<Option<ConfirmationStatus>> {
+        if self.is_dry_run() || signature.starts_with(DRY_RUN_SIGNATURE_PREFIX) {
+            return Ok(Some(ConfirmationStatus::Confirmed));
+        }
+
+        let response = self
+            .http()
+            .post(rpc_url)
+            .timeout(self.timeout())
+            .json(&status_request(signature))
+            .send()
+            .await?;
+        parse_signature_status(&response.json::<Value>().await?)
+    }
+}
+
+/// `getSignatureStatuses` request for `signature`, searching past the recent
+/// status cache
+fn status_request(signature: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "getSignatureStatuses",
+        "params": [[signature], { "searchTransactionHistory": true }],
+    })
+}
+
+/// Read a `getSignatureStatuses` response, None while still pending
+fn parse_signature_status(response: &Value) -> Result<Option<ConfirmationStatus>> {
+    if let Some(error) = response.get("error") {
+        let message = error
+            .get("message")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| error.to_string());
+        return Err(PumpPortalError::ApiError(format!("RPC error: {}", message)));
+    }
+
+    let status = match response.pointer("/result/value/0") {
+        Some(status) if !status.is_null() => status,
+        _ => return Ok(None), // Not seen by the node yet
+    };
+
+    if let Some(err) = status.get("err").filter(|err| !err.is_null()) {
+        return Ok(Some(ConfirmationStatus::Failed(err.to_string())));
+    }
+
+    match status.get("confirmationStatus").and_then(Value::as_str) {
+        Some("confirmed") | Some("finalized") => Ok(Some(ConfirmationStatus::Confirmed)),
+        _ => Ok(None), // Processed, not yet confirmed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rpc_response(status: Value) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": { "context": { "slot": 1 }, "value": [status] },
+        })
+    }
+
+    #[test]
+    fn test_parse_signature_status() {
+        assert_eq!(parse_signature_status(&rpc_response(Value::Null)).unwrap(), None);
+
+        let processed = rpc_response(json!({ "err": null, "confirmationStatus": "processed" }));
+        assert_eq!(parse_signature_status(&processed).unwrap(), None);
+
+        let finalized = rpc_response(json!({ "err": null, "confirmationStatus": "finalized" }));
+        assert_eq!(
+            parse_signature_status(&finalized).unwrap(),
+            Some(ConfirmationStatus::Confirmed)
+        );
+
+        let failed = rpc_response(json!({
+            "err": { "InstructionError": [0, { "Custom": 6001 }] },
+            "confirmationStatus": "confirmed",
+        }));
+        assert!(matches!(
+            parse_signature_status(&failed).unwrap(),
+            Some(ConfirmationStatus::Failed(reason)) if reason.contains("6001")
+        ));
+
+        let invalid = json!({ "error": { "code": -32602, "message": "Invalid param" } });
+        assert!(matches!(
+            parse_signature_status(&invalid),
+            Err(PumpPortalError::ApiError(msg)) if msg.contains("Invalid param")
+        ));
+    }
+
+    #[test]
+    fn test_slippage_failure() {
+        let slipped = ConfirmationStatus::Failed(r#"{"InstructionError":[2,{"Custom":6002}]}"#.to_string());
+        assert!(slipped.is_slippage_failure());
+        assert!(ConfirmationStatus::Failed(r#"{"InstructionError":[2,{"Custom":6003}]}"#.to_string()).is_slippage_failure());
+
+        let other = ConfirmationStatus::Failed(r#"{"InstructionError":[2,{"Custom":6001}]}"#.to_string());
+        assert!(!other.is_slippage_failure());
+        assert!(!ConfirmationStatus::Timeout.is_slippage_failure());
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_confirms_immediately() {
+        let client = PumpPortalClient::new("test-key".to_string()).dry_run(true);
+        let status = client
+            .confirm("PAPER_123", "http://127.0.0.1:1", Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert_eq!(status, ConfirmationStatus::Confirmed);
+    }
+
+    #[tokio::test]
+    async fn test_signature_status_errors_when_the_node_is_unreachable() {
+        let client = PumpPortalClient::new("test-key".to_string());
+        // Nothing listens there, so this can't read as "not found"
+        assert!(client.signature_status("5sig", "http://127.0.0.1:1").await.is_err());
+    }
+}
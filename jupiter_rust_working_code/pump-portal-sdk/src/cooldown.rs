@@ -0,0 +1,212 @@
+//! Cooldowns that hold the bots back from buying
+//!
+//! Launch feeds can repeat a mint (re-broadcasts, migrations, copycat events).
+//! Without a cooldown a rapid-fire bot re-enters immediately and churns fees
+//! on a token it has just sold. [`LossStreak`] pauses buying altogether after
+//! a run of losing exits, the same circuit breaker the core `RiskManager` has.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Default cooldown after a position on a mint closes
+pub const DEFAULT_MINT_COOLDOWN: Duration = Duration::from_secs(300);
+
+/// Default number of losing exits in a row that pauses buying
+pub const DEFAULT_LOSS_STREAK_LIMIT: u32 = 3;
+
+/// Default pause once the loss streak limit is hit
+pub const DEFAULT_LOSS_COOLDOWN: Duration = Duration::from_secs(900);
+
+/// Tracks when each mint was last traded
+#[derive(Debug, Clone)]
+pub struct MintCooldown {
+    cooldown: Duration,
+    recently_traded: HashMap<String, Instant>,
+}
+
+impl MintCooldown {
+    pub fn new(cooldown: Duration) -> Self {
+        Self {
+            cooldown,
+            recently_traded: HashMap::new(),
+        }
+    }
+
+    pub fn cooldown(&self) -> Duration {
+        self.cooldown
+    }
+
+    /// Start the cooldown for `mint` now
+    pub fn record(&mut self, mint: &str) {
+        self.record_at(mint, Instant::now());
+    }
+
+    fn record_at(&mut self, mint: &str, at: Instant) {
+        self.recently_traded.insert(mint.to_string(), at);
+    }
+
+    /// Time left before `mint` may be bought again, None if it's clear.
+    /// Expired entries are dropped as a side effect.
+    pub fn remaining(&mut self, mint: &str) -> Option<Duration> {
+        self.remaining_at(mint, Instant::now())
+    }
+
+    fn remaining_at(&mut self, mint: &str, now: Instant) -> Option<Duration> {
+        let cooldown = self.cooldown;
+        self.recently_traded
+            .retain(|_, traded| now.saturating_duration_since(*traded) < cooldown);
+        self.recently_traded
+            .get(mint)
+            .map(|traded| cooldown - now.saturating_duration_since(*traded))
+    }
+
+    /// Number of mints currently cooling down (including expired, not yet pruned)
+    pub fn len(&self) -> usize {
+        self.recently_traded.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.recently_traded.is_empty()
+    }
+}
+
+impl Default for MintCooldown {
+    fn default() -> Self {
+        Self::new(DEFAULT_MINT_COOLDOWN)
+    }
+}
+
+/// Counts losing exits in a row and pauses buying once there are too many
+///
+/// Every loss at or past `limit` restarts the pause, and a win resets the
+/// streak and lifts it. A `limit` of 0 never pauses.
+#[derive(Debug, Clone)]
+pub struct LossStreak {
+    limit: u32,
+    cooldown: Duration,
+    losses: u32,
+    paused_until: Option<Instant>,
+}
+
+impl LossStreak {
+    pub fn new(limit: u32, cooldown: Duration) -> Self {
+        Self {
+            limit,
+            cooldown,
+            losses: 0,
+            paused_until: None,
+        }
+    }
+
+    pub fn cooldown(&self) -> Duration {
+        self.cooldown
+    }
+
+    /// Losing exits since the last win
+    pub fn losses(&self) -> u32 {
+        self.losses
+    }
+
+    /// Record how an exit went. Returns the pause when this loss starts one.
+    pub fn record(&mut self, won: bool) -> Option<Duration> {
+        self.record_at(won, Instant::now())
+    }
+
+    fn record_at(&mut self, won: bool, at: Instant) -> Option<Duration> {
+        if won {
+            self.losses = 0;
+            self.paused_until = None;
+            return None;
+        }
+
+        self.losses += 1;
+        if self.limit == 0 || self.losses < self.limit || self.cooldown.is_zero() {
+            return None;
+        }
+        self.paused_until = Some(at + self.cooldown);
+        Some(self.cooldown)
+    }
+
+    /// Time left before buying may resume, None if it's clear
+    pub fn remaining(&self) -> Option<Duration> {
+        self.remaining_at(Instant::now())
+    }
+
+    fn remaining_at(&self, now: Instant) -> Option<Duration> {
+        self.paused_until
+            .filter(|until| *until > now)
+            .map(|until| until - now)
+    }
+}
+
+impl Default for LossStreak {
+    fn default() -> Self {
+        Self::new(DEFAULT_LOSS_STREAK_LIMIT, DEFAULT_LOSS_COOLDOWN)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cooldown_blocks_then_expires() {
+        let mut cooldown = MintCooldown::new(Duration::from_secs(60));
+        let start = Instant::now();
+
+        assert_eq!(cooldown.remaining_at("MintA", start), None);
+
+        cooldown.record_at("MintA", start);
+        assert_eq!(
+            cooldown.remaining_at("MintA", start + Duration::from_secs(20)),
+            Some(Duration::from_secs(40))
+        );
+        assert_eq!(cooldown.remaining_at("MintB", start), None);
+
+        assert_eq!(cooldown.remaining_at("MintA", start + Duration::from_secs(60)), None);
+        assert!(cooldown.is_empty());
+    }
+
+    #[test]
+    fn test_zero_cooldown_never_blocks() {
+        let mut cooldown = MintCooldown::new(Duration::ZERO);
+        cooldown.record("MintA");
+        assert_eq!(cooldown.remaining("MintA"), None);
+    }
+
+    #[test]
+    fn test_loss_streak_pauses_then_win_resets() {
+        let mut streak = LossStreak::new(3, Duration::from_secs(600));
+        let start = Instant::now();
+
+        assert_eq!(streak.record_at(false, start), None);
+        assert_eq!(streak.record_at(false, start), None);
+        assert_eq!(streak.remaining_at(start), None);
+
+        assert_eq!(streak.record_at(false, start), Some(Duration::from_secs(600)));
+        assert_eq!(
+            streak.remaining_at(start + Duration::from_secs(100)),
+            Some(Duration::from_secs(500))
+        );
+        assert_eq!(streak.remaining_at(start + Duration::from_secs(600)), None);
+
+        // Still on the streak, so the next loss pauses again straight away
+        let later = start + Duration::from_secs(700);
+        assert_eq!(streak.record_at(false, later), Some(Duration::from_secs(600)));
+        assert_eq!(streak.losses(), 4);
+
+        // A win from a position still open lifts the pause
+        assert_eq!(streak.record_at(true, later), None);
+        assert_eq!(streak.losses(), 0);
+        assert_eq!(streak.remaining_at(later), None);
+    }
+
+    #[test]
+    fn test_zero_limit_never_pauses() {
+        let mut streak = LossStreak::new(0, Duration::from_secs(600));
+        for _ in 0..10 {
+            assert_eq!(streak.record(false), None);
+        }
+        assert_eq!(streak.remaining(), None);
+    }
+}
@@ -0,0 +1,225 @@
+//! Local dedup so a retried buy can't spend twice
+//!
+//! PumpPortal has no idempotency key. A buy that times out or hits a 5xx may
+//! still have executed, and blindly resending it buys the token a second time.
+//! [`BuyGuard`] remembers each buy (by mint and amount) for a window:
+//!
+//! - a repeat while the first is still in flight waits for it and shares its result
+//! - a repeat after the first returned a signature gets that signature back
+//! - a repeat after the first failed ambiguously is refused with
+//!   `PumpPortalError::DuplicateBuy`, since only the chain knows whether it landed
+//!
+//! A buy the API definitely rejected (bad request, insufficient funds, rate
+//! limited) is forgotten straight away, so it can be retried.
+
+use crate::{PumpPortalError, Result, TradeRequest, TradeResponse};
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tokio::sync::{watch, Mutex};
+
+/// Default time a buy is remembered
+pub const DEFAULT_BUY_DEDUP_WINDOW: Duration = Duration::from_secs(30);
+
+/// What identifies "the same buy": mint, amount and its unit
+type BuyKey = (String, String, bool);
+
+/// How a remembered buy ended
+#[derive(Debug, Clone)]
+enum Outcome {
+    Sent(TradeResponse),
+    /// Failed in a way that doesn't say whether it executed
+    Unknown(String),
+    /// Definitely not executed; the entry is already gone
+    Rejected,
+}
+
+#[derive(Debug)]
+struct Entry {
+    started: Instant,
+    outcome: watch::Receiver<Option<Outcome>>,
+}
+
+/// Remembers recent buys and keeps identical ones from being sent twice
+#[derive(Debug)]
+pub struct BuyGuard {
+    window: Duration,
+    buys: Mutex<HashMap<BuyKey, Entry>>,
+}
+
+impl BuyGuard {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            buys: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Send `request` with `send` unless an identical buy is already in flight or
+    /// was sent within the window
+    pub async fn run<F, Fut>(&self, request: &TradeRequest, send: F) -> Result<TradeResponse>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<TradeResponse>>,
+    {
+        let key = (request.mint.clone(), request.amount.clone(), request.denominated_in_sol);
+
+        // Find an earlier buy to defer to, or claim the key for this one
+        let sender = loop {
+            let mut earlier = {
+                let mut buys = self.buys.lock().await;
+                let window = self.window;
+                buys.retain(|_, entry| entry.started.elapsed() < window);
+                match buys.get(&key) {
+                    Some(entry) => entry.outcome.clone(),
+                    None => {
+                        let (tx, rx) = watch::channel(None);
+                        buys.insert(key.clone(), Entry { started: Instant::now(), outcome: rx });
+                        break tx;
+                    }
+                }
+            };
+
+            // A dropped sender means the first caller gave up mid-request
+            let outcome = match earlier.wait_for(Option::is_some).await {
+                Ok(outcome) => outcome.clone().unwrap(),
+                Err(_) => Outcome::Unknown("earlier buy was cancelled in flight".to_string()),
+            };
+            match outcome {
+                Outcome::Sent(response) => {
+                    log::warn!("Buy of {} already sent, reusing its signature", request.mint);
+                    return Ok(response);
+                }
+                Outcome::Unknown(reason) => {
+                    return Err(PumpPortalError::DuplicateBuy {
+                        mint: request.mint.clone(),
+                        reason,
+                    });
+                }
+                // Gone from the map, so the next pass claims it
+                Outcome::Rejected => continue,
+            }
+        };
+
+        let result = send().await;
+        let outcome = match &result {
+            Ok(response) => Outcome::Sent(response.clone()),
+            Err(e) if is_ambiguous(e) => Outcome::Unknown(e.to_string()),
+            Err(_) => {
+                self.buys.lock().await.remove(&key);
+                Outcome::Rejected
+            }
+        };
+        let _ = sender.send(Some(outcome));
+        result
+    }
+}
+
+impl Default for BuyGuard {
+    fn default() -> Self {
+        Self::new(DEFAULT_BUY_DEDUP_WINDOW)
+    }
+}
+
+/// Whether a failed trade may still have executed server-side
+fn is_ambiguous(err: &PumpPortalError) -> bool {
+    matches!(
+        err,
+        PumpPortalError::Timeout(_) | PumpPortalError::RequestFailed(_) | PumpPortalError::ServerError(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    const MINT: &str = "TokenMintAddress";
+
+    fn buy(sol: f64) -> TradeRequest {
+        TradeRequest::buy(MINT.to_string(), sol, 10, 0.0001)
+    }
+
+    fn signed(signature: &str) -> TradeResponse {
+        TradeResponse {
+            signature: Some(signature.to_string()),
+            error: None,
+            pool: None,
+            input_amount: None,
+            extra: json!({}),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_duplicate_shares_first_signature() {
+        let guard = Arc::new(BuyGuard::new(Duration::from_secs(30)));
+        let sends = Arc::new(AtomicUsize::new(0));
+
+        let attempt = |signature: &'static str| {
+            let (guard, sends) = (guard.clone(), sends.clone());
+            async move {
+                guard
+                    .run(&buy(0.05), || async move {
+                        sends.fetch_add(1, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        Ok(signed(signature))
+                    })
+                    .await
+            }
+        };
+        let (first, second) = tokio::join!(attempt("sig1"), attempt("sig2"));
+
+        assert_eq!(sends.load(Ordering::SeqCst), 1);
+        assert_eq!(first.unwrap().signature, second.unwrap().signature);
+
+        // Still remembered afterwards; a different amount is a different buy
+        let again = guard.run(&buy(0.05), || async { Ok(signed("sig3")) }).await.unwrap();
+        assert_ne!(again.signature.as_deref(), Some("sig3"));
+        let other = guard.run(&buy(0.1), || async { Ok(signed("sig4")) }).await.unwrap();
+        assert_eq!(other.signature.as_deref(), Some("sig4"));
+    }
+
+    #[tokio::test]
+    async fn test_ambiguous_failure_blocks_resend() {
+        let guard = BuyGuard::new(Duration::from_secs(30));
+
+        let err = guard
+            .run(&buy(0.05), || async { Err(PumpPortalError::ServerError(502)) })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PumpPortalError::ServerError(502)));
+
+        let retry = guard.run(&buy(0.05), || async { Ok(signed("sig2")) }).await;
+        assert!(matches!(retry, Err(PumpPortalError::DuplicateBuy { ref mint, .. }) if mint == MINT));
+    }
+
+    #[tokio::test]
+    async fn test_definite_rejection_can_be_retried() {
+        let guard = BuyGuard::new(Duration::from_secs(30));
+
+        let err = guard
+            .run(&buy(0.05), || async { Err(PumpPortalError::InsufficientFunds) })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PumpPortalError::InsufficientFunds));
+
+        let retry = guard.run(&buy(0.05), || async { Ok(signed("sig2")) }).await.unwrap();
+        assert_eq!(retry.signature.as_deref(), Some("sig2"));
+    }
+
+    #[tokio::test]
+    async fn test_forgotten_after_window() {
+        let guard = BuyGuard::new(Duration::from_millis(20));
+        guard.run(&buy(0.05), || async { Ok(signed("sig1")) }).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        let later = guard.run(&buy(0.05), || async { Ok(signed("sig2")) }).await.unwrap();
+        assert_eq!(later.signature.as_deref(), Some("sig2"));
+    }
+}
@@ -0,0 +1,222 @@
+//! Events from PumpPortal's real-time data feed
+//!
+//! Every message on `wss://pumpportal.fun/api/data` that reports a transaction
+//! carries a `txType`: `create` for new tokens, `buy`/`sell` for trades, and
+//! `migrate` when a token graduates off its bonding curve. Token trades
+//! (`subscribeTokenTrade`) and account trades (`subscribeAccountTrade`) share
+//! one payload, so both parse as `Buy`/`Sell`; the subscription that asked for
+//! them is what tells them apart. Anything else, like subscription
+//! acknowledgements, fails to parse as an event.
+//!
+//! ```
+//! use pump_portal_sdk::PumpPortalEvent;
+//!
+//! let text = r#"{"signature":"5sig","mint":"Mint","txType":"sell","tokenAmount":1000.0,"solAmount":0.01}"#;
+//! if let Ok(PumpPortalEvent::Sell(trade)) = serde_json::from_str(text) {
+//!     assert_eq!(trade.sol_amount, 0.01);
+//! }
+//! ```
+
+use crate::types::BondingCurve;
+use serde::{Deserialize, Serialize};
+
+/// One message from the data feed, by `txType`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "txType", rename_all = "lowercase")]
+pub enum PumpPortalEvent {
+    /// New token launched (`subscribeNewToken`)
+    #[serde(rename = "create")]
+    NewToken(NewTokenEvent),
+    /// Token or account trade
+    Buy(TokenTradeEvent),
+    Sell(TokenTradeEvent),
+    /// Token left its bonding curve for an AMM (`subscribeMigration`)
+    #[serde(rename = "migrate")]
+    Migration(MigrationEvent),
+}
+
+/// Side of a trade event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+impl PumpPortalEvent {
+    pub fn mint(&self) -> &str {
+        match self {
+            PumpPortalEvent::NewToken(event) => &event.mint,
+            PumpPortalEvent::Buy(trade) | PumpPortalEvent::Sell(trade) => &trade.mint,
+            PumpPortalEvent::Migration(event) => &event.mint,
+        }
+    }
+
+    /// The trade and its side, for `Buy` and `Sell`
+    pub fn trade(&self) -> Option<(TradeSide, &TokenTradeEvent)> {
+        match self {
+            PumpPortalEvent::Buy(trade) => Some((TradeSide::Buy, trade)),
+            PumpPortalEvent::Sell(trade) => Some((TradeSide::Sell, trade)),
+            _ => None,
+        }
+    }
+}
+
+/// Token launch, including the creator's initial buy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewTokenEvent {
+    #[serde(default)]
+    pub signature: String,
+    pub mint: String,
+    /// Creator wallet
+    #[serde(default)]
+    pub trader_public_key: String,
+    /// Tokens the creator bought at launch
+    pub initial_buy: Option<f64>,
+    /// SOL the creator spent on the initial buy
+    pub sol_amount: Option<f64>,
+    pub bonding_curve_key: Option<String>,
+    pub v_tokens_in_bonding_curve: Option<f64>,
+    /// Virtual SOL reserves after the initial buy, in SOL
+    pub v_sol_in_bonding_curve: Option<f64>,
+    pub market_cap_sol: Option<f64>,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub symbol: String,
+    /// Metadata URI (name, image, socials)
+    pub uri: Option<String>,
+    /// Launchpad, e.g. `pump` or `bonk`
+    pub pool: Option<String>,
+}
+
+impl NewTokenEvent {
+    /// Creator wallet, if reported
+    pub fn creator(&self) -> Option<&str> {
+        Some(self.trader_public_key.as_str()).filter(|key| !key.is_empty())
+    }
+
+    /// Bonding-curve reserves after the initial buy, if reported
+    pub fn curve(&self) -> Option<BondingCurve> {
+        curve(self.v_sol_in_bonding_curve, self.v_tokens_in_bonding_curve)
+    }
+
+    /// SOL in the bonding curve when it's below the `min_sol` floor; launches
+    /// that don't report reserves pass
+    pub fn thin_reserves(&self, min_sol: f64) -> Option<f64> {
+        self.v_sol_in_bonding_curve.filter(|sol| *sol < min_sol)
+    }
+}
+
+/// Buy or sell on a token's bonding curve
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenTradeEvent {
+    #[serde(default)]
+    pub signature: String,
+    pub mint: String,
+    /// Wallet that traded
+    #[serde(default)]
+    pub trader_public_key: String,
+    pub token_amount: f64,
+    pub sol_amount: f64,
+    /// Trader's token balance after the trade
+    pub new_token_balance: Option<f64>,
+    pub bonding_curve_key: Option<String>,
+    pub v_tokens_in_bonding_curve: Option<f64>,
+    /// Virtual SOL reserves after the trade, in SOL
+    pub v_sol_in_bonding_curve: Option<f64>,
+    pub market_cap_sol: Option<f64>,
+    pub pool: Option<String>,
+}
+
+impl TokenTradeEvent {
+    /// Bonding-curve reserves after the trade, if reported
+    pub fn curve(&self) -> Option<BondingCurve> {
+        curve(self.v_sol_in_bonding_curve, self.v_tokens_in_bonding_curve)
+    }
+
+    /// Price in SOL per token: the post-trade curve price, else the trade's own SOL/token ratio
+    pub fn price(&self) -> Option<f64> {
+        let price = match self.curve() {
+            Some(curve) => curve.price(),
+            None => self.sol_amount / self.token_amount,
+        };
+        (price.is_finite() && price > 0.0).then_some(price)
+    }
+}
+
+/// Token graduated from its bonding curve
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationEvent {
+    #[serde(default)]
+    pub signature: String,
+    pub mint: String,
+    /// Pool it migrated to, e.g. `pump-amm` or `raydium`
+    pub pool: Option<String>,
+}
+
+fn curve(sol: Option<f64>, tokens: Option<f64>) -> Option<BondingCurve> {
+    match (sol, tokens) {
+        (Some(sol), Some(tokens)) if sol > 0.0 && tokens > 0.0 => Some(BondingCurve::new(sol, tokens)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Payloads as sent by wss://pumpportal.fun/api/data
+    const CREATE: &str = r#"{"signature":"3xXvYuFq4Bzo9xjKsSgqRJ7Lq4hxuG6RyCLyHGXuxZHXcSvYTe6dYtGDiXxgvBcnyj4mnXTeVsq4hjLFXmFVN1wd","mint":"7GCihgDB8fe6KNjn2MYtkzZcRjQy3t9GHdC8uHYmW2hr","traderPublicKey":"8kV2fb3Kc3KQy3X2WeGXtJqDqWZmYCWjdMzbEHSvHpXr","txType":"create","initialBuy":65231546.478735,"solAmount":2.0,"bondingCurveKey":"HmPhVQ1vLXa2YpWCmGL3KzDmXwoebvFN3ntJCvWSwNzD","vTokensInBondingCurve":1007768453.521265,"vSolInBondingCurve":31.94153846153846,"marketCapSol":31.69529479405867,"name":"Test Coin","symbol":"TEST","uri":"https://ipfs.io/ipfs/QmTestMetadataHash","pool":"pump"}"#;
+    const BUY: &str = r#"{"signature":"4uTyXpYm8RPXsiJvYBgd7EGZeMvmHGw1G4Bw3Tfh5VLzpUVqhLSw3jvFGzrPVGFTYtqjTPqrTBnwH8ckWg6LrfsV","mint":"7GCihgDB8fe6KNjn2MYtkzZcRjQy3t9GHdC8uHYmW2hr","traderPublicKey":"3nMFwZXwY1s1M5s8vYAHqd4wGs4iSxXE4LRoUMMYqEgF","txType":"buy","tokenAmount":3542938.431049,"solAmount":0.1,"newTokenBalance":3542938.431049,"bondingCurveKey":"HmPhVQ1vLXa2YpWCmGL3KzDmXwoebvFN3ntJCvWSwNzD","vTokensInBondingCurve":1004225515.090216,"vSolInBondingCurve":32.04153846153846,"marketCapSol":31.906738431818636,"pool":"pump"}"#;
+    const SELL: &str = r#"{"signature":"2mqhLpbGZsrZxqcEqQk1JTV9b4Pj6NBHxDyR1mUCn3nF9bYqbsS1MZbL4fVpU3dR3vsJ3cTSnmJVbRQj8FqyzDzA","mint":"7GCihgDB8fe6KNjn2MYtkzZcRjQy3t9GHdC8uHYmW2hr","traderPublicKey":"3nMFwZXwY1s1M5s8vYAHqd4wGs4iSxXE4LRoUMMYqEgF","txType":"sell","tokenAmount":3542938.431049,"solAmount":0.0985,"newTokenBalance":0,"bondingCurveKey":"HmPhVQ1vLXa2YpWCmGL3KzDmXwoebvFN3ntJCvWSwNzD","vTokensInBondingCurve":1007768453.521265,"vSolInBondingCurve":31.94153846153846,"marketCapSol":31.69529479405867,"pool":"pump"}"#;
+    const MIGRATE: &str = r#"{"signature":"5Hk8rTWgqgPcNbYJyvQ4J5q8qgKM6bDpPJa6RCTGKNBoEp6mqAWoCkQV1pmt2iU3EAPqgATHvYtbMmVMpxWPPdMu","mint":"7GCihgDB8fe6KNjn2MYtkzZcRjQy3t9GHdC8uHYmW2hr","txType":"migrate","pool":"pump-amm"}"#;
+
+    #[test]
+    fn test_new_token() {
+        let PumpPortalEvent::NewToken(event) = serde_json::from_str(CREATE).unwrap() else {
+            panic!("expected NewToken");
+        };
+        assert_eq!(event.mint, "7GCihgDB8fe6KNjn2MYtkzZcRjQy3t9GHdC8uHYmW2hr");
+        assert_eq!(event.creator(), Some("8kV2fb3Kc3KQy3X2WeGXtJqDqWZmYCWjdMzbEHSvHpXr"));
+        assert_eq!((event.name.as_str(), event.symbol.as_str()), ("Test Coin", "TEST"));
+        assert_eq!(event.initial_buy, Some(65231546.478735));
+        assert_eq!(event.uri.as_deref(), Some("https://ipfs.io/ipfs/QmTestMetadataHash"));
+        assert_eq!(event.pool.as_deref(), Some("pump"));
+        let curve = event.curve().unwrap();
+        assert_eq!(curve.virtual_sol_reserves, 31.94153846153846);
+        assert_eq!(event.thin_reserves(0.0), None);
+        assert_eq!(event.thin_reserves(40.0), Some(31.94153846153846));
+    }
+
+    #[test]
+    fn test_trades() {
+        let buy: PumpPortalEvent = serde_json::from_str(BUY).unwrap();
+        let (side, trade) = buy.trade().unwrap();
+        assert_eq!(side, TradeSide::Buy);
+        assert_eq!(trade.sol_amount, 0.1);
+        assert_eq!(trade.new_token_balance, Some(3542938.431049));
+        assert!((trade.price().unwrap() - 32.04153846153846 / 1004225515.090216).abs() < 1e-18);
+
+        let sell: PumpPortalEvent = serde_json::from_str(SELL).unwrap();
+        assert_eq!(sell.trade().unwrap().0, TradeSide::Sell);
+        assert_eq!(sell.mint(), "7GCihgDB8fe6KNjn2MYtkzZcRjQy3t9GHdC8uHYmW2hr");
+
+        // Without reserves the trade prices itself
+        let bare: PumpPortalEvent =
+            serde_json::from_str(r#"{"mint":"M","txType":"buy","tokenAmount":1000.0,"solAmount":0.5}"#).unwrap();
+        assert_eq!(bare.trade().unwrap().1.price(), Some(0.0005));
+    }
+
+    #[test]
+    fn test_migration_and_non_events() {
+        let migrate: PumpPortalEvent = serde_json::from_str(MIGRATE).unwrap();
+        assert!(matches!(&migrate, PumpPortalEvent::Migration(e) if e.pool.as_deref() == Some("pump-amm")));
+        assert!(migrate.trade().is_none());
+
+        let ack = r#"{"message":"Successfully subscribed to token creation events."}"#;
+        assert!(serde_json::from_str::<PumpPortalEvent>(ack).is_err());
+    }
+}
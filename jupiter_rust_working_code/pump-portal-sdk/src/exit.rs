@@ -0,0 +1,573 @@
+//! Pluggable exit strategies shared by the sniper bots
+//!
+//! Each bot builds a [`PositionState`] from whatever it tracks and asks an
+//! [`ExitStrategy`] what to do. Strategies are pure functions of that state,
+//! so the same implementation runs live and in backtests.
+
+use crate::{PumpPortalError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Names accepted by [`by_name`]
+pub const STRATEGY_NAMES: [&str; 4] = ["time", "2x_ladder", "vwap_momentum", "trailing_stop"];
+
+/// Default checkpoints for [`VwapMomentum`] as (elapsed_secs, min_momentum)
+pub const DEFAULT_CHECKPOINTS: [(u64, f64); 5] = [
+    (10, 0.2),
+    (20, 0.3),
+    (30, 0.4),
+    (45, 0.5),
+    (60, 0.6),
+];
+
+/// Default for the bots' `MAX_HOLD_SECS` hard stop
+pub const DEFAULT_MAX_HOLD_SECS: u64 = 600;
+
+/// Default profit take: half the position at 2x
+pub const DEFAULT_TAKE_PROFIT: TakeProfitTier = TakeProfitTier {
+    multiple: 2.0,
+    fraction: 0.5,
+};
+
+/// Sell `fraction` of the original position once it reaches `multiple`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TakeProfitTier {
+    /// Price multiple over entry (2.0 = doubled)
+    pub multiple: f64,
+    /// Share of the tokens originally bought (0.33 = a third)
+    pub fraction: f64,
+}
+
+/// Snapshot of an open position
+///
+/// Bots that don't track a field leave it at the neutral default; strategies
+/// that depend on it simply never fire.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionState {
+    pub elapsed_secs: u64,
+    pub pnl_multiplier: f64,  // Current price / entry price (1.0 = break even)
+    pub peak_multiplier: f64, // Highest pnl_multiplier seen so far
+    pub momentum: f64,        // 0.0-1.0, 0.5 = neutral
+    pub vwap_deviation: f64,  // Fraction from VWAP (-0.05 = 5% below)
+    pub exits_taken: u32,     // Partial sells already executed
+}
+
+impl PositionState {
+    /// Neutral state for a position held `elapsed_secs`
+    pub fn at(elapsed_secs: u64) -> Self {
+        Self {
+            elapsed_secs,
+            ..Self::default()
+        }
+    }
+}
+
+impl Default for PositionState {
+    fn default() -> Self {
+        Self {
+            elapsed_secs: 0,
+            pnl_multiplier: 1.0,
+            peak_multiplier: 1.0,
+            momentum: 0.5,
+            vwap_deviation: 0.0,
+            exits_taken: 0,
+        }
+    }
+}
+
+/// Why a position was sold
+///
+/// Every bot records one of these with each exit (database, DuckDB export,
+/// notifications), so results can be grouped by reason. It displays and
+/// parses as its snake_case name; parsing also accepts the free-form strings
+/// older builds stored, so existing rows still group correctly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitReason {
+    /// A profit-taking tier or ladder step
+    TakeProfit,
+    /// Sold a slice into a parabolic momentum spike
+    ScaleOut,
+    /// Price fell too far off its peak
+    TrailingStop,
+    /// Momentum faded below what the strategy needs to keep holding
+    MomentumDied,
+    /// Price broke below VWAP
+    BelowVwap,
+    /// The strategy's planned holding time ran out
+    TimeExit,
+    /// The `MAX_HOLD_SECS` safety stop
+    HardTimeStop,
+    /// Dev sold or liquidity was pulled
+    Rug,
+    /// No socials and already losing
+    ZeroSocials,
+    /// Never got a price within the grace window
+    NeverIndexed,
+    /// Price data disappeared after the position had one
+    PriceDataLost,
+    /// Price lookups kept failing
+    NoPriceData,
+    /// Sold when the bot shut down
+    Shutdown,
+}
+
+impl ExitReason {
+    pub const ALL: [ExitReason; 13] = [
+        ExitReason::TakeProfit,
+        ExitReason::ScaleOut,
+        ExitReason::TrailingStop,
+        ExitReason::MomentumDied,
+        ExitReason::BelowVwap,
+        ExitReason::TimeExit,
+        ExitReason::HardTimeStop,
+        ExitReason::Rug,
+        ExitReason::ZeroSocials,
+        ExitReason::NeverIndexed,
+        ExitReason::PriceDataLost,
+        ExitReason::NoPriceData,
+        ExitReason::Shutdown,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExitReason::TakeProfit => "take_profit",
+            ExitReason::ScaleOut => "scale_out",
+            ExitReason::TrailingStop => "trailing_stop",
+            ExitReason::MomentumDied => "momentum_died",
+            ExitReason::BelowVwap => "below_vwap",
+            ExitReason::TimeExit => "time_exit",
+            ExitReason::HardTimeStop => "hard_time_stop",
+            ExitReason::Rug => "rug",
+            ExitReason::ZeroSocials => "zero_socials",
+            ExitReason::NeverIndexed => "never_indexed",
+            ExitReason::PriceDataLost => "price_data_lost",
+            ExitReason::NoPriceData => "no_price_data",
+            ExitReason::Shutdown => "shutdown",
+        }
+    }
+}
+
+impl fmt::Display for ExitReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ExitReason {
+    type Err = PumpPortalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let lower = s.trim().to_lowercase();
+        if let Some(reason) = ExitReason::ALL.into_iter().find(|reason| reason.as_str() == lower) {
+            return Ok(reason);
+        }
+        // Strings recorded before exit reasons were typed
+        let legacy = match lower.as_str() {
+            "ladder" | "recover_initial" | "secure_3x" | "secure_6x" => Some(ExitReason::TakeProfit),
+            "no_momentum" => Some(ExitReason::MomentumDied),
+            "rug_detected" => Some(ExitReason::Rug),
+            "zero_socials_losing" => Some(ExitReason::ZeroSocials),
+            _ if lower.ends_with("x profit")
+                || lower.ends_with("x_profit")
+                || lower.ends_with("x ladder")
+                || lower.starts_with("ladder_") =>
+            {
+                Some(ExitReason::TakeProfit)
+            }
+            _ if lower.starts_with("trailing stop") => Some(ExitReason::TrailingStop),
+            _ if lower.contains("checkpoint - momentum") => Some(ExitReason::MomentumDied),
+            _ if lower.contains("below vwap") => Some(ExitReason::BelowVwap),
+            _ if lower.starts_with("held >") => Some(ExitReason::TimeExit),
+            _ if lower.starts_with("hard time stop") => Some(ExitReason::HardTimeStop),
+            _ => None,
+        };
+        legacy.ok_or_else(|| PumpPortalError::InvalidParameter(format!("unknown exit reason '{}'", s.trim())))
+    }
+}
+
+/// What an exit strategy wants done with a position
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExitDecision {
+    Hold,
+    /// Sell `percent` of the tokens currently held (100 closes the position),
+    /// with a human-readable `detail` for the logs
+    Sell { percent: u32, reason: ExitReason, detail: String },
+}
+
+impl ExitDecision {
+    pub fn sell(percent: u32, reason: ExitReason, detail: impl Into<String>) -> Self {
+        ExitDecision::Sell {
+            percent: percent.min(100),
+            reason,
+            detail: detail.into(),
+        }
+    }
+
+    /// Whether this decision closes the whole position
+    pub fn is_full_exit(&self) -> bool {
+        matches!(self, ExitDecision::Sell { percent: 100, .. })
+    }
+}
+
+/// Decides when to sell an open position
+pub trait ExitStrategy: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn evaluate(&self, position: &PositionState) -> ExitDecision;
+}
+
+/// Force a full exit once a position has been held `max_hold_secs`
+///
+/// Bots check this before their own exit logic, so a position whose normal
+/// exit is stuck (no price data, failing momentum checks) still gets closed.
+/// A `max_hold_secs` of 0 disables the stop.
+pub fn hard_time_stop(elapsed_secs: u64, max_hold_secs: u64) -> Option<ExitDecision> {
+    if max_hold_secs > 0 && elapsed_secs >= max_hold_secs {
+        Some(ExitDecision::sell(
+            100,
+            ExitReason::HardTimeStop,
+            format!("hard time stop after {}s", elapsed_secs),
+        ))
+    } else {
+        None
+    }
+}
+
+/// Build a strategy with default parameters from its name
+///
+/// Accepts the names in [`STRATEGY_NAMES`] plus the short forms
+/// `ladder`, `vwap` and `trailing`.
+pub fn by_name(name: &str) -> Result<Box<dyn ExitStrategy>> {
+    match name.trim().to_lowercase().as_str() {
+        "time" => Ok(Box::new(TimeBased::default())),
+        "2x_ladder" | "ladder" => Ok(Box::new(TwoXLadder::default())),
+        "vwap_momentum" | "vwap" => Ok(Box::new(VwapMomentum::default())),
+        "trailing_stop" | "trailing" => Ok(Box::new(TrailingStop::default())),
+        other => Err(PumpPortalError::InvalidParameter(format!(
+            "unknown exit strategy '{}', expected one of: {}",
+            other,
+            STRATEGY_NAMES.join(", ")
+        ))),
+    }
+}
+
+/// Exit everything once the position has been held too long
+#[derive(Debug, Clone)]
+pub struct TimeBased {
+    pub max_hold_secs: u64,
+}
+
+impl TimeBased {
+    pub fn new(max_hold_secs: u64) -> Self {
+        Self { max_hold_secs }
+    }
+}
+
+impl Default for TimeBased {
+    fn default() -> Self {
+        Self::new(60)
+    }
+}
+
+impl ExitStrategy for TimeBased {
+    fn name(&self) -> &'static str {
+        "time"
+    }
+
+    fn evaluate(&self, position: &PositionState) -> ExitDecision {
+        if position.elapsed_secs > self.max_hold_secs {
+            ExitDecision::sell(100, ExitReason::TimeExit, format!("Held > {}s", self.max_hold_secs))
+        } else {
+            ExitDecision::Hold
+        }
+    }
+}
+
+/// Sell a slice of the remaining bag at each multiple, keeping a moon bag
+#[derive(Debug, Clone)]
+pub struct TwoXLadder {
+    /// (pnl_multiplier, percent of remaining tokens), ascending
+    pub steps: Vec<(f64, u32)>,
+}
+
+impl TwoXLadder {
+    pub fn new(mut steps: Vec<(f64, u32)>) -> Self {
+        steps.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { steps }
+    }
+}
+
+impl Default for TwoXLadder {
+    fn default() -> Self {
+        Self::new(vec![(2.0, 50), (5.0, 50), (10.0, 50)])
+    }
+}
+
+impl ExitStrategy for TwoXLadder {
+    fn name(&self) -> &'static str {
+        "2x_ladder"
+    }
+
+    fn evaluate(&self, position: &PositionState) -> ExitDecision {
+        match self.steps.get(position.exits_taken as usize) {
+            Some(&(multiple, percent)) if position.pnl_multiplier >= multiple => {
+                ExitDecision::sell(percent, ExitReason::TakeProfit, format!("{}x ladder", multiple))
+            }
+            _ => ExitDecision::Hold,
+        }
+    }
+}
+
+/// Take profit at 2x, then exit on failed momentum checkpoints or a VWAP break
+#[derive(Debug, Clone)]
+pub struct VwapMomentum {
+    /// (pnl_multiplier, percent) sold once before the other rules apply
+    pub take_profit: Option<(f64, u32)>,
+    /// (elapsed_secs, min_momentum), ascending by time
+    pub checkpoints: Vec<(u64, f64)>,
+    /// Exit when price is this far below VWAP (0.05 = 5%)
+    pub vwap_exit_deviation: f64,
+}
+
+impl VwapMomentum {
+    pub fn with_take_profit(mut self, take_profit: Option<(f64, u32)>) -> Self {
+        self.take_profit = take_profit;
+        self
+    }
+
+    pub fn with_checkpoints(mut self, mut checkpoints: Vec<(u64, f64)>) -> Self {
+        checkpoints.sort_by_key(|(secs, _)| *secs);
+        self.checkpoints = checkpoints;
+        self
+    }
+
+    pub fn with_vwap_exit(mut self, deviation: f64) -> Self {
+        self.vwap_exit_deviation = deviation;
+        self
+    }
+}
+
+impl Default for VwapMomentum {
+    fn default() -> Self {
+        Self {
+            take_profit: Some((2.0, 50)),
+            checkpoints: DEFAULT_CHECKPOINTS.to_vec(),
+            vwap_exit_deviation: 0.05,
+        }
+    }
+}
+
+impl ExitStrategy for VwapMomentum {
+    fn name(&self) -> &'static str {
+        "vwap_momentum"
+    }
+
+    fn evaluate(&self, position: &PositionState) -> ExitDecision {
+        if let Some((multiple, percent)) = self.take_profit {
+            if position.exits_taken == 0 && position.pnl_multiplier >= multiple {
+                return ExitDecision::sell(percent, ExitReason::TakeProfit, format!("{}X PROFIT", multiple));
+            }
+        }
+
+        // Latest checkpoint reached applies; too early to exit before the first
+        let checkpoint = self
+            .checkpoints
+            .iter()
+            .rev()
+            .find(|(secs, _)| position.elapsed_secs >= *secs);
+
+        let (checkpoint_secs, threshold) = match checkpoint {
+            Some(&c) => c,
+            None => return ExitDecision::Hold,
+        };
+
+        if position.momentum < threshold {
+            return ExitDecision::sell(
+                100,
+                ExitReason::MomentumDied,
+                format!(
+                    "{}s checkpoint - momentum {:.1}% < {:.0}%",
+                    checkpoint_secs,
+                    position.momentum * 100.0,
+                    threshold * 100.0
+                ),
+            );
+        }
+
+        if position.vwap_deviation < -self.vwap_exit_deviation {
+            return ExitDecision::sell(
+                100,
+                ExitReason::BelowVwap,
+                format!("Price {:.1}% below VWAP", (position.vwap_deviation * 100.0).abs()),
+            );
+        }
+
+        ExitDecision::Hold
+    }
+}
+
+/// Exit everything once price falls a fixed fraction off its peak
+#[derive(Debug, Clone)]
+pub struct TrailingStop {
+    /// Fraction off the peak that triggers the exit (0.15 = 15%)
+    pub trail_fraction: f64,
+    /// Only trail once the peak has reached this multiple
+    pub activation_multiplier: f64,
+}
+
+impl TrailingStop {
+    pub fn new(trail_fraction: f64, activation_multiplier: f64) -> Self {
+        Self {
+            trail_fraction,
+            activation_multiplier,
+        }
+    }
+}
+
+impl Default for TrailingStop {
+    fn default() -> Self {
+        Self::new(0.15, 1.0)
+    }
+}
+
+impl ExitStrategy for TrailingStop {
+    fn name(&self) -> &'static str {
+        "trailing_stop"
+    }
+
+    fn evaluate(&self, position: &PositionState) -> ExitDecision {
+        let peak = position.peak_multiplier.max(position.pnl_multiplier);
+        if peak < self.activation_multiplier {
+            return ExitDecision::Hold;
+        }
+
+        if position.pnl_multiplier <= peak * (1.0 - self.trail_fraction) {
+            ExitDecision::sell(
+                100,
+                ExitReason::TrailingStop,
+                format!(
+                    "Trailing stop - {:.2}x is {:.0}% off peak {:.2}x",
+                    position.pnl_multiplier,
+                    self.trail_fraction * 100.0,
+                    peak
+                ),
+            )
+        } else {
+            ExitDecision::Hold
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_by_name() {
+        for name in STRATEGY_NAMES {
+            assert_eq!(by_name(name).unwrap().name(), name);
+        }
+        assert_eq!(by_name("vwap").unwrap().name(), "vwap_momentum");
+        assert!(matches!(
+            by_name("yolo"),
+            Err(PumpPortalError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_exit_reason_round_trip() {
+        for reason in ExitReason::ALL {
+            assert_eq!(reason.to_string().parse::<ExitReason>().unwrap(), reason);
+        }
+        // Strings stored before the enum existed
+        assert_eq!("2X_PROFIT".parse::<ExitReason>().unwrap(), ExitReason::TakeProfit);
+        assert_eq!("ladder_3x".parse::<ExitReason>().unwrap(), ExitReason::TakeProfit);
+        assert_eq!("no_momentum".parse::<ExitReason>().unwrap(), ExitReason::MomentumDied);
+        assert_eq!("rug_detected".parse::<ExitReason>().unwrap(), ExitReason::Rug);
+        assert_eq!("Price 8.0% below VWAP".parse::<ExitReason>().unwrap(), ExitReason::BelowVwap);
+        assert!("moon".parse::<ExitReason>().is_err());
+    }
+
+    #[test]
+    fn test_time_based() {
+        let strategy = TimeBased::new(10);
+        assert_eq!(strategy.evaluate(&PositionState::at(10)), ExitDecision::Hold);
+        assert!(strategy.evaluate(&PositionState::at(11)).is_full_exit());
+    }
+
+    #[test]
+    fn test_hard_time_stop() {
+        assert_eq!(hard_time_stop(599, 600), None);
+        assert!(hard_time_stop(600, 600).unwrap().is_full_exit());
+        assert_eq!(hard_time_stop(100_000, 0), None);
+    }
+
+    #[test]
+    fn test_ladder_steps_follow_exits_taken() {
+        let strategy = TwoXLadder::default();
+        let mut state = PositionState {
+            pnl_multiplier: 2.5,
+            ..PositionState::at(5)
+        };
+
+        assert!(matches!(strategy.evaluate(&state), ExitDecision::Sell { percent: 50, .. }));
+
+        // First rung already sold, next one is 5x
+        state.exits_taken = 1;
+        assert_eq!(strategy.evaluate(&state), ExitDecision::Hold);
+    }
+
+    #[test]
+    fn test_vwap_momentum() {
+        let strategy = VwapMomentum::default();
+
+        let doubled = PositionState {
+            pnl_multiplier: 2.0,
+            ..PositionState::at(5)
+        };
+        assert!(matches!(strategy.evaluate(&doubled), ExitDecision::Sell { percent: 50, .. }));
+
+        let weak = PositionState {
+            momentum: 0.1,
+            ..PositionState::at(10)
+        };
+        assert!(strategy.evaluate(&weak).is_full_exit());
+
+        let below_vwap = PositionState {
+            momentum: 0.9,
+            vwap_deviation: -0.08,
+            ..PositionState::at(15)
+        };
+        match strategy.evaluate(&below_vwap) {
+            ExitDecision::Sell { reason, detail, .. } => {
+                assert_eq!(reason, ExitReason::BelowVwap);
+                assert!(detail.contains("below VWAP"));
+            }
+            ExitDecision::Hold => panic!("expected VWAP exit"),
+        }
+
+        assert_eq!(strategy.evaluate(&PositionState::at(5)), ExitDecision::Hold);
+    }
+
+    #[test]
+    fn test_trailing_stop() {
+        let strategy = TrailingStop::new(0.2, 1.5);
+
+        let not_armed = PositionState {
+            pnl_multiplier: 0.9,
+            peak_multiplier: 1.2,
+            ..PositionState::at(30)
+        };
+        assert_eq!(strategy.evaluate(&not_armed), ExitDecision::Hold);
+
+        let off_peak = PositionState {
+            pnl_multiplier: 2.3,
+            peak_multiplier: 3.0,
+            ..PositionState::at(30)
+        };
+        assert!(strategy.evaluate(&off_peak).is_full_exit());
+    }
+}
@@ -0,0 +1,590 @@
+//! DuckDB research export for paper trading (`paper` feature)
+//!
+//! [`DataExporter`] writes each simulated trade, the positions they open and
+//! close, and per-position metric snapshots to a DuckDB file, so paper runs of
+//! any bot can be compared with the same queries.
+
+use crate::latency::{LatencyReport, SnipeLatency};
+use crate::ExitReason;
+use duckdb::{params, Connection, Result};
+use log::{info, warn};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone)]
+pub struct TradeRecord {
+    pub trade_id: String,
+    pub timestamp_micros: i64,
+    pub mint: String,
+    pub trade_type: String, // "BUY" or "SELL"
+    pub price: f64,
+    pub sol_amount: f64,
+    /// Tokens bought or sold, in UI units (mint decimals applied)
+    pub tokens: f64,
+    pub fee_sol: f64,
+    pub priority_fee_sol: f64,
+    pub balance_after: f64,
+    pub signature: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct PositionRecord {
+    pub position_id: String,
+    pub mint: String,
+    pub entry_time_micros: i64,
+    pub exit_time_micros: Option<i64>,
+    pub entry_price: f64,
+    pub exit_price: Option<f64>,
+    pub sol_invested: f64,
+    pub sol_returned: Option<f64>,
+    /// Tokens held, in UI units (mint decimals applied)
+    pub tokens: f64,
+    pub pnl_sol: Option<f64>,
+    pub pnl_percent: Option<f64>,
+    pub hold_duration_secs: Option<i64>,
+    pub holder_count_entry: u64,
+    pub holder_count_exit: Option<u64>,
+    pub exit_reason: Option<ExitReason>,
+    pub profits_taken: bool,
+    /// `pnl_sol` at the SOL/USD price when the position closed
+    pub pnl_usd: Option<f64>,
+}
+
+/// Closed positions sharing one [`ExitReason`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExitReasonStats {
+    pub reason: ExitReason,
+    pub positions: i64,
+    pub wins: i64,
+    pub avg_pnl_percent: f64,
+}
+
+/// One confirmed snipe's stage latencies (see [`crate::latency`])
+#[derive(Debug, Clone)]
+pub struct SnipeLatencyRecord {
+    pub signature: String,
+    pub timestamp_micros: i64,
+    pub mint: String,
+    /// Settings the snipe ran with, what runs are compared by
+    pub label: String,
+    pub latency: SnipeLatency,
+}
+
+#[derive(Debug, Clone)]
+pub struct PositionMetricRecord {
+    pub metric_id: String,
+    pub position_id: String,
+    pub mint: String,
+    pub timestamp_micros: i64,
+    pub elapsed_secs: i64,
+    pub current_price: f64,
+    pub pnl_multiplier: f64,
+    pub pnl_percent: f64,
+    pub vwap: f64,
+    pub vwap_distance_percent: f64,
+    pub momentum_score: f64,
+    pub buy_ratio: f64,
+    pub holder_count: u64,
+}
+
+pub struct DataExporter {
+    conn: Connection,
+    enabled: bool,
+}
+
+impl DataExporter {
+    pub fn new(db_path: &str, enabled: bool) -> Result<Self> {
+        if !enabled {
+            return Ok(Self {
+                conn: Connection::open_in_memory()?,
+                enabled: false,
+            });
+        }
+
+        let conn = Connection::open(db_path)?;
+
+        // Initialize schema
+        Self::init_schema(&conn)?;
+
+        info!("📊 DuckDB Analytics initialized at: {}", db_path);
+
+        Ok(Self { conn, enabled })
+    }
+
+    fn init_schema(conn: &Connection) -> Result<()> {
+        // Trades table
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS trades (
+                trade_id VARCHAR PRIMARY KEY,
+                timestamp_micros BIGINT NOT NULL,
+                mint VARCHAR NOT NULL,
+                trade_type VARCHAR NOT NULL,
+                price DOUBLE NOT NULL,
+                sol_amount DOUBLE NOT NULL,
+                tokens DOUBLE NOT NULL,
+                fee_sol DOUBLE NOT NULL,
+                priority_fee_sol DOUBLE NOT NULL,
+                balance_after DOUBLE NOT NULL,
+                signature VARCHAR NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_trades_mint ON trades(mint)",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_trades_timestamp ON trades(timestamp_micros)",
+            [],
+        )?;
+
+        // Positions table
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS positions (
+                position_id VARCHAR PRIMARY KEY,
+                mint VARCHAR NOT NULL,
+                entry_time_micros BIGINT NOT NULL,
+                exit_time_micros BIGINT,
+                entry_price DOUBLE NOT NULL,
+                exit_price DOUBLE,
+                sol_invested DOUBLE NOT NULL,
+                sol_returned DOUBLE,
+                tokens DOUBLE NOT NULL,
+                pnl_sol DOUBLE,
+                pnl_percent DOUBLE,
+                hold_duration_secs BIGINT,
+                holder_count_entry BIGINT NOT NULL,
+                holder_count_exit BIGINT,
+                exit_reason VARCHAR,
+                profits_taken BOOLEAN DEFAULT FALSE,
+                pnl_usd DOUBLE
+            )",
+            [],
+        )?;
+
+        // pnl_usd was added later; bring older databases up to date
+        conn.execute("ALTER TABLE positions ADD COLUMN IF NOT EXISTS pnl_usd DOUBLE", [])?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_positions_mint ON positions(mint)",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_positions_entry_time ON positions(entry_time_micros)",
+            [],
+        )?;
+
+        // Position metrics table (time-series with microsecond precision)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS position_metrics (
+                metric_id VARCHAR PRIMARY KEY,
+                position_id VARCHAR NOT NULL,
+                mint VARCHAR NOT NULL,
+                timestamp_micros BIGINT NOT NULL,
+                elapsed_secs BIGINT NOT NULL,
+                current_price DOUBLE NOT NULL,
+                pnl_multiplier DOUBLE NOT NULL,
+                pnl_percent DOUBLE NOT NULL,
+                vwap DOUBLE NOT NULL,
+                vwap_distance_percent DOUBLE NOT NULL,
+                momentum_score DOUBLE NOT NULL,
+                buy_ratio DOUBLE NOT NULL,
+                holder_count BIGINT NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_metrics_position ON position_metrics(position_id)",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_metrics_timestamp ON position_metrics(timestamp_micros)",
+            [],
+        )?;
+
+        // Snipe latency table (launch received to buy confirmed, per stage)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS snipe_latency (
+                signature VARCHAR PRIMARY KEY,
+                timestamp_micros BIGINT NOT NULL,
+                mint VARCHAR NOT NULL,
+                label VARCHAR NOT NULL,
+                decide_ms DOUBLE NOT NULL,
+                submit_ms DOUBLE NOT NULL,
+                confirm_ms DOUBLE NOT NULL
+            )",
+            [],
+        )?;
+
+        info!("✅ Database schema initialized");
+        Ok(())
+    }
+
+    pub fn record_trade(&self, trade: TradeRecord) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        self.conn.execute(
+            "INSERT INTO trades VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                trade.trade_id,
+                trade.timestamp_micros,
+                trade.mint,
+                trade.trade_type,
+                trade.price,
+                trade.sol_amount,
+                trade.tokens,
+                trade.fee_sol,
+                trade.priority_fee_sol,
+                trade.balance_after,
+                trade.signature,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn record_position(&self, position: PositionRecord) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        self.conn.execute(
+            "INSERT OR REPLACE INTO positions VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                position.position_id,
+                position.mint,
+                position.entry_time_micros,
+                position.exit_time_micros,
+                position.entry_price,
+                position.exit_price,
+                position.sol_invested,
+                position.sol_returned,
+                position.tokens,
+                position.pnl_sol,
+                position.pnl_percent,
+                position.hold_duration_secs,
+                position.holder_count_entry as i64,
+                position.holder_count_exit.map(|h| h as i64),
+                position.exit_reason.map(|reason| reason.to_string()),
+                position.profits_taken,
+                position.pnl_usd,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn record_metric(&self, metric: PositionMetricRecord) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        self.conn.execute(
+            "INSERT INTO position_metrics VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                metric.metric_id,
+                metric.position_id,
+                metric.mint,
+                metric.timestamp_micros,
+                metric.elapsed_secs,
+                metric.current_price,
+                metric.pnl_multiplier,
+                metric.pnl_percent,
+                metric.vwap,
+                metric.vwap_distance_percent,
+                metric.momentum_score,
+                metric.buy_ratio,
+                metric.holder_count as i64,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn record_snipe_latency(&self, record: SnipeLatencyRecord) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO snipe_latency VALUES (?, ?, ?, ?, ?, ?, ?)",
+            params![
+                record.signature,
+                record.timestamp_micros,
+                record.mint,
+                record.label,
+                ms(record.latency.decide),
+                ms(record.latency.submit),
+                ms(record.latency.confirm),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Snipe latency percentiles for each label, by label
+    pub fn latency_reports(&self) -> Result<Vec<(String, LatencyReport)>> {
+        if !self.enabled {
+            return Ok(Vec::new());
+        }
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT label, decide_ms, submit_ms, confirm_ms FROM snipe_latency")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, f64>(1)?,
+                row.get::<_, f64>(2)?,
+                row.get::<_, f64>(3)?,
+            ))
+        })?;
+
+        let ms = |ms: f64| Duration::from_secs_f64(ms.max(0.0) / 1000.0);
+        let mut by_label: BTreeMap<String, Vec<SnipeLatency>> = BTreeMap::new();
+        for row in rows {
+            let (label, decide, submit, confirm) = row?;
+            by_label.entry(label).or_default().push(SnipeLatency {
+                decide: ms(decide),
+                submit: ms(submit),
+                confirm: ms(confirm),
+            });
+        }
+        Ok(by_label
+            .into_iter()
+            .filter_map(|(label, samples)| Some((label, LatencyReport::from_samples(&samples)?)))
+            .collect())
+    }
+
+    pub fn get_stats(&self) -> Result<(i64, i64, i64)> {
+        if !self.enabled {
+            return Ok((0, 0, 0));
+        }
+
+        let trade_count: i64 = self.conn
+            .query_row("SELECT COUNT(*) FROM trades", [], |row| row.get(0))?;
+
+        let position_count: i64 = self.conn
+            .query_row("SELECT COUNT(*) FROM positions", [], |row| row.get(0))?;
+
+        let metric_count: i64 = self.conn
+            .query_row("SELECT COUNT(*) FROM position_metrics", [], |row| row.get(0))?;
+
+        Ok((trade_count, position_count, metric_count))
+    }
+
+    /// Closed positions grouped by exit reason, most frequent first
+    ///
+    /// Rows whose stored reason doesn't parse as an [`ExitReason`] are left out.
+    pub fn exit_reason_stats(&self) -> Result<Vec<ExitReasonStats>> {
+        if !self.enabled {
+            return Ok(Vec::new());
+        }
+
+        let mut stmt = self.conn.prepare(
+            "SELECT
+                exit_reason,
+                COUNT(*) as positions,
+                COUNT(CASE WHEN pnl_sol > 0 THEN 1 END) as wins,
+                AVG(pnl_percent) as avg_pnl
+             FROM positions
+             WHERE exit_time_micros IS NOT NULL AND exit_reason IS NOT NULL
+             GROUP BY exit_reason",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, Option<f64>>(3)?,
+            ))
+        })?;
+
+        // Older rows may store a legacy spelling of the same reason, so merge after parsing
+        let mut stats: Vec<ExitReasonStats> = Vec::new();
+        for row in rows {
+            let (reason, positions, wins, avg_pnl) = row?;
+            let Ok(reason) = reason.parse::<ExitReason>() else {
+                continue;
+            };
+            let pnl_total = avg_pnl.unwrap_or(0.0) * positions as f64;
+            match stats.iter_mut().find(|s| s.reason == reason) {
+                Some(s) => {
+                    let total = s.avg_pnl_percent * s.positions as f64 + pnl_total;
+                    s.positions += positions;
+                    s.wins += wins;
+                    s.avg_pnl_percent = total / s.positions as f64;
+                }
+                None => stats.push(ExitReasonStats {
+                    reason,
+                    positions,
+                    wins,
+                    avg_pnl_percent: avg_pnl.unwrap_or(0.0),
+                }),
+            }
+        }
+        stats.sort_by_key(|s| std::cmp::Reverse(s.positions));
+        Ok(stats)
+    }
+
+    pub fn print_summary(&self) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let (trades, positions, metrics) = self.get_stats()?;
+
+        info!("📊 DATABASE SUMMARY:");
+        info!("   Trades: {}", trades);
+        info!("   Positions: {}", positions);
+        info!("   Metrics: {} (holder count snapshots)", metrics);
+
+        // Calculate win rate if we have closed positions
+        if positions > 0 {
+            let win_stats: Result<(i64, i64, f64)> = self.conn.query_row(
+                "SELECT
+                    SUM(CASE WHEN pnl_sol > 0 THEN 1 ELSE 0 END) as wins,
+                    SUM(CASE WHEN pnl_sol <= 0 THEN 1 ELSE 0 END) as losses,
+                    AVG(pnl_percent) as avg_pnl
+                 FROM positions
+                 WHERE exit_time_micros IS NOT NULL",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            );
+
+            if let Ok((wins, losses, avg_pnl)) = win_stats {
+                let total = wins + losses;
+                if total > 0 {
+                    let win_rate = (wins as f64 / total as f64) * 100.0;
+                    info!("   Win Rate: {:.1}% ({}/{} trades)", win_rate, wins, total);
+                    info!("   Avg P&L: {:.2}%", avg_pnl);
+                }
+            }
+
+            for stats in self.exit_reason_stats()? {
+                info!(
+                    "   {}: {} closed, {} wins, avg {:+.2}%",
+                    stats.reason, stats.positions, stats.wins, stats.avg_pnl_percent
+                );
+            }
+        }
+
+        for (label, report) in self.latency_reports()? {
+            report.log(&label);
+        }
+
+        Ok(())
+    }
+}
+
+pub type SharedExporter = Arc<Mutex<DataExporter>>;
+
+/// Exporter for the DuckDB file at `DUCKDB_PATH` (default
+/// [`DEFAULT_DUCKDB_PATH`](crate::paper::DEFAULT_DUCKDB_PATH)), None if it won't open
+pub fn shared_from_env() -> Option<SharedExporter> {
+    let db_path = std::env::var("DUCKDB_PATH").unwrap_or_else(|_| crate::paper::DEFAULT_DUCKDB_PATH.to_string());
+
+    match DataExporter::new(&db_path, true) {
+        Ok(exp) => {
+            info!("📊 DuckDB Analytics: ENABLED");
+            info!("   Database: {}", db_path);
+            Some(Arc::new(Mutex::new(exp)))
+        }
+        Err(e) => {
+            warn!("⚠️  Failed to initialize DuckDB: {}", e);
+            None
+        }
+    }
+}
+
+/// Microseconds since the Unix epoch, the timestamp unit of every table
+pub fn get_timestamp_micros() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_micros() as i64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn closed(mint: &str, reason: &str, pnl_sol: f64) -> PositionRecord {
+        PositionRecord {
+            position_id: mint.to_string(),
+            mint: mint.to_string(),
+            entry_time_micros: 0,
+            exit_time_micros: Some(1_000_000),
+            entry_price: 1.0,
+            exit_price: Some(1.0 + pnl_sol),
+            sol_invested: 1.0,
+            sol_returned: Some(1.0 + pnl_sol),
+            tokens: 1.0,
+            pnl_sol: Some(pnl_sol),
+            pnl_percent: Some(pnl_sol * 100.0),
+            hold_duration_secs: Some(1),
+            holder_count_entry: 0,
+            holder_count_exit: None,
+            exit_reason: reason.parse().ok(),
+            profits_taken: false,
+            pnl_usd: None,
+        }
+    }
+
+    #[test]
+    fn test_exit_reason_stats() {
+        let exporter = DataExporter::new(":memory:", true).unwrap();
+        exporter.record_position(closed("a", "take_profit", 1.0)).unwrap();
+        exporter.record_position(closed("b", "trailing_stop", -0.5)).unwrap();
+        exporter.record_position(closed("c", "take_profit", 0.5)).unwrap();
+        // A row written before exit reasons were typed groups with the same reason
+        exporter.record_position(closed("d", "trailing_stop", 0.2)).unwrap();
+        exporter
+            .conn
+            .execute("UPDATE positions SET exit_reason = '2X_PROFIT' WHERE mint = 'd'", [])
+            .unwrap();
+
+        let stats = exporter.exit_reason_stats().unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].reason, ExitReason::TakeProfit);
+        assert_eq!((stats[0].positions, stats[0].wins), (3, 3));
+        assert!((stats[0].avg_pnl_percent - 170.0 / 3.0).abs() < 1e-9);
+        assert_eq!(stats[1].reason, ExitReason::TrailingStop);
+        assert_eq!((stats[1].positions, stats[1].wins), (1, 0));
+    }
+
+    #[test]
+    fn test_latency_reports_by_label() {
+        let exporter = DataExporter::new(":memory:", true).unwrap();
+        let ms = Duration::from_millis;
+        for (i, (label, confirm)) in [("jito", 400), ("jito", 600), ("rpc", 900)].into_iter().enumerate() {
+            exporter
+                .record_snipe_latency(SnipeLatencyRecord {
+                    signature: format!("sig{}", i),
+                    timestamp_micros: 0,
+                    mint: "mint".to_string(),
+                    label: label.to_string(),
+                    latency: SnipeLatency { decide: ms(2), submit: ms(100), confirm: ms(confirm) },
+                })
+                .unwrap();
+        }
+
+        let reports = exporter.latency_reports().unwrap();
+        assert_eq!(reports.len(), 2);
+        let (label, jito) = &reports[0];
+        assert_eq!((label.as_str(), jito.snipes), ("jito", 2));
+        assert_eq!((jito.confirm.p50, jito.confirm.max), (ms(400), ms(600)));
+        assert_eq!(jito.total.max, ms(702));
+        assert_eq!((reports[1].0.as_str(), reports[1].1.snipes), ("rpc", 1));
+    }
+}
@@ -0,0 +1,440 @@
+//! What a confirmed trade actually did on-chain
+//!
+//! The price a bot sees when it decides to buy is not what it pays: slippage,
+//! fees and other buyers in the same slot all move it. [`PumpPortalClient::fill`]
+//! reads the landed transaction and works out what the wallet really paid,
+//! [`PumpPortalClient::proceeds`] what a sell really returned, and
+//! [`PumpPortalClient::pool_used`] tells which pool it was routed through.
+
+use crate::client::DRY_RUN_SIGNATURE_PREFIX;
+use crate::confirm::CONFIRM_POLL_INTERVAL;
+use crate::{Pool, PumpPortalClient, PumpPortalError, Result};
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+
+/// Rent locked in a new SPL token account. Refunded when the account is
+/// closed, so it isn't part of the entry cost.
+pub const TOKEN_ACCOUNT_RENT_LAMPORTS: u64 = 2_039_280;
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// What a buy actually cost and returned
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fill {
+    /// SOL that left the wallet, including fees and tips, excluding account rent
+    pub sol_spent: f64,
+    /// Tokens that arrived, in UI units (decimals applied)
+    pub tokens_received: f64,
+}
+
+impl Fill {
+    /// Entry price in SOL per token, the same unit the bots track prices in
+    pub fn price(&self) -> f64 {
+        self.sol_spent / self.tokens_received
+    }
+}
+
+/// What a sell actually returned
+#[derive(Debug, Clone, PartialEq)]
+pub struct Proceeds {
+    /// SOL that arrived in the wallet, net of fees and tips, excluding refunded account rent
+    pub sol_received: f64,
+    /// Tokens that left, in UI units (decimals applied)
+    pub tokens_sold: f64,
+}
+
+impl Proceeds {
+    /// Exit price in SOL per token
+    pub fn price(&self) -> f64 {
+        self.sol_received / self.tokens_sold
+    }
+}
+
+impl PumpPortalClient {
+    /// Work out the real entry price of a buy from its on-chain transaction
+    ///
+    /// Fetches `signature` from `rpc_url` (retrying every 500ms until `timeout`
+    /// while the node hasn't indexed it yet) and compares the fee payer's SOL
+    /// and `mint` balances before and after.
+    ///
+    /// # Errors
+    ///
+    /// - `PumpPortalError::InvalidParameter` for dry-run signatures, which
+    ///   never land on-chain
+    /// - `PumpPortalError::ApiError` if the transaction failed, moved no
+    ///   tokens of `mint`, or couldn't be fetched before the timeout
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use pump_portal_sdk::PumpPortalClient;
+    /// # use std::time::Duration;
+    /// # async fn example(client: PumpPortalClient, signature: String) -> Result<(), Box<dyn std::error::Error>> {
+    /// let rpc_url = "https://api.mainnet-beta.solana.com";
+    /// let fill = client
+    ///     .fill(&signature, "TokenMintAddress", rpc_url, Duration::from_secs(30))
+    ///     .await?;
+    /// println!("Paid {:.4} SOL for {:.0} tokens ({:.10} SOL each)",
+    ///          fill.sol_spent, fill.tokens_received, fill.price());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fill(
+        &self,
+        signature: &str,
+        mint: &str,
+        rpc_url: &str,
+        timeout: Duration,
+    ) -> Result<Fill> {
+        if self.is_dry_run() || signature.starts_with(DRY_RUN_SIGNATURE_PREFIX) {
+            return Err(PumpPortalError::InvalidParameter(
+                "dry-run trades have no on-chain fill".to_string(),
+            ));
+        }
+
+        let tx = self.get_transaction(signature, rpc_url, timeout).await?;
+        parse_fill(&tx, mint)
+    }
+
+    /// Work out what a sell really returned from its on-chain transaction
+    ///
+    /// The sell-side counterpart of [`fill`](Self::fill), with the same
+    /// polling and errors (a transaction that sold no `mint` is an error).
+    pub async fn proceeds(
+        &self,
+        signature: &str,
+        mint: &str,
+        rpc_url: &str,
+        timeout: Duration,
+    ) -> Result<Proceeds> {
+        if self.is_dry_run() || signature.starts_with(DRY_RUN_SIGNATURE_PREFIX) {
+            return Err(PumpPortalError::InvalidParameter(
+                "dry-run trades have no on-chain fill".to_string(),
+            ));
+        }
+
+        let tx = self.get_transaction(signature, rpc_url, timeout).await?;
+        parse_proceeds(&tx, mint)
+    }
+
+    /// Find which pool a confirmed trade went through
+    ///
+    /// Useful with `Pool::Auto`, when the API response doesn't say where the
+    /// trade was routed. Looks for a known pool program among the programs the
+    /// transaction invoked; None if there is none (or it was a dry run).
+    pub async fn pool_used(
+        &self,
+        signature: &str,
+        rpc_url: &str,
+        timeout: Duration,
+    ) -> Result<Option<Pool>> {
+        if self.is_dry_run() || signature.starts_with(DRY_RUN_SIGNATURE_PREFIX) {
+            return Ok(None);
+        }
+        let tx = self.get_transaction(signature, rpc_url, timeout).await?;
+        Ok(parse_pool(&tx))
+    }
+
+    /// Fetch a jsonParsed transaction, polling while the node hasn't indexed it yet
+    async fn get_transaction(&self, signature: &str, rpc_url: &str, timeout: Duration) -> Result<Value> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getTransaction",
+            "params": [signature, {
+                "encoding": "jsonParsed",
+                "commitment": "confirmed",
+                "maxSupportedTransactionVersion": 0,
+            }],
+        });
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.http().post(rpc_url).timeout(self.timeout()).json(&body).send().await {
+                Ok(response) => match response.json::<Value>().await {
+                    Ok(mut value) => {
+                        if let Some(error) = value.get("error") {
+                            return Err(PumpPortalError::ApiError(format!("RPC error: {}", error)));
+                        }
+                        match value.get_mut("result").map(Value::take) {
+                            Some(tx) if !tx.is_null() => return Ok(tx),
+                            _ => {}
+                        }
+                    }
+                    Err(e) => log::debug!("Unreadable RPC response for {}: {}", signature, e),
+                },
+                Err(e) => log::debug!("RPC request failed for {}: {}", signature, e),
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(PumpPortalError::ApiError(format!(
+                    "transaction {} not found after {:?}",
+                    signature, timeout
+                )));
+            }
+            tokio::time::sleep(CONFIRM_POLL_INTERVAL.min(deadline - now)).await;
+        }
+    }
+}
+
+/// First known pool program invoked by a jsonParsed transaction, top-level or inner
+fn parse_pool(tx: &Value) -> Option<Pool> {
+    let top_level = tx
+        .pointer("/transaction/message/instructions")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten();
+    let inner = tx
+        .pointer("/meta/innerInstructions")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|group| group.get("instructions").and_then(Value::as_array))
+        .flatten();
+
+    top_level
+        .chain(inner)
+        .filter_map(|ix| ix.get("programId").and_then(Value::as_str))
+        .find_map(Pool::from_program_id)
+}
+
+/// The fee payer's lamports and `mint` balances before and after a successful transaction
+struct BalanceChange {
+    pre: u64,
+    post: u64,
+    pre_tokens: Option<f64>,
+    post_tokens: Option<f64>,
+}
+
+impl BalanceChange {
+    fn read(tx: &Value, mint: &str) -> Result<Self> {
+        let meta = tx
+            .get("meta")
+            .ok_or_else(|| PumpPortalError::ApiError("transaction has no meta".to_string()))?;
+        if let Some(err) = meta.get("err").filter(|err| !err.is_null()) {
+            return Err(PumpPortalError::ApiError(format!("transaction failed: {}", err)));
+        }
+
+        let payer = tx
+            .pointer("/transaction/message/accountKeys/0/pubkey")
+            .and_then(Value::as_str)
+            .ok_or_else(|| PumpPortalError::ApiError("transaction has no fee payer".to_string()))?;
+
+        let lamports = |key: &str| meta.pointer(&format!("/{}/0", key)).and_then(Value::as_u64);
+        let (pre, post) = match (lamports("preBalances"), lamports("postBalances")) {
+            (Some(pre), Some(post)) => (pre, post),
+            _ => return Err(PumpPortalError::ApiError("transaction has no balances".to_string())),
+        };
+
+        Ok(Self {
+            pre,
+            post,
+            pre_tokens: token_balance(meta.get("preTokenBalances"), mint, payer),
+            post_tokens: token_balance(meta.get("postTokenBalances"), mint, payer),
+        })
+    }
+}
+
+/// Read the fee payer's SOL outflow and `mint` inflow from a jsonParsed transaction
+fn parse_fill(tx: &Value, mint: &str) -> Result<Fill> {
+    let change = BalanceChange::read(tx, mint)?;
+    let tokens_received = change.post_tokens.unwrap_or(0.0) - change.pre_tokens.unwrap_or(0.0);
+    if tokens_received <= 0.0 {
+        return Err(PumpPortalError::ApiError(format!(
+            "transaction received no {} tokens",
+            mint
+        )));
+    }
+
+    // A token account that didn't exist before was created by this buy
+    let rent = if change.pre_tokens.is_none() {
+        TOKEN_ACCOUNT_RENT_LAMPORTS
+    } else {
+        0
+    };
+    let spent = change.pre.saturating_sub(change.post).saturating_sub(rent);
+
+    Ok(Fill {
+        sol_spent: spent as f64 / LAMPORTS_PER_SOL,
+        tokens_received,
+    })
+}
+
+/// Read the fee payer's SOL inflow and `mint` outflow from a jsonParsed transaction
+fn parse_proceeds(tx: &Value, mint: &str) -> Result<Proceeds> {
+    let change = BalanceChange::read(tx, mint)?;
+    let tokens_sold = change.pre_tokens.unwrap_or(0.0) - change.post_tokens.unwrap_or(0.0);
+    if tokens_sold <= 0.0 {
+        return Err(PumpPortalError::ApiError(format!(
+            "transaction sold no {} tokens",
+            mint
+        )));
+    }
+
+    // A token account that's gone afterwards was closed by this sell
+    let rent = if change.post_tokens.is_none() {
+        TOKEN_ACCOUNT_RENT_LAMPORTS
+    } else {
+        0
+    };
+    let received = change.post.saturating_sub(change.pre).saturating_sub(rent);
+
+    Ok(Proceeds {
+        sol_received: received as f64 / LAMPORTS_PER_SOL,
+        tokens_sold,
+    })
+}
+
+/// Sum of `owner`'s balances of `mint`, None if it held no account for it
+fn token_balance(balances: Option<&Value>, mint: &str, owner: &str) -> Option<f64> {
+    balances?
+        .as_array()?
+        .iter()
+        .filter(|b| {
+            b.get("mint").and_then(Value::as_str) == Some(mint)
+                && b.get("owner").and_then(Value::as_str) == Some(owner)
+        })
+        .map(|b| {
+            b.pointer("/uiTokenAmount/uiAmountString")
+                .and_then(Value::as_str)
+                .and_then(|amount| amount.parse::<f64>().ok())
+                .unwrap_or(0.0)
+        })
+        .fold(None, |total, amount| Some(total.unwrap_or(0.0) + amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAYER: &str = "Payer111111111111111111111111111111111111111";
+    const MINT: &str = "Mint1111111111111111111111111111111111111111";
+
+    fn token_entry(owner: &str, amount: &str) -> Value {
+        json!({
+            "accountIndex": 3,
+            "mint": MINT,
+            "owner": owner,
+            "uiTokenAmount": { "uiAmountString": amount, "decimals": 6 },
+        })
+    }
+
+    fn buy_tx(pre_tokens: Vec<Value>, post_tokens: Vec<Value>, pre: u64, post: u64) -> Value {
+        json!({
+            "transaction": { "message": { "accountKeys": [
+                { "pubkey": PAYER, "signer": true, "writable": true },
+            ]}},
+            "meta": {
+                "err": null,
+                "fee": 5000,
+                "preBalances": [pre],
+                "postBalances": [post],
+                "preTokenBalances": pre_tokens,
+                "postTokenBalances": post_tokens,
+            },
+        })
+    }
+
+    #[test]
+    fn test_parse_fill_first_buy_excludes_rent() {
+        // 0.1 SOL + rent + fee left the wallet for 3.5M tokens
+        let spent = 100_000_000 + TOKEN_ACCOUNT_RENT_LAMPORTS + 5000;
+        let tx = buy_tx(
+            vec![],
+            vec![token_entry(PAYER, "3500000")],
+            1_000_000_000,
+            1_000_000_000 - spent,
+        );
+
+        let fill = parse_fill(&tx, MINT).unwrap();
+        assert!((fill.sol_spent - 0.100005).abs() < 1e-12);
+        assert_eq!(fill.tokens_received, 3_500_000.0);
+        assert!((fill.price() - 0.100005 / 3_500_000.0).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_parse_fill_add_to_existing_position() {
+        let tx = buy_tx(
+            vec![token_entry(PAYER, "1000"), token_entry("SomeoneElse", "50")],
+            vec![token_entry(PAYER, "1500"), token_entry("SomeoneElse", "10")],
+            500_000_000,
+            450_000_000,
+        );
+
+        let fill = parse_fill(&tx, MINT).unwrap();
+        assert!((fill.sol_spent - 0.05).abs() < 1e-12);
+        assert_eq!(fill.tokens_received, 500.0);
+    }
+
+    #[test]
+    fn test_parse_fill_rejects_failed_or_empty() {
+        let mut failed = buy_tx(vec![], vec![token_entry(PAYER, "10")], 10, 5);
+        failed["meta"]["err"] = json!({ "InstructionError": [2, { "Custom": 6002 }] });
+        assert!(matches!(
+            parse_fill(&failed, MINT),
+            Err(PumpPortalError::ApiError(msg)) if msg.contains("6002")
+        ));
+
+        let nothing = buy_tx(vec![], vec![], 10, 5);
+        assert!(matches!(parse_fill(&nothing, MINT), Err(PumpPortalError::ApiError(_))));
+    }
+
+    #[test]
+    fn test_parse_proceeds_partial_and_closing_sells() {
+        // Half the bag for 0.06 SOL, fee already taken
+        let half = buy_tx(
+            vec![token_entry(PAYER, "3000")],
+            vec![token_entry(PAYER, "1500")],
+            500_000_000,
+            560_000_000,
+        );
+        let proceeds = parse_proceeds(&half, MINT).unwrap();
+        assert!((proceeds.sol_received - 0.06).abs() < 1e-12);
+        assert_eq!(proceeds.tokens_sold, 1500.0);
+
+        // Selling the rest closes the account and refunds its rent
+        let rest = buy_tx(
+            vec![token_entry(PAYER, "1500")],
+            vec![],
+            560_000_000,
+            560_000_000 + 40_000_000 + TOKEN_ACCOUNT_RENT_LAMPORTS,
+        );
+        let proceeds = parse_proceeds(&rest, MINT).unwrap();
+        assert!((proceeds.sol_received - 0.04).abs() < 1e-12);
+        assert_eq!(proceeds.tokens_sold, 1500.0);
+
+        // A buy isn't a sell
+        let buy = buy_tx(vec![], vec![token_entry(PAYER, "10")], 10, 5);
+        assert!(matches!(parse_proceeds(&buy, MINT), Err(PumpPortalError::ApiError(_))));
+    }
+
+    #[test]
+    fn test_parse_pool_from_inner_instructions() {
+        // Routed through a router program that CPIs into Pump AMM
+        let tx = json!({
+            "transaction": { "message": { "instructions": [
+                { "programId": "ComputeBudget111111111111111111111111111111" },
+                { "programId": "Router1111111111111111111111111111111111111" },
+            ]}},
+            "meta": { "innerInstructions": [{
+                "index": 1,
+                "instructions": [
+                    { "programId": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA" },
+                    { "programId": "pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA" },
+                ],
+            }]},
+        });
+        assert_eq!(parse_pool(&tx), Some(Pool::PumpAmm));
+        assert_eq!(parse_pool(&json!({})), None);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_has_no_fill() {
+        let client = PumpPortalClient::new("test-key".to_string()).dry_run(true);
+        let result = client
+            .fill("PAPER_123", MINT, "http://127.0.0.1:1", Duration::from_secs(1))
+            .await;
+        assert!(matches!(result, Err(PumpPortalError::InvalidParameter(_))));
+    }
+}
@@ -0,0 +1,81 @@
+//! Buy/sell pressure on a token from its live trades
+//!
+//! [`TradeFlow`] keeps each side's SOL volume over a rolling window, so a bot
+//! subscribed to a token's trades can fill [`PositionState::momentum`]
+//! without a momentum model of its own.
+//!
+//! [`PositionState::momentum`]: crate::PositionState
+
+use crate::events::TradeSide;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Recent trades on one token, by side and SOL size
+#[derive(Debug, Clone)]
+pub struct TradeFlow {
+    window: Duration,
+    trades: VecDeque<(Instant, TradeSide, f64)>,
+}
+
+impl TradeFlow {
+    /// Score momentum over the trades of the last `window`
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            trades: VecDeque::new(),
+        }
+    }
+
+    /// Count a trade of `sol` on `side` seen at `at`. Zero and non-finite sizes are ignored.
+    pub fn record(&mut self, side: TradeSide, sol: f64, at: Instant) {
+        if !(sol.is_finite() && sol > 0.0) {
+            return;
+        }
+        self.trades.push_back((at, side, sol));
+        while let Some(&(oldest, _, _)) = self.trades.front() {
+            if at.saturating_duration_since(oldest) <= self.window {
+                break;
+            }
+            self.trades.pop_front();
+        }
+    }
+
+    /// Share of the window's SOL volume that bought: 1.0 all buys, 0.0 all
+    /// sells, 0.5 (neutral) when nothing traded
+    pub fn momentum(&self, now: Instant) -> f64 {
+        let (buys, total) = self
+            .trades
+            .iter()
+            .filter(|(at, _, _)| now.saturating_duration_since(*at) <= self.window)
+            .fold((0.0, 0.0), |(buys, total), &(_, side, sol)| {
+                let bought = if side == TradeSide::Buy { sol } else { 0.0 };
+                (buys + bought, total + sol)
+            });
+        if total > 0.0 {
+            buys / total
+        } else {
+            0.5
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_momentum_is_buy_share_of_recent_volume() {
+        let start = Instant::now();
+        let mut flow = TradeFlow::new(Duration::from_secs(60));
+        assert_eq!(flow.momentum(start), 0.5);
+
+        flow.record(TradeSide::Buy, 3.0, start);
+        flow.record(TradeSide::Sell, 1.0, start + Duration::from_secs(10));
+        flow.record(TradeSide::Sell, f64::NAN, start + Duration::from_secs(10));
+        assert_eq!(flow.momentum(start + Duration::from_secs(10)), 0.75);
+
+        // The buy ages out of the window, leaving only selling
+        assert_eq!(flow.momentum(start + Duration::from_secs(65)), 0.0);
+        assert_eq!(flow.momentum(start + Duration::from_secs(200)), 0.5);
+    }
+}
@@ -0,0 +1,185 @@
+//! Launch-to-fill latency of snipes
+//!
+//! RPC round trips (`benchmark-rpcs`) are only one part of how fast a snipe
+//! lands. [`SnipeTimer`] starts when a launch arrives and marks each stage:
+//!
+//! - **decide**: launch received to the buy passing every filter
+//! - **submit**: trade request sent to a signature back
+//! - **confirm**: signature to the buy confirmed on-chain
+//!
+//! [`LatencyReport`] gives percentiles per stage across many launches, so
+//! settings like Jito routing or the priority fee can be compared by label.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Times one snipe from the moment its launch was received
+#[derive(Debug, Clone, Copy)]
+pub struct SnipeTimer {
+    received: Instant,
+    decided: Option<Instant>,
+    submitted: Option<Instant>,
+}
+
+impl SnipeTimer {
+    /// Start timing a launch received now
+    pub fn start() -> Self {
+        Self::started_at(Instant::now())
+    }
+
+    pub fn started_at(received: Instant) -> Self {
+        Self {
+            received,
+            decided: None,
+            submitted: None,
+        }
+    }
+
+    /// The buy passed every filter and is about to be sent
+    pub fn decided(&mut self) {
+        self.decided = Some(Instant::now());
+    }
+
+    /// The trade request came back with a signature
+    pub fn submitted(&mut self) {
+        self.submitted = Some(Instant::now());
+    }
+
+    /// The buy confirmed now; stages never marked count as taking no time
+    pub fn confirmed(&self) -> SnipeLatency {
+        self.confirmed_at(Instant::now())
+    }
+
+    fn confirmed_at(&self, confirmed: Instant) -> SnipeLatency {
+        let decided = self.decided.unwrap_or(self.received);
+        let submitted = self.submitted.unwrap_or(decided);
+        SnipeLatency {
+            decide: decided.saturating_duration_since(self.received),
+            submit: submitted.saturating_duration_since(decided),
+            confirm: confirmed.saturating_duration_since(submitted),
+        }
+    }
+}
+
+/// Time one snipe spent in each stage
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SnipeLatency {
+    pub decide: Duration,
+    pub submit: Duration,
+    pub confirm: Duration,
+}
+
+impl SnipeLatency {
+    /// Launch received to buy confirmed
+    pub fn total(&self) -> Duration {
+        self.decide + self.submit + self.confirm
+    }
+}
+
+/// Percentiles of one stage, nearest rank
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Percentiles {
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+impl Percentiles {
+    /// None for no samples
+    pub fn of(mut samples: Vec<Duration>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort();
+        let rank = |p: f64| samples[((p * samples.len() as f64).ceil() as usize).clamp(1, samples.len()) - 1];
+        Some(Self {
+            p50: rank(0.50),
+            p90: rank(0.90),
+            p99: rank(0.99),
+            max: samples[samples.len() - 1],
+        })
+    }
+}
+
+impl fmt::Display for Percentiles {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "p50 {}ms | p90 {}ms | p99 {}ms | max {}ms",
+            self.p50.as_millis(),
+            self.p90.as_millis(),
+            self.p99.as_millis(),
+            self.max.as_millis()
+        )
+    }
+}
+
+/// Stage percentiles across a set of snipes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatencyReport {
+    pub snipes: usize,
+    pub decide: Percentiles,
+    pub submit: Percentiles,
+    pub confirm: Percentiles,
+    pub total: Percentiles,
+}
+
+impl LatencyReport {
+    /// None for no snipes
+    pub fn from_samples(samples: &[SnipeLatency]) -> Option<Self> {
+        let stage = |f: fn(&SnipeLatency) -> Duration| Percentiles::of(samples.iter().map(f).collect());
+        Some(Self {
+            snipes: samples.len(),
+            decide: stage(|s| s.decide)?,
+            submit: stage(|s| s.submit)?,
+            confirm: stage(|s| s.confirm)?,
+            total: stage(|s| s.total())?,
+        })
+    }
+
+    /// Log one line per stage, under `label`
+    pub fn log(&self, label: &str) {
+        log::info!("⏱️  Snipe latency [{}] over {} snipes:", label, self.snipes);
+        log::info!("   Decide:  {}", self.decide);
+        log::info!("   Submit:  {}", self.submit);
+        log::info!("   Confirm: {}", self.confirm);
+        log::info!("   Total:   {}", self.total);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_timer_splits_stages() {
+        let received = Instant::now();
+        let mut timer = SnipeTimer::started_at(received);
+        timer.decided = Some(received + ms(5));
+        timer.submitted = Some(received + ms(125));
+
+        let latency = timer.confirmed_at(received + ms(925));
+        assert_eq!(latency, SnipeLatency { decide: ms(5), submit: ms(120), confirm: ms(800) });
+        assert_eq!(latency.total(), ms(925));
+
+        // Unmarked stages take no time rather than swallowing the next one
+        let latency = SnipeTimer::started_at(received).confirmed_at(received + ms(50));
+        assert_eq!(latency, SnipeLatency { confirm: ms(50), ..SnipeLatency::default() });
+    }
+
+    #[test]
+    fn test_percentiles_nearest_rank() {
+        let p = Percentiles::of((1..=100).rev().map(ms).collect()).unwrap();
+        assert_eq!(p, Percentiles { p50: ms(50), p90: ms(90), p99: ms(99), max: ms(100) });
+
+        let one = Percentiles::of(vec![ms(7)]).unwrap();
+        assert_eq!((one.p50, one.p99), (ms(7), ms(7)));
+        assert_eq!(Percentiles::of(Vec::new()), None);
+        assert!(LatencyReport::from_samples(&[]).is_none());
+    }
+}
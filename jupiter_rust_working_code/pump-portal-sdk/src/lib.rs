@@ -3,13 +3,67 @@
 //! A Rust client for interacting with the PumpPortal Lightning Transaction API.
 //! This SDK provides a simple interface for executing buy and sell trades on Solana.
 
+use std::time::Duration;
 use thiserror::Error;
 
 pub mod types;
+pub mod balance;
+pub mod budget;
+#[cfg(feature = "stream")]
+pub mod capture;
 pub mod client;
+pub mod compute_budget;
+#[cfg(feature = "config")]
+pub mod config;
+pub mod confirm;
+pub mod cooldown;
+pub mod dedup;
+pub mod events;
+pub mod exit;
+#[cfg(feature = "paper")]
+pub mod export;
+pub mod fill;
+pub mod flow;
+pub mod latency;
+#[cfg(feature = "logging")]
+pub mod logging;
+pub mod metadata;
+pub mod notify;
+#[cfg(feature = "paper")]
+pub mod paper;
+pub mod pnl;
+pub mod pool;
+pub mod price;
+pub mod quote;
+pub mod rate_limit;
+pub mod slippage;
+#[cfg(feature = "stream")]
+pub mod stream;
 
 pub use types::*;
+pub use balance::BalanceCheck;
+pub use budget::{SolBudget, SolReservation};
 pub use client::PumpPortalClient;
+pub use confirm::ConfirmationStatus;
+pub use cooldown::{LossStreak, MintCooldown};
+pub use dedup::BuyGuard;
+pub use events::{MigrationEvent, NewTokenEvent, PumpPortalEvent, TokenTradeEvent, TradeSide};
+pub use exit::{ExitDecision, ExitReason, ExitStrategy, PositionState};
+pub use fill::{Fill, Proceeds};
+pub use flow::TradeFlow;
+pub use latency::{LatencyReport, SnipeLatency, SnipeTimer};
+pub use metadata::{fetch_token_metadata, TokenMetadata};
+pub use notify::{Notification, Notifier, NotifyEvent, WebhookNotifier};
+#[cfg(feature = "paper")]
+pub use paper::PaperTradingSimulator;
+pub use pnl::PnlTracker;
+pub use pool::PumpPortalClientPool;
+pub use price::{sol_usd, SolPrice};
+pub use quote::Quote;
+pub use rate_limit::RateLimiter;
+pub use slippage::AdaptiveSlippage;
+#[cfg(feature = "stream")]
+pub use stream::{PumpPortalStream, StreamConfig, StreamHandle, Subscription};
 
 /// Result type for PumpPortal SDK operations
 pub type Result<T> = std::result::Result<T, PumpPortalError>;
@@ -21,10 +75,35 @@ pub enum PumpPortalError {
     #[error("HTTP request failed: {0}")]
     RequestFailed(#[from] reqwest::Error),
 
-    /// API returned an error
+    /// API returned an error not covered by a more specific variant
     #[error("API error: {0}")]
     ApiError(String),
 
+    /// API rejected the request for exceeding its rate limit (HTTP 429)
+    #[error("Rate limited by API (retry after {retry_after:?})")]
+    RateLimited { retry_after: Option<Duration> },
+
+    /// Wallet doesn't hold enough SOL or tokens for the trade
+    #[error("Insufficient funds for trade")]
+    InsufficientFunds,
+
+    /// API failed on its side (HTTP 5xx)
+    #[error("Server error: HTTP {0}")]
+    ServerError(u16),
+
+    /// Price moved past the request's slippage tolerance before the trade executed
+    #[error("Slippage exceeded: {0}")]
+    SlippageExceeded(String),
+
+    /// Buy would move the bonding-curve price more than the request allows
+    #[error("Price impact {impact_bps} bps exceeds max {max_bps} bps")]
+    PriceImpactTooHigh { impact_bps: u32, max_bps: u32 },
+
+    /// An identical buy failed ambiguously within the dedup window and may have
+    /// landed, so it wasn't resent (see [`PumpPortalClient::with_buy_dedup`])
+    #[error("Buy of {mint} may already have gone through ({reason}), not resending")]
+    DuplicateBuy { mint: String, reason: String },
+
     /// Invalid parameter provided
     #[error("Invalid parameter: {0}")]
     InvalidParameter(String),
@@ -32,4 +111,116 @@ pub enum PumpPortalError {
     /// Serialization/deserialization error
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
+
+    /// WebSocket connection failed
+    #[error("WebSocket error: {0}")]
+    WebSocket(String),
+
+    /// Capture or replay file couldn't be opened
+    #[error("Capture file error: {0}")]
+    Capture(String),
+
+    /// Request did not complete within the configured timeout
+    #[error("Request timed out: {0}")]
+    Timeout(reqwest::Error),
+}
+
+impl PumpPortalError {
+    /// Whether retrying the same request may succeed
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            PumpPortalError::Timeout(_)
+                | PumpPortalError::RateLimited { .. }
+                | PumpPortalError::ServerError(_)
+        )
+    }
+
+    /// How long the API asked us to wait before retrying, if it said
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            PumpPortalError::RateLimited { retry_after } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// Classify a failed API response from its status, `Retry-After` header and error message
+    pub(crate) fn from_response(status: u16, retry_after: Option<&str>, message: String) -> Self {
+        let lower = message.to_lowercase();
+
+        if status == 429 || lower.contains("rate limit") || lower.contains("too many requests") {
+            PumpPortalError::RateLimited {
+                retry_after: retry_after
+                    .and_then(|v| v.trim().parse::<f64>().ok())
+                    .filter(|secs| secs.is_finite() && *secs >= 0.0)
+                    .map(Duration::from_secs_f64),
+            }
+        } else if lower.contains("insufficient") {
+            PumpPortalError::InsufficientFunds
+        } else if is_slippage_message(&lower) {
+            PumpPortalError::SlippageExceeded(message)
+        } else if (500..600).contains(&status) {
+            PumpPortalError::ServerError(status)
+        } else {
+            PumpPortalError::ApiError(message)
+        }
+    }
+
+    /// Map a reqwest error, separating out elapsed timeouts
+    pub(crate) fn from_request(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            PumpPortalError::Timeout(err)
+        } else {
+            PumpPortalError::RequestFailed(err)
+        }
+    }
+}
+
+/// Whether a lowercased API error says the price moved past the slippage limit
+///
+/// Covers the pump.fun program's `TooMuchSolRequired` / `TooLittleSolReceived`
+/// (custom errors 6002/6003) as well as a plain "slippage" message.
+fn is_slippage_message(lower: &str) -> bool {
+    ["slippage", "toomuchsolrequired", "toolittlesolreceived", "0x1772", "0x1773"]
+        .iter()
+        .any(|needle| lower.contains(needle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_response_classification() {
+        let err = PumpPortalError::from_response(429, Some("2"), "Too Many Requests".to_string());
+        assert!(matches!(err, PumpPortalError::RateLimited { .. }));
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(2)));
+        assert!(err.is_retryable());
+
+        let err = PumpPortalError::from_response(400, None, "Rate limit exceeded".to_string());
+        assert!(matches!(err, PumpPortalError::RateLimited { retry_after: None }));
+
+        let err = PumpPortalError::from_response(
+            400,
+            None,
+            "Transfer: insufficient lamports 1000, need 2000".to_string(),
+        );
+        assert!(matches!(err, PumpPortalError::InsufficientFunds));
+        assert!(!err.is_retryable());
+
+        let err = PumpPortalError::from_response(503, None, "HTTP 503: upstream".to_string());
+        assert!(matches!(err, PumpPortalError::ServerError(503)));
+        assert!(err.is_retryable());
+
+        let err = PumpPortalError::from_response(
+            400,
+            None,
+            "Simulation failed: custom program error: 0x1772 (TooMuchSolRequired)".to_string(),
+        );
+        assert!(matches!(err, PumpPortalError::SlippageExceeded(_)));
+        assert!(!err.is_retryable());
+
+        let err = PumpPortalError::from_response(400, None, "Invalid mint".to_string());
+        assert!(matches!(err, PumpPortalError::ApiError(msg) if msg == "Invalid mint"));
+    }
 }
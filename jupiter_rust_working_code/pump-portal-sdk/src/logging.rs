@@ -0,0 +1,50 @@
+//! Log setup shared by the bots (`logging` feature)
+//!
+//! By default every record is written as one JSON line, so logs can be
+//! shipped to Loki/Elastic and queried by field. Build with the `pretty-logs`
+//! feature for the human-readable output used when running interactively.
+//! `log` records from the bots and this SDK are forwarded either way.
+//!
+//! Trade events carry an `event` field plus whichever of the fields below
+//! apply, so one query finds every buy across all bots:
+//!
+//! | field       | meaning                                        |
+//! |-------------|------------------------------------------------|
+//! | `event`     | `buy`, `sell`, `buy_failed`, `sell_failed`, `skip` |
+//! | `mint`      | token mint                                     |
+//! | `sol`       | SOL spent (buys)                               |
+//! | `percent`   | share of the position sold (sells)             |
+//! | `slippage`  | slippage tolerance in percent                  |
+//! | `signature` | transaction signature                          |
+//! | `reason`    | why a sell or skip happened                    |
+//!
+//! ```no_run
+//! pump_portal_sdk::logging::init();
+//! tracing::info!(event = "buy", mint = "TokenMint", sol = 0.05, slippage = 10,
+//!                signature = "5sig", "✅ BUY EXECUTED");
+//! ```
+
+use tracing_subscriber::EnvFilter;
+
+/// Install the global subscriber. Filters with `RUST_LOG`, defaulting to `info`.
+///
+/// # Panics
+///
+/// If a global logger or subscriber is already installed.
+pub fn init() {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+
+    #[cfg(feature = "pretty-logs")]
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_target(false)
+        .init();
+
+    #[cfg(not(feature = "pretty-logs"))]
+    tracing_subscriber::fmt()
+        .json()
+        .flatten_event(true)
+        .with_current_span(false)
+        .with_env_filter(filter)
+        .init();
+}
@@ -0,0 +1,186 @@
+//! Off-chain token metadata behind a launch's `uri`
+//!
+//! Create events carry only the name and symbol. The description, image and
+//! socials live in a JSON file the event's `uri` points to, usually on IPFS.
+//! [`fetch_token_metadata`] resolves the URI through a gateway when needed
+//! and parses the standard pump.fun metadata format.
+
+use crate::{PumpPortalError, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Public gateway `ipfs://` URIs are fetched through
+pub const DEFAULT_IPFS_GATEWAY: &str = "https://ipfs.io/ipfs/";
+
+/// pump.fun-style token metadata JSON
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenMetadata {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub symbol: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Image URI, often `ipfs://` (see [`image_url`](Self::image_url))
+    #[serde(default)]
+    pub image: Option<String>,
+    #[serde(default)]
+    pub twitter: Option<String>,
+    #[serde(default)]
+    pub telegram: Option<String>,
+    #[serde(default)]
+    pub website: Option<String>,
+    /// Some launchpads nest socials here instead
+    #[serde(default)]
+    pub extensions: HashMap<String, Value>,
+}
+
+impl TokenMetadata {
+    /// Every non-empty link the creator gave: `twitter`, `telegram`, `website`,
+    /// then string values under `extensions`. Not checked for being real socials.
+    pub fn social_links(&self) -> Vec<&str> {
+        let top_level = [&self.twitter, &self.telegram, &self.website]
+            .into_iter()
+            .filter_map(|link| link.as_deref());
+        let nested = self.extensions.values().filter_map(Value::as_str);
+
+        top_level
+            .chain(nested)
+            .map(str::trim)
+            .filter(|link| !link.is_empty())
+            .collect()
+    }
+
+    /// Non-empty description
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref().map(str::trim).filter(|d| !d.is_empty())
+    }
+
+    /// Image as an http(s) URL, via the default gateway for `ipfs://` images
+    pub fn image_url(&self) -> Option<String> {
+        self.image
+            .as_deref()
+            .map(str::trim)
+            .filter(|image| !image.is_empty())
+            .map(|image| gateway_url(image, DEFAULT_IPFS_GATEWAY))
+    }
+}
+
+/// An http(s) URL for `uri`: `ipfs://<cid>` and bare CIDs go through
+/// `gateway`, anything else is returned as is
+pub fn gateway_url(uri: &str, gateway: &str) -> String {
+    let uri = uri.trim();
+    let gateway = gateway.trim_end_matches('/');
+
+    if let Some(path) = uri.strip_prefix("ipfs://") {
+        let path = path.strip_prefix("ipfs/").unwrap_or(path);
+        return format!("{}/{}", gateway, path);
+    }
+    let is_cid = (uri.starts_with("Qm") && uri.len() == 46) || uri.starts_with("bafy");
+    if is_cid && uri.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return format!("{}/{}", gateway, uri);
+    }
+    uri.to_string()
+}
+
+/// Fetch and parse the metadata behind a launch's `uri`
+///
+/// Uses a shared HTTP client with the SDK's default timeout and
+/// [`DEFAULT_IPFS_GATEWAY`]; see [`fetch_token_metadata_via`] to choose both.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn example() -> pump_portal_sdk::Result<()> {
+/// let metadata = pump_portal_sdk::fetch_token_metadata("ipfs://QmTestMetadataHash").await?;
+/// println!("{}: {} socials", metadata.name, metadata.social_links().len());
+/// # Ok(())
+/// # }
+/// ```
+pub async fn fetch_token_metadata(uri: &str) -> Result<TokenMetadata> {
+    static HTTP: OnceLock<Client> = OnceLock::new();
+    let client = HTTP.get_or_init(|| {
+        Client::builder()
+            .timeout(crate::client::DEFAULT_TIMEOUT)
+            .build()
+            .unwrap_or_default()
+    });
+    fetch_token_metadata_via(client, uri, DEFAULT_IPFS_GATEWAY).await
+}
+
+/// [`fetch_token_metadata`] with your own HTTP client and IPFS gateway
+///
+/// # Errors
+///
+/// - `PumpPortalError::InvalidParameter` for an empty URI
+/// - `PumpPortalError::ApiError` if the host answers with an error status
+/// - `PumpPortalError::SerializationError` if the body isn't metadata JSON
+pub async fn fetch_token_metadata_via(client: &Client, uri: &str, gateway: &str) -> Result<TokenMetadata> {
+    if uri.trim().is_empty() {
+        return Err(PumpPortalError::InvalidParameter("empty metadata URI".to_string()));
+    }
+
+    let url = gateway_url(uri, gateway);
+    let response = client.get(&url).send().await.map_err(PumpPortalError::from_request)?;
+    let status = response.status();
+    let body = response.text().await.map_err(PumpPortalError::from_request)?;
+    if !status.is_success() {
+        return Err(PumpPortalError::ApiError(format!("HTTP {} fetching metadata from {}", status, url)));
+    }
+    Ok(serde_json::from_str(&body)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gateway_url() {
+        let gateway = "https://gateway.example/ipfs/";
+        let cid = "QmTestMetadataHash1111111111111111111111111111";
+        assert_eq!(cid.len(), 46);
+
+        assert_eq!(gateway_url(&format!("ipfs://{}", cid), gateway), format!("https://gateway.example/ipfs/{}", cid));
+        assert_eq!(gateway_url(&format!("ipfs://ipfs/{}", cid), gateway), format!("https://gateway.example/ipfs/{}", cid));
+        assert_eq!(gateway_url(cid, gateway), format!("https://gateway.example/ipfs/{}", cid));
+
+        // Already fetchable, including other gateways
+        let http = "https://ipfs.io/ipfs/QmOther";
+        assert_eq!(gateway_url(http, gateway), http);
+        assert_eq!(gateway_url("https://arweave.net/abc", gateway), "https://arweave.net/abc");
+    }
+
+    #[test]
+    fn test_parse_pump_fun_metadata() {
+        let json = r#"{
+            "name": "Test Token",
+            "symbol": "TEST",
+            "description": "  ",
+            "image": "ipfs://QmImage",
+            "showName": true,
+            "createdOn": "https://pump.fun",
+            "twitter": "https://x.com/testtoken",
+            "website": "",
+            "extensions": { "telegram": "https://t.me/testtoken", "decimals": 6 }
+        }"#;
+        let metadata: TokenMetadata = serde_json::from_str(json).unwrap();
+
+        assert_eq!(metadata.name, "Test Token");
+        assert_eq!(metadata.description(), None);
+        assert_eq!(metadata.image_url().as_deref(), Some("https://ipfs.io/ipfs/QmImage"));
+        assert_eq!(metadata.social_links(), vec!["https://x.com/testtoken", "https://t.me/testtoken"]);
+
+        let bare: TokenMetadata = serde_json::from_str("{}").unwrap();
+        assert_eq!(bare, TokenMetadata::default());
+        assert!(bare.social_links().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_empty_uri_is_invalid() {
+        let result = fetch_token_metadata(" ").await;
+        assert!(matches!(result, Err(PumpPortalError::InvalidParameter(_))));
+    }
+}
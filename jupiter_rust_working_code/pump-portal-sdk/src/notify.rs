@@ -0,0 +1,336 @@
+//! Trade notifications
+//!
+//! Bots report buys, sells and rugs through a [`Notifier`] so someone can get
+//! a ping instead of watching logs. [`WebhookNotifier`] POSTs each event as
+//! JSON; [`NoopNotifier`] is what a bot uses when no webhook is configured.
+//!
+//! The payload carries the event fields plus the same one-line summary under
+//! both `content` and `text`, so it posts as-is to a Discord or Slack
+//! webhook, or to Telegram's `sendMessage?chat_id=...` URL:
+//!
+//! ```json
+//! {"event":"sell","mint":"...","sol":0.067,"pnl_percent":34.0,"signature":"...",
+//!  "reason":"take_profit","source":"ladder-sniper","content":"...","text":"..."}
+//! ```
+
+use crate::{ExitReason, PumpPortalError, Result};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Wait before the one retry of a failed webhook POST
+pub const WEBHOOK_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Kinds of trade event a notifier can report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyEvent {
+    Buy,
+    Sell,
+    Rug,
+}
+
+impl NotifyEvent {
+    pub const ALL: [NotifyEvent; 3] = [NotifyEvent::Buy, NotifyEvent::Sell, NotifyEvent::Rug];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotifyEvent::Buy => "buy",
+            NotifyEvent::Sell => "sell",
+            NotifyEvent::Rug => "rug",
+        }
+    }
+}
+
+impl fmt::Display for NotifyEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NotifyEvent {
+    type Err = PumpPortalError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "buy" => Ok(NotifyEvent::Buy),
+            "sell" => Ok(NotifyEvent::Sell),
+            "rug" => Ok(NotifyEvent::Rug),
+            other => Err(PumpPortalError::InvalidParameter(format!(
+                "unknown notify event '{}' (expected buy, sell or rug)",
+                other
+            ))),
+        }
+    }
+}
+
+/// One trade event to report
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Notification {
+    pub event: NotifyEvent,
+    pub mint: String,
+    /// SOL spent on a buy or received from a sell, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sol: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pnl_percent: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// Why a position was sold, or what gave a rug away
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl Notification {
+    fn new(event: NotifyEvent, mint: &str) -> Self {
+        Self {
+            event,
+            mint: mint.to_string(),
+            sol: None,
+            pnl_percent: None,
+            signature: None,
+            reason: None,
+        }
+    }
+
+    pub fn buy(mint: &str, sol: f64, signature: &str) -> Self {
+        Self {
+            sol: Some(sol),
+            signature: Some(signature.to_string()),
+            ..Self::new(NotifyEvent::Buy, mint)
+        }
+    }
+
+    /// A sell; add proceeds and P&L with [`with_sol`](Self::with_sol) and
+    /// [`with_pnl`](Self::with_pnl) once they're known
+    pub fn sell(mint: &str, signature: &str, reason: ExitReason) -> Self {
+        Self {
+            signature: Some(signature.to_string()),
+            reason: Some(reason.to_string()),
+            ..Self::new(NotifyEvent::Sell, mint)
+        }
+    }
+
+    pub fn rug(mint: &str, reason: &str) -> Self {
+        Self {
+            reason: Some(reason.to_string()),
+            ..Self::new(NotifyEvent::Rug, mint)
+        }
+    }
+
+    pub fn with_sol(mut self, sol: f64) -> Self {
+        self.sol = Some(sol);
+        self
+    }
+
+    pub fn with_pnl(mut self, percent: f64) -> Self {
+        self.pnl_percent = Some(percent);
+        self
+    }
+
+    /// One-line human-readable summary
+    pub fn summary(&self) -> String {
+        let mut line = format!("{} {}", self.event.as_str().to_uppercase(), self.mint);
+        if let Some(sol) = self.sol {
+            line.push_str(&format!(" | {:.4} SOL", sol));
+        }
+        if let Some(pnl) = self.pnl_percent {
+            line.push_str(&format!(" | {:+.1}%", pnl));
+        }
+        if let Some(reason) = &self.reason {
+            line.push_str(&format!(" | {}", reason));
+        }
+        line
+    }
+}
+
+/// Somewhere trade events get reported
+///
+/// `notify` is called from the trading path, so it must return right away
+/// and never fail the trade; deliver in the background.
+pub trait Notifier: Send + Sync {
+    fn notify(&self, notification: Notification);
+}
+
+/// Drops every notification
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopNotifier;
+
+impl Notifier for NoopNotifier {
+    fn notify(&self, _notification: Notification) {}
+}
+
+/// POSTs notifications as JSON to a webhook URL
+///
+/// Each POST runs on its own task and is retried once after
+/// [`WEBHOOK_RETRY_DELAY`], so a slow or flaky webhook never holds up a trade.
+///
+/// # Example
+///
+/// ```no_run
+/// use pump_portal_sdk::notify::{Notification, Notifier, NotifyEvent, WebhookNotifier};
+///
+/// # async fn example() {
+/// let notifier = WebhookNotifier::new("https://discord.com/api/webhooks/...")
+///     .with_source("simple-sniper")
+///     .with_events(&[NotifyEvent::Sell, NotifyEvent::Rug]);
+/// notifier.notify(Notification::rug("MintAddress", "dev sold"));
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WebhookNotifier {
+    client: Client,
+    url: String,
+    source: String,
+    events: Vec<NotifyEvent>,
+}
+
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    #[serde(flatten)]
+    notification: &'a Notification,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    source: &'a str,
+    content: &'a str,
+    text: &'a str,
+}
+
+impl WebhookNotifier {
+    /// Report every event to `url`
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(crate::client::DEFAULT_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            url: url.into(),
+            source: String::new(),
+            events: NotifyEvent::ALL.to_vec(),
+        }
+    }
+
+    /// Name the bot in each message, for several bots sharing a channel
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = source.into();
+        self
+    }
+
+    /// Only report these events
+    pub fn with_events(mut self, events: &[NotifyEvent]) -> Self {
+        self.events = events.to_vec();
+        self
+    }
+
+    /// Whether `event` is one this notifier reports
+    pub fn wants(&self, event: NotifyEvent) -> bool {
+        self.events.contains(&event)
+    }
+
+    /// POST `notification` now, retrying once on failure
+    pub async fn send(&self, notification: &Notification) -> Result<()> {
+        match self.post(notification).await {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                log::warn!("Webhook failed ({}), retrying in {:?}", e, WEBHOOK_RETRY_DELAY);
+                tokio::time::sleep(WEBHOOK_RETRY_DELAY).await;
+                self.post(notification).await
+            }
+        }
+    }
+
+    async fn post(&self, notification: &Notification) -> Result<()> {
+        let summary = notification.summary();
+        let text = if self.source.is_empty() {
+            summary
+        } else {
+            format!("[{}] {}", self.source, summary)
+        };
+        let payload = WebhookPayload {
+            notification,
+            source: &self.source,
+            content: &text,
+            text: &text,
+        };
+
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&payload)
+            .send()
+            .await
+            .map_err(PumpPortalError::from_request)?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(PumpPortalError::from_response(
+                status.as_u16(),
+                None,
+                format!("HTTP {} from webhook", status),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn notify(&self, notification: Notification) {
+        if !self.wants(notification.event) {
+            return;
+        }
+        let notifier = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = notifier.send(&notification).await {
+                log::warn!("Dropped {} notification for {}: {}", notification.event, notification.mint, e);
+            }
+        });
+    }
+}
+
+/// A [`WebhookNotifier`] for `url` reporting `events`, or a [`NoopNotifier`] without a URL
+pub fn notifier(url: Option<&str>, source: &str, events: &[NotifyEvent]) -> Arc<dyn Notifier> {
+    match url {
+        Some(url) => Arc::new(WebhookNotifier::new(url).with_source(source).with_events(events)),
+        None => Arc::new(NoopNotifier),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_shape() {
+        let sell = Notification::sell("Mint111", "Sig111", ExitReason::TakeProfit)
+            .with_sol(0.0672)
+            .with_pnl(34.4);
+        assert_eq!(sell.summary(), "SELL Mint111 | 0.0672 SOL | +34.4% | take_profit");
+
+        let payload = WebhookPayload {
+            notification: &sell,
+            source: "ladder-sniper",
+            content: "hi",
+            text: "hi",
+        };
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["event"], "sell");
+        assert_eq!(json["pnl_percent"], 34.4);
+        assert_eq!(json["source"], "ladder-sniper");
+        assert_eq!(json["content"], "hi");
+
+        // Unknown fields are left out rather than sent as null
+        let rug = serde_json::to_value(Notification::rug("Mint111", "dev sold")).unwrap();
+        assert!(rug.get("sol").is_none() && rug.get("signature").is_none());
+    }
+
+    #[test]
+    fn test_event_filter() {
+        let notifier = WebhookNotifier::new("http://localhost/hook").with_events(&[NotifyEvent::Rug]);
+        assert!(notifier.wants(NotifyEvent::Rug));
+        assert!(!notifier.wants(NotifyEvent::Buy));
+
+        assert_eq!("SELL".parse::<NotifyEvent>().unwrap(), NotifyEvent::Sell);
+        assert!("fill".parse::<NotifyEvent>().is_err());
+    }
+}
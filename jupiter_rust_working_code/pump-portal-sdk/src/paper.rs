@@ -0,0 +1,467 @@
+//! Paper trading (`paper` feature)
+//!
+//! [`PaperTradingSimulator`] stands in for the trade API when a bot runs with
+//! `paper.enabled` (`PAPER_MODE=true` or `--paper`): buys and sells fill
+//! against a [`PaperWallet`] after the configured latency and fees, return
+//! synthetic `PAPER_` signatures, and are written to the DuckDB research
+//! database when an exporter is attached. Every bot shares this simulator, so
+//! paper results are comparable across strategies.
+//!
+//! Bots without a price for a sell pass `None` and the sell fills at the
+//! entry price, which leaves fees as the only P&L.
+
+use crate::client::dry_run_signature;
+use crate::config::PaperConfig;
+use crate::export::{self, get_timestamp_micros, PositionRecord, SharedExporter, TradeRecord};
+use crate::{ExitReason, PumpPortalError, Proceeds, Result};
+use log::{info, warn};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::time::{sleep, Duration};
+
+/// SOL per token assumed for a paper buy when the launch didn't report its
+/// bonding curve (about where a fresh pump.fun curve starts)
+pub const ESTIMATED_LAUNCH_PRICE: f64 = 0.0000001;
+
+/// Research database used when `DUCKDB_PATH` isn't set
+pub const DEFAULT_DUCKDB_PATH: &str = "./data/research.duckdb";
+
+#[derive(Debug, Clone)]
+pub struct PaperPosition {
+    pub mint: String,
+    /// SOL still at stake, at cost
+    pub amount_sol: f64,
+    /// Token balance in base units, as the token account would hold it
+    pub raw_tokens: u64,
+    pub decimals: u8,
+    /// SOL per token (UI units)
+    pub entry_price: f64,
+    pub entry_time_micros: i64,
+}
+
+impl PaperPosition {
+    /// Token balance in UI units (decimals applied)
+    pub fn tokens(&self) -> f64 {
+        to_ui_amount(self.raw_tokens, self.decimals)
+    }
+}
+
+/// Base units of `ui_amount`, rounded down like a real fill
+fn to_raw_amount(ui_amount: f64, decimals: u8) -> u64 {
+    (ui_amount * 10_f64.powi(decimals as i32)).floor() as u64
+}
+
+fn to_ui_amount(raw_amount: u64, decimals: u8) -> f64 {
+    raw_amount as f64 / 10_f64.powi(decimals as i32)
+}
+
+#[derive(Debug)]
+pub struct PaperWallet {
+    balance: f64,
+    positions: HashMap<String, PaperPosition>,
+    total_fees_paid: f64,
+    total_trades: u32,
+    winning_trades: u32,
+    losing_trades: u32,
+}
+
+impl PaperWallet {
+    pub fn new(starting_balance: f64) -> Self {
+        Self {
+            balance: starting_balance,
+            positions: HashMap::new(),
+            total_fees_paid: 0.0,
+            total_trades: 0,
+            winning_trades: 0,
+            losing_trades: 0,
+        }
+    }
+
+    pub fn balance(&self) -> f64 {
+        self.balance
+    }
+
+    pub fn has_position(&self, mint: &str) -> bool {
+        self.positions.contains_key(mint)
+    }
+
+    pub fn get_position(&self, mint: &str) -> Option<&PaperPosition> {
+        self.positions.get(mint)
+    }
+
+    pub fn open_positions(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn stats(&self) -> (u32, u32, u32, f64) {
+        (self.total_trades, self.winning_trades, self.losing_trades, self.total_fees_paid)
+    }
+
+    /// Buy at `price` SOL per token of a mint with `decimals`; returns the
+    /// tokens received in UI units. Buying a mint already held adds to it.
+    pub fn buy(&mut self, mint: String, sol_amount: f64, price: f64, decimals: u8, fee_percent: f64, priority_fee: f64) -> Result<f64> {
+        if !price.is_finite() || price <= 0.0 {
+            return Err(PumpPortalError::InvalidParameter(format!("paper buy price {} must be positive", price)));
+        }
+
+        // Calculate fees
+        let trade_fee = sol_amount * (fee_percent / 100.0);
+        let total_cost = sol_amount + trade_fee + priority_fee;
+
+        if self.balance < total_cost {
+            warn!("Paper balance {} SOL < {} SOL", self.balance, total_cost);
+            return Err(PumpPortalError::InsufficientFunds);
+        }
+
+        // Calculate tokens received (after fee)
+        let net_sol = sol_amount - trade_fee;
+        let raw_tokens = to_raw_amount(net_sol / price, decimals);
+
+        // Deduct from balance
+        self.balance -= total_cost;
+        self.total_fees_paid += trade_fee + priority_fee;
+
+        // Record position, averaging into one that's already open
+        let position = self.positions.entry(mint.clone()).or_insert_with(|| PaperPosition {
+            mint,
+            amount_sol: 0.0,
+            raw_tokens: 0,
+            decimals,
+            entry_price: price,
+            entry_time_micros: get_timestamp_micros(),
+        });
+        position.amount_sol += sol_amount;
+        position.raw_tokens += raw_tokens;
+        if position.tokens() > 0.0 {
+            position.entry_price = position.amount_sol / position.tokens();
+        }
+
+        self.total_trades += 1;
+
+        Ok(to_ui_amount(raw_tokens, decimals))
+    }
+
+    /// Sell `percent` (1-100) of a position at `price` SOL per token; returns
+    /// what the sell fetched and its P&L percent against the cost of what it sold
+    pub fn sell(&mut self, mint: &str, percent: u32, price: f64, fee_percent: f64, priority_fee: f64) -> Result<(Proceeds, f64)> {
+        let position = self
+            .positions
+            .get_mut(mint)
+            .ok_or_else(|| PumpPortalError::InvalidParameter(format!("No paper position for {}", mint)))?;
+
+        let percent = percent.clamp(1, 100);
+        let raw_sold = (position.raw_tokens as u128 * percent as u128 / 100) as u64;
+        let cost = position.amount_sol * f64::from(percent) / 100.0;
+        let tokens_sold = to_ui_amount(raw_sold, position.decimals);
+
+        // Calculate SOL received
+        let gross_sol = tokens_sold * price;
+        let trade_fee = gross_sol * (fee_percent / 100.0);
+        let net_sol = gross_sol - trade_fee - priority_fee;
+
+        // Calculate P&L
+        let pnl = net_sol - cost;
+        let pnl_percent = (pnl / cost) * 100.0;
+
+        position.raw_tokens -= raw_sold;
+        position.amount_sol -= cost;
+        if percent >= 100 {
+            self.positions.remove(mint);
+        }
+
+        // Update balance
+        self.balance += net_sol;
+        self.total_fees_paid += trade_fee + priority_fee;
+
+        // Track win/loss
+        if pnl > 0.0 {
+            self.winning_trades += 1;
+        } else {
+            self.losing_trades += 1;
+        }
+
+        self.total_trades += 1;
+
+        Ok((Proceeds { sol_received: net_sol, tokens_sold }, pnl_percent))
+    }
+}
+
+pub type SharedPaperWallet = Arc<Mutex<PaperWallet>>;
+
+pub struct PaperTradingSimulator {
+    config: PaperConfig,
+    wallet: SharedPaperWallet,
+    exporter: Option<SharedExporter>,
+}
+
+impl PaperTradingSimulator {
+    pub fn new(config: PaperConfig, exporter: Option<SharedExporter>) -> Self {
+        let wallet = Arc::new(Mutex::new(PaperWallet::new(config.starting_balance)));
+        Self { config, wallet, exporter }
+    }
+
+    /// A simulator exporting to the DuckDB file at `DUCKDB_PATH` (default
+    /// [`DEFAULT_DUCKDB_PATH`]); runs without export if it won't open
+    pub fn with_duckdb(config: PaperConfig) -> Self {
+        Self::new(config, export::shared_from_env())
+    }
+
+    pub fn wallet(&self) -> SharedPaperWallet {
+        self.wallet.clone()
+    }
+
+    /// Simulate a buy order with realistic latency and fees. `price` is SOL per
+    /// token in UI units of a mint with `decimals`.
+    pub async fn simulate_buy(&self, mint: String, sol_amount: f64, price: f64, decimals: u8) -> Result<String> {
+        // Simulate network latency
+        sleep(Duration::from_millis(self.config.buy_latency_ms)).await;
+
+        // Execute trade
+        let mut wallet = self.wallet.lock().await;
+        let tokens = wallet.buy(
+            mint.clone(),
+            sol_amount,
+            price,
+            decimals,
+            self.config.trade_fee_percent,
+            self.config.priority_fee_sol,
+        )?;
+
+        let balance = wallet.balance();
+        let position = wallet.get_position(&mint).cloned();
+        drop(wallet);
+
+        let signature = dry_run_signature();
+
+        // Record trade and position in database
+        if let (Some(exporter), Some(position)) = (&self.exporter, position) {
+            let fee_sol = sol_amount * (self.config.trade_fee_percent / 100.0);
+            let timestamp = get_timestamp_micros();
+
+            let trade_record = TradeRecord {
+                trade_id: signature.clone(),
+                timestamp_micros: timestamp,
+                mint: mint.clone(),
+                trade_type: "BUY".to_string(),
+                price,
+                sol_amount,
+                tokens,
+                fee_sol,
+                priority_fee_sol: self.config.priority_fee_sol,
+                balance_after: balance,
+                signature: signature.clone(),
+            };
+
+            // Also record position open
+            let position_record = PositionRecord {
+                position_id: mint.clone(), // Use mint as position ID
+                mint: mint.clone(),
+                entry_time_micros: position.entry_time_micros,
+                exit_time_micros: None,
+                entry_price: position.entry_price,
+                exit_price: None,
+                sol_invested: position.amount_sol,
+                sol_returned: None,
+                tokens: position.tokens(),
+                pnl_sol: None,
+                pnl_percent: None,
+                hold_duration_secs: None,
+                holder_count_entry: 0,
+                holder_count_exit: None,
+                exit_reason: None,
+                profits_taken: false,
+                pnl_usd: None,
+            };
+
+            let exp = exporter.lock().await;
+            let _ = exp.record_trade(trade_record);
+            let _ = exp.record_position(position_record);
+        }
+
+        info!("📝 PAPER BUY:");
+        info!("   Mint: {}", short(&mint));
+        info!("   Spent: {} SOL", sol_amount);
+        info!("   Price: {} SOL/token", price);
+        info!("   Tokens: {}", tokens);
+        info!("   Fee: {}%", self.config.trade_fee_percent);
+        info!("   Remaining: {} SOL", balance);
+
+        Ok(signature)
+    }
+
+    /// Simulate selling `percent` of a position with realistic latency and
+    /// fees, at `price` SOL per token or the entry price without one
+    pub async fn simulate_sell(&self, mint: &str, percent: u32, price: Option<f64>, exit_reason: Option<ExitReason>) -> Result<(String, Proceeds)> {
+        // Simulate network latency
+        sleep(Duration::from_millis(self.config.sell_latency_ms)).await;
+
+        // Execute trade
+        let mut wallet = self.wallet.lock().await;
+        let position = wallet
+            .get_position(mint)
+            .cloned()
+            .ok_or_else(|| PumpPortalError::InvalidParameter(format!("No paper position for {}", mint)))?;
+        let price = price.filter(|p| p.is_finite() && *p > 0.0).unwrap_or(position.entry_price);
+        let (proceeds, pnl_percent) = wallet.sell(
+            mint,
+            percent,
+            price,
+            self.config.trade_fee_percent,
+            self.config.priority_fee_sol,
+        )?;
+
+        let balance = wallet.balance();
+        let closed = !wallet.has_position(mint);
+        let (total, wins, losses, fees) = wallet.stats();
+        drop(wallet);
+
+        let signature = dry_run_signature();
+        let sol_usd = crate::sol_usd().await.ok();
+
+        // Record trade, and the position once it's fully closed
+        if let Some(exporter) = &self.exporter {
+            let fee_sol = proceeds.sol_received * (self.config.trade_fee_percent / 100.0);
+            let timestamp = get_timestamp_micros();
+
+            let trade_record = TradeRecord {
+                trade_id: signature.clone(),
+                timestamp_micros: timestamp,
+                mint: mint.to_string(),
+                trade_type: "SELL".to_string(),
+                price,
+                sol_amount: proceeds.sol_received,
+                tokens: proceeds.tokens_sold,
+                fee_sol,
+                priority_fee_sol: self.config.priority_fee_sol,
+                balance_after: balance,
+                signature: signature.clone(),
+            };
+
+            let exp = exporter.lock().await;
+            let _ = exp.record_trade(trade_record);
+            if closed {
+                let pnl_sol = proceeds.sol_received - position.amount_sol;
+                let position_record = PositionRecord {
+                    position_id: mint.to_string(),
+                    mint: mint.to_string(),
+                    entry_time_micros: position.entry_time_micros,
+                    exit_time_micros: Some(timestamp),
+                    entry_price: position.entry_price,
+                    exit_price: Some(price),
+                    sol_invested: position.amount_sol,
+                    sol_returned: Some(proceeds.sol_received),
+                    tokens: position.tokens(),
+                    pnl_sol: Some(pnl_sol),
+                    pnl_percent: Some(pnl_percent),
+                    hold_duration_secs: Some((timestamp - position.entry_time_micros) / 1_000_000),
+                    holder_count_entry: 0,
+                    holder_count_exit: None,
+                    exit_reason,
+                    profits_taken: false,
+                    pnl_usd: sol_usd.map(|price| pnl_sol * price),
+                };
+                let _ = exp.record_position(position_record);
+            }
+        }
+
+        let pnl_emoji = if pnl_percent > 0.0 { "📈" } else { "📉" };
+
+        info!("📝 PAPER SELL:");
+        info!("   Mint: {} ({}%)", short(mint), percent.min(100));
+        info!("   Received: {} SOL", proceeds.sol_received);
+        info!("   Price: {} SOL/token", price);
+        info!("   {} P&L: {:+.1}%", pnl_emoji, pnl_percent);
+        match sol_usd {
+            Some(price) => info!("   Balance: {} SOL (${:.2})", balance, balance * price),
+            None => info!("   Balance: {} SOL", balance),
+        }
+        info!("   Stats: {} trades | {}W {}L | {:.4} SOL fees", total, wins, losses, fees);
+
+        Ok((signature, proceeds))
+    }
+
+    pub async fn print_summary(&self) {
+        let wallet = self.wallet.lock().await;
+        let (total, wins, losses, fees) = wallet.stats();
+        let balance = wallet.balance();
+        let starting = self.config.starting_balance;
+        let pnl = balance - starting;
+        let pnl_percent = (pnl / starting) * 100.0;
+        let win_rate = if total > 0 { (wins as f64 / total as f64) * 100.0 } else { 0.0 };
+
+        info!("");
+        info!("📊 PAPER TRADING SUMMARY");
+        info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        info!("   Starting: {} SOL", starting);
+        info!("   Current:  {} SOL", balance);
+        match crate::sol_usd().await {
+            Ok(price) => info!("   P&L:      {:+.4} SOL / ${:+.2} ({:+.1}%)", pnl, pnl * price, pnl_percent),
+            Err(_) => info!("   P&L:      {:+.4} SOL ({:+.1}%)", pnl, pnl_percent),
+        }
+        info!("   Trades:   {} total | {} wins | {} losses", total, wins, losses);
+        info!("   Win Rate: {:.1}%", win_rate);
+        info!("   Fees:     {:.4} SOL", fees);
+        info!("   Open:     {} positions", wallet.open_positions());
+        info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+        if let Some(exporter) = &self.exporter {
+            let _ = exporter.lock().await.print_summary();
+        }
+    }
+}
+
+/// First 8 characters of a mint, for log lines
+fn short(mint: &str) -> &str {
+    mint.get(..8).unwrap_or(mint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_in_ui_units() {
+        let mut wallet = PaperWallet::new(1.0);
+
+        // 0.1 SOL at 0.00000003 SOL/token, no fees: 3,333,333.333333 tokens at 6 decimals
+        let tokens = wallet.buy("mint".to_string(), 0.1, 0.00000003, 6, 0.0, 0.0).unwrap();
+        let position = wallet.get_position("mint").unwrap();
+        assert_eq!(position.raw_tokens, 3_333_333_333_333);
+        assert_eq!(tokens, position.tokens());
+        assert!((tokens - 3_333_333.333333).abs() < 1e-6);
+
+        // Selling at the same price returns the SOL, less the rounded-off dust
+        let (proceeds, _) = wallet.sell("mint", 100, 0.00000003, 0.0, 0.0).unwrap();
+        assert!((0.1 - proceeds.sol_received).abs() < 1e-12);
+        assert!(!wallet.has_position("mint"));
+    }
+
+    #[test]
+    fn test_partial_sells() {
+        let mut wallet = PaperWallet::new(1.0);
+        wallet.buy("mint".to_string(), 0.1, 0.0000001, 6, 0.0, 0.0).unwrap();
+
+        // Half at 2x doubles the half it sold
+        let (proceeds, pnl_percent) = wallet.sell("mint", 50, 0.0000002, 0.0, 0.0).unwrap();
+        assert!((proceeds.sol_received - 0.1).abs() < 1e-9);
+        assert!((pnl_percent - 100.0).abs() < 1e-6);
+        let position = wallet.get_position("mint").unwrap();
+        assert!((position.tokens() - 500_000.0).abs() < 1e-3);
+        assert!((position.amount_sol - 0.05).abs() < 1e-12);
+
+        // The rest at half price loses half
+        let (_, pnl_percent) = wallet.sell("mint", 100, 0.00000005, 0.0, 0.0).unwrap();
+        assert!((pnl_percent + 50.0).abs() < 1e-6);
+        assert!(!wallet.has_position("mint"));
+        assert_eq!(wallet.stats(), (3, 1, 1, 0.0));
+        assert!(matches!(
+            wallet.sell("mint", 100, 0.0000001, 0.0, 0.0),
+            Err(PumpPortalError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            wallet.buy("other".to_string(), 5.0, 0.0000001, 6, 0.0, 0.0),
+            Err(PumpPortalError::InsufficientFunds)
+        ));
+    }
+}
@@ -0,0 +1,191 @@
+//! Unrealized P&L of an open position
+//!
+//! [`PnlTracker`] holds a position's entry price and size and is marked with
+//! each new price, so every bot derives multiplier, percent, SOL P&L and
+//! drawdown from the same formulas. Prices can be in any unit (SOL or USD per
+//! token) as long as entry and marks use the same one.
+
+/// Entry, size and price history of an open position
+#[derive(Debug, Clone, PartialEq)]
+pub struct PnlTracker {
+    entry_price: f64,
+    size_sol: f64,
+    price: f64,
+    peak_multiplier: f64,
+}
+
+impl PnlTracker {
+    /// Track a position of `size_sol` bought at `entry_price`
+    ///
+    /// Pass an entry price of 0.0 when it isn't known yet; the first price
+    /// marked becomes the entry, and until then P&L reads break even.
+    pub fn new(entry_price: f64, size_sol: f64) -> Self {
+        let entry_price = if is_price(entry_price) { entry_price } else { 0.0 };
+        Self {
+            entry_price,
+            size_sol,
+            price: entry_price,
+            peak_multiplier: 1.0,
+        }
+    }
+
+    pub fn entry_price(&self) -> Option<f64> {
+        is_price(self.entry_price).then_some(self.entry_price)
+    }
+
+    /// Replace the entry price, e.g. once the real fill is known. Resets the
+    /// peak, which was measured against the old entry.
+    pub fn set_entry_price(&mut self, entry_price: f64) {
+        if !is_price(entry_price) {
+            return;
+        }
+        self.entry_price = entry_price;
+        if !is_price(self.price) {
+            self.price = entry_price;
+        }
+        self.peak_multiplier = self.multiplier().max(1.0);
+    }
+
+    /// SOL still in the position at cost
+    pub fn size_sol(&self) -> f64 {
+        self.size_sol
+    }
+
+    /// Latest price marked (the entry price until then)
+    pub fn price(&self) -> f64 {
+        self.price
+    }
+
+    /// Mark the position at `price`. Zero, negative and non-finite prices are ignored.
+    pub fn update(&mut self, price: f64) {
+        if !is_price(price) {
+            return;
+        }
+        if self.entry_price().is_none() {
+            self.entry_price = price;
+        }
+        self.price = price;
+        self.peak_multiplier = self.peak_multiplier.max(self.multiplier());
+    }
+
+    /// Add `sol` bought at `price`; the entry becomes the SOL-weighted average
+    pub fn add(&mut self, sol: f64, price: f64) {
+        if sol <= 0.0 || !is_price(price) {
+            return;
+        }
+        match self.entry_price() {
+            Some(entry) => {
+                let tokens = self.size_sol / entry + sol / price;
+                self.size_sol += sol;
+                self.set_entry_price(self.size_sol / tokens);
+            }
+            None => {
+                self.size_sol += sol;
+                self.set_entry_price(price);
+            }
+        }
+        self.update(price);
+    }
+
+    /// Take `percent` of the position off; the entry price is unchanged
+    pub fn reduce(&mut self, percent: u32) {
+        self.size_sol *= 1.0 - f64::from(percent.min(100)) / 100.0;
+    }
+
+    /// Current price / entry price (1.0 = break even, also while the entry is unknown)
+    pub fn multiplier(&self) -> f64 {
+        match self.entry_price() {
+            Some(entry) if is_price(self.price) => self.price / entry,
+            _ => 1.0,
+        }
+    }
+
+    /// P&L in percent (100.0 = doubled)
+    pub fn percent(&self) -> f64 {
+        (self.multiplier() - 1.0) * 100.0
+    }
+
+    /// Unrealized P&L in SOL on what's still held
+    pub fn unrealized_sol(&self) -> f64 {
+        self.size_sol * (self.multiplier() - 1.0)
+    }
+
+    /// Highest multiplier marked so far
+    pub fn peak_multiplier(&self) -> f64 {
+        self.peak_multiplier
+    }
+
+    /// Fraction given back from the peak (0.25 = 25% below it)
+    pub fn drawdown(&self) -> f64 {
+        (1.0 - self.multiplier() / self.peak_multiplier).max(0.0)
+    }
+}
+
+fn is_price(price: f64) -> bool {
+    price.is_finite() && price > 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_pnl_math() {
+        let mut pnl = PnlTracker::new(0.002, 0.5);
+        assert_eq!(pnl.multiplier(), 1.0);
+
+        pnl.update(0.006);
+        assert!(approx(pnl.multiplier(), 3.0));
+        assert!(approx(pnl.percent(), 200.0));
+        assert!(approx(pnl.unrealized_sol(), 1.0));
+
+        pnl.update(0.0045);
+        assert!(approx(pnl.peak_multiplier(), 3.0));
+        assert!(approx(pnl.drawdown(), 0.25));
+        assert!(approx(pnl.percent(), 125.0));
+
+        // Selling half halves the SOL at stake, not the multiplier
+        pnl.reduce(50);
+        assert!(approx(pnl.size_sol(), 0.25));
+        assert!(approx(pnl.unrealized_sol(), 0.3125));
+        assert!(approx(pnl.multiplier(), 2.25));
+    }
+
+    #[test]
+    fn test_zero_entry_price_guard() {
+        let mut pnl = PnlTracker::new(0.0, 0.1);
+        assert_eq!(pnl.entry_price(), None);
+        assert_eq!(pnl.multiplier(), 1.0);
+        assert_eq!(pnl.unrealized_sol(), 0.0);
+        assert_eq!(pnl.drawdown(), 0.0);
+
+        // Bad marks are ignored; the first real one becomes the entry
+        pnl.update(0.0);
+        pnl.update(f64::NAN);
+        assert_eq!(pnl.entry_price(), None);
+        pnl.update(0.004);
+        assert_eq!(pnl.entry_price(), Some(0.004));
+        assert_eq!(pnl.multiplier(), 1.0);
+
+        // The real fill replaces the provisional entry and resets the peak
+        pnl.update(0.008);
+        pnl.set_entry_price(0.008);
+        assert_eq!(pnl.multiplier(), 1.0);
+        assert_eq!(pnl.peak_multiplier(), 1.0);
+    }
+
+    #[test]
+    fn test_add_averages_entry() {
+        let mut pnl = PnlTracker::new(0.001, 0.1);
+        pnl.add(0.1, 0.002);
+
+        // 100 + 50 tokens for 0.2 SOL
+        assert!(approx(pnl.entry_price().unwrap(), 0.2 / 150.0));
+        assert!(approx(pnl.size_sol(), 0.2));
+        assert!(approx(pnl.multiplier(), 0.002 / (0.2 / 150.0)));
+    }
+}
@@ -0,0 +1,172 @@
+//! Multi-wallet client pool
+
+use crate::{PumpPortalClient, PumpPortalError, Result, TradeRequest, TradeResponse};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A keyed set of PumpPortal clients, one per wallet/API key
+///
+/// Routes trades to a specific wallet by key id, by strategy name, or
+/// round-robin, with optional per-key rate limits so a burst of exits on one
+/// wallet doesn't trip PumpPortal's limits.
+///
+/// # Example
+///
+/// ```no_run
+/// use pump_portal_sdk::{PumpPortalClient, PumpPortalClientPool, TradeRequest};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let pool = PumpPortalClientPool::new()
+///     .with_client("snipe", PumpPortalClient::new("snipe-key".to_string()))
+///     .with_client("moonbag", PumpPortalClient::new("moonbag-key".to_string()))
+///     .with_rate_limit("snipe", 5.0)
+///     .with_strategy("ladder", "snipe");
+///
+/// let request = TradeRequest::buy("TokenMint".to_string(), 0.01, 10, 0.0001);
+/// pool.trade_for_strategy("ladder", request).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct PumpPortalClientPool {
+    clients: Vec<(String, PumpPortalClient)>,
+    strategies: HashMap<String, String>,
+    next: AtomicUsize,
+}
+
+impl PumpPortalClientPool {
+    /// Create an empty pool
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a client under `key_id`, replacing any existing client with that id
+    pub fn with_client(mut self, key_id: impl Into<String>, client: PumpPortalClient) -> Self {
+        let key_id = key_id.into();
+        self.clients.retain(|(id, _)| *id != key_id);
+        self.clients.push((key_id, client));
+        self
+    }
+
+    /// Limit trades routed to `key_id` to `max_per_sec`
+    ///
+    /// Must be called after the client is added with `with_client`.
+    pub fn with_rate_limit(mut self, key_id: &str, max_per_sec: f64) -> Self {
+        match self.clients.iter_mut().find(|(id, _)| id == key_id) {
+            Some((_, client)) => *client = client.clone().with_rate_limit(max_per_sec),
+            None => log::warn!("Rate limit for unknown client key ignored: {}", key_id),
+        }
+        self
+    }
+
+    /// Route trades for `strategy` to `key_id`
+    pub fn with_strategy(mut self, strategy: impl Into<String>, key_id: impl Into<String>) -> Self {
+        self.strategies.insert(strategy.into(), key_id.into());
+        self
+    }
+
+    /// Client registered under `key_id`
+    pub fn get(&self, key_id: &str) -> Option<&PumpPortalClient> {
+        self.clients
+            .iter()
+            .find(|(id, _)| id == key_id)
+            .map(|(_, client)| client)
+    }
+
+    /// Registered key ids, in insertion order
+    pub fn key_ids(&self) -> impl Iterator<Item = &str> {
+        self.clients.iter().map(|(id, _)| id.as_str())
+    }
+
+    /// Number of clients in the pool
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Whether the pool has no clients
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+
+    /// Execute a trade on the wallet registered under `key_id`
+    pub async fn trade_with(&self, key_id: &str, request: TradeRequest) -> Result<TradeResponse> {
+        let client = self.get(key_id).ok_or_else(|| {
+            PumpPortalError::InvalidParameter(format!("unknown client key: {}", key_id))
+        })?;
+        client.trade(request).await
+    }
+
+    /// Execute a trade on the wallet assigned to `strategy`
+    pub async fn trade_for_strategy(
+        &self,
+        strategy: &str,
+        request: TradeRequest,
+    ) -> Result<TradeResponse> {
+        let key_id = self.strategies.get(strategy).ok_or_else(|| {
+            PumpPortalError::InvalidParameter(format!("no client assigned to strategy: {}", strategy))
+        })?;
+        self.trade_with(key_id, request).await
+    }
+
+    /// Execute a trade on the next wallet in round-robin order
+    ///
+    /// Returns the key id used alongside the response, so the position can be
+    /// sold from the same wallet later.
+    pub async fn trade_round_robin(&self, request: TradeRequest) -> Result<(String, TradeResponse)> {
+        if self.clients.is_empty() {
+            return Err(PumpPortalError::InvalidParameter(
+                "client pool is empty".to_string(),
+            ));
+        }
+
+        let idx = self.next.fetch_add(1, Ordering::Relaxed) % self.clients.len();
+        let key_id = self.clients[idx].0.clone();
+        let response = self.trade_with(&key_id, request).await?;
+        Ok((key_id, response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool() -> PumpPortalClientPool {
+        PumpPortalClientPool::new()
+            .with_client("a", PumpPortalClient::new("key-a".to_string()).dry_run(true))
+            .with_client("b", PumpPortalClient::new("key-b".to_string()).dry_run(true))
+            .with_rate_limit("b", 10.0)
+            .with_strategy("snipe", "b")
+    }
+
+    fn request() -> TradeRequest {
+        TradeRequest::buy("TokenMint".to_string(), 0.01, 10, 0.0001)
+    }
+
+    #[tokio::test]
+    async fn test_round_robin_alternates_keys() {
+        let pool = pool();
+        let (first, _) = pool.trade_round_robin(request()).await.unwrap();
+        let (second, _) = pool.trade_round_robin(request()).await.unwrap();
+        let (third, _) = pool.trade_round_robin(request()).await.unwrap();
+
+        assert_eq!(first, "a");
+        assert_eq!(second, "b");
+        assert_eq!(third, "a");
+        assert_eq!(pool.get("a").unwrap().rate_limit(), None);
+        assert_eq!(pool.get("b").unwrap().rate_limit(), Some(10.0));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_key_and_strategy() {
+        let pool = pool();
+        assert!(pool.trade_for_strategy("snipe", request()).await.is_ok());
+        assert!(matches!(
+            pool.trade_with("missing", request()).await,
+            Err(PumpPortalError::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            pool.trade_for_strategy("missing", request()).await,
+            Err(PumpPortalError::InvalidParameter(_))
+        ));
+    }
+}
@@ -0,0 +1,141 @@
+//! SOL/USD price shared by the bots
+//!
+//! The bots trade in SOL but report P&L in dollars. Every conversion goes
+//! through one cached price so logs, databases and summaries agree, and a
+//! burst of closes doesn't hammer the price API.
+
+use crate::{PumpPortalError, Result};
+use reqwest::Client;
+use serde_json::Value;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Jupiter price API
+const PRICE_URL: &str = "https://lite-api.jup.ag/price/v3";
+
+/// Wrapped SOL mint
+pub const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// How long a fetched price is reused before refreshing
+pub const DEFAULT_PRICE_TTL: Duration = Duration::from_secs(30);
+
+/// Cached SOL/USD price from the Jupiter price API
+pub struct SolPrice {
+    client: Client,
+    url: String,
+    ttl: Duration,
+    cached: Mutex<Option<(f64, Instant)>>,
+}
+
+impl SolPrice {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(crate::client::DEFAULT_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            url: PRICE_URL.to_string(),
+            ttl,
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// Fetch from another price endpoint (same response format)
+    pub fn with_url(mut self, url: &str) -> Self {
+        self.url = url.to_string();
+        self
+    }
+
+    /// Current SOL price in USD, refreshed once the cached one is older than the TTL.
+    /// If the refresh fails, the last known price is returned instead.
+    pub async fn sol_usd(&self) -> Result<f64> {
+        let mut cached = self.cached.lock().await;
+        if let Some((price, fetched)) = *cached {
+            if fetched.elapsed() < self.ttl {
+                return Ok(price);
+            }
+        }
+
+        match self.fetch().await {
+            Ok(price) => {
+                *cached = Some((price, Instant::now()));
+                Ok(price)
+            }
+            Err(e) => match *cached {
+                Some((price, _)) => {
+                    log::warn!("SOL price refresh failed ({}), using last price ${:.2}", e, price);
+                    Ok(price)
+                }
+                None => Err(e),
+            },
+        }
+    }
+
+    /// Convert a SOL amount to USD at the current price
+    pub async fn to_usd(&self, sol: f64) -> Result<f64> {
+        Ok(sol * self.sol_usd().await?)
+    }
+
+    async fn fetch(&self) -> Result<f64> {
+        let response: Value = self
+            .client
+            .get(&self.url)
+            .query(&[("ids", SOL_MINT)])
+            .send()
+            .await
+            .map_err(PumpPortalError::from_request)?
+            .json()
+            .await?;
+        parse_sol_price(&response)
+    }
+}
+
+/// Process-wide price so every module converts with the same number
+pub fn global() -> &'static SolPrice {
+    static PRICE: OnceLock<SolPrice> = OnceLock::new();
+    PRICE.get_or_init(|| SolPrice::new(DEFAULT_PRICE_TTL))
+}
+
+/// Current SOL price in USD from the shared [`global`] cache
+pub async fn sol_usd() -> Result<f64> {
+    global().sol_usd().await
+}
+
+/// Read the SOL price from a price API response keyed by mint
+fn parse_sol_price(response: &Value) -> Result<f64> {
+    response
+        .get(SOL_MINT)
+        .and_then(|entry| entry.get("usdPrice"))
+        .and_then(Value::as_f64)
+        .filter(|price| price.is_finite() && *price > 0.0)
+        .ok_or_else(|| PumpPortalError::ApiError(format!("No SOL price in response: {}", response)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_sol_price() {
+        let response = json!({
+            SOL_MINT: { "usdPrice": 187.42, "blockId": 348_000_000u64, "decimals": 9 }
+        });
+        assert_eq!(parse_sol_price(&response).unwrap(), 187.42);
+
+        assert!(parse_sol_price(&json!({})).is_err());
+        assert!(parse_sol_price(&json!({ SOL_MINT: { "usdPrice": 0.0 } })).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cached_price_survives_failed_refresh() {
+        // Nothing listens here, so every fetch fails
+        let price = SolPrice::new(Duration::ZERO).with_url("http://127.0.0.1:1/price");
+        assert!(price.sol_usd().await.is_err());
+
+        *price.cached.lock().await = Some((150.0, Instant::now()));
+        assert_eq!(price.sol_usd().await.unwrap(), 150.0);
+        assert_eq!(price.to_usd(0.5).await.unwrap(), 75.0);
+    }
+}
@@ -0,0 +1,231 @@
+//! Pre-trade quotes for bonding-curve buys
+//!
+//! The Lightning API executes a buy without saying what it will get. A
+//! [`Quote`] works that out from the curve's virtual reserves, either ones a
+//! launch or trade event already carried ([`Quote::from_curve`]) or ones read
+//! from the bonding-curve account ([`PumpPortalClient::quote`]): expected
+//! tokens, the price impact, and the effective price including the fee.
+
+use crate::{BondingCurve, Pool, PumpPortalClient, PumpPortalError, Result, PUMP_TOKEN_DECIMALS};
+use serde_json::{json, Value};
+
+/// pump.fun's fee on bonding-curve trades, taken from the SOL going in
+pub const CURVE_FEE_BPS: u32 = 100;
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// Anchor discriminator, then virtual token and SOL reserves, real token and
+/// SOL reserves and total supply (u64 each), then `complete`
+const CURVE_ACCOUNT_LEN: usize = 8 + 5 * 8 + 1;
+
+/// What a buy is expected to get, before it's sent
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quote {
+    pub mint: String,
+    pub pool: Pool,
+    /// SOL spent, fee included
+    pub sol_in: f64,
+    /// Fee taken from `sol_in`
+    pub fee_sol: f64,
+    pub expected_tokens: f64,
+    /// Curve price before the buy, SOL per token
+    pub spot_price: f64,
+    /// SOL per token actually paid, fee included
+    pub effective_price: f64,
+    /// How far the curve's average fill sits above spot
+    pub price_impact_bps: f64,
+}
+
+impl Quote {
+    /// Quote a buy of `sol_in` against known reserves
+    ///
+    /// # Errors
+    ///
+    /// `InvalidParameter` if `sol_in` isn't positive, the reserves aren't
+    /// usable, or `pool` isn't a bonding curve (`Pump` or `Auto`).
+    pub fn from_curve(mint: &str, sol_in: f64, pool: Pool, curve: &BondingCurve) -> Result<Self> {
+        if !matches!(pool, Pool::Pump | Pool::Auto) {
+            return Err(PumpPortalError::InvalidParameter(format!(
+                "Quotes cover the pump.fun bonding curve only, not {:?}",
+                pool
+            )));
+        }
+        if !sol_in.is_finite() || sol_in <= 0.0 {
+            return Err(PumpPortalError::InvalidParameter(format!("Quote amount {} SOL must be positive", sol_in)));
+        }
+        if !curve.is_valid() {
+            return Err(PumpPortalError::InvalidParameter(format!("Unusable curve reserves {:?}", curve)));
+        }
+
+        let fee_sol = sol_in * CURVE_FEE_BPS as f64 / 10_000.0;
+        let net_sol = sol_in - fee_sol;
+        let expected_tokens = curve.tokens_out(net_sol);
+        Ok(Self {
+            mint: mint.to_string(),
+            pool,
+            sol_in,
+            fee_sol,
+            expected_tokens,
+            spot_price: curve.price(),
+            effective_price: sol_in / expected_tokens,
+            price_impact_bps: curve.buy_impact_bps(net_sol),
+        })
+    }
+
+    /// Average SOL per token paid to the curve, fee left out
+    pub fn curve_price(&self) -> f64 {
+        (self.sol_in - self.fee_sol) / self.expected_tokens
+    }
+
+    /// Fewest tokens the buy may get within `slippage` percent
+    pub fn min_tokens(&self, slippage: u32) -> f64 {
+        self.expected_tokens * (1.0 - slippage.min(100) as f64 / 100.0)
+    }
+}
+
+impl PumpPortalClient {
+    /// Quote a buy of `amount_sol` from the bonding-curve account's current reserves
+    ///
+    /// `curve_account` is the mint's bonding-curve address, as launch and trade
+    /// events report it (`bondingCurveKey`). Nothing is executed.
+    ///
+    /// # Errors
+    ///
+    /// `InvalidParameter` for a pool other than `Pump` / `Auto` or a curve that
+    /// has completed (the token migrated), `ApiError` if the account can't be
+    /// read or isn't a bonding curve.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use pump_portal_sdk::{Pool, PumpPortalClient};
+    /// # async fn example() -> pump_portal_sdk::Result<()> {
+    /// let client = PumpPortalClient::new("your-api-key".to_string());
+    /// let quote = client
+    ///     .quote("TokenMintAddress", 0.1, Pool::Pump, "BondingCurveAddress", "https://api.mainnet-beta.solana.com")
+    ///     .await?;
+    /// println!("{:.0} tokens at {:.10} SOL each ({:.0} bps impact)",
+    ///          quote.expected_tokens, quote.effective_price, quote.price_impact_bps);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn quote(&self, mint: &str, amount_sol: f64, pool: Pool, curve_account: &str, rpc_url: &str) -> Result<Quote> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getAccountInfo",
+            "params": [curve_account, { "encoding": "base64", "commitment": "processed" }],
+        });
+
+        let response: Value = self
+            .http()
+            .post(rpc_url)
+            .timeout(self.timeout())
+            .json(&body)
+            .send()
+            .await
+            .map_err(PumpPortalError::from_request)?
+            .json()
+            .await
+            .map_err(PumpPortalError::from_request)?;
+
+        if let Some(error) = response.get("error") {
+            return Err(PumpPortalError::ApiError(format!("RPC error: {}", error)));
+        }
+        let data = response
+            .pointer("/result/value/data/0")
+            .and_then(Value::as_str)
+            .ok_or_else(|| PumpPortalError::ApiError(format!("No bonding-curve account at {}", curve_account)))?;
+        let (curve, complete) = decode_base64(data)
+            .as_deref()
+            .and_then(parse_curve_account)
+            .ok_or_else(|| PumpPortalError::ApiError(format!("{} is not a bonding-curve account", curve_account)))?;
+        if complete {
+            return Err(PumpPortalError::InvalidParameter(format!(
+                "{}'s bonding curve is complete; the token trades on an AMM now",
+                mint
+            )));
+        }
+
+        Quote::from_curve(mint, amount_sol, pool, &curve)
+    }
+}
+
+/// Virtual reserves and the `complete` flag of a bonding-curve account
+fn parse_curve_account(data: &[u8]) -> Option<(BondingCurve, bool)> {
+    if data.len() < CURVE_ACCOUNT_LEN {
+        return None;
+    }
+    let u64_at = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+    let tokens = u64_at(8) as f64 / 10f64.powi(PUMP_TOKEN_DECIMALS as i32);
+    let sol = u64_at(16) as f64 / LAMPORTS_PER_SOL;
+    Some((BondingCurve::new(sol, tokens), data[48] != 0))
+}
+
+/// Standard base64 with padding, as RPC nodes encode account data
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let value = |c: u8| match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    };
+    let text = text.trim_end_matches('=').as_bytes();
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    let (mut acc, mut bits) = (0u32, 0);
+    for &c in text {
+        acc = (acc << 6) | value(c)? as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_from_curve() {
+        // A fresh curve: 30 SOL against 1.073B tokens
+        let curve = BondingCurve::new(30.0, 1_073_000_000.0);
+        let quote = Quote::from_curve("mint", 1.0, Pool::Pump, &curve).unwrap();
+
+        assert!((quote.fee_sol - 0.01).abs() < 1e-12);
+        assert!((quote.expected_tokens - curve.tokens_out(0.99)).abs() < 1e-6);
+        assert!((quote.price_impact_bps - 330.0).abs() < 1e-9);
+        assert!(quote.effective_price > quote.curve_price());
+        assert!(quote.curve_price() > quote.spot_price);
+        assert!((quote.min_tokens(10) - quote.expected_tokens * 0.9).abs() < 1e-6);
+
+        assert!(Quote::from_curve("mint", 1.0, Pool::Raydium, &curve).is_err());
+        assert!(Quote::from_curve("mint", 0.0, Pool::Pump, &curve).is_err());
+        assert!(Quote::from_curve("mint", 1.0, Pool::Auto, &BondingCurve::new(0.0, 1.0)).is_err());
+    }
+
+    #[test]
+    fn test_parse_curve_account() {
+        let mut data = vec![0u8; 8];
+        for value in [1_073_000_000_000_000u64, 30_000_000_000, 793_100_000_000_000, 0, 1_000_000_000_000_000] {
+            data.extend(value.to_le_bytes());
+        }
+        data.push(0);
+
+        let (curve, complete) = parse_curve_account(&data).unwrap();
+        assert_eq!(curve, BondingCurve::new(30.0, 1_073_000_000.0));
+        assert!(!complete);
+        assert!(parse_curve_account(&data[..40]).is_none());
+    }
+
+    #[test]
+    fn test_decode_base64() {
+        assert_eq!(decode_base64("aGVsbG8=").unwrap(), b"hello");
+        assert_eq!(decode_base64("AAEC/w==").unwrap(), vec![0, 1, 2, 255]);
+        assert!(decode_base64("no spaces").is_none());
+    }
+}
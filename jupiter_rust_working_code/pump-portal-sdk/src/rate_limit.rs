@@ -0,0 +1,112 @@
+//! Token-bucket rate limiting for outgoing trade requests
+
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Async token-bucket rate limiter
+///
+/// Allows bursts of up to `max_per_sec` requests, then spaces requests out at
+/// `max_per_sec`. Callers that exceed the budget are delayed rather than rejected.
+#[derive(Debug)]
+pub struct RateLimiter {
+    max_per_sec: f64,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Create a limiter allowing `max_per_sec` requests per second
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_per_sec` is not positive.
+    pub fn new(max_per_sec: f64) -> Self {
+        assert!(max_per_sec > 0.0, "rate limit must be positive");
+        Self {
+            max_per_sec,
+            bucket: Mutex::new(Bucket {
+                tokens: max_per_sec.max(1.0),
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Configured requests per second
+    pub fn max_per_sec(&self) -> f64 {
+        self.max_per_sec
+    }
+
+    /// Wait until a request is allowed, returning how long we were delayed
+    pub async fn acquire(&self) -> Duration {
+        let wait = {
+            let mut bucket = self.bucket.lock().await;
+            let now = Instant::now();
+            let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * self.max_per_sec).min(self.max_per_sec.max(1.0));
+            bucket.last_refill = now;
+
+            // Reserve a token; a negative balance queues later callers behind us
+            bucket.tokens -= 1.0;
+            if bucket.tokens >= 0.0 {
+                Duration::ZERO
+            } else {
+                Duration::from_secs_f64(-bucket.tokens / self.max_per_sec)
+            }
+        };
+
+        if !wait.is_zero() {
+            log::info!("Rate limit reached, delaying request by {:?}", wait);
+            tokio::time::sleep(wait).await;
+        }
+        wait
+    }
+
+    /// Hold off all callers for at least `pause`, e.g. after the API returned 429
+    ///
+    /// Empties the bucket and pushes the next token out by `pause`; requests
+    /// already queued stay queued behind it.
+    pub async fn back_off(&self, pause: Duration) {
+        let mut bucket = self.bucket.lock().await;
+        let debt = -pause.as_secs_f64() * self.max_per_sec;
+        if debt < bucket.tokens {
+            bucket.tokens = debt;
+            bucket.last_refill = Instant::now();
+        }
+        log::warn!("Rate limited by API, backing off for {:?}", pause);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_burst_then_delay() {
+        let limiter = RateLimiter::new(10.0);
+
+        for _ in 0..10 {
+            assert_eq!(limiter.acquire().await, Duration::ZERO);
+        }
+
+        // Bucket is empty, the next request waits roughly 1/10th of a second
+        let waited = limiter.acquire().await;
+        assert!(waited > Duration::from_millis(50));
+        assert!(waited <= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_back_off_delays_next_request() {
+        let limiter = RateLimiter::new(10.0);
+        limiter.back_off(Duration::from_millis(300)).await;
+
+        // Full bucket is discarded; next token is 300ms + one interval away
+        let waited = limiter.acquire().await;
+        assert!(waited >= Duration::from_millis(300));
+        assert!(waited <= Duration::from_millis(400));
+    }
+}
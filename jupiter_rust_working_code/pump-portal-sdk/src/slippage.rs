@@ -0,0 +1,236 @@
+//! Buy slippage tuned from the bot's own recent fills
+//!
+//! A fixed slippage is a guess: too tight and buys on busy launches keep
+//! failing, too loose and every fill pays for it. [`AdaptiveSlippage`] keeps
+//! the outcome of the last few buys and steps the slippage up when slippage
+//! failures cluster, and back down after a clean run of fills, never leaving
+//! its bounds. After each step the window starts over, so one bad stretch
+//! moves it once rather than on every buy that follows.
+//!
+//! This is separate from [`PumpPortalClient::with_slippage_retry`](crate::PumpPortalClient::with_slippage_retry),
+//! which retries a single failed trade higher; here the starting point moves.
+
+use crate::{ConfirmationStatus, PumpPortalClient, PumpPortalError, Result};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Buys looked back over
+pub const DEFAULT_WINDOW: usize = 10;
+
+/// Share of slippage failures in the window that raises slippage
+pub const DEFAULT_RAISE_AT: f64 = 0.3;
+
+/// Percentage points per adjustment
+pub const DEFAULT_STEP: u32 = 5;
+
+/// How long [`AdaptiveSlippage::record_when_confirmed`] waits for a buy to settle
+pub const CONFIRM_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+struct State {
+    current: u32,
+    /// true for a fill, false for a slippage failure; newest last
+    outcomes: VecDeque<bool>,
+}
+
+/// Current buy slippage, moved by recent outcomes within `[min, max]` percent
+///
+/// Clones share the same state.
+#[derive(Debug, Clone)]
+pub struct AdaptiveSlippage {
+    min: u32,
+    max: u32,
+    step: u32,
+    window: usize,
+    raise_at: f64,
+    state: Arc<Mutex<State>>,
+}
+
+impl AdaptiveSlippage {
+    /// Start at `min` percent and never go past `max`
+    pub fn new(min: u32, max: u32) -> Self {
+        let max = max.clamp(min, 100);
+        Self {
+            min,
+            max,
+            step: DEFAULT_STEP,
+            window: DEFAULT_WINDOW,
+            raise_at: DEFAULT_RAISE_AT,
+            state: Arc::new(Mutex::new(State {
+                current: min.min(max),
+                outcomes: VecDeque::with_capacity(DEFAULT_WINDOW),
+            })),
+        }
+    }
+
+    /// Move this many percentage points at a time (default 5)
+    pub fn with_step(mut self, step: u32) -> Self {
+        self.step = step.max(1);
+        self
+    }
+
+    /// Judge over the last `window` buys (default 10)
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window.max(1);
+        self
+    }
+
+    pub fn bounds(&self) -> (u32, u32) {
+        (self.min, self.max)
+    }
+
+    /// Slippage percent to buy with now
+    pub fn current(&self) -> u32 {
+        self.state.lock().unwrap().current
+    }
+
+    /// Count a buy's result: a fill, a slippage failure, or anything else,
+    /// which says nothing about slippage and is ignored
+    ///
+    /// Returns the new slippage when this moved it.
+    pub fn record<T>(&self, result: &Result<T>) -> Option<u32> {
+        match result {
+            Ok(_) => self.record_fill(),
+            Err(e) => self.record_error(e),
+        }
+    }
+
+    /// Count a buy that filled
+    pub fn record_fill(&self) -> Option<u32> {
+        self.record_outcome(true)
+    }
+
+    /// Count a failed buy if it failed on slippage (`PumpPortalError::SlippageExceeded`);
+    /// takes any error so a bot's `anyhow::Error` works via `as_ref()`
+    pub fn record_error(&self, error: &(dyn std::error::Error + 'static)) -> Option<u32> {
+        match error.downcast_ref::<PumpPortalError>() {
+            Some(PumpPortalError::SlippageExceeded(_)) => self.record_outcome(false),
+            _ => None,
+        }
+    }
+
+    /// Count a buy by how it settled on-chain: a fill once confirmed, a failure
+    /// if it failed on slippage; anything else is ignored
+    pub fn record_confirmation(&self, status: &ConfirmationStatus) -> Option<u32> {
+        match status {
+            ConfirmationStatus::Confirmed => self.record_fill(),
+            status if status.is_slippage_failure() => self.record_outcome(false),
+            _ => None,
+        }
+    }
+
+    /// Count the buy sent as `signature` once it settles on `rpc_url`, from a
+    /// background task
+    ///
+    /// Buys skip preflight, so one that slipped still comes back with a
+    /// signature and only fails on-chain; counting it when the signature
+    /// returns would call it a fill.
+    pub fn record_when_confirmed(&self, client: &PumpPortalClient, signature: &str, rpc_url: &str) {
+        let (slippage, client) = (self.clone(), client.clone());
+        let (signature, rpc_url) = (signature.to_string(), rpc_url.to_string());
+        tokio::spawn(async move {
+            match client.confirm(&signature, &rpc_url, CONFIRM_TIMEOUT).await {
+                Ok(status) => {
+                    slippage.record_confirmation(&status);
+                }
+                Err(e) => log::warn!("Couldn't confirm buy {} for slippage tuning: {}", signature, e),
+            }
+        });
+    }
+
+    fn record_outcome(&self, filled: bool) -> Option<u32> {
+        let mut state = self.state.lock().unwrap();
+        if state.outcomes.len() == self.window {
+            state.outcomes.pop_front();
+        }
+        state.outcomes.push_back(filled);
+
+        let failures = state.outcomes.iter().filter(|filled| !**filled).count();
+        let full = state.outcomes.len() == self.window;
+        let old = state.current;
+        let new = if !filled && failures as f64 >= self.raise_at * self.window as f64 {
+            (old + self.step).min(self.max)
+        } else if full && failures == 0 {
+            old.saturating_sub(self.step).max(self.min)
+        } else {
+            old
+        };
+        if new == old {
+            return None;
+        }
+
+        state.current = new;
+        state.outcomes.clear();
+        let direction = if new > old { "Raising" } else { "Lowering" };
+        log::info!(
+            "🎚️  {} buy slippage {}% → {}% ({}/{} recent buys failed on slippage)",
+            direction,
+            old,
+            new,
+            failures,
+            self.window
+        );
+        Some(new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slipped() -> Result<()> {
+        Err(PumpPortalError::SlippageExceeded("TooMuchSolRequired".to_string()))
+    }
+
+    #[test]
+    fn test_raises_on_clustered_failures_within_bounds() {
+        let slippage = AdaptiveSlippage::new(10, 20).with_window(10);
+        assert_eq!(slippage.current(), 10);
+
+        // 3 of 10 failing trips it, on the third failure
+        assert_eq!(slippage.record(&slipped()), None);
+        assert_eq!(slippage.record(&Ok(())), None);
+        assert_eq!(slippage.record(&slipped()), None);
+        assert_eq!(slippage.record(&Err::<(), _>(PumpPortalError::InsufficientFunds)), None);
+        assert_eq!(slippage.record(&slipped()), Some(15));
+
+        // The window starts over, so the next step takes fresh failures
+        assert_eq!(slippage.record(&slipped()), None);
+        assert_eq!(slippage.record(&slipped()), None);
+        assert_eq!(slippage.record(&slipped()), Some(20));
+        for _ in 0..5 {
+            assert_eq!(slippage.record(&slipped()), None);
+        }
+        assert_eq!(slippage.current(), 20);
+    }
+
+    #[test]
+    fn test_records_on_chain_outcomes() {
+        let slippage = AdaptiveSlippage::new(10, 20).with_window(3);
+        let slipped = ConfirmationStatus::Failed(r#"{"InstructionError":[2,{"Custom":6002}]}"#.to_string());
+
+        assert_eq!(slippage.record_confirmation(&ConfirmationStatus::Timeout), None);
+        assert_eq!(slippage.record_confirmation(&ConfirmationStatus::Failed("BlockhashNotFound".to_string())), None);
+        assert_eq!(slippage.record_confirmation(&ConfirmationStatus::Confirmed), None);
+        assert_eq!(slippage.record_confirmation(&slipped), Some(15));
+    }
+
+    #[test]
+    fn test_lowers_after_a_clean_window() {
+        let slippage = AdaptiveSlippage::new(10, 30).with_window(4);
+        for _ in 0..2 {
+            slippage.record_outcome(false);
+        }
+        assert_eq!(slippage.current(), 15);
+
+        for _ in 0..3 {
+            assert_eq!(slippage.record_outcome(true), None);
+        }
+        assert_eq!(slippage.record_outcome(true), Some(10));
+        for _ in 0..8 {
+            assert_eq!(slippage.record_outcome(true), None);
+        }
+        assert_eq!(slippage.current(), 10);
+    }
+}
@@ -0,0 +1,479 @@
+//! Reconnecting PumpPortal data stream
+//!
+//! PumpPortal's WebSocket sometimes goes quiet without a close frame. Nothing
+//! errors, `read.next()` just waits forever, and the bot looks alive while it
+//! receives no launches. `PumpPortalStream` pings the server on an interval and
+//! reconnects when nothing, not even a pong, has arrived within the stall
+//! timeout. Dropped connections reconnect the same way, and the subscriptions
+//! in effect are replayed on the new connection.
+//!
+//! Every message can also be captured to a file, and a stream can play a
+//! capture back instead of connecting (see [`crate::capture`]).
+
+use crate::capture::{Capture, Replay};
+use crate::{PumpPortalError, Result};
+use futures_util::stream::{SplitSink, SplitStream};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+/// PumpPortal's real-time data feed
+pub const DATA_WS_URL: &str = "wss://pumpportal.fun/api/data";
+
+/// Reconnect after this long without a message or pong
+pub const DEFAULT_STALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Wait between reconnect attempts
+pub const DEFAULT_RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+#[derive(Debug, Clone)]
+pub struct StreamConfig {
+    pub url: String,
+    pub ping_interval: Duration,
+    pub stall_timeout: Duration,
+    pub reconnect_delay: Duration,
+    /// Append every received message to this file
+    pub capture_path: Option<PathBuf>,
+    /// Play this capture file back instead of connecting
+    pub replay_path: Option<PathBuf>,
+}
+
+impl StreamConfig {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            ping_interval: DEFAULT_STALL_TIMEOUT / 3,
+            stall_timeout: DEFAULT_STALL_TIMEOUT,
+            reconnect_delay: DEFAULT_RECONNECT_DELAY,
+            capture_path: None,
+            replay_path: None,
+        }
+    }
+
+    /// Reconnect after `timeout` of silence, pinging three times within it
+    pub fn with_stall_timeout(mut self, timeout: Duration) -> Self {
+        self.stall_timeout = timeout;
+        self.ping_interval = timeout / 3;
+        self
+    }
+
+    pub fn with_reconnect_delay(mut self, delay: Duration) -> Self {
+        self.reconnect_delay = delay;
+        self
+    }
+
+    /// Append every received message to `path`, with its receive time
+    pub fn with_capture(mut self, path: impl Into<PathBuf>) -> Self {
+        self.capture_path = Some(path.into());
+        self
+    }
+
+    /// Play back a file written by [`with_capture`](Self::with_capture) at its
+    /// recorded pace instead of connecting; subscriptions are not sent anywhere
+    pub fn with_replay(mut self, path: impl Into<PathBuf>) -> Self {
+        self.replay_path = Some(path.into());
+        self
+    }
+}
+
+impl Default for StreamConfig {
+    fn default() -> Self {
+        Self::new(DATA_WS_URL)
+    }
+}
+
+/// Tracks how long a connection has been silent
+#[derive(Debug, Clone, Copy)]
+pub struct Watchdog {
+    timeout: Duration,
+    last_seen: Instant,
+}
+
+impl Watchdog {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            last_seen: Instant::now(),
+        }
+    }
+
+    /// Record a message, ping or pong from the server
+    pub fn touch(&mut self) {
+        self.last_seen = Instant::now();
+    }
+
+    pub fn silent_for(&self) -> Duration {
+        self.last_seen.elapsed()
+    }
+
+    pub fn is_stalled(&self) -> bool {
+        self.silent_for() >= self.timeout
+    }
+}
+
+/// A PumpPortal data feed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Subscription {
+    /// Token launches (`subscribeNewToken`)
+    NewTokens,
+    /// Tokens leaving the bonding curve (`subscribeMigration`)
+    Migrations,
+    /// Trades on these mints (`subscribeTokenTrade`)
+    TokenTrades(Vec<String>),
+    /// Trades by these wallets (`subscribeAccountTrade`)
+    AccountTrades(Vec<String>),
+}
+
+impl Subscription {
+    /// Message that starts this feed
+    pub fn subscribe_message(&self) -> Value {
+        self.message("subscribe")
+    }
+
+    /// Message that stops this feed; for mints and wallets, only the ones listed
+    pub fn unsubscribe_message(&self) -> Value {
+        self.message("unsubscribe")
+    }
+
+    fn message(&self, prefix: &str) -> Value {
+        let (feed, keys) = match self {
+            Self::NewTokens => ("NewToken", None),
+            Self::Migrations => ("Migration", None),
+            Self::TokenTrades(mints) => ("TokenTrade", Some(mints)),
+            Self::AccountTrades(wallets) => ("AccountTrade", Some(wallets)),
+        };
+        let method = format!("{}{}", prefix, feed);
+        match keys {
+            Some(keys) => json!({ "method": method, "keys": keys }),
+            None => json!({ "method": method }),
+        }
+    }
+}
+
+/// Subscriptions in effect, replayed after a reconnect
+///
+/// Unsubscribes cancel earlier subscribes instead of piling up, so a bot that
+/// follows and drops thousands of mints replays only what it still follows.
+#[derive(Debug, Default)]
+struct Subscriptions {
+    /// Subscribe method → keys (empty for keyless feeds like `subscribeNewToken`)
+    methods: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Subscriptions {
+    fn record(&mut self, message: &Value) {
+        let Some(method) = message["method"].as_str() else {
+            return;
+        };
+        let keys: Vec<String> = message["keys"]
+            .as_array()
+            .map(|keys| keys.iter().filter_map(|k| k.as_str().map(String::from)).collect())
+            .unwrap_or_default();
+
+        if let Some(feed) = method.strip_prefix("unsubscribe") {
+            let method = format!("subscribe{}", feed);
+            if keys.is_empty() {
+                self.methods.remove(&method);
+            } else if let Some(held) = self.methods.get_mut(&method) {
+                for key in &keys {
+                    held.remove(key);
+                }
+                if held.is_empty() {
+                    self.methods.remove(&method);
+                }
+            }
+        } else if method.starts_with("subscribe") {
+            self.methods.entry(method.to_string()).or_default().extend(keys);
+        }
+    }
+
+    fn replay(&self) -> Vec<Value> {
+        self.methods
+            .iter()
+            .map(|(method, keys)| {
+                if keys.is_empty() {
+                    json!({ "method": method })
+                } else {
+                    json!({ "method": method, "keys": keys })
+                }
+            })
+            .collect()
+    }
+}
+
+/// Sends messages on a `PumpPortalStream` from other tasks
+#[derive(Debug, Clone)]
+pub struct StreamHandle {
+    tx: mpsc::UnboundedSender<Value>,
+}
+
+impl StreamHandle {
+    /// Queue `message` for the stream; subscriptions are replayed after every reconnect
+    pub fn send(&self, message: Value) {
+        let _ = self.tx.send(message);
+    }
+
+    /// Start `subscription` now and again after every reconnect
+    pub fn subscribe(&self, subscription: &Subscription) {
+        self.send(subscription.subscribe_message());
+    }
+
+    /// Stop `subscription` and leave it out of later reconnects
+    pub fn unsubscribe(&self, subscription: &Subscription) {
+        self.send(subscription.unsubscribe_message());
+    }
+}
+
+enum Event {
+    Text(String),
+    Alive,
+    Outbound(Value),
+    Tick,
+    Lost(String),
+}
+
+/// PumpPortal WebSocket that pings, detects stalls, and reconnects on its own
+pub struct PumpPortalStream {
+    config: StreamConfig,
+    connection: Option<(SplitSink<Socket, Message>, SplitStream<Socket>)>,
+    watchdog: Watchdog,
+    ping: Interval,
+    subscriptions: Subscriptions,
+    handle: StreamHandle,
+    outbound: mpsc::UnboundedReceiver<Value>,
+    reconnects: u64,
+    capture: Option<Capture>,
+    replay: Option<Replay>,
+}
+
+impl PumpPortalStream {
+    /// Connect, failing if the first connection can't be made
+    ///
+    /// With a replay path, opens the capture file instead and never connects.
+    pub async fn connect(config: StreamConfig) -> Result<Self> {
+        let (connection, replay) = match &config.replay_path {
+            Some(path) => (None, Some(Replay::open(path).await?)),
+            None => {
+                let (socket, _) = connect_async(&config.url)
+                    .await
+                    .map_err(|e| PumpPortalError::WebSocket(e.to_string()))?;
+                (Some(socket.split()), None)
+            }
+        };
+        let capture = match (&config.capture_path, &replay) {
+            (Some(path), None) => Some(Capture::open(path)?),
+            _ => None,
+        };
+        let (tx, outbound) = mpsc::unbounded_channel();
+        let mut ping = tokio::time::interval_at(Instant::now() + config.ping_interval, config.ping_interval);
+        ping.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        Ok(Self {
+            watchdog: Watchdog::new(config.stall_timeout),
+            connection,
+            ping,
+            subscriptions: Subscriptions::default(),
+            handle: StreamHandle { tx },
+            outbound,
+            reconnects: 0,
+            capture,
+            replay,
+            config,
+        })
+    }
+
+    /// Handle for sending subscriptions from other tasks
+    pub fn handle(&self) -> StreamHandle {
+        self.handle.clone()
+    }
+
+    /// Start `subscription` now and again after every reconnect
+    pub fn subscribe(&self, subscription: &Subscription) {
+        self.handle.subscribe(subscription);
+    }
+
+    /// Stop `subscription` and leave it out of later reconnects
+    pub fn unsubscribe(&self, subscription: &Subscription) {
+        self.handle.unsubscribe(subscription);
+    }
+
+    /// Times the stream has reconnected since `connect`
+    pub fn reconnects(&self) -> u64 {
+        self.reconnects
+    }
+
+    /// Next text message from the server
+    ///
+    /// Never ends: drops and stalls are logged and reconnected through. Queued
+    /// messages are only sent while a caller is waiting here.
+    pub async fn next(&mut self) -> String {
+        if self.replay.is_some() {
+            return self.next_replayed().await;
+        }
+
+        loop {
+            let Some((sink, source)) = self.connection.as_mut() else {
+                self.reconnect().await;
+                continue;
+            };
+
+            let event = tokio::select! {
+                msg = source.next() => match msg {
+                    Some(Ok(Message::Text(text))) => Event::Text(text),
+                    Some(Ok(Message::Close(frame))) => Event::Lost(format!("closed by server: {:?}", frame)),
+                    Some(Ok(_)) => Event::Alive,
+                    Some(Err(e)) => Event::Lost(e.to_string()),
+                    None => Event::Lost("stream ended".to_string()),
+                },
+                Some(message) = self.outbound.recv() => Event::Outbound(message),
+                _ = self.ping.tick() => Event::Tick,
+            };
+
+            match event {
+                Event::Text(text) => {
+                    self.watchdog.touch();
+                    if let Some(capture) = &mut self.capture {
+                        capture.record(&text);
+                    }
+                    return text;
+                }
+                Event::Alive => self.watchdog.touch(),
+                Event::Outbound(message) => {
+                    self.subscriptions.record(&message);
+                    // A failed send is covered by the replay after reconnecting
+                    let sent = sink.send(Message::Text(message.to_string())).await;
+                    if let Err(e) = sent {
+                        self.drop_connection(&e.to_string());
+                    }
+                }
+                Event::Tick => {
+                    if self.watchdog.is_stalled() {
+                        let reason = format!("no message for {:.0?}", self.watchdog.silent_for());
+                        self.drop_connection(&reason);
+                    } else {
+                        let sent = sink.send(Message::Ping(Vec::new())).await;
+                        if let Err(e) = sent {
+                            self.drop_connection(&e.to_string());
+                        }
+                    }
+                }
+                Event::Lost(reason) => self.drop_connection(&reason),
+            }
+        }
+    }
+
+    /// Next message from the replay file; once it runs out, waits forever
+    /// like a live stream with nothing to say
+    async fn next_replayed(&mut self) -> String {
+        while let Ok(message) = self.outbound.try_recv() {
+            self.subscriptions.record(&message);
+        }
+        if let Some(replay) = self.replay.as_mut().filter(|replay| !replay.is_finished()) {
+            if let Some(text) = replay.next().await {
+                return text;
+            }
+            log::info!("📼 Replay finished after {} messages", replay.replayed());
+        }
+        std::future::pending().await
+    }
+
+    fn drop_connection(&mut self, reason: &str) {
+        log::warn!("📡 PumpPortal stream lost ({}), reconnecting", reason);
+        self.connection = None;
+    }
+
+    /// Reconnect and replay subscriptions, retrying until it works
+    async fn reconnect(&mut self) {
+        loop {
+            tokio::time::sleep(self.config.reconnect_delay).await;
+
+            let (mut sink, source) = match connect_async(&self.config.url).await {
+                Ok((socket, _)) => socket.split(),
+                Err(e) => {
+                    log::warn!("📡 Reconnect failed: {}, retrying in {:?}", e, self.config.reconnect_delay);
+                    continue;
+                }
+            };
+
+            let replay = self.subscriptions.replay();
+            let mut replayed = true;
+            for message in &replay {
+                if let Err(e) = sink.send(Message::Text(message.to_string())).await {
+                    log::warn!("📡 Resubscribe failed: {}, retrying in {:?}", e, self.config.reconnect_delay);
+                    replayed = false;
+                    break;
+                }
+            }
+            if !replayed {
+                continue;
+            }
+
+            self.reconnects += 1;
+            self.watchdog.touch();
+            self.ping.reset();
+            self.connection = Some((sink, source));
+            log::info!("📡 Reconnected to PumpPortal, {} subscriptions restored", replay.len());
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_watchdog_stalls_after_timeout() {
+        let mut watchdog = Watchdog::new(Duration::from_millis(50));
+        assert!(!watchdog.is_stalled());
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(watchdog.is_stalled());
+
+        watchdog.touch();
+        assert!(!watchdog.is_stalled());
+    }
+
+    #[test]
+    fn test_subscriptions_replay_what_is_still_followed() {
+        let mut subs = Subscriptions::default();
+        subs.record(&json!({ "method": "subscribeNewToken" }));
+        subs.record(&json!({ "method": "subscribeTokenTrade", "keys": ["mintA", "mintB"] }));
+        subs.record(&json!({ "method": "subscribeTokenTrade", "keys": ["mintC"] }));
+        subs.record(&json!({ "method": "unsubscribeTokenTrade", "keys": ["mintA", "mintC"] }));
+        subs.record(&json!({ "method": "subscribeAccountTrade", "keys": ["wallet"] }));
+        subs.record(&json!({ "method": "unsubscribeAccountTrade", "keys": ["wallet"] }));
+
+        assert_eq!(
+            subs.replay(),
+            vec![
+                json!({ "method": "subscribeNewToken" }),
+                json!({ "method": "subscribeTokenTrade", "keys": ["mintB"] }),
+            ]
+        );
+
+        subs.record(&json!({ "method": "unsubscribeNewToken" }));
+        subs.record(&json!({ "method": "unsubscribeTokenTrade", "keys": ["mintB"] }));
+        assert!(subs.replay().is_empty());
+    }
+
+    #[test]
+    fn test_subscription_messages() {
+        assert_eq!(Subscription::NewTokens.subscribe_message(), json!({ "method": "subscribeNewToken" }));
+        assert_eq!(Subscription::Migrations.unsubscribe_message(), json!({ "method": "unsubscribeMigration" }));
+
+        let trades = Subscription::TokenTrades(vec!["mintA".to_string()]);
+        assert_eq!(trades.subscribe_message(), json!({ "method": "subscribeTokenTrade", "keys": ["mintA"] }));
+        let wallets = Subscription::AccountTrades(vec!["wallet".to_string()]);
+        assert_eq!(wallets.unsubscribe_message(), json!({ "method": "unsubscribeAccountTrade", "keys": ["wallet"] }));
+    }
+}
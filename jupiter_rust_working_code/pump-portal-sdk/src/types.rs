@@ -13,7 +13,7 @@ pub enum TradeAction {
 }
 
 /// Pool/Exchange options
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum Pool {
     /// Pump.fun exchange
@@ -32,6 +32,21 @@ pub enum Pool {
     Auto,
 }
 
+impl Pool {
+    /// Pool whose on-chain program has this id, if it's one PumpPortal routes to.
+    /// Bonk launches run on the LaunchLab program, so they map to `Launchlab`.
+    pub fn from_program_id(program_id: &str) -> Option<Pool> {
+        match program_id {
+            "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P" => Some(Pool::Pump),
+            "pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA" => Some(Pool::PumpAmm),
+            "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8" => Some(Pool::Raydium),
+            "CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C" => Some(Pool::RaydiumCpmm),
+            "LanMV9sAd7wArD4vJFi2qDdfnVhFxYSUg6eADduJ3uj" => Some(Pool::Launchlab),
+            _ => None,
+        }
+    }
+}
+
 impl Default for Pool {
     fn default() -> Self {
         Pool::Pump
@@ -74,6 +89,66 @@ pub struct TradeRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(serialize_with = "serialize_optional_bool_as_string")]
     pub jito_only: Option<bool>,
+
+    /// Largest price impact a buy may have, in basis points (checked locally, not sent)
+    #[serde(skip)]
+    pub max_price_impact_bps: Option<u32>,
+
+    /// Bonding-curve reserves the price impact is estimated from (not sent)
+    #[serde(skip)]
+    pub curve: Option<BondingCurve>,
+}
+
+/// Decimals of every pump.fun mint; event token amounts are already in UI units
+pub const PUMP_TOKEN_DECIMALS: u8 = 6;
+
+/// Virtual reserves of a pump.fun bonding curve, as reported on launch and trade events
+/// (`vSolInBondingCurve` / `vTokensInBondingCurve`)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BondingCurve {
+    /// Virtual SOL reserves, in SOL
+    pub virtual_sol_reserves: f64,
+    /// Virtual token reserves, in tokens
+    pub virtual_token_reserves: f64,
+}
+
+impl BondingCurve {
+    pub fn new(virtual_sol_reserves: f64, virtual_token_reserves: f64) -> Self {
+        Self {
+            virtual_sol_reserves,
+            virtual_token_reserves,
+        }
+    }
+
+    /// Spot price in SOL per token
+    pub fn price(&self) -> f64 {
+        self.virtual_sol_reserves / self.virtual_token_reserves
+    }
+
+    /// Tokens a buy of `sol_in` receives on the constant-product curve (before fees)
+    pub fn tokens_out(&self, sol_in: f64) -> f64 {
+        self.virtual_token_reserves * sol_in / (self.virtual_sol_reserves + sol_in)
+    }
+
+    /// SOL needed to buy `tokens` off the curve, or `None` if the curve doesn't hold that many
+    pub fn sol_for_tokens(&self, tokens: f64) -> Option<f64> {
+        (tokens < self.virtual_token_reserves)
+            .then(|| self.virtual_sol_reserves * tokens / (self.virtual_token_reserves - tokens))
+    }
+
+    /// Price impact of buying with `sol_in`, in basis points: how far the average fill
+    /// price sits above the spot price. On a constant-product curve that is `sol_in / S`.
+    pub fn buy_impact_bps(&self, sol_in: f64) -> f64 {
+        sol_in / self.virtual_sol_reserves * 10_000.0
+    }
+
+    pub(crate) fn is_valid(&self) -> bool {
+        self.virtual_sol_reserves.is_finite()
+            && self.virtual_sol_reserves > 0.0
+            && self.virtual_token_reserves.is_finite()
+            && self.virtual_token_reserves > 0.0
+    }
 }
 
 /// Helper function to serialize bool as string
@@ -98,6 +173,28 @@ where
     }
 }
 
+/// Accept an amount sent either as a JSON string or a number
+fn deserialize_optional_amount<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(match Option::<serde_json::Value>::deserialize(deserializer)? {
+        Some(serde_json::Value::String(s)) => Some(s),
+        Some(serde_json::Value::Number(n)) => Some(n.to_string()),
+        _ => None,
+    })
+}
+
+/// Read a pool name, treating one this SDK doesn't know yet as unreported
+/// rather than failing the whole response
+fn deserialize_optional_pool<'de, D>(deserializer: D) -> Result<Option<Pool>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(Option::<serde_json::Value>::deserialize(deserializer)?
+        .and_then(|value| serde_json::from_value(value).ok()))
+}
+
 /// Trade response from the API
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeResponse {
@@ -109,6 +206,25 @@ pub struct TradeResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
 
+    /// Pool the trade was routed through, when the API reports one we know.
+    /// Otherwise see [`PumpPortalClient::pool_used`](crate::PumpPortalClient::pool_used).
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_optional_pool"
+    )]
+    pub pool: Option<Pool>,
+
+    /// Input amount the API executed (SOL for buys, tokens or a percentage for sells)
+    #[serde(
+        default,
+        rename = "amount",
+        alias = "inAmount",
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_optional_amount"
+    )]
+    pub input_amount: Option<String>,
+
     /// Additional response fields (API may include extra data)
     #[serde(flatten)]
     pub extra: serde_json::Value,
@@ -127,9 +243,16 @@ impl TradeRequest {
             pool: None,
             skip_preflight: Some(true),
             jito_only: None,
+            max_price_impact_bps: None,
+            curve: None,
         }
     }
 
+    /// Create a buy request for exactly `token_amount` tokens instead of a SOL amount
+    pub fn buy_tokens(mint: String, token_amount: f64, slippage: u32, priority_fee: f64) -> Self {
+        Self::buy(mint, token_amount, slippage, priority_fee).with_denominated_in_sol(false)
+    }
+
     /// Create a new sell request
     pub fn sell(mint: String, token_amount: String, slippage: u32, priority_fee: f64) -> Self {
         Self {
@@ -142,6 +265,8 @@ impl TradeRequest {
             pool: None,
             skip_preflight: Some(true),
             jito_only: None,
+            max_price_impact_bps: None,
+            curve: None,
         }
     }
 
@@ -151,6 +276,12 @@ impl TradeRequest {
         self
     }
 
+    /// Whether `amount` is SOL (true) or tokens (false)
+    pub fn with_denominated_in_sol(mut self, in_sol: bool) -> Self {
+        self.denominated_in_sol = in_sol;
+        self
+    }
+
     /// Enable/disable preflight simulation
     pub fn with_skip_preflight(mut self, skip: bool) -> Self {
         self.skip_preflight = Some(skip);
@@ -162,4 +293,336 @@ impl TradeRequest {
         self.jito_only = Some(jito);
         self
     }
+
+    /// Reject the buy if its estimated price impact on the bonding curve exceeds `bps`.
+    /// Needs the curve reserves from [`with_curve_reserves`](Self::with_curve_reserves);
+    /// ignored for sells.
+    pub fn with_max_price_impact_bps(mut self, bps: u32) -> Self {
+        self.max_price_impact_bps = Some(bps);
+        self
+    }
+
+    /// Bonding-curve reserves (SOL, tokens) to estimate price impact from
+    pub fn with_curve_reserves(mut self, virtual_sol_reserves: f64, virtual_token_reserves: f64) -> Self {
+        self.curve = Some(BondingCurve::new(virtual_sol_reserves, virtual_token_reserves));
+        self
+    }
+
+    /// Estimated price impact of this buy in basis points, when curve reserves are set.
+    /// `None` for sells, percentage amounts, or an amount larger than the curve holds.
+    pub fn price_impact_bps(&self) -> Option<f64> {
+        if !matches!(self.action, TradeAction::Buy) {
+            return None;
+        }
+        let curve = self.curve.filter(BondingCurve::is_valid)?;
+        let amount = self.amount.parse::<f64>().ok()?;
+        let sol_in = if self.denominated_in_sol {
+            amount
+        } else {
+            curve.sol_for_tokens(amount)?
+        };
+        Some(curve.buy_impact_bps(sol_in))
+    }
+
+    /// JSON body sent to the trade endpoint
+    pub fn to_json(&self) -> crate::Result<serde_json::Value> {
+        Ok(serde_json::to_value(self)?)
+    }
+
+    /// Check the request for obviously invalid parameters before sending
+    ///
+    /// Amounts must be a positive number, or a percentage in `(0, 100]`. A
+    /// percentage is of the tokens held, so only a token-denominated sell takes one.
+    pub fn validate(&self) -> crate::Result<()> {
+        if self.mint.trim().is_empty() {
+            return Err(crate::PumpPortalError::InvalidParameter(
+                "mint must not be empty".to_string(),
+            ));
+        }
+
+        let amount_ok = match self.amount.strip_suffix('%') {
+            Some(pct) => pct
+                .parse::<f64>()
+                .map(|p| p > 0.0 && p <= 100.0)
+                .unwrap_or(false),
+            None => self
+                .amount
+                .parse::<f64>()
+                .map(|a| a.is_finite() && a > 0.0)
+                .unwrap_or(false),
+        };
+        if !amount_ok {
+            return Err(crate::PumpPortalError::InvalidParameter(format!(
+                "invalid amount: {}",
+                self.amount
+            )));
+        }
+
+        if self.amount.ends_with('%') && (self.denominated_in_sol || matches!(self.action, TradeAction::Buy)) {
+            return Err(crate::PumpPortalError::InvalidParameter(format!(
+                "percentage amount {} needs a token-denominated sell",
+                self.amount
+            )));
+        }
+
+        if self.slippage > 100 {
+            return Err(crate::PumpPortalError::InvalidParameter(format!(
+                "slippage must be at most 100%, got {}",
+                self.slippage
+            )));
+        }
+
+        if !self.priority_fee.is_finite() || self.priority_fee < 0.0 {
+            return Err(crate::PumpPortalError::InvalidParameter(format!(
+                "invalid priority fee: {}",
+                self.priority_fee
+            )));
+        }
+
+        if let (TradeAction::Buy, Some(max_bps)) = (&self.action, self.max_price_impact_bps) {
+            if !self.curve.is_some_and(|curve| curve.is_valid()) {
+                return Err(crate::PumpPortalError::InvalidParameter(
+                    "max price impact set without valid bonding-curve reserves".to_string(),
+                ));
+            }
+            let impact_bps = self.price_impact_bps().unwrap_or(f64::INFINITY);
+            if impact_bps > max_bps as f64 {
+                return Err(crate::PumpPortalError::PriceImpactTooHigh {
+                    impact_bps: impact_bps.min(u32::MAX as f64).round() as u32,
+                    max_bps,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const MINT: &str = "TokenMintAddress";
+
+    #[test]
+    fn test_buy_body() {
+        let request = TradeRequest::buy(MINT.to_string(), 0.1, 10, 0.0001);
+        assert_eq!(
+            request.to_json().unwrap(),
+            json!({
+                "action": "buy",
+                "mint": MINT,
+                "amount": "0.1",
+                "denominatedInSol": "true",
+                "slippage": 10,
+                "priorityFee": 0.0001,
+                "skipPreflight": "true",
+            })
+        );
+    }
+
+    #[test]
+    fn test_sell_body() {
+        let request = TradeRequest::sell(MINT.to_string(), "100%".to_string(), 20, 0.0005);
+        assert_eq!(
+            request.to_json().unwrap(),
+            json!({
+                "action": "sell",
+                "mint": MINT,
+                "amount": "100%",
+                "denominatedInSol": "false",
+                "slippage": 20,
+                "priorityFee": 0.0005,
+                "skipPreflight": "true",
+            })
+        );
+    }
+
+    #[test]
+    fn test_buy_tokens_body() {
+        let request = TradeRequest::buy_tokens(MINT.to_string(), 1_000_000.0, 10, 0.0001);
+        let body = request.to_json().unwrap();
+        assert_eq!(body["amount"], json!("1000000"));
+        assert_eq!(body["denominatedInSol"], json!("false"));
+        assert!(request.validate().is_ok());
+
+        // Back to SOL, and a sell for a SOL amount
+        let body = request.with_denominated_in_sol(true).to_json().unwrap();
+        assert_eq!(body["denominatedInSol"], json!("true"));
+        let sell = TradeRequest::sell(MINT.to_string(), "0.5".to_string(), 10, 0.0001).with_denominated_in_sol(true);
+        assert!(sell.validate().is_ok());
+    }
+
+    #[test]
+    fn test_percentage_needs_token_denominated_sell() {
+        let sell = TradeRequest::sell(MINT.to_string(), "50%".to_string(), 10, 0.0001);
+        assert!(sell.validate().is_ok());
+        assert!(matches!(
+            sell.with_denominated_in_sol(true).validate(),
+            Err(crate::PumpPortalError::InvalidParameter(_))
+        ));
+
+        let mut buy = TradeRequest::buy_tokens(MINT.to_string(), 1.0, 10, 0.0001);
+        buy.amount = "50%".to_string();
+        assert!(matches!(buy.validate(), Err(crate::PumpPortalError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_pool_names() {
+        let pools = [
+            (Pool::Pump, "pump"),
+            (Pool::Raydium, "raydium"),
+            (Pool::PumpAmm, "pump-amm"),
+            (Pool::Launchlab, "launchlab"),
+            (Pool::RaydiumCpmm, "raydium-cpmm"),
+            (Pool::Bonk, "bonk"),
+            (Pool::Auto, "auto"),
+        ];
+
+        for (pool, name) in pools {
+            for request in [
+                TradeRequest::buy(MINT.to_string(), 0.1, 10, 0.0001),
+                TradeRequest::sell(MINT.to_string(), "50%".to_string(), 10, 0.0001),
+            ] {
+                let body = request.with_pool(pool).to_json().unwrap();
+                assert_eq!(body["pool"], json!(name));
+            }
+        }
+    }
+
+    #[test]
+    fn test_jito_and_preflight_flags() {
+        for jito in [true, false] {
+            for skip in [true, false] {
+                let body = TradeRequest::buy(MINT.to_string(), 0.1, 10, 0.0001)
+                    .with_jito_only(jito)
+                    .with_skip_preflight(skip)
+                    .to_json()
+                    .unwrap();
+
+                // The API expects these booleans as strings
+                assert_eq!(body["jitoOnly"], json!(jito.to_string()));
+                assert_eq!(body["skipPreflight"], json!(skip.to_string()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_options_body() {
+        let request = TradeRequest::sell(MINT.to_string(), "1000".to_string(), 15, 0.001)
+            .with_pool(Pool::RaydiumCpmm)
+            .with_skip_preflight(false)
+            .with_jito_only(true);
+
+        assert_eq!(
+            request.to_json().unwrap(),
+            json!({
+                "action": "sell",
+                "mint": MINT,
+                "amount": "1000",
+                "denominatedInSol": "false",
+                "slippage": 15,
+                "priorityFee": 0.001,
+                "pool": "raydium-cpmm",
+                "skipPreflight": "false",
+                "jitoOnly": "true",
+            })
+        );
+    }
+
+    #[test]
+    fn test_unset_options_are_omitted() {
+        let mut request = TradeRequest::buy(MINT.to_string(), 0.1, 10, 0.0001);
+        request.skip_preflight = None;
+
+        let body = request.to_json().unwrap();
+        let fields = body.as_object().unwrap();
+        assert!(!fields.contains_key("pool"));
+        assert!(!fields.contains_key("skipPreflight"));
+        assert!(!fields.contains_key("jitoOnly"));
+    }
+
+    #[test]
+    fn test_response_reads_pool_and_amount() {
+        let echoed: TradeResponse = serde_json::from_value(json!({
+            "signature": "5sig",
+            "pool": "pump-amm",
+            "amount": 0.1,
+            "slot": 123,
+        }))
+        .unwrap();
+        assert_eq!(echoed.pool, Some(Pool::PumpAmm));
+        assert_eq!(echoed.input_amount.as_deref(), Some("0.1"));
+        assert_eq!(echoed.extra["slot"], 123);
+
+        let bare: TradeResponse = serde_json::from_value(json!({ "signature": "5sig" })).unwrap();
+        assert_eq!(bare.pool, None);
+        assert_eq!(bare.input_amount, None);
+
+        let unknown: TradeResponse = serde_json::from_value(json!({
+            "signature": "5sig",
+            "pool": "meteora-dbc",
+        }))
+        .unwrap();
+        assert_eq!(unknown.signature.as_deref(), Some("5sig"));
+        assert_eq!(unknown.pool, None);
+    }
+
+    #[test]
+    fn test_pool_from_program_id() {
+        assert_eq!(
+            Pool::from_program_id("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P"),
+            Some(Pool::Pump)
+        );
+        assert_eq!(Pool::from_program_id("11111111111111111111111111111111"), None);
+    }
+
+    #[test]
+    fn test_bonding_curve_math() {
+        // Fresh pump.fun curve: 30 SOL / 1.073B tokens virtual
+        let curve = BondingCurve::new(30.0, 1_073_000_000.0);
+
+        assert!((curve.buy_impact_bps(0.3) - 100.0).abs() < 1e-9);
+        let tokens = curve.tokens_out(0.3);
+        assert!((curve.sol_for_tokens(tokens).unwrap() - 0.3).abs() < 1e-9);
+        // Average fill price is `impact` above spot
+        assert!(((0.3 / tokens) / curve.price() - 1.01).abs() < 1e-9);
+        assert_eq!(curve.sol_for_tokens(2_000_000_000.0), None);
+    }
+
+    #[test]
+    fn test_max_price_impact_guard() {
+        let buy = |sol: f64| {
+            TradeRequest::buy(MINT.to_string(), sol, 10, 0.0001)
+                .with_curve_reserves(30.0, 1_073_000_000.0)
+                .with_max_price_impact_bps(200)
+        };
+
+        assert!(buy(0.5).validate().is_ok());
+        assert!(matches!(
+            buy(1.5).validate(),
+            Err(crate::PumpPortalError::PriceImpactTooHigh { impact_bps: 500, max_bps: 200 })
+        ));
+
+        // Token-denominated buys are converted to SOL on the curve
+        let tokens = TradeRequest::buy_tokens(MINT.to_string(), 100_000_000.0, 10, 0.0001)
+            .with_curve_reserves(30.0, 1_073_000_000.0)
+            .with_max_price_impact_bps(200);
+        assert!(matches!(
+            tokens.validate(),
+            Err(crate::PumpPortalError::PriceImpactTooHigh { .. })
+        ));
+
+        // A limit without reserves can't be checked
+        let no_curve = TradeRequest::buy(MINT.to_string(), 0.1, 10, 0.0001).with_max_price_impact_bps(200);
+        assert!(matches!(no_curve.validate(), Err(crate::PumpPortalError::InvalidParameter(_))));
+
+        // Sells aren't guarded, and the guard never reaches the request body
+        let sell = TradeRequest::sell(MINT.to_string(), "100%".to_string(), 10, 0.0001).with_max_price_impact_bps(1);
+        assert!(sell.validate().is_ok());
+        let body = buy(0.5).to_json().unwrap();
+        assert!(!body.as_object().unwrap().contains_key("maxPriceImpactBps"));
+        assert!(!body.as_object().unwrap().contains_key("curve"));
+    }
 }
@@ -0,0 +1,295 @@
+//! `PumpPortalClient::trade` against a mock PumpPortal endpoint
+
+use pump_portal_sdk::{BalanceCheck, BondingCurve, Pool, PumpPortalClient, PumpPortalError, Quote, TradeRequest};
+use serde_json::json;
+use std::time::Duration;
+use wiremock::matchers::{body_json, body_partial_json, header, method, path, query_param};
+use wiremock::{Mock, MockBuilder, MockServer, ResponseTemplate};
+
+const API_KEY: &str = "test-key";
+const MINT: &str = "7GCihgDB8fe6KNjn2MYtkzZcRjQy3t9GHdC8uHYmW2hr";
+const SIGNATURE: &str = "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW";
+
+async fn setup() -> (MockServer, PumpPortalClient) {
+    let server = MockServer::start().await;
+    let client = PumpPortalClient::new(API_KEY.to_string())
+        .with_base_url(&format!("{}/api/trade", server.uri()));
+    (server, client)
+}
+
+fn trade_endpoint() -> MockBuilder {
+    Mock::given(method("POST"))
+        .and(path("/api/trade"))
+        .and(query_param("api-key", API_KEY))
+}
+
+#[tokio::test]
+async fn test_buy_sends_expected_body() {
+    let (server, client) = setup().await;
+    trade_endpoint()
+        .and(body_json(json!({
+            "action": "buy",
+            "mint": MINT,
+            "amount": "0.05",
+            "denominatedInSol": "true",
+            "slippage": 10,
+            "priorityFee": 0.0001,
+            "skipPreflight": "true",
+            "jitoOnly": "true"
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "signature": SIGNATURE })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let request = TradeRequest::buy(MINT.to_string(), 0.05, 10, 0.0001).with_jito_only(true);
+    let response = client.trade(request).await.unwrap();
+
+    assert_eq!(response.signature.as_deref(), Some(SIGNATURE));
+    assert!(response.error.is_none());
+}
+
+#[tokio::test]
+async fn test_sell_sends_expected_body() {
+    let (server, client) = setup().await;
+    trade_endpoint()
+        .and(body_json(json!({
+            "action": "sell",
+            "mint": MINT,
+            "amount": "100%",
+            "denominatedInSol": "false",
+            "slippage": 20,
+            "priorityFee": 0.0001,
+            "skipPreflight": "true"
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "signature": SIGNATURE })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = client
+        .sell(MINT.to_string(), "100%".to_string(), 20, 0.0001)
+        .await
+        .unwrap();
+
+    assert_eq!(response.signature.as_deref(), Some(SIGNATURE));
+}
+
+#[tokio::test]
+async fn test_error_body_is_api_error() {
+    let (server, client) = setup().await;
+    trade_endpoint()
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "error": "Invalid mint" })))
+        .mount(&server)
+        .await;
+
+    let result = client.buy(MINT.to_string(), 0.05, 10, 0.0001).await;
+
+    assert!(matches!(result, Err(PumpPortalError::ApiError(msg)) if msg == "Invalid mint"));
+}
+
+#[tokio::test]
+async fn test_429_is_rate_limited() {
+    let (server, client) = setup().await;
+    trade_endpoint()
+        .respond_with(
+            ResponseTemplate::new(429)
+                .insert_header("Retry-After", "2")
+                .set_body_string("Too Many Requests"),
+        )
+        .mount(&server)
+        .await;
+
+    let err = client.buy(MINT.to_string(), 0.05, 10, 0.0001).await.unwrap_err();
+
+    assert!(matches!(err, PumpPortalError::RateLimited { .. }));
+    assert_eq!(err.retry_after(), Some(Duration::from_secs(2)));
+    assert!(err.is_retryable());
+}
+
+#[tokio::test]
+async fn test_500_is_server_error() {
+    let (server, client) = setup().await;
+    trade_endpoint()
+        .respond_with(ResponseTemplate::new(500).set_body_string("Internal Server Error"))
+        .mount(&server)
+        .await;
+
+    let err = client.buy(MINT.to_string(), 0.05, 10, 0.0001).await.unwrap_err();
+
+    assert!(matches!(err, PumpPortalError::ServerError(500)));
+    assert!(err.is_retryable());
+}
+
+#[tokio::test]
+async fn test_custom_http_client_is_used() {
+    let server = MockServer::start().await;
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert("x-proxy-auth", "secret".parse().unwrap());
+    let http = reqwest::Client::builder().default_headers(headers).build().unwrap();
+    let client = PumpPortalClient::new(API_KEY.to_string())
+        .with_http_client(http)
+        .with_base_url(&format!("{}/api/trade", server.uri()));
+
+    trade_endpoint()
+        .and(header("x-proxy-auth", "secret"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "signature": SIGNATURE })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = client.buy(MINT.to_string(), 0.05, 10, 0.0001).await.unwrap();
+    assert_eq!(response.signature.as_deref(), Some(SIGNATURE));
+}
+
+#[tokio::test]
+async fn test_timeout_applies_to_custom_http_client() {
+    let server = MockServer::start().await;
+    let client = PumpPortalClient::new(API_KEY.to_string())
+        .with_http_client(reqwest::Client::new())
+        .with_timeout(Duration::from_millis(100))
+        .with_base_url(&format!("{}/api/trade", server.uri()));
+
+    trade_endpoint()
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "signature": SIGNATURE }))
+                .set_delay(Duration::from_millis(500)),
+        )
+        .mount(&server)
+        .await;
+
+    let err = client.buy(MINT.to_string(), 0.05, 10, 0.0001).await.unwrap_err();
+    assert!(matches!(err, PumpPortalError::Timeout(_)));
+}
+
+#[tokio::test]
+async fn test_buy_dedup_refuses_resend_after_timeout() {
+    let server = MockServer::start().await;
+    let client = PumpPortalClient::new(API_KEY.to_string())
+        .with_base_url(&format!("{}/api/trade", server.uri()))
+        .with_timeout(Duration::from_millis(100))
+        .with_buy_dedup(Duration::from_secs(30));
+    // The first buy goes through server-side, but too slowly for the client
+    trade_endpoint()
+        .and(body_partial_json(json!({ "action": "buy" })))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "signature": SIGNATURE }))
+                .set_delay(Duration::from_millis(500)),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = client.buy(MINT.to_string(), 0.05, 10, 0.0001).await.unwrap_err();
+    assert!(matches!(err, PumpPortalError::Timeout(_)));
+    assert!(err.is_retryable());
+
+    let retry = client.clone().buy(MINT.to_string(), 0.05, 10, 0.0001).await.unwrap_err();
+    assert!(matches!(retry, PumpPortalError::DuplicateBuy { ref mint, .. } if mint == MINT));
+    assert!(!retry.is_retryable());
+
+    // Sells aren't guarded
+    trade_endpoint()
+        .and(body_partial_json(json!({ "action": "sell" })))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(2)
+        .mount(&server)
+        .await;
+    for _ in 0..2 {
+        let err = client.sell(MINT.to_string(), "100%".to_string(), 10, 0.0001).await.unwrap_err();
+        assert!(matches!(err, PumpPortalError::ServerError(500)));
+    }
+}
+
+#[tokio::test]
+async fn test_slippage_failure_retried_once_at_higher_slippage() {
+    let (server, client) = setup().await;
+    let client = client.with_slippage_retry(25).with_buy_dedup(Duration::from_secs(30));
+    let slipped = ResponseTemplate::new(400)
+        .set_body_json(json!({ "error": "custom program error: 0x1772 (TooMuchSolRequired)" }));
+    trade_endpoint()
+        .and(body_partial_json(json!({ "slippage": 10 })))
+        .respond_with(slipped.clone())
+        .expect(1)
+        .mount(&server)
+        .await;
+    trade_endpoint()
+        .and(body_partial_json(json!({ "slippage": 20 })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "signature": SIGNATURE })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = client.buy(MINT.to_string(), 0.05, 10, 0.0001).await.unwrap();
+    assert_eq!(response.signature.as_deref(), Some(SIGNATURE));
+
+    // Already at the ceiling: no retry, the slippage error comes back
+    trade_endpoint()
+        .and(body_partial_json(json!({ "slippage": 25 })))
+        .respond_with(slipped)
+        .expect(1)
+        .mount(&server)
+        .await;
+    let err = client.sell(MINT.to_string(), "100%".to_string(), 25, 0.0001).await.unwrap_err();
+    assert!(matches!(err, PumpPortalError::SlippageExceeded(_)));
+}
+
+#[tokio::test]
+async fn test_balance_check_caches_and_debits() {
+    let (server, client) = setup().await;
+    Mock::given(method("POST"))
+        .and(path("/rpc"))
+        .and(body_partial_json(json!({ "method": "getBalance" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": { "context": { "slot": 1 }, "value": 120_000_000u64 }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let balance = BalanceCheck::new("Wallet", &format!("{}/rpc", server.uri())).with_ttl(Duration::from_secs(60));
+
+    // 0.12 SOL pays for two 0.05 buys from one fetch, not a third
+    let buy = TradeRequest::buy(MINT.to_string(), 0.05, 10, 0.0001);
+    balance.ensure(&client, &buy).await.unwrap();
+    balance.ensure(&client, &buy).await.unwrap();
+    let err = balance.ensure(&client, &buy).await.unwrap_err();
+    assert!(matches!(err, PumpPortalError::InsufficientFunds));
+}
+
+#[tokio::test]
+async fn test_quote_reads_bonding_curve_account() {
+    let (server, client) = setup().await;
+    let account = |data: &str| {
+        ResponseTemplate::new(200).set_body_json(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": { "context": { "slot": 1 }, "value": { "data": [data, "base64"], "owner": "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P" } }
+        }))
+    };
+    let rpc = |curve: &'static str| {
+        Mock::given(method("POST"))
+            .and(path("/rpc"))
+            .and(body_partial_json(json!({ "method": "getAccountInfo", "params": [curve] })))
+    };
+    // 30 SOL / 1.073B tokens, then the same curve marked complete
+    rpc("Curve")
+        .respond_with(account("AAAAAAAAAAAAENhH488DAACsI/wGAAAAAHjF+1HRAgAAAAAAAAAAAACAxqR+jQMAAA=="))
+        .mount(&server)
+        .await;
+    rpc("Migrated")
+        .respond_with(account("AAAAAAAAAAAAENhH488DAACsI/wGAAAAAHjF+1HRAgAAAAAAAAAAAACAxqR+jQMAAQ=="))
+        .mount(&server)
+        .await;
+    let rpc_url = format!("{}/rpc", server.uri());
+
+    let quote = client.quote(MINT, 0.5, Pool::Pump, "Curve", &rpc_url).await.unwrap();
+    let expected = Quote::from_curve(MINT, 0.5, Pool::Pump, &BondingCurve::new(30.0, 1_073_000_000.0)).unwrap();
+    assert_eq!(quote, expected);
+
+    let err = client.quote(MINT, 0.5, Pool::Pump, "Migrated", &rpc_url).await.unwrap_err();
+    assert!(matches!(err, PumpPortalError::InvalidParameter(_)));
+}
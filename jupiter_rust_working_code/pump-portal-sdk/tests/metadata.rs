@@ -0,0 +1,47 @@
+//! `fetch_token_metadata_via` against a mock IPFS gateway
+
+use pump_portal_sdk::metadata::fetch_token_metadata_via;
+use pump_portal_sdk::PumpPortalError;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn test_ipfs_uri_fetched_through_gateway() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/ipfs/QmMetadata"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "name": "Test Token",
+            "symbol": "TEST",
+            "description": "A test token",
+            "twitter": "https://x.com/testtoken",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let gateway = format!("{}/ipfs/", server.uri());
+    let metadata = fetch_token_metadata_via(&reqwest::Client::new(), "ipfs://QmMetadata", &gateway)
+        .await
+        .unwrap();
+
+    assert_eq!(metadata.symbol, "TEST");
+    assert_eq!(metadata.description(), Some("A test token"));
+    assert_eq!(metadata.social_links(), vec!["https://x.com/testtoken"]);
+}
+
+#[tokio::test]
+async fn test_missing_metadata_is_api_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("not found"))
+        .mount(&server)
+        .await;
+
+    let uri = format!("{}/metadata.json", server.uri());
+    let err = fetch_token_metadata_via(&reqwest::Client::new(), &uri, "https://unused.example/ipfs/")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, PumpPortalError::ApiError(msg) if msg.contains("404")));
+}
@@ -0,0 +1,48 @@
+//! `WebhookNotifier` against a mock webhook
+
+use pump_portal_sdk::notify::{Notification, WebhookNotifier};
+use pump_portal_sdk::PumpPortalError;
+use serde_json::json;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn test_failed_post_retried_once() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(502))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/hook"))
+        .and(body_partial_json(json!({
+            "event": "buy",
+            "mint": "Mint111",
+            "sol": 0.05,
+            "source": "simple-sniper",
+            "content": "[simple-sniper] BUY Mint111 | 0.0500 SOL",
+        })))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let notifier = WebhookNotifier::new(format!("{}/hook", server.uri())).with_source("simple-sniper");
+    notifier.send(&Notification::buy("Mint111", 0.05, "Sig111")).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_gives_up_after_one_retry() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let notifier = WebhookNotifier::new(server.uri());
+    let err = notifier.send(&Notification::rug("Mint111", "dev sold")).await.unwrap_err();
+    assert!(matches!(err, PumpPortalError::ServerError(500)));
+}
@@ -0,0 +1,85 @@
+//! `PumpPortalStream` reconnecting through a silent stall
+#![cfg(feature = "stream")]
+
+use futures_util::{SinkExt, StreamExt};
+use pump_portal_sdk::{PumpPortalStream, StreamConfig, Subscription};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio_tungstenite::accept_async;
+use tokio_tungstenite::tungstenite::Message;
+
+#[tokio::test]
+async fn test_stalled_stream_reconnects_and_resubscribes() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        // First connection takes the subscription, then goes quiet without closing
+        let (tcp, _) = listener.accept().await.unwrap();
+        let mut silent = accept_async(tcp).await.unwrap();
+        let first = silent.next().await.unwrap().unwrap();
+
+        let (tcp, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(tcp).await.unwrap();
+        let replayed = ws.next().await.unwrap().unwrap();
+        ws.send(Message::Text(r#"{"mint":"launch"}"#.to_string())).await.unwrap();
+        (first, replayed, silent)
+    });
+
+    let config = StreamConfig::new(&url)
+        .with_stall_timeout(Duration::from_millis(300))
+        .with_reconnect_delay(Duration::from_millis(10));
+    let mut stream = PumpPortalStream::connect(config).await.unwrap();
+    stream.subscribe(&Subscription::NewTokens);
+
+    let text = tokio::time::timeout(Duration::from_secs(5), stream.next()).await.unwrap();
+
+    assert_eq!(text, r#"{"mint":"launch"}"#);
+    assert_eq!(stream.reconnects(), 1);
+    let (first, replayed, _silent) = server.await.unwrap();
+    assert_eq!(first, replayed);
+    assert_eq!(first.into_text().unwrap(), r#"{"method":"subscribeNewToken"}"#);
+}
+
+#[tokio::test]
+async fn test_reconnect_restores_trade_subscriptions() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        // First connection takes three (un)subscribes, then drops
+        let (tcp, _) = listener.accept().await.unwrap();
+        let mut first = accept_async(tcp).await.unwrap();
+        for _ in 0..3 {
+            first.next().await.unwrap().unwrap();
+        }
+        drop(first);
+
+        let (tcp, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(tcp).await.unwrap();
+        let mut replayed = Vec::new();
+        for _ in 0..2 {
+            replayed.push(ws.next().await.unwrap().unwrap().into_text().unwrap());
+        }
+        ws.send(Message::Text(r#"{"mint":"mintB","txType":"buy"}"#.to_string())).await.unwrap();
+        (replayed, ws)
+    });
+
+    let config = StreamConfig::new(&url).with_reconnect_delay(Duration::from_millis(10));
+    let mut stream = PumpPortalStream::connect(config).await.unwrap();
+    // Per-position feeds come and go through a handle, as a monitor would use them
+    let handle = stream.handle();
+    stream.subscribe(&Subscription::NewTokens);
+    handle.subscribe(&Subscription::TokenTrades(vec!["mintA".to_string(), "mintB".to_string()]));
+    handle.unsubscribe(&Subscription::TokenTrades(vec!["mintA".to_string()]));
+
+    let text = tokio::time::timeout(Duration::from_secs(5), stream.next()).await.unwrap();
+
+    assert_eq!(text, r#"{"mint":"mintB","txType":"buy"}"#);
+    assert_eq!(stream.reconnects(), 1);
+    let (replayed, _ws) = server.await.unwrap();
+    assert_eq!(
+        replayed,
+        [r#"{"method":"subscribeNewToken"}"#, r#"{"keys":["mintB"],"method":"subscribeTokenTrade"}"#]
+    );
+}
@@ -2,28 +2,44 @@
 //!
 //! Tracks positions, transactions, whale wallets, and momentum data
 
+use pump_portal_sdk::ExitReason;
 use rusqlite::{Connection, Result as SqlResult, params};
 use anyhow::{Result, Context};
 use log::{info, error};
 use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Position errors callers may want to handle rather than fail on
+#[derive(Debug, thiserror::Error)]
+pub enum PositionError {
+    /// `mint` already has a position row, e.g. from a re-delivered launch
+    #[error("position for {0} already exists")]
+    AlreadyOpen(String),
+}
 
+/// Handle to the bot's database; clones share one connection
+#[derive(Clone)]
 pub struct Database {
-    conn: Connection,
+    conn: Arc<Mutex<Connection>>,
 }
 
 impl Database {
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
         let conn = Connection::open(path)?;
-        let db = Self { conn };
+        let db = Self { conn: Arc::new(Mutex::new(conn)) };
         db.initialize_schema()?;
         Ok(db)
     }
 
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap()
+    }
+
     fn initialize_schema(&self) -> Result<()> {
         info!("Initializing database schema...");
 
         // Positions table
-        self.conn.execute(
+        self.conn().execute(
             "CREATE TABLE IF NOT EXISTS positions (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
                 mint TEXT NOT NULL UNIQUE,
@@ -40,13 +56,19 @@ impl Database {
                 profit_loss_sol REAL,
                 profit_loss_percent REAL,
                 exit_reason TEXT,
-                created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+                created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+                profit_loss_usd REAL
             )",
             [],
         )?;
 
+        // profit_loss_usd was added later; bring older databases up to date
+        if self.conn().prepare("SELECT profit_loss_usd FROM positions LIMIT 0").is_err() {
+            self.conn().execute("ALTER TABLE positions ADD COLUMN profit_loss_usd REAL", [])?;
+        }
+
         // Transactions table
-        self.conn.execute(
+        self.conn().execute(
             "CREATE TABLE IF NOT EXISTS transactions (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
                 signature TEXT NOT NULL UNIQUE,
@@ -64,7 +86,7 @@ impl Database {
         )?;
 
         // Whale wallets table
-        self.conn.execute(
+        self.conn().execute(
             "CREATE TABLE IF NOT EXISTS whale_wallets (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
                 mint TEXT NOT NULL,
@@ -80,7 +102,7 @@ impl Database {
         )?;
 
         // Momentum snapshots table
-        self.conn.execute(
+        self.conn().execute(
             "CREATE TABLE IF NOT EXISTS momentum_snapshots (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
                 mint TEXT NOT NULL,
@@ -100,27 +122,27 @@ impl Database {
         )?;
 
         // Create indexes
-        self.conn.execute(
+        self.conn().execute(
             "CREATE INDEX IF NOT EXISTS idx_positions_mint ON positions(mint)",
             [],
         )?;
-        self.conn.execute(
+        self.conn().execute(
             "CREATE INDEX IF NOT EXISTS idx_positions_status ON positions(status)",
             [],
         )?;
-        self.conn.execute(
+        self.conn().execute(
             "CREATE INDEX IF NOT EXISTS idx_transactions_mint ON transactions(mint)",
             [],
         )?;
-        self.conn.execute(
+        self.conn().execute(
             "CREATE INDEX IF NOT EXISTS idx_transactions_signature ON transactions(signature)",
             [],
         )?;
-        self.conn.execute(
+        self.conn().execute(
             "CREATE INDEX IF NOT EXISTS idx_whale_wallets_mint ON whale_wallets(mint)",
             [],
         )?;
-        self.conn.execute(
+        self.conn().execute(
             "CREATE INDEX IF NOT EXISTS idx_momentum_snapshots_mint ON momentum_snapshots(mint)",
             [],
         )?;
@@ -140,23 +162,55 @@ impl Database {
             .duration_since(std::time::UNIX_EPOCH)?
             .as_secs() as i64;
 
-        self.conn.execute(
+        let inserted = self.conn().execute(
             "INSERT INTO positions (mint, entry_signature, entry_time, entry_sol_amount, status)
              VALUES (?1, ?2, ?3, ?4, 'active')",
             params![mint, entry_signature, now, entry_sol_amount],
-        )?;
+        );
+        match inserted {
+            Err(rusqlite::Error::SqliteFailure(e, _))
+                if e.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_UNIQUE =>
+            {
+                return Err(PositionError::AlreadyOpen(mint.to_string()).into());
+            }
+            result => result?,
+        };
 
         info!("✅ Position created: {} @ {} SOL", mint, entry_sol_amount);
         Ok(())
     }
 
+    /// Claim `mint` for a buy that's about to be sent, before any SOL is spent;
+    /// fails with `PositionError::AlreadyOpen` if it's taken. The entry
+    /// signature is filled in by `set_entry_signature` once the buy is sent.
+    pub fn reserve_position(&self, mint: &str, entry_sol_amount: f64) -> Result<()> {
+        self.create_position(mint, "", entry_sol_amount)
+    }
+
+    pub fn set_entry_signature(&self, mint: &str, entry_signature: &str) -> Result<()> {
+        self.conn().execute(
+            "UPDATE positions SET entry_signature = ?1 WHERE mint = ?2 AND status = 'active'",
+            params![entry_signature, mint],
+        )?;
+        Ok(())
+    }
+
+    /// Give up the active position in `mint` whose buy failed, freeing the mint
+    pub fn release_position(&self, mint: &str) -> Result<()> {
+        self.conn().execute(
+            "DELETE FROM positions WHERE mint = ?1 AND status = 'active'",
+            params![mint],
+        )?;
+        Ok(())
+    }
+
     pub fn update_position_entry_details(
         &self,
         mint: &str,
         token_amount: f64,
         price: f64,
     ) -> Result<()> {
-        self.conn.execute(
+        self.conn().execute(
             "UPDATE positions
              SET entry_token_amount = ?1, entry_price = ?2, current_token_amount = ?1
              WHERE mint = ?3 AND status = 'active'",
@@ -166,76 +220,82 @@ impl Database {
     }
 
     pub fn update_position_balance(&self, mint: &str, current_amount: f64) -> Result<()> {
-        self.conn.execute(
+        self.conn().execute(
             "UPDATE positions SET current_token_amount = ?1 WHERE mint = ?2 AND status = 'active'",
             params![current_amount, mint],
         )?;
         Ok(())
     }
 
+    /// Close the active position in `mint`; `sol_usd` (from `pump_portal_sdk::sol_usd`)
+    /// also records the P&L in dollars
     pub fn close_position(
         &self,
         mint: &str,
         exit_signature: &str,
-        exit_sol: f64,
-        reason: &str,
+        exit_sol: Option<f64>,
+        reason: ExitReason,
+        sol_usd: Option<f64>,
     ) -> Result<()> {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)?
             .as_secs() as i64;
 
-        // Calculate P&L
-        let entry_sol: f64 = self.conn.query_row(
+        // Calculate P&L against what the buys really cost when their fills
+        // were read, else the amount the position was opened with
+        let entry_sol: f64 = self.conn().query_row(
             "SELECT entry_sol_amount FROM positions WHERE mint = ?1 AND status = 'active'",
             params![mint],
             |row| row.get(0),
         )?;
+        let ledger = self.mint_ledger(mint)?;
+        let entry_sol = if ledger.tokens_bought > 0.0 { ledger.sol_spent } else { entry_sol };
 
-        let profit_loss = exit_sol - entry_sol;
-        let profit_percent = (profit_loss / entry_sol) * 100.0;
+        // Without the exit SOL the P&L columns stay NULL rather than read as a total loss
+        let profit_loss = exit_sol.map(|exit_sol| exit_sol - entry_sol);
+        let profit_percent = profit_loss.map(|profit_loss| (profit_loss / entry_sol) * 100.0);
+        let profit_loss_usd = profit_loss.zip(sol_usd).map(|(profit_loss, price)| profit_loss * price);
 
-        self.conn.execute(
+        self.conn().execute(
             "UPDATE positions
              SET exit_signature = ?1, exit_time = ?2, exit_sol_received = ?3,
                  status = 'closed', profit_loss_sol = ?4, profit_loss_percent = ?5,
-                 exit_reason = ?6
-             WHERE mint = ?7 AND status = 'active'",
+                 exit_reason = ?6, profit_loss_usd = ?7
+             WHERE mint = ?8 AND status = 'active'",
             params![
                 exit_signature,
                 now,
                 exit_sol,
                 profit_loss,
                 profit_percent,
-                reason,
+                reason.as_str(),
+                profit_loss_usd,
                 mint
             ],
         )?;
 
-        info!(
-            "✅ Position closed: {} | P&L: {:.4} SOL ({:.1}%) | Reason: {}",
-            mint, profit_loss, profit_percent, reason
-        );
+        match (profit_loss.zip(profit_percent), profit_loss_usd) {
+            (Some((profit_loss, profit_percent)), Some(usd)) => info!(
+                "✅ Position closed: {} | P&L: {:.4} SOL / ${:.2} ({:.1}%) | Reason: {}",
+                mint, profit_loss, usd, profit_percent, reason
+            ),
+            (Some((profit_loss, profit_percent)), None) => info!(
+                "✅ Position closed: {} | P&L: {:.4} SOL ({:.1}%) | Reason: {}",
+                mint, profit_loss, profit_percent, reason
+            ),
+            (None, _) => info!("✅ Position closed: {} | P&L: unscored | Reason: {}", mint, reason),
+        }
         Ok(())
     }
 
     pub fn get_active_position(&self, mint: &str) -> Result<Option<Position>> {
-        let result = self.conn.query_row(
+        let result = self.conn().query_row(
             "SELECT mint, entry_signature, entry_time, entry_sol_amount,
                     entry_token_amount, entry_price, current_token_amount
              FROM positions
              WHERE mint = ?1 AND status = 'active'",
             params![mint],
-            |row| {
-                Ok(Position {
-                    mint: row.get(0)?,
-                    entry_signature: row.get(1)?,
-                    entry_time: row.get(2)?,
-                    entry_sol_amount: row.get(3)?,
-                    entry_token_amount: row.get(4)?,
-                    entry_price: row.get(5)?,
-                    current_token_amount: row.get(6)?,
-                })
-            },
+            read_position,
         );
 
         match result {
@@ -245,6 +305,112 @@ impl Database {
         }
     }
 
+    /// Active positions whose entry buy was never confirmed on-chain
+    pub fn unverified_positions(&self) -> Result<Vec<Position>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT p.mint, p.entry_signature, p.entry_time, p.entry_sol_amount,
+                    p.entry_token_amount, p.entry_price, p.current_token_amount
+             FROM positions p
+             LEFT JOIN transactions t ON t.signature = p.entry_signature
+             WHERE p.status = 'active' AND COALESCE(t.verified, 0) = 0",
+        )?;
+        let positions = stmt.query_map([], read_position)?.collect::<SqlResult<Vec<_>>>()?;
+        Ok(positions)
+    }
+
+    /// Take the active position in `mint` out of play because its entry buy
+    /// never landed; it stays on record as `phantom` but no longer counts as
+    /// held or at risk
+    pub fn mark_position_phantom(&self, mint: &str) -> Result<()> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs() as i64;
+
+        self.conn().execute(
+            "UPDATE positions SET status = 'phantom', exit_time = ?1 WHERE mint = ?2 AND status = 'active'",
+            params![now, mint],
+        )?;
+        Ok(())
+    }
+
+    /// Closed positions with a scored exit, oldest exit first
+    pub fn closed_positions(&self) -> Result<Vec<ClosedPosition>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT mint, entry_time, exit_time, entry_sol_amount, exit_sol_received,
+                    profit_loss_sol, profit_loss_percent, profit_loss_usd, exit_reason
+             FROM positions
+             WHERE status = 'closed' AND profit_loss_sol IS NOT NULL
+             ORDER BY exit_time ASC",
+        )?;
+
+        let positions = stmt.query_map([], |row| {
+            Ok(ClosedPosition {
+                mint: row.get(0)?,
+                entry_time: row.get(1)?,
+                exit_time: row.get(2)?,
+                entry_sol_amount: row.get(3)?,
+                exit_sol_received: row.get(4)?,
+                profit_loss_sol: row.get(5)?,
+                profit_loss_percent: row.get(6)?,
+                profit_loss_usd: row.get(7)?,
+                // Unrecognized legacy strings read back as no reason
+                exit_reason: row.get::<_, Option<String>>(8)?.and_then(|reason| reason.parse().ok()),
+            })
+        })?
+        .collect::<SqlResult<Vec<_>>>()?;
+
+        Ok(positions)
+    }
+
+    pub fn active_position_count(&self) -> Result<usize> {
+        let count: i64 = self.conn().query_row(
+            "SELECT COUNT(*) FROM positions WHERE status = 'active'",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    /// SOL spent on positions still active, what `MAX_TOTAL_SOL_AT_RISK` caps
+    pub fn sol_at_risk(&self) -> Result<f64> {
+        let sol = self.conn().query_row(
+            "SELECT COALESCE(SUM(entry_sol_amount), 0) FROM positions WHERE status = 'active'",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(sol)
+    }
+
+    /// Total P&L of closed positions, in SOL
+    pub fn realized_pnl_sol(&self) -> Result<f64> {
+        let pnl = self.conn().query_row(
+            "SELECT COALESCE(SUM(profit_loss_sol), 0) FROM positions WHERE status = 'closed'",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(pnl)
+    }
+
+    /// Submitted transactions by type, and how many were confirmed on-chain
+    pub fn transaction_counts(&self) -> Result<TransactionCounts> {
+        let counts = self.conn().query_row(
+            "SELECT COALESCE(SUM(tx_type = 'buy'), 0), COALESCE(SUM(tx_type = 'sell'), 0),
+                    COALESCE(SUM(verified), 0)
+             FROM transactions",
+            [],
+            |row| {
+                Ok(TransactionCounts {
+                    buys: row.get::<_, i64>(0)? as usize,
+                    sells: row.get::<_, i64>(1)? as usize,
+                    verified: row.get::<_, i64>(2)? as usize,
+                })
+            },
+        )?;
+        Ok(counts)
+    }
+
     // Transaction operations
     pub fn record_transaction(
         &self,
@@ -254,7 +420,7 @@ impl Database {
         sol_amount: f64,
         timestamp: i64,
     ) -> Result<()> {
-        self.conn.execute(
+        self.conn().execute(
             "INSERT OR IGNORE INTO transactions
              (signature, mint, tx_type, sol_amount, timestamp)
              VALUES (?1, ?2, ?3, ?4, ?5)",
@@ -263,12 +429,51 @@ impl Database {
         Ok(())
     }
 
+    /// Store what a confirmed transaction actually moved: SOL spent on a buy
+    /// or received on a sell, and the tokens that came or went
+    pub fn record_transaction_fill(&self, signature: &str, sol_amount: f64, token_amount: f64) -> Result<()> {
+        self.conn().execute(
+            "UPDATE transactions SET sol_amount = ?1, token_amount = ?2, price = ?1 / ?2
+             WHERE signature = ?3",
+            params![sol_amount, token_amount, signature],
+        )?;
+        Ok(())
+    }
+
+    /// Verified fills of `mint`'s active position, summed per side
+    ///
+    /// Only transactions since the position opened count, so an earlier
+    /// position in the same mint doesn't leak in; empty without an active
+    /// position. Transactions need a recorded token amount too, so a buy whose
+    /// fill couldn't be read doesn't skew the cost basis.
+    pub fn mint_ledger(&self, mint: &str) -> Result<MintLedger> {
+        let ledger = self.conn().query_row(
+            "SELECT COALESCE(SUM(CASE WHEN tx_type = 'buy' THEN sol_amount END), 0),
+                    COALESCE(SUM(CASE WHEN tx_type = 'buy' THEN token_amount END), 0),
+                    COALESCE(SUM(CASE WHEN tx_type = 'sell' THEN sol_amount END), 0),
+                    COALESCE(SUM(CASE WHEN tx_type = 'sell' THEN token_amount END), 0)
+             FROM transactions
+             WHERE mint = ?1 AND verified = 1 AND token_amount IS NOT NULL
+               AND timestamp >= (SELECT entry_time FROM positions WHERE mint = ?1 AND status = 'active')",
+            params![mint],
+            |row| {
+                Ok(MintLedger {
+                    sol_spent: row.get(0)?,
+                    tokens_bought: row.get(1)?,
+                    sol_received: row.get(2)?,
+                    tokens_sold: row.get(3)?,
+                })
+            },
+        )?;
+        Ok(ledger)
+    }
+
     pub fn mark_transaction_verified(&self, signature: &str, verified: bool) -> Result<()> {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)?
             .as_secs() as i64;
 
-        self.conn.execute(
+        self.conn().execute(
             "UPDATE transactions SET verified = ?1, verification_time = ?2 WHERE signature = ?3",
             params![verified, now, signature],
         )?;
@@ -276,7 +481,7 @@ impl Database {
     }
 
     pub fn is_transaction_verified(&self, signature: &str) -> Result<bool> {
-        let result: i32 = self.conn.query_row(
+        let result: i32 = self.conn().query_row(
             "SELECT verified FROM transactions WHERE signature = ?1",
             params![signature],
             |row| row.get(0),
@@ -290,7 +495,7 @@ impl Database {
             .duration_since(std::time::UNIX_EPOCH)?
             .as_secs() as i64;
 
-        self.conn.execute(
+        self.conn().execute(
             "INSERT OR REPLACE INTO whale_wallets
              (mint, wallet_address, token_amount, holdings_percent, danger_level, last_check)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
@@ -300,7 +505,8 @@ impl Database {
     }
 
     pub fn get_whales(&self, mint: &str) -> Result<Vec<WhaleWallet>> {
-        let mut stmt = self.conn.prepare(
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
             "SELECT wallet_address, token_amount, holdings_percent, danger_level, last_check
              FROM whale_wallets
              WHERE mint = ?1
@@ -339,7 +545,7 @@ impl Database {
             .duration_since(std::time::UNIX_EPOCH)?
             .as_secs() as i64;
 
-        self.conn.execute(
+        self.conn().execute(
             "INSERT INTO momentum_snapshots
              (mint, timestamp, score, rug_risk, volume_velocity, price_momentum, holder_health,
               buy_count, sell_count, unique_buyers, unique_sellers)
@@ -367,7 +573,8 @@ impl Database {
             .as_secs() as i64
             - seconds;
 
-        let mut stmt = self.conn.prepare(
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
             "SELECT timestamp, score, rug_risk, volume_velocity, price_momentum, holder_health,
                     buy_count, sell_count, unique_buyers, unique_sellers
              FROM momentum_snapshots
@@ -395,6 +602,20 @@ impl Database {
     }
 }
 
+/// Map a row of `mint, entry_signature, entry_time, entry_sol_amount,
+/// entry_token_amount, entry_price, current_token_amount`
+fn read_position(row: &rusqlite::Row) -> SqlResult<Position> {
+    Ok(Position {
+        mint: row.get(0)?,
+        entry_signature: row.get(1)?,
+        entry_time: row.get(2)?,
+        entry_sol_amount: row.get(3)?,
+        entry_token_amount: row.get(4)?,
+        entry_price: row.get(5)?,
+        current_token_amount: row.get(6)?,
+    })
+}
+
 #[derive(Debug, Clone)]
 pub struct Position {
     pub mint: String,
@@ -406,6 +627,66 @@ pub struct Position {
     pub current_token_amount: Option<f64>,
 }
 
+#[derive(Debug, Clone)]
+pub struct ClosedPosition {
+    pub mint: String,
+    pub entry_time: i64,
+    pub exit_time: i64,
+    pub entry_sol_amount: f64,
+    pub exit_sol_received: f64,
+    pub profit_loss_sol: f64,
+    pub profit_loss_percent: f64,
+    pub profit_loss_usd: Option<f64>,
+    pub exit_reason: Option<ExitReason>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TransactionCounts {
+    pub buys: usize,
+    pub sells: usize,
+    pub verified: usize,
+}
+
+/// Share of the tokens bought a full exit may leave unsold (rounding dust)
+const UNSOLD_DUST: f64 = 0.001;
+
+/// One mint's verified fills, from the `transactions` table
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MintLedger {
+    pub sol_spent: f64,
+    pub tokens_bought: f64,
+    pub sol_received: f64,
+    pub tokens_sold: f64,
+}
+
+impl MintLedger {
+    /// Average SOL paid per token, None before any buy fill
+    pub fn avg_entry_price(&self) -> Option<f64> {
+        (self.tokens_bought > 0.0).then(|| self.sol_spent / self.tokens_bought)
+    }
+
+    pub fn tokens_held(&self) -> f64 {
+        (self.tokens_bought - self.tokens_sold).max(0.0)
+    }
+
+    /// Whether every token bought has a sell on record, so `sol_received` is
+    /// the whole exit; a sell whose proceeds couldn't be read leaves some held
+    pub fn is_fully_sold(&self) -> bool {
+        self.tokens_sold > 0.0 && self.tokens_held() <= self.tokens_bought * UNSOLD_DUST
+    }
+
+    /// SOL cost of the tokens still held, at the average entry price
+    pub fn cost_basis_sol(&self) -> f64 {
+        self.avg_entry_price().map_or(0.0, |price| price * self.tokens_held())
+    }
+
+    /// SOL received for the tokens sold, less what they cost
+    pub fn realized_pnl_sol(&self) -> f64 {
+        let sold_cost = self.avg_entry_price().map_or(0.0, |price| price * self.tokens_sold);
+        self.sol_received - sold_cost
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct WhaleWallet {
     pub wallet_address: String,
@@ -428,3 +709,141 @@ pub struct MomentumSnapshot {
     pub unique_buyers: i32,
     pub unique_sellers: i32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicate_position_is_already_open() {
+        let db = Database::new(":memory:").unwrap();
+        db.create_position("mint1", "sig1", 0.05).unwrap();
+
+        let err = db.create_position("mint1", "sig2", 0.05).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<PositionError>(),
+            Some(PositionError::AlreadyOpen(mint)) if mint == "mint1"
+        ));
+
+        // Other failures aren't mistaken for duplicates
+        db.conn().execute("DROP TABLE positions", []).unwrap();
+        let err = db.create_position("mint2", "sig3", 0.05).unwrap_err();
+        assert!(err.downcast_ref::<PositionError>().is_none());
+    }
+
+    #[test]
+    fn test_reserved_position_until_released() {
+        let db = Database::new(":memory:").unwrap();
+        db.reserve_position("mint1", 0.05).unwrap();
+        // Counts as at risk before the buy is even sent
+        assert_eq!(db.sol_at_risk().unwrap(), 0.05);
+        assert!(db.reserve_position("mint1", 0.05).is_err());
+
+        db.set_entry_signature("mint1", "sig1").unwrap();
+        assert_eq!(db.get_active_position("mint1").unwrap().unwrap().entry_signature, "sig1");
+
+        db.release_position("mint1").unwrap();
+        assert_eq!(db.sol_at_risk().unwrap(), 0.0);
+        db.reserve_position("mint1", 0.05).unwrap();
+    }
+
+    #[test]
+    fn test_realized_pnl_counts_closed_only() {
+        let db = Database::new(":memory:").unwrap();
+        assert_eq!(db.realized_pnl_sol().unwrap(), 0.0);
+
+        for mint in ["win", "loss", "open"] {
+            db.create_position(mint, &format!("{}-buy", mint), 0.05).unwrap();
+        }
+        db.close_position("win", "win-sell", Some(0.12), ExitReason::TakeProfit, None).unwrap();
+        db.close_position("loss", "loss-sell", Some(0.02), ExitReason::MomentumDied, None).unwrap();
+
+        assert!((db.realized_pnl_sol().unwrap() - 0.04).abs() < 1e-12);
+        assert_eq!(db.active_position_count().unwrap(), 1);
+        assert!((db.sol_at_risk().unwrap() - 0.05).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_phantom_positions_stop_counting() {
+        let db = Database::new(":memory:").unwrap();
+        // One confirmed buy, one that never landed, one with no transaction row at all
+        for (mint, verified) in [("landed", Some(true)), ("dropped", Some(false)), ("legacy", None)] {
+            let sig = format!("{}-buy", mint);
+            db.create_position(mint, &sig, 0.05).unwrap();
+            if let Some(verified) = verified {
+                db.record_transaction(&sig, mint, "buy", 0.05, 0).unwrap();
+                db.mark_transaction_verified(&sig, verified).unwrap();
+            }
+        }
+
+        let mut unverified: Vec<String> = db.unverified_positions().unwrap().into_iter().map(|p| p.mint).collect();
+        unverified.sort();
+        assert_eq!(unverified, ["dropped", "legacy"]);
+
+        db.mark_position_phantom("dropped").unwrap();
+        assert!(db.get_active_position("dropped").unwrap().is_none());
+        assert_eq!(db.active_position_count().unwrap(), 2);
+        assert!((db.sol_at_risk().unwrap() - 0.10).abs() < 1e-12);
+        assert!(db.closed_positions().unwrap().is_empty());
+        // The mint stays taken, so a re-delivered launch isn't bought again
+        assert!(db.create_position("dropped", "again", 0.05).is_err());
+    }
+
+    #[test]
+    fn test_mint_ledger_from_fills() {
+        let db = Database::new(":memory:").unwrap();
+        // A sell left over from an earlier position in the same mint
+        db.record_transaction("old_sell", "mint1", "sell", 0.0, 0).unwrap();
+        db.mark_transaction_verified("old_sell", true).unwrap();
+        db.record_transaction_fill("old_sell", 1.0, 1000.0).unwrap();
+
+        db.create_position("mint1", "buy1", 0.05).unwrap();
+        let now = db.get_active_position("mint1").unwrap().unwrap().entry_time;
+
+        // Buy confirmed at 0.052 SOL for 1000 tokens, fees included
+        db.record_transaction("buy1", "mint1", "buy", 0.05, now).unwrap();
+        db.mark_transaction_verified("buy1", true).unwrap();
+        db.record_transaction_fill("buy1", 0.052, 1000.0).unwrap();
+
+        // Half sold for 0.04, the rest for 0.02; an unconfirmed sell doesn't count
+        for (sig, sol, verified) in [("sell1", 0.04, true), ("sell2", 0.02, true), ("dropped", 0.5, false)] {
+            db.record_transaction(sig, "mint1", "sell", 0.0, now).unwrap();
+            db.mark_transaction_verified(sig, verified).unwrap();
+            db.record_transaction_fill(sig, sol, 500.0).unwrap();
+        }
+
+        let ledger = db.mint_ledger("mint1").unwrap();
+        assert!((ledger.avg_entry_price().unwrap() - 0.000052).abs() < 1e-12);
+        assert_eq!(ledger.tokens_held(), 0.0);
+        assert!(ledger.is_fully_sold());
+        assert_eq!(ledger.cost_basis_sol(), 0.0);
+        assert!((ledger.realized_pnl_sol() - 0.008).abs() < 1e-12);
+        assert_eq!(db.mint_ledger("other").unwrap(), MintLedger::default());
+
+        // The position's P&L is measured against what the buy really cost
+        db.close_position("mint1", "sell2", Some(0.06), ExitReason::TakeProfit, None).unwrap();
+        let closed = &db.closed_positions().unwrap()[0];
+        assert!((closed.profit_loss_sol - 0.008).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_unscored_exit_is_closed_without_pnl() {
+        let db = Database::new(":memory:").unwrap();
+        db.create_position("mint1", "buy1", 0.05).unwrap();
+        let now = db.get_active_position("mint1").unwrap().unwrap().entry_time;
+        db.record_transaction("buy1", "mint1", "buy", 0.05, now).unwrap();
+        db.mark_transaction_verified("buy1", true).unwrap();
+        db.record_transaction_fill("buy1", 0.05, 1000.0).unwrap();
+
+        // Half sold and read, the other half's proceeds never came back
+        db.record_transaction("sell1", "mint1", "sell", 0.0, now).unwrap();
+        db.mark_transaction_verified("sell1", true).unwrap();
+        db.record_transaction_fill("sell1", 0.04, 500.0).unwrap();
+        assert!(!db.mint_ledger("mint1").unwrap().is_fully_sold());
+
+        db.close_position("mint1", "sell2", None, ExitReason::TakeProfit, Some(200.0)).unwrap();
+        assert_eq!(db.active_position_count().unwrap(), 0);
+        assert!(db.closed_positions().unwrap().is_empty());
+        assert_eq!(db.realized_pnl_sol().unwrap(), 0.0);
+    }
+}
@@ -7,9 +7,23 @@ use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
 use solana_transaction_status::UiTransactionEncoding;
 use log::{info, warn, error};
+use pump_portal_sdk::{BondingCurve, PUMP_TOKEN_DECIMALS};
 use std::str::FromStr;
 use std::collections::{HashSet, HashMap};
+use std::sync::{Arc, Mutex};
 use crate::database::Database;
+use crate::dev_watch::DevSellWatcher;
+
+/// pump.fun bonding curve program
+const PUMP_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+
+/// Below this real SOL reserve a curve is too thin for drops to mean anything
+const MIN_TRACKED_RESERVE_SOL: f64 = 0.5;
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// Rug risk once the creator has sold: outranks every other signal
+pub const DEV_SELL_RUG_RISK: f64 = 1.0;
 
 #[derive(Debug, Clone)]
 pub struct MomentumSignals {
@@ -20,19 +34,79 @@ pub struct MomentumSignals {
     pub holder_health: f64,
 }
 
+/// pump.fun bonding curve account state (after the 8-byte discriminator)
+#[derive(Debug, Clone, PartialEq)]
+pub struct BondingCurveState {
+    pub virtual_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub token_total_supply: u64,
+    pub complete: bool,
+}
+
+impl BondingCurveState {
+    /// Parse raw account data
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        if data.len() < 49 {
+            return None;
+        }
+
+        let read_u64 = |offset: usize| -> Option<u64> {
+            data.get(offset..offset + 8)?.try_into().ok().map(u64::from_le_bytes)
+        };
+
+        Some(Self {
+            virtual_token_reserves: read_u64(8)?,
+            virtual_sol_reserves: read_u64(16)?,
+            real_token_reserves: read_u64(24)?,
+            real_sol_reserves: read_u64(32)?,
+            token_total_supply: read_u64(40)?,
+            complete: data[48] != 0,
+        })
+    }
+
+    /// Real SOL liquidity held by the curve
+    pub fn real_sol(&self) -> f64 {
+        self.real_sol_reserves as f64 / LAMPORTS_PER_SOL
+    }
+
+    /// Virtual reserves in SOL and whole tokens, for pricing
+    pub fn curve(&self) -> BondingCurve {
+        BondingCurve::new(
+            self.virtual_sol_reserves as f64 / LAMPORTS_PER_SOL,
+            self.virtual_token_reserves as f64 / 10f64.powi(PUMP_TOKEN_DECIMALS as i32),
+        )
+    }
+}
+
+/// SOL reserve history for one bonding curve
+#[derive(Debug, Clone, Copy)]
+struct ReserveWatch {
+    peak_sol: f64,
+    last_sol: f64,
+}
+
 pub struct MomentumDetector {
     rpc: RpcClient,
     db: Database,
+    reserves: Mutex<HashMap<String, ReserveWatch>>,
+    dev_sells: Arc<DevSellWatcher>,
 }
 
 impl MomentumDetector {
-    pub fn new(rpc_url: String, db: Database) -> Result<Self> {
+    pub fn new(rpc_url: String, db: Database, dev_sells: Arc<DevSellWatcher>) -> Result<Self> {
         let rpc = RpcClient::new_with_commitment(
             rpc_url,
             CommitmentConfig::confirmed()
         );
 
-        Ok(Self { rpc, db })
+        Ok(Self {
+            rpc,
+            db,
+            reserves: Mutex::new(HashMap::new()),
+            dev_sells,
+        })
     }
 
     /// Check current momentum for a token by analyzing recent transactions
@@ -42,11 +116,14 @@ impl MomentumDetector {
         // Get recent transaction signatures for this mint
         let signatures = self.get_recent_signatures(token_mint, 60).await?;
 
+        // On-chain rug signals (liquidity removal + whale concentration)
+        let rug_patterns = self.check_rug_patterns(token_mint).await?;
+
         if signatures.is_empty() {
             warn!("No recent transactions found for {}", token_mint);
             return Ok(MomentumSignals {
                 score: 0.0,
-                rug_risk: 0.5,
+                rug_risk: rug_patterns,
                 volume_velocity: 0.0,
                 price_momentum: 0.0,
                 holder_health: 0.5,
@@ -60,7 +137,7 @@ impl MomentumDetector {
         let momentum_score = self.calculate_momentum_score(&analysis);
 
         // Calculate rug risk
-        let rug_risk = self.calculate_rug_risk(&analysis);
+        let rug_risk = self.calculate_rug_risk(&analysis).max(rug_patterns);
 
         let signals = MomentumSignals {
             score: momentum_score,
@@ -209,47 +286,132 @@ impl MomentumDetector {
         risk.clamp(0.0, 1.0)
     }
 
-    /// Detect rug pull patterns by analyzing holder distribution
+    /// Detect rug pull patterns from dev sells, liquidity removal and holder distribution
+    ///
+    /// RED FLAGS:
+    /// 1. Creator wallet sold (CRITICAL, decides on its own)
+    /// 2. Liquidity removed from the bonding curve (CRITICAL)
+    /// 3. Single holder with >50% supply (CRITICAL)
+    /// 4. Top holder >30% supply (HIGH)
     pub async fn check_rug_patterns(&self, token_mint: &str) -> Result<f64> {
         info!("🚨 Checking rug patterns for {}", token_mint);
 
-        // In production, you would:
-        // 1. Get all token accounts for this mint
-        // 2. Analyze holder distribution
-        // 3. Check for concentrated holdings
-        // 4. Monitor for large sells
+        if let Some(sell) = self.dev_sells.dev_sold(token_mint) {
+            warn!("🚨 Dev {} sold {:.3} SOL ({})", sell.creator, sell.sol_amount, sell.signature);
+            return Ok(DEV_SELL_RUG_RISK);
+        }
 
-        // RED FLAGS:
-        // 1. Single holder with >50% supply (CRITICAL)
-        // 2. Top 3 holders >80% supply (HIGH)
-        // 3. Large sudden sell (MEDIUM)
-        // 4. Liquidity removed (CRITICAL)
+        let liquidity_risk = match self.check_liquidity_removal(token_mint).await {
+            Ok(risk) => risk,
+            Err(e) => {
+                warn!("Liquidity check failed for {}: {}", token_mint, e);
+                0.0
+            }
+        };
+
+        let whales = self.db.get_whales(token_mint).unwrap_or_default();
+        let top_whale_percent = whales.first().map(|w| w.holdings_percent).unwrap_or(0.0);
+        let whale_risk = whale_concentration_risk(top_whale_percent);
+
+        let risk = combine_rug_risk(liquidity_risk, whale_risk);
+        if risk >= thresholds::HIGH_RUG_RISK {
+            warn!(
+                "🚨 Rug risk {:.2} (liquidity {:.2}, whale {:.2})",
+                risk, liquidity_risk, whale_risk
+            );
+        }
 
-        // For now, return low risk
-        // Real implementation would check on-chain accounts
+        Ok(risk)
+    }
 
-        warn!("Rug pattern detection using mock data");
+    /// Read `token_mint`'s bonding curve account
+    pub async fn curve_state(&self, token_mint: &str) -> Result<BondingCurveState> {
+        let curve = bonding_curve_address(token_mint)?;
+        let account = self.rpc.get_account(&curve).await
+            .context("Failed to fetch bonding curve account")?;
+        BondingCurveState::parse(&account.data)
+            .context("Invalid bonding curve account data")
+    }
 
-        // Check if we have whale data from database
-        let whales = self.db.get_whales(token_mint).unwrap_or_default();
+    /// Watch the bonding curve's SOL reserve and score sudden drops
+    pub async fn check_liquidity_removal(&self, token_mint: &str) -> Result<f64> {
+        let state = self.curve_state(token_mint).await?;
 
-        if !whales.is_empty() {
-            // Calculate risk from whale concentration
-            let top_whale_percent = whales.first().map(|w| w.holdings_percent).unwrap_or(0.0);
+        // Completed curves migrate their liquidity, that isn't a rug
+        if state.complete {
+            self.reserves.lock().unwrap().remove(token_mint);
+            return Ok(0.0);
+        }
 
-            if top_whale_percent > 50.0 {
-                return Ok(0.9); // CRITICAL
-            } else if top_whale_percent > 30.0 {
-                return Ok(0.6); // HIGH
-            } else if top_whale_percent > 15.0 {
-                return Ok(0.3); // MEDIUM
-            }
+        let current = state.real_sol();
+        let mut reserves = self.reserves.lock().unwrap();
+        let watch = reserves.entry(token_mint.to_string()).or_insert(ReserveWatch {
+            peak_sol: current,
+            last_sol: current,
+        });
+
+        let risk = liquidity_rug_risk(watch.peak_sol, watch.last_sol, current);
+        if risk > 0.0 {
+            warn!(
+                "💧 Liquidity drop on {}: {:.3} → {:.3} SOL (peak {:.3})",
+                token_mint, watch.last_sol, current, watch.peak_sol
+            );
         }
 
-        Ok(0.1) // LOW
+        watch.peak_sol = watch.peak_sol.max(current);
+        watch.last_sol = current;
+
+        Ok(risk)
+    }
+}
+
+/// Bonding curve PDA for a pump.fun mint
+pub fn bonding_curve_address(token_mint: &str) -> Result<Pubkey> {
+    let mint = Pubkey::from_str(token_mint).context("Invalid mint address")?;
+    let program = Pubkey::from_str(PUMP_PROGRAM_ID)?;
+    let (curve, _) = Pubkey::find_program_address(&[b"bonding-curve", mint.as_ref()], &program);
+    Ok(curve)
+}
+
+/// Rug risk from a bonding curve's SOL reserve history
+/// A large drop since the last check is a dev pulling liquidity
+pub fn liquidity_rug_risk(peak_sol: f64, last_sol: f64, current_sol: f64) -> f64 {
+    if peak_sol < MIN_TRACKED_RESERVE_SOL {
+        return 0.0;
+    }
+
+    let drop_from_last = if last_sol > 0.0 { 1.0 - current_sol / last_sol } else { 0.0 };
+    let drop_from_peak = 1.0 - current_sol / peak_sol;
+
+    if drop_from_last >= 0.5 {
+        0.95 // CRITICAL: half the liquidity gone in one interval
+    } else if drop_from_peak >= 0.7 {
+        0.8 // HIGH: drained from peak
+    } else if drop_from_last >= 0.25 {
+        0.5 // MEDIUM: heavy selling
+    } else {
+        0.0
+    }
+}
+
+/// Rug risk from the largest holder's share of supply
+pub fn whale_concentration_risk(top_whale_percent: f64) -> f64 {
+    if top_whale_percent > 50.0 {
+        0.9 // CRITICAL
+    } else if top_whale_percent > 30.0 {
+        0.6 // HIGH
+    } else if top_whale_percent > 15.0 {
+        0.3 // MEDIUM
+    } else {
+        0.1 // LOW
     }
 }
 
+/// Combine independent rug signals; either one alone can push risk near 1.0
+pub fn combine_rug_risk(liquidity_risk: f64, whale_risk: f64) -> f64 {
+    (1.0 - (1.0 - liquidity_risk) * (1.0 - whale_risk)).clamp(0.0, 1.0)
+}
+
 #[derive(Debug)]
 struct TransactionAnalysis {
     buy_count: i32,
@@ -272,3 +434,52 @@ pub mod thresholds {
     pub const MEDIUM_RUG_RISK: f64 = 0.4;
     pub const LOW_RUG_RISK: f64 = 0.2;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bonding_curve() {
+        let mut data = vec![0u8; 8];
+        let fields: [u64; 5] = [
+            1_073_000_000_000_000, // virtual token
+            30_000_000_000,        // virtual SOL
+            793_100_000_000_000,   // real token
+            5_000_000_000,         // real SOL
+            1_000_000_000_000_000, // supply
+        ];
+        for value in fields {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.push(0);
+
+        let state = BondingCurveState::parse(&data).unwrap();
+        assert_eq!(state.virtual_sol_reserves, 30_000_000_000);
+        assert_eq!(state.real_sol(), 5.0);
+        assert!(!state.complete);
+        // 30 SOL over 1.073B tokens
+        assert!((state.curve().price() - 30.0 / 1_073_000_000.0).abs() < 1e-18);
+
+        assert!(BondingCurveState::parse(&data[..40]).is_none());
+    }
+
+    #[test]
+    fn test_liquidity_drop_flags_rug() {
+        // Half the SOL pulled between checks
+        assert!(liquidity_rug_risk(10.0, 10.0, 4.0) > thresholds::HIGH_RUG_RISK);
+        // Slow bleed from the peak
+        assert!(liquidity_rug_risk(10.0, 3.5, 2.5) > thresholds::HIGH_RUG_RISK);
+        // Normal trading noise
+        assert_eq!(liquidity_rug_risk(10.0, 9.5, 9.0), 0.0);
+        // Too little liquidity to judge
+        assert_eq!(liquidity_rug_risk(0.2, 0.2, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_combine_rug_risk() {
+        assert!(combine_rug_risk(0.95, 0.1) > 0.95);
+        assert!((combine_rug_risk(0.0, 0.1) - 0.1).abs() < 1e-9);
+        assert!(combine_rug_risk(0.5, 0.6) > thresholds::HIGH_RUG_RISK);
+    }
+}
@@ -0,0 +1,202 @@
+//! Dev-sell detection
+//!
+//! The creator selling their own launch is the clearest tell that a rug is
+//! coming, usually well before the liquidity drop `detector.rs` looks for.
+//! `DevSellWatcher` follows the creator wallet of every open position on
+//! PumpPortal's account-trade feed and records any sell of that mint.
+
+use anyhow::Result;
+use log::info;
+use pump_portal_sdk::{PumpPortalEvent, PumpPortalStream, StreamConfig, StreamHandle, Subscription};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+/// A creator selling the token they launched
+#[derive(Debug, Clone, PartialEq)]
+pub struct DevSell {
+    pub creator: String,
+    pub signature: String,
+    pub sol_amount: f64,
+}
+
+/// Creators being watched and the sells seen from them, by mint
+#[derive(Debug, Default)]
+struct DevWatchlist {
+    creators: HashMap<String, String>,
+    sells: HashMap<String, DevSell>,
+}
+
+impl DevWatchlist {
+    /// Start watching `creator` for sells of `mint`
+    /// Returns false if another watched mint already follows the wallet
+    fn watch(&mut self, mint: &str, creator: &str) -> bool {
+        let followed = self.creators.values().any(|c| c == creator);
+        self.creators.insert(mint.to_string(), creator.to_string());
+        !followed
+    }
+
+    /// Stop watching `mint`
+    /// Returns its creator when no other watched mint still needs the wallet
+    fn unwatch(&mut self, mint: &str) -> Option<String> {
+        self.sells.remove(mint);
+        let creator = self.creators.remove(mint)?;
+        if self.creators.values().any(|c| *c == creator) {
+            return None;
+        }
+        Some(creator)
+    }
+
+    /// Record `event` if it's a watched creator selling their own mint
+    fn record(&mut self, event: &PumpPortalEvent) -> Option<&DevSell> {
+        let PumpPortalEvent::Sell(trade) = event else {
+            return None;
+        };
+        let creator = self.creators.get(&trade.mint)?;
+        if trade.trader_public_key != *creator {
+            return None;
+        }
+
+        let sell = DevSell {
+            creator: creator.clone(),
+            signature: trade.signature.clone(),
+            sol_amount: trade.sol_amount,
+        };
+        // Keep the first sell; later ones don't make it any more of a rug
+        Some(self.sells.entry(trade.mint.clone()).or_insert(sell))
+    }
+}
+
+/// Follows creator wallets on the PumpPortal feed and flags their sells
+pub struct DevSellWatcher {
+    watchlist: Arc<Mutex<DevWatchlist>>,
+    sold: Arc<Notify>,
+    /// None for a watcher with no feed, which never sees a sell
+    stream: Option<StreamHandle>,
+}
+
+impl DevSellWatcher {
+    /// Connect to the data feed and start recording dev sells
+    pub async fn start(config: StreamConfig) -> Result<Self> {
+        let mut stream = PumpPortalStream::connect(config).await?;
+        let watchlist = Arc::new(Mutex::new(DevWatchlist::default()));
+        let sold = Arc::new(Notify::new());
+        let handle = stream.handle();
+
+        // The stream reconnects itself, so this task runs for the life of the bot
+        {
+            let watchlist = watchlist.clone();
+            let sold = sold.clone();
+            tokio::spawn(async move {
+                loop {
+                    let text = stream.next().await;
+                    let Ok(event) = serde_json::from_str::<PumpPortalEvent>(&text) else {
+                        continue;
+                    };
+                    let mut watchlist = watchlist.lock().unwrap();
+                    if let Some(sell) = watchlist.record(&event) {
+                        tracing::warn!(event = "dev_sell", mint = %event.mint(), creator = %sell.creator,
+                                       sol = sell.sol_amount, signature = %sell.signature,
+                                       "🚨 DEV SOLD {:.3} SOL", sell.sol_amount);
+                        sold.notify_waiters();
+                    }
+                }
+            });
+        }
+
+        info!("👁️  Dev-sell watcher connected");
+        Ok(Self { watchlist, sold, stream: Some(handle) })
+    }
+
+    /// A watcher with no feed behind it, for driving the bot in tests
+    #[cfg(test)]
+    pub fn detached() -> Self {
+        Self { watchlist: Arc::default(), sold: Arc::default(), stream: None }
+    }
+
+    /// Watch `creator` for sells of `mint` until `unwatch`
+    pub fn watch(&self, mint: &str, creator: &str) {
+        let subscribe = self.watchlist.lock().unwrap().watch(mint, creator);
+        if let (true, Some(stream)) = (subscribe, &self.stream) {
+            stream.subscribe(&Subscription::AccountTrades(vec![creator.to_string()]));
+        }
+    }
+
+    /// Stop watching `mint` and forget any sell recorded for it
+    pub fn unwatch(&self, mint: &str) {
+        let creator = self.watchlist.lock().unwrap().unwatch(mint);
+        if let (Some(creator), Some(stream)) = (creator, &self.stream) {
+            stream.unsubscribe(&Subscription::AccountTrades(vec![creator]));
+        }
+    }
+
+    /// The creator's sell of `mint`, if one has been seen
+    pub fn dev_sold(&self, mint: &str) -> Option<DevSell> {
+        self.watchlist.lock().unwrap().sells.get(mint).cloned()
+    }
+
+    /// Wait until the creator of `mint` sells
+    pub async fn sold(&self, mint: &str) -> DevSell {
+        loop {
+            // Registered before the check so a sell in between isn't missed
+            let notified = self.sold.notified();
+            if let Some(sell) = self.dev_sold(mint) {
+                return sell;
+            }
+            notified.await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const MINT: &str = "7GCihgDB8fe6KNjn2MYtkzZcRjQy3t9GHdC8uHYmW2hr";
+    const DEV: &str = "8kV2fb3Kc3KQy3X2WeGXtJqDqWZmYCWjdMzbEHSvHpXr";
+
+    fn trade(tx_type: &str, mint: &str, trader: &str) -> PumpPortalEvent {
+        serde_json::from_value(json!({
+            "signature": "5sig",
+            "mint": mint,
+            "traderPublicKey": trader,
+            "txType": tx_type,
+            "tokenAmount": 1000000.0,
+            "solAmount": 0.8,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_only_the_creator_selling_their_mint_counts() {
+        let mut watchlist = DevWatchlist::default();
+        assert!(watchlist.watch(MINT, DEV));
+
+        // Someone else selling, the dev buying, the dev selling another token
+        assert!(watchlist.record(&trade("sell", MINT, "SomeoneElse")).is_none());
+        assert!(watchlist.record(&trade("buy", MINT, DEV)).is_none());
+        assert!(watchlist.record(&trade("sell", "OtherMint", DEV)).is_none());
+        assert!(watchlist.sells.is_empty());
+
+        let sell = watchlist.record(&trade("sell", MINT, DEV)).unwrap();
+        assert_eq!(sell.creator, DEV);
+        assert_eq!(sell.sol_amount, 0.8);
+        assert!(watchlist.sells.contains_key(MINT));
+    }
+
+    #[test]
+    fn test_shared_creator_stays_subscribed() {
+        let mut watchlist = DevWatchlist::default();
+        assert!(watchlist.watch(MINT, DEV));
+        // Same dev launched a second token we hold, already subscribed
+        assert!(!watchlist.watch("SecondMint", DEV));
+
+        watchlist.record(&trade("sell", MINT, DEV));
+        assert_eq!(watchlist.unwatch(MINT), None);
+        assert!(watchlist.sells.is_empty());
+
+        assert_eq!(watchlist.unwatch("SecondMint").as_deref(), Some(DEV));
+        assert_eq!(watchlist.unwatch("SecondMint"), None);
+    }
+}
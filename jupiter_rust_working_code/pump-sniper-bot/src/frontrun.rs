@@ -5,13 +5,59 @@
 //! 2. Watch for pending sell transactions
 //! 3. Front-run with our sell before theirs executes
 //! 4. Protect against getting dumped on
-
-use anyhow::Result;
-use solana_client::rpc_client::RpcClient;
+//!
+//! Sells are picked up from pump.fun `TradeEvent` logs over an RPC WebSocket
+//! `logsSubscribe`, at `processed` commitment. That's the earliest public
+//! signal, so in practice we react to a whale's sell rather than beat it.
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use solana_account_decoder::parse_token::UiTokenAmount;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_request::TokenAccountsFilter;
 use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
 use log::{info, warn};
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use crate::database::Database;
+use crate::detector::bonding_curve_address;
+
+/// Anchor discriminator of pump.fun's `TradeEvent` (sha256("event:TradeEvent")[..8])
+const TRADE_EVENT_DISCRIMINATOR: [u8; 8] = [189, 219, 127, 211, 78, 230, 97, 238];
+
+/// pump.fun tokens use 6 decimals
+const PUMP_TOKEN_DECIMALS: i32 = 6;
+
+/// A logged whale sell stays actionable this long
+const SELL_SIGNAL_TTL: Duration = Duration::from_secs(15);
+
+/// Wait before reconnecting a dropped log subscription
+const LOG_RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// Recent sells seen in logs, keyed by (mint, seller wallet)
+type SellSignals = Arc<Mutex<HashMap<(String, String), SellSignal>>>;
+
+#[derive(Debug, Clone)]
+struct SellSignal {
+    token_amount: f64,
+    seen_at: Instant,
+}
+
+/// Buy or sell decoded from a pump.fun `TradeEvent` log
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeLog {
+    pub mint: Pubkey,
+    pub sol_amount: u64,   // Lamports
+    pub token_amount: u64, // Raw units
+    pub is_buy: bool,
+    pub user: Pubkey,
+}
 
 #[derive(Debug, Clone)]
 pub struct WhaleWallet {
@@ -22,7 +68,7 @@ pub struct WhaleWallet {
     pub danger_level: DangerLevel,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum DangerLevel {
     Safe,      // <5% held
     Watch,     // 5-20% held
@@ -32,11 +78,17 @@ pub enum DangerLevel {
 
 pub struct FrontRunProtector {
     rpc: RpcClient,
-    monitored_whales: HashMap<String, WhaleWallet>,
+    ws_url: String,
+    db: Database,
+    /// Whales of each mint we hold, keyed by mint
+    monitored_whales: Mutex<HashMap<String, Vec<WhaleWallet>>>,
+    sell_signals: SellSignals,
+    log_watchers: Mutex<HashMap<String, JoinHandle<()>>>,
 }
 
 impl FrontRunProtector {
-    pub fn new(rpc_url: String) -> Result<Self> {
+    pub fn new(rpc_url: String, db: Database) -> Result<Self> {
+        let ws_url = rpc_ws_url(&rpc_url);
         let rpc = RpcClient::new_with_commitment(
             rpc_url,
             CommitmentConfig::confirmed()
@@ -44,29 +96,62 @@ impl FrontRunProtector {
 
         Ok(Self {
             rpc,
-            monitored_whales: HashMap::new(),
+            ws_url,
+            db,
+            monitored_whales: Mutex::new(HashMap::new()),
+            sell_signals: Arc::new(Mutex::new(HashMap::new())),
+            log_watchers: Mutex::new(HashMap::new()),
         })
     }
 
+    /// Start streaming trade logs for a mint so whale sells are seen as they land
+    /// No-op if already watching
+    pub fn watch_trade_logs(&self, token_mint: &str) {
+        let mut log_watchers = self.log_watchers.lock().unwrap();
+        if log_watchers.contains_key(token_mint) {
+            return;
+        }
+
+        info!("📡 Subscribing to trade logs for {}", token_mint);
+        let handle = tokio::spawn(stream_trade_logs(
+            self.ws_url.clone(),
+            token_mint.to_string(),
+            self.sell_signals.clone(),
+        ));
+        log_watchers.insert(token_mint.to_string(), handle);
+    }
+
+    /// Stop the log subscription for a mint and forget its whales (e.g. after
+    /// exiting the position)
+    pub fn stop_watching(&self, token_mint: &str) {
+        if let Some(handle) = self.log_watchers.lock().unwrap().remove(token_mint) {
+            handle.abort();
+        }
+        self.monitored_whales.lock().unwrap().remove(token_mint);
+        self.sell_signals
+            .lock()
+            .unwrap()
+            .retain(|(mint, _), _| mint != token_mint);
+    }
+
     /// Identify and start monitoring large holders
-    pub async fn identify_whales(&mut self, token_mint: &str) -> Result<Vec<WhaleWallet>> {
+    /// `creator` is the launch's dev wallet, if known
+    pub async fn identify_whales(&self, token_mint: &str, creator: Option<&str>) -> Result<Vec<WhaleWallet>> {
         info!("🐋 Identifying whale holders for {}", token_mint);
 
-        // TODO: Implement actual holder analysis
-        // Need to:
-        // 1. Get all token holders
-        // 2. Calculate percentage held
-        // 3. Identify dev wallet
-        // 4. Classify by danger level
-
-        let whales = self.analyze_holders(token_mint).await?;
+        let whales = self.analyze_holders(token_mint, creator).await?;
+        if !whales.is_empty() {
+            self.watch_trade_logs(token_mint);
+        }
 
-        // Store whales for monitoring
         for whale in &whales {
-            self.monitored_whales.insert(
-                whale.address.to_string(),
-                whale.clone()
-            );
+            self.db.update_whale(
+                token_mint,
+                &whale.address.to_string(),
+                whale.token_balance,
+                whale.percentage_held,
+                whale.danger_level.as_str(),
+            )?;
 
             match whale.danger_level {
                 DangerLevel::Critical => {
@@ -85,6 +170,8 @@ impl FrontRunProtector {
             }
         }
 
+        // Store whales for monitoring
+        self.monitored_whales.lock().unwrap().insert(token_mint.to_string(), whales.clone());
         Ok(whales)
     }
 
@@ -93,54 +180,71 @@ impl FrontRunProtector {
         // Monitor pending transactions from whale wallets
         // If we detect a sell, return immediately so we can front-run
 
-        for (address, whale) in &self.monitored_whales {
+        for whale in self.whales_of(token_mint) {
+            let address = whale.address.to_string();
             // Check if whale has pending sell transaction
-            if self.has_pending_sell(address, token_mint).await? {
+            if self.has_pending_sell(&address, token_mint).await? {
                 warn!("🚨 WHALE DUMP DETECTED!");
                 warn!("   Wallet: {}", address);
                 warn!("   Holding: {:.1}%", whale.percentage_held);
                 warn!("   🏃 FRONT-RUNNING NOW!");
 
-                return Ok(Some(whale.clone()));
+                return Ok(Some(whale));
             }
         }
 
         Ok(None)
     }
 
-    /// Check if wallet has pending sell transaction
-    async fn has_pending_sell(&self, _wallet: &str, _token_mint: &str) -> Result<bool> {
-        // TODO: Implement mempool monitoring
-        // Options:
-        // 1. Monitor RPC mempool
-        // 2. Listen to program logs
-        // 3. Check transaction signatures
+    /// Whales being monitored for `token_mint`
+    fn whales_of(&self, token_mint: &str) -> Vec<WhaleWallet> {
+        self.monitored_whales.lock().unwrap().get(token_mint).cloned().unwrap_or_default()
+    }
+
+    /// Check if wallet just sold, per the trade log subscription
+    /// Each signal is consumed once so the same sell doesn't re-trigger
+    async fn has_pending_sell(&self, wallet: &str, token_mint: &str) -> Result<bool> {
+        let key = (token_mint.to_string(), wallet.to_string());
+        let signal = self.sell_signals.lock().unwrap().remove(&key);
 
-        // For now, return false
-        Ok(false)
+        match signal {
+            Some(signal) if signal.seen_at.elapsed() <= SELL_SIGNAL_TTL => {
+                warn!("   Sold {:.0} tokens {:?} ago", signal.token_amount, signal.seen_at.elapsed());
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
     }
 
     /// Analyze token holders
-    async fn analyze_holders(&self, _token_mint: &str) -> Result<Vec<WhaleWallet>> {
-        // TODO: Implement real holder analysis
-        // Need to:
-        // 1. Get all token accounts for mint
-        // 2. Get balances
-        // 3. Calculate percentages
-        // 4. Identify suspicious patterns
-
-        // Mock data for now
-        let mock_whales = vec![
-            WhaleWallet {
-                address: Pubkey::from_str("11111111111111111111111111111111").unwrap(),
-                token_balance: 500000.0,
-                percentage_held: 50.0,
-                is_dev: true,
-                danger_level: DangerLevel::Critical,
-            },
-        ];
+    /// Uses the mint's largest token accounts (top 20), which is where any whale will be
+    async fn analyze_holders(&self, token_mint: &str, creator: Option<&str>) -> Result<Vec<WhaleWallet>> {
+        let mint = Pubkey::from_str(token_mint).context("Invalid mint address")?;
+
+        let supply = ui_amount(&self.rpc.get_token_supply(&mint).await?);
+        let largest = self.rpc.get_token_largest_accounts(&mint).await?;
+
+        let account_keys = largest
+            .iter()
+            .map(|a| Pubkey::from_str(&a.address))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let accounts = self.rpc.get_multiple_accounts(&account_keys).await?;
+
+        // Token accounts → owning wallets; one wallet can hold several accounts
+        let mut balances: HashMap<Pubkey, f64> = HashMap::new();
+        for (balance, account) in largest.iter().zip(accounts) {
+            let owner = match account.as_ref().and_then(|a| token_account_owner(&a.data)) {
+                Some(owner) => owner,
+                None => continue,
+            };
+            *balances.entry(owner).or_default() += ui_amount(&balance.amount);
+        }
+
+        let creator = creator.map(Pubkey::from_str).transpose()?;
+        let curve = bonding_curve_address(token_mint)?;
+        let holders: Vec<(Pubkey, f64)> = balances.into_iter().collect();
 
-        Ok(mock_whales)
+        Ok(classify_holders(&holders, supply, creator.as_ref(), &curve))
     }
 
     /// Calculate front-run timing
@@ -152,7 +256,7 @@ impl FrontRunProtector {
                 FrontRunStrategy {
                     action: FrontRunAction::SellImmediately,
                     priority_fee: 0.001, // High fee to guarantee first
-                    slippage: 30.0,      // Accept high slippage
+                    slippage: 30,        // Accept high slippage
                     use_jito: true,
                 }
             }
@@ -162,7 +266,7 @@ impl FrontRunProtector {
                 FrontRunStrategy {
                     action: FrontRunAction::SellFast,
                     priority_fee: 0.0005,
-                    slippage: 20.0,
+                    slippage: 20,
                     use_jito: true,
                 }
             }
@@ -172,7 +276,7 @@ impl FrontRunProtector {
                 FrontRunStrategy {
                     action: FrontRunAction::MonitorClosely,
                     priority_fee: 0.0001,
-                    slippage: 15.0,
+                    slippage: 15,
                     use_jito: false,
                 }
             }
@@ -181,7 +285,7 @@ impl FrontRunProtector {
                 FrontRunStrategy {
                     action: FrontRunAction::Ignore,
                     priority_fee: 0.0001,
-                    slippage: 10.0,
+                    slippage: 10,
                     use_jito: false,
                 }
             }
@@ -192,9 +296,10 @@ impl FrontRunProtector {
     pub async fn check_whale_movements(&self, token_mint: &str) -> Result<Vec<WhaleMovement>> {
         let mut movements = Vec::new();
 
-        for (address, whale) in &self.monitored_whales {
+        for whale in self.whales_of(token_mint) {
+            let address = whale.address.to_string();
             // Get current balance
-            let current_balance = self.get_token_balance(address, token_mint).await?;
+            let current_balance = self.get_token_balance(&address, token_mint).await?;
 
             // Compare to stored balance
             if current_balance < whale.token_balance * 0.9 {
@@ -207,7 +312,7 @@ impl FrontRunProtector {
                 warn!("   Sold: {:.0} tokens ({:.1}%)", amount_sold, percent_sold);
 
                 movements.push(WhaleMovement {
-                    whale: whale.clone(),
+                    whale,
                     amount_sold,
                     percent_sold,
                     timestamp: chrono::Utc::now().timestamp(),
@@ -218,9 +323,17 @@ impl FrontRunProtector {
         Ok(movements)
     }
 
-    async fn get_token_balance(&self, _wallet: &str, _token_mint: &str) -> Result<f64> {
-        // TODO: Implement real balance check
-        Ok(1000000.0)
+    async fn get_token_balance(&self, wallet: &str, token_mint: &str) -> Result<f64> {
+        let owner = Pubkey::from_str(wallet).context("Invalid wallet address")?;
+        let mint = Pubkey::from_str(token_mint).context("Invalid mint address")?;
+
+        let mut total = 0.0;
+        for account in self.rpc.get_token_accounts_by_owner(&owner, TokenAccountsFilter::Mint(mint)).await? {
+            let pubkey = Pubkey::from_str(&account.pubkey)?;
+            total += ui_amount(&self.rpc.get_token_account_balance(&pubkey).await?);
+        }
+
+        Ok(total)
     }
 }
 
@@ -228,7 +341,7 @@ impl FrontRunProtector {
 pub struct FrontRunStrategy {
     pub action: FrontRunAction,
     pub priority_fee: f64,
-    pub slippage: f64,
+    pub slippage: u32,
     pub use_jito: bool,
 }
 
@@ -249,6 +362,16 @@ pub struct WhaleMovement {
 }
 
 impl DangerLevel {
+    /// Value stored in `whale_wallets.danger_level`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DangerLevel::Safe => "SAFE",
+            DangerLevel::Watch => "WATCH",
+            DangerLevel::Risky => "RISKY",
+            DangerLevel::Critical => "CRITICAL",
+        }
+    }
+
     pub fn from_percentage(percent: f64, is_dev: bool) -> Self {
         if is_dev && percent > 20.0 {
             return DangerLevel::Critical;
@@ -263,6 +386,173 @@ impl DangerLevel {
     }
 }
 
+/// Subscribe to logs mentioning `mint` and record every sell, reconnecting on failure
+async fn stream_trade_logs(ws_url: String, mint: String, sells: SellSignals) {
+    loop {
+        if let Err(e) = read_trade_logs(&ws_url, &mint, &sells).await {
+            warn!("Trade log subscription for {} dropped: {}", mint, e);
+        }
+        tokio::time::sleep(LOG_RECONNECT_DELAY).await;
+    }
+}
+
+async fn read_trade_logs(ws_url: &str, mint: &str, sells: &SellSignals) -> Result<()> {
+    let (mut ws, _) = connect_async(ws_url).await.context("Failed to connect to RPC WebSocket")?;
+
+    let subscribe = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "logsSubscribe",
+        "params": [{ "mentions": [mint] }, { "commitment": "processed" }],
+    });
+    ws.send(Message::Text(subscribe.to_string())).await?;
+
+    while let Some(msg) = ws.next().await {
+        let text = match msg? {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        let value: Value = match serde_json::from_str(&text) {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+
+        // Failed transactions didn't sell anything
+        let notification = match value.pointer("/params/result/value") {
+            Some(n) if n.get("err").is_none_or(Value::is_null) => n,
+            _ => continue,
+        };
+
+        let logs: Vec<&str> = notification
+            .get("logs")
+            .and_then(Value::as_array)
+            .map(|logs| logs.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+
+        for trade in trade_events_in_logs(&logs) {
+            if trade.is_buy || trade.mint.to_string() != mint {
+                continue;
+            }
+
+            let signal = SellSignal {
+                token_amount: trade.token_amount as f64 / 10_f64.powi(PUMP_TOKEN_DECIMALS),
+                seen_at: Instant::now(),
+            };
+            sells
+                .lock()
+                .unwrap()
+                .insert((mint.to_string(), trade.user.to_string()), signal);
+        }
+    }
+
+    Ok(())
+}
+
+/// WebSocket endpoint for an HTTP RPC URL
+/// A validator serves PubSub one port above an explicit RPC port (8899 → 8900);
+/// a scheme's default port carries over as is
+fn rpc_ws_url(rpc_url: &str) -> String {
+    let (scheme, default_port, rest) = if let Some(rest) = rpc_url.strip_prefix("https://") {
+        ("wss://", 443, rest)
+    } else if let Some(rest) = rpc_url.strip_prefix("http://") {
+        ("ws://", 80, rest)
+    } else {
+        return rpc_url.to_string();
+    };
+
+    let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let pubsub_port = authority
+        .rsplit_once(':')
+        .and_then(|(host, port)| Some((host, port.parse::<u16>().ok()?)))
+        .filter(|&(_, port)| port != default_port)
+        .and_then(|(host, port)| Some((host, port.checked_add(1)?)));
+    match pubsub_port {
+        Some((host, port)) => format!("{}{}:{}{}", scheme, host, port, path),
+        None => format!("{}{}", scheme, rest),
+    }
+}
+
+/// Decode every pump.fun `TradeEvent` emitted in a transaction's logs
+pub fn trade_events_in_logs(logs: &[&str]) -> Vec<TradeLog> {
+    logs.iter()
+        .filter_map(|line| line.strip_prefix("Program data: "))
+        .filter_map(|data| base64::engine::general_purpose::STANDARD.decode(data).ok())
+        .filter_map(|bytes| parse_trade_event(&bytes))
+        .collect()
+}
+
+/// Parse `TradeEvent` event data
+/// Layout: discriminator(8) mint(32) sol_amount(8) token_amount(8) is_buy(1) user(32) ...
+pub fn parse_trade_event(data: &[u8]) -> Option<TradeLog> {
+    if data.get(..8)? != TRADE_EVENT_DISCRIMINATOR {
+        return None;
+    }
+
+    let pubkey_at = |offset: usize| -> Option<Pubkey> {
+        let bytes: [u8; 32] = data.get(offset..offset + 32)?.try_into().ok()?;
+        Some(Pubkey::new_from_array(bytes))
+    };
+    let u64_at = |offset: usize| -> Option<u64> {
+        Some(u64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?))
+    };
+
+    Some(TradeLog {
+        mint: pubkey_at(8)?,
+        sol_amount: u64_at(40)?,
+        token_amount: u64_at(48)?,
+        is_buy: *data.get(56)? != 0,
+        user: pubkey_at(57)?,
+    })
+}
+
+/// Token amount in UI units (decimals applied)
+fn ui_amount(amount: &UiTokenAmount) -> f64 {
+    amount.amount.parse::<f64>().unwrap_or(0.0) / 10_f64.powi(amount.decimals as i32)
+}
+
+/// Owner of an SPL token account (bytes 32-64 of the account data)
+fn token_account_owner(data: &[u8]) -> Option<Pubkey> {
+    let bytes: [u8; 32] = data.get(32..64)?.try_into().ok()?;
+    Some(Pubkey::new_from_array(bytes))
+}
+
+/// Turn per-wallet balances into whales worth watching, largest first
+///
+/// The bonding curve holds unsold supply and is skipped. The dev wallet is
+/// always kept so we can watch it; everyone else needs at least `Watch`.
+pub fn classify_holders(
+    holders: &[(Pubkey, f64)],
+    supply: f64,
+    creator: Option<&Pubkey>,
+    bonding_curve: &Pubkey,
+) -> Vec<WhaleWallet> {
+    if supply <= 0.0 {
+        return Vec::new();
+    }
+
+    let mut whales: Vec<WhaleWallet> = holders
+        .iter()
+        .filter(|(owner, _)| owner != bonding_curve)
+        .map(|&(address, balance)| {
+            let is_dev = creator == Some(&address);
+            let percentage_held = balance / supply * 100.0;
+            WhaleWallet {
+                address,
+                token_balance: balance,
+                percentage_held,
+                is_dev,
+                danger_level: DangerLevel::from_percentage(percentage_held, is_dev),
+            }
+        })
+        .filter(|w| w.is_dev || w.danger_level != DangerLevel::Safe)
+        .collect();
+
+    whales.sort_by(|a, b| b.percentage_held.total_cmp(&a.percentage_held));
+    whales
+}
+
 /// Integration with main strategy
 pub async fn should_emergency_exit(
     protector: &FrontRunProtector,
@@ -288,3 +578,97 @@ pub async fn should_emergency_exit(
 
     Ok(None)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_holders() {
+        let curve = Pubkey::new_unique();
+        let dev = Pubkey::new_unique();
+        let whale = Pubkey::new_unique();
+        let minnow = Pubkey::new_unique();
+
+        let holders = vec![
+            (curve, 700.0),
+            (minnow, 10.0),
+            (dev, 30.0),
+            (whale, 250.0),
+        ];
+        let whales = classify_holders(&holders, 1000.0, Some(&dev), &curve);
+
+        // Curve skipped, minnow (1%) below Watch, dev kept despite 3%
+        assert_eq!(whales.len(), 2);
+        assert_eq!(whales[0].address, whale);
+        assert_eq!(whales[0].danger_level, DangerLevel::Risky);
+        assert!((whales[0].percentage_held - 25.0).abs() < 1e-9);
+        assert!(whales[1].is_dev);
+        assert_eq!(whales[1].danger_level, DangerLevel::Safe);
+
+        assert!(classify_holders(&holders, 0.0, None, &curve).is_empty());
+    }
+
+    fn trade_event_bytes(mint: &Pubkey, is_buy: bool, user: &Pubkey) -> Vec<u8> {
+        let mut data = TRADE_EVENT_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(mint.as_ref());
+        data.extend_from_slice(&1_500_000_000u64.to_le_bytes());
+        data.extend_from_slice(&25_000_000_000u64.to_le_bytes());
+        data.push(is_buy as u8);
+        data.extend_from_slice(user.as_ref());
+        data.extend_from_slice(&1_700_000_000i64.to_le_bytes()); // timestamp
+        data
+    }
+
+    #[test]
+    fn test_trade_events_in_logs() {
+        let mint = Pubkey::new_unique();
+        let whale = Pubkey::new_unique();
+        let encoded = base64::engine::general_purpose::STANDARD
+            .encode(trade_event_bytes(&mint, false, &whale));
+        let program_data = format!("Program data: {}", encoded);
+
+        let logs = [
+            "Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P invoke [1]",
+            "Program log: Instruction: Sell",
+            program_data.as_str(),
+            "Program data: bm90IGEgdHJhZGU=", // Some other event
+        ];
+
+        let trades = trade_events_in_logs(&logs);
+        assert_eq!(trades.len(), 1);
+        assert_eq!(
+            trades[0],
+            TradeLog {
+                mint,
+                sol_amount: 1_500_000_000,
+                token_amount: 25_000_000_000,
+                is_buy: false,
+                user: whale,
+            }
+        );
+
+        // Truncated event is ignored rather than misread
+        let bytes = trade_event_bytes(&mint, true, &whale);
+        assert!(parse_trade_event(&bytes[..80]).is_none());
+    }
+
+    #[test]
+    fn test_rpc_ws_url() {
+        assert_eq!(rpc_ws_url("https://api.mainnet-beta.solana.com"), "wss://api.mainnet-beta.solana.com");
+        assert_eq!(rpc_ws_url("http://localhost/rpc"), "ws://localhost/rpc");
+        assert_eq!(rpc_ws_url("http://127.0.0.1:8899"), "ws://127.0.0.1:8900");
+        assert_eq!(rpc_ws_url("https://rpc.example.com:443/key/abc"), "wss://rpc.example.com:443/key/abc");
+        assert_eq!(rpc_ws_url("http://[::1]:8899/"), "ws://[::1]:8900/");
+    }
+
+    #[test]
+    fn test_token_account_owner() {
+        let owner = Pubkey::new_unique();
+        let mut data = vec![0u8; 165];
+        data[32..64].copy_from_slice(owner.as_ref());
+
+        assert_eq!(token_account_owner(&data), Some(owner));
+        assert_eq!(token_account_owner(&data[..40]), None);
+    }
+}
@@ -3,100 +3,58 @@
 //! Monitors new pump.fun token launches using PumpPortal's WebSocket feed.
 //! Much simpler and more reliable than parsing raw Solana logs.
 
-use anyhow::{Result, Context};
-use log::{info, warn, error};
-use serde::{Deserialize, Serialize};
-use tokio::sync::mpsc;
+use anyhow::{anyhow, Result, Context};
+use log::{debug, info, warn, error};
+use pump_portal_sdk::{NewTokenEvent, PumpPortalEvent};
+use std::collections::{HashSet, VecDeque};
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
 use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use tokio_util::sync::CancellationToken;
 use futures_util::StreamExt;
 
 /// PumpPortal WebSocket URL for new token launches
 const PUMPPORTAL_WS_URL: &str = "wss://pumpportal.fun/api/data";
 
-/// Represents a newly detected token launch from PumpPortal
-#[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct TokenLaunch {
-    pub mint: String,
-    pub name: String,
-    pub symbol: String,
-    pub description: Option<String>,
-    pub image: Option<String>,
-    pub metadata_uri: Option<String>,
-    pub twitter: Option<String>,
-    pub telegram: Option<String>,
-    pub bonding_curve: Option<String>,
-    pub associated_bonding_curve: Option<String>,
-    pub creator: Option<String>,
-    pub created_timestamp: u64,
-    pub raydium_pool: Option<String>,
-    pub complete: bool,
-    pub virtual_sol_reserves: Option<u64>,
-    pub virtual_token_reserves: Option<u64>,
-    pub total_supply: Option<u64>,
-    pub website: Option<String>,
-    pub show_name: bool,
-    pub king_of_the_hill_timestamp: Option<u64>,
-    pub market_cap: Option<f64>,
-    pub reply_count: Option<u32>,
-    pub last_reply: Option<u64>,
-    pub nsfw: bool,
-    pub market_id: Option<String>,
-    pub inverted: Option<bool>,
-    pub username: Option<String>,
-    pub profile_image: Option<String>,
-    pub usd_market_cap: Option<f64>,
-}
+/// A newly detected token launch from PumpPortal
+pub type TokenLaunch = NewTokenEvent;
 
-impl TokenLaunch {
-    pub fn display(&self) {
-        info!("🚀 NEW LAUNCH DETECTED");
-        info!("   Name: {} ({})", self.name, self.symbol);
-        info!("   Mint: {}", self.mint);
-        if let Some(creator) = &self.creator {
-            info!("   Creator: {}", creator);
-        }
-        if let Some(desc) = &self.description {
-            info!("   Description: {}", desc);
-        }
-        if let Some(mc) = self.market_cap {
-            info!("   Market Cap: ${:.2}", mc);
-        }
-        info!("   🔗 https://pump.fun/{}", self.mint);
-        info!("   🔗 https://solscan.io/token/{}", self.mint);
+fn display_launch(launch: &TokenLaunch) {
+    info!("🚀 NEW LAUNCH DETECTED");
+    info!("   Name: {} ({})", launch.name, launch.symbol);
+    info!("   Mint: {}", launch.mint);
+    if let Some(creator) = launch.creator() {
+        info!("   Creator: {}", creator);
     }
+    if let Some(mc) = launch.market_cap_sol {
+        info!("   Market Cap: {:.2} SOL", mc);
+    }
+    info!("   🔗 https://pump.fun/{}", launch.mint);
+    info!("   🔗 https://solscan.io/token/{}", launch.mint);
+}
 
-    /// Check if this token is suitable for sniping
-    pub fn is_snipeable(&self) -> bool {
-        // Don't snipe completed tokens (already graduated to Raydium)
-        if self.complete {
-            warn!("   ⚠️  Token already graduated to Raydium, skipping");
-            return false;
-        }
-
-        // Don't snipe NSFW tokens (optional filter)
-        if self.nsfw {
-            warn!("   ⚠️  NSFW token, skipping");
-            return false;
-        }
+/// Check if this token is suitable for sniping
+///
+/// Launches whose bonding curve holds less than `min_sol_reserves` SOL are
+/// too thin to buy into; ours alone would move the price. Launches that
+/// don't report reserves are let through.
+fn is_snipeable(launch: &TokenLaunch, min_sol_reserves: f64) -> bool {
+    // Check for basic metadata
+    if launch.name.is_empty() || launch.symbol.is_empty() {
+        warn!("   ⚠️  Missing name/symbol, skipping");
+        return false;
+    }
 
-        // Check for basic metadata
-        if self.name.is_empty() || self.symbol.is_empty() {
-            warn!("   ⚠️  Missing name/symbol, skipping");
+    // Don't snipe launches without enough depth
+    if let Some(reserves) = launch.v_sol_in_bonding_curve {
+        if reserves < min_sol_reserves {
+            warn!("   ⚠️  Low liquidity: {:.2} SOL in curve (min {:.2}), skipping",
+                reserves, min_sol_reserves);
             return false;
         }
-
-        true
     }
-}
 
-/// WebSocket message from PumpPortal
-#[derive(Debug, Deserialize)]
-#[serde(tag = "txType")]
-enum PumpPortalMessage {
-    #[serde(rename = "create")]
-    Create(TokenLaunch),
-    #[serde(other)]
-    Other,
+    true
 }
 
 /// Launch detector configuration
@@ -104,6 +62,10 @@ pub struct LaunchDetectorConfig {
     pub ws_url: String,
     pub buffer_size: usize,
     pub reconnect_delay_secs: u64,
+    /// Minimum virtual SOL reserves (in SOL) for a launch to be snipeable
+    pub min_sol_reserves: f64,
+    /// How many recent mints to remember for dropping re-delivered launches
+    pub dedup_window: usize,
 }
 
 impl Default for LaunchDetectorConfig {
@@ -112,10 +74,82 @@ impl Default for LaunchDetectorConfig {
             ws_url: PUMPPORTAL_WS_URL.to_string(),
             buffer_size: 100,
             reconnect_delay_secs: 5,
+            min_sol_reserves: 0.0,
+            dedup_window: 1000,
         }
     }
 }
 
+/// Bounded set of recently seen mints; the oldest is forgotten once full
+struct RecentMints {
+    seen: HashSet<String>,
+    order: VecDeque<String>,
+    capacity: usize,
+}
+
+impl RecentMints {
+    fn new(capacity: usize) -> Self {
+        Self {
+            seen: HashSet::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Remember `mint`, returning false if it was already seen
+    fn insert(&mut self, mint: &str) -> bool {
+        if self.capacity == 0 {
+            return true;
+        }
+        if self.seen.contains(mint) {
+            return false;
+        }
+        if self.order.len() >= self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        self.seen.insert(mint.to_string());
+        self.order.push_back(mint.to_string());
+        true
+    }
+}
+
+/// Connection state of the detector's WebSocket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connecting,
+    Connected,
+    /// Connection lost or refused, waiting `reconnect_delay_secs` before retrying
+    Reconnecting,
+    /// Monitoring task has exited
+    Stopped,
+}
+
+/// Handle to a running launch monitor
+///
+/// Dropping it doesn't stop the task; call `stop()`.
+pub struct LaunchMonitor {
+    /// Snipeable launches, closed once the task exits
+    pub launches: mpsc::Receiver<TokenLaunch>,
+    /// Latest connection state
+    pub state: watch::Receiver<ConnectionState>,
+    task: JoinHandle<Result<()>>,
+    shutdown: CancellationToken,
+}
+
+impl LaunchMonitor {
+    /// Ask the monitoring task to stop
+    pub fn stop(&self) {
+        self.shutdown.cancel();
+    }
+
+    /// Wait for the monitoring task to exit, returning why it did
+    pub async fn join(self) -> Result<()> {
+        self.task.await.map_err(|e| anyhow!("Launch detector task failed: {}", e))?
+    }
+}
+
 /// Main launch detector using PumpPortal WebSocket
 pub struct LaunchDetector {
     config: LaunchDetectorConfig,
@@ -128,31 +162,55 @@ impl LaunchDetector {
 
     /// Start monitoring for new token launches
     ///
-    /// Returns a channel receiver that yields TokenLaunch events
-    pub async fn start_monitoring(&self) -> Result<mpsc::Receiver<TokenLaunch>> {
+    /// Returns a handle whose `launches` channel yields TokenLaunch events
+    pub async fn start_monitoring(&self) -> Result<LaunchMonitor> {
         let (tx, rx) = mpsc::channel(self.config.buffer_size);
+        let (state_tx, state_rx) = watch::channel(ConnectionState::Connecting);
+        let shutdown = CancellationToken::new();
 
         info!("🔍 Starting PumpPortal launch detector...");
         info!("   WebSocket: {}", self.config.ws_url);
 
         let ws_url = self.config.ws_url.clone();
         let reconnect_delay = self.config.reconnect_delay_secs;
+        let min_sol_reserves = self.config.min_sol_reserves;
+        let recent = RecentMints::new(self.config.dedup_window);
 
         // Spawn monitoring task
-        tokio::spawn(async move {
-            if let Err(e) = Self::monitor_websocket(ws_url, reconnect_delay, tx).await {
-                error!("Launch detector error: {}", e);
-            }
-        });
+        let task = {
+            let shutdown = shutdown.clone();
+            tokio::spawn(async move {
+                let result = tokio::select! {
+                    result = Self::monitor_websocket(ws_url, reconnect_delay, min_sol_reserves, recent, tx, &state_tx) => result,
+                    _ = shutdown.cancelled() => {
+                        info!("Launch detector stopped");
+                        Ok(())
+                    }
+                };
+                if let Err(e) = &result {
+                    error!("Launch detector error: {}", e);
+                }
+                let _ = state_tx.send(ConnectionState::Stopped);
+                result
+            })
+        };
 
-        Ok(rx)
+        Ok(LaunchMonitor {
+            launches: rx,
+            state: state_rx,
+            task,
+            shutdown,
+        })
     }
 
     /// Monitor PumpPortal WebSocket for token creation events
     async fn monitor_websocket(
         ws_url: String,
         reconnect_delay: u64,
+        min_sol_reserves: f64,
+        mut recent: RecentMints,
         tx: mpsc::Sender<TokenLaunch>,
+        state: &watch::Sender<ConnectionState>,
     ) -> Result<()> {
         loop {
             info!("Connecting to PumpPortal WebSocket...");
@@ -160,6 +218,7 @@ impl LaunchDetector {
             match connect_async(&ws_url).await {
                 Ok((ws_stream, _)) => {
                     info!("✅ Connected to PumpPortal");
+                    let _ = state.send(ConnectionState::Connected);
 
                     let (_, mut read) = ws_stream.split();
 
@@ -175,23 +234,29 @@ impl LaunchDetector {
                         match message {
                             Ok(Message::Text(text)) => {
                                 // Parse message
-                                match serde_json::from_str::<PumpPortalMessage>(&text) {
-                                    Ok(PumpPortalMessage::Create(launch)) => {
-                                        launch.display();
+                                match serde_json::from_str::<PumpPortalEvent>(&text) {
+                                    Ok(PumpPortalEvent::NewToken(launch)) => {
+                                        // PumpPortal sometimes re-delivers a create, including across reconnects
+                                        if !recent.insert(&launch.mint) {
+                                            warn!("Duplicate launch event for {}, ignoring", launch.mint);
+                                            continue;
+                                        }
+                                        display_launch(&launch);
 
-                                        if launch.is_snipeable() {
+                                        if is_snipeable(&launch, min_sol_reserves) {
                                             info!("   ✅ Token is snipeable!");
-                                            if let Err(e) = tx.send(launch).await {
-                                                error!("Failed to send launch event: {}", e);
-                                                break;
+                                            if tx.send(launch).await.is_err() {
+                                                // Nobody is listening any more
+                                                return Err(anyhow!("Launch receiver dropped"));
                                             }
                                         }
                                     }
-                                    Ok(PumpPortalMessage::Other) => {
-                                        // Ignore other message types (trades, etc.)
+                                    Ok(_) => {
+                                        // Ignore other event types (trades, migrations)
                                     }
                                     Err(e) => {
-                                        error!("Failed to parse message: {} - {}", e, text);
+                                        // Subscription acks and anything without a known txType
+                                        debug!("Ignoring non-event message: {} - {}", e, text);
                                     }
                                 }
                             }
@@ -211,11 +276,13 @@ impl LaunchDetector {
                     }
 
                     warn!("WebSocket stream ended, reconnecting in {} seconds...", reconnect_delay);
+                    let _ = state.send(ConnectionState::Reconnecting);
                     tokio::time::sleep(tokio::time::Duration::from_secs(reconnect_delay)).await;
                 }
                 Err(e) => {
                     error!("Failed to connect to WebSocket: {}", e);
                     warn!("Retrying in {} seconds...", reconnect_delay);
+                    let _ = state.send(ConnectionState::Reconnecting);
                     tokio::time::sleep(tokio::time::Duration::from_secs(reconnect_delay)).await;
                 }
             }
@@ -229,27 +296,71 @@ mod tests {
 
     #[test]
     fn test_launch_parsing() {
-        let json = r#"{
-            "txType": "create",
-            "mint": "GgoaCoyqvnDE5KGLSpLPiyNVGeF8w8rm8b1Hd9JFpump",
-            "name": "Test Token",
-            "symbol": "TEST",
-            "description": "A test token",
-            "created_timestamp": 1234567890,
-            "complete": false,
-            "nsfw": false,
-            "show_name": true
-        }"#;
+        let json = r#"{"signature":"3xXvYuFq4Bzo9xjKsSgqRJ7Lq4hxuG6RyCLyHGXuxZHXcSvYTe6dYtGDiXxgvBcnyj4mnXTeVsq4hjLFXmFVN1wd","mint":"GgoaCoyqvnDE5KGLSpLPiyNVGeF8w8rm8b1Hd9JFpump","traderPublicKey":"8kV2fb3Kc3KQy3X2WeGXtJqDqWZmYCWjdMzbEHSvHpXr","txType":"create","initialBuy":65231546.478735,"solAmount":2.0,"bondingCurveKey":"HmPhVQ1vLXa2YpWCmGL3KzDmXwoebvFN3ntJCvWSwNzD","vTokensInBondingCurve":1007768453.521265,"vSolInBondingCurve":31.94153846153846,"marketCapSol":31.69529479405867,"name":"Test Token","symbol":"TEST","uri":"https://ipfs.io/ipfs/QmTestMetadataHash","pool":"pump"}"#;
 
-        let msg: PumpPortalMessage = serde_json::from_str(json).unwrap();
-        match msg {
-            PumpPortalMessage::Create(launch) => {
+        match serde_json::from_str::<PumpPortalEvent>(json).unwrap() {
+            PumpPortalEvent::NewToken(launch) => {
                 assert_eq!(launch.mint, "GgoaCoyqvnDE5KGLSpLPiyNVGeF8w8rm8b1Hd9JFpump");
                 assert_eq!(launch.name, "Test Token");
                 assert_eq!(launch.symbol, "TEST");
-                assert!(launch.is_snipeable());
+                assert_eq!(launch.creator(), Some("8kV2fb3Kc3KQy3X2WeGXtJqDqWZmYCWjdMzbEHSvHpXr"));
+                assert!(is_snipeable(&launch, 0.0));
             }
-            _ => panic!("Expected Create message"),
+            _ => panic!("Expected NewToken event"),
         }
     }
+
+    #[test]
+    fn test_min_sol_reserves() {
+        let json = r#"{
+            "mint": "GgoaCoyqvnDE5KGLSpLPiyNVGeF8w8rm8b1Hd9JFpump",
+            "name": "Test Token",
+            "symbol": "TEST",
+            "vSolInBondingCurve": 30.0
+        }"#;
+
+        let mut launch: TokenLaunch = serde_json::from_str(json).unwrap();
+        assert!(is_snipeable(&launch, 30.0));
+        assert!(!is_snipeable(&launch, 31.0));
+
+        // Unknown reserves aren't rejected
+        launch.v_sol_in_bonding_curve = None;
+        assert!(is_snipeable(&launch, 31.0));
+    }
+
+    #[test]
+    fn test_recent_mints() {
+        let mut recent = RecentMints::new(2);
+        assert!(recent.insert("a"));
+        assert!(!recent.insert("a"));
+        assert!(recent.insert("b"));
+
+        // "a" is pushed out by "c" and counts as new again
+        assert!(recent.insert("c"));
+        assert!(!recent.insert("b"));
+        assert!(recent.insert("a"));
+
+        let mut disabled = RecentMints::new(0);
+        assert!(disabled.insert("a"));
+        assert!(disabled.insert("a"));
+    }
+
+    #[tokio::test]
+    async fn test_stop_monitoring() {
+        // Nothing listens here, so the detector keeps reconnecting until stopped
+        let detector = LaunchDetector::new(LaunchDetectorConfig {
+            ws_url: "ws://127.0.0.1:1".to_string(),
+            reconnect_delay_secs: 60,
+            ..LaunchDetectorConfig::default()
+        });
+        let mut monitor = detector.start_monitoring().await.unwrap();
+
+        monitor.state.wait_for(|state| *state == ConnectionState::Reconnecting).await.unwrap();
+        assert!(!monitor.task.is_finished());
+
+        monitor.stop();
+        assert!(monitor.launches.recv().await.is_none());
+        assert_eq!(*monitor.state.borrow(), ConnectionState::Stopped);
+        monitor.join().await.unwrap();
+    }
 }
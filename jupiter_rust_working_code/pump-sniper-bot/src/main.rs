@@ -3,17 +3,38 @@ mod monitor;
 mod detector;
 mod launch_detector;
 mod database;
+mod reputation;
+mod report;
+mod dev_watch;
+mod frontrun;
+mod metrics;
 
 use dotenv::dotenv;
+use pump_portal_sdk::config::BotConfig;
+use pump_portal_sdk::{NotifyEvent, PumpPortalClient};
 use std::env;
 use log::info;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Initialize logging
-    env_logger::init();
+    pump_portal_sdk::logging::init();
     dotenv().ok();
 
+    // Settings from config.toml / config.json or the environment (see BotConfig);
+    // PAPER_MODE=true or --paper fills trades on a simulated wallet instead
+    let config = BotConfig::load(BotConfig {
+        snipe_amount_sol: 0.025, // ~$5 at $200/SOL
+        ..BotConfig::default()
+    })?;
+
+    // Report mode: print the session report from the database and exit
+    if env::args().any(|arg| arg == "--report") {
+        let db = database::Database::new(&config.database_path)?;
+        println!("{}", report::generate_report(&db)?);
+        return Ok(());
+    }
+
     info!("🚀 Pump.fun Sniper Bot Starting...");
 
     // Verify configuration
@@ -23,23 +44,102 @@ async fn main() -> anyhow::Result<()> {
     let rpc_url = env::var("HELIUS_RPC_URL")
         .expect("HELIUS_RPC_URL must be set");
 
-    let trade_amount_sol = env::var("SNIPE_AMOUNT_SOL")
-        .unwrap_or_else(|_| "0.025".to_string()) // ~$5 at $200/SOL
-        .parse::<f64>()
-        .expect("Invalid SNIPE_AMOUNT_SOL");
+    let trade_amount_sol = config.snipe_amount_sol;
+
+    // Manage positions with an SDK exit strategy instead of the built-in exits (unset = built-in)
+    let exit_strategy = config
+        .exit_strategy
+        .as_deref()
+        .map(pump_portal_sdk::exit::by_name)
+        .transpose()?;
 
     info!("📊 Configuration:");
-    info!("   Trade Size: {} SOL (~${:.2})", trade_amount_sol, trade_amount_sol * 200.0);
+    match pump_portal_sdk::sol_usd().await {
+        Ok(price) => info!("   Trade Size: {} SOL (~${:.2})", trade_amount_sol, trade_amount_sol * price),
+        Err(_) => info!("   Trade Size: {} SOL", trade_amount_sol),
+    }
+    info!("   Min Liquidity: {} SOL in curve", config.min_sol_reserves);
+    info!("   Max Hold: {}s", config.max_hold_secs);
+    if let Some(strategy) = &exit_strategy {
+        info!("   Exit Strategy: {}", strategy.name());
+    }
+    if let Some(max) = config.max_slippage {
+        info!("   Slippage Retry: up to {}%", max);
+    }
+    if let Some(max) = config.max_total_sol_at_risk {
+        info!("   Max SOL At Risk: {} SOL", max);
+    }
     info!("   RPC: Helius Premium");
     info!("   Strategy: Fast in, smart exit");
+    if config.webhook_url.is_some() {
+        let events: Vec<&str> = config.webhook_events.iter().map(NotifyEvent::as_str).collect();
+        info!("   Webhook: ON ({})", events.join(", "));
+    }
+    if config.paper.enabled {
+        info!("   Mode: 📝 PAPER TRADING ({} SOL starting balance)", config.paper.starting_balance);
+    }
 
     // Initialize database
-    let db_path = env::var("DATABASE_PATH").unwrap_or_else(|_| "sniper_bot.db".to_string());
-    let db = database::Database::new(&db_path)?;
-    info!("   Database: {}", db_path);
+    let db_path = &config.database_path;
+    let db = database::Database::new(db_path)?;
+    info!("   Database: {}", db_path.display());
+
+    // The server reads positions and P&L through its own connection
+    metrics::init();
+    let metrics_db = database::Database::new(db_path)?;
+    tokio::spawn(metrics::serve(config.metrics_port, metrics_db));
+    info!("   Metrics: http://0.0.0.0:{}/metrics (health at /healthz)", config.metrics_port);
+
+    // Active positions whose entry buy was never confirmed (left by builds that
+    // didn't wait for it) are checked on-chain; ones that never landed are dropped
+    let verify_db = database::Database::new(db_path)?;
+    tokio::spawn(strategy::verify_open_positions(
+        PumpPortalClient::new(api_key.clone()),
+        verify_db,
+        rpc_url.clone(),
+    ));
+
+    // Skip devs with this many rugs on record (0 = only the blocklist)
+    let dev_max_rugs = config.dev_max_rugs;
+    let reputation = reputation::DevReputation::new(db_path, dev_max_rugs)?;
+    if let Some(blocklist) = &config.dev_blocklist_file {
+        reputation.load_blocklist(blocklist)?;
+    }
+    if dev_max_rugs > 0 {
+        info!("   Dev Filter: blocklist + {} rug(s) on record", dev_max_rugs);
+    } else {
+        info!("   Dev Filter: blocklist only");
+    }
+
+    // Follow each position's creator wallet; a dev sell is an emergency exit
+    let dev_sells = dev_watch::DevSellWatcher::start(pump_portal_sdk::StreamConfig::default()).await?;
 
     // Start the bot
-    let bot = strategy::SniperBot::new(api_key, rpc_url, trade_amount_sol, db)?;
+    let bot = strategy::SniperBot::new(
+        api_key,
+        rpc_url,
+        &config,
+        db,
+        reputation,
+        dev_sells,
+    )?
+    .with_notifier(config.notifier("pump-sniper-bot"));
+    let bot = match config.max_slippage {
+        Some(max) => bot.with_max_slippage(max),
+        None => bot,
+    };
+    let bot = match config.max_total_sol_at_risk {
+        Some(max) => bot.with_max_sol_at_risk(max),
+        None => bot,
+    };
+    let bot = match exit_strategy {
+        Some(strategy) => bot.with_exit_strategy(strategy),
+        None => bot,
+    };
+    let bot = match config.paper_simulator() {
+        Some(paper) => bot.with_paper(paper),
+        None => bot,
+    };
 
     info!("✅ Bot initialized successfully");
     info!("👀 Monitoring for new pump.fun launches...\n");
@@ -0,0 +1,86 @@
+//! Health check and Prometheus metrics
+//!
+//! `/healthz` answers 200 while the launch detector's WebSocket is connected
+//! and 503 otherwise. `/metrics` counts launches and snipes since startup and
+//! reads open positions and realized P&L from the database on each scrape.
+
+use crate::database::Database;
+use log::error;
+use once_cell::sync::Lazy;
+use prometheus::{register_gauge, register_int_counter, register_int_gauge, Encoder, Gauge, IntCounter, IntGauge, TextEncoder};
+use std::sync::{Arc, Mutex};
+use warp::Filter;
+
+/// Snipeable launches received from the launch detector.
+pub static LAUNCHES_SEEN: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!("launches_seen_total", "Snipeable launches received from the launch detector").unwrap()
+});
+
+/// Snipes confirmed on-chain.
+pub static SNIPES_EXECUTED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!("snipes_executed_total", "Snipes confirmed on-chain").unwrap()
+});
+
+/// Positions still open in the database.
+pub static OPEN_POSITIONS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!("open_positions", "Number of currently open positions").unwrap()
+});
+
+/// Realized P&L of every closed position in the database, in SOL.
+pub static REALIZED_PNL: Lazy<Gauge> = Lazy::new(|| {
+    register_gauge!("realized_pnl_sol", "Realized P&L in SOL").unwrap()
+});
+
+/// 1 while the launch detector's WebSocket is connected.
+pub static DETECTOR_CONNECTED: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!("launch_detector_connected", "1 while the launch detector is connected").unwrap()
+});
+
+/// Register all metrics up front so `/metrics` lists them before the first update.
+pub fn init() {
+    Lazy::force(&LAUNCHES_SEEN);
+    Lazy::force(&SNIPES_EXECUTED);
+    Lazy::force(&OPEN_POSITIONS);
+    Lazy::force(&REALIZED_PNL);
+    Lazy::force(&DETECTOR_CONNECTED);
+}
+
+/// Refresh the position gauges from the database
+fn update_from_db(db: &Database) -> anyhow::Result<()> {
+    OPEN_POSITIONS.set(db.active_position_count()? as i64);
+    REALIZED_PNL.set(db.realized_pnl_sol()?);
+    Ok(())
+}
+
+/// Serve `/healthz` and `/metrics` on `port` until the bot exits
+pub async fn serve(port: u16, db: Database) {
+    let db = Arc::new(Mutex::new(db));
+
+    let metrics = warp::path("metrics").map(move || {
+        if let Err(e) = update_from_db(&db.lock().unwrap()) {
+            error!("Failed to read metrics from the database: {}", e);
+        }
+        let mut buffer = Vec::new();
+        let encoder = TextEncoder::new();
+        encoder.encode(&prometheus::gather(), &mut buffer).unwrap();
+        warp::http::Response::builder()
+            .header("content-type", encoder.format_type())
+            .body(buffer)
+            .unwrap()
+    });
+    let healthz = warp::path("healthz").map(|| {
+        if DETECTOR_CONNECTED.get() == 1 {
+            warp::http::Response::builder()
+                .status(warp::http::StatusCode::OK)
+                .body("OK")
+                .unwrap()
+        } else {
+            warp::http::Response::builder()
+                .status(warp::http::StatusCode::SERVICE_UNAVAILABLE)
+                .body("launch detector disconnected")
+                .unwrap()
+        }
+    });
+
+    warp::serve(metrics.or(healthz)).run(([0, 0, 0, 0], port)).await;
+}
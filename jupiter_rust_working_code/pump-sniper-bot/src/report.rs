@@ -0,0 +1,288 @@
+//! Session Report
+//!
+//! One readout of how the bot has done, built from the `positions` and
+//! `transactions` tables: win rate, best and worst trades, hold times,
+//! estimated fee drag, and which exit reasons made or lost money.
+//! Run with `pump-sniper-bot --report`.
+
+use crate::database::{ClosedPosition, Database, TransactionCounts};
+use anyhow::Result;
+use pump_portal_sdk::ExitReason;
+use std::collections::HashMap;
+use std::fmt;
+
+/// PumpPortal's Lightning API fee, charged on the SOL side of every buy and sell
+pub const PLATFORM_FEE_PERCENT: f64 = 1.0;
+
+/// One closed trade, as shown for best/worst
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeSummary {
+    pub mint: String,
+    pub profit_loss_sol: f64,
+    pub profit_loss_percent: f64,
+    pub hold_secs: i64,
+    pub exit_reason: Option<ExitReason>,
+}
+
+/// Closed trades and P&L for one exit reason
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExitReasonStats {
+    /// `None` for trades closed without a recognized reason
+    pub reason: Option<ExitReason>,
+    pub trades: usize,
+    pub wins: usize,
+    pub profit_loss_sol: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SessionReport {
+    pub closed_trades: usize,
+    pub open_positions: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub invested_sol: f64,
+    pub profit_loss_sol: f64,
+    /// Dollar P&L of the trades that recorded one
+    pub profit_loss_usd: Option<f64>,
+    pub best: Option<TradeSummary>,
+    pub worst: Option<TradeSummary>,
+    pub avg_hold_secs: Option<f64>,
+    /// Platform fees on entry and exit volume, at `PLATFORM_FEE_PERCENT`
+    pub estimated_fees_sol: f64,
+    pub transactions: TransactionCounts,
+    /// Most trades first
+    pub exit_reasons: Vec<ExitReasonStats>,
+}
+
+impl SessionReport {
+    /// Build from closed positions, the number still open, and transaction counts
+    pub fn from_positions(closed: &[ClosedPosition], open_positions: usize, transactions: TransactionCounts) -> Self {
+        let mut report = Self {
+            closed_trades: closed.len(),
+            open_positions,
+            transactions,
+            ..Self::default()
+        };
+
+        let mut reasons: HashMap<Option<ExitReason>, ExitReasonStats> = HashMap::new();
+        let mut total_hold = 0i64;
+
+        for position in closed {
+            let won = position.profit_loss_sol > 0.0;
+            if won {
+                report.wins += 1;
+            } else {
+                report.losses += 1;
+            }
+            report.invested_sol += position.entry_sol_amount;
+            report.profit_loss_sol += position.profit_loss_sol;
+            if let Some(usd) = position.profit_loss_usd {
+                *report.profit_loss_usd.get_or_insert(0.0) += usd;
+            }
+            report.estimated_fees_sol +=
+                (position.entry_sol_amount + position.exit_sol_received) * PLATFORM_FEE_PERCENT / 100.0;
+
+            let hold_secs = (position.exit_time - position.entry_time).max(0);
+            total_hold += hold_secs;
+
+            let reason = position.exit_reason;
+            let stats = reasons.entry(reason).or_insert_with(|| ExitReasonStats {
+                reason,
+                trades: 0,
+                wins: 0,
+                profit_loss_sol: 0.0,
+            });
+            stats.trades += 1;
+            stats.wins += won as usize;
+            stats.profit_loss_sol += position.profit_loss_sol;
+
+            let summary = TradeSummary {
+                mint: position.mint.clone(),
+                profit_loss_sol: position.profit_loss_sol,
+                profit_loss_percent: position.profit_loss_percent,
+                hold_secs,
+                exit_reason: reason,
+            };
+            if report.best.as_ref().is_none_or(|best| summary.profit_loss_sol > best.profit_loss_sol) {
+                report.best = Some(summary.clone());
+            }
+            if report.worst.as_ref().is_none_or(|worst| summary.profit_loss_sol < worst.profit_loss_sol) {
+                report.worst = Some(summary);
+            }
+        }
+
+        if !closed.is_empty() {
+            report.avg_hold_secs = Some(total_hold as f64 / closed.len() as f64);
+        }
+
+        report.exit_reasons = reasons.into_values().collect();
+        report
+            .exit_reasons
+            .sort_by(|a, b| b.trades.cmp(&a.trades).then_with(|| reason_label(a.reason).cmp(reason_label(b.reason))));
+        report
+    }
+
+    /// Winning share of closed trades, in percent
+    pub fn win_rate(&self) -> Option<f64> {
+        (self.closed_trades > 0).then(|| self.wins as f64 / self.closed_trades as f64 * 100.0)
+    }
+
+    /// Net P&L as a percent of SOL invested
+    pub fn return_percent(&self) -> Option<f64> {
+        (self.invested_sol > 0.0).then(|| self.profit_loss_sol / self.invested_sol * 100.0)
+    }
+
+    /// Estimated fees as a percent of SOL invested
+    pub fn fee_drag_percent(&self) -> Option<f64> {
+        (self.invested_sol > 0.0).then(|| self.estimated_fees_sol / self.invested_sol * 100.0)
+    }
+}
+
+/// Report on everything recorded in `db`
+pub fn generate_report(db: &Database) -> Result<SessionReport> {
+    let closed = db.closed_positions()?;
+    let open = db.active_position_count()?;
+    let transactions = db.transaction_counts()?;
+    Ok(SessionReport::from_positions(&closed, open, transactions))
+}
+
+fn format_hold(secs: f64) -> String {
+    if secs >= 3600.0 {
+        format!("{:.1}h", secs / 3600.0)
+    } else if secs >= 60.0 {
+        format!("{:.1}m", secs / 60.0)
+    } else {
+        format!("{:.0}s", secs)
+    }
+}
+
+fn reason_label(reason: Option<ExitReason>) -> &'static str {
+    reason.map_or("unknown", |reason| reason.as_str())
+}
+
+fn format_trade(trade: &TradeSummary) -> String {
+    format!(
+        "{} {:+.4} SOL ({:+.1}%) held {} → {}",
+        trade.mint,
+        trade.profit_loss_sol,
+        trade.profit_loss_percent,
+        format_hold(trade.hold_secs as f64),
+        reason_label(trade.exit_reason)
+    )
+}
+
+impl fmt::Display for SessionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "📒 SESSION REPORT")?;
+        writeln!(f, "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━")?;
+
+        writeln!(f, "Trades: {} closed, {} open", self.closed_trades, self.open_positions)?;
+        if self.closed_trades == 0 {
+            return writeln!(f, "No closed trades yet");
+        }
+        writeln!(
+            f,
+            "Win Rate: {:.1}% ({} W / {} L)",
+            self.win_rate().unwrap_or_default(),
+            self.wins,
+            self.losses
+        )?;
+        writeln!(f, "Invested: {:.4} SOL", self.invested_sol)?;
+        match self.profit_loss_usd {
+            Some(usd) => writeln!(
+                f,
+                "Net P&L: {:+.4} SOL / ${:+.2} ({:+.1}%)",
+                self.profit_loss_sol,
+                usd,
+                self.return_percent().unwrap_or_default()
+            )?,
+            None => writeln!(
+                f,
+                "Net P&L: {:+.4} SOL ({:+.1}%)",
+                self.profit_loss_sol,
+                self.return_percent().unwrap_or_default()
+            )?,
+        }
+        if let Some(avg) = self.avg_hold_secs {
+            writeln!(f, "Avg Hold: {}", format_hold(avg))?;
+        }
+        writeln!(
+            f,
+            "Fee Drag: ~{:.4} SOL ({:.1}% of invested, {}% platform fee per side)",
+            self.estimated_fees_sol,
+            self.fee_drag_percent().unwrap_or_default(),
+            PLATFORM_FEE_PERCENT
+        )?;
+        writeln!(
+            f,
+            "Transactions: {} buys / {} sells ({} confirmed)",
+            self.transactions.buys, self.transactions.sells, self.transactions.verified
+        )?;
+
+        if let Some(best) = &self.best {
+            writeln!(f, "Best:  {}", format_trade(best))?;
+        }
+        if let Some(worst) = &self.worst {
+            writeln!(f, "Worst: {}", format_trade(worst))?;
+        }
+
+        writeln!(f, "\nExit Reasons:")?;
+        for reason in &self.exit_reasons {
+            writeln!(
+                f,
+                "   {:<20} {:>4} trades | {:>5.1}% win | {:+.4} SOL",
+                reason_label(reason.reason),
+                reason.trades,
+                reason.wins as f64 / reason.trades as f64 * 100.0,
+                reason.profit_loss_sol
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_from_database() {
+        let db = Database::new(":memory:").unwrap();
+        for (mint, exit_sol, reason) in [
+            ("mint1", 0.15, ExitReason::TakeProfit),
+            ("mint2", 0.02, ExitReason::Rug),
+            ("mint3", 0.07, ExitReason::TakeProfit),
+        ] {
+            db.create_position(mint, &format!("{}_buy", mint), 0.05).unwrap();
+            db.close_position(mint, &format!("{}_sell", mint), Some(exit_sol), reason, Some(200.0)).unwrap();
+        }
+        db.create_position("mint4", "mint4_buy", 0.05).unwrap();
+        db.record_transaction("mint4_buy", "mint4", "buy", 0.05, 0).unwrap();
+        db.mark_transaction_verified("mint4_buy", true).unwrap();
+
+        let report = generate_report(&db).unwrap();
+
+        assert_eq!((report.closed_trades, report.open_positions), (3, 1));
+        assert_eq!((report.wins, report.losses), (2, 1));
+        assert!((report.win_rate().unwrap() - 66.666).abs() < 0.01);
+        assert!((report.profit_loss_sol - 0.09).abs() < 1e-9);
+        assert!((report.profit_loss_usd.unwrap() - 18.0).abs() < 1e-6);
+        assert!((report.estimated_fees_sol - 0.0039).abs() < 1e-9);
+        assert_eq!(report.best.as_ref().unwrap().mint, "mint1");
+        assert_eq!(report.worst.as_ref().unwrap().mint, "mint2");
+        assert_eq!(report.exit_reasons[0].reason, Some(ExitReason::TakeProfit));
+        assert_eq!((report.exit_reasons[0].trades, report.exit_reasons[0].wins), (2, 2));
+        assert_eq!((report.transactions.buys, report.transactions.verified), (1, 1));
+
+        let printed = report.to_string();
+        assert!(printed.contains("Win Rate: 66.7% (2 W / 1 L)"));
+        assert!(printed.contains("rug "));
+    }
+
+    #[test]
+    fn test_empty_report() {
+        let report = SessionReport::from_positions(&[], 0, TransactionCounts::default());
+        assert_eq!(report.win_rate(), None);
+        assert!(report.to_string().contains("No closed trades yet"));
+    }
+}
@@ -0,0 +1,194 @@
+//! Dev Wallet Reputation
+//!
+//! Remembers which creator launched each token we bought and how the trade
+//! ended, so creators with a history of rugs can be skipped before sniping.
+//! Can be seeded with a blocklist file of known bad wallets.
+
+use rusqlite::{Connection, params};
+use anyhow::{Result, Context};
+use log::info;
+use pump_portal_sdk::ExitReason;
+use std::path::Path;
+
+pub struct DevReputation {
+    conn: Connection,
+    max_rugs: u32,
+}
+
+/// Track record of one creator wallet
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DevStats {
+    pub launches: u32,
+    pub rugs: u32,
+    pub closed: u32,
+}
+
+impl DevReputation {
+    /// Open the store; creators with `max_rugs` or more rugs are rejected
+    pub fn new<P: AsRef<Path>>(path: P, max_rugs: u32) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        let reputation = Self { conn, max_rugs };
+        reputation.initialize_schema()?;
+        Ok(reputation)
+    }
+
+    fn initialize_schema(&self) -> Result<()> {
+        // Tokens we bought, by creator, with how the position ended
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS dev_launches (
+                mint TEXT PRIMARY KEY,
+                creator TEXT NOT NULL,
+                bought_at INTEGER NOT NULL,
+                exit_reason TEXT,
+                rugged BOOLEAN NOT NULL DEFAULT 0
+            )",
+            [],
+        )?;
+
+        // Wallets rejected outright
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS dev_blocklist (
+                creator TEXT PRIMARY KEY,
+                reason TEXT NOT NULL,
+                created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+            )",
+            [],
+        )?;
+
+        self.conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_dev_launches_creator ON dev_launches(creator)",
+            [],
+        )?;
+        Ok(())
+    }
+
+    /// Add wallets from a blocklist file: one address per line, `#` starts a comment
+    ///
+    /// Returns how many wallets were read.
+    pub fn load_blocklist<P: AsRef<Path>>(&self, path: P) -> Result<usize> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read blocklist {}", path.display()))?;
+
+        let mut count = 0;
+        for line in contents.lines() {
+            let creator = line.split('#').next().unwrap_or_default().trim();
+            if creator.is_empty() {
+                continue;
+            }
+            self.block(creator, "blocklist file")?;
+            count += 1;
+        }
+
+        info!("🚫 Loaded {} dev wallets from {}", count, path.display());
+        Ok(count)
+    }
+
+    /// Reject every future launch from `creator`
+    pub fn block(&self, creator: &str, reason: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO dev_blocklist (creator, reason) VALUES (?1, ?2)",
+            params![creator, reason],
+        )?;
+        Ok(())
+    }
+
+    /// Remember that we bought `mint`, launched by `creator`
+    pub fn record_launch(&self, creator: &str, mint: &str) -> Result<()> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs() as i64;
+
+        self.conn.execute(
+            "INSERT OR IGNORE INTO dev_launches (mint, creator, bought_at) VALUES (?1, ?2, ?3)",
+            params![mint, creator, now],
+        )?;
+        Ok(())
+    }
+
+    /// Record how a position in `mint` ended; a rug exit counts against its creator
+    pub fn record_exit(&self, mint: &str, exit_reason: ExitReason) -> Result<()> {
+        self.conn.execute(
+            "UPDATE dev_launches SET exit_reason = ?1, rugged = rugged OR ?2 WHERE mint = ?3",
+            params![exit_reason.as_str(), exit_reason == ExitReason::Rug, mint],
+        )?;
+        Ok(())
+    }
+
+    pub fn stats(&self, creator: &str) -> Result<DevStats> {
+        let stats = self.conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(rugged), 0), COUNT(exit_reason)
+             FROM dev_launches
+             WHERE creator = ?1",
+            params![creator],
+            |row| {
+                Ok(DevStats {
+                    launches: row.get(0)?,
+                    rugs: row.get(1)?,
+                    closed: row.get(2)?,
+                })
+            },
+        )?;
+        Ok(stats)
+    }
+
+    /// Why launches from `creator` should be skipped, None if it has a clean record
+    pub fn rejection(&self, creator: &str) -> Result<Option<String>> {
+        let blocked = self.conn.query_row(
+            "SELECT reason FROM dev_blocklist WHERE creator = ?1",
+            params![creator],
+            |row| row.get::<_, String>(0),
+        );
+        match blocked {
+            Ok(reason) => return Ok(Some(format!("blocklisted ({})", reason))),
+            Err(rusqlite::Error::QueryReturnedNoRows) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        let stats = self.stats(creator)?;
+        if self.max_rugs > 0 && stats.rugs >= self.max_rugs {
+            return Ok(Some(format!("{} rugs in {} launches", stats.rugs, stats.launches)));
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEV: &str = "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU";
+
+    #[test]
+    fn test_rugs_reject_creator() {
+        let reputation = DevReputation::new(":memory:", 2).unwrap();
+        assert_eq!(reputation.rejection(DEV).unwrap(), None);
+
+        reputation.record_launch(DEV, "mint1").unwrap();
+        reputation.record_exit("mint1", ExitReason::Rug).unwrap();
+        reputation.record_launch(DEV, "mint2").unwrap();
+        reputation.record_exit("mint2", ExitReason::TrailingStop).unwrap();
+        assert_eq!(
+            reputation.stats(DEV).unwrap(),
+            DevStats { launches: 2, rugs: 1, closed: 2 }
+        );
+        assert_eq!(reputation.rejection(DEV).unwrap(), None);
+
+        reputation.record_launch(DEV, "mint3").unwrap();
+        reputation.record_exit("mint3", ExitReason::Rug).unwrap();
+        assert!(reputation.rejection(DEV).unwrap().unwrap().contains("2 rugs"));
+    }
+
+    #[test]
+    fn test_blocklist_file() {
+        let path = std::env::temp_dir().join(format!("dev_blocklist_{}.txt", std::process::id()));
+        std::fs::write(&path, format!("# known ruggers\n{}  # rugged 3 tokens\n\n", DEV)).unwrap();
+
+        let reputation = DevReputation::new(":memory:", 1).unwrap();
+        assert_eq!(reputation.load_blocklist(&path).unwrap(), 1);
+        assert!(reputation.rejection(DEV).unwrap().unwrap().starts_with("blocklisted"));
+        assert_eq!(reputation.rejection("SomeOtherWallet").unwrap(), None);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -7,37 +7,135 @@
 //! 4. At 2x: recover initial + 10%, trail the rest
 //! 5. Ladder out on way up, keep moon bag
 
-use pump_portal_sdk::{PumpPortalClient, TradeRequest};
+use pump_portal_sdk::config::BotConfig;
+use pump_portal_sdk::dedup::DEFAULT_BUY_DEDUP_WINDOW;
+use pump_portal_sdk::notify::NoopNotifier;
+use pump_portal_sdk::{exit, paper, BondingCurve, ConfirmationStatus, ExitDecision, ExitReason, ExitStrategy, Notification, Notifier, PaperTradingSimulator, PositionState, Proceeds, PumpPortalClient, SolBudget, TradeAction, TradeRequest, TradeResponse, PUMP_TOKEN_DECIMALS};
 use crate::monitor::PositionMonitor;
-use crate::detector::MomentumDetector;
-use crate::launch_detector::{LaunchDetector, LaunchDetectorConfig};
-use anyhow::Result;
+use crate::detector::{MomentumDetector, MomentumSignals};
+use crate::dev_watch::DevSellWatcher;
+use crate::frontrun::{self, FrontRunAction, FrontRunProtector};
+use crate::launch_detector::{ConnectionState, LaunchDetector, LaunchDetectorConfig};
+use crate::metrics;
+use crate::database::{Database, Position, PositionError};
+use crate::reputation::DevReputation;
+use anyhow::{anyhow, Context, Result};
 use log::{info, warn, error};
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// How long to wait for a snipe to land before treating it as dropped
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A transaction still unseen this long after it was sent can't land any
+/// more: its blockhash has expired
+const BLOCKHASH_LIFETIME: Duration = Duration::from_secs(90);
+
+/// Entries whose check timed out are looked at again this many times, this far apart
+const VERIFY_ATTEMPTS: u32 = 3;
+const VERIFY_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Failed checks in a row before a position is sold rather than held blind
+const MAX_CHECK_FAILURES: u32 = 5;
+
+const BUY_SLIPPAGE: u32 = 20; // High slippage for launch volatility
+const SELL_SLIPPAGE: u32 = 20; // High slippage for fast exit
 
 pub struct SniperBot {
     pumpportal: PumpPortalClient,
     monitor: PositionMonitor,
     detector: MomentumDetector,
+    db: Database,
+    reputation: DevReputation,
+    dev_sells: Arc<DevSellWatcher>,
+    /// Watches the mint's largest holders for dumps
+    frontrun: FrontRunProtector,
+    notifier: Arc<dyn Notifier>,
+    /// Fills trades on a paper wallet instead of sending them, in paper mode
+    paper: Option<Arc<PaperTradingSimulator>>,
+    /// Caps SOL across active positions; uncapped by default
+    budget: SolBudget,
+    /// Replaces the built-in momentum/2x/ladder exits when set
+    exit_strategy: Option<Box<dyn ExitStrategy>>,
+    rpc_url: String,
     trade_amount: f64,
+    min_sol_reserves: f64,
+    max_hold_secs: u64,
 }
 
 impl SniperBot {
-    pub fn new(api_key: String, rpc_url: String, trade_amount: f64, db: crate::database::Database) -> Result<Self> {
+    /// Trade size, liquidity floor and max hold come from `config`
+    pub fn new(
+        api_key: String,
+        rpc_url: String,
+        config: &BotConfig,
+        db: crate::database::Database,
+        reputation: DevReputation,
+        dev_sells: DevSellWatcher,
+    ) -> Result<Self> {
+        let dev_sells = Arc::new(dev_sells);
         Ok(Self {
-            pumpportal: PumpPortalClient::new(api_key),
+            pumpportal: PumpPortalClient::new(api_key).with_buy_dedup(DEFAULT_BUY_DEDUP_WINDOW),
             monitor: PositionMonitor::new(rpc_url.clone(), db.clone())?,
-            detector: MomentumDetector::new(rpc_url, db)?,
-            trade_amount,
+            detector: MomentumDetector::new(rpc_url.clone(), db.clone(), dev_sells.clone())?,
+            frontrun: FrontRunProtector::new(rpc_url.clone(), db.clone())?,
+            db,
+            reputation,
+            dev_sells,
+            notifier: Arc::new(NoopNotifier),
+            paper: None,
+            budget: SolBudget::default(),
+            exit_strategy: None,
+            rpc_url,
+            trade_amount: config.snipe_amount_sol,
+            min_sol_reserves: config.min_sol_reserves,
+            max_hold_secs: config.max_hold_secs,
         })
     }
 
+    /// Report buys, sells and rugs through `notifier`
+    pub fn with_notifier(mut self, notifier: Arc<dyn Notifier>) -> Self {
+        self.notifier = notifier;
+        self
+    }
+
+    /// Retry a snipe or exit that slipped once, at up to `max_slippage` percent
+    pub fn with_max_slippage(mut self, max_slippage: u32) -> Self {
+        self.pumpportal = self.pumpportal.with_slippage_retry(max_slippage);
+        self
+    }
+
+    /// Skip launches once active positions hold `max_sol` SOL
+    pub fn with_max_sol_at_risk(mut self, max_sol: f64) -> Self {
+        self.budget = SolBudget::new(Some(max_sol));
+        self
+    }
+
+    /// Manage positions with `strategy` instead of the built-in exits
+    ///
+    /// Rug exits and the hard time stop still apply.
+    pub fn with_exit_strategy(mut self, strategy: Box<dyn ExitStrategy>) -> Self {
+        self.exit_strategy = Some(strategy);
+        self
+    }
+
+    /// Paper trade through `paper` instead of spending SOL
+    pub fn with_paper(mut self, paper: Arc<PaperTradingSimulator>) -> Self {
+        self.paper = Some(paper);
+        self
+    }
+
     pub async fn run(self) -> Result<()> {
         info!("🎯 Strategy: Fast In, Smart Exit");
         info!("   Entry: ~$5 per launch");
-        info!("   Exit: No momentum → Fast exit");
-        info!("   Exit: 2x reached → Recover + 10%, trail rest");
-        info!("   Exit: High momentum → Ladder out, keep moon bag\n");
+        match &self.exit_strategy {
+            Some(strategy) => info!("   Exit: {} (rug exits still apply)\n", strategy.name()),
+            None => {
+                info!("   Exit: No momentum → Fast exit");
+                info!("   Exit: 2x reached → Recover + 10%, trail rest");
+                info!("   Exit: High momentum → Ladder out, keep moon bag\n");
+            }
+        }
 
         // Start monitoring for new launches
         self.monitor_launches().await
@@ -45,22 +143,114 @@ impl SniperBot {
 
     async fn monitor_launches(&self) -> Result<()> {
         // Initialize launch detector with PumpPortal WebSocket
-        let detector = LaunchDetector::new(LaunchDetectorConfig::default());
-        let mut launch_rx = detector.start_monitoring().await?;
+        let detector = LaunchDetector::new(LaunchDetectorConfig {
+            min_sol_reserves: self.min_sol_reserves,
+            ..LaunchDetectorConfig::default()
+        });
+        let mut monitor = detector.start_monitoring().await?;
 
         info!("✅ Launch detector running, waiting for new tokens...\n");
 
+        // Ctrl+C between launches stops the detector; a position being managed finishes first
+        let ctrl_c = tokio::signal::ctrl_c();
+        tokio::pin!(ctrl_c);
+
         // Process new token launches
-        while let Some(launch) = launch_rx.recv().await {
+        loop {
+            let launch = tokio::select! {
+                _ = &mut ctrl_c => {
+                    info!("🛑 Ctrl+C received, stopping the launch detector...");
+                    monitor.stop();
+                    return monitor.join().await;
+                }
+                launch = monitor.launches.recv() => match launch {
+                    Some(launch) => launch,
+                    None => break,
+                },
+                Ok(()) = monitor.state.changed() => {
+                    let state = *monitor.state.borrow_and_update();
+                    metrics::DETECTOR_CONNECTED.set((state == ConnectionState::Connected) as i64);
+                    match state {
+                        ConnectionState::Connected => info!("📡 Launch detector connected"),
+                        ConnectionState::Reconnecting => warn!("📡 Launch detector disconnected, reconnecting..."),
+                        ConnectionState::Connecting | ConnectionState::Stopped => {}
+                    }
+                    continue;
+                }
+            };
+            metrics::LAUNCHES_SEEN.inc();
             info!("🎯 New snipeable token detected: {} ({})", launch.name, launch.symbol);
 
+            // Skip creators with a bad track record
+            if let Some(creator) = launch.creator() {
+                match self.reputation.rejection(creator) {
+                    Ok(Some(reason)) => {
+                        tracing::warn!(event = "skip", mint = %launch.mint, creator = %creator, reason = "dev_reputation",
+                                       "🚫 Dev wallet {}: {}, skipping", creator, reason);
+                        continue;
+                    }
+                    Ok(None) => {}
+                    Err(e) => error!("Dev reputation check failed for {}: {}", creator, e),
+                }
+            }
+
+            // Keep total SOL at risk under the cap; the reservation lasts until the position is recorded
+            let reservation = match self.db.sol_at_risk() {
+                Ok(at_risk) => self.budget.reserve(at_risk, self.trade_amount),
+                Err(e) => {
+                    error!("SOL at risk lookup failed, skipping {}: {}", launch.mint, e);
+                    continue;
+                }
+            };
+            let reservation = match reservation {
+                Ok(reservation) => reservation,
+                Err(left) => {
+                    tracing::warn!(event = "skip", mint = %launch.mint, reason = "max_sol_at_risk", budget_left_sol = left,
+                                   "💰 {} SOL left under the {} SOL cap, skipping",
+                                   left, self.budget.max_sol().unwrap_or_default());
+                    continue;
+                }
+            };
+
+            // Subscribe before buying so a creator dump in the same slots as our buy isn't missed
+            if let Some(creator) = launch.creator() {
+                self.dev_sells.watch(&launch.mint, creator);
+            }
+
             // Execute snipe
-            match self.execute_snipe(&launch.mint).await {
+            let snipe = self.execute_snipe(&launch.mint, launch.curve()).await;
+            drop(reservation);
+            if snipe.is_err() {
+                self.dev_sells.unwatch(&launch.mint);
+            }
+            match snipe {
                 Ok(signature) => {
+                    if let Ok(at_risk) = self.db.sol_at_risk() {
+                        if let Some(left) = self.budget.remaining(at_risk) {
+                            info!("💰 At risk: {:.4} SOL ({:.4} SOL budget left)", at_risk, left);
+                        }
+                    }
+
+                    if let Some(creator) = launch.creator() {
+                        if let Err(e) = self.reputation.record_launch(creator, &launch.mint) {
+                            error!("Failed to record launch for dev {}: {}", creator, e);
+                        }
+                    }
+                    if let Err(e) = self.frontrun.identify_whales(&launch.mint, launch.creator()).await {
+                        warn!("⚠️  Couldn't identify whales for {}: {}", launch.mint, e);
+                    }
+
                     // Start position management
                     if let Err(e) = self.manage_position(&launch.mint, &signature).await {
                         error!("Position management failed: {}", e);
                     }
+                    self.dev_sells.unwatch(&launch.mint);
+                    self.frontrun.stop_watching(&launch.mint);
+                }
+                Err(e) if e.downcast_ref::<PositionError>().is_some() => {
+                    // Re-delivered launch: the first snipe already owns this position
+                    tracing::warn!(event = "skip", mint = %launch.mint, reason = "already_open",
+                                   "⏭️  {}, not managing it twice", e);
                 }
                 Err(e) => {
                     error!("Snipe failed for {}: {}", launch.mint, e);
@@ -70,46 +260,242 @@ impl SniperBot {
             info!("\n👀 Monitoring for next launch...\n");
         }
 
-        Ok(())
+        // The channel only closes when the detector task is gone
+        monitor.join().await?;
+        Err(anyhow!("Launch detector stopped unexpectedly"))
     }
 
-    /// Execute snipe on new token
-    pub async fn execute_snipe(&self, token_mint: &str) -> Result<String> {
+    /// Execute snipe on new token; `curve` is the launch's bonding curve, when it reported one
+    pub async fn execute_snipe(&self, token_mint: &str, curve: Option<BondingCurve>) -> Result<String> {
         info!("⚡ SNIPING: {}", token_mint);
 
+        // Claim the mint before spending SOL, so a re-delivered launch is
+        // turned away here rather than after a second buy
+        self.db.reserve_position(token_mint, self.trade_amount)?;
+        let sig = match self.send_snipe(token_mint, curve).await {
+            Ok(sig) => sig,
+            Err(e) => {
+                if let Err(e) = self.db.release_position(token_mint) {
+                    error!("Failed to release position for {}: {}", token_mint, e);
+                }
+                return Err(e);
+            }
+        };
+
+        metrics::SNIPES_EXECUTED.inc();
+        self.record_fill(&sig, token_mint).await;
+        self.notifier.notify(Notification::buy(token_mint, self.trade_amount, &sig));
+        Ok(sig)
+    }
+
+    /// Send the buy for a reserved position and wait for it to land
+    async fn send_snipe(&self, token_mint: &str, curve: Option<BondingCurve>) -> Result<String> {
         // Use aggressive settings for speed
-        let request = TradeRequest::buy(
+        let mut request = TradeRequest::buy(
             token_mint.to_string(),
             self.trade_amount,
-            20, // High slippage for launch volatility
+            BUY_SLIPPAGE,
             0.0005, // Higher priority fee for speed
         )
         .with_jito_only(true); // Jito for best execution
+        if let Some(curve) = curve {
+            request = request.with_curve_reserves(curve.virtual_sol_reserves, curve.virtual_token_reserves);
+        }
 
-        match self.pumpportal.trade(request).await {
-            Ok(response) => {
+        match self.trade(request, None).await {
+            Ok((response, _)) => {
                 if let Some(sig) = response.signature {
-                    info!("✅ SNIPE EXECUTED: {}", sig);
+                    tracing::info!(event = "buy", mint = token_mint, sol = self.trade_amount, slippage = BUY_SLIPPAGE,
+                                   signature = %sig, "✅ SNIPE SUBMITTED");
                     info!("   🔗 https://solscan.io/tx/{}", sig);
 
-                    // Verify transaction actually exists
-                    tokio::time::sleep(Duration::from_secs(2)).await;
-
-                    // TODO: Verify on-chain
-
+                    self.db.set_entry_signature(token_mint, &sig)?;
+                    self.confirm_transaction(&sig, token_mint, "buy", self.trade_amount).await?;
                     Ok(sig)
                 } else {
-                    error!("❌ Snipe failed: No signature");
+                    tracing::error!(event = "buy_failed", mint = token_mint, sol = self.trade_amount,
+                                    error = "no signature", "❌ Snipe failed");
                     Err(anyhow::anyhow!("No signature returned"))
                 }
             }
             Err(e) => {
-                error!("❌ Snipe error: {}", e);
+                tracing::error!(event = "buy_failed", mint = token_mint, sol = self.trade_amount,
+                                error = %e, "❌ Snipe error");
                 Err(e.into())
             }
         }
     }
 
+    /// Send `request`, or fill it on the paper wallet in paper mode
+    ///
+    /// Paper buys fill at the launch curve price the request carries, paper
+    /// sells at the curve's current price (the entry price if it can't be
+    /// read). A paper sell has no transaction to read back, so its proceeds
+    /// come with it.
+    async fn trade(
+        &self,
+        request: TradeRequest,
+        exit_reason: Option<ExitReason>,
+    ) -> pump_portal_sdk::Result<(TradeResponse, Option<Proceeds>)> {
+        let Some(sim) = &self.paper else {
+            return Ok((self.pumpportal.trade(request).await?, None));
+        };
+
+        request.validate()?;
+        let (signature, proceeds) = match request.action {
+            TradeAction::Buy => {
+                let price = request.curve.map(|curve| curve.price()).unwrap_or(paper::ESTIMATED_LAUNCH_PRICE);
+                let signature = sim.simulate_buy(request.mint.clone(), self.trade_amount, price, PUMP_TOKEN_DECIMALS).await?;
+                (signature, None)
+            }
+            TradeAction::Sell => {
+                let percent = percent_of(&request.amount).unwrap_or(100.0).round() as u32;
+                let price = self.curve_price(&request.mint).await;
+                let (signature, proceeds) = sim.simulate_sell(&request.mint, percent, price, exit_reason).await?;
+                (signature, Some(proceeds))
+            }
+        };
+        let response = TradeResponse {
+            signature: Some(signature),
+            error: None,
+            pool: request.pool,
+            input_amount: Some(request.amount),
+            extra: serde_json::json!({ "paper": true }),
+        };
+        Ok((response, proceeds))
+    }
+
+    /// Record a submitted transaction and wait for it to land on-chain
+    /// Errors if it failed or wasn't confirmed within `CONFIRM_TIMEOUT`
+    async fn confirm_transaction(
+        &self,
+        signature: &str,
+        token_mint: &str,
+        tx_type: &str,
+        sol_amount: f64,
+    ) -> Result<()> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        self.db.record_transaction(signature, token_mint, tx_type, sol_amount, now)?;
+
+        let status = self
+            .pumpportal
+            .confirm(signature, &self.rpc_url, CONFIRM_TIMEOUT)
+            .await?;
+        self.db.mark_transaction_verified(signature, status.is_confirmed())?;
+
+        match status {
+            ConfirmationStatus::Confirmed => {
+                info!("✅ CONFIRMED ON-CHAIN: {}", signature);
+                Ok(())
+            }
+            ConfirmationStatus::Failed(reason) => {
+                error!("❌ Transaction failed on-chain: {}", reason);
+                Err(anyhow::anyhow!("{} {} failed on-chain: {}", tx_type, signature, reason))
+            }
+            ConfirmationStatus::Timeout => {
+                error!("❌ Not confirmed after {:?}, treating as dropped", CONFIRM_TIMEOUT);
+                Err(anyhow::anyhow!("{} {} not confirmed", tx_type, signature))
+            }
+        }
+    }
+
+    /// Store the entry price and size the confirmed buy actually got
+    async fn record_fill(&self, signature: &str, token_mint: &str) {
+        if let Some(sim) = &self.paper {
+            // Paper buys have no transaction to read; the wallet has the fill
+            let wallet = sim.wallet();
+            let wallet = wallet.lock().await;
+            if let Some(pos) = wallet.get_position(token_mint) {
+                if let Err(e) = self.db.update_position_entry_details(token_mint, pos.tokens(), pos.entry_price) {
+                    error!("Failed to store entry details: {}", e);
+                }
+                if let Err(e) = self.db.record_transaction_fill(signature, pos.amount_sol, pos.tokens()) {
+                    error!("Failed to store fill: {}", e);
+                }
+            }
+            return;
+        }
+
+        match self
+            .pumpportal
+            .fill(signature, token_mint, &self.rpc_url, CONFIRM_TIMEOUT)
+            .await
+        {
+            Ok(fill) => {
+                info!("📌 ENTRY: {:.4} SOL for {:.0} tokens ({:.10} SOL/token)",
+                      fill.sol_spent, fill.tokens_received, fill.price());
+                if let Err(e) = self.db.update_position_entry_details(
+                    token_mint,
+                    fill.tokens_received,
+                    fill.price(),
+                ) {
+                    error!("Failed to store entry details: {}", e);
+                }
+                if let Err(e) = self.db.record_transaction_fill(signature, fill.sol_spent, fill.tokens_received) {
+                    error!("Failed to store fill: {}", e);
+                }
+            }
+            Err(e) => warn!("⚠️  Couldn't read fill for {}: {}", signature, e),
+        }
+    }
+
+    /// Store what a confirmed sell actually returned, so the mint's ledger
+    /// has both sides of the trade
+    async fn record_proceeds(&self, signature: &str, token_mint: &str, paper_proceeds: Option<Proceeds>) {
+        let proceeds = match paper_proceeds {
+            Some(proceeds) => proceeds,
+            None => match self
+                .pumpportal
+                .proceeds(signature, token_mint, &self.rpc_url, CONFIRM_TIMEOUT)
+                .await
+            {
+                Ok(proceeds) => proceeds,
+                Err(e) => {
+                    warn!("⚠️  Couldn't read proceeds for {}: {}", signature, e);
+                    return;
+                }
+            },
+        };
+
+        info!("📌 EXIT: {:.4} SOL for {:.0} tokens ({:.10} SOL/token)",
+              proceeds.sol_received, proceeds.tokens_sold, proceeds.price());
+        if let Err(e) = self.db.record_transaction_fill(signature, proceeds.sol_received, proceeds.tokens_sold) {
+            error!("Failed to store fill: {}", e);
+            return;
+        }
+
+        // Running audit of the position: what's been realized and what the rest cost
+        match self.db.mint_ledger(token_mint) {
+            Ok(ledger) => info!("📒 LEDGER: realized {:+.4} SOL | {:.0} tokens held, cost basis {:.4} SOL",
+                               ledger.realized_pnl_sol(), ledger.tokens_held(), ledger.cost_basis_sol()),
+            Err(e) => error!("Ledger lookup failed for {}: {}", token_mint, e),
+        }
+    }
+
+    /// Close `token_mint`'s row after a full exit, so it stops counting as held
+    /// and at risk and shows up in the report
+    ///
+    /// The exit is scored from the ledger only when every sell's proceeds were
+    /// read; otherwise the row closes with no P&L instead of a made-up one.
+    async fn close_position(&self, token_mint: &str, signature: &str, reason: ExitReason) {
+        // Earlier partial sells are in the ledger alongside this one
+        let exit_sol = match self.db.mint_ledger(token_mint) {
+            Ok(ledger) if ledger.is_fully_sold() => Some(ledger.sol_received),
+            Ok(_) => {
+                warn!("⚠️  Not every sell of {} has its proceeds on record, leaving the exit unscored", token_mint);
+                None
+            }
+            Err(e) => {
+                error!("Ledger lookup failed for {}, leaving its exit unscored: {}", token_mint, e);
+                None
+            }
+        };
+        let sol_usd = pump_portal_sdk::sol_usd().await.ok();
+        if let Err(e) = self.db.close_position(token_mint, signature, exit_sol, reason, sol_usd) {
+            error!("Failed to close position {}: {}", token_mint, e);
+        }
+    }
+
     /// Monitor position and execute exit strategy
     pub async fn manage_position(
         &self,
@@ -117,27 +503,54 @@ impl SniperBot {
         entry_signature: &str,
     ) -> Result<()> {
         info!("📊 Managing position for {}", token_mint);
+        let entry_time = Instant::now();
+
+        if let Some(strategy) = &self.exit_strategy {
+            return self.strategy_exit(strategy.as_ref(), token_mint, entry_time).await;
+        }
 
         let mut check_count = 0;
         let max_no_momentum_checks = 6; // 60 seconds of no momentum = exit
+        let mut failures = 0;
 
         loop {
-            tokio::time::sleep(Duration::from_secs(10)).await;
+            self.wait_for_check(token_mint, Duration::from_secs(10)).await;
             check_count += 1;
 
+            if let Some(result) = self.hard_time_stop(token_mint, entry_time).await {
+                return result;
+            }
+            if let Some(result) = self.whale_dump_exit(token_mint).await {
+                return result;
+            }
+
             // Check momentum
-            let momentum = self.detector.check_momentum(token_mint).await?;
+            let (momentum, current_value) = match self.read_position(token_mint).await {
+                Ok(reading) => {
+                    failures = 0;
+                    reading
+                }
+                Err(e) => match self.check_failed(token_mint, &mut failures, e).await {
+                    Some(result) => return result,
+                    None => continue,
+                },
+            };
 
             info!("📈 Check #{}: Momentum = {:.1}%", check_count, momentum.score * 100.0);
 
+            // RULE 0: Rug pull detection (dev sell, liquidity pull) beats every other rule
+            if momentum.rug_risk > 0.7 {
+                error!("🚨 RUG PULL DETECTED! Emergency exit!");
+                return self.execute_exit(token_mint, "100%", ExitReason::Rug).await;
+            }
+
             // RULE 1: No momentum after 60 seconds = fast exit
             if check_count >= max_no_momentum_checks && momentum.score < 0.3 {
                 warn!("⚠️  NO MOMENTUM DETECTED - Fast exit!");
-                return self.execute_exit(token_mint, "100%", "no_momentum").await;
+                return self.execute_exit(token_mint, "100%", ExitReason::MomentumDied).await;
             }
 
             // RULE 2: Check for 2x (or current profit)
-            let current_value = self.monitor.get_position_value(token_mint).await?;
             let profit_multiple = current_value / self.trade_amount;
 
             info!("   Current: {:.3} SOL ({:.2}x)", current_value, profit_multiple);
@@ -152,41 +565,109 @@ impl SniperBot {
                 self.execute_exit(
                     token_mint,
                     &format!("{:.0}%", recovery_percent),
-                    "recover_initial"
+                    ExitReason::TakeProfit
                 ).await?;
 
                 info!("💰 Recovered {:.3} SOL", recovery_amount);
                 info!("🚀 Trailing the rest with high momentum");
 
                 // Now trail the rest
-                return self.trail_position(token_mint, current_value - recovery_amount).await;
+                return self.trail_position(token_mint, current_value - recovery_amount, entry_time).await;
+            }
+
+            // RULE 3: High momentum detected - prepare for ladder
+            if momentum.score > 0.8 && profit_multiple > 1.5 {
+                info!("🚀 HIGH MOMENTUM + PROFIT - Starting ladder strategy");
+                return self.ladder_exit(token_mint, entry_time).await;
+            }
+        }
+    }
+
+    /// Evaluate `strategy` on every check until it has sold the whole position
+    async fn strategy_exit(&self, strategy: &dyn ExitStrategy, token_mint: &str, entry_time: Instant) -> Result<()> {
+        let mut state = PositionState::default();
+        // Share of the bought tokens still held, so partial sells don't read as losses
+        let mut held = 1.0;
+        let mut failures = 0;
+
+        loop {
+            self.wait_for_check(token_mint, Duration::from_secs(10)).await;
+
+            if let Some(result) = self.hard_time_stop(token_mint, entry_time).await {
+                return result;
+            }
+            if let Some(result) = self.whale_dump_exit(token_mint).await {
+                return result;
             }
 
-            // RULE 3: Rug pull detection
+            let (momentum, current_value) = match self.read_position(token_mint).await {
+                Ok(reading) => {
+                    failures = 0;
+                    reading
+                }
+                Err(e) => match self.check_failed(token_mint, &mut failures, e).await {
+                    Some(result) => return result,
+                    None => continue,
+                },
+            };
             if momentum.rug_risk > 0.7 {
                 error!("🚨 RUG PULL DETECTED! Emergency exit!");
-                return self.execute_exit(token_mint, "100%", "rug_detected").await;
+                return self.execute_exit(token_mint, "100%", ExitReason::Rug).await;
             }
 
-            // RULE 4: High momentum detected - prepare for ladder
-            if momentum.score > 0.8 && profit_multiple > 1.5 {
-                info!("🚀 HIGH MOMENTUM + PROFIT - Starting ladder strategy");
-                return self.ladder_exit(token_mint, current_value).await;
+            state.elapsed_secs = entry_time.elapsed().as_secs();
+            state.pnl_multiplier = current_value / (self.trade_amount * held);
+            state.peak_multiplier = state.peak_multiplier.max(state.pnl_multiplier);
+            state.momentum = momentum.score;
+
+            info!("📈 {}s: {:.2}x (peak {:.2}x), momentum {:.1}%",
+                  state.elapsed_secs, state.pnl_multiplier, state.peak_multiplier, momentum.score * 100.0);
+
+            if let ExitDecision::Sell { percent, reason, detail } = strategy.evaluate(&state) {
+                info!("🎯 {}, selling {}%", detail, percent);
+                self.execute_exit(token_mint, &format!("{}%", percent), reason).await?;
+                if percent >= 100 {
+                    return Ok(());
+                }
+                held *= 1.0 - f64::from(percent) / 100.0;
+                state.exits_taken += 1;
             }
         }
     }
 
     /// Trail position with tight stops
-    async fn trail_position(&self, token_mint: &str, initial_value: f64) -> Result<()> {
+    async fn trail_position(&self, token_mint: &str, initial_value: f64, entry_time: Instant) -> Result<()> {
         info!("📈 TRAILING POSITION");
 
         let mut highest_value = initial_value;
         let trailing_stop_percent = 0.85; // Sell if drops 15% from high
+        let mut failures = 0;
 
         loop {
-            tokio::time::sleep(Duration::from_secs(5)).await;
+            self.wait_for_check(token_mint, Duration::from_secs(5)).await;
 
-            let current_value = self.monitor.get_position_value(token_mint).await?;
+            if let Some(result) = self.hard_time_stop(token_mint, entry_time).await {
+                return result;
+            }
+            if let Some(result) = self.whale_dump_exit(token_mint).await {
+                return result;
+            }
+
+            // Check for rug before the trailing stop, so a dev sell is recorded as one
+            let (momentum, current_value) = match self.read_position(token_mint).await {
+                Ok(reading) => {
+                    failures = 0;
+                    reading
+                }
+                Err(e) => match self.check_failed(token_mint, &mut failures, e).await {
+                    Some(result) => return result,
+                    None => continue,
+                },
+            };
+            if momentum.rug_risk > 0.7 {
+                error!("🚨 RUG DETECTED during trail! Exit now!");
+                return self.execute_exit(token_mint, "100%", ExitReason::Rug).await;
+            }
 
             if current_value > highest_value {
                 highest_value = current_value;
@@ -197,20 +678,13 @@ impl SniperBot {
 
             if drop_percent < trailing_stop_percent {
                 warn!("⚠️  Trailing stop hit! Exiting remaining position");
-                return self.execute_exit(token_mint, "100%", "trailing_stop").await;
-            }
-
-            // Check for rug
-            let momentum = self.detector.check_momentum(token_mint).await?;
-            if momentum.rug_risk > 0.7 {
-                error!("🚨 RUG DETECTED during trail! Exit now!");
-                return self.execute_exit(token_mint, "100%", "rug_detected").await;
+                return self.execute_exit(token_mint, "100%", ExitReason::TrailingStop).await;
             }
         }
     }
 
     /// Ladder out on the way up
-    async fn ladder_exit(&self, token_mint: &str, current_value: f64) -> Result<()> {
+    async fn ladder_exit(&self, token_mint: &str, entry_time: Instant) -> Result<()> {
         info!("🪜 LADDER EXIT STRATEGY");
 
         let ladder_steps = vec![
@@ -222,12 +696,34 @@ impl SniperBot {
         ];
 
         let mut remaining_percent = 100.0;
+        let mut failures = 0;
 
         for (target_multiple, sell_percent, label) in ladder_steps {
             loop {
-                tokio::time::sleep(Duration::from_secs(5)).await;
+                self.wait_for_check(token_mint, Duration::from_secs(5)).await;
+
+                if let Some(result) = self.hard_time_stop(token_mint, entry_time).await {
+                    return result;
+                }
+                if let Some(result) = self.whale_dump_exit(token_mint).await {
+                    return result;
+                }
 
-                let current = self.monitor.get_position_value(token_mint).await?;
+                // Rug check
+                let (momentum, current) = match self.read_position(token_mint).await {
+                    Ok(reading) => {
+                        failures = 0;
+                        reading
+                    }
+                    Err(e) => match self.check_failed(token_mint, &mut failures, e).await {
+                        Some(result) => return result,
+                        None => continue,
+                    },
+                };
+                if momentum.rug_risk > 0.7 {
+                    error!("🚨 RUG! Selling remaining {:.0}%", remaining_percent);
+                    return self.execute_exit(token_mint, "100%", ExitReason::Rug).await;
+                }
                 let multiple = current / self.trade_amount;
 
                 if multiple >= target_multiple {
@@ -236,24 +732,13 @@ impl SniperBot {
                     self.execute_exit(
                         token_mint,
                         &format!("{:.0}%", sell_percent),
-                        &format!("ladder_{}", label)
+                        ExitReason::TakeProfit
                     ).await?;
 
                     remaining_percent -= sell_percent;
                     info!("   Remaining: {:.0}%", remaining_percent);
                     break;
                 }
-
-                // Rug check
-                let momentum = self.detector.check_momentum(token_mint).await?;
-                if momentum.rug_risk > 0.7 {
-                    error!("🚨 RUG! Selling remaining {:.0}%", remaining_percent);
-                    return self.execute_exit(
-                        token_mint,
-                        &format!("{:.0}%", remaining_percent),
-                        "rug_detected"
-                    ).await;
-                }
             }
         }
 
@@ -261,38 +746,295 @@ impl SniperBot {
         Ok(())
     }
 
+    /// Read `token_mint`'s momentum and current SOL value for one check
+    ///
+    /// Paper positions aren't in the wallet, so they're valued from the paper
+    /// wallet's tokens at the curve price.
+    async fn read_position(&self, token_mint: &str) -> Result<(MomentumSignals, f64)> {
+        let momentum = self.detector.check_momentum(token_mint).await?;
+        let value = match &self.paper {
+            Some(sim) => {
+                let tokens = sim
+                    .wallet()
+                    .lock()
+                    .await
+                    .get_position(token_mint)
+                    .map(|pos| pos.tokens())
+                    .context("No paper position found")?;
+                let price = self.curve_price(token_mint).await.context("Couldn't read curve price")?;
+                tokens * price
+            }
+            None => self.monitor.get_position_value(token_mint).await?.current_value,
+        };
+        Ok((momentum, value))
+    }
+
+    /// `token_mint`'s spot price in SOL per token, from its bonding curve
+    async fn curve_price(&self, token_mint: &str) -> Option<f64> {
+        match self.detector.curve_state(token_mint).await {
+            Ok(state) => Some(state.curve().price()),
+            Err(e) => {
+                warn!("⚠️  Couldn't read curve for {}: {}", token_mint, e);
+                None
+            }
+        }
+    }
+
+    /// Count a check that couldn't read the position; after `MAX_CHECK_FAILURES`
+    /// in a row it's sold rather than held blind
+    ///
+    /// Returns the exit result when it sold, None to try again on the next check.
+    async fn check_failed(&self, token_mint: &str, failures: &mut u32, error: anyhow::Error) -> Option<Result<()>> {
+        *failures += 1;
+        warn!("⚠️  Check {}/{} for {} failed: {}", failures, MAX_CHECK_FAILURES, token_mint, error);
+        if *failures < MAX_CHECK_FAILURES {
+            return None;
+        }
+
+        error!("🚨 Couldn't read {} for {} checks, exiting", token_mint, failures);
+        Some(self.execute_exit(token_mint, "100%", ExitReason::NoPriceData).await)
+    }
+
+    /// Sleep until the next check, waking early if the creator sells
+    async fn wait_for_check(&self, token_mint: &str, period: Duration) {
+        tokio::select! {
+            _ = tokio::time::sleep(period) => {}
+            sell = self.dev_sells.sold(token_mint) => {
+                warn!("🚨 Dev {} sold, checking {} now", sell.creator, token_mint);
+            }
+        }
+    }
+
+    /// Sell everything once the position has been held `max_hold_secs`
+    ///
+    /// Returns the exit result when the stop fired, None while within the limit.
+    async fn hard_time_stop(&self, token_mint: &str, entry_time: Instant) -> Option<Result<()>> {
+        let elapsed = entry_time.elapsed().as_secs();
+        exit::hard_time_stop(elapsed, self.max_hold_secs)?;
+
+        warn!("🛑 Hard time stop: held {}s (max {}s)", elapsed, self.max_hold_secs);
+        Some(self.execute_exit(token_mint, "100%", ExitReason::HardTimeStop).await)
+    }
+
+    /// Sell everything when a large holder dumps the mint
+    ///
+    /// Returns the exit result when a dump was seen, None otherwise. A failed
+    /// check is logged and tried again on the next one.
+    async fn whale_dump_exit(&self, token_mint: &str) -> Option<Result<()>> {
+        let strategy = match frontrun::should_emergency_exit(&self.frontrun, token_mint).await {
+            Ok(strategy) => strategy?,
+            Err(e) => {
+                warn!("⚠️  Whale check failed for {}: {}", token_mint, e);
+                return None;
+            }
+        };
+
+        match strategy.action {
+            FrontRunAction::SellImmediately | FrontRunAction::SellFast => {
+                error!("🐋 WHALE DUMP! Emergency exit!");
+                let request = TradeRequest::sell(
+                    token_mint.to_string(),
+                    "100%".to_string(),
+                    strategy.slippage,
+                    strategy.priority_fee,
+                )
+                .with_jito_only(strategy.use_jito);
+                Some(self.execute_exit_with(request, ExitReason::Rug).await)
+            }
+            FrontRunAction::MonitorClosely | FrontRunAction::Ignore => None,
+        }
+    }
+
     /// Execute exit
     async fn execute_exit(
         &self,
         token_mint: &str,
         amount: &str,
-        reason: &str,
+        reason: ExitReason,
     ) -> Result<()> {
-        info!("🔴 EXITING: {} ({})", amount, reason);
-
         let request = TradeRequest::sell(
             token_mint.to_string(),
             amount.to_string(),
-            20, // High slippage for fast exit
+            SELL_SLIPPAGE,
             0.0005,
         )
         .with_jito_only(true);
+        self.execute_exit_with(request, reason).await
+    }
 
-        match self.pumpportal.trade(request).await {
-            Ok(response) => {
+    /// Execute exit with the slippage, fee and routing `request` carries
+    async fn execute_exit_with(&self, request: TradeRequest, reason: ExitReason) -> Result<()> {
+        let (token_mint, amount, slippage) = (request.mint.clone(), request.amount.clone(), request.slippage);
+        let (token_mint, amount) = (token_mint.as_str(), amount.as_str());
+        info!("🔴 EXITING: {} ({})", amount, reason);
+        // Ping before selling, so a rug is reported even if the sell fails
+        if reason == ExitReason::Rug {
+            self.notifier.notify(Notification::rug(token_mint, "rug risk above 70% (dev sell or liquidity pull)"));
+        }
+
+        match self.trade(request, Some(reason)).await {
+            Ok((response, paper_proceeds)) => {
                 if let Some(sig) = response.signature {
-                    info!("✅ EXIT EXECUTED: {}", sig);
+                    tracing::info!(event = "sell", mint = token_mint, percent = percent_of(amount), slippage,
+                                   signature = %sig, reason = %reason, "✅ EXIT SUBMITTED");
                     info!("   🔗 https://solscan.io/tx/{}", sig);
+
+                    if let Err(e) = self.reputation.record_exit(token_mint, reason) {
+                        error!("Failed to record exit for dev reputation: {}", e);
+                    }
+
+                    self.confirm_transaction(&sig, token_mint, "sell", 0.0).await?;
+                    self.record_proceeds(&sig, token_mint, paper_proceeds).await;
+                    if percent_of(amount).is_some_and(|percent| percent >= 100.0) {
+                        self.close_position(token_mint, &sig, reason).await;
+                    }
+                    self.notifier.notify(Notification::sell(token_mint, &sig, reason));
                     Ok(())
                 } else {
-                    error!("❌ Exit failed: No signature");
+                    tracing::error!(event = "sell_failed", mint = token_mint, percent = percent_of(amount),
+                                    error = "no signature", "❌ Exit failed");
                     Err(anyhow::anyhow!("Exit failed"))
                 }
             }
             Err(e) => {
-                error!("❌ Exit error: {}", e);
+                tracing::error!(event = "sell_failed", mint = token_mint, percent = percent_of(amount),
+                                error = %e, "❌ Exit error");
                 Err(e.into())
             }
         }
     }
 }
+
+/// Check that `position`'s entry buy landed on-chain, recording the result on
+/// its transaction; a buy that failed, or that the node never saw before its
+/// blockhash expired, leaves a phantom position, which is taken out of play.
+/// Returns whether the buy landed, None while that's still unknown.
+pub async fn verify_signature_landed(
+    client: &PumpPortalClient,
+    db: &Database,
+    rpc_url: &str,
+    position: &Position,
+) -> Result<Option<bool>> {
+    let (mint, signature) = (&position.mint, &position.entry_signature);
+    if signature.is_empty() {
+        // Reserved, but the bot stopped before its buy was sent
+        db.release_position(mint)?;
+        tracing::warn!(event = "phantom_position", mint = %mint, "👻 Entry buy was never sent, releasing position");
+        return Ok(Some(false));
+    }
+    // Positions from before buys were recorded have no row to mark yet
+    db.record_transaction(signature, mint, "buy", position.entry_sol_amount, position.entry_time)?;
+
+    let sent_at = UNIX_EPOCH + Duration::from_secs(position.entry_time.max(0) as u64);
+    let expired = SystemTime::now().duration_since(sent_at).is_ok_and(|age| age >= BLOCKHASH_LIFETIME);
+    let status = match client.confirm(signature, rpc_url, CONFIRM_TIMEOUT).await? {
+        // A node that answers without it after that long means it was dropped
+        ConfirmationStatus::Timeout if expired => client.signature_status(signature, rpc_url).await?,
+        status => Some(status),
+    };
+
+    match status {
+        Some(ConfirmationStatus::Confirmed) => {
+            db.mark_transaction_verified(signature, true)?;
+            info!("✅ Entry for {} verified on-chain: {}", mint, signature);
+            Ok(Some(true))
+        }
+        Some(ConfirmationStatus::Timeout) => {
+            warn!("⏳ Entry for {} not confirmed yet, checking again later: {}", mint, signature);
+            Ok(None)
+        }
+        status => {
+            db.mark_transaction_verified(signature, false)?;
+            db.mark_position_phantom(mint)?;
+            tracing::warn!(event = "phantom_position", mint = %mint, signature = %signature,
+                           status = ?status, "👻 Entry buy never landed, dropping phantom position");
+            Ok(Some(false))
+        }
+    }
+}
+
+/// Verify every active position whose entry buy was never confirmed, in the
+/// background so it doesn't hold up launches
+pub async fn verify_open_positions(client: PumpPortalClient, db: Database, rpc_url: String) {
+    let mut pending = match db.unverified_positions() {
+        Ok(positions) => positions,
+        Err(e) => {
+            error!("Unverified position lookup failed: {}", e);
+            return;
+        }
+    };
+    if pending.is_empty() {
+        return;
+    }
+
+    info!("🔍 Verifying {} open position(s) with unconfirmed entries", pending.len());
+    for attempt in 1..=VERIFY_ATTEMPTS {
+        let mut undecided = Vec::new();
+        for position in pending {
+            match verify_signature_landed(&client, &db, &rpc_url, &position).await {
+                Ok(Some(_)) => {}
+                Ok(None) => undecided.push(position),
+                Err(e) => {
+                    error!("Couldn't verify entry for {}: {}", position.mint, e);
+                    undecided.push(position);
+                }
+            }
+        }
+        pending = undecided;
+        if pending.is_empty() {
+            return;
+        }
+        if attempt < VERIFY_ATTEMPTS {
+            tokio::time::sleep(VERIFY_RETRY_DELAY).await;
+        }
+    }
+    warn!("⏳ {} entry buy(s) still unconfirmed, leaving them active", pending.len());
+}
+
+/// Percentage from a sell amount like "50%", None for absolute token amounts
+fn percent_of(amount: &str) -> Option<f64> {
+    amount.strip_suffix('%')?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::generate_report;
+    use pump_portal_sdk::config::PaperConfig;
+
+    const MINT: &str = "7GCihgDB8fe6KNjn2MYtkzZcRjQy3t9GHdC8uHYmW2hr";
+
+    /// A bot paper trading into `db`
+    fn paper_bot(db: &Database) -> SniperBot {
+        let config = PaperConfig { enabled: true, buy_latency_ms: 0, sell_latency_ms: 0, ..PaperConfig::default() };
+        let reputation = DevReputation::new(":memory:", 3).unwrap();
+        let bot_config = BotConfig { snipe_amount_sol: 0.05, max_hold_secs: 60, ..BotConfig::default() };
+        SniperBot::new(String::new(), "http://127.0.0.1:8899".to_string(), &bot_config,
+                       db.clone(), reputation, DevSellWatcher::detached())
+            .unwrap()
+            .with_paper(Arc::new(PaperTradingSimulator::new(config, None)))
+    }
+
+    #[tokio::test]
+    async fn test_full_exit_closes_the_position_for_the_report() {
+        let db = Database::new(":memory:").unwrap();
+        let bot = paper_bot(&db);
+        bot.execute_snipe(MINT, None).await.unwrap();
+        assert!(db.sol_at_risk().unwrap() > 0.0);
+
+        // A partial sell leaves the position open
+        bot.execute_exit(MINT, "50%", ExitReason::TakeProfit).await.unwrap();
+        assert_eq!(db.active_position_count().unwrap(), 1);
+        assert_eq!(generate_report(&db).unwrap().closed_trades, 0);
+
+        bot.execute_exit(MINT, "100%", ExitReason::TakeProfit).await.unwrap();
+        assert_eq!(db.sol_at_risk().unwrap(), 0.0);
+
+        let report = generate_report(&db).unwrap();
+        assert_eq!((report.closed_trades, report.open_positions), (1, 0));
+        assert_eq!(report.exit_reasons[0].reason, Some(ExitReason::TakeProfit));
+        // Paper sells fill at the entry price, so fees make it a small loss
+        assert_eq!(report.losses, 1);
+        assert!(report.profit_loss_sol < 0.0 && report.profit_loss_sol > -0.01);
+    }
+}
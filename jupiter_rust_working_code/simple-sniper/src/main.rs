@@ -1,166 +1,454 @@
 use anyhow::Result;
 use dotenv::dotenv;
-use futures_util::{SinkExt, StreamExt};
-use log::{info, warn, error};
-use pump_portal_sdk::{PumpPortalClient, TradeRequest};
-use serde::{Deserialize, Serialize};
+use log::{info, warn};
+use pump_portal_sdk::config::BotConfig;
+use pump_portal_sdk::dedup::DEFAULT_BUY_DEDUP_WINDOW;
+use pump_portal_sdk::export::{self, get_timestamp_micros, SharedExporter, SnipeLatencyRecord};
+use pump_portal_sdk::{exit, paper, AdaptiveSlippage, ConfirmationStatus, ExitDecision, ExitReason, ExitStrategy, LatencyReport, LossStreak, MintCooldown, Notification, Notifier, PaperTradingSimulator, PnlTracker, Proceeds, PositionState, PumpPortalClient, PumpPortalEvent, PumpPortalStream, SnipeLatency, SnipeTimer, Subscription, TokenTradeEvent, TradeFlow, TradeRequest, TradeSide, PUMP_TOKEN_DECIMALS};
 use std::collections::HashMap;
 use std::env;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tokio::time::{sleep, Duration};
-use tokio_tungstenite::{connect_async, tungstenite::Message};
 
 #[derive(Debug, Clone)]
 struct Position {
     mint: String,
-    entry_sol: f64,
-    entry_signature: String,
+    /// Entry price in SOL per token (the launch price for paper buys, else the
+    /// first trade seen) and SOL at cost not yet sold, what the next sell is
+    /// scored against
+    pnl: PnlTracker,
+    /// Buy/sell pressure from the token's trades since entry
+    flow: TradeFlow,
     entry_time: std::time::Instant,
+    exits_taken: u32,
 }
 
-#[derive(Debug, Deserialize)]
-struct TokenCreatedEvent {
-    signature: Option<String>,
-    mint: Option<String>,
-    #[serde(rename = "traderPublicKey")]
-    trader_public_key: Option<String>,
-    #[serde(rename = "txType")]
-    tx_type: Option<String>,
-    #[serde(rename = "initialBuy")]
-    initial_buy: Option<f64>,
+impl Position {
+    /// Mark the position with a trade from its token's feed
+    fn mark(&mut self, side: TradeSide, trade: &TokenTradeEvent) {
+        if let Some(price) = trade.price() {
+            self.pnl.update(price);
+        }
+        self.flow.record(side, trade.sol_amount, std::time::Instant::now());
+    }
 }
 
 type Positions = Arc<Mutex<HashMap<String, Position>>>;
 
+/// SOL at cost still in open positions, what `max_total_sol_at_risk` caps
+fn sol_at_risk(positions: &HashMap<String, Position>) -> f64 {
+    positions.values().map(|pos| pos.pnl.size_sol()).sum()
+}
+
+/// What holds back new buys: per-mint re-buy cooldowns and the loss streak
+struct BuyCooldowns {
+    mints: MintCooldown,
+    losses: LossStreak,
+}
+
+type Cooldowns = Arc<Mutex<BuyCooldowns>>;
+
+/// How long to wait for a sell to show up on-chain when scoring it
+const PROCEEDS_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long to wait for a live buy to land before keeping the provisional entry
+const FILL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long a benchmarked buy may take to confirm before it's left out
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Trades this recent count toward a position's momentum
+const FLOW_WINDOW: Duration = Duration::from_secs(60);
+
+/// Log latency percentiles every this many benchmarked snipes
+const LATENCY_REPORT_EVERY: usize = 10;
+
+/// Launch-to-confirmed-buy timings, in latency benchmark mode
+struct LatencyBenchmark {
+    /// What this run's timings are stored and compared under
+    label: String,
+    exporter: Option<SharedExporter>,
+    samples: Mutex<Vec<SnipeLatency>>,
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    env_logger::init();
+    pump_portal_sdk::logging::init();
     dotenv().ok();
 
     info!("🚀 SIMPLE SNIPER BOT - LFG!");
 
     let api_key = env::var("PUMPPORTAL_API_KEY").expect("PUMPPORTAL_API_KEY required");
-    let snipe_amount = env::var("SNIPE_AMOUNT_SOL")
-        .unwrap_or_else(|_| "0.05".to_string())
-        .parse::<f64>()?;
-
-    let max_positions = env::var("MAX_POSITIONS")
-        .unwrap_or_else(|_| "3".to_string())
-        .parse::<usize>()?;
-
-    info!("💰 Config:");
-    info!("   Snipe Amount: {} SOL per trade", snipe_amount);
-    info!("   Max Positions: {}", max_positions);
-    info!("   Strategy: Buy launches → 2x exit → Repeat");
-
-    let client = Arc::new(PumpPortalClient::new(api_key));
+    // config.toml / config.json if present, else env vars
+    let config = Arc::new(BotConfig::load(BotConfig::default())?);
+    let (snipe_amount, max_positions) = (config.snipe_amount_sol, config.max_positions);
+
+    // Held tokens' trades feed price and momentum, so any strategy can fire
+    let exit_strategy: Arc<dyn ExitStrategy> = match &config.exit_strategy {
+        Some(name) => Arc::from(exit::by_name(name)?),
+        None => Arc::new(exit::TimeBased::new(60)),
+    };
+
+    config.display();
+    info!("   Strategy: Buy launches → {} exit → Repeat", exit_strategy.name());
+
+    // A buy resent after an ambiguous failure must not double the position
+    let mut client = PumpPortalClient::new(api_key).with_buy_dedup(DEFAULT_BUY_DEDUP_WINDOW);
+    if let Some(max) = config.max_slippage {
+        client = client.with_slippage_retry(max);
+    }
+    let client = Arc::new(client);
+    let notifier = config.notifier("simple-sniper");
+    // Paper trading with DuckDB export for research (PAPER_MODE or --paper)
+    let paper_sim = config.paper_simulator();
+    let budget = config.sol_budget();
+    // Live buys only: paper buys never fail on slippage
+    let adaptive_slippage = config.adaptive_slippage().filter(|_| paper_sim.is_none());
+    let benchmark = config.latency_benchmark.then(|| {
+        Arc::new(LatencyBenchmark {
+            label: config.latency_label(),
+            exporter: export::shared_from_env(),
+            samples: Mutex::new(Vec::new()),
+        })
+    });
     let positions: Positions = Arc::new(Mutex::new(HashMap::new()));
+    let cooldowns: Cooldowns = Arc::new(Mutex::new(BuyCooldowns {
+        mints: MintCooldown::new(Duration::from_secs(config.mint_cooldown_secs)),
+        losses: LossStreak::new(config.loss_streak_limit, Duration::from_secs(config.loss_cooldown_secs)),
+    }));
 
     // Start position monitor
     let monitor_client = client.clone();
     let monitor_positions = positions.clone();
+    let monitor_cooldowns = cooldowns.clone();
+    let monitor_config = config.clone();
+    let monitor_notifier = notifier.clone();
+    let monitor_paper_sim = paper_sim.clone();
     tokio::spawn(async move {
-        monitor_positions_loop(monitor_client, monitor_positions).await;
+        monitor_positions_loop(monitor_client, monitor_positions, monitor_cooldowns, exit_strategy, monitor_config, monitor_notifier, monitor_paper_sim).await;
     });
 
     // Connect to PumpPortal WebSocket
     info!("📡 Connecting to PumpPortal WebSocket...");
-    let ws_url = "wss://pumpportal.fun/api/data";
-    let (ws_stream, _) = connect_async(ws_url).await?;
-    let (mut write, mut read) = ws_stream.split();
+    let mut stream = PumpPortalStream::connect(config.stream_config()).await?;
 
     // Subscribe to new token launches
-    let subscribe_msg = serde_json::json!({
-        "method": "subscribeNewToken"
-    });
-    write.send(Message::Text(subscribe_msg.to_string())).await?;
+    stream.subscribe(&Subscription::NewTokens);
     info!("✅ Subscribed to new token launches\n");
     info!("🎯 WATCHING FOR LAUNCHES... Press Ctrl+C to stop\n");
 
-    // Process launch events
-    while let Some(msg) = read.next().await {
-        match msg {
-            Ok(Message::Text(text)) => {
-                if let Ok(event) = serde_json::from_str::<TokenCreatedEvent>(&text) {
-                    // Check if we have room for another position
-                    let current_positions = positions.lock().await.len();
-                    if current_positions >= max_positions {
-                        warn!("⏸️  Max positions ({}) reached, skipping launch", max_positions);
-                        continue;
-                    }
+    // Process launch events; the stream reconnects itself on drops and stalls
+    loop {
+        let text = stream.next().await;
+        let mut timer = SnipeTimer::start();
+        let Ok(event) = serde_json::from_str::<PumpPortalEvent>(&text) else {
+            continue;
+        };
 
-                    if let Some(mint) = event.mint {
-                        info!("🔔 NEW LAUNCH DETECTED!");
-                        info!("   Mint: {}", mint);
-
-                        // Execute buy
-                        match execute_buy(&client, &mint, snipe_amount).await {
-                            Ok(signature) => {
-                                info!("✅ BUY EXECUTED!");
-                                info!("   Signature: {}", signature);
-                                info!("   Amount: {} SOL", snipe_amount);
-
-                                // Store position
-                                let position = Position {
-                                    mint: mint.clone(),
-                                    entry_sol: snipe_amount,
-                                    entry_signature: signature,
-                                    entry_time: std::time::Instant::now(),
-                                };
-                                positions.lock().await.insert(mint, position);
-
-                                let remaining = max_positions - current_positions - 1;
-                                info!("💼 Positions: {}/{} ({}left)", current_positions + 1, max_positions, remaining);
-                            }
-                            Err(e) => {
-                                error!("❌ Buy failed: {}", e);
-                            }
-                        }
+        // Trade on a held token: mark its price and momentum for the exit strategy
+        if let Some((side, trade)) = event.trade() {
+            match positions.lock().await.get_mut(&trade.mint) {
+                Some(pos) => pos.mark(side, trade),
+                // Sold since; its feed isn't needed any more
+                None => stream.unsubscribe(&Subscription::TokenTrades(vec![trade.mint.clone()])),
+            }
+            continue;
+        }
+
+        if let PumpPortalEvent::NewToken(event) = event {
+            // Check if we have room for another position
+            let current_positions = positions.lock().await.len();
+            if current_positions >= max_positions {
+                tracing::warn!(event = "skip", mint = %event.mint,
+                               reason = "max_positions", "⏸️  Max positions ({}) reached, skipping launch", max_positions);
+                continue;
+            }
+
+            let launch_price = event.curve().map(|curve| curve.price());
+            if let Some(reserves) = event.thin_reserves(config.min_sol_reserves) {
+                tracing::info!(event = "skip", mint = %event.mint, reason = "low_liquidity", sol_reserves = reserves,
+                               "💧 Only {:.2} SOL in the curve (min {:.2}), skipping", reserves, config.min_sol_reserves);
+                continue;
+            }
+            let mint = event.mint;
+            if let Some(left) = cooldowns.lock().await.losses.remaining() {
+                tracing::info!(event = "skip", mint = %mint, reason = "loss_streak", cooldown_left_secs = left.as_secs(),
+                               "🧊 Paused after a losing streak ({}s left), skipping {}", left.as_secs(), mint);
+                continue;
+            }
+            if positions.lock().await.contains_key(&mint) {
+                tracing::info!(event = "skip", mint = %mint, reason = "holding", "⏭️  Already holding {}, skipping", mint);
+                continue;
+            }
+            if let Some(left) = cooldowns.lock().await.mints.remaining(&mint) {
+                tracing::info!(event = "skip", mint = %mint, reason = "cooldown", cooldown_left_secs = left.as_secs(),
+                               "⏳ {} exited recently, cooling down ({}s left), skipping", mint, left.as_secs());
+                continue;
+            }
+
+            // Hold back the buy's SOL so the cap counts it until it's a position
+            let reservation = match budget.reserve(sol_at_risk(&*positions.lock().await), snipe_amount) {
+                Ok(reservation) => reservation,
+                Err(left) => {
+                    tracing::warn!(event = "skip", mint = %mint, reason = "max_sol_at_risk", budget_left_sol = left,
+                                   "💰 {} SOL left under the {} SOL cap, skipping {}",
+                                   left, budget.max_sol().unwrap_or_default(), mint);
+                    continue;
+                }
+            };
+
+            info!("🔔 NEW LAUNCH DETECTED!");
+            info!("   Mint: {}", mint);
+
+            // Execute buy
+            timer.decided();
+            let slippage = adaptive_slippage.as_ref().map_or(config.buy_slippage, AdaptiveSlippage::current);
+            let result = execute_buy(&client, &mint, snipe_amount, slippage, &config, launch_price, &paper_sim).await;
+            if let Some(adaptive) = &adaptive_slippage {
+                // A sent buy can still fail on slippage on-chain, so it's counted once it settles
+                match &result {
+                    Ok(signature) => adaptive.record_when_confirmed(&client, signature, &config.rpc_url),
+                    Err(e) => {
+                        adaptive.record_error(e.as_ref());
                     }
                 }
             }
-            Ok(Message::Close(_)) => {
-                warn!("WebSocket closed, reconnecting...");
-                break;
-            }
-            Err(e) => {
-                error!("WebSocket error: {}", e);
+            match result {
+                Ok(signature) => {
+                    timer.submitted();
+                    tracing::info!(event = "buy", mint = %mint, sol = snipe_amount, slippage,
+                                   signature = %signature, "✅ BUY EXECUTED");
+                    notifier.notify(Notification::buy(&mint, snipe_amount, &signature));
+                    if let Some(benchmark) = &benchmark {
+                        record_latency(&client, benchmark, &config.rpc_url, &mint, &signature, timer);
+                    }
+
+                    // Store position and follow its trades
+                    let entry_price = launch_price.filter(|_| paper_sim.is_some()).unwrap_or(0.0);
+                    let position = Position {
+                        mint: mint.clone(),
+                        pnl: PnlTracker::new(entry_price, snipe_amount),
+                        flow: TradeFlow::new(FLOW_WINDOW),
+                        entry_time: std::time::Instant::now(),
+                        exits_taken: 0,
+                    };
+                    stream.subscribe(&Subscription::TokenTrades(vec![mint.clone()]));
+                    let at_risk = {
+                        let mut locked = positions.lock().await;
+                        locked.insert(mint.clone(), position);
+                        drop(reservation);
+                        sol_at_risk(&locked)
+                    };
+                    // Replace the provisional entry with what the buy actually paid
+                    if paper_sim.is_none() {
+                        capture_entry_price(&client, &positions, &config.rpc_url, &mint, &signature);
+                    }
+
+                    let remaining = max_positions - current_positions - 1;
+                    info!("💼 Positions: {}/{} ({}left)", current_positions + 1, max_positions, remaining);
+                    if let Some(left) = budget.remaining(at_risk) {
+                        info!("💰 At risk: {:.4} SOL ({:.4} SOL budget left)", at_risk, left);
+                    }
+                }
+                Err(e) => {
+                    tracing::error!(event = "buy_failed", mint = %mint, sol = snipe_amount, error = %e, "❌ Buy failed");
+                }
             }
-            _ => {}
         }
     }
-
-    info!("Bot stopped");
-    Ok(())
 }
 
-async fn execute_buy(client: &PumpPortalClient, mint: &str, amount_sol: f64) -> Result<String> {
+/// Buy `amount_sol` of a launch; paper buys fill at its curve `price`
+async fn execute_buy(
+    client: &PumpPortalClient,
+    mint: &str,
+    amount_sol: f64,
+    slippage: u32,
+    config: &BotConfig,
+    price: Option<f64>,
+    paper_sim: &Option<Arc<PaperTradingSimulator>>,
+) -> Result<String> {
     let request = TradeRequest::buy(
         mint.to_string(),
         amount_sol,
-        10, // 10% slippage
-        0.0001, // priority fee
-    ).with_jito_only(true); // Lightning fast Jito routing
+        slippage,
+        config.priority_fee_sol,
+    ).with_jito_only(config.jito_only); // Lightning fast Jito routing by default
+
+    // Paper trading mode
+    if let Some(sim) = paper_sim {
+        request.validate()?;
+        let price = price.unwrap_or(paper::ESTIMATED_LAUNCH_PRICE);
+        return Ok(sim.simulate_buy(mint.to_string(), amount_sol, price, PUMP_TOKEN_DECIMALS).await?);
+    }
 
     let response = client.trade(request).await?;
     Ok(response.signature.unwrap_or_else(|| "unknown".to_string()))
 }
 
-async fn execute_sell(client: &PumpPortalClient, mint: &str, _amount_sol: f64) -> Result<String> {
-    // Sell 100% of tokens
+/// Sell `percent` of a position. Paper sells fill at `price`, the last one
+/// its trade feed marked, and come back with their proceeds; a live sell's
+/// are read from the chain once it lands.
+async fn execute_sell(
+    client: &PumpPortalClient,
+    mint: &str,
+    percent: u32,
+    price: f64,
+    config: &BotConfig,
+    paper_sim: &Option<Arc<PaperTradingSimulator>>,
+    exit_reason: ExitReason,
+) -> Result<(String, Option<Proceeds>)> {
+    if let Some(sim) = paper_sim {
+        let (signature, proceeds) = sim.simulate_sell(mint, percent, Some(price), Some(exit_reason)).await?;
+        return Ok((signature, Some(proceeds)));
+    }
+
     let request = TradeRequest::sell(
         mint.to_string(),
-        "100%".to_string(), // Sell everything
-        20, // 20% slippage for fast exit
-        0.0001, // priority fee
-    ).with_jito_only(true);
+        format!("{}%", percent),
+        config.sell_slippage,
+        config.priority_fee_sol,
+    ).with_jito_only(config.jito_only);
 
     let response = client.trade(request).await?;
-    Ok(response.signature.unwrap_or_else(|| "unknown".to_string()))
+    Ok((response.signature.unwrap_or_else(|| "unknown".to_string()), None))
+}
+
+/// Set a position's entry price from its confirmed buy transaction
+fn capture_entry_price(client: &PumpPortalClient, positions: &Positions, rpc_url: &str, mint: &str, signature: &str) {
+    let (client, positions) = (client.clone(), positions.clone());
+    let (rpc_url, mint, signature) = (rpc_url.to_string(), mint.to_string(), signature.to_string());
+    tokio::spawn(async move {
+        match client.fill(&signature, &mint, &rpc_url, FILL_TIMEOUT).await {
+            Ok(fill) => {
+                info!("   📌 {} filled: {:.4} SOL for {:.0} tokens ({:.10} SOL/token)",
+                      mint, fill.sol_spent, fill.tokens_received, fill.price());
+                if let Some(pos) = positions.lock().await.get_mut(&mint) {
+                    pos.pnl.set_entry_price(fill.price());
+                }
+            }
+            Err(e) => warn!("   ⚠️  No fill for {} ({}), keeping the first trade as entry", mint, e),
+        }
+    });
 }
 
-async fn monitor_positions_loop(client: Arc<PumpPortalClient>, positions: Positions) {
+/// Once a benchmarked buy confirms, store how long each stage took and log
+/// percentiles every [`LATENCY_REPORT_EVERY`] snipes
+///
+/// Runs in the background so the launch loop isn't held up. Buys that fail or
+/// don't confirm in time are left out rather than skewing the numbers.
+fn record_latency(
+    client: &PumpPortalClient,
+    benchmark: &Arc<LatencyBenchmark>,
+    rpc_url: &str,
+    mint: &str,
+    signature: &str,
+    timer: SnipeTimer,
+) {
+    let (client, benchmark) = (client.clone(), benchmark.clone());
+    let (rpc_url, mint, signature) = (rpc_url.to_string(), mint.to_string(), signature.to_string());
+    tokio::spawn(async move {
+        match client.confirm(&signature, &rpc_url, CONFIRM_TIMEOUT).await {
+            Ok(ConfirmationStatus::Confirmed) => {}
+            Ok(status) => {
+                warn!("   ⏱️  Buy of {} not confirmed ({:?}), left out of the benchmark", mint, status);
+                return;
+            }
+            Err(e) => {
+                warn!("   ⏱️  Couldn't confirm buy of {}: {}", mint, e);
+                return;
+            }
+        }
+        let latency = timer.confirmed();
+        tracing::info!(event = "snipe_latency", mint = %mint, signature = %signature, label = %benchmark.label,
+                       decide_ms = latency.decide.as_millis() as u64, submit_ms = latency.submit.as_millis() as u64,
+                       confirm_ms = latency.confirm.as_millis() as u64, total_ms = latency.total().as_millis() as u64,
+                       "⏱️  Launch to confirmed buy: {}ms", latency.total().as_millis());
+
+        if let Some(exporter) = &benchmark.exporter {
+            let record = SnipeLatencyRecord {
+                signature,
+                timestamp_micros: get_timestamp_micros(),
+                mint,
+                label: benchmark.label.clone(),
+                latency,
+            };
+            if let Err(e) = exporter.lock().await.record_snipe_latency(record) {
+                warn!("   ⚠️  Failed to store snipe latency: {}", e);
+            }
+        }
+
+        let mut samples = benchmark.samples.lock().await;
+        samples.push(latency);
+        if samples.len() % LATENCY_REPORT_EVERY == 0 {
+            if let Some(report) = LatencyReport::from_samples(&samples) {
+                report.log(&benchmark.label);
+            }
+        }
+    });
+}
+
+/// Once a sell lands, score it against the cost of what it sold, feed the
+/// loss streak and send the `sell` notification with the result
+///
+/// Runs in the background so the monitor isn't held up waiting on the RPC.
+/// `proceeds` are already known for paper sells. A sell whose proceeds can't
+/// be read isn't counted either way.
+fn record_outcome(
+    client: &PumpPortalClient,
+    cooldowns: &Cooldowns,
+    notifier: &Arc<dyn Notifier>,
+    rpc_url: &str,
+    sell: Notification,
+    cost_sol: f64,
+    proceeds: Option<Proceeds>,
+) {
+    let (client, cooldowns, notifier) = (client.clone(), cooldowns.clone(), notifier.clone());
+    let rpc_url = rpc_url.to_string();
+    tokio::spawn(async move {
+        let mint = sell.mint.clone();
+        let signature = sell.signature.clone().unwrap_or_default();
+        let proceeds = match proceeds {
+            Some(proceeds) => Ok(proceeds),
+            None => client.proceeds(&signature, &mint, &rpc_url, PROCEEDS_TIMEOUT).await,
+        };
+        let proceeds = match proceeds {
+            Ok(proceeds) => proceeds,
+            Err(e) => {
+                warn!("   ⚠️  No proceeds for sell of {}: {}", mint, e);
+                notifier.notify(sell);
+                return;
+            }
+        };
+
+        // The sold tokens at cost, marked at the price they actually fetched
+        let mut pnl = PnlTracker::new(cost_sol / proceeds.tokens_sold, cost_sol);
+        pnl.update(proceeds.price());
+        let pnl_sol = pnl.unrealized_sol();
+        tracing::info!(event = "exit_pnl", mint = %mint, pnl_sol, pnl_percent = pnl.percent(),
+                       "💰 {} exit: {:+.4} SOL ({:+.1}%)", &mint[..8], pnl_sol, pnl.percent());
+        notifier.notify(sell.with_sol(proceeds.sol_received).with_pnl(pnl.percent()));
+
+        let mut cooldowns = cooldowns.lock().await;
+        if let Some(pause) = cooldowns.losses.record(pnl_sol >= 0.0) {
+            tracing::warn!(event = "loss_streak", mint = %mint, pnl_sol, losses = cooldowns.losses.losses(),
+                           cooldown_secs = pause.as_secs(),
+                           "🧊 {} losing exits in a row, pausing buys for {}s", cooldowns.losses.losses(), pause.as_secs());
+        }
+    });
+}
+
+async fn monitor_positions_loop(
+    client: Arc<PumpPortalClient>,
+    positions: Positions,
+    cooldowns: Cooldowns,
+    exit_strategy: Arc<dyn ExitStrategy>,
+    config: Arc<BotConfig>,
+    notifier: Arc<dyn Notifier>,
+    paper_sim: Option<Arc<PaperTradingSimulator>>,
+) {
+    let (max_hold_secs, sell_slippage) = (config.max_hold_secs, config.sell_slippage);
     info!("👀 Position monitor started\n");
 
     loop {
@@ -180,22 +468,46 @@ async fn monitor_positions_loop(client: Arc<PumpPortalClient>, positions: Positi
         for position in positions_snapshot {
             let elapsed = position.entry_time.elapsed().as_secs();
 
-            info!("   {} - {}s elapsed", &position.mint[..8], elapsed);
-
-            // Simple exit strategy:
-            // 1. If > 60s old, exit (assume no momentum)
-            // 2. Manual 2x checking would require price tracking
-
-            if elapsed > 60 {
-                info!("   ⏰ Position aged > 60s, exiting...");
-
-                match execute_sell(&client, &position.mint, position.entry_sol).await {
-                    Ok(sig) => {
-                        info!("   ✅ SOLD: {}", sig);
-                        positions.lock().await.remove(&position.mint);
+            let state = PositionState {
+                pnl_multiplier: position.pnl.multiplier(),
+                peak_multiplier: position.pnl.peak_multiplier(),
+                momentum: position.flow.momentum(std::time::Instant::now()),
+                exits_taken: position.exits_taken,
+                ..PositionState::at(elapsed)
+            };
+            info!("   {} - {}s | {:.2}x (peak {:.2}x) | momentum {:.2}",
+                  &position.mint[..8], elapsed, state.pnl_multiplier, state.peak_multiplier, state.momentum);
+
+            let decision = match exit::hard_time_stop(elapsed, max_hold_secs) {
+                Some(stop) => {
+                    warn!("   🛑 Hard time stop: held {}s (max {}s)", elapsed, max_hold_secs);
+                    stop
+                }
+                None => exit_strategy.evaluate(&state),
+            };
+
+            if let ExitDecision::Sell { percent, reason, detail } = decision {
+                info!("   ⏰ {}, selling {}%...", detail, percent);
+
+                match execute_sell(&client, &position.mint, percent, position.pnl.price(), &config, &paper_sim, reason).await {
+                    Ok((sig, proceeds)) => {
+                        tracing::info!(event = "sell", mint = %position.mint, percent, slippage = sell_slippage,
+                                       signature = %sig, reason = %reason, "✅ SOLD");
+                        let sold_cost = position.pnl.size_sol() * f64::from(percent.min(100)) / 100.0;
+                        let sell = Notification::sell(&position.mint, &sig, reason);
+                        record_outcome(&client, &cooldowns, &notifier, &config.rpc_url, sell, sold_cost, proceeds);
+
+                        let mut locked = positions.lock().await;
+                        if percent >= 100 {
+                            locked.remove(&position.mint);
+                            cooldowns.lock().await.mints.record(&position.mint);
+                        } else if let Some(pos) = locked.get_mut(&position.mint) {
+                            pos.exits_taken += 1;
+                            pos.pnl.reduce(percent);
+                        }
                     }
                     Err(e) => {
-                        error!("   ❌ Sell failed: {}", e);
+                        tracing::error!(event = "sell_failed", mint = %position.mint, percent, error = %e, "❌ Sell failed");
                     }
                 }
             }
@@ -1,49 +1,116 @@
 mod social_checker;
 mod momentum_tracker;
+mod position_sizing;
 
 use anyhow::Result;
 use dotenv::dotenv;
-use futures_util::{SinkExt, StreamExt};
 use log::{error, info, warn};
-use momentum_tracker::check_momentum;
-use pump_portal_sdk::{PumpPortalClient, TradeRequest};
+use momentum_tracker::{check_momentum, MomentumError};
+use position_sizing::{PositionSizer, MIN_RISK_SCORE};
+use pump_portal_sdk::config::BotConfig;
+use pump_portal_sdk::dedup::DEFAULT_BUY_DEDUP_WINDOW;
+use pump_portal_sdk::{exit, fetch_token_metadata, paper, AdaptiveSlippage, ExitDecision, ExitReason, ExitStrategy, LossStreak, MintCooldown, Notification, Notifier, PaperTradingSimulator, PnlTracker, PositionState, Proceeds, PumpPortalClient, Pool, Quote, SolBudget, SolReservation, PumpPortalEvent, PumpPortalStream, Subscription, TokenMetadata, TradeRequest, PUMP_TOKEN_DECIMALS};
 use serde::{Deserialize, Serialize};
-use social_checker::{check_social_momentum, SocialScore};
+use social_checker::{SocialChecker, SocialScore};
 use std::collections::HashMap;
 use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
 use tokio::time::sleep;
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
 
 #[derive(Debug, Clone)]
 struct Position {
     mint: String,
-    entry_sol: f64,
-    entry_price_usd: f64,
-    entry_signature: String,
+    /// Entry price in USD per token and SOL at stake
+    pnl: PnlTracker,
     entry_time: std::time::Instant,
     risk_score: f64,
     social_score: Option<SocialScore>,
     fast_exit: bool,
     add_count: u32, // Track how many times we've added to position
     last_add_time: std::time::Instant, // Prevent rapid adds
+    /// Partial sells an exit strategy has taken
+    exits_taken: u32,
+    /// Last DexScreener price in SOL per token, what paper sells fill at
+    last_price_sol: Option<f64>,
 }
 
 type Positions = Arc<Mutex<HashMap<String, Position>>>;
 
-#[derive(Debug, Deserialize)]
-struct TokenCreatedEvent {
-    mint: Option<String>,
-    #[serde(default)]
-    name: Option<String>,
-    #[serde(default)]
-    symbol: Option<String>,
-    #[serde(default)]
-    uri: Option<String>,
+/// SOL at cost still in open positions, what `max_total_sol_at_risk` caps
+fn sol_at_risk(positions: &HashMap<String, Position>) -> f64 {
+    positions.values().map(|pos| pos.pnl.size_sol()).sum()
+}
+
+/// A position slot claimed for a buy in flight, and the SOL reserved for it
+/// once it's sized; both handed back on drop unless [`fill_slot`] filled it first
+struct PositionSlot {
+    pending: Arc<AtomicUsize>,
+    sol: Option<SolReservation>,
+}
+
+impl Drop for PositionSlot {
+    fn drop(&mut self) {
+        self.pending.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
+/// Claim a slot for buying `mint`, or the skip reason: already held, or open
+/// positions plus buys in flight are at `max_positions`
+///
+/// Checked and claimed under one lock, so two buys can't both take the last slot.
+async fn claim_slot(
+    positions: &Positions,
+    pending: &Arc<AtomicUsize>,
+    mint: &str,
+    max_positions: usize,
+) -> std::result::Result<PositionSlot, &'static str> {
+    let locked = positions.lock().await;
+    if locked.contains_key(mint) {
+        return Err("holding");
+    }
+    if locked.len() + pending.load(Ordering::SeqCst) >= max_positions {
+        return Err("max_positions");
+    }
+    pending.fetch_add(1, Ordering::SeqCst);
+    Ok(PositionSlot { pending: pending.clone(), sol: None })
+}
+
+/// Reserve `amount` SOL of `budget` for the buy holding `slot`, or the SOL left under the cap
+async fn reserve_sol(
+    positions: &Positions,
+    budget: &SolBudget,
+    slot: &mut PositionSlot,
+    amount: f64,
+) -> std::result::Result<(), f64> {
+    let locked = positions.lock().await;
+    slot.sol = Some(budget.reserve(sol_at_risk(&locked), amount)?);
+    Ok(())
+}
+
+/// Put a bought position in its claimed slot; returns how many are open and the SOL they have at risk
+async fn fill_slot(positions: &Positions, slot: PositionSlot, position: Position) -> (usize, f64) {
+    let mut locked = positions.lock().await;
+    locked.insert(position.mint.clone(), position);
+    // Released under the lock, so a claim never sees the position and its slot both
+    drop(slot);
+    (locked.len(), sol_at_risk(&locked))
+}
+
+/// What holds back new buys: per-mint re-buy cooldowns, the loss streak and
+/// the cap on SOL at risk; plus the slippage new buys start from
+struct BuyCooldowns {
+    mints: MintCooldown,
+    losses: LossStreak,
+    budget: SolBudget,
+    /// Base buy slippage when ADAPTIVE_SLIPPAGE is on, shared by entries and adds
+    slippage: Option<AdaptiveSlippage>,
+}
+
+type Cooldowns = Arc<Mutex<BuyCooldowns>>;
+
 #[derive(Debug, Serialize)]
 struct AIAnalysisRequest {
     token_name: String,
@@ -59,10 +126,20 @@ struct AIAnalysisResponse {
     reasoning: String,
 }
 
-/// Analyze token using simple heuristics + AI scoring
-async fn analyze_token(mint: &str, name: Option<String>, symbol: Option<String>) -> Result<(bool, f64)> {
-    let name = name.unwrap_or_else(|| "Unknown".to_string());
-    let symbol = symbol.unwrap_or_else(|| "???".to_string());
+/// Analyze token using simple heuristics + AI scoring, plus its off-chain
+/// metadata when the URI resolved
+async fn analyze_token(mint: &str, name: &str, symbol: &str, metadata: Option<&TokenMetadata>) -> Result<(bool, f64)> {
+    let name = match metadata {
+        Some(m) if name.is_empty() && !m.name.is_empty() => m.name.as_str(),
+        _ if name.is_empty() => "Unknown",
+        _ => name,
+    };
+    let symbol = match metadata {
+        Some(m) if symbol.is_empty() && !m.symbol.is_empty() => m.symbol.as_str(),
+        _ if symbol.is_empty() => "???",
+        _ => symbol,
+    };
+    let description = metadata.and_then(TokenMetadata::description).map(str::to_lowercase);
 
     info!("🔍 Analyzing: {} ({})", name, symbol);
 
@@ -81,7 +158,8 @@ async fn analyze_token(mint: &str, name: Option<String>, symbol: Option<String>)
     let symbol_lower = symbol.to_lowercase();
 
     for keyword in &scam_keywords {
-        if name_lower.contains(keyword) || symbol_lower.contains(keyword) {
+        let in_description = description.as_deref().is_some_and(|d| d.contains(keyword));
+        if name_lower.contains(keyword) || symbol_lower.contains(keyword) || in_description {
             warn!("   ❌ Scam keyword detected: {}", keyword);
             return Ok((false, 0.0));
         }
@@ -103,17 +181,30 @@ async fn analyze_token(mint: &str, name: Option<String>, symbol: Option<String>)
         risk_score *= 1.1;
     }
 
+    // Filter 6: Creators who bother with a description and image are less
+    // likely to be churning out throwaway launches
+    if let Some(metadata) = metadata {
+        match (description.is_some(), metadata.image_url().is_some()) {
+            (true, true) => risk_score *= 1.1,
+            (false, false) => {
+                risk_score *= 0.8;
+                warn!("   ⚠️  No description or image, reduced risk score");
+            }
+            _ => {}
+        }
+    }
+
     // AI Enhancement: Use DeepSeek-style reasoning
     // For now, use rule-based "AI" - can add real API later
     let ai_score = calculate_ai_score(&name, &symbol);
     risk_score *= ai_score;
 
-    let should_buy = risk_score >= 0.6;
+    let should_buy = risk_score >= MIN_RISK_SCORE;
 
     if should_buy {
         info!("   ✅ PASS - Risk Score: {:.2}", risk_score);
     } else {
-        warn!("   ⛔ FAIL - Risk Score: {:.2} (need >= 0.6)", risk_score);
+        warn!("   ⛔ FAIL - Risk Score: {:.2} (need >= {})", risk_score, MIN_RISK_SCORE);
     }
 
     Ok((should_buy, risk_score))
@@ -159,21 +250,27 @@ fn calculate_ai_score(name: &str, symbol: &str) -> f64 {
     score
 }
 
-async fn execute_buy(
-    client: &PumpPortalClient,
-    mint: &str,
-    amount_sol: f64,
-    risk_score: f64,
-) -> Result<String> {
-    // Dynamic slippage based on risk score
+/// Dynamic buy slippage based on risk score, widening from the configured `base`
+fn buy_slippage(base: u32, risk_score: f64) -> u32 {
     let slippage = if risk_score > 0.9 {
-        10 // Low slippage for high-quality tokens
+        base // Low slippage for high-quality tokens
     } else if risk_score > 0.7 {
-        15 // Medium slippage
+        base + 5 // Medium slippage
     } else {
-        20 // High slippage for risky plays
+        base + 10 // High slippage for risky plays
     };
+    slippage.min(100)
+}
 
+/// Buy `amount_sol` of a token; paper buys fill at `price` SOL per token
+async fn execute_buy(
+    client: &PumpPortalClient,
+    mint: &str,
+    amount_sol: f64,
+    slippage: u32,
+    price: Option<f64>,
+    paper_sim: &Option<Arc<PaperTradingSimulator>>,
+) -> Result<String> {
     let request = TradeRequest::buy(
         mint.to_string(),
         amount_sol,
@@ -181,26 +278,213 @@ async fn execute_buy(
         0.0001,
     ).with_jito_only(true);
 
+    // Paper trading mode
+    if let Some(sim) = paper_sim {
+        request.validate()?;
+        let price = price.unwrap_or(paper::ESTIMATED_LAUNCH_PRICE);
+        return Ok(sim.simulate_buy(mint.to_string(), amount_sol, price, PUMP_TOKEN_DECIMALS).await?);
+    }
+
     let response = client.trade(request).await?;
     Ok(response.signature.unwrap_or_else(|| "unknown".to_string()))
 }
 
-async fn execute_sell(client: &PumpPortalClient, mint: &str) -> Result<String> {
+/// Feed a live buy's outcome to `adaptive_slippage`. A sent buy can still fail
+/// on slippage on-chain, so it's counted once it settles on `rpc_url`.
+fn record_buy(adaptive_slippage: Option<&AdaptiveSlippage>, client: &PumpPortalClient, rpc_url: &str, result: &Result<String>) {
+    let Some(adaptive) = adaptive_slippage else {
+        return;
+    };
+    match result {
+        Ok(signature) => adaptive.record_when_confirmed(client, signature, rpc_url),
+        Err(e) => {
+            adaptive.record_error(e.as_ref());
+        }
+    }
+}
+
+/// Sell `percent` of a position. Paper sells fill at `price` SOL per token
+/// (the entry price without one) and come back with their proceeds; a live
+/// sell's are read from the chain once it lands.
+async fn execute_sell(
+    client: &PumpPortalClient,
+    mint: &str,
+    percent: u32,
+    slippage: u32,
+    price: Option<f64>,
+    paper_sim: &Option<Arc<PaperTradingSimulator>>,
+    exit_reason: ExitReason,
+) -> Result<(String, Option<Proceeds>)> {
+    if let Some(sim) = paper_sim {
+        let (signature, proceeds) = sim.simulate_sell(mint, percent, price, Some(exit_reason)).await?;
+        return Ok((signature, Some(proceeds)));
+    }
+
     let request = TradeRequest::sell(
         mint.to_string(),
-        "100%".to_string(),
-        20,
+        format!("{}%", percent),
+        slippage,
         0.0001,
     ).with_jito_only(true);
 
     let response = client.trade(request).await?;
-    Ok(response.signature.unwrap_or_else(|| "unknown".to_string()))
+    Ok((response.signature.unwrap_or_else(|| "unknown".to_string()), None))
+}
+
+/// Drop a fully sold position, start its mint's re-buy cooldown and score the sell
+async fn close_position(
+    client: &PumpPortalClient,
+    positions: &Positions,
+    cooldowns: &Cooldowns,
+    notifier: &Arc<dyn Notifier>,
+    rpc_url: &str,
+    sell: Notification,
+    proceeds: Option<Proceeds>,
+) {
+    let closed = positions.lock().await.remove(&sell.mint);
+    cooldowns.lock().await.mints.record(&sell.mint);
+    if let Some(position) = closed {
+        record_outcome(client, cooldowns, notifier, rpc_url, sell, position.pnl.size_sol(), proceeds);
+    }
+}
+
+/// Once a sell lands, score it against what the position cost, feed the loss
+/// streak and send the `sell` notification with the result
+///
+/// Runs in the background so the monitor isn't held up waiting on the RPC.
+/// `proceeds` are already known for paper sells. A sell whose proceeds can't
+/// be read isn't counted either way.
+fn record_outcome(
+    client: &PumpPortalClient,
+    cooldowns: &Cooldowns,
+    notifier: &Arc<dyn Notifier>,
+    rpc_url: &str,
+    sell: Notification,
+    cost_sol: f64,
+    proceeds: Option<Proceeds>,
+) {
+    let (client, cooldowns, notifier) = (client.clone(), cooldowns.clone(), notifier.clone());
+    let rpc_url = rpc_url.to_string();
+    tokio::spawn(async move {
+        let mint = sell.mint.clone();
+        let signature = sell.signature.clone().unwrap_or_default();
+        let proceeds = match proceeds {
+            Some(proceeds) => Ok(proceeds),
+            None => client.proceeds(&signature, &mint, &rpc_url, PROCEEDS_TIMEOUT).await,
+        };
+        let proceeds = match proceeds {
+            Ok(proceeds) => proceeds,
+            Err(e) => {
+                warn!("   ⚠️  No proceeds for sell of {}: {}", mint, e);
+                notifier.notify(sell);
+                return;
+            }
+        };
+
+        // The sold tokens at cost, marked at the price they actually fetched
+        let mut pnl = PnlTracker::new(cost_sol / proceeds.tokens_sold, cost_sol);
+        pnl.update(proceeds.price());
+        let pnl_sol = pnl.unrealized_sol();
+        tracing::info!(event = "exit_pnl", mint = %mint, pnl_sol, pnl_percent = pnl.percent(),
+                       "💰 {} exit: {:+.4} SOL ({:+.1}%)", &mint[..8], pnl_sol, pnl.percent());
+        notifier.notify(sell.with_sol(proceeds.sol_received).with_pnl(pnl.percent()));
+
+        let mut cooldowns = cooldowns.lock().await;
+        if let Some(pause) = cooldowns.losses.record(pnl_sol >= 0.0) {
+            tracing::warn!(event = "loss_streak", mint = %mint, pnl_sol, losses = cooldowns.losses.losses(),
+                           cooldown_secs = pause.as_secs(),
+                           "🧊 {} losing exits in a row, pausing buys for {}s", cooldowns.losses.losses(), pause.as_secs());
+        }
+    });
+}
+
+/// How long to wait for a buy to show up on-chain when reading its fill
+const FILL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long to wait for a sell to show up on-chain when scoring it
+const PROCEEDS_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long each sell gets on Ctrl+C before the bot exits anyway
+const EXIT_SELL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Exit after this many seconds when momentum exits are disabled
+const TIME_EXIT_SECS: u64 = 10;
+
+/// Monitor exit mode: DexScreener momentum, or a pluggable strategy fed DexScreener prices
+enum ExitMode {
+    Momentum,
+    Strategy(Box<dyn ExitStrategy>),
+}
+
+/// Sell every open position in full, giving each sell `EXIT_SELL_TIMEOUT`
+async fn sell_all_on_exit(
+    client: &PumpPortalClient,
+    positions: &Positions,
+    cooldowns: &Cooldowns,
+    notifier: &Arc<dyn Notifier>,
+    rpc_url: &str,
+    slippage: u32,
+    paper_sim: &Option<Arc<PaperTradingSimulator>>,
+) {
+    let marks: Vec<(String, Option<f64>)> =
+        positions.lock().await.values().map(|pos| (pos.mint.clone(), pos.last_price_sol)).collect();
+    if marks.is_empty() {
+        info!("💼 No open positions to sell");
+        return;
+    }
+
+    warn!("🚨 Selling {} open positions before exit...", marks.len());
+    let sells = marks.iter().map(|(mint, price_sol)| async move {
+        let sell = execute_sell(client, mint, 100, slippage, *price_sol, paper_sim, ExitReason::Shutdown);
+        let result = match tokio::time::timeout(EXIT_SELL_TIMEOUT, sell).await {
+            Ok(result) => result,
+            Err(_) => Err(anyhow::anyhow!("timed out after {}s", EXIT_SELL_TIMEOUT.as_secs())),
+        };
+        (mint, result)
+    });
+
+    let mut sold = 0;
+    for (mint, result) in futures_util::future::join_all(sells).await {
+        match result {
+            Ok((sig, proceeds)) => {
+                tracing::info!(event = "sell", mint = %mint, percent = 100, slippage,
+                               signature = %sig, reason = %ExitReason::Shutdown, "✅ SOLD");
+                let sell = Notification::sell(mint, &sig, ExitReason::Shutdown);
+                close_position(client, positions, cooldowns, notifier, rpc_url, sell, proceeds).await;
+                sold += 1;
+            }
+            Err(e) => tracing::error!(event = "sell_failed", mint = %mint, percent = 100,
+                                      error = %e, "❌ Sell failed"),
+        }
+    }
+
+    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    info!("🏁 Exit sell-off: {}/{} positions sold", sold, marks.len());
+    for mint in positions.lock().await.keys() {
+        warn!("   ⚠️  Still holding {}", mint);
+    }
 }
 
-async fn monitor_positions_loop(client: Arc<PumpPortalClient>, positions: Positions, snipe_amount: f64) {
-    info!("👀 Momentum-based position monitor started");
-    info!("   Strategy: HOLD winners as long as they pump\n");
-    info!("   Buy-into-strength: Enabled (add up to 3x on strong momentum)\n");
+async fn monitor_positions_loop(
+    client: Arc<PumpPortalClient>,
+    positions: Positions,
+    cooldowns: Cooldowns,
+    config: Arc<BotConfig>,
+    notifier: Arc<dyn Notifier>,
+    exit_mode: ExitMode,
+    paper_sim: Option<Arc<PaperTradingSimulator>>,
+) {
+    let (max_hold_secs, sell_slippage) = (config.max_hold_secs, config.sell_slippage);
+    info!("👀 Position monitor started");
+    match &exit_mode {
+        ExitMode::Momentum => {
+            info!("   Strategy: HOLD winners as long as they pump");
+            info!("   Buy-into-strength: Enabled (add up to 3x on strong momentum)\n");
+        }
+        ExitMode::Strategy(strategy) => {
+            info!("   Strategy: {} exit (MOMENTUM_EXITS=false)\n", strategy.name());
+        }
+    }
 
     loop {
         sleep(Duration::from_secs(3)).await; // Check every 3s for faster exits
@@ -212,115 +496,242 @@ async fn monitor_positions_loop(client: Arc<PumpPortalClient>, positions: Positi
         if !positions_snapshot.is_empty() {
             info!("📊 Checking {} positions...", positions_snapshot.len());
         }
+        // One batched DexScreener call for every position instead of one each
+        if !positions_snapshot.is_empty() {
+            let mints: Vec<String> = positions_snapshot.iter().map(|p| p.mint.clone()).collect();
+            if let Err(e) = momentum_tracker::global().prefetch(&mints).await {
+                warn!("   ⚠️  DexScreener prefetch failed: {}", e);
+            }
+        }
 
         for position in positions_snapshot {
             let elapsed = position.entry_time.elapsed().as_secs();
 
-            // SIMPLE TIME-BASED EXIT (10s for all tokens)
-            if elapsed > 10 {
-                info!("   ⏰ 10s elapsed - EXITING");
-                match execute_sell(&client, &position.mint).await {
-                    Ok(sig) => {
-                        info!("   ✅ SOLD - {}", sig);
-                        positions.lock().await.remove(&position.mint);
-                        continue;
+            // Hard stop runs first: momentum checks that keep failing never reach an exit
+            if let Some(ExitDecision::Sell { reason, .. }) = exit::hard_time_stop(elapsed, max_hold_secs) {
+                warn!("   🛑 Hard time stop: {} held {}s (max {}s)", &position.mint[..8], elapsed, max_hold_secs);
+                match execute_sell(&client, &position.mint, 100, sell_slippage, position.last_price_sol, &paper_sim, reason).await {
+                    Ok((sig, proceeds)) => {
+                        tracing::info!(event = "sell", mint = %position.mint, percent = 100, slippage = sell_slippage,
+                                       signature = %sig, reason = %reason, "✅ SOLD");
+                        let sell = Notification::sell(&position.mint, &sig, reason);
+                        close_position(&client, &positions, &cooldowns, &notifier, &config.rpc_url, sell, proceeds).await;
                     }
-                    Err(e) => error!("   ❌ Sell failed: {}", e),
+                    Err(e) => tracing::error!(event = "sell_failed", mint = %position.mint, percent = 100,
+                                              error = %e, "❌ Sell failed"),
                 }
+                continue;
             }
 
-            // SKIP momentum check for now - using simple time exits
-            if false {
-            match check_momentum(&position.mint, position.entry_price_usd).await {
-                Ok(momentum) => {
-                    let social_info = if position.fast_exit {
-                        " | ⚠️  ZERO SOCIALS".to_string()
-                    } else if let Some(ref s) = position.social_score {
-                        format!(" | social: {:.2}", s.momentum_score)
-                    } else {
-                        "".to_string()
-                    };
-
-                    info!("   {} - {}s | P&L: {:+.1}% | momentum: {:.2} | vol: ${:.0}{}",
-                          &position.mint[..8],
-                          elapsed,
-                          momentum.pnl_percent,
-                          momentum.momentum_score,
-                          momentum.volume_24h,
-                          social_info);
-
-                    // BUY INTO STRENGTH: Add to winners
-                    let time_since_last_add = position.last_add_time.elapsed().as_secs();
-                    if momentum.momentum_score > 0.7
-                        && momentum.pnl_percent > 20.0
-                        && position.add_count < 3
-                        && time_since_last_add > 30
-                        && !position.fast_exit
-                    {
-                        info!("   🚀 STRONG MOMENTUM DETECTED! Adding to position...");
-                        match execute_buy(&client, &position.mint, snipe_amount, position.risk_score).await {
+            let strategy = match &exit_mode {
+                ExitMode::Momentum => {
+                    manage_momentum(&client, &positions, &cooldowns, &notifier, &position, &config, &paper_sim).await;
+                    continue;
+                }
+                ExitMode::Strategy(strategy) => strategy,
+            };
+
+            let (state, price_sol) = strategy_state(&positions, &position, elapsed).await;
+            if let ExitDecision::Sell { percent, reason, detail } = strategy.evaluate(&state) {
+                info!("   ⏰ {}, selling {}%...", detail, percent);
+                match execute_sell(&client, &position.mint, percent, sell_slippage, price_sol, &paper_sim, reason).await {
+                    Ok((sig, proceeds)) => {
+                        tracing::info!(event = "sell", mint = %position.mint, percent, slippage = sell_slippage,
+                                       signature = %sig, reason = %reason, "✅ SOLD");
+                        let sell = Notification::sell(&position.mint, &sig, reason);
+                        if percent >= 100 {
+                            close_position(&client, &positions, &cooldowns, &notifier, &config.rpc_url, sell, proceeds).await;
+                        } else {
+                            let sold_cost = position.pnl.size_sol() * f64::from(percent) / 100.0;
+                            record_outcome(&client, &cooldowns, &notifier, &config.rpc_url, sell, sold_cost, proceeds);
+                            if let Some(pos) = positions.lock().await.get_mut(&position.mint) {
+                                pos.exits_taken += 1;
+                                pos.pnl.reduce(percent);
+                            }
+                        }
+                    }
+                    Err(e) => tracing::error!(event = "sell_failed", mint = %position.mint, percent,
+                                              error = %e, "❌ Sell failed"),
+                }
+            }
+        }
+    }
+}
+
+/// What an exit strategy sees of `position`, marked at DexScreener's price,
+/// and that price in SOL per token for a paper sell to fill at
+///
+/// If the lookup fails the last marked prices stand and momentum reads neutral.
+async fn strategy_state(positions: &Positions, position: &Position, elapsed: u64) -> (PositionState, Option<f64>) {
+    let mut pnl = position.pnl.clone();
+    let mut price_sol = position.last_price_sol;
+    let mut momentum = 0.5;
+    match check_momentum(&position.mint).await {
+        Ok(data) => {
+            pnl.update(data.current_price_usd);
+            price_sol = data.current_price_sol.or(price_sol);
+            momentum = data.strength();
+            // Stored too, so the peak carries over to the next check
+            if let Some(pos) = positions.lock().await.get_mut(&position.mint) {
+                pos.pnl = pnl.clone();
+                pos.last_price_sol = price_sol;
+            }
+        }
+        Err(e) => warn!("   ⚠️  No DexScreener price for {}, using the last one: {}", &position.mint[..8], e),
+    }
+    info!("   {} - {}s | P&L: {:+.1}% (peak {:.2}x) | momentum: {:.2}",
+          &position.mint[..8], elapsed, pnl.percent(), pnl.peak_multiplier(), momentum);
+
+    let state = PositionState {
+        pnl_multiplier: pnl.multiplier(),
+        peak_multiplier: pnl.peak_multiplier(),
+        momentum,
+        exits_taken: position.exits_taken,
+        ..PositionState::at(elapsed)
+    };
+    (state, price_sol)
+}
+
+/// Momentum-driven management for one position: add into strength, exit when momentum dies
+async fn manage_momentum(
+    client: &PumpPortalClient,
+    positions: &Positions,
+    cooldowns: &Cooldowns,
+    notifier: &Arc<dyn Notifier>,
+    position: &Position,
+    config: &BotConfig,
+    paper_sim: &Option<Arc<PaperTradingSimulator>>,
+) {
+    let (snipe_amount, sell_slippage) = (config.snipe_amount_sol, config.sell_slippage);
+    let elapsed = position.entry_time.elapsed().as_secs();
+    match check_momentum(&position.mint).await {
+        Ok(momentum) => {
+            // Mark the stored position too, so the peak carries over to the next check
+            let mut pnl = position.pnl.clone();
+            pnl.update(momentum.current_price_usd);
+            if let Some(pos) = positions.lock().await.get_mut(&position.mint) {
+                pos.pnl = pnl.clone();
+                pos.last_price_sol = momentum.current_price_sol.or(pos.last_price_sol);
+            }
+
+            let social_info = if position.fast_exit {
+                " | ⚠️  ZERO SOCIALS".to_string()
+            } else if let Some(ref s) = position.social_score {
+                format!(" | social: {:.2}", s.momentum_score)
+            } else {
+                "".to_string()
+            };
+
+            info!("   {} - {}s | P&L: {:+.1}% ({:+.4} SOL) | momentum: {:.2} | vol: ${:.0}{}",
+                  &position.mint[..8],
+                  elapsed,
+                  pnl.percent(),
+                  pnl.unrealized_sol(),
+                  momentum.momentum_score,
+                  momentum.volume_24h,
+                  social_info);
+
+            // BUY INTO STRENGTH: Add to winners
+            let time_since_last_add = position.last_add_time.elapsed().as_secs();
+            if momentum.momentum_score > 0.7
+                && pnl.percent() > 20.0
+                && position.add_count < 3
+                && time_since_last_add > 30
+                && !position.fast_exit
+            {
+                info!("   🚀 STRONG MOMENTUM DETECTED! Adding to position...");
+                let (budget, adaptive_slippage) = {
+                    let cooldowns = cooldowns.lock().await;
+                    (cooldowns.budget.clone(), cooldowns.slippage.clone())
+                };
+                match budget.reserve(sol_at_risk(&*positions.lock().await), snipe_amount) {
+                    Err(left) => tracing::warn!(event = "skip", mint = %position.mint, reason = "max_sol_at_risk",
+                                                budget_left_sol = left, "💰 {} SOL left under the cap, not adding", left),
+                    Ok(reservation) => {
+                        let base = adaptive_slippage.as_ref().map_or(config.buy_slippage, AdaptiveSlippage::current);
+                        let slippage = buy_slippage(base, position.risk_score);
+                        let result = execute_buy(client, &position.mint, snipe_amount, slippage,
+                                                 momentum.current_price_sol, paper_sim).await;
+                        record_buy(adaptive_slippage.as_ref(), client, &config.rpc_url, &result);
+                        match result {
                             Ok(add_sig) => {
-                                info!("   ✅ ADDED {} SOL (add #{}) - {}",
-                                      snipe_amount, position.add_count + 1, add_sig);
+                                tracing::info!(event = "buy", mint = %position.mint, sol = snipe_amount,
+                                               slippage, signature = %add_sig,
+                                               add = position.add_count + 1, "✅ ADDED TO POSITION");
+                                notifier.notify(Notification::buy(&position.mint, snipe_amount, &add_sig));
                                 // Update position
                                 let mut locked_positions = positions.lock().await;
                                 if let Some(pos) = locked_positions.get_mut(&position.mint) {
-                                    pos.entry_sol += snipe_amount;
+                                    pos.pnl.add(snipe_amount, momentum.current_price_usd);
                                     pos.add_count += 1;
                                     pos.last_add_time = std::time::Instant::now();
                                 }
+                                // Released with the add in place, so the cap never misses it
+                                drop(reservation);
                             }
-                            Err(e) => error!("   ❌ Add failed: {}", e),
+                            Err(e) => tracing::error!(event = "buy_failed", mint = %position.mint, sol = snipe_amount,
+                                                      error = %e, "❌ Add failed"),
                         }
                     }
+                }
+            }
 
-                    // EXIT CONDITIONS (momentum-based, NOT time-based):
-                    let should_exit = if !momentum.should_hold {
-                        // Momentum tracker says exit
-                        info!("   📉 Momentum died → EXIT");
-                        true
-                    } else if position.fast_exit && momentum.pnl_percent < -10.0 {
-                        // Fast exit for zero-social tokens if losing >10%
-                        warn!("   🚨 Zero socials + losing → EXIT");
-                        true
-                    } else if momentum.pnl_percent > 200.0 && momentum.momentum_score < 0.0 {
-                        // Secure 3x gains if momentum turns negative
-                        info!("   💰 3x gains + negative momentum → SECURE PROFITS");
-                        true
-                    } else if momentum.pnl_percent > 500.0 && momentum.momentum_score < 0.3 {
-                        // Secure 6x gains if momentum weakening
-                        info!("   💎 6x gains + weak momentum → SECURE PROFITS");
-                        true
-                    } else {
-                        // KEEP HOLDING - momentum still strong
-                        false
-                    };
-
-                    if should_exit {
-                        match execute_sell(&client, &position.mint).await {
-                            Ok(sig) => {
-                                info!("   ✅ SOLD at {:+.1}% P&L", momentum.pnl_percent);
-                                info!("   Signature: {}", sig);
-                                positions.lock().await.remove(&position.mint);
-                            }
-                            Err(e) => error!("   ❌ Sell failed: {}", e),
-                        }
+            // EXIT CONDITIONS (momentum-based, NOT time-based):
+            let exit_reason = if !momentum.should_hold(&pnl) {
+                // Momentum tracker says exit
+                info!("   📉 Momentum died → EXIT");
+                Some(ExitReason::MomentumDied)
+            } else if position.fast_exit && pnl.percent() < -10.0 {
+                // Fast exit for zero-social tokens if losing >10%
+                warn!("   🚨 Zero socials + losing → EXIT");
+                Some(ExitReason::ZeroSocials)
+            } else if pnl.percent() > 200.0 && momentum.momentum_score < 0.0 {
+                // Secure 3x gains if momentum turns negative
+                info!("   💰 3x gains + negative momentum → SECURE PROFITS");
+                Some(ExitReason::TakeProfit)
+            } else if pnl.percent() > 500.0 && momentum.momentum_score < 0.3 {
+                // Secure 6x gains if momentum weakening
+                info!("   💎 6x gains + weak momentum → SECURE PROFITS");
+                Some(ExitReason::TakeProfit)
+            } else {
+                // KEEP HOLDING - momentum still strong
+                None
+            };
+
+            if let Some(reason) = exit_reason {
+                match execute_sell(client, &position.mint, 100, sell_slippage, momentum.current_price_sol, paper_sim, reason).await {
+                    Ok((sig, proceeds)) => {
+                        tracing::info!(event = "sell", mint = %position.mint, percent = 100, slippage = sell_slippage,
+                                       signature = %sig, reason = %reason, pnl_percent = pnl.percent(), "✅ SOLD");
+                        let sell = Notification::sell(&position.mint, &sig, reason);
+                        close_position(client, positions, cooldowns, notifier, &config.rpc_url, sell, proceeds).await;
                     }
+                    Err(e) => tracing::error!(event = "sell_failed", mint = %position.mint, percent = 100,
+                                              error = %e, "❌ Sell failed"),
                 }
-                Err(e) => {
-                    // NO DATA = DUMP IMMEDIATELY
-                    warn!("   ⚠️  Momentum check failed: {} (DUMPING)", e);
-                    if elapsed > 3 {
-                        warn!("   🚨 NO PRICE DATA - EMERGENCY DUMP");
-                        match execute_sell(&client, &position.mint).await {
-                            Ok(sig) => {
-                                info!("   ✅ DUMPED (no data) - {}", sig);
-                                positions.lock().await.remove(&position.mint);
-                            }
-                            Err(e) => error!("   ❌ Dump failed: {}", e),
-                        }
-                    }
+            }
+        }
+        // Being throttled says nothing about the token; check again next round
+        Err(MomentumError::RateLimited(wait)) => {
+            warn!("   ⏳ {} - DexScreener rate limited, holding (retry in {:?})", &position.mint[..8], wait);
+        }
+        Err(e) => {
+            let had_price = position.pnl.entry_price().is_some();
+            let Some(reason) = e.exit_reason(had_price, elapsed, config.no_price_grace_secs) else {
+                info!("   ⏳ {} - {}s, no price yet ({}), waiting up to {}s",
+                      &position.mint[..8], elapsed, e, config.no_price_grace_secs);
+                return;
+            };
+            warn!("   🚨 {} - {} ({}) - EMERGENCY DUMP", &position.mint[..8], reason, e);
+            match execute_sell(client, &position.mint, 100, sell_slippage, position.last_price_sol, paper_sim, reason).await {
+                Ok((sig, proceeds)) => {
+                    tracing::info!(event = "sell", mint = %position.mint, percent = 100, slippage = sell_slippage,
+                                   signature = %sig, reason = %reason, "✅ DUMPED");
+                    let sell = Notification::sell(&position.mint, &sig, reason);
+                    close_position(client, positions, cooldowns, notifier, &config.rpc_url, sell, proceeds).await;
                 }
+                Err(e) => tracing::error!(event = "sell_failed", mint = %position.mint, percent = 100,
+                                          error = %e, "❌ Dump failed"),
             }
         }
     }
@@ -328,48 +739,113 @@ async fn monitor_positions_loop(client: Arc<PumpPortalClient>, positions: Positi
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    env_logger::init();
+    pump_portal_sdk::logging::init();
     dotenv().ok();
 
     info!("🧠 SMART SNIPER BOT - AI-Powered Trading");
     info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
 
     let api_key = env::var("PUMPPORTAL_API_KEY").expect("PUMPPORTAL_API_KEY required");
-    let snipe_amount = env::var("SNIPE_AMOUNT_SOL")
-        .unwrap_or_else(|_| "0.05".to_string())
-        .parse::<f64>()?;
-    let max_positions = env::var("MAX_POSITIONS")
-        .unwrap_or_else(|_| "999".to_string())
-        .parse::<usize>()?;
-
-    info!("💰 Config:");
-    info!("   Snipe Amount: {} SOL per trade", snipe_amount);
+    // config.toml / config.json if present, else env vars
+    let config = Arc::new(BotConfig::load(BotConfig {
+        max_positions: 999,
+        ..BotConfig::default()
+    })?);
+    let use_dexscreener = env::var("SOCIALS_DEXSCREENER")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
+    // How often each position's DexScreener price is refreshed, and the request budget for it
+    let dexscreener_poll = env::var("DEXSCREENER_POLL_MS")
+        .ok()
+        .map(|ms| ms.parse::<u64>().map(Duration::from_millis))
+        .transpose()?
+        .unwrap_or(momentum_tracker::DEFAULT_POLL_INTERVAL);
+    let dexscreener_rate = env::var("DEXSCREENER_MAX_REQUESTS_PER_SEC")
+        .ok()
+        .map(|rate| rate.parse::<f64>())
+        .transpose()?
+        .filter(|rate| *rate > 0.0)
+        .unwrap_or(momentum_tracker::DEFAULT_MAX_REQUESTS_PER_SEC);
+    momentum_tracker::configure(dexscreener_poll, dexscreener_rate);
+
+    // Buy size range scaled by launch confidence; both default to snipe_amount_sol (fixed size)
+    let sizer = PositionSizer::new(
+        config.min_snipe_sol.unwrap_or(config.snipe_amount_sol),
+        config.max_snipe_sol.unwrap_or(config.snipe_amount_sol),
+    );
+    let max_positions = config.max_positions;
+
+    config.display();
+    if !sizer.is_fixed() {
+        info!("   Buys: {}-{} SOL scaled by risk + social score, adds use {} SOL",
+              sizer.min_sol(), sizer.max_sol(), config.snipe_amount_sol);
+    }
     if max_positions >= 999 {
         info!("   Max Positions: UNLIMITED (rapid fire)");
+    }
+    let exit_mode = if config.momentum_exits {
+        ExitMode::Momentum
     } else {
-        info!("   Max Positions: {}", max_positions);
+        match &config.exit_strategy {
+            Some(name) => ExitMode::Strategy(exit::by_name(name)?),
+            None => ExitMode::Strategy(Box::new(exit::TimeBased::new(TIME_EXIT_SECS))),
+        }
+    };
+    match &exit_mode {
+        ExitMode::Momentum => info!(
+            "   Strategy: AI-filtered launches → Momentum exits (DexScreener every {:?}, max {} req/s)",
+            dexscreener_poll,
+            dexscreener_rate
+        ),
+        ExitMode::Strategy(strategy) => {
+            info!("   Strategy: AI-filtered launches → {} exits", strategy.name())
+        }
+    }
+    if matches!(exit_mode, ExitMode::Momentum) {
+        info!("   No-Price Grace: {}s before an unindexed token is sold", config.no_price_grace_secs);
     }
-    info!("   Strategy: AI-filtered launches → Momentum exits");
-    info!("   Risk Threshold: 0.6 minimum\n");
+    info!("   Risk Threshold: {} minimum", MIN_RISK_SCORE);
+    info!("   Socials: token metadata{}\n", if use_dexscreener { " + DexScreener" } else { "" });
 
-    let client = Arc::new(PumpPortalClient::new(api_key));
+    let social_checker = SocialChecker::new()?.with_dexscreener(use_dexscreener);
+    // A buy resent after an ambiguous failure must not double the position
+    let mut client = PumpPortalClient::new(api_key).with_buy_dedup(DEFAULT_BUY_DEDUP_WINDOW);
+    if let Some(max) = config.max_slippage {
+        client = client.with_slippage_retry(max);
+    }
+    let client = Arc::new(client);
+    let notifier = config.notifier("smart-sniper");
+    // Paper trading with DuckDB export for research (PAPER_MODE or --paper)
+    let paper_sim = config.paper_simulator();
     let positions: Positions = Arc::new(Mutex::new(HashMap::new()));
+    // Buys past the slot check but not yet in `positions`
+    let pending_buys = Arc::new(AtomicUsize::new(0));
+    let budget = config.sol_budget();
+    // Live buys only: paper buys never fail on slippage
+    let adaptive_slippage = config.adaptive_slippage().filter(|_| paper_sim.is_none());
+    let cooldowns: Cooldowns = Arc::new(Mutex::new(BuyCooldowns {
+        mints: MintCooldown::new(Duration::from_secs(config.mint_cooldown_secs)),
+        losses: LossStreak::new(config.loss_streak_limit, Duration::from_secs(config.loss_cooldown_secs)),
+        budget: budget.clone(),
+        slippage: adaptive_slippage.clone(),
+    }));
 
     // Start position monitor
     let monitor_client = client.clone();
     let monitor_positions = positions.clone();
+    let monitor_cooldowns = cooldowns.clone();
+    let monitor_config = config.clone();
+    let monitor_notifier = notifier.clone();
+    let monitor_paper_sim = paper_sim.clone();
     tokio::spawn(async move {
-        monitor_positions_loop(monitor_client, monitor_positions, snipe_amount).await;
+        monitor_positions_loop(monitor_client, monitor_positions, monitor_cooldowns, monitor_config, monitor_notifier, exit_mode, monitor_paper_sim).await;
     });
 
     // Connect to WebSocket
-    let ws_url = "wss://pumpportal.fun/api/data";
     info!("📡 Connecting to PumpPortal WebSocket...");
-    let (ws_stream, _) = connect_async(ws_url).await?;
-    let (mut write, mut read) = ws_stream.split();
+    let mut stream = PumpPortalStream::connect(config.stream_config()).await?;
 
-    let subscribe_msg = serde_json::json!({"method": "subscribeNewToken"});
-    write.send(Message::Text(subscribe_msg.to_string())).await?;
+    stream.subscribe(&Subscription::NewTokens);
     info!("✅ Subscribed to new token launches\n");
     info!("🎯 ANALYZING LAUNCHES... Press Ctrl+C to stop\n");
 
@@ -377,122 +853,225 @@ async fn main() -> Result<()> {
     let mut total_filtered = 0u64;
     let mut total_bought = 0u64;
 
-    while let Some(msg) = read.next().await {
-        match msg {
-            Ok(Message::Text(text)) => {
-                if let Ok(event) = serde_json::from_str::<TokenCreatedEvent>(&text) {
-                    if let Some(mint) = event.mint {
-                        total_detected += 1;
-
-                        info!("🔔 NEW LAUNCH DETECTED! (#{} total)", total_detected);
-                        info!("   Mint: {}", mint);
-                        if let Some(ref name) = event.name {
-                            info!("   Name: {}", name);
-                        }
-                        if let Some(ref symbol) = event.symbol {
-                            info!("   Symbol: {}", symbol);
-                        }
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    loop {
+        // The stream reconnects itself on drops and stalls
+        let text = tokio::select! {
+            text = stream.next() => text,
+            _ = &mut ctrl_c => {
+                warn!("🛑 Ctrl+C received, shutting down");
+                break;
+            }
+        };
+
+        if let Ok(PumpPortalEvent::NewToken(event)) = serde_json::from_str::<PumpPortalEvent>(&text) {
+            let launch_curve = event.curve();
+            let thin_reserves = event.thin_reserves(config.min_sol_reserves);
+            let mint = event.mint;
+            total_detected += 1;
+
+            info!("🔔 NEW LAUNCH DETECTED! (#{} total)", total_detected);
+            info!("   Mint: {}", mint);
+            if !event.name.is_empty() {
+                info!("   Name: {}", event.name);
+            }
+            if !event.symbol.is_empty() {
+                info!("   Symbol: {}", event.symbol);
+            }
+
+            if let Some(reserves) = thin_reserves {
+                tracing::info!(event = "skip", mint = %mint, reason = "low_liquidity", sol_reserves = reserves,
+                               "💧 Only {:.2} SOL in the curve (min {:.2}), skipping\n", reserves, config.min_sol_reserves);
+                total_filtered += 1;
+                continue;
+            }
+
+            // Don't re-buy a mint we just exited
+            if let Some(left) = cooldowns.lock().await.losses.remaining() {
+                tracing::info!(event = "skip", mint = %mint, reason = "loss_streak", cooldown_left_secs = left.as_secs(),
+                               "🧊 Paused after a losing streak ({}s left), skipping\n", left.as_secs());
+                total_filtered += 1;
+                continue;
+            }
+            if let Some(left) = cooldowns.lock().await.mints.remaining(&mint) {
+                tracing::info!(event = "skip", mint = %mint, reason = "cooldown", cooldown_left_secs = left.as_secs(),
+                               "⏳ {} exited recently, cooling down ({}s left), skipping\n", mint, left.as_secs());
+                total_filtered += 1;
+                continue;
+            }
+
+            // Check if we can buy, and hold the slot until the buy fills it or fails
+            let mut slot = match claim_slot(&positions, &pending_buys, &mint, max_positions).await {
+                Ok(slot) => slot,
+                Err("holding") => {
+                    tracing::info!(event = "skip", mint = %mint, reason = "holding", "⏭️  Already holding {}, skipping\n", mint);
+                    continue;
+                }
+                Err(reason) => {
+                    tracing::warn!(event = "skip", mint = %mint, reason,
+                                   "⏸️  Max positions ({}) reached, skipping\n", max_positions);
+                    continue;
+                }
+            };
 
-                        // Check if we can buy
-                        let current_positions = positions.lock().await.len();
-                        if current_positions >= max_positions {
-                            warn!("⏸️  Max positions ({}) reached, skipping\n", max_positions);
+            // Off-chain metadata (description, image, socials) behind the event's URI
+            let metadata = match event.uri.as_deref().filter(|uri| !uri.is_empty()) {
+                Some(uri) => match fetch_token_metadata(uri).await {
+                    Ok(metadata) => Some(metadata),
+                    Err(e) => {
+                        warn!("   ⚠️  Failed to fetch token metadata: {}", e);
+                        None
+                    }
+                },
+                None => None,
+            };
+
+            // CHECK SOCIALS FIRST - BEFORE BUYING!
+            info!("🔍 Checking socials BEFORE buying...");
+            let social_check = social_checker.check_social_momentum(&mint, metadata.as_ref()).await;
+            if let Ok(score) = &social_check {
+                if score.should_fast_exit {
+                    tracing::warn!(event = "skip", mint = %mint, reason = "zero_socials", "❌ ZERO SOCIALS - SKIPPING\n");
+                    total_filtered += 1;
+                    continue;
+                } else {
+                    info!("   ✅ Has socials (score: {:.2})", score.momentum_score);
+                }
+            }
+
+            // AI-powered analysis
+            match analyze_token(&mint, &event.name, &event.symbol, metadata.as_ref()).await {
+                Ok((should_buy, risk_score)) => {
+                    if should_buy {
+                        let buy_amount = sizer.size(risk_score, social_check.as_ref().ok());
+                        if let Err(left) = reserve_sol(&positions, &budget, &mut slot, buy_amount).await {
+                            tracing::warn!(event = "skip", mint = %mint, reason = "max_sol_at_risk", budget_left_sol = left,
+                                           "💰 {} SOL left under the {} SOL cap, skipping {} SOL buy\n",
+                                           left, budget.max_sol().unwrap_or_default(), buy_amount);
                             continue;
                         }
-
-                        // CHECK SOCIALS FIRST - BEFORE BUYING!
-                        info!("🔍 Checking socials BEFORE buying...");
-                        let social_check = check_social_momentum(&mint).await;
-                        if let Ok(score) = &social_check {
-                            if score.should_fast_exit {
-                                warn!("   ❌ ZERO SOCIALS - SKIPPING\n");
-                                total_filtered += 1;
-                                continue;
-                            } else {
-                                info!("   ✅ Has socials (score: {:.2})", score.momentum_score);
-                            }
+                        let base = adaptive_slippage.as_ref().map_or(config.buy_slippage, AdaptiveSlippage::current);
+                        let slippage = buy_slippage(base, risk_score);
+                        // What the launch curve says the buy gets; paper buys fill at its price
+                        let quote = launch_curve.and_then(|curve| Quote::from_curve(&mint, buy_amount, Pool::Pump, &curve).ok());
+                        if let Some(quote) = &quote {
+                            info!("   Quote: {:.0} tokens at {:.10} SOL each ({:.0} bps impact)",
+                                  quote.expected_tokens, quote.effective_price, quote.price_impact_bps);
                         }
+                        // Execute buy
+                        let result = execute_buy(&client, &mint, buy_amount, slippage,
+                                                 quote.as_ref().map(Quote::curve_price), &paper_sim).await;
+                        record_buy(adaptive_slippage.as_ref(), &client, &config.rpc_url, &result);
+                        match result {
+                            Ok(signature) => {
+                                total_bought += 1;
+                                tracing::info!(event = "buy", mint = %mint, sol = buy_amount,
+                                               slippage, signature = %signature,
+                                               risk_score, "✅ BUY EXECUTED");
+                                notifier.notify(Notification::buy(&mint, buy_amount, &signature));
 
-                        // AI-powered analysis
-                        match analyze_token(&mint, event.name, event.symbol).await {
-                            Ok((should_buy, risk_score)) => {
-                                if should_buy {
-                                    // Execute buy
-                                    match execute_buy(&client, &mint, snipe_amount, risk_score).await {
-                                        Ok(signature) => {
-                                            total_bought += 1;
-                                            info!("✅ BUY EXECUTED!");
-                                            info!("   Signature: {}", signature);
-                                            info!("   Amount: {} SOL", snipe_amount);
-                                            info!("   Risk Score: {:.2}", risk_score);
-
-                                            // IMMEDIATELY check socials after buying
-                                            let social_result = check_social_momentum(&mint).await;
-                                            let (social_score_opt, fast_exit) = match social_result {
-                                                Ok(score) => {
-                                                    let fast = score.should_fast_exit;
-                                                    if fast {
-                                                        warn!("   🚨 ZERO SOCIALS DETECTED - FAST EXIT IN 12s");
-                                                    } else {
-                                                        info!("   📊 Social momentum: {:.2}", score.momentum_score);
-                                                    }
-                                                    (Some(score), fast)
-                                                }
-                                                Err(e) => {
-                                                    warn!("   ⚠️  Social check failed: {}", e);
-                                                    (None, false)
-                                                }
-                                            };
-
-                                            // Get entry price (wait a moment for DexScreener to index)
-                                            sleep(Duration::from_secs(2)).await;
-                                            let entry_price_usd = match check_momentum(&mint, 0.0).await {
-                                                Ok(momentum_data) => momentum_data.current_price_usd,
-                                                Err(_) => 0.0001, // Default tiny price for new launches
-                                            };
-                                            info!("   Entry price: ${:.8}", entry_price_usd);
-
-                                            let now = std::time::Instant::now();
-                                            let position = Position {
-                                                mint: mint.clone(),
-                                                entry_sol: snipe_amount,
-                                                entry_price_usd,
-                                                entry_signature: signature,
-                                                entry_time: now,
-                                                risk_score,
-                                                social_score: social_score_opt,
-                                                fast_exit,
-                                                add_count: 0,
-                                                last_add_time: now,
-                                            };
-
-                                            positions.lock().await.insert(mint, position);
-                                            let current = positions.lock().await.len();
-                                            let remaining = max_positions.saturating_sub(current);
-                                            info!("💼 Positions: {}/{} ({}left)", current, max_positions, remaining);
-                                            info!("📊 Stats: {} detected | {} filtered | {} bought\n",
-                                                  total_detected, total_filtered, total_bought);
+                                // IMMEDIATELY check socials after buying
+                                let social_result = social_checker.check_social_momentum(&mint, metadata.as_ref()).await;
+                                let (social_score_opt, fast_exit) = match social_result {
+                                    Ok(score) => {
+                                        let fast = score.should_fast_exit;
+                                        if fast {
+                                            warn!("   🚨 ZERO SOCIALS DETECTED - FAST EXIT IN 12s");
+                                        } else {
+                                            info!("   📊 Social momentum: {:.2}", score.momentum_score);
                                         }
+                                        (Some(score), fast)
+                                    }
+                                    Err(e) => {
+                                        warn!("   ⚠️  Social check failed: {}", e);
+                                        (None, false)
+                                    }
+                                };
+
+                                // Entry price: what the buy actually paid in SOL, valued at
+                                // DexScreener's SOL/USD rate. Fall back to the quoted price,
+                                // which is all a paper buy has.
+                                let fill = match &paper_sim {
+                                    Some(_) => None,
+                                    None => match client.fill(&signature, &mint, &config.rpc_url, FILL_TIMEOUT).await {
+                                        Ok(fill) => Some(fill),
                                         Err(e) => {
-                                            error!("❌ Buy failed: {}\n", e);
+                                            warn!("   ⚠️  No fill for buy: {}", e);
+                                            None
                                         }
-                                    }
+                                    },
+                                };
+                                sleep(Duration::from_secs(2)).await; // Let DexScreener index
+                                let (entry_price_usd, last_price_sol) = match (fill, check_momentum(&mint).await) {
+                                    (Some(fill), Ok(momentum_data)) => match momentum_data.current_price_sol {
+                                        Some(price_sol) => (fill.price() * momentum_data.current_price_usd / price_sol, Some(price_sol)),
+                                        None => (momentum_data.current_price_usd, Some(fill.price())),
+                                    },
+                                    (None, Ok(momentum_data)) => (momentum_data.current_price_usd, momentum_data.current_price_sol),
+                                    // Not indexed yet: the first price the monitor sees becomes the entry
+                                    (fill, Err(_)) => (0.0, fill.map(|fill| fill.price())),
+                                };
+                                if entry_price_usd > 0.0 {
+                                    info!("   Entry price: ${:.8}", entry_price_usd);
                                 } else {
-                                    total_filtered += 1;
-                                    info!("📊 Stats: {} detected | {} filtered | {} bought\n",
-                                          total_detected, total_filtered, total_bought);
+                                    info!("   Entry price: pending DexScreener");
                                 }
+
+                                let now = std::time::Instant::now();
+                                let position = Position {
+                                    mint: mint.clone(),
+                                    pnl: PnlTracker::new(entry_price_usd, buy_amount),
+                                    entry_time: now,
+                                    risk_score,
+                                    social_score: social_score_opt,
+                                    fast_exit,
+                                    add_count: 0,
+                                    last_add_time: now,
+                                    exits_taken: 0,
+                                    last_price_sol,
+                                };
+
+                                let (current, at_risk) = fill_slot(&positions, slot, position).await;
+                                let remaining = max_positions.saturating_sub(current);
+                                info!("💼 Positions: {}/{} ({}left)", current, max_positions, remaining);
+                                if let Some(left) = budget.remaining(at_risk) {
+                                    info!("💰 At risk: {:.4} SOL ({:.4} SOL budget left)", at_risk, left);
+                                }
+                                info!("📊 Stats: {} detected | {} filtered | {} bought\n",
+                                      total_detected, total_filtered, total_bought);
                             }
                             Err(e) => {
-                                error!("❌ Analysis failed: {}\n", e);
+                                tracing::error!(event = "buy_failed", mint = %mint, sol = buy_amount,
+                                                error = %e, "❌ Buy failed\n");
                             }
                         }
+                    } else {
+                        total_filtered += 1;
+                        info!("📊 Stats: {} detected | {} filtered | {} bought\n",
+                              total_detected, total_filtered, total_bought);
                     }
                 }
+                Err(e) => {
+                    error!("❌ Analysis failed: {}\n", e);
+                }
             }
-            _ => {}
         }
     }
 
+    // Only Ctrl+C ends the loop
+    if config.sell_on_exit {
+        sell_all_on_exit(&client, &positions, &cooldowns, &notifier, &config.rpc_url, config.sell_slippage, &paper_sim).await;
+    } else {
+        let held = positions.lock().await.len();
+        warn!("💼 sell_on_exit is off, leaving {} positions open", held);
+    }
+    info!("📊 Stats: {} detected | {} filtered | {} bought",
+          total_detected, total_filtered, total_bought);
+    if let Some(sim) = &paper_sim {
+        sim.print_summary().await;
+    }
+
     Ok(())
 }
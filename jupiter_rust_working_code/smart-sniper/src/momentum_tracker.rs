@@ -0,0 +1,438 @@
+//! Price and momentum for open positions from DexScreener
+//!
+//! Every lookup goes through one shared [`DexScreener`] client: requests are
+//! rate limited, the monitor fetches all its mints in batched calls, and
+//! results are reused for the poll interval. A 429 backs the client off
+//! exponentially and comes back as [`MomentumError::RateLimited`], which
+//! callers should treat as "no new data yet", not as a reason to sell.
+
+use anyhow::anyhow;
+use log::{debug, warn};
+use pump_portal_sdk::{ExitReason, PnlTracker, RateLimiter};
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+const TOKENS_URL: &str = "https://api.dexscreener.com/latest/dex/tokens";
+
+/// Most token addresses DexScreener accepts in one request
+const MAX_BATCH: usize = 30;
+
+/// Default time a fetched price is reused before asking again
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Default request budget, under DexScreener's 300 requests a minute
+pub const DEFAULT_MAX_REQUESTS_PER_SEC: f64 = 4.0;
+
+/// First back-off after a 429, doubled on each one in a row
+const BACKOFF_BASE: Duration = Duration::from_secs(2);
+const BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+/// Momentum below this means the pump is over
+const HOLD_THRESHOLD: f64 = -0.1;
+
+/// Hard stop: exit regardless of momentum past this loss
+const STOP_LOSS_PERCENT: f64 = -30.0;
+
+#[derive(Debug, Clone)]
+pub struct MomentumData {
+    pub current_price_usd: f64,
+    pub current_price_sol: Option<f64>,
+    pub momentum_score: f64, // -1.0 = dumping, 0.0 = flat, 1.0 = ripping
+    pub volume_24h: f64,
+}
+
+impl MomentumData {
+    /// Keep holding while momentum holds up and the position is above the stop loss
+    pub fn should_hold(&self, pnl: &PnlTracker) -> bool {
+        self.momentum_score >= HOLD_THRESHOLD && pnl.percent() > STOP_LOSS_PERCENT
+    }
+
+    /// `momentum_score` on the exit strategies' 0.0-1.0 scale (0.5 = flat)
+    pub fn strength(&self) -> f64 {
+        ((self.momentum_score + 1.0) / 2.0).clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DexScreenerResponse {
+    #[serde(default)]
+    pairs: Option<Vec<DexScreenerPair>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DexScreenerPair {
+    #[serde(default)]
+    base_token: Option<BaseToken>,
+    price_usd: Option<String>,
+    #[serde(default)]
+    price_native: Option<String>,
+    #[serde(default)]
+    volume: Option<Volume>,
+    #[serde(default)]
+    price_change: Option<PriceChange>,
+    #[serde(default)]
+    txns: Option<Txns>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BaseToken {
+    address: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Volume {
+    #[serde(default)]
+    h24: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct PriceChange {
+    #[serde(default)]
+    m5: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Txns {
+    #[serde(default)]
+    m5: Option<TxnCount>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TxnCount {
+    #[serde(default)]
+    buys: u64,
+    #[serde(default)]
+    sells: u64,
+}
+
+/// Why a momentum check has no data
+#[derive(Debug)]
+pub enum MomentumError {
+    /// DexScreener rate limited us; ask again once this has passed
+    RateLimited(Duration),
+    /// Token not indexed on DexScreener yet
+    NotIndexed,
+    Failed(anyhow::Error),
+}
+
+impl fmt::Display for MomentumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MomentumError::RateLimited(wait) => write!(f, "DexScreener rate limited, retry in {:?}", wait),
+            MomentumError::NotIndexed => f.write_str("Token not indexed on DexScreener yet"),
+            MomentumError::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for MomentumError {}
+
+impl MomentumError {
+    /// Exit reason for a position with no momentum data, or None to wait for it
+    ///
+    /// A token that has never had a price is most likely not indexed yet, so
+    /// it gets `grace_secs` before it's sold. One that had a price and lost it
+    /// is sold right away. Rate limiting never sells.
+    pub fn exit_reason(&self, had_price: bool, elapsed_secs: u64, grace_secs: u64) -> Option<ExitReason> {
+        match self {
+            MomentumError::RateLimited(_) => None,
+            _ if !had_price && elapsed_secs < grace_secs => None,
+            MomentumError::NotIndexed if had_price => Some(ExitReason::PriceDataLost),
+            MomentumError::NotIndexed => Some(ExitReason::NeverIndexed),
+            MomentumError::Failed(_) => Some(ExitReason::NoPriceData),
+        }
+    }
+}
+
+impl From<reqwest::Error> for MomentumError {
+    fn from(e: reqwest::Error) -> Self {
+        MomentumError::Failed(e.into())
+    }
+}
+
+/// Consecutive 429s and when we may ask again
+#[derive(Debug, Default)]
+struct Backoff {
+    strikes: u32,
+    until: Option<Instant>,
+}
+
+/// Rate-limited, caching DexScreener client shared by every momentum check
+pub struct DexScreener {
+    client: Client,
+    limiter: RateLimiter,
+    poll_interval: Duration,
+    cache: Mutex<HashMap<String, (MomentumData, Instant)>>,
+    backoff: Mutex<Backoff>,
+}
+
+impl DexScreener {
+    pub fn new(poll_interval: Duration, max_requests_per_sec: f64) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .unwrap_or_default(),
+            limiter: RateLimiter::new(max_requests_per_sec),
+            poll_interval,
+            cache: Mutex::new(HashMap::new()),
+            backoff: Mutex::new(Backoff::default()),
+        }
+    }
+
+    /// Current price and momentum for `mint`, reusing a result younger than the poll interval
+    pub async fn check_momentum(&self, mint: &str) -> Result<MomentumData, MomentumError> {
+        if let Some(data) = self.cached(mint).await {
+            return Ok(data);
+        }
+        self.fetch(&[mint.to_string()]).await?;
+        self.cached(mint).await.ok_or(MomentumError::NotIndexed)
+    }
+
+    /// Refresh every stale mint in `mints`, up to [`MAX_BATCH`] per request,
+    /// so the checks that follow are served from the cache
+    pub async fn prefetch(&self, mints: &[String]) -> Result<(), MomentumError> {
+        let mut stale = Vec::new();
+        for mint in mints {
+            if self.cached(mint).await.is_none() {
+                stale.push(mint.clone());
+            }
+        }
+        for batch in stale.chunks(MAX_BATCH) {
+            self.fetch(batch).await?;
+        }
+        Ok(())
+    }
+
+    async fn cached(&self, mint: &str) -> Option<MomentumData> {
+        let cache = self.cache.lock().await;
+        cache
+            .get(mint)
+            .filter(|(_, fetched)| fetched.elapsed() < self.poll_interval)
+            .map(|(data, _)| data.clone())
+    }
+
+    /// One request for `mints`; indexed ones land in the cache
+    async fn fetch(&self, mints: &[String]) -> Result<(), MomentumError> {
+        if let Some(until) = self.backoff.lock().await.until {
+            let now = Instant::now();
+            if until > now {
+                return Err(MomentumError::RateLimited(until - now));
+            }
+        }
+
+        self.limiter.acquire().await;
+        let url = format!("{}/{}", TOKENS_URL, mints.join(","));
+        let response = self.client.get(&url).send().await?;
+
+        if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .map(Duration::from_secs);
+            let mut backoff = self.backoff.lock().await;
+            backoff.strikes += 1;
+            let wait = backoff_delay(backoff.strikes, retry_after);
+            backoff.until = Some(Instant::now() + wait);
+            warn!("DexScreener returned 429 ({} in a row), backing off {:?}", backoff.strikes, wait);
+            return Err(MomentumError::RateLimited(wait));
+        }
+        if !response.status().is_success() {
+            return Err(MomentumError::Failed(anyhow!("DexScreener returned HTTP {}", response.status())));
+        }
+        *self.backoff.lock().await = Backoff::default();
+
+        let response: DexScreenerResponse = response.json().await?;
+        let found = parse_pairs(response, mints);
+        let now = Instant::now();
+        let mut cache = self.cache.lock().await;
+        cache.retain(|_, (_, fetched)| fetched.elapsed() < self.poll_interval);
+        for (mint, data) in found {
+            cache.insert(mint, (data, now));
+        }
+        Ok(())
+    }
+}
+
+/// Back-off after `strikes` 429s in a row: doubling from [`BACKOFF_BASE`] up
+/// to [`BACKOFF_MAX`], or longer if DexScreener said so
+fn backoff_delay(strikes: u32, retry_after: Option<Duration>) -> Duration {
+    let doubled = BACKOFF_BASE.saturating_mul(1 << strikes.saturating_sub(1).min(16));
+    doubled.min(BACKOFF_MAX).max(retry_after.unwrap_or_default())
+}
+
+static DEXSCREENER: OnceLock<DexScreener> = OnceLock::new();
+
+/// Set the shared client's poll interval and rate limit; only the first call
+/// (before any check) takes effect
+pub fn configure(poll_interval: Duration, max_requests_per_sec: f64) {
+    if DEXSCREENER.set(DexScreener::new(poll_interval, max_requests_per_sec)).is_err() {
+        warn!("DexScreener client already configured, keeping its settings");
+    }
+}
+
+/// Process-wide client, so every position shares one rate limit and cache
+pub fn global() -> &'static DexScreener {
+    DEXSCREENER.get_or_init(|| DexScreener::new(DEFAULT_POLL_INTERVAL, DEFAULT_MAX_REQUESTS_PER_SEC))
+}
+
+/// Fetch current price and momentum for a token through the shared [`global`] client
+pub async fn check_momentum(mint: &str) -> Result<MomentumData, MomentumError> {
+    global().check_momentum(mint).await
+}
+
+/// First pair DexScreener lists for each of `mints`, as momentum data
+fn parse_pairs(response: DexScreenerResponse, mints: &[String]) -> HashMap<String, MomentumData> {
+    let mut found = HashMap::new();
+    for pair in response.pairs.unwrap_or_default() {
+        // A single-mint lookup may come back without base token info
+        let mint = match (&pair.base_token, mints) {
+            (Some(base), _) => base.address.clone(),
+            (None, [only]) => only.clone(),
+            (None, _) => continue,
+        };
+        if found.contains_key(&mint) || !mints.contains(&mint) {
+            continue;
+        }
+        if let Some(data) = momentum_data(&mint, pair) {
+            found.insert(mint, data);
+        }
+    }
+    found
+}
+
+/// Momentum from one DexScreener pair, None without a USD price
+fn momentum_data(mint: &str, pair: DexScreenerPair) -> Option<MomentumData> {
+    let current_price_usd = pair.price_usd.as_deref().and_then(|p| p.parse::<f64>().ok())?;
+    let current_price_sol = pair
+        .price_native
+        .as_deref()
+        .and_then(|p| p.parse::<f64>().ok())
+        .filter(|p| *p > 0.0);
+
+    let price_change_5m = pair.price_change.map(|c| c.m5).unwrap_or(0.0);
+    let (buys, sells) = pair
+        .txns
+        .and_then(|t| t.m5)
+        .map(|t| (t.buys, t.sells))
+        .unwrap_or((0, 0));
+    let volume_24h = pair.volume.map(|v| v.h24).unwrap_or(0.0);
+
+    let momentum_score = momentum_score(price_change_5m, buys, sells);
+
+    debug!(
+        "{} price ${:.8} | 5m {:+.1}% | {} buys / {} sells | momentum {:.2}",
+        mint, current_price_usd, price_change_5m, buys, sells, momentum_score
+    );
+
+    Some(MomentumData {
+        current_price_usd,
+        current_price_sol,
+        momentum_score,
+        volume_24h,
+    })
+}
+
+/// Blend 5m price trend (60%) and buy/sell imbalance (40%) into -1.0..1.0
+fn momentum_score(price_change_5m: f64, buys: u64, sells: u64) -> f64 {
+    let trend = (price_change_5m / 20.0).tanh();
+
+    let total = buys + sells;
+    let flow = if total > 0 {
+        (buys as f64 - sells as f64) / total as f64
+    } else {
+        0.0
+    };
+
+    (trend * 0.6 + flow * 0.4).clamp(-1.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_momentum_score_range() {
+        assert!(momentum_score(80.0, 90, 10) > 0.7);
+        assert!(momentum_score(-50.0, 5, 95) < -0.7);
+        assert_eq!(momentum_score(0.0, 0, 0), 0.0);
+    }
+
+    #[test]
+    fn test_stop_loss_overrides_momentum() {
+        let momentum = MomentumData {
+            current_price_usd: 0.0006,
+            current_price_sol: None,
+            momentum_score: 0.5,
+            volume_24h: 0.0,
+        };
+        let mut pnl = PnlTracker::new(0.001, 0.1);
+        pnl.update(0.0008);
+        assert!(momentum.should_hold(&pnl));
+        pnl.update(momentum.current_price_usd);
+        assert!(!momentum.should_hold(&pnl));
+    }
+
+    #[test]
+    fn test_strength_rescales_score() {
+        let at = |momentum_score| MomentumData {
+            current_price_usd: 0.001,
+            current_price_sol: None,
+            momentum_score,
+            volume_24h: 0.0,
+        };
+        assert_eq!(at(-1.0).strength(), 0.0);
+        assert_eq!(at(0.0).strength(), 0.5);
+        assert_eq!(at(0.5).strength(), 0.75);
+    }
+
+    #[test]
+    fn test_batch_response_maps_pairs_to_mints() {
+        let response: DexScreenerResponse = serde_json::from_value(serde_json::json!({
+            "pairs": [
+                { "baseToken": { "address": "MintA" }, "priceUsd": "0.0012", "priceNative": "0.00001" },
+                { "baseToken": { "address": "MintA" }, "priceUsd": "0.9" },
+                { "baseToken": { "address": "MintB" }, "priceUsd": "0.5", "priceChange": { "m5": 40.0 } },
+                { "baseToken": { "address": "Other" }, "priceUsd": "1.0" },
+                { "baseToken": { "address": "MintC" } }
+            ]
+        }))
+        .unwrap();
+        let mints = ["MintA", "MintB", "MintC"].map(String::from);
+
+        let found = parse_pairs(response, &mints);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found["MintA"].current_price_usd, 0.0012);
+        assert_eq!(found["MintA"].current_price_sol, Some(0.00001));
+        assert!(found["MintB"].momentum_score > 0.0);
+    }
+
+    #[test]
+    fn test_no_data_waits_for_indexing() {
+        let not_indexed = MomentumError::NotIndexed;
+        assert_eq!(not_indexed.exit_reason(false, 10, 60), None);
+        assert_eq!(not_indexed.exit_reason(false, 60, 60), Some(ExitReason::NeverIndexed));
+        assert_eq!(not_indexed.exit_reason(true, 10, 60), Some(ExitReason::PriceDataLost));
+
+        let failed = MomentumError::Failed(anyhow!("timeout"));
+        assert_eq!(failed.exit_reason(false, 10, 60), None);
+        assert_eq!(failed.exit_reason(true, 10, 60), Some(ExitReason::NoPriceData));
+        assert_eq!(MomentumError::RateLimited(BACKOFF_BASE).exit_reason(true, 600, 60), None);
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        assert_eq!(backoff_delay(1, None), Duration::from_secs(2));
+        assert_eq!(backoff_delay(3, None), Duration::from_secs(8));
+        assert_eq!(backoff_delay(40, None), BACKOFF_MAX);
+        assert_eq!(backoff_delay(1, Some(Duration::from_secs(10))), Duration::from_secs(10));
+    }
+}
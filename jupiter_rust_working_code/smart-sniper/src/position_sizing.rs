@@ -0,0 +1,99 @@
+//! Buy size from launch analysis
+//!
+//! The risk score from `analyze_token` and the social score from the pre-buy
+//! check both say how much we like a launch. Instead of only gating the buy,
+//! they pick a size between `MIN_SNIPE_SOL` and `MAX_SNIPE_SOL`: a launch that
+//! barely passes the filters gets the minimum, a strong one the cap.
+
+use crate::social_checker::SocialScore;
+
+/// Lowest risk score that passes the buy filter
+pub const MIN_RISK_SCORE: f64 = 0.6;
+
+/// Risk score at which a launch gets the full size; the heuristic bonuses top out around here
+const FULL_SIZE_RISK_SCORE: f64 = 1.5;
+
+/// Share of the confidence taken from socials when they were checked
+const SOCIAL_WEIGHT: f64 = 0.3;
+
+#[derive(Debug, Clone, Copy)]
+pub struct PositionSizer {
+    min_sol: f64,
+    max_sol: f64,
+}
+
+impl PositionSizer {
+    /// Sizes between `min_sol` and `max_sol`; the bounds are swapped if given backwards
+    pub fn new(min_sol: f64, max_sol: f64) -> Self {
+        Self {
+            min_sol: min_sol.min(max_sol),
+            max_sol: min_sol.max(max_sol),
+        }
+    }
+
+    pub fn min_sol(&self) -> f64 {
+        self.min_sol
+    }
+
+    pub fn max_sol(&self) -> f64 {
+        self.max_sol
+    }
+
+    /// Fixed size when both bounds are equal
+    pub fn is_fixed(&self) -> bool {
+        self.min_sol == self.max_sol
+    }
+
+    /// Confidence in a launch from 0.0 (marginal) to 1.0 (strongest)
+    pub fn confidence(risk_score: f64, social: Option<&SocialScore>) -> f64 {
+        let risk = ((risk_score - MIN_RISK_SCORE) / (FULL_SIZE_RISK_SCORE - MIN_RISK_SCORE)).clamp(0.0, 1.0);
+        match social {
+            Some(score) => risk * (1.0 - SOCIAL_WEIGHT) + score.momentum_score.clamp(0.0, 1.0) * SOCIAL_WEIGHT,
+            None => risk,
+        }
+    }
+
+    /// SOL to spend on a launch with these scores
+    pub fn size(&self, risk_score: f64, social: Option<&SocialScore>) -> f64 {
+        self.min_sol + (self.max_sol - self.min_sol) * Self::confidence(risk_score, social)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn socials(momentum_score: f64) -> SocialScore {
+        SocialScore {
+            has_twitter: momentum_score > 0.0,
+            has_telegram: false,
+            has_website: false,
+            momentum_score,
+            should_fast_exit: momentum_score == 0.0,
+        }
+    }
+
+    #[test]
+    fn test_size_scales_with_scores() {
+        let sizer = PositionSizer::new(0.02, 0.1);
+
+        assert_eq!(sizer.size(MIN_RISK_SCORE, None), 0.02);
+        assert_eq!(sizer.size(3.0, None), 0.1);
+        assert!((sizer.size(1.05, None) - 0.06).abs() < 1e-9);
+
+        // Strong socials lift a marginal launch, missing ones hold back a strong one
+        assert!(sizer.size(MIN_RISK_SCORE, Some(&socials(1.0))) > 0.02);
+        assert!(sizer.size(3.0, Some(&socials(0.0))) < 0.1);
+        assert_eq!(sizer.size(3.0, Some(&socials(1.0))), 0.1);
+    }
+
+    #[test]
+    fn test_fixed_and_swapped_bounds() {
+        let fixed = PositionSizer::new(0.05, 0.05);
+        assert!(fixed.is_fixed());
+        assert_eq!(fixed.size(2.0, Some(&socials(1.0))), 0.05);
+
+        let swapped = PositionSizer::new(0.1, 0.02);
+        assert_eq!((swapped.min_sol(), swapped.max_sol()), (0.02, 0.1));
+    }
+}
@@ -1,93 +1,280 @@
+//! Token social presence checks
+//!
+//! What counts as "has socials": a link that points somewhere a community can
+//! actually live, taken from the token's metadata JSON (fetched from the `uri`
+//! on the create event with `pump_portal_sdk::fetch_token_metadata`) and, if
+//! enabled, DexScreener's token info:
+//!
+//! - Twitter: an `x.com` / `twitter.com` URL with a path (not the bare domain)
+//! - Telegram: a `t.me` / `telegram.me` URL with a path
+//! - Website: any other http(s) URL that isn't pump.fun, a social domain, or
+//!   an image/IPFS host
+//!
+//! Empty strings, placeholder text and links back to pump.fun don't count.
+//! Zero qualifying links is the "ZERO SOCIALS → fast exit" case.
+
 use anyhow::Result;
 use log::{info, warn};
+use pump_portal_sdk::TokenMetadata;
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Score weight for each social; Twitter is where pump.fun communities form
+const TWITTER_WEIGHT: f64 = 0.4;
+const TELEGRAM_WEIGHT: f64 = 0.3;
+const WEBSITE_WEIGHT: f64 = 0.3;
+
+/// Zero-social results are re-checked after this, since DexScreener can lag
+/// the launch by a few seconds
+const NO_SOCIALS_CACHE_TTL: Duration = Duration::from_secs(30);
 
 #[derive(Debug, Clone)]
 pub struct SocialScore {
     pub has_twitter: bool,
     pub has_telegram: bool,
     pub has_website: bool,
-    pub momentum_score: f64,
+    pub momentum_score: f64, // 0.0 = no socials, 1.0 = twitter + telegram + website
     pub should_fast_exit: bool,
 }
 
+impl SocialScore {
+    /// Build a graded score from the socials found
+    fn from_links(links: &SocialLinks) -> Self {
+        let mut momentum_score = 0.0;
+        if links.twitter {
+            momentum_score += TWITTER_WEIGHT;
+        }
+        if links.telegram {
+            momentum_score += TELEGRAM_WEIGHT;
+        }
+        if links.website {
+            momentum_score += WEBSITE_WEIGHT;
+        }
+
+        Self {
+            has_twitter: links.twitter,
+            has_telegram: links.telegram,
+            has_website: links.website,
+            momentum_score,
+            should_fast_exit: !links.any(),
+        }
+    }
+}
+
+/// Which socials have qualifying links
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct SocialLinks {
+    twitter: bool,
+    telegram: bool,
+    website: bool,
+}
+
+impl SocialLinks {
+    fn any(&self) -> bool {
+        self.twitter || self.telegram || self.website
+    }
+
+    fn merge(&mut self, other: SocialLinks) {
+        self.twitter |= other.twitter;
+        self.telegram |= other.telegram;
+        self.website |= other.website;
+    }
+
+    /// Classify one URL into the social it represents
+    fn add_url(&mut self, url: &str) {
+        match classify_link(url) {
+            Some(LinkKind::Twitter) => self.twitter = true,
+            Some(LinkKind::Telegram) => self.telegram = true,
+            Some(LinkKind::Website) => self.website = true,
+            None => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LinkKind {
+    Twitter,
+    Telegram,
+    Website,
+}
+
+/// Decide what a link counts as, if anything
+fn classify_link(url: &str) -> Option<LinkKind> {
+    let url = url.trim();
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let host = host.to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let has_path = !path.trim_matches('/').is_empty();
+
+    match host {
+        "x.com" | "twitter.com" => has_path.then_some(LinkKind::Twitter),
+        "t.me" | "telegram.me" => has_path.then_some(LinkKind::Telegram),
+        "" => None,
+        h if h == "pump.fun" || h.ends_with(".pump.fun") => None,
+        h if h.contains("ipfs") || h.ends_with("arweave.net") => None,
+        h if h.contains('.') => Some(LinkKind::Website),
+        _ => None,
+    }
+}
+
+/// Qualifying socials among the metadata's links
+fn metadata_links(metadata: &TokenMetadata) -> SocialLinks {
+    let mut links = SocialLinks::default();
+    for url in metadata.social_links() {
+        links.add_url(url);
+    }
+    links
+}
+
 #[derive(Debug, Deserialize)]
-struct PumpFunTokenData {
+struct DexScreenerResponse {
     #[serde(default)]
-    twitter: Option<String>,
+    pairs: Option<Vec<DexScreenerPair>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DexScreenerPair {
+    #[serde(default)]
+    info: Option<DexScreenerInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DexScreenerInfo {
     #[serde(default)]
-    telegram: Option<String>,
+    websites: Vec<DexScreenerLink>,
     #[serde(default)]
-    website: Option<String>,
+    socials: Vec<DexScreenerLink>,
 }
 
-/// Check token socials and momentum
-pub async fn check_social_momentum(mint: &str) -> Result<SocialScore> {
-    let client = Client::builder()
-        .timeout(std::time::Duration::from_secs(5))
-        .build()?;
-
-    info!("   🔍 Checking socials for {}...", &mint[..8]);
-
-    // Try pump.fun API first
-    let pump_url = format!("https://frontend-api.pump.fun/coins/{}", mint);
-
-    let mut has_twitter = false;
-    let mut has_telegram = false;
-    let mut has_website = false;
-
-    match client.get(&pump_url).send().await {
-        Ok(response) => {
-            if let Ok(data) = response.json::<PumpFunTokenData>().await {
-                has_twitter = data.twitter.as_ref().map_or(false, |t| !t.is_empty());
-                has_telegram = data.telegram.as_ref().map_or(false, |t| !t.is_empty());
-                has_website = data.website.as_ref().map_or(false, |w| !w.is_empty());
-
-                if has_twitter {
-                    info!("   ✅ Twitter found");
-                }
-                if has_telegram {
-                    info!("   ✅ Telegram found");
-                }
-                if has_website {
-                    info!("   ✅ Website found");
-                }
+#[derive(Debug, Deserialize)]
+struct DexScreenerLink {
+    url: String,
+}
+
+/// Checks token socials, caching results per mint
+pub struct SocialChecker {
+    client: Client,
+    use_dexscreener: bool,
+    cache: Mutex<HashMap<String, (SocialScore, Instant)>>,
+}
+
+impl SocialChecker {
+    pub fn new() -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()?;
+
+        Ok(Self {
+            client,
+            use_dexscreener: false,
+            cache: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Also look up socials on DexScreener (slower, but catches links added after launch)
+    pub fn with_dexscreener(mut self, enabled: bool) -> Self {
+        self.use_dexscreener = enabled;
+        self
+    }
+
+    /// Check token socials from its metadata (and DexScreener if enabled)
+    pub async fn check_social_momentum(&self, mint: &str, metadata: Option<&TokenMetadata>) -> Result<SocialScore> {
+        if let Some((score, checked_at)) = self.cache.lock().await.get(mint) {
+            if !score.should_fast_exit || checked_at.elapsed() < NO_SOCIALS_CACHE_TTL {
+                return Ok(score.clone());
             }
         }
-        Err(e) => {
-            warn!("   ⚠️  Failed to fetch pump.fun data: {}", e);
+
+        info!("   🔍 Checking socials for {}...", &mint[..8]);
+
+        let mut links = metadata.map(metadata_links).unwrap_or_default();
+
+        if self.use_dexscreener {
+            match self.fetch_dexscreener_links(mint).await {
+                Ok(found) => links.merge(found),
+                Err(e) => warn!("   ⚠️  Failed to fetch DexScreener data: {}", e),
+            }
+        }
+
+        if links.twitter {
+            info!("   ✅ Twitter found");
+        }
+        if links.telegram {
+            info!("   ✅ Telegram found");
         }
+        if links.website {
+            info!("   ✅ Website found");
+        }
+
+        let score = SocialScore::from_links(&links);
+
+        if score.should_fast_exit {
+            warn!("   ❌ ZERO SOCIALS - FAST EXIT MODE");
+        } else if score.momentum_score < 0.5 {
+            warn!("   ⚠️  Weak socials - reduced hold time");
+        }
+
+        self.cache
+            .lock()
+            .await
+            .insert(mint.to_string(), (score.clone(), Instant::now()));
+
+        Ok(score)
+    }
+
+    async fn fetch_dexscreener_links(&self, mint: &str) -> Result<SocialLinks> {
+        let url = format!("https://api.dexscreener.com/latest/dex/tokens/{}", mint);
+        let response: DexScreenerResponse = self.client.get(&url).send().await?.json().await?;
+
+        let mut links = SocialLinks::default();
+        for info in response.pairs.unwrap_or_default().into_iter().filter_map(|p| p.info) {
+            for link in info.websites.iter().chain(info.socials.iter()) {
+                links.add_url(&link.url);
+            }
+        }
+        Ok(links)
     }
+}
 
-    // Calculate momentum score
-    let social_count = [has_twitter, has_telegram, has_website]
-        .iter()
-        .filter(|&&x| x)
-        .count();
-
-    let momentum_score = match social_count {
-        3 => 1.0,  // All socials = strong project
-        2 => 0.7,  // Two socials = decent
-        1 => 0.4,  // One social = weak
-        0 => 0.0,  // No socials = RED FLAG
-        _ => 0.0,
-    };
-
-    // Fast exit if NO socials
-    let should_fast_exit = social_count == 0;
-
-    if should_fast_exit {
-        warn!("   ❌ ZERO SOCIALS - FAST EXIT MODE");
-    } else if social_count == 1 {
-        warn!("   ⚠️  Weak socials - reduced hold time");
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_link() {
+        assert_eq!(classify_link("https://x.com/sometoken"), Some(LinkKind::Twitter));
+        assert_eq!(classify_link("https://twitter.com/"), None);
+        assert_eq!(classify_link("https://t.me/sometoken_portal"), Some(LinkKind::Telegram));
+        assert_eq!(classify_link("https://sometoken.xyz"), Some(LinkKind::Website));
+        assert_eq!(classify_link("https://pump.fun/coin/abc"), None);
+        assert_eq!(classify_link("https://ipfs.io/ipfs/Qm123"), None);
+        assert_eq!(classify_link("not a link"), None);
+        assert_eq!(classify_link(""), None);
     }
 
-    Ok(SocialScore {
-        has_twitter,
-        has_telegram,
-        has_website,
-        momentum_score,
-        should_fast_exit,
-    })
+    #[test]
+    fn test_metadata_scoring() {
+        let json = r#"{"name":"Token","symbol":"TKN","image":"https://ipfs.io/ipfs/Qm1","twitter":"https://x.com/token","website":"https://pump.fun","extensions":{"telegram":"https://t.me/token"}}"#;
+        let metadata: TokenMetadata = serde_json::from_str(json).unwrap();
+        let score = SocialScore::from_links(&metadata_links(&metadata));
+
+        assert!(score.has_twitter && score.has_telegram && !score.has_website);
+        assert!((score.momentum_score - 0.7).abs() < 1e-9);
+        assert!(!score.should_fast_exit);
+    }
+
+    #[test]
+    fn test_zero_socials_fast_exit() {
+        let metadata: TokenMetadata = serde_json::from_str(r#"{"twitter":"","telegram":"tg"}"#).unwrap();
+        let score = SocialScore::from_links(&metadata_links(&metadata));
+
+        assert_eq!(score.momentum_score, 0.0);
+        assert!(score.should_fast_exit);
+    }
 }
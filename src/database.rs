@@ -1,6 +1,7 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 use tracing::{info, warn};
@@ -48,11 +49,22 @@ impl Database {
                 realized_pnl_pct REAL,
                 exit_reason TEXT,
                 confidence_score REAL,
-                volatility REAL
+                volatility REAL,
+                token_amount REAL,
+                paper BOOLEAN NOT NULL DEFAULT 0
             )",
             [],
         )?;
 
+        // token_amount was added later; bring older databases up to date
+        if conn.prepare("SELECT token_amount FROM positions LIMIT 0").is_err() {
+            conn.execute("ALTER TABLE positions ADD COLUMN token_amount REAL", [])?;
+        }
+        // So was paper; every earlier position was live
+        if conn.prepare("SELECT paper FROM positions LIMIT 0").is_err() {
+            conn.execute("ALTER TABLE positions ADD COLUMN paper BOOLEAN NOT NULL DEFAULT 0", [])?;
+        }
+
         // Trades table (execution records)
         conn.execute(
             "CREATE TABLE IF NOT EXISTS trades (
@@ -67,11 +79,21 @@ impl Database {
                 slippage_bps REAL,
                 fees_usd REAL,
                 execution_time_ms INTEGER,
+                fees_sol REAL,
+                price_impact_pct REAL,
                 FOREIGN KEY (position_id) REFERENCES positions(id)
             )",
             [],
         )?;
 
+        // fees_sol and price_impact_pct were added later. Older rows stored price
+        // impact in fees_usd, which isn't a fee, so it's cleared rather than counted.
+        if conn.prepare("SELECT fees_sol FROM trades LIMIT 0").is_err() {
+            conn.execute("ALTER TABLE trades ADD COLUMN fees_sol REAL", [])?;
+            conn.execute("ALTER TABLE trades ADD COLUMN price_impact_pct REAL", [])?;
+            conn.execute("UPDATE trades SET fees_usd = NULL", [])?;
+        }
+
         // Risk metrics snapshots
         conn.execute(
             "CREATE TABLE IF NOT EXISTS risk_snapshots (
@@ -106,11 +128,17 @@ impl Database {
                 similar_patterns_count INTEGER,
                 executed BOOLEAN DEFAULT 0,
                 position_id INTEGER,
+                version INTEGER NOT NULL DEFAULT 0,
                 FOREIGN KEY (position_id) REFERENCES positions(id)
             )",
             [],
         )?;
 
+        // version was added later; rows written before it are version 0
+        if conn.prepare("SELECT version FROM signals LIMIT 0").is_err() {
+            conn.execute("ALTER TABLE signals ADD COLUMN version INTEGER NOT NULL DEFAULT 0", [])?;
+        }
+
         info!("Database schema initialized");
         Ok(())
     }
@@ -122,8 +150,8 @@ impl Database {
             "INSERT INTO positions (
                 symbol, mint_address, entry_price, current_price, size_usd,
                 entry_time, status, peak_price, trailing_stop, unrealized_pnl,
-                unrealized_pnl_pct, confidence_score, volatility
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                unrealized_pnl_pct, confidence_score, volatility, token_amount, paper
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
             params![
                 pos.symbol,
                 pos.mint_address,
@@ -138,6 +166,8 @@ impl Database {
                 pos.unrealized_pnl_pct,
                 pos.confidence_score,
                 pos.volatility,
+                pos.token_amount,
+                pos.paper,
             ],
         )?;
         Ok(conn.last_insert_rowid())
@@ -159,6 +189,19 @@ impl Database {
         Ok(())
     }
 
+    /// Record the tokens actually held for a position, scaling `size_usd` to match
+    pub fn update_token_amount(&self, id: i64, token_amount: f64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE positions SET
+                size_usd = CASE WHEN token_amount > 0 THEN size_usd * ?1 / token_amount ELSE size_usd END,
+                token_amount = ?1
+            WHERE id = ?2",
+            params![token_amount, id],
+        )?;
+        Ok(())
+    }
+
     /// Close position with exit details
     pub fn close_position(&self, id: i64, exit_price: f64, realized_pnl: f64, realized_pnl_pct: f64, exit_reason: &str) -> Result<()> {
         let conn = self.conn.lock().unwrap();
@@ -179,17 +222,17 @@ impl Database {
         Ok(())
     }
 
-    /// Get all open positions
-    pub fn get_open_positions(&self) -> Result<Vec<(i64, String, f64, f64, f64)>> {
+    /// Get all open paper or live positions
+    pub fn get_open_positions(&self, paper: bool) -> Result<Vec<(i64, String, f64, f64, f64)>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
             "SELECT id, symbol, entry_price, current_price, size_usd
              FROM positions
-             WHERE status = 'open'"
+             WHERE status = 'open' AND paper = ?1"
         )?;
 
         let positions = stmt
-            .query_map([], |row| {
+            .query_map(params![paper], |row| {
                 Ok((
                     row.get(0)?,
                     row.get(1)?,
@@ -203,14 +246,118 @@ impl Database {
         Ok(positions)
     }
 
+    /// Get (id, symbol, mint_address) for every open live position
+    pub fn get_open_position_mints(&self) -> Result<Vec<(i64, String, Option<String>)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, symbol, mint_address
+             FROM positions
+             WHERE status = 'open' AND paper = 0"
+        )?;
+
+        let positions = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(positions)
+    }
+
+    /// Get (id, symbol, mint_address, token_amount) for every open live position
+    pub fn get_open_position_holdings(&self) -> Result<Vec<(i64, String, Option<String>, Option<f64>)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, symbol, mint_address, token_amount
+             FROM positions
+             WHERE status = 'open' AND paper = 0"
+        )?;
+
+        let positions = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(positions)
+    }
+
+    /// Get closed positions, most recently exited first
+    pub fn get_closed_positions(&self, limit: usize, offset: usize) -> Result<Vec<ClosedPositionRecord>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, symbol, mint_address, entry_price, exit_price, size_usd,
+                    entry_time, exit_time, realized_pnl, realized_pnl_pct,
+                    exit_reason, confidence_score, volatility, paper
+             FROM positions
+             WHERE status = 'closed'
+             ORDER BY exit_time DESC
+             LIMIT ?1 OFFSET ?2"
+        )?;
+
+        let positions = stmt
+            .query_map(params![limit as i64, offset as i64], |row| {
+                Ok(ClosedPositionRecord {
+                    id: row.get(0)?,
+                    symbol: row.get(1)?,
+                    mint_address: row.get(2)?,
+                    entry_price: row.get(3)?,
+                    exit_price: row.get(4)?,
+                    size_usd: row.get(5)?,
+                    entry_time: parse_timestamp(6, row.get(6)?)?,
+                    exit_time: parse_timestamp(7, row.get(7)?)?,
+                    realized_pnl: row.get(8)?,
+                    realized_pnl_pct: row.get(9)?,
+                    exit_reason: row.get(10)?,
+                    confidence_score: row.get(11)?,
+                    volatility: row.get(12)?,
+                    paper: row.get(13)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(positions)
+    }
+
+    /// Get all trades recorded against a position, in execution order
+    pub fn get_trades_for_position(&self, position_id: i64) -> Result<Vec<TradeRecord>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT position_id, trade_type, symbol, price, size_usd, timestamp,
+                    signature, slippage_bps, fees_usd, execution_time_ms,
+                    fees_sol, price_impact_pct
+             FROM trades
+             WHERE position_id = ?1
+             ORDER BY timestamp ASC"
+        )?;
+
+        let trades = stmt
+            .query_map(params![position_id], |row| {
+                Ok(TradeRecord {
+                    position_id: row.get(0)?,
+                    trade_type: row.get(1)?,
+                    symbol: row.get(2)?,
+                    price: row.get(3)?,
+                    size_usd: row.get(4)?,
+                    timestamp: parse_timestamp(5, row.get(5)?)?,
+                    signature: row.get(6)?,
+                    slippage_bps: row.get(7)?,
+                    fees_usd: row.get(8)?,
+                    execution_time_ms: row.get(9)?,
+                    fees_sol: row.get(10)?,
+                    price_impact_pct: row.get(11)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(trades)
+    }
+
     /// Record trade execution
     pub fn insert_trade(&self, trade: &TradeRecord) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
             "INSERT INTO trades (
                 position_id, trade_type, symbol, price, size_usd, timestamp,
-                signature, slippage_bps, fees_usd, execution_time_ms
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                signature, slippage_bps, fees_usd, execution_time_ms,
+                fees_sol, price_impact_pct
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
             params![
                 trade.position_id,
                 trade.trade_type,
@@ -222,6 +369,8 @@ impl Database {
                 trade.slippage_bps,
                 trade.fees_usd,
                 trade.execution_time_ms,
+                trade.fees_sol,
+                trade.price_impact_pct,
             ],
         )?;
         Ok(())
@@ -260,8 +409,8 @@ impl Database {
         conn.execute(
             "INSERT INTO signals (
                 timestamp, symbol, confidence, predicted_return, predicted_volatility,
-                embedding_vector, anomaly_score, similar_patterns_count
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                embedding_vector, anomaly_score, similar_patterns_count, version
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             params![
                 signal.timestamp.to_rfc3339(),
                 signal.symbol,
@@ -271,6 +420,7 @@ impl Database {
                 signal.embedding_vector,
                 signal.anomaly_score,
                 signal.similar_patterns_count,
+                signal.version,
             ],
         )?;
         Ok(conn.last_insert_rowid())
@@ -286,7 +436,8 @@ impl Database {
         Ok(())
     }
 
-    /// Get performance statistics
+    /// Get performance statistics, gross and net of the fees each closed
+    /// position's buys and sells paid
     pub fn get_performance_stats(&self) -> Result<PerformanceStats> {
         let conn = self.conn.lock().unwrap();
 
@@ -297,19 +448,28 @@ impl Database {
                 AVG(realized_pnl_pct) as avg_return_pct,
                 MAX(realized_pnl_pct) as max_return_pct,
                 MIN(realized_pnl_pct) as min_return_pct,
-                SUM(realized_pnl) as total_pnl
-             FROM positions
+                SUM(realized_pnl) as total_pnl,
+                SUM(CASE WHEN realized_pnl - COALESCE(f.fees_usd, 0) > 0 THEN 1 ELSE 0 END) as net_winning_trades,
+                AVG((realized_pnl - COALESCE(f.fees_usd, 0)) / size_usd * 100) as avg_net_return_pct,
+                SUM(f.fees_usd) as total_fees_usd
+             FROM positions p
+             LEFT JOIN (
+                SELECT position_id, SUM(fees_usd) as fees_usd FROM trades GROUP BY position_id
+             ) f ON f.position_id = p.id
              WHERE status = 'closed'"
         )?;
 
         let stats = stmt.query_row([], |row| {
             Ok(PerformanceStats {
                 total_trades: row.get(0)?,
-                winning_trades: row.get(1)?,
+                winning_trades: row.get::<_, Option<i32>>(1)?.unwrap_or(0),
                 avg_return_pct: row.get(2).unwrap_or(0.0),
                 max_return_pct: row.get(3).unwrap_or(0.0),
                 min_return_pct: row.get(4).unwrap_or(0.0),
                 total_pnl: row.get(5).unwrap_or(0.0),
+                net_winning_trades: row.get::<_, Option<i32>>(6)?.unwrap_or(0),
+                avg_net_return_pct: row.get(7).unwrap_or(0.0),
+                total_fees_usd: row.get(8).unwrap_or(0.0),
             })
         })?;
 
@@ -317,6 +477,91 @@ impl Database {
     }
 }
 
+/// Non-blocking handle for async code
+///
+/// `Database` holds a blocking SQLite connection behind a std `Mutex`; calling it
+/// from a task stalls that runtime worker for the whole query. Each call here
+/// runs on tokio's blocking pool instead, so nothing blocks across an `.await`.
+#[derive(Clone)]
+pub struct AsyncDatabase {
+    db: Database,
+}
+
+impl AsyncDatabase {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Run `f` against the database on the blocking pool
+    async fn run<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&Database) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let db = self.db.clone();
+        tokio::task::spawn_blocking(move || f(&db)).await?
+    }
+
+    pub async fn insert_position(&self, pos: PositionRecord) -> Result<i64> {
+        self.run(move |db| db.insert_position(&pos)).await
+    }
+
+    pub async fn update_position(&self, id: i64, current_price: f64, unrealized_pnl: f64, unrealized_pnl_pct: f64, peak_price: f64, trailing_stop: f64) -> Result<()> {
+        self.run(move |db| db.update_position(id, current_price, unrealized_pnl, unrealized_pnl_pct, peak_price, trailing_stop))
+            .await
+    }
+
+    pub async fn update_token_amount(&self, id: i64, token_amount: f64) -> Result<()> {
+        self.run(move |db| db.update_token_amount(id, token_amount)).await
+    }
+
+    pub async fn close_position(&self, id: i64, exit_price: f64, realized_pnl: f64, realized_pnl_pct: f64, exit_reason: &str) -> Result<()> {
+        let exit_reason = exit_reason.to_string();
+        self.run(move |db| db.close_position(id, exit_price, realized_pnl, realized_pnl_pct, &exit_reason))
+            .await
+    }
+
+    pub async fn get_open_positions(&self, paper: bool) -> Result<Vec<(i64, String, f64, f64, f64)>> {
+        self.run(move |db| db.get_open_positions(paper)).await
+    }
+
+    pub async fn get_open_position_mints(&self) -> Result<Vec<(i64, String, Option<String>)>> {
+        self.run(|db| db.get_open_position_mints()).await
+    }
+
+    pub async fn get_open_position_holdings(&self) -> Result<Vec<(i64, String, Option<String>, Option<f64>)>> {
+        self.run(|db| db.get_open_position_holdings()).await
+    }
+
+    pub async fn get_closed_positions(&self, limit: usize, offset: usize) -> Result<Vec<ClosedPositionRecord>> {
+        self.run(move |db| db.get_closed_positions(limit, offset)).await
+    }
+
+    pub async fn get_trades_for_position(&self, position_id: i64) -> Result<Vec<TradeRecord>> {
+        self.run(move |db| db.get_trades_for_position(position_id)).await
+    }
+
+    pub async fn insert_trade(&self, trade: TradeRecord) -> Result<()> {
+        self.run(move |db| db.insert_trade(&trade)).await
+    }
+
+    pub async fn insert_risk_snapshot(&self, metrics: crate::risk_manager::RiskMetrics) -> Result<()> {
+        self.run(move |db| db.insert_risk_snapshot(&metrics)).await
+    }
+
+    pub async fn insert_signal(&self, signal: SignalRecord) -> Result<i64> {
+        self.run(move |db| db.insert_signal(&signal)).await
+    }
+
+    pub async fn mark_signal_executed(&self, signal_id: i64, position_id: i64) -> Result<()> {
+        self.run(move |db| db.mark_signal_executed(signal_id, position_id)).await
+    }
+
+    pub async fn get_performance_stats(&self) -> Result<PerformanceStats> {
+        self.run(|db| db.get_performance_stats()).await
+    }
+}
+
 /// Position record for database
 #[derive(Debug, Clone)]
 pub struct PositionRecord {
@@ -332,10 +577,32 @@ pub struct PositionRecord {
     pub unrealized_pnl_pct: f64,
     pub confidence_score: f32,
     pub volatility: f64,
+    pub token_amount: Option<f64>,
+    /// Booked by the simulator, never traded
+    pub paper: bool,
+}
+
+/// Closed position with realized results
+#[derive(Debug, Clone, Serialize)]
+pub struct ClosedPositionRecord {
+    pub id: i64,
+    pub symbol: String,
+    pub mint_address: Option<String>,
+    pub entry_price: f64,
+    pub exit_price: f64,
+    pub size_usd: f64,
+    pub entry_time: DateTime<Utc>,
+    pub exit_time: DateTime<Utc>,
+    pub realized_pnl: f64,
+    pub realized_pnl_pct: f64,
+    pub exit_reason: Option<String>,
+    pub confidence_score: Option<f32>,
+    pub volatility: Option<f64>,
+    pub paper: bool,
 }
 
 /// Trade execution record
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct TradeRecord {
     pub position_id: Option<i64>,
     pub trade_type: String, // "buy" or "sell"
@@ -345,13 +612,20 @@ pub struct TradeRecord {
     pub timestamp: DateTime<Utc>,
     pub signature: Option<String>,
     pub slippage_bps: Option<f64>,
+    /// Network fees (base + priority) valued at the SOL price of the trade
     pub fees_usd: Option<f64>,
     pub execution_time_ms: Option<i64>,
+    /// Network fees (base + priority) the confirmed transaction paid
+    pub fees_sol: Option<f64>,
+    /// Price impact as the quote reported it; a cost of the fill, not a fee
+    pub price_impact_pct: Option<f64>,
 }
 
 /// ML signal record
 #[derive(Debug, Clone)]
 pub struct SignalRecord {
+    /// `types::Signal` schema version the row was written with
+    pub version: u32,
     pub timestamp: DateTime<Utc>,
     pub symbol: String,
     pub confidence: f32,
@@ -371,6 +645,10 @@ pub struct PerformanceStats {
     pub max_return_pct: f64,
     pub min_return_pct: f64,
     pub total_pnl: f64,
+    /// Closed positions still in profit after fees
+    pub net_winning_trades: i32,
+    pub avg_net_return_pct: f64,
+    pub total_fees_usd: f64,
 }
 
 impl PerformanceStats {
@@ -381,4 +659,26 @@ impl PerformanceStats {
             0.0
         }
     }
+
+    pub fn net_win_rate(&self) -> f64 {
+        if self.total_trades > 0 {
+            self.net_winning_trades as f64 / self.total_trades as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// Realized P&L less the fees paid getting in and out
+    pub fn net_pnl(&self) -> f64 {
+        self.total_pnl - self.total_fees_usd
+    }
+}
+
+/// Parse an RFC 3339 timestamp column back into a `DateTime<Utc>`
+fn parse_timestamp(idx: usize, value: String) -> rusqlite::Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(&value)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+        })
 }
@@ -1,39 +1,55 @@
-use crate::database::{Database, PositionRecord, TradeRecord};
+use crate::database::{AsyncDatabase, PositionRecord, TradeRecord};
+use crate::metrics;
+use crate::notify::{NoopNotifier, Notification, Notifier};
 use crate::risk_manager::{RiskError, RiskManager};
 use crate::types::Signal;
 use anyhow::{anyhow, Result};
 use chrono::Utc;
-use jup_ag_sdk::types::{QuoteRequest, SwapRequest};
+use jup_ag_sdk::types::{PriorityLevel, QuoteRequest, QuoteResponse, SwapRequest};
 use jup_ag_sdk::JupiterClient;
+use serde::Serialize;
 use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
     pubkey::Pubkey,
     signature::{Keypair, Signature, Signer},
     transaction::Transaction,
 };
+use solana_transaction_status::UiTransactionEncoding;
+use std::collections::HashMap;
 use std::str::FromStr;
-use std::sync::Arc;
-use std::time::Instant;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 
+/// Decimals of native SOL (lamports per SOL = 10^9)
+const SOL_DECIMALS: u8 = 9;
+/// Relative difference between recorded and on-chain amounts ignored by reconciliation
+const RECONCILE_TOLERANCE: f64 = 0.01;
+
 /// Execution engine for memecoin trading via Jupiter
 pub struct ExecutionEngine {
     jupiter: JupiterClient,
     rpc_client: Arc<RpcClient>,
     wallet: Arc<Keypair>,
     risk_manager: Arc<tokio::sync::Mutex<RiskManager>>,
-    database: Database,
+    database: AsyncDatabase,
     config: ExecutionConfig,
+    mint_decimals: Mutex<HashMap<String, u8>>, // Decimals never change, so fetch once per mint
+    notifier: Arc<dyn Notifier>,
 }
 
 #[derive(Debug, Clone)]
 pub struct ExecutionConfig {
-    pub max_slippage_bps: u64,        // Max slippage in basis points (e.g., 50 = 0.5%)
+    pub max_slippage_bps: u16,        // Max slippage in basis points (e.g., 50 = 0.5%)
     pub max_price_impact_bps: u64,    // Max price impact (e.g., 100 = 1%)
     pub priority_fee_lamports: u64,   // Priority fee for transactions
+    pub dynamic_slippage: bool,       // Let Jupiter estimate slippage, capped by max_slippage_bps
     pub sol_mint: String,             // SOL mint address
     pub confirmation_timeout_sec: u64, // Transaction confirmation timeout
+    pub record_fees: bool,            // Read each swap's network fee from the chain (one RPC call per trade)
 }
 
 impl Default for ExecutionConfig {
@@ -42,9 +58,26 @@ impl Default for ExecutionConfig {
             max_slippage_bps: 50,                    // 0.5% slippage
             max_price_impact_bps: 100,               // 1% price impact
             priority_fee_lamports: 5000,             // 0.000005 SOL priority fee
+            dynamic_slippage: true,
             sol_mint: "So11111111111111111111111111111111111111112".to_string(),
             confirmation_timeout_sec: 60,
+            record_fees: true,
+        }
+    }
+}
+
+impl ExecutionConfig {
+    /// Swap request for `quote` carrying our priority fee and slippage preferences
+    pub fn swap_request(&self, user_pubkey: &str, quote: &QuoteResponse) -> SwapRequest {
+        let request = SwapRequest::new(user_pubkey, user_pubkey, quote.clone())
+            .dynamic_compute_unit_limit(true)
+            .dynamic_slippage(self.dynamic_slippage);
+
+        if self.priority_fee_lamports == 0 {
+            return request;
         }
+        let max_lamports = u32::try_from(self.priority_fee_lamports).unwrap_or(u32::MAX);
+        request.prioritization_fee_config(max_lamports, PriorityLevel::High)
     }
 }
 
@@ -54,7 +87,7 @@ impl ExecutionEngine {
         rpc_url: String,
         wallet: Keypair,
         risk_manager: Arc<tokio::sync::Mutex<RiskManager>>,
-        database: Database,
+        database: AsyncDatabase,
         config: ExecutionConfig,
     ) -> Self {
         let jupiter = JupiterClient::new(&jupiter_url);
@@ -70,9 +103,17 @@ impl ExecutionEngine {
             risk_manager,
             database,
             config,
+            mint_decimals: Mutex::new(HashMap::new()),
+            notifier: Arc::new(NoopNotifier),
         }
     }
 
+    /// Report executed buys and sells through `notifier`
+    pub fn with_notifier(mut self, notifier: Arc<dyn Notifier>) -> Self {
+        self.notifier = notifier;
+        self
+    }
+
     /// Execute a buy order based on ML signal
     pub async fn execute_buy(
         &self,
@@ -88,37 +129,65 @@ impl ExecutionEngine {
 
         // 2. Calculate position size via RiskManager
         let mut rm = self.risk_manager.lock().await;
-        let size_usd = rm.calculate_position_size(signal, volatility)?;
+        let atr_pct = rm.atr_cache.get(symbol).copied();
+        let size_usd = rm.calculate_position_size(signal, volatility, atr_pct)?;
 
         // 3. Validate trade
         rm.validate_trade(signal, size_usd, volatility)
             .map_err(|e| anyhow!("Risk validation failed: {}", e))?;
 
-        // Get current SOL balance
+        // Don't take a trade whose stop-out alone would breach the daily limit
+        let preview = rm.preview_open(signal, size_usd);
+        info!(
+            "   Preview: exposure ${:.2} ({:.1}%), ${:.2} left, max loss ${:.2} at hard stop",
+            preview.exposure_usd,
+            preview.exposure_pct * 100.0,
+            preview.remaining_capital,
+            preview.max_loss_usd
+        );
+        if rm.breaches_daily_drawdown(&preview) {
+            return Err(anyhow!(
+                "Risk validation failed: {} (daily P&L would reach {:.2}% at the hard stop)",
+                RiskError::DrawdownLimitExceeded,
+                preview.daily_pnl_pct_at_stop * 100.0
+            ));
+        }
+
+        // Convert the USD size to SOL at the current price
+        let sol_price = self.sol_price_usd().await?;
+        let sol_to_spend = size_usd / sol_price;
         let sol_balance = self.get_sol_balance().await?;
-        let sol_to_spend = (size_usd / sol_balance) * sol_balance; // Simplified price calculation
+        if sol_to_spend > sol_balance {
+            return Err(anyhow!(
+                "Insufficient SOL: need {:.4}, have {:.4}",
+                sol_to_spend,
+                sol_balance
+            ));
+        }
 
-        info!("   Size: ${:.2} (~{:.4} SOL)", size_usd, sol_to_spend);
+        info!("   Size: ${:.2} (~{:.4} SOL @ ${:.2})", size_usd, sol_to_spend, sol_price);
 
         // 4. Get quote from Jupiter
-        let amount_lamports = (sol_to_spend * 1_000_000_000.0) as u64;
+        let token_decimals = self.mint_decimals(mint_address).await?;
+        let amount_lamports = to_base_units(sol_to_spend, SOL_DECIMALS);
         let quote_req = QuoteRequest::new(
             &self.config.sol_mint,
             mint_address,
             amount_lamports,
         )
-        .slippage_bps(self.config.max_slippage_bps);
+        .slippage_bps(self.config.max_slippage_bps)
+        .dynamic_slippage(self.config.dynamic_slippage);
 
         let quote = self.jupiter.get_quote(&quote_req).await
             .map_err(|e| anyhow!("Jupiter quote failed: {:?}", e))?;
 
-        info!("   Quote: {} SOL → {} tokens",
-              quote.in_amount as f64 / 1e9,
-              quote.out_amount);
+        let sol_in = from_base_units(parse_amount(&quote.in_amount)?, SOL_DECIMALS);
+        let tokens_out = from_base_units(parse_amount(&quote.out_amount)?, token_decimals);
+        info!("   Quote: {} SOL → {} tokens", sol_in, tokens_out);
 
         // 5. Get swap transaction
         let user_pubkey = self.wallet.pubkey().to_string();
-        let swap_req = SwapRequest::new(&user_pubkey, &quote);
+        let swap_req = self.config.swap_request(&user_pubkey, &quote);
 
         let swap_response = self.jupiter.get_swap_transaction(&swap_req).await
             .map_err(|e| anyhow!("Jupiter swap request failed: {:?}", e))?;
@@ -128,14 +197,17 @@ impl ExecutionEngine {
 
         let execution_time_ms = start_time.elapsed().as_millis() as i64;
         info!("   ✅ BUY EXECUTED: {} ({:.0}ms)", signature, execution_time_ms);
+        let fees_sol = self.swap_fees_sol(&signature).await;
 
         // 7. Calculate entry details
-        let entry_price = quote.in_amount as f64 / quote.out_amount as f64;
+        let entry_price = sol_in / tokens_out; // SOL per token
         let actual_slippage = 0.0; // TODO: Calculate actual vs expected
 
         // 8. Record position in risk manager
         let position_size_usd = size_usd;
         rm.open_position(symbol.to_string(), entry_price, position_size_usd)?;
+        metrics::TRADES_EXECUTED.with_label_values(&["buy"]).inc();
+        metrics::update_portfolio(&rm.get_metrics());
         drop(rm); // Release lock
 
         // 9. Record position in database
@@ -152,9 +224,11 @@ impl ExecutionEngine {
             unrealized_pnl_pct: 0.0,
             confidence_score: signal.confidence,
             volatility,
+            token_amount: Some(tokens_out),
+            paper: false,
         };
 
-        let position_id = self.database.insert_position(&position_record)?;
+        let position_id = self.database.insert_position(position_record).await?;
 
         // 10. Record trade execution
         let trade_record = TradeRecord {
@@ -166,16 +240,19 @@ impl ExecutionEngine {
             timestamp: Utc::now(),
             signature: Some(signature.to_string()),
             slippage_bps: Some(actual_slippage),
-            fees_usd: Some(quote.price_impact_pct as f64 * position_size_usd),
+            fees_usd: fees_sol.map(|fees| fees * sol_price),
             execution_time_ms: Some(execution_time_ms),
+            fees_sol,
+            price_impact_pct: quote.price_impact_pct.parse().ok(),
         };
 
-        self.database.insert_trade(&trade_record)?;
+        self.database.insert_trade(trade_record).await?;
+        self.notifier.notify(Notification::buy(symbol, position_size_usd, &signature.to_string()));
 
         Ok(ExecutionResult {
             signature,
             entry_price,
-            amount: quote.out_amount as f64,
+            amount: tokens_out,
             size_usd: position_size_usd,
             slippage_bps: actual_slippage,
             execution_time_ms,
@@ -196,24 +273,26 @@ impl ExecutionEngine {
         info!("💰 Executing SELL: {} ({:.0} tokens)", symbol, sell_amount);
 
         // 1. Get quote from Jupiter (sell tokens for SOL)
-        let amount_lamports = sell_amount as u64;
+        let token_decimals = self.mint_decimals(mint_address).await?;
+        let sol_price = self.sol_price_usd().await?; // Before the swap, so a failure can't skip the bookkeeping
         let quote_req = QuoteRequest::new(
             mint_address,
             &self.config.sol_mint,
-            amount_lamports,
+            to_base_units(sell_amount, token_decimals),
         )
-        .slippage_bps(self.config.max_slippage_bps);
+        .slippage_bps(self.config.max_slippage_bps)
+        .dynamic_slippage(self.config.dynamic_slippage);
 
         let quote = self.jupiter.get_quote(&quote_req).await
             .map_err(|e| anyhow!("Jupiter quote failed: {:?}", e))?;
 
-        info!("   Quote: {} tokens → {:.4} SOL",
-              quote.in_amount,
-              quote.out_amount as f64 / 1e9);
+        let tokens_in = from_base_units(parse_amount(&quote.in_amount)?, token_decimals);
+        let sol_received = from_base_units(parse_amount(&quote.out_amount)?, SOL_DECIMALS);
+        info!("   Quote: {} tokens → {:.4} SOL", tokens_in, sol_received);
 
         // 2. Get swap transaction
         let user_pubkey = self.wallet.pubkey().to_string();
-        let swap_req = SwapRequest::new(&user_pubkey, &quote);
+        let swap_req = self.config.swap_request(&user_pubkey, &quote);
 
         let swap_response = self.jupiter.get_swap_transaction(&swap_req).await
             .map_err(|e| anyhow!("Jupiter swap request failed: {:?}", e))?;
@@ -223,19 +302,22 @@ impl ExecutionEngine {
 
         let execution_time_ms = start_time.elapsed().as_millis() as i64;
         info!("   ✅ SELL EXECUTED: {} ({:.0}ms)", signature, execution_time_ms);
+        let fees_sol = self.swap_fees_sol(&signature).await;
 
         // 4. Calculate exit details
-        let exit_price = quote.out_amount as f64 / quote.in_amount as f64;
-        let sol_received = quote.out_amount as f64 / 1e9;
+        let exit_price = sol_received / tokens_in; // SOL per token
+        let size_usd = sol_received * sol_price;
 
         // 5. Close position in risk manager
         let mut rm = self.risk_manager.lock().await;
         let realized_pnl = rm.close_position(symbol, exit_price, exit_reason)?;
+        metrics::TRADES_EXECUTED.with_label_values(&["sell"]).inc();
+        metrics::update_portfolio(&rm.get_metrics());
         drop(rm);
 
         // 6. Update database
         let realized_pnl_pct = 0.0; // TODO: Calculate from entry price
-        self.database.close_position(position_id, exit_price, realized_pnl, realized_pnl_pct, exit_reason)?;
+        self.database.close_position(position_id, exit_price, realized_pnl, realized_pnl_pct, exit_reason).await?;
 
         // 7. Record trade
         let trade_record = TradeRecord {
@@ -243,27 +325,141 @@ impl ExecutionEngine {
             trade_type: "sell".to_string(),
             symbol: symbol.to_string(),
             price: exit_price,
-            size_usd: sol_received * 100.0, // Approximate USD value
+            size_usd,
             timestamp: Utc::now(),
             signature: Some(signature.to_string()),
             slippage_bps: None,
-            fees_usd: Some(quote.price_impact_pct as f64 * sol_received),
+            fees_usd: fees_sol.map(|fees| fees * sol_price),
             execution_time_ms: Some(execution_time_ms),
+            fees_sol,
+            price_impact_pct: quote.price_impact_pct.parse().ok(),
         };
 
-        self.database.insert_trade(&trade_record)?;
+        self.database.insert_trade(trade_record).await?;
+        self.notifier.notify(Notification::sell(symbol, size_usd, realized_pnl, &signature.to_string(), exit_reason));
 
         Ok(ExecutionResult {
             signature,
             entry_price: exit_price,
-            amount: quote.out_amount as f64,
-            size_usd: sol_received * 100.0,
+            amount: sol_received,
+            size_usd,
             slippage_bps: 0.0,
             execution_time_ms,
             position_id,
         })
     }
 
+    /// Market-sell every open position, e.g. on shutdown or from `/close_all`.
+    /// Keeps going past failures and reports which positions were closed and
+    /// which weren't.
+    pub async fn close_all_positions(&self, exit_reason: &str) -> Result<CloseAllReport> {
+        let mut report = CloseAllReport::default();
+        for (position_id, symbol, mint_address) in self.database.get_open_position_mints().await? {
+            match self.close_position(position_id, &symbol, mint_address.as_deref(), exit_reason).await {
+                Ok(result) => report.closed.push(ClosedPosition {
+                    position_id,
+                    symbol,
+                    signature: result.signature.to_string(),
+                    sol_received: result.amount,
+                }),
+                Err(e) => {
+                    error!("Failed to close {} (#{}): {}", symbol, position_id, e);
+                    report.failed.push(FailedClose { position_id, symbol, error: e.to_string() });
+                }
+            }
+        }
+        info!("Closed {} positions, {} failed", report.closed.len(), report.failed.len());
+        Ok(report)
+    }
+
+    /// Sell the open position in `symbol`, e.g. when its stop fires
+    pub async fn close_symbol(&self, symbol: &str, exit_reason: &str) -> Result<ExecutionResult> {
+        let (position_id, _, mint_address) = self
+            .database
+            .get_open_position_mints()
+            .await?
+            .into_iter()
+            .find(|(_, open_symbol, _)| open_symbol == symbol)
+            .ok_or_else(|| anyhow!("no open position in {}", symbol))?;
+        self.close_position(position_id, symbol, mint_address.as_deref(), exit_reason).await
+    }
+
+    /// Sell the wallet's whole balance of one open position's token
+    async fn close_position(
+        &self,
+        position_id: i64,
+        symbol: &str,
+        mint_address: Option<&str>,
+        exit_reason: &str,
+    ) -> Result<ExecutionResult> {
+        let mint_address = mint_address.ok_or_else(|| anyhow!("no mint address recorded"))?;
+        let balance = self.get_token_balance(mint_address).await?;
+        if balance <= 0.0 {
+            return Err(anyhow!("no token balance"));
+        }
+        self.execute_sell(position_id, symbol, mint_address, balance, exit_reason).await
+    }
+
+    /// Compare every open position with the wallet's on-chain balance.
+    /// Positions whose tokens are gone (sold out-of-band, or a sell landed
+    /// without its DB update) are closed; others get their recorded size
+    /// corrected. Returns (closed, resized).
+    pub async fn reconcile_positions(&self) -> Result<(usize, usize)> {
+        let (mut closed, mut resized) = (0, 0);
+        for (position_id, symbol, mint_address, recorded) in self.database.get_open_position_holdings().await? {
+            let Some(mint_address) = mint_address else {
+                warn!("Cannot reconcile {} (#{}): no mint address recorded", symbol, position_id);
+                continue;
+            };
+
+            let balance = match self.get_token_balance(&mint_address).await {
+                Ok(balance) => balance,
+                Err(e) => {
+                    warn!("Cannot reconcile {} (#{}): {}", symbol, position_id, e);
+                    continue;
+                }
+            };
+
+            match reconcile_action(recorded, balance) {
+                Reconcile::InSync => {}
+                Reconcile::Close => {
+                    warn!("Position {} (#{}) is open in the DB but the wallet holds none, closing", symbol, position_id);
+                    // The real exit price is unknown, so no P&L is booked
+                    self.database
+                        .close_position(position_id, 0.0, 0.0, 0.0, "reconciled_no_balance")
+                        .await?;
+                    let mut rm = self.risk_manager.lock().await;
+                    rm.forget_position(&symbol);
+                    metrics::update_portfolio(&rm.get_metrics());
+                    closed += 1;
+                }
+                Reconcile::Resize => {
+                    warn!("Position {} (#{}) records {:?} tokens but the wallet holds {}, updating",
+                          symbol, position_id, recorded, balance);
+                    self.database.update_token_amount(position_id, balance).await?;
+                    resized += 1;
+                }
+            }
+        }
+        Ok((closed, resized))
+    }
+
+    /// Run `reconcile_positions` every `every` until `shutdown` fires
+    pub async fn run_reconciler(&self, every: Duration, shutdown: CancellationToken) {
+        let mut interval = tokio::time::interval(every);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = shutdown.cancelled() => break,
+            }
+            match self.reconcile_positions().await {
+                Ok((0, 0)) => {}
+                Ok((closed, resized)) => info!("Reconciled positions: {} closed, {} resized", closed, resized),
+                Err(e) => error!("Position reconciliation failed: {}", e),
+            }
+        }
+    }
+
     /// Sign and send a transaction
     async fn sign_and_send_transaction(&self, tx_b64: &str) -> Result<Signature> {
         // Decode base64 transaction
@@ -286,6 +482,39 @@ impl ExecutionEngine {
         Ok(signature)
     }
 
+    /// Network fees a landed swap paid, or None with `record_fees` off or when
+    /// the transaction can't be read; a missing fee never fails the trade
+    async fn swap_fees_sol(&self, signature: &Signature) -> Option<f64> {
+        if !self.config.record_fees {
+            return None;
+        }
+        match self.transaction_fee_sol(signature) {
+            Ok(fees) => {
+                info!("   Fees: {:.6} SOL", fees);
+                Some(fees)
+            }
+            Err(e) => {
+                warn!("   Couldn't read fees for {}: {}", signature, e);
+                None
+            }
+        }
+    }
+
+    /// Lamports a confirmed transaction paid in fees (base + priority), in SOL
+    fn transaction_fee_sol(&self, signature: &Signature) -> Result<f64> {
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        let transaction = self.rpc_client
+            .get_transaction_with_config(signature, config)
+            .map_err(|e| anyhow!("Failed to get transaction: {}", e))?;
+        let meta = transaction.transaction.meta
+            .ok_or_else(|| anyhow!("Transaction has no status metadata"))?;
+        Ok(from_base_units(meta.fee, SOL_DECIMALS))
+    }
+
     /// Get current SOL balance
     async fn get_sol_balance(&self) -> Result<f64> {
         let balance = self.rpc_client
@@ -295,6 +524,38 @@ impl ExecutionEngine {
         Ok(balance as f64 / 1_000_000_000.0)
     }
 
+    /// Current SOL price in USD from Jupiter
+    async fn sol_price_usd(&self) -> Result<f64> {
+        let prices = self.jupiter
+            .get_tokens_price(&[self.config.sol_mint.clone()])
+            .await
+            .map_err(|e| anyhow!("Jupiter price request failed: {:?}", e))?;
+
+        match prices.get(&self.config.sol_mint) {
+            Some(price) if price.usd_price > 0.0 => Ok(price.usd_price),
+            _ => Err(anyhow!("No SOL price from Jupiter")),
+        }
+    }
+
+    /// Decimals of an SPL mint, cached after the first lookup
+    async fn mint_decimals(&self, mint_address: &str) -> Result<u8> {
+        if mint_address == self.config.sol_mint {
+            return Ok(SOL_DECIMALS);
+        }
+        if let Some(decimals) = self.mint_decimals.lock().unwrap().get(mint_address) {
+            return Ok(*decimals);
+        }
+
+        let mint_pubkey = Pubkey::from_str(mint_address)?;
+        let decimals = self.rpc_client
+            .get_token_supply(&mint_pubkey)
+            .map_err(|e| anyhow!("Failed to get decimals for {}: {}", mint_address, e))?
+            .decimals;
+
+        self.mint_decimals.lock().unwrap().insert(mint_address.to_string(), decimals);
+        Ok(decimals)
+    }
+
     /// Get token balance for a specific mint
     pub async fn get_token_balance(&self, mint_address: &str) -> Result<f64> {
         let mint_pubkey = Pubkey::from_str(mint_address)?;
@@ -307,13 +568,15 @@ impl ExecutionEngine {
             )
             .map_err(|e| anyhow!("Failed to get token accounts: {}", e))?;
 
-        if token_accounts.is_empty() {
-            return Ok(0.0);
+        let mut balance = 0.0;
+        for account in token_accounts {
+            let account_pubkey = Pubkey::from_str(&account.pubkey)?;
+            let amount = self.rpc_client
+                .get_token_account_balance(&account_pubkey)
+                .map_err(|e| anyhow!("Failed to get token balance: {}", e))?;
+            balance += from_base_units(parse_amount(&amount.amount)?, amount.decimals);
         }
-
-        // Parse balance from first account
-        // TODO: Properly parse token account data
-        Ok(0.0)
+        Ok(balance)
     }
 }
 
@@ -329,9 +592,138 @@ pub struct ExecutionResult {
     pub position_id: i64,
 }
 
-/// Stub function for compatibility with existing code
+/// Outcome of [`ExecutionEngine::close_all_positions`]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CloseAllReport {
+    pub closed: Vec<ClosedPosition>,
+    pub failed: Vec<FailedClose>,
+}
+
+/// A position `close_all_positions` sold
+#[derive(Debug, Clone, Serialize)]
+pub struct ClosedPosition {
+    pub position_id: i64,
+    pub symbol: String,
+    pub signature: String,
+    pub sol_received: f64,
+}
+
+/// A position `close_all_positions` couldn't sell, and why
+#[derive(Debug, Clone, Serialize)]
+pub struct FailedClose {
+    pub position_id: i64,
+    pub symbol: String,
+    pub error: String,
+}
+
+/// What reconciliation should do with an open position
+#[derive(Debug, PartialEq)]
+enum Reconcile {
+    InSync,
+    Close,
+    Resize,
+}
+
+fn reconcile_action(recorded: Option<f64>, on_chain: f64) -> Reconcile {
+    if on_chain <= 0.0 {
+        return Reconcile::Close;
+    }
+    match recorded {
+        Some(recorded) if (on_chain - recorded).abs() <= recorded * RECONCILE_TOLERANCE => Reconcile::InSync,
+        _ => Reconcile::Resize,
+    }
+}
+
+/// Raw on-chain amount for a UI amount of a token with `decimals`
+fn to_base_units(amount: f64, decimals: u8) -> u64 {
+    (amount * 10f64.powi(decimals as i32)).round() as u64
+}
+
+/// UI amount for a raw on-chain amount of a token with `decimals`
+fn from_base_units(raw: u64, decimals: u8) -> f64 {
+    raw as f64 / 10f64.powi(decimals as i32)
+}
+
+/// Parse a raw amount string from a Jupiter quote
+fn parse_amount(raw: &str) -> Result<u64> {
+    raw.parse().map_err(|_| anyhow!("Invalid amount in quote: {}", raw))
+}
+
+/// Load the trading wallet from a Solana keypair file (JSON byte array, as `solana-keygen` writes)
+pub fn read_wallet(path: &str) -> Result<Keypair> {
+    solana_sdk::signature::read_keypair_file(path)
+        .map_err(|e| anyhow!("Failed to read wallet keypair {}: {}", path, e))
+}
+
+/// Log-only stand-in for live signals when no wallet is configured
 pub async fn execute_trade(signal: Signal) -> Result<()> {
-    info!("[Execution] Received signal with confidence {:.3}", signal.confidence);
-    info!("[Execution] Note: Use ExecutionEngine for real trading");
+    info!("[Execution] Received {} signal with confidence {:.3}", signal.market, signal.confidence);
+    info!("[Execution] No wallet configured (--wallet-keypair), not trading it");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote() -> QuoteResponse {
+        serde_json::from_value(serde_json::json!({
+            "inputMint": "So11111111111111111111111111111111111111112",
+            "inAmount": "100000000",
+            "outputMint": "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263",
+            "outAmount": "4200000000",
+            "otherAmountThreshold": "4179000000",
+            "swapMode": "ExactIn",
+            "slippageBps": 50,
+            "priceImpactPct": "0.001",
+            "routePlan": [],
+            "contextSlot": 1,
+            "timeTaken": 0.01
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_amounts_scale_by_decimals() {
+        assert_eq!(to_base_units(0.25, SOL_DECIMALS), 250_000_000);
+        // Typical pump.fun token with 6 decimals
+        assert_eq!(to_base_units(1_500.5, 6), 1_500_500_000);
+        assert_eq!(to_base_units(0.1, 6), 100_000);
+        assert_eq!(from_base_units(1_500_500_000, 6), 1_500.5);
+        assert_eq!(from_base_units(parse_amount("100000000").unwrap(), SOL_DECIMALS), 0.1);
+        assert!(parse_amount("1.5").is_err());
+    }
+
+    #[test]
+    fn test_reconcile_action() {
+        assert_eq!(reconcile_action(Some(1_000.0), 0.0), Reconcile::Close);
+        assert_eq!(reconcile_action(None, 0.0), Reconcile::Close);
+        assert_eq!(reconcile_action(Some(1_000.0), 1_005.0), Reconcile::InSync);
+        assert_eq!(reconcile_action(Some(1_000.0), 400.0), Reconcile::Resize);
+        // Positions recorded before token amounts were tracked
+        assert_eq!(reconcile_action(None, 400.0), Reconcile::Resize);
+    }
+
+    #[test]
+    fn test_swap_request_carries_fee_and_slippage() {
+        let config = ExecutionConfig::default();
+        let request = serde_json::to_value(config.swap_request("Wallet111", &quote())).unwrap();
+
+        assert_eq!(
+            request["prioritizationFeeLamports"]["priorityLevelWithMaxLamports"]["maxLamports"],
+            config.priority_fee_lamports
+        );
+        assert_eq!(request["dynamicSlippage"], true);
+        assert_eq!(request["dynamicComputeUnitLimit"], true);
+        assert_eq!(request["quoteResponse"]["slippageBps"], 50);
+
+        let fixed = ExecutionConfig {
+            priority_fee_lamports: 0,
+            dynamic_slippage: false,
+            ..config
+        };
+        let request = serde_json::to_value(fixed.swap_request("Wallet111", &quote())).unwrap();
+        assert!(request.get("prioritizationFeeLamports").is_none());
+        assert_eq!(request["dynamicSlippage"], false);
+    }
+}
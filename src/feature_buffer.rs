@@ -1,9 +1,14 @@
 use crate::types::TickData;
+use tracing::warn;
+
+/// Features per tick in `extract_features`: price, price diff, volume
+pub const FEATURE_COUNT: usize = 3;
 
 /// Simple rolling buffer to collect ticks for feature extraction.
 pub struct FeatureBuffer {
     window_size: usize,
     data: Vec<TickData>,
+    last_price: Option<f64>,
 }
 
 impl FeatureBuffer {
@@ -11,24 +16,71 @@ impl FeatureBuffer {
         FeatureBuffer {
             window_size,
             data: Vec::with_capacity(window_size),
+            last_price: None,
         }
     }
 
-    pub fn push(&mut self, tick: TickData) {
+    /// Add a tick, sanitizing bad values so they never reach the model.
+    /// A non-finite or non-positive price is forward-filled from the last good
+    /// tick, or rejected if there isn't one yet; a bad volume becomes 0.
+    /// Returns whether the tick was buffered.
+    pub fn push(&mut self, mut tick: TickData) -> bool {
+        if !tick.price.is_finite() || tick.price <= 0.0 {
+            match self.last_price {
+                Some(last) => {
+                    warn!("Bad price {} for {}, forward-filling {}", tick.price, tick.symbol, last);
+                    tick.price = last;
+                }
+                None => {
+                    warn!("Bad price {} for {}, dropping tick", tick.price, tick.symbol);
+                    return false;
+                }
+            }
+        }
+        if !tick.volume.is_finite() || tick.volume < 0.0 {
+            tick.volume = 0.0;
+        }
+
+        self.last_price = Some(tick.price);
         self.data.push(tick);
         if self.data.len() > self.window_size {
             self.data.remove(0);
         }
+        true
     }
 
     pub fn is_ready(&self) -> bool {
         self.data.len() == self.window_size
     }
 
-    /// Extracts a simple feature tensor of shape (1, window_size, 3):
+    /// Warm-up progress, 0.0 (empty) to 1.0 (ready)
+    pub fn fill_ratio(&self) -> f64 {
+        if self.window_size == 0 {
+            return 1.0;
+        }
+        self.data.len() as f64 / self.window_size as f64
+    }
+
+    /// Standard deviation of tick-to-tick returns across the window,
+    /// 0.0 until there are at least two ticks.
+    pub fn volatility(&self) -> f64 {
+        let returns: Vec<f64> = self
+            .data
+            .windows(2)
+            .map(|pair| pair[1].price / pair[0].price - 1.0)
+            .collect();
+        if returns.is_empty() {
+            return 0.0;
+        }
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / returns.len() as f64;
+        variance.sqrt()
+    }
+
+    /// Extracts a simple feature tensor of shape (1, window_size, FEATURE_COUNT):
     /// [price, price_diff, volume] per tick.
     pub fn extract_features(&self) -> ndarray::Array3<f32> {
-        let mut arr = ndarray::Array3::<f32>::zeros((1, self.window_size, 3));
+        let mut arr = ndarray::Array3::<f32>::zeros((1, self.window_size, FEATURE_COUNT));
         for (i, tick) in self.data.iter().enumerate() {
             let price = tick.price as f32;
             let volume = tick.volume as f32;
@@ -44,3 +96,72 @@ impl FeatureBuffer {
         arr
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tick(price: f64, volume: f64) -> TickData {
+        TickData {
+            symbol: "BONK".to_string(),
+            price,
+            volume,
+            timestamp: 0,
+            source: "test".to_string(),
+            market: "BONK/SOL".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_bad_ticks_are_filled_or_rejected() {
+        let mut buf = FeatureBuffer::new(4);
+
+        // Nothing to forward-fill from yet
+        assert!(!buf.push(tick(f64::NAN, 1.0)));
+        assert_eq!(buf.fill_ratio(), 0.0);
+
+        assert!(buf.push(tick(2.0, 1.0)));
+        assert!(buf.push(tick(0.0, f64::INFINITY)));
+        assert!(buf.push(tick(-1.0, -5.0)));
+        assert!(buf.push(tick(f64::NAN, f64::NAN)));
+        assert!(buf.is_ready());
+        assert_eq!(buf.fill_ratio(), 1.0);
+
+        let features = buf.extract_features();
+        assert!(features.iter().all(|v| v.is_finite()));
+        for i in 0..4 {
+            assert_eq!(features[[0, i, 0]], 2.0);
+        }
+        assert_eq!(features[[0, 1, 2]], 0.0);
+    }
+
+    #[test]
+    fn test_identical_prices_have_zero_variance() {
+        let mut buf = FeatureBuffer::new(8);
+        for _ in 0..8 {
+            buf.push(tick(0.000012, 100.0));
+        }
+
+        let features = buf.extract_features();
+        assert!(features.iter().all(|v| v.is_finite()));
+
+        let prices: Vec<f32> = (0..8).map(|i| features[[0, i, 0]]).collect();
+        let mean = prices.iter().sum::<f32>() / prices.len() as f32;
+        let variance = prices.iter().map(|p| (p - mean).powi(2)).sum::<f32>() / prices.len() as f32;
+        assert_eq!(variance, 0.0);
+        assert!((0..8).all(|i| features[[0, i, 1]] == 0.0));
+        assert_eq!(buf.volatility(), 0.0);
+    }
+
+    #[test]
+    fn test_volatility() {
+        let mut buf = FeatureBuffer::new(3);
+        buf.push(tick(1.0, 1.0));
+        assert_eq!(buf.volatility(), 0.0);
+
+        // Returns of +10% and -10%
+        buf.push(tick(1.1, 1.0));
+        buf.push(tick(0.99, 1.0));
+        assert!((buf.volatility() - 0.1).abs() < 1e-9);
+    }
+}
@@ -1,13 +1,16 @@
-use anyhow::Result;
-use ndarray::Array3;
-use once_cell::sync::Lazy;
+use crate::metrics::INFERENCE_LATENCY;
+use anyhow::{anyhow, bail, Result};
+use ndarray::{Array3, Axis};
 use ort::execution_providers::CPUExecutionProvider;
 use ort::init;
 use ort::session::builder::{GraphOptimizationLevel, SessionBuilder};
 use ort::session::Session;
 use ort::{inputs, value::Tensor};
-use prometheus::{register_histogram_vec, HistogramVec};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
 use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
 
 /// Inference engine powered by ONNX Runtime.
 /// ONNX Runtime inference engine for sequential features.
@@ -15,68 +18,305 @@ use tokio::sync::Mutex;
 /// ONNX Runtime inference engine for sequential features.
 pub struct InferenceEngine {
     /// ONNX Runtime session; protected by a mutex for mutable access
+    /// (and swapped under it on reload, so a predict never sees half a model)
     session: Mutex<Session>,
+    /// File the current session was loaded from
+    model: RwLock<ModelInfo>,
+    /// I/O layout every loaded model is checked against
+    config: ModelConfig,
 }
 
-/// Histogram for inference latency in seconds.
-static INFERENCE_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
-    register_histogram_vec!(
-        "inference_latency_seconds",
-        "Inference latency in seconds",
-        &[]
-    )
-    .expect("failed to create histogram")
-});
+/// Tensor names and input shape the model must have.
+/// Unset names fall back to the model's first input and first two outputs.
+#[derive(Debug, Clone)]
+pub struct ModelConfig {
+    pub input_name: Option<String>,
+    pub embedding_output: Option<String>,
+    pub reconstruction_output: Option<String>,
+    /// Ticks per input window
+    pub window_size: usize,
+    /// Features per tick
+    pub feature_count: usize,
+}
+
+impl ModelConfig {
+    pub fn new(window_size: usize, feature_count: usize) -> Self {
+        Self {
+            input_name: None,
+            embedding_output: None,
+            reconstruction_output: None,
+            window_size,
+            feature_count,
+        }
+    }
+}
+
+/// Names of the tensors a session is fed and read from
+struct ModelIo {
+    input: String,
+    embedding: String,
+    reconstruction: String,
+}
+
+/// Identifies the loaded model so logs show which version produced a signal
+#[derive(Debug, Clone)]
+pub struct ModelInfo {
+    pub path: PathBuf,
+    /// FNV-1a hash of the model file, hex encoded
+    pub hash: String,
+    pub size_bytes: usize,
+    pub modified: Option<SystemTime>,
+    /// Most windows the model takes per run: None for a dynamic batch dim
+    pub max_batch: Option<usize>,
+}
 
 impl InferenceEngine {
     /// Create a new inference engine, loading the specified ONNX model.
-    pub async fn new(model_path: &str) -> Result<Self> {
+    /// Fails if the model's tensors don't match `config`.
+    pub async fn new(model_path: &str, config: ModelConfig) -> Result<Self> {
         // Commit global environment with CPU execution provider (boolean indicates first init)
         let _ = init()
             .with_execution_providers([CPUExecutionProvider::default().build()])
             .commit()?;
-        // Build session with optimizations and load model
-        let session = SessionBuilder::new()?
-            .with_optimization_level(GraphOptimizationLevel::Level3)?
-            .commit_from_file(model_path)?;
+        let (session, model) = load_session(Path::new(model_path), &config)?;
         Ok(Self {
             session: tokio::sync::Mutex::new(session),
+            model: RwLock::new(model),
+            config,
+        })
+    }
+
+    /// Currently loaded model
+    pub fn model_info(&self) -> ModelInfo {
+        self.model.read().unwrap().clone()
+    }
+
+    /// Swap in the model at `new_path` without stopping inference.
+    /// The new session is built off the runtime; if loading fails or the new
+    /// model doesn't match the config, the current model keeps serving and the
+    /// error is returned.
+    pub async fn reload(&self, new_path: &str) -> Result<ModelInfo> {
+        let path = PathBuf::from(new_path);
+        let config = self.config.clone();
+        let (session, model) = tokio::task::spawn_blocking(move || load_session(&path, &config)).await??;
+
+        let previous = self.model_info();
+        // Both swap under the session lock, so a predict never pairs one with the other's batch limit
+        let mut current = self.session.lock().await;
+        *current = session;
+        *self.model.write().unwrap() = model.clone();
+        drop(current);
+
+        tracing::info!(
+            "[Inference] reloaded model {} (hash {} -> {})",
+            model.path.display(),
+            previous.hash,
+            model.hash
+        );
+        Ok(model)
+    }
+
+    /// Poll the current model file every `interval` and reload it when it changes
+    pub fn watch_model(self: Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+
+                let current = self.model_info();
+                let modified = std::fs::metadata(&current.path)
+                    .and_then(|m| m.modified())
+                    .ok();
+                if modified.is_none() || modified == current.modified {
+                    continue;
+                }
+
+                let path = current.path.to_string_lossy().into_owned();
+                if let Err(e) = self.reload(&path).await {
+                    tracing::warn!("[Inference] model reload from {} failed: {}", path, e);
+                }
+            }
         })
     }
 
     /// Run inference on the provided feature tensor, returning (embedding, anomaly_score).
     pub async fn predict(&self, features: Array3<f32>) -> Result<(Vec<f32>, f32)> {
+        self.predict_batch(vec![features])
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("model returned no outputs"))
+    }
+
+    /// Run several feature tensors (e.g. one per market) through as few ONNX calls
+    /// as the model allows. Inputs are stacked along the batch axis, so they must
+    /// share a window size; a model with a fixed batch dim gets them that many at
+    /// a time. Returns (embedding, anomaly_score) per input, in input order.
+    pub async fn predict_batch(&self, batch: Vec<Array3<f32>>) -> Result<Vec<(Vec<f32>, f32)>> {
+        let mut session = self.session.lock().await;
+        let chunk = self.model.read().unwrap().max_batch.unwrap_or(batch.len()).max(1);
+        let mut results = Vec::with_capacity(batch.len());
+        for windows in batch.chunks(chunk) {
+            results.extend(self.run(&mut session, windows)?);
+        }
+        Ok(results)
+    }
+
+    /// One ONNX call over `batch` stacked into a single input
+    fn run(&self, session: &mut Session, batch: &[Array3<f32>]) -> Result<Vec<(Vec<f32>, f32)>> {
+        let batch_size = batch.len();
+
+        // Stack (1, window, features) inputs into (batch, window, features)
+        let views: Vec<_> = batch.iter().map(|features| features.view()).collect();
+        let stacked = ndarray::concatenate(Axis(0), &views)?;
         tracing::info!(
             "[Inference] running ONNX inference, input shape: {:?}",
-            features.dim()
+            stacked.dim()
         );
         // Flatten array into contiguous Vec and record shape
-        let shape: Vec<i64> = features.shape().iter().map(|&d| d as i64).collect();
-        let raw_data: Vec<f32> = features.into_raw_vec();
+        let shape: Vec<i64> = stacked.shape().iter().map(|&d| d as i64).collect();
+        let raw_data: Vec<f32> = stacked.into_raw_vec();
         // Build input tensor
-        let input = Tensor::from_array((shape.clone(), raw_data.clone()))?;
-        // Run session (positional inputs)
-        // Run inference (lock session for mutable access)
-        let mut session = self.session.lock().await;
+        let input = Tensor::from_array((shape, raw_data.clone()))?;
+        let io = model_io(session, &self.config)?;
         // Measure inference latency
-        let timer = INFERENCE_LATENCY
-            .with_label_values(&[] as &[&str])
-            .start_timer();
-        let outputs = session.run(inputs![input])?;
+        let timer = INFERENCE_LATENCY.start_timer();
+        let outputs = session.run(inputs![io.input.as_str() => input])?;
         timer.observe_duration();
 
-        // Extract embedding (first output) and reconstruction (second)
-        let emb_arr = outputs[0].try_extract_array::<f32>()?;
-        let rec_arr = outputs[1].try_extract_array::<f32>()?;
-        let embedding: Vec<f32> = emb_arr.iter().cloned().collect();
-        // Compute anomaly score as mean squared error over reconstruction
-        let mut mse = 0.0_f32;
-        for (a, b) in raw_data.iter().zip(rec_arr.iter()) {
-            mse += (a - b).powi(2);
+        // Extract embedding and reconstruction
+        let embeddings: Vec<f32> = outputs[io.embedding.as_str()].try_extract_array::<f32>()?.iter().cloned().collect();
+        let reconstructions: Vec<f32> = outputs[io.reconstruction.as_str()].try_extract_array::<f32>()?.iter().cloned().collect();
+        if embeddings.len() % batch_size != 0 || reconstructions.len() % batch_size != 0 {
+            bail!(
+                "model outputs ({} embedding, {} reconstruction values) don't split into batch of {}",
+                embeddings.len(),
+                reconstructions.len(),
+                batch_size
+            );
         }
-        if !raw_data.is_empty() {
-            mse /= raw_data.len() as f32;
-        }
-        Ok((embedding, mse))
+
+        let embedding_len = embeddings.len() / batch_size;
+        let reconstruction_len = reconstructions.len() / batch_size;
+        let input_len = raw_data.len() / batch_size;
+
+        Ok((0..batch_size)
+            .map(|i| {
+                let embedding = embeddings[i * embedding_len..(i + 1) * embedding_len].to_vec();
+                let input = &raw_data[i * input_len..(i + 1) * input_len];
+                let reconstruction = &reconstructions[i * reconstruction_len..(i + 1) * reconstruction_len];
+
+                // Compute anomaly score as mean squared error over reconstruction
+                let mut mse = 0.0_f32;
+                for (a, b) in input.iter().zip(reconstruction.iter()) {
+                    mse += (a - b).powi(2);
+                }
+                if !input.is_empty() {
+                    mse /= input.len() as f32;
+                }
+                (embedding, mse)
+            })
+            .collect())
+    }
+}
+
+/// Build an optimized session from a model file, fingerprinting the exact bytes loaded
+/// and checking its tensors match `config`
+fn load_session(path: &Path, config: &ModelConfig) -> Result<(Session, ModelInfo)> {
+    let bytes = std::fs::read(path)?;
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+
+    // Build session with optimizations and load model
+    let session = SessionBuilder::new()?
+        .with_optimization_level(GraphOptimizationLevel::Level3)?
+        .commit_from_memory(&bytes)?;
+
+    let io = model_io(&session, config)
+        .map_err(|e| anyhow!("model {} doesn't fit the pipeline: {}", path.display(), e))?;
+    let input = session.inputs.iter().find(|i| i.name == io.input).expect("input resolved by model_io");
+    let dims = input
+        .input_type
+        .tensor_shape()
+        .ok_or_else(|| anyhow!("model {} input '{}' is not a tensor", path.display(), io.input))?;
+    let max_batch = check_input_shape(dims, config.window_size, config.feature_count)
+        .map_err(|e| anyhow!("model {} input '{}': {}", path.display(), io.input, e))?;
+
+    let model = ModelInfo {
+        path: path.to_path_buf(),
+        hash: format!("{:016x}", fnv1a(&bytes)),
+        size_bytes: bytes.len(),
+        modified,
+        max_batch,
+    };
+    tracing::info!(
+        "[Inference] loaded model {} (hash {}, {} bytes, batch {})",
+        model.path.display(),
+        model.hash,
+        model.size_bytes,
+        max_batch.map_or("dynamic".to_string(), |n| n.to_string())
+    );
+    Ok((session, model))
+}
+
+/// Resolve the configured tensor names against the session, failing on any the model lacks
+fn model_io(session: &Session, config: &ModelConfig) -> Result<ModelIo> {
+    let inputs: Vec<&str> = session.inputs.iter().map(|i| i.name.as_str()).collect();
+    let outputs: Vec<&str> = session.outputs.iter().map(|o| o.name.as_str()).collect();
+    let pick = |configured: &Option<String>, available: &[&str], index: usize, kind: &str| match configured {
+        Some(name) if available.contains(&name.as_str()) => Ok(name.clone()),
+        Some(name) => Err(anyhow!("no {} named '{}' (model has {:?})", kind, name, available)),
+        None => available
+            .get(index)
+            .map(|name| name.to_string())
+            .ok_or_else(|| anyhow!("expected at least {} {}s, model has {:?}", index + 1, kind, available)),
+    };
+    Ok(ModelIo {
+        input: pick(&config.input_name, &inputs, 0, "input")?,
+        embedding: pick(&config.embedding_output, &outputs, 0, "output")?,
+        reconstruction: pick(&config.reconstruction_output, &outputs, 1, "output")?,
+    })
+}
+
+/// Check a (batch, window, features) input shape against the feature window.
+/// Dynamic dimensions (negative) match anything. Returns the fixed batch size,
+/// if the model has one.
+fn check_input_shape(dims: &[i64], window_size: usize, feature_count: usize) -> Result<Option<usize>> {
+    let [batch, window, features] = dims else {
+        bail!("expected shape (batch, {}, {}), model has {:?}", window_size, feature_count, dims);
+    };
+    let fits = |dim: i64, expected: usize| dim < 0 || dim as usize == expected;
+    if !fits(*window, window_size) || !fits(*features, feature_count) {
+        bail!(
+            "expected shape (batch, {}, {}) = {} values per window, model has {:?}",
+            window_size,
+            feature_count,
+            window_size * feature_count,
+            dims
+        );
+    }
+    // A fixed batch dim caps how many windows go through one run
+    Ok((*batch > 0).then_some(*batch as usize))
+}
+
+/// 64-bit FNV-1a, enough to tell model versions apart in logs
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_input_shape() {
+        assert_eq!(check_input_shape(&[1, 50, 3], 50, 3).unwrap(), Some(1));
+        assert_eq!(check_input_shape(&[8, 50, 3], 50, 3).unwrap(), Some(8));
+        assert_eq!(check_input_shape(&[-1, 50, 3], 50, 3).unwrap(), None);
+        assert_eq!(check_input_shape(&[-1, -1, 3], 50, 3).unwrap(), None);
+
+        let err = check_input_shape(&[1, 30, 3], 50, 3).unwrap_err().to_string();
+        assert!(err.contains("(batch, 50, 3) = 150 values"), "{}", err);
+        assert!(check_input_shape(&[1, 50, 4], 50, 3).is_err());
+        assert!(check_input_shape(&[150], 50, 3).is_err());
     }
 }
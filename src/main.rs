@@ -3,22 +3,32 @@ mod execution;
 mod feature_buffer;
 mod inference;
 mod metrics;
+mod notify;
+mod paper;
 mod questdb;
+mod regime;
 mod risk_manager;
 mod types;
 mod vector_store;
 mod websocket;
 
 use clap::Parser;
-use execution::execute_trade;
+use database::{AsyncDatabase, Database};
+use execution::{execute_trade, ExecutionConfig, ExecutionEngine};
 use feature_buffer::FeatureBuffer;
-use inference::InferenceEngine;
+use inference::{InferenceEngine, ModelConfig};
 use once_cell::sync::Lazy;
 use prometheus::{gather, Encoder, TextEncoder};
 use questdb::QuestDBClient;
+use risk_manager::{RiskConfig, RiskManager, SizingMethod};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 use tracing_subscriber::prelude::*;
-use vector_store::VectorStore;
+use vector_store::{DistanceMetric, VectorStore};
 use warp::Filter;
 use websocket::stream_jupiter_websocket;
 
@@ -45,89 +55,609 @@ async fn main() -> anyhow::Result<()> {
         /// WebSocket URL for market data
         #[arg(long, default_value = "wss://api.jup.ag/ws/v1/quotes")]
         ws_url: String,
-        /// Market symbol (e.g. BONK/SOL)
-        #[arg(long, default_value = "BONK/SOL")]
-        market: String,
-        /// QuestDB HTTP URL
-        #[arg(long, default_value = "http://localhost:9000")]
+        /// Market symbol (e.g. BONK/SOL); repeat to trade several markets on one connection
+        #[arg(long = "market", default_value = "BONK/SOL")]
+        markets: Vec<String>,
+        /// QuestDB ingress config (e.g. `http::addr=localhost:9000;`)
+        #[arg(long, default_value = "http::addr=localhost:9000;")]
         questdb_url: String,
+        /// QuestDB HTTP API base URL for replay queries; defaults to the ingress config's `addr`
+        #[arg(long)]
+        questdb_query_url: Option<String>,
+        /// Ticks buffered before writing a batch to QuestDB
+        #[arg(long, default_value_t = questdb::DEFAULT_BATCH_ROWS)]
+        questdb_batch_rows: usize,
+        /// Write buffered ticks to QuestDB at least this often, in milliseconds
+        #[arg(long, default_value_t = 1000)]
+        questdb_flush_ms: u64,
+        /// Replay recorded ticks from QuestDB starting at this time (epoch millis) instead of the live feed;
+        /// replays always run in simulate mode
+        #[arg(long, conflicts_with = "wallet_keypair")]
+        replay_from: Option<i64>,
+        /// Stop the replay at this time (epoch millis); defaults to the latest recorded tick
+        #[arg(long, requires = "replay_from")]
+        replay_to: Option<i64>,
         /// Qdrant HTTP URL
         #[arg(long, default_value = "http://localhost:6334")]
         qdrant_url: String,
         /// ONNX model path
         #[arg(long, default_value = "lstm_autoencoder.onnx")]
         model_path: String,
+        /// ONNX input tensor name (defaults to the model's first input)
+        #[arg(long)]
+        onnx_input: Option<String>,
+        /// ONNX output tensor holding the embedding (defaults to the first output)
+        #[arg(long)]
+        onnx_embedding_output: Option<String>,
+        /// ONNX output tensor holding the reconstruction (defaults to the second output)
+        #[arg(long)]
+        onnx_reconstruction_output: Option<String>,
+        /// Check the model file every N seconds and hot-reload it on change (0 = off)
+        #[arg(long, default_value_t = 0)]
+        model_watch_secs: u64,
         /// Feature rolling window size
         #[arg(long, default_value_t = 50)]
         window_size: usize,
         /// Confidence threshold for signals
         #[arg(long, default_value_t = 0.8)]
         threshold: f32,
+        /// Similar stored patterns a signal needs before it is traded
+        #[arg(long, default_value_t = 2)]
+        min_patterns: usize,
+        /// Skip signals when per-tick return volatility over the window exceeds this (0 = no limit)
+        #[arg(long, default_value_t = 0.05)]
+        max_volatility: f64,
+        /// Volatility at or below which the market is calm (0 = never calm)
+        #[arg(long, default_value_t = 0.0005)]
+        calm_volatility: f64,
+        /// Volatility above which the market is volatile (0 = never volatile)
+        #[arg(long, default_value_t = 0.02)]
+        volatile_volatility: f64,
+        /// What to do with signals in a calm market (trade, reduce or stand-down)
+        #[arg(long, default_value = "stand-down")]
+        calm_action: String,
+        /// What to do with signals in a normal market (trade, reduce or stand-down)
+        #[arg(long, default_value = "trade")]
+        normal_action: String,
+        /// What to do with signals in a volatile market (trade, reduce or stand-down)
+        #[arg(long, default_value = "reduce")]
+        volatile_action: String,
+        /// Position size multiplier for regimes set to reduce
+        #[arg(long, default_value_t = 0.5)]
+        regime_reduced_size: f64,
+        /// Anomaly score above which a market is treated as anomalous (0 = off)
+        #[arg(long, default_value_t = 0.5)]
+        anomaly_threshold: f32,
+        /// Suppress trading in an anomalous market for this many seconds
+        #[arg(long, default_value_t = 300)]
+        anomaly_cooldown_secs: u64,
+        /// Minimum similarity for a stored pattern to count as a match
+        #[arg(long, default_value_t = 0.8)]
+        min_similarity: f32,
+        /// Vector similarity metric (cosine or dot)
+        #[arg(long, default_value = "cosine")]
+        vector_metric: String,
+        /// Skip storing patterns within this similarity of an existing one (0 = store all; cosine only)
+        #[arg(long, default_value_t = 0.02)]
+        pattern_dedup_epsilon: f32,
+        /// Drop stored patterns older than this many seconds (0 = keep forever)
+        #[arg(long, default_value_t = 86_400)]
+        pattern_retention_secs: u64,
         /// HTTP port for metrics & health endpoints
         #[arg(long, default_value_t = 9090)]
         metrics_port: u16,
+        /// SQLite database path for positions and trades
+        #[arg(long, default_value = "trading_engine.db")]
+        database_path: String,
+        /// Starting capital in USD for the risk manager
+        #[arg(long, default_value_t = 1000.0)]
+        starting_capital: f64,
+        /// Size positions to risk this many USD per trade using ATR instead of Kelly
+        #[arg(long)]
+        risk_per_trade_usd: Option<f64>,
+        /// Stop distance in ATRs for ATR sizing
+        #[arg(long, default_value_t = 2.0)]
+        atr_mult: f64,
+        /// Prices older than this many milliseconds can't trigger stops (0 = no limit)
+        #[arg(long, default_value_t = 10_000)]
+        max_price_age_ms: u64,
+        /// What to do with a position whose price went stale (hold or exit)
+        #[arg(long, default_value = "hold")]
+        stale_price_action: String,
+        /// Market-sell open positions on shutdown instead of leaving them open
+        #[arg(long)]
+        close_positions_on_shutdown: bool,
+        /// POST buy/sell/anomaly notifications to this webhook (Discord, Slack, Telegram or any JSON endpoint)
+        #[arg(long)]
+        webhook_url: Option<String>,
+        /// Events sent to the webhook, comma-separated (buy, sell, anomaly)
+        #[arg(long, default_value = "buy,sell,anomaly")]
+        webhook_events: String,
+        /// Solana keypair file of the trading wallet; without it live signals are only logged
+        #[arg(long)]
+        wallet_keypair: Option<String>,
+        /// Solana RPC URL for sending and confirming swaps
+        #[arg(long, default_value = "https://api.mainnet-beta.solana.com")]
+        rpc_url: String,
+        /// Jupiter swap API base URL
+        #[arg(long, default_value = "https://lite-api.jup.ag")]
+        jupiter_url: String,
+        /// Token mint to buy for a */SOL market, as MARKET=MINT (e.g. BONK/SOL=DezXAZ...); repeat per market
+        #[arg(long = "mint")]
+        mints: Vec<String>,
+        /// live trades signals; simulate paper-trades them through the risk manager and books them in the database
+        #[arg(long, default_value = "live")]
+        mode: String,
+        /// Bearer token for the admin endpoints `/halt`, `/resume` and `/close_all` (or ADMIN_TOKEN); unset disables them
+        #[arg(long)]
+        admin_token: Option<String>,
     }
     let opt = Opt::parse();
+    let mut mode: paper::RunMode = opt.mode.parse()?;
+    metrics::init();
+    // Replayed ticks are history, so trading them would swap at today's prices
+    if opt.replay_from.is_some() && mode == paper::RunMode::Live {
+        tracing::info!("Replaying, so switching to simulate mode");
+        mode = paper::RunMode::Simulate;
+    }
+    if mode == paper::RunMode::Simulate {
+        tracing::info!("Simulate mode: signals are paper-traded, nothing reaches Jupiter or the wallet");
+    }
+
+    // Cancelled on SIGINT/SIGTERM; every long-running task exits on it
+    let shutdown = CancellationToken::new();
 
     // Channels for ticks and trading signals
     let (tick_tx, mut tick_rx) = tokio::sync::mpsc::unbounded_channel();
     let (signal_tx, mut signal_rx) = tokio::sync::mpsc::unbounded_channel();
+    // Simulate mode marks paper positions to each accepted tick's price
+    let (price_tx, mut price_rx) = tokio::sync::mpsc::unbounded_channel::<(String, f64, std::time::Instant)>();
 
     // Initialize clients and engines
-    let questdb = QuestDBClient::new(&opt.questdb_url);
-    let inference = Arc::new(InferenceEngine::new(&opt.model_path).await?);
-    let vector_store = Arc::new(VectorStore::new(&opt.qdrant_url).await?);
+    let mut questdb = QuestDBClient::new(&opt.questdb_url).with_batch_rows(opt.questdb_batch_rows);
+    if let Some(query_url) = &opt.questdb_query_url {
+        questdb = questdb.with_query_url(query_url);
+    }
+    let model_config = ModelConfig {
+        input_name: opt.onnx_input.clone(),
+        embedding_output: opt.onnx_embedding_output.clone(),
+        reconstruction_output: opt.onnx_reconstruction_output.clone(),
+        ..ModelConfig::new(opt.window_size, feature_buffer::FEATURE_COUNT)
+    };
+    let inference = Arc::new(InferenceEngine::new(&opt.model_path, model_config).await?);
+    let model_watcher = (opt.model_watch_secs > 0).then(|| {
+        Arc::clone(&inference).watch_model(Duration::from_secs(opt.model_watch_secs))
+    });
+    let vector_metric: DistanceMetric = opt.vector_metric.parse()?;
+    let mut vector_store = VectorStore::new(&opt.qdrant_url).await?.with_metric(vector_metric);
+    if opt.pattern_dedup_epsilon > 0.0 {
+        if vector_metric == DistanceMetric::Cosine {
+            vector_store = vector_store.with_dedup(opt.pattern_dedup_epsilon);
+        } else {
+            tracing::warn!("Pattern dedup only works with the cosine metric, storing every pattern");
+        }
+    }
+    let vector_store = Arc::new(vector_store);
+    let database = AsyncDatabase::new(Database::new(&opt.database_path)?);
+    let notifier = notify::notifier(opt.webhook_url.as_deref(), &notify::parse_events(&opt.webhook_events)?);
+    // Markets whose anomaly score spiked, shared with the `/anomalies` endpoint
+    let anomaly_guard = Arc::new(std::sync::Mutex::new(types::AnomalyGuard::new(
+        opt.anomaly_threshold,
+        Duration::from_secs(opt.anomaly_cooldown_secs),
+    )));
+    let mut risk_config = RiskConfig {
+        max_price_age_ms: opt.max_price_age_ms,
+        stale_price_action: opt.stale_price_action.parse()?,
+        ..RiskConfig::default()
+    };
+    if let Some(risk_per_trade_usd) = opt.risk_per_trade_usd {
+        risk_config.sizing = SizingMethod::AtrTarget {
+            risk_per_trade_usd,
+            atr_mult: opt.atr_mult,
+        };
+    }
+    let mut risk_manager = RiskManager::new(risk_config, opt.starting_capital);
+    // Positions an earlier run in the same mode left open, so their stops and sells find them
+    let paper_mode = mode == paper::RunMode::Simulate;
+    let mut restored_paper = HashMap::new();
+    for (id, symbol, entry_price, _, size_usd) in database.get_open_positions(paper_mode).await? {
+        if let Err(e) = risk_manager.open_position(symbol.clone(), entry_price, size_usd) {
+            tracing::warn!("Not restoring open position #{} {}: {}", id, symbol, e);
+            continue;
+        }
+        tracing::info!("Restoring open position #{} {} at {:.6}, size ${:.2}", id, symbol, entry_price, size_usd);
+        if paper_mode {
+            restored_paper.insert(symbol, id);
+        }
+    }
+    let risk_manager = Arc::new(tokio::sync::Mutex::new(risk_manager));
 
-    // Task 1: WebSocket ingestion
-    // Task 1: WebSocket ingestion
-    {
+    // Live trading needs a wallet; without one signals only reach the `execute_trade` log
+    let mints = parse_mints(&opt.mints)?;
+    let execution_engine: Option<Arc<ExecutionEngine>> = match (&opt.wallet_keypair, mode) {
+        (Some(path), paper::RunMode::Live) => {
+            let engine = ExecutionEngine::new(
+                opt.jupiter_url.clone(),
+                opt.rpc_url.clone(),
+                execution::read_wallet(path)?,
+                Arc::clone(&risk_manager),
+                database.clone(),
+                ExecutionConfig::default(),
+            )
+            .with_notifier(Arc::clone(&notifier));
+            for market in opt.markets.iter().filter(|market| !mints.contains_key(*market)) {
+                tracing::warn!("No --mint for {}, its signals won't be traded", market);
+            }
+            Some(Arc::new(engine))
+        }
+        (None, paper::RunMode::Live) => {
+            tracing::warn!("No --wallet-keypair, live signals are logged but not traded");
+            None
+        }
+        (_, paper::RunMode::Simulate) => None,
+    };
+
+    // Set while the market data WebSocket is connected and subscribed
+    let ingestion_healthy = Arc::new(AtomicBool::new(false));
+
+    // Task 1: WebSocket ingestion, or a replay of recorded ticks through the same channel
+    if let Some(from) = opt.replay_from {
+        let tick_tx = tick_tx.clone();
+        let questdb = questdb.clone();
+        let markets = opt.markets.clone();
+        let to = opt.replay_to;
+        let ingestion_healthy = Arc::clone(&ingestion_healthy);
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            tracing::info!("Replaying {} ticks from {} to {:?}", markets.join(", "), from, to);
+            ingestion_healthy.store(true, Ordering::SeqCst);
+            tokio::select! {
+                result = questdb.stream_ticks(&markets, from, to, &tick_tx) => match result {
+                    Ok(count) => tracing::info!("Replay finished after {} ticks", count),
+                    Err(e) => tracing::error!("Replay failed: {}", e),
+                },
+                _ = shutdown.cancelled() => tracing::info!("Replay stopped"),
+            }
+            ingestion_healthy.store(false, Ordering::SeqCst);
+        });
+    } else {
         let tick_tx = tick_tx.clone();
         let url = opt.ws_url.clone();
-        let market = opt.market.clone();
+        let markets = opt.markets.clone();
+        let ingestion_healthy = Arc::clone(&ingestion_healthy);
+        let shutdown = shutdown.clone();
         tokio::spawn(async move {
-            let _ = stream_jupiter_websocket(url, market, tick_tx).await;
+            tokio::select! {
+                result = stream_jupiter_websocket(url, markets, tick_tx, ingestion_healthy) => {
+                    if let Err(e) = result {
+                        tracing::error!("WebSocket ingestion failed: {}", e);
+                    }
+                }
+                _ = shutdown.cancelled() => tracing::info!("WebSocket ingestion stopped"),
+            }
         });
     }
 
-    // Task 2: Feature engineering + Inference
+    // Task 1b: Write partial QuestDB batches so a quiet market still lands in storage
     {
+        let questdb = questdb.clone();
+        let shutdown = shutdown.clone();
+        let flush_every = Duration::from_millis(opt.questdb_flush_ms.max(1));
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(flush_every);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = shutdown.cancelled() => break,
+                }
+                if let Err(e) = questdb.flush().await {
+                    tracing::warn!("QuestDB flush failed: {}", e);
+                }
+            }
+        });
+    }
+
+    // Task 2: Feature engineering + Inference
+    let inference_task = {
         let questdb = questdb.clone();
         let inference = Arc::clone(&inference);
         let vector_store = Arc::clone(&vector_store);
         let signal_tx = signal_tx.clone();
+        let price_tx = (mode == paper::RunMode::Simulate || execution_engine.is_some()).then_some(price_tx);
+        let database = database.clone();
+        let anomaly_guard = Arc::clone(&anomaly_guard);
+        let notifier = Arc::clone(&notifier);
+        let markets = opt.markets.clone();
+        let risk_manager = Arc::clone(&risk_manager);
+        // ATR needs one more candle than its lookback to seed the first true range
+        let candle_capacity = risk_manager.lock().await.config.vol_lookback_periods + 1;
+        // Replayed ticks are already stored
+        let record_ticks = opt.replay_from.is_none();
+        let gate = types::SignalGate {
+            min_confidence: opt.threshold,
+            min_patterns: opt.min_patterns,
+            max_volatility: opt.max_volatility,
+        };
+        let regime = regime::RegimeDetector {
+            calm_below: opt.calm_volatility,
+            volatile_above: opt.volatile_volatility,
+            calm: opt.calm_action.parse()?,
+            normal: opt.normal_action.parse()?,
+            volatile: opt.volatile_action.parse()?,
+            reduced_size: opt.regime_reduced_size,
+        };
+        let shutdown = shutdown.clone();
         tokio::spawn(async move {
-            let mut buf = FeatureBuffer::new(opt.window_size);
-            while let Some(tick) = tick_rx.recv().await {
-                let _ = questdb.insert_tick(&tick).await;
-                buf.push(tick.clone());
-                if buf.is_ready() {
-                    let features = buf.extract_features();
-                    if let Ok((embedding, score)) = inference.predict(features).await {
-                        let similar = vector_store
-                            .find_similar(&embedding, 5)
-                            .await
-                            .unwrap_or_default();
-                        let signal = types::analyze_pattern(&similar, score);
-                        if signal.confidence > opt.threshold {
+            // Each market gets its own window so ticks from different markets never mix
+            let mut buffers: HashMap<String, FeatureBuffer> = HashMap::new();
+            let mut warmup_logged: HashMap<String, u32> = HashMap::new();
+            let mut last_timestamps: HashMap<String, i64> = HashMap::new();
+            let mut candles: HashMap<String, types::CandleSeries> = HashMap::new();
+            loop {
+                let first = tokio::select! {
+                    tick = tick_rx.recv() => match tick {
+                        Some(tick) => tick,
+                        None => break,
+                    },
+                    _ = shutdown.cancelled() => break,
+                };
+                // Ticks that queued up meanwhile (usually other markets') go through the model together
+                let mut ticks = vec![first];
+                while ticks.len() < MAX_INFERENCE_BATCH {
+                    match tick_rx.try_recv() {
+                        Ok(tick) => ticks.push(tick),
+                        Err(_) => break,
+                    }
+                }
+
+                // (tick, volatility) and the window for every tick that filled one
+                let mut ready = Vec::new();
+                let mut windows = Vec::new();
+                for tick in ticks {
+                    // Mismatched or out-of-order ticks would corrupt the rolling features and the stored series
+                    let last_timestamp = last_timestamps.get(&tick.market).copied();
+                    if let Err(rejection) = tick.check_market(&markets).and_then(|_| tick.check_order(last_timestamp)) {
+                        tracing::warn!("Dropping tick from {}: {}", tick.source, rejection);
+                        metrics::TICKS_REJECTED.with_label_values(&[rejection.label()]).inc();
+                        continue;
+                    }
+                    last_timestamps.insert(tick.market.clone(), tick.timestamp);
+                    if let Some(price_tx) = &price_tx {
+                        let observed_at = tick.observed_at(std::time::Instant::now(), chrono::Utc::now().timestamp_millis());
+                        let _ = price_tx.send((tick.market.clone(), tick.price, observed_at));
+                    }
+
+                    let series = candles
+                        .entry(tick.market.clone())
+                        .or_insert_with(|| types::CandleSeries::new(CANDLE_INTERVAL, candle_capacity));
+                    if series.push(tick.price, tick.timestamp) {
+                        risk_manager.lock().await.calculate_atr(&tick.market, &series.candles());
+                    }
+
+                    if record_ticks {
+                        let _ = questdb.insert_tick(&tick).await;
+                    }
+                    metrics::TICKS_INGESTED.inc();
+                    let buf = buffers
+                        .entry(tick.market.clone())
+                        .or_insert_with(|| FeatureBuffer::new(opt.window_size));
+                    if !buf.push(tick.clone()) {
+                        continue;
+                    }
+
+                    // Log warm-up progress in 25% steps until inference starts
+                    let quarter = (buf.fill_ratio() * 4.0).floor() as u32;
+                    let logged = warmup_logged.entry(tick.market.clone()).or_default();
+                    if quarter > *logged {
+                        *logged = quarter;
+                        tracing::info!("{} feature buffer warm-up: {:.0}%", tick.market, buf.fill_ratio() * 100.0);
+                    }
+
+                    if buf.is_ready() {
+                        windows.push(buf.extract_features());
+                        ready.push((tick, buf.volatility()));
+                    }
+                }
+                if windows.is_empty() {
+                    continue;
+                }
+
+                let predictions = match inference.predict_batch(windows).await {
+                    Ok(predictions) => predictions,
+                    Err(e) => {
+                        tracing::warn!("Inference on {} windows failed: {}", ready.len(), e);
+                        continue;
+                    }
+                };
+                let mut patterns = Vec::with_capacity(ready.len());
+                for ((tick, volatility), (embedding, score)) in ready.into_iter().zip(predictions) {
+                    let similar = vector_store
+                        .find_similar_above(&embedding, 5, opt.min_similarity)
+                        .await
+                        .unwrap_or_default();
+                    let mut signal = types::Signal {
+                        market: tick.market.clone(),
+                        ..types::analyze_pattern(&similar, score)
+                    };
+                    let verdict = {
+                        let now = std::time::Instant::now();
+                        let mut guard = anomaly_guard.lock().unwrap();
+                        if guard.observe(&signal.market, score, now) {
+                            metrics::ANOMALIES_DETECTED.inc();
+                            tracing::warn!(
+                                "{} anomaly score {:.4} above {:.4}, suppressing trading for {:?}",
+                                signal.market, score, guard.threshold, guard.cooldown
+                            );
+                            notifier.notify(notify::Notification::anomaly(
+                                &signal.market,
+                                &format!("anomaly score {:.4}, trading suspended for {:?}", score, guard.cooldown),
+                            ));
+                        }
+                        guard
+                            .check(&signal.market, now)
+                            .and_then(|_| gate.check(&signal, similar.len(), volatility))
+                            .and_then(|_| regime.check(volatility))
+                    };
+                    // Keep the autoencoder's output for every signal that wasn't simply weak
+                    if !matches!(verdict, Err(types::SignalRejection::LowConfidence { .. })) {
+                        let record = database::SignalRecord {
+                            version: signal.version,
+                            timestamp: chrono::Utc::now(),
+                            symbol: signal.market.clone(),
+                            confidence: signal.confidence,
+                            predicted_return: None,
+                            predicted_volatility: Some(volatility as f32),
+                            embedding_vector: None,
+                            anomaly_score: Some(signal.anomaly_score),
+                            similar_patterns_count: Some(similar.len() as i32),
+                        };
+                        if let Err(e) = database.insert_signal(record).await {
+                            tracing::warn!("Failed to record signal: {}", e);
+                        }
+                    }
+                    match verdict {
+                        Ok(size_factor) => {
+                            if size_factor < 1.0 {
+                                tracing::info!(
+                                    "{} {} regime, trading at {:.0}% size",
+                                    signal.market,
+                                    regime.classify(volatility),
+                                    size_factor * 100.0
+                                );
+                            }
+                            signal.size_factor = size_factor;
+                            metrics::SIGNALS_EMITTED.inc();
                             let _ = signal_tx.send(signal);
                         }
-                        let _ = vector_store
-                            .insert_pattern(&embedding, &types::PatternMetadata::from_tick(&tick))
-                            .await;
+                        Err(rejection) => {
+                            metrics::SIGNALS_REJECTED.with_label_values(&[rejection.label()]).inc();
+                            // Most ticks fall short on confidence; keep those out of the default log
+                            if let types::SignalRejection::LowConfidence { .. } = rejection {
+                                tracing::debug!("{} signal rejected: {}", signal.market, rejection);
+                            } else {
+                                tracing::info!("{} signal rejected: {}", signal.market, rejection);
+                            }
+                        }
                     }
+                    patterns.push((embedding, types::PatternMetadata::from_tick(&tick)));
+                }
+                if let Err(e) = vector_store.insert_patterns_batch(&patterns).await {
+                    tracing::warn!("Failed to store {} patterns: {}", patterns.len(), e);
+                }
+            }
+        })
+    };
+
+    // Task 2b: Keep the pattern store bounded on long runs
+    if opt.pattern_retention_secs > 0 {
+        let vector_store = Arc::clone(&vector_store);
+        let retention = opt.pattern_retention_secs;
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs((retention / 4).clamp(60, 3600)));
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = shutdown.cancelled() => break,
+                }
+                let cutoff = chrono::Utc::now().timestamp() - retention as i64;
+                if let Err(e) = vector_store.prune_older_than(cutoff).await {
+                    tracing::warn!("Pattern prune failed: {}", e);
+                    continue;
+                }
+                if let Ok(count) = vector_store.count().await {
+                    tracing::info!("Pattern store holds {} vectors", count);
                 }
             }
         });
     }
 
+    // Task 2c: Catch positions sold or resized outside the bot
+    if let Some(engine) = execution_engine.clone() {
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move { engine.run_reconciler(RECONCILE_INTERVAL, shutdown).await });
+    }
+
+    let admin_token = opt.admin_token.clone().or_else(|| std::env::var("ADMIN_TOKEN").ok());
+
     // Task 3: Execution engine
-    {
+    let execution_task = {
+        let risk_manager = Arc::clone(&risk_manager);
+        let execution_engine = execution_engine.clone();
+        let mut paper = (mode == paper::RunMode::Simulate)
+            .then(|| paper::PaperExecutor::new(Arc::clone(&risk_manager), database.clone()).with_open(restored_paper));
+        let close_paper_on_shutdown = opt.close_positions_on_shutdown;
+        let halted = risk_manager.lock().await.halt_flag();
+        let shutdown = shutdown.clone();
         tokio::spawn(async move {
-            while let Some(signal) = signal_rx.recv().await {
-                let _ = execute_trade(signal).await;
+            // Stops still fire on a silent feed: a stale price is only noticed when checked
+            let mut position_check = tokio::time::interval(POSITION_CHECK_INTERVAL);
+            loop {
+                // Stop taking new signals on shutdown; a trade already in
+                // flight runs to completion so its DB writes land. Prices go
+                // first so a signal fills at the tick that produced it.
+                let signal = tokio::select! {
+                    biased;
+                    _ = shutdown.cancelled() => break,
+                    Some((market, price, observed_at)) = price_rx.recv(), if paper.is_some() || execution_engine.is_some() => {
+                        if let Some(paper) = paper.as_mut() {
+                            if let Err(e) = paper.on_price(&market, price, observed_at).await {
+                                tracing::warn!("Paper position update for {} failed: {}", market, e);
+                            }
+                        } else if let Some(engine) = &execution_engine {
+                            let prices = HashMap::from([(market, (price, observed_at))]);
+                            update_live_positions(&risk_manager, engine, &prices).await;
+                        }
+                        continue;
+                    }
+                    _ = position_check.tick(), if paper.is_some() || execution_engine.is_some() => {
+                        if let Some(paper) = paper.as_mut() {
+                            if let Err(e) = paper.check_positions().await {
+                                tracing::warn!("Paper position check failed: {}", e);
+                            }
+                        } else if let Some(engine) = &execution_engine {
+                            update_live_positions(&risk_manager, engine, &HashMap::new()).await;
+                        }
+                        continue;
+                    }
+                    signal = signal_rx.recv() => match signal {
+                        Some(signal) => signal,
+                        None => break,
+                    },
+                };
+                match paper.as_mut() {
+                    Some(paper) => {
+                        if let Err(e) = paper.on_signal(&signal).await {
+                            tracing::info!("Paper trade on {} skipped: {}", signal.market, e);
+                        }
+                    }
+                    // The paper path gets this from `validate_trade`
+                    None if halted.load(Ordering::SeqCst) => {
+                        tracing::warn!("Trading halted, dropping {} signal", signal.market);
+                        metrics::SIGNALS_REJECTED.with_label_values(&["halted"]).inc();
+                    }
+                    None => match (&execution_engine, mints.get(&signal.market)) {
+                        (Some(engine), Some(mint)) => {
+                            if let Err(e) = engine.execute_buy(&signal, &signal.market, mint).await {
+                                tracing::warn!("Buy on {} failed: {}", signal.market, e);
+                            }
+                        }
+                        (Some(_), None) => tracing::debug!("No mint for {}, signal not traded", signal.market),
+                        (None, _) => {
+                            let _ = execute_trade(signal).await;
+                        }
+                    },
+                }
+                metrics::update_portfolio(&risk_manager.lock().await.get_metrics());
             }
-        });
-    }
+            if close_paper_on_shutdown {
+                if let Some(paper) = paper.as_mut() {
+                    paper.close_all("shutdown").await;
+                }
+            }
+        })
+    };
 
     // Metrics & health endpoints
     let metrics_route = {
@@ -141,18 +671,365 @@ async fn main() -> anyhow::Result<()> {
                 .body(buffer)
                 .unwrap()
         });
-        let healthz = warp::path("healthz").map(|| {
-            warp::http::Response::builder()
-                .status(warp::http::StatusCode::OK)
-                .body("OK")
-                .unwrap()
-        });
-        metrics.or(healthz).boxed()
+        let healthz = {
+            let ingestion_healthy = Arc::clone(&ingestion_healthy);
+            warp::path("healthz").map(move || {
+                if ingestion_healthy.load(Ordering::SeqCst) {
+                    warp::http::Response::builder()
+                        .status(warp::http::StatusCode::OK)
+                        .body("OK")
+                        .unwrap()
+                } else {
+                    warp::http::Response::builder()
+                        .status(warp::http::StatusCode::SERVICE_UNAVAILABLE)
+                        .body("websocket ingestion disconnected")
+                        .unwrap()
+                }
+            })
+        };
+        let portfolio = {
+            let risk_manager = Arc::clone(&risk_manager);
+            warp::path!("portfolio").and_then(move || {
+                let risk_manager = Arc::clone(&risk_manager);
+                async move {
+                    let rm = risk_manager.lock().await;
+                    Ok::<_, warp::Rejection>(warp::reply::json(&rm.get_metrics()))
+                }
+            })
+        };
+        let open_positions = {
+            let risk_manager = Arc::clone(&risk_manager);
+            warp::path!("positions").and_then(move || {
+                let risk_manager = Arc::clone(&risk_manager);
+                async move {
+                    let rm = risk_manager.lock().await;
+                    let positions: Vec<_> = rm.positions.values().cloned().collect();
+                    Ok::<_, warp::Rejection>(warp::reply::json(&positions))
+                }
+            })
+        };
+        let closed_positions = {
+            let database = database.clone();
+            warp::path!("positions" / "closed")
+                .and(warp::query::<PositionsQuery>())
+                .and_then(move |q: PositionsQuery| {
+                    let database = database.clone();
+                    async move {
+                        let result = database
+                            .get_closed_positions(q.limit.unwrap_or(50), q.offset.unwrap_or(0))
+                            .await;
+                        Ok::<_, warp::Rejection>(json_or_500(result))
+                    }
+                })
+        };
+        let position_trades = {
+            let database = database.clone();
+            warp::path!("positions" / i64 / "trades")
+                .and_then(move |id: i64| {
+                    let database = database.clone();
+                    async move {
+                        Ok::<_, warp::Rejection>(json_or_500(database.get_trades_for_position(id).await))
+                    }
+                })
+        };
+        let preview = {
+            let risk_manager = Arc::clone(&risk_manager);
+            warp::path!("preview")
+                .and(warp::query::<PreviewQuery>())
+                .and_then(move |q: PreviewQuery| {
+                    let risk_manager = Arc::clone(&risk_manager);
+                    async move {
+                        let rm = risk_manager.lock().await;
+                        let signal = types::Signal::new(q.market.unwrap_or_default(), q.confidence, 0.0);
+                        // Size like the execution engine would unless one is given
+                        let size = match q.size {
+                            Some(size) => Ok(size),
+                            None => rm.calculate_position_size(&signal, q.volatility.unwrap_or(0.02), None),
+                        };
+                        Ok::<_, warp::Rejection>(json_or_500(size.map(|size| rm.preview_open(&signal, size))))
+                    }
+                })
+        };
+        let anomalies = {
+            let anomaly_guard = Arc::clone(&anomaly_guard);
+            warp::path!("anomalies").map(move || {
+                let anomalous = anomaly_guard.lock().unwrap().anomalous(std::time::Instant::now());
+                warp::reply::json(&anomalous)
+            })
+        };
+        let halt = {
+            let risk_manager = Arc::clone(&risk_manager);
+            let admin_token = admin_token.clone();
+            warp::post()
+                .and(warp::path!("halt"))
+                .and(warp::header::optional::<String>("authorization"))
+                .and(warp::query::<HaltQuery>())
+                .and_then(move |auth: Option<String>, q: HaltQuery| {
+                    let risk_manager = Arc::clone(&risk_manager);
+                    let admin_token = admin_token.clone();
+                    async move {
+                        use warp::Reply;
+                        if let Some(denied) = admin_denial(admin_token.as_deref(), auth.as_deref()) {
+                            return Ok::<_, warp::Rejection>(denied);
+                        }
+                        let mut rm = risk_manager.lock().await;
+                        rm.halt(q.reason.as_deref().unwrap_or("manual halt via /halt"));
+                        metrics::update_portfolio(&rm.get_metrics());
+                        Ok(warp::reply::json(&rm.get_metrics()).into_response())
+                    }
+                })
+        };
+        // Clears automatic halts too, so it takes the same token as `/close_all`
+        let resume = {
+            let risk_manager = Arc::clone(&risk_manager);
+            let admin_token = admin_token.clone();
+            warp::post()
+                .and(warp::path!("resume"))
+                .and(warp::header::optional::<String>("authorization"))
+                .and_then(move |auth: Option<String>| {
+                    let risk_manager = Arc::clone(&risk_manager);
+                    let admin_token = admin_token.clone();
+                    async move {
+                        use warp::Reply;
+                        if let Some(denied) = admin_denial(admin_token.as_deref(), auth.as_deref()) {
+                            return Ok::<_, warp::Rejection>(denied);
+                        }
+                        let mut rm = risk_manager.lock().await;
+                        rm.resume();
+                        metrics::update_portfolio(&rm.get_metrics());
+                        Ok(warp::reply::json(&rm.get_metrics()).into_response())
+                    }
+                })
+        };
+        let close_all = {
+            let execution_engine = execution_engine.clone();
+            let admin_token = admin_token.clone();
+            warp::post()
+                .and(warp::path!("close_all"))
+                .and(warp::header::optional::<String>("authorization"))
+                .and(warp::query::<HaltQuery>())
+                .and_then(move |auth: Option<String>, q: HaltQuery| {
+                    let execution_engine = execution_engine.clone();
+                    let admin_token = admin_token.clone();
+                    async move {
+                        use warp::http::StatusCode;
+                        use warp::Reply;
+                        if let Some(denied) = admin_denial(admin_token.as_deref(), auth.as_deref()) {
+                            return Ok::<_, warp::Rejection>(denied);
+                        }
+                        let Some(engine) = execution_engine else {
+                            return Ok(warp::reply::with_status(
+                                "no execution engine configured",
+                                StatusCode::SERVICE_UNAVAILABLE,
+                            )
+                            .into_response());
+                        };
+                        let reason = q.reason.as_deref().unwrap_or("manual close via /close_all");
+                        tracing::warn!("Closing all positions: {}", reason);
+                        Ok(json_or_500(engine.close_all_positions(reason).await))
+                    }
+                })
+        };
+        metrics
+            .or(healthz)
+            .or(portfolio)
+            .or(open_positions)
+            .or(closed_positions)
+            .or(position_trades)
+            .or(preview)
+            .or(anomalies)
+            .or(halt)
+            .or(resume)
+            .or(close_all)
+            .boxed()
     };
     let metrics_port = opt.metrics_port;
-    tokio::spawn(warp::serve(metrics_route).run(([0, 0, 0, 0], metrics_port)));
+    let (_, server) = {
+        let shutdown = shutdown.clone();
+        warp::serve(metrics_route).bind_with_graceful_shutdown(
+            ([0, 0, 0, 0], metrics_port),
+            async move { shutdown.cancelled().await },
+        )
+    };
+    tokio::spawn(server);
 
     // Wait for shutdown signal
+    wait_for_shutdown_signal().await?;
+    tracing::info!("Shutdown requested, stopping tasks");
+    shutdown.cancel();
+    if let Some(watcher) = model_watcher {
+        watcher.abort();
+    }
+
+    // Let the workers finish the tick/signal they're on so pending DB writes land
+    for (name, task) in [("inference", inference_task), ("execution", execution_task)] {
+        if tokio::time::timeout(SHUTDOWN_GRACE, task).await.is_err() {
+            tracing::warn!("{} task did not stop within {:?}", name, SHUTDOWN_GRACE);
+        }
+    }
+
+    match questdb.flush().await {
+        Ok(rows) => tracing::info!("Flushed {} buffered ticks to QuestDB", rows),
+        Err(e) => tracing::error!("Failed to flush buffered ticks to QuestDB: {}", e),
+    }
+
+    let open_positions = database.get_open_positions(paper_mode).await.unwrap_or_default();
+    if !open_positions.is_empty() {
+        if opt.close_positions_on_shutdown {
+            match &execution_engine {
+                Some(engine) => match engine.close_all_positions("shutdown").await {
+                    Ok(report) => {
+                        for failed in &report.failed {
+                            tracing::warn!("Left #{} {} open: {}", failed.position_id, failed.symbol, failed.error);
+                        }
+                    }
+                    Err(e) => tracing::error!("Failed to close positions on shutdown: {}", e),
+                },
+                None => tracing::warn!(
+                    "No execution engine configured, cannot market-sell {} open positions",
+                    open_positions.len()
+                ),
+            }
+        } else {
+            tracing::warn!("Leaving {} positions open", open_positions.len());
+        }
+        for (id, symbol, entry_price, current_price, size_usd) in &open_positions {
+            tracing::warn!(
+                "  #{} {} entry ${:.6} now ${:.6} size ${:.2}",
+                id, symbol, entry_price, current_price, size_usd
+            );
+        }
+    }
+
+    // Final snapshot so the last session's risk state is on record
+    let final_metrics = risk_manager.lock().await.get_metrics();
+    if let Err(e) = database.insert_risk_snapshot(final_metrics).await {
+        tracing::error!("Failed to record final risk snapshot: {}", e);
+    }
+    tracing::info!("Shutdown complete");
+    Ok(())
+}
+
+/// Most windows sent through the model in one call
+const MAX_INFERENCE_BATCH: usize = 64;
+
+/// Bar length the risk manager's ATR is computed over
+const CANDLE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often open positions are checked against the wallet's balances
+const RECONCILE_INTERVAL: Duration = Duration::from_secs(300);
+
+/// How often open positions are re-checked between ticks
+const POSITION_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long each worker gets to finish its current item on shutdown
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
+/// Mark live positions to `prices` and sell any whose stop fired
+async fn update_live_positions(
+    risk_manager: &tokio::sync::Mutex<RiskManager>,
+    engine: &ExecutionEngine,
+    prices: &HashMap<String, (f64, std::time::Instant)>,
+) {
+    let triggered = risk_manager.lock().await.update_positions(prices);
+    for (symbol, reason) in triggered {
+        if let Err(e) = engine.close_symbol(&symbol, &reason).await {
+            tracing::error!("Failed to sell {} on {}: {}", symbol, reason, e);
+        }
+    }
+}
+
+/// Resolve on Ctrl+C, or SIGTERM on Unix (what `docker stop`/systemd send)
+async fn wait_for_shutdown_signal() -> anyhow::Result<()> {
+    #[cfg(unix)]
+    {
+        let mut sigterm =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result?,
+            _ = sigterm.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
     tokio::signal::ctrl_c().await?;
     Ok(())
 }
+
+/// Pagination parameters for the `/positions/closed` endpoint
+#[derive(Debug, Deserialize)]
+struct PositionsQuery {
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+/// Hypothetical trade for the `/preview` endpoint
+#[derive(Debug, Deserialize)]
+struct PreviewQuery {
+    market: Option<String>,
+    confidence: f32,
+    size: Option<f64>,
+    volatility: Option<f64>,
+}
+
+/// Optional reason for the `/halt` and `/close_all` endpoints
+#[derive(Debug, Deserialize)]
+struct HaltQuery {
+    reason: Option<String>,
+}
+
+/// `--mint` values (`MARKET=MINT`) by market
+///
+/// Only `*/SOL` markets are accepted: positions are entered at a SOL-per-token
+/// price, so their stops need ticks quoted in SOL too.
+fn parse_mints(values: &[String]) -> anyhow::Result<HashMap<String, String>> {
+    values
+        .iter()
+        .map(|value| match value.split_once('=') {
+            Some((market, mint)) if !market.is_empty() && !mint.is_empty() => {
+                let market = market.trim();
+                match market.split_once('/') {
+                    Some((_, quote)) if quote.eq_ignore_ascii_case("SOL") => {
+                        Ok((market.to_string(), mint.trim().to_string()))
+                    }
+                    _ => Err(anyhow::anyhow!("--mint '{}' isn't a */SOL market, its prices can't be compared with SOL entries", value)),
+                }
+            }
+            _ => Err(anyhow::anyhow!("invalid --mint '{}' (expected MARKET=MINT)", value)),
+        })
+        .collect()
+}
+
+/// Whether an `Authorization` header carries `token` as a bearer token,
+/// compared in constant time
+fn bearer_matches(header: Option<&str>, token: &str) -> bool {
+    let Some(given) = header.and_then(|h| h.strip_prefix("Bearer ")) else {
+        return false;
+    };
+    given.len() == token.len() && given.bytes().zip(token.bytes()).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// The 403/401 to refuse an admin request with, None to let it through
+fn admin_denial(admin_token: Option<&str>, auth: Option<&str>) -> Option<warp::reply::Response> {
+    use warp::http::StatusCode;
+    use warp::Reply;
+    let Some(token) = admin_token else {
+        return Some(warp::reply::with_status("admin endpoints are disabled", StatusCode::FORBIDDEN).into_response());
+    };
+    if !bearer_matches(auth, token) {
+        return Some(warp::reply::with_status("bad admin token", StatusCode::UNAUTHORIZED).into_response());
+    }
+    None
+}
+
+/// Serialize a database result as JSON, or a 500 with the error message
+fn json_or_500<T: serde::Serialize>(result: anyhow::Result<T>) -> warp::reply::Response {
+    use warp::Reply;
+    match result {
+        Ok(value) => warp::reply::json(&value).into_response(),
+        Err(e) => warp::reply::with_status(
+            e.to_string(),
+            warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+        )
+        .into_response(),
+    }
+}
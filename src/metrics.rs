@@ -1,12 +1,84 @@
+use crate::risk_manager::RiskMetrics;
 use once_cell::sync::Lazy;
-use prometheus::{register_int_counter, IntCounter};
+use prometheus::{
+    register_gauge, register_histogram, register_int_counter, register_int_counter_vec,
+    register_int_gauge, Gauge, Histogram, IntCounter, IntCounterVec, IntGauge,
+};
 
 /// Total WebSocket ticks received.
 pub static TICKS_RECEIVED: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!("ticks_received_total", "Total WebSocket ticks received").unwrap()
 });
 
+/// Total ticks processed by the feature/inference task.
+pub static TICKS_INGESTED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!("ticks_ingested_total", "Total ticks processed by the feature pipeline").unwrap()
+});
+
+/// Ticks dropped by validation, labelled by reason ("wrong_market" or "out_of_order").
+pub static TICKS_REJECTED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!("ticks_rejected_total", "Ticks dropped by validation", &["reason"]).unwrap()
+});
+
+/// Inference latency in seconds.
+pub static INFERENCE_LATENCY: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!("inference_latency_seconds", "Inference latency in seconds").unwrap()
+});
+
 /// Total trading signals emitted.
 pub static SIGNALS_EMITTED: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!("signals_emitted_total", "Total trading signals emitted").unwrap()
 });
+
+/// Signals kept from execution, labelled by `SignalRejection` reason.
+pub static SIGNALS_REJECTED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!("signals_rejected_total", "Signals kept from execution by the signal gate", &["reason"]).unwrap()
+});
+
+/// Times a market's anomaly score crossed the threshold and trading in it was suppressed.
+pub static ANOMALIES_DETECTED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!("anomalies_detected_total", "Markets suppressed after an anomaly score spike").unwrap()
+});
+
+/// Total trades executed, labelled by side ("buy" or "sell"): swaps by the
+/// execution engine when live, paper fills in simulate mode.
+pub static TRADES_EXECUTED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!("trades_executed_total", "Total trades executed", &["side"]).unwrap()
+});
+
+/// Number of currently open positions.
+pub static OPEN_POSITIONS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!("open_positions", "Number of currently open positions").unwrap()
+});
+
+/// Realized P&L in USD since startup.
+pub static REALIZED_PNL: Lazy<Gauge> = Lazy::new(|| {
+    register_gauge!("realized_pnl_usd", "Realized P&L in USD").unwrap()
+});
+
+/// 1 while the risk manager's kill switch is engaged.
+pub static TRADING_HALTED: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!("trading_halted", "1 while trading is halted by the kill switch").unwrap()
+});
+
+/// Register all metrics up front so `/metrics` lists them before the first update.
+pub fn init() {
+    Lazy::force(&TICKS_RECEIVED);
+    Lazy::force(&TICKS_INGESTED);
+    Lazy::force(&TICKS_REJECTED);
+    Lazy::force(&INFERENCE_LATENCY);
+    Lazy::force(&SIGNALS_EMITTED);
+    Lazy::force(&SIGNALS_REJECTED);
+    Lazy::force(&ANOMALIES_DETECTED);
+    Lazy::force(&TRADES_EXECUTED);
+    Lazy::force(&OPEN_POSITIONS);
+    Lazy::force(&REALIZED_PNL);
+    Lazy::force(&TRADING_HALTED);
+}
+
+/// Refresh the portfolio gauges from a risk metrics snapshot.
+pub fn update_portfolio(metrics: &RiskMetrics) {
+    OPEN_POSITIONS.set(metrics.num_positions as i64);
+    REALIZED_PNL.set(metrics.realized_pnl);
+    TRADING_HALTED.set(metrics.trading_halted as i64);
+}
@@ -0,0 +1,261 @@
+//! Trade notifications
+//!
+//! Buys, sells and anomaly alerts go out through a [`Notifier`] so someone
+//! can get a ping instead of watching logs. [`WebhookNotifier`] POSTs each
+//! event as JSON with a one-line summary under both `content` and `text`,
+//! which Discord, Slack and Telegram (`sendMessage?chat_id=...`) webhooks
+//! accept as-is; [`NoopNotifier`] is used when no webhook is configured.
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Wait before the one retry of a failed webhook POST
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Kinds of event a notifier can report
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyEvent {
+    Buy,
+    Sell,
+    /// A market's anomaly score spiked and trading in it was suspended
+    Anomaly,
+}
+
+impl NotifyEvent {
+    pub const ALL: [NotifyEvent; 3] = [NotifyEvent::Buy, NotifyEvent::Sell, NotifyEvent::Anomaly];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            NotifyEvent::Buy => "buy",
+            NotifyEvent::Sell => "sell",
+            NotifyEvent::Anomaly => "anomaly",
+        }
+    }
+}
+
+impl FromStr for NotifyEvent {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "buy" => Ok(NotifyEvent::Buy),
+            "sell" => Ok(NotifyEvent::Sell),
+            "anomaly" => Ok(NotifyEvent::Anomaly),
+            other => Err(anyhow!("unknown notify event '{}' (expected buy, sell or anomaly)", other)),
+        }
+    }
+}
+
+/// Parse a comma-separated event list like `buy,sell`
+pub fn parse_events(list: &str) -> Result<Vec<NotifyEvent>> {
+    list.split(',')
+        .filter(|event| !event.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// One event to report
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Notification {
+    pub event: NotifyEvent,
+    pub symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_usd: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pnl_usd: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// Exit reason, or what the anomaly was
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl Notification {
+    fn new(event: NotifyEvent, symbol: &str) -> Self {
+        Self {
+            event,
+            symbol: symbol.to_string(),
+            size_usd: None,
+            pnl_usd: None,
+            signature: None,
+            reason: None,
+        }
+    }
+
+    pub fn buy(symbol: &str, size_usd: f64, signature: &str) -> Self {
+        Self {
+            size_usd: Some(size_usd),
+            signature: Some(signature.to_string()),
+            ..Self::new(NotifyEvent::Buy, symbol)
+        }
+    }
+
+    pub fn sell(symbol: &str, size_usd: f64, pnl_usd: f64, signature: &str, reason: &str) -> Self {
+        Self {
+            size_usd: Some(size_usd),
+            pnl_usd: Some(pnl_usd),
+            signature: Some(signature.to_string()),
+            reason: Some(reason.to_string()),
+            ..Self::new(NotifyEvent::Sell, symbol)
+        }
+    }
+
+    pub fn anomaly(symbol: &str, reason: &str) -> Self {
+        Self {
+            reason: Some(reason.to_string()),
+            ..Self::new(NotifyEvent::Anomaly, symbol)
+        }
+    }
+
+    /// One-line human-readable summary
+    pub fn summary(&self) -> String {
+        let mut line = format!("{} {}", self.event.label().to_uppercase(), self.symbol);
+        if let Some(size) = self.size_usd {
+            line.push_str(&format!(" | ${:.2}", size));
+        }
+        if let Some(pnl) = self.pnl_usd {
+            line.push_str(&format!(" | P&L {:+.2} USD", pnl));
+        }
+        if let Some(reason) = &self.reason {
+            line.push_str(&format!(" | {}", reason));
+        }
+        line
+    }
+}
+
+/// Somewhere events get reported
+///
+/// Called from the trading path, so `notify` must return right away and
+/// never fail the trade; deliver in the background.
+pub trait Notifier: Send + Sync {
+    fn notify(&self, notification: Notification);
+}
+
+/// Drops every notification
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoopNotifier;
+
+impl Notifier for NoopNotifier {
+    fn notify(&self, _notification: Notification) {}
+}
+
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    #[serde(flatten)]
+    notification: &'a Notification,
+    source: &'static str,
+    content: &'a str,
+    text: &'a str,
+}
+
+/// POSTs notifications as JSON to a webhook, each on its own task with one
+/// retry after [`RETRY_DELAY`], so a flaky webhook never holds up trading
+#[derive(Clone, Debug)]
+pub struct WebhookNotifier {
+    http: reqwest::Client,
+    url: String,
+    events: Vec<NotifyEvent>,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: &str, events: &[NotifyEvent]) -> Self {
+        Self {
+            http: reqwest::Client::builder()
+                .timeout(WEBHOOK_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            url: url.to_string(),
+            events: events.to_vec(),
+        }
+    }
+
+    pub fn wants(&self, event: NotifyEvent) -> bool {
+        self.events.contains(&event)
+    }
+
+    /// POST `notification` now, retrying once on failure
+    pub async fn send(&self, notification: &Notification) -> Result<()> {
+        if let Err(e) = self.post(notification).await {
+            tracing::warn!("Webhook failed ({}), retrying in {:?}", e, RETRY_DELAY);
+            tokio::time::sleep(RETRY_DELAY).await;
+            return self.post(notification).await;
+        }
+        Ok(())
+    }
+
+    async fn post(&self, notification: &Notification) -> Result<()> {
+        let text = format!("[trading-engine] {}", notification.summary());
+        let payload = WebhookPayload {
+            notification,
+            source: "trading-engine",
+            content: &text,
+            text: &text,
+        };
+        let response = self.http.post(&self.url).json(&payload).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("HTTP {} from webhook", response.status()));
+        }
+        Ok(())
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn notify(&self, notification: Notification) {
+        if !self.wants(notification.event) {
+            return;
+        }
+        let notifier = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = notifier.send(&notification).await {
+                tracing::warn!(
+                    "Dropped {} notification for {}: {}",
+                    notification.event.label(),
+                    notification.symbol,
+                    e
+                );
+            }
+        });
+    }
+}
+
+/// A [`WebhookNotifier`] for `url`, or a [`NoopNotifier`] without one
+pub fn notifier(url: Option<&str>, events: &[NotifyEvent]) -> Arc<dyn Notifier> {
+    match url {
+        Some(url) => Arc::new(WebhookNotifier::new(url, events)),
+        None => Arc::new(NoopNotifier),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_and_events() {
+        let sell = Notification::sell("BONK/SOL", 120.0, -4.5, "Sig111", "trailing_stop");
+        assert_eq!(sell.summary(), "SELL BONK/SOL | $120.00 | P&L -4.50 USD | trailing_stop");
+
+        let json = serde_json::to_value(WebhookPayload {
+            notification: &sell,
+            source: "trading-engine",
+            content: "hi",
+            text: "hi",
+        })
+        .unwrap();
+        assert_eq!(json["event"], "sell");
+        assert_eq!(json["pnl_usd"], -4.5);
+        assert_eq!(json["text"], "hi");
+        let anomaly = serde_json::to_value(Notification::anomaly("WIF/SOL", "score 0.91")).unwrap();
+        assert!(anomaly.get("size_usd").is_none());
+
+        assert_eq!(parse_events("buy, anomaly,").unwrap(), vec![NotifyEvent::Buy, NotifyEvent::Anomaly]);
+        assert!(parse_events("buy,rug").is_err());
+        let notifier = WebhookNotifier::new("http://localhost/hook", &[NotifyEvent::Sell]);
+        assert!(notifier.wants(NotifyEvent::Sell) && !notifier.wants(NotifyEvent::Buy));
+    }
+}
@@ -0,0 +1,320 @@
+//! Paper trading for `--mode simulate`
+//!
+//! Signals go through the same `RiskManager` sizing, validation and stops as
+//! live trades, but fill at the market's last tick price and are booked in the
+//! database marked `paper`. Nothing reaches Jupiter or the wallet.
+
+use crate::database::{AsyncDatabase, PositionRecord, TradeRecord};
+use crate::metrics;
+use crate::risk_manager::{RiskError, RiskManager};
+use crate::types::Signal;
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// Volatility assumed when sizing, the same placeholder the execution engine uses
+const ESTIMATED_VOLATILITY: f64 = 0.02;
+
+/// What the engine does with signals that pass every gate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunMode {
+    /// Trade them
+    Live,
+    /// Paper-trade them through `PaperExecutor`
+    Simulate,
+}
+
+impl FromStr for RunMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "live" => Ok(Self::Live),
+            "simulate" => Ok(Self::Simulate),
+            other => Err(anyhow!("unknown mode '{}' (expected live or simulate)", other)),
+        }
+    }
+}
+
+/// Opens paper positions on signals and closes them when the risk manager's stops fire
+pub struct PaperExecutor {
+    risk_manager: Arc<Mutex<RiskManager>>,
+    database: AsyncDatabase,
+    /// Last tick price per market
+    prices: HashMap<String, f64>,
+    /// Database id of each market's open paper position
+    open: HashMap<String, i64>,
+}
+
+impl PaperExecutor {
+    pub fn new(risk_manager: Arc<Mutex<RiskManager>>, database: AsyncDatabase) -> Self {
+        Self {
+            risk_manager,
+            database,
+            prices: HashMap::new(),
+            open: HashMap::new(),
+        }
+    }
+
+    /// Track paper positions an earlier run left open, by market and database id
+    ///
+    /// Each must already be open in the risk manager.
+    pub fn with_open(mut self, open: HashMap<String, i64>) -> Self {
+        self.open = open;
+        self
+    }
+
+    /// Mark `market` to `price`, observed at `observed_at`, and close any paper
+    /// position whose stop it triggered
+    pub async fn on_price(&mut self, market: &str, price: f64, observed_at: Instant) -> Result<()> {
+        self.prices.insert(market.to_string(), price);
+        if !self.open.contains_key(market) {
+            return Ok(());
+        }
+        self.update_positions(HashMap::from([(market.to_string(), (price, observed_at))])).await
+    }
+
+    /// Re-check open paper positions without a new price, so a stale feed
+    /// still trips `max_price_age_ms` when no ticks arrive
+    pub async fn check_positions(&mut self) -> Result<()> {
+        if self.open.is_empty() {
+            return Ok(());
+        }
+        self.update_positions(HashMap::new()).await
+    }
+
+    async fn update_positions(&mut self, prices: HashMap<String, (f64, Instant)>) -> Result<()> {
+        let triggered = self.risk_manager.lock().await.update_positions(&prices);
+        for (symbol, reason) in triggered {
+            self.close(&symbol, &reason).await?;
+        }
+        Ok(())
+    }
+
+    /// Size and validate `signal` like a live buy, then book it at the market's last price
+    ///
+    /// Returns the paper position's database id.
+    pub async fn on_signal(&mut self, signal: &Signal) -> Result<i64> {
+        let market = &signal.market;
+        let price = *self
+            .prices
+            .get(market)
+            .ok_or_else(|| anyhow!("No price for {} yet", market))?;
+        if self.open.contains_key(market) {
+            return Err(anyhow!("Already holding a paper position in {}", market));
+        }
+
+        let (size_usd, trailing_stop) = {
+            let mut rm = self.risk_manager.lock().await;
+            let atr_pct = rm.atr_cache.get(market).copied();
+            let size_usd = rm.calculate_position_size(signal, ESTIMATED_VOLATILITY, atr_pct)?;
+            rm.validate_trade(signal, size_usd, ESTIMATED_VOLATILITY)
+                .map_err(|e| anyhow!("Risk validation failed: {}", e))?;
+            if rm.breaches_daily_drawdown(&rm.preview_open(signal, size_usd)) {
+                return Err(anyhow!("Risk validation failed: {}", RiskError::DrawdownLimitExceeded));
+            }
+            rm.open_position(market.clone(), price, size_usd)?;
+            (size_usd, rm.positions[market].trailing_stop)
+        };
+
+        let inserted = self
+            .database
+            .insert_position(PositionRecord {
+                symbol: market.clone(),
+                mint_address: None,
+                entry_price: price,
+                current_price: price,
+                size_usd,
+                entry_time: Utc::now(),
+                peak_price: price,
+                trailing_stop,
+                unrealized_pnl: 0.0,
+                unrealized_pnl_pct: 0.0,
+                confidence_score: signal.confidence,
+                volatility: ESTIMATED_VOLATILITY,
+                token_amount: None,
+                paper: true,
+            })
+            .await;
+        // Without a row nothing could ever close it, so hand the capital back
+        let id = match inserted {
+            Ok(id) => id,
+            Err(e) => {
+                self.risk_manager.lock().await.forget_position(market);
+                return Err(e);
+            }
+        };
+        self.open.insert(market.clone(), id);
+        self.database.insert_trade(paper_trade("buy", id, market, price, size_usd)).await?;
+        metrics::TRADES_EXECUTED.with_label_values(&["buy"]).inc();
+
+        info!("📝 Paper buy #{} {} ${:.2} at {:.8}", id, market, size_usd, price);
+        Ok(id)
+    }
+
+    /// Close every open paper position at its market's last price
+    pub async fn close_all(&mut self, reason: &str) {
+        let markets: Vec<String> = self.open.keys().cloned().collect();
+        for market in markets {
+            if let Err(e) = self.close(&market, reason).await {
+                warn!("Failed to close paper position in {}: {}", market, e);
+            }
+        }
+    }
+
+    /// Book the exit in the database, then in the risk manager, so a failed
+    /// write leaves the position open in both to be retried
+    async fn close(&mut self, market: &str, reason: &str) -> Result<()> {
+        let Some(&id) = self.open.get(market) else {
+            return Ok(());
+        };
+
+        let (price, size_usd, pnl, pnl_pct) = {
+            let mut rm = self.risk_manager.lock().await;
+            let position = rm
+                .positions
+                .get(market)
+                .ok_or_else(|| anyhow!("Position not found: {}", market))?;
+            // A restored position may close before its market's first tick
+            let price = self.prices.get(market).copied().unwrap_or(position.current_price);
+            let (entry_price, size_usd) = (position.entry_price, position.size);
+            let pnl_pct = (price - entry_price) / entry_price;
+            let pnl = (price - entry_price) * (size_usd / entry_price);
+
+            self.database.close_position(id, price, pnl, pnl_pct, reason).await?;
+            rm.close_position(market, price, reason)?;
+            (price, size_usd, pnl, pnl_pct)
+        };
+        self.open.remove(market);
+
+        self.database.insert_trade(paper_trade("sell", id, market, price, size_usd + pnl)).await?;
+        metrics::TRADES_EXECUTED.with_label_values(&["sell"]).inc();
+
+        info!(
+            "📝 Paper sell #{} {} at {:.8} ({}): P&L ${:.2} ({:+.2}%)",
+            id, market, price, reason, pnl, pnl_pct * 100.0
+        );
+        Ok(())
+    }
+}
+
+/// A fill that never touched the chain: no signature, slippage or fees
+fn paper_trade(trade_type: &str, position_id: i64, market: &str, price: f64, size_usd: f64) -> TradeRecord {
+    TradeRecord {
+        position_id: Some(position_id),
+        trade_type: trade_type.to_string(),
+        symbol: market.to_string(),
+        price,
+        size_usd,
+        timestamp: Utc::now(),
+        signature: None,
+        slippage_bps: None,
+        fees_usd: None,
+        execution_time_ms: None,
+        fees_sol: None,
+        price_impact_pct: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::risk_manager::RiskConfig;
+
+    #[test]
+    fn test_run_mode_parse() {
+        assert_eq!("live".parse::<RunMode>().unwrap(), RunMode::Live);
+        assert_eq!("Simulate".parse::<RunMode>().unwrap(), RunMode::Simulate);
+        assert!("paper".parse::<RunMode>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_paper_position_opens_and_stops_out() {
+        let database = AsyncDatabase::new(Database::new(":memory:").unwrap());
+        let risk_manager = Arc::new(Mutex::new(RiskManager::new(RiskConfig::default(), 1000.0)));
+        let mut paper = PaperExecutor::new(Arc::clone(&risk_manager), database.clone());
+        let signal = Signal::new("BONK/SOL".to_string(), 0.9, 0.1);
+
+        // Nothing to fill at before the first tick
+        assert!(paper.on_signal(&signal).await.is_err());
+
+        paper.on_price("BONK/SOL", 1.0, Instant::now()).await.unwrap();
+        let id = paper.on_signal(&signal).await.unwrap();
+        assert!(paper.on_signal(&signal).await.is_err());
+        assert_eq!(database.get_open_positions(true).await.unwrap().len(), 1);
+        // Live-only queries never see it
+        assert!(database.get_open_positions(false).await.unwrap().is_empty());
+        assert!(database.get_open_position_mints().await.unwrap().is_empty());
+
+        // 10% down trips the 5% hard stop
+        paper.on_price("BONK/SOL", 0.9, Instant::now()).await.unwrap();
+        assert!(risk_manager.lock().await.positions.is_empty());
+        assert!(database.get_open_positions(true).await.unwrap().is_empty());
+
+        let closed = database.get_closed_positions(10, 0).await.unwrap();
+        assert_eq!(closed.len(), 1);
+        assert!(closed[0].paper);
+        assert_eq!(closed[0].exit_reason.as_deref(), Some("hard_stop"));
+        // Kelly sizes this at $208, capped at 20% of capital
+        assert!((closed[0].realized_pnl + 20.0).abs() < 1e-9);
+
+        let trades = database.get_trades_for_position(id).await.unwrap();
+        assert_eq!(trades.iter().map(|t| t.trade_type.as_str()).collect::<Vec<_>>(), ["buy", "sell"]);
+        assert!(trades.iter().all(|t| t.signature.is_none()));
+        assert!((trades[1].size_usd - 180.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_stale_price_exits_without_a_new_tick() {
+        let database = AsyncDatabase::new(Database::new(":memory:").unwrap());
+        let config = RiskConfig {
+            max_price_age_ms: 50,
+            stale_price_action: crate::risk_manager::StalePriceAction::Exit,
+            ..RiskConfig::default()
+        };
+        let risk_manager = Arc::new(Mutex::new(RiskManager::new(config, 1000.0)));
+        let mut paper = PaperExecutor::new(Arc::clone(&risk_manager), database.clone());
+
+        paper.on_price("BONK/SOL", 1.0, Instant::now()).await.unwrap();
+        paper.on_signal(&Signal::new("BONK/SOL".to_string(), 0.9, 0.1)).await.unwrap();
+        paper.check_positions().await.unwrap();
+        assert_eq!(risk_manager.lock().await.positions.len(), 1);
+
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        paper.check_positions().await.unwrap();
+        assert!(risk_manager.lock().await.positions.is_empty());
+        let closed = database.get_closed_positions(10, 0).await.unwrap();
+        assert_eq!(closed[0].exit_reason.as_deref(), Some("stale_price"));
+    }
+
+    #[tokio::test]
+    async fn test_restored_position_closes_before_its_first_tick() {
+        let database = AsyncDatabase::new(Database::new(":memory:").unwrap());
+        let risk_manager = Arc::new(Mutex::new(RiskManager::new(RiskConfig::default(), 1000.0)));
+        let mut first = PaperExecutor::new(Arc::clone(&risk_manager), database.clone());
+        first.on_price("BONK/SOL", 1.0, Instant::now()).await.unwrap();
+        let id = first.on_signal(&Signal::new("BONK/SOL".to_string(), 0.9, 0.1)).await.unwrap();
+
+        // A restart restores the row into a fresh risk manager and executor
+        let mut rm = RiskManager::new(RiskConfig::default(), 1000.0);
+        let (_, symbol, entry_price, _, size_usd) = database.get_open_positions(true).await.unwrap().remove(0);
+        rm.open_position(symbol.clone(), entry_price, size_usd).unwrap();
+        let risk_manager = Arc::new(Mutex::new(rm));
+        let mut paper = PaperExecutor::new(Arc::clone(&risk_manager), database.clone())
+            .with_open(HashMap::from([(symbol, id)]));
+
+        // It isn't sniped again, and still closes with no tick seen this run
+        paper.on_price("BONK/SOL", 1.0, Instant::now()).await.unwrap();
+        assert!(paper.on_signal(&Signal::new("BONK/SOL".to_string(), 0.9, 0.1)).await.is_err());
+        paper.prices.clear();
+        paper.close_all("shutdown").await;
+        assert!(risk_manager.lock().await.positions.is_empty());
+        assert!(database.get_open_positions(true).await.unwrap().is_empty());
+    }
+}
@@ -1,36 +1,290 @@
 use crate::types::TickData;
-use anyhow::Result;
-use questdb::ingress::Sender;
+use anyhow::{anyhow, Result};
+use questdb::ingress::{Buffer, Sender, TimestampMicros};
+use serde_json::Value;
 use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::Mutex;
 
+/// Rows buffered before `insert_tick` flushes on its own.
+pub const DEFAULT_BATCH_ROWS: usize = 500;
+/// Batches kept while QuestDB is unreachable before rows start being dropped.
+const MAX_PENDING_BATCHES: usize = 20;
+/// Rows fetched per query when replaying history.
+const REPLAY_PAGE_ROWS: usize = 10_000;
+
+struct Batch {
+    sender: Sender,
+    buffer: Buffer,
+    rows: usize,
+}
+
 /// Client for ingesting ticks into QuestDB with shared, asynchronous sender.
+///
+/// Ticks are buffered and written in batches: `insert_tick` flushes once
+/// `batch_rows` rows are pending, and callers flush on a timer and on shutdown
+/// so a quiet market doesn't leave rows sitting in memory.
 #[derive(Clone)]
 pub struct QuestDBClient {
-    sender: Arc<Mutex<Sender>>,
+    batch: Arc<Mutex<Batch>>,
+    batch_rows: usize,
+    http: reqwest::Client,
+    query_url: String,
 }
 
 impl QuestDBClient {
     /// Create a new client with a QuestDB HTTP ingress connection string.
     pub fn new(conf: &str) -> Self {
         let sender = Sender::from_conf(conf).expect("Invalid QuestDB config");
+        let buffer = sender.new_buffer();
         QuestDBClient {
-            sender: Arc::new(Mutex::new(sender)),
+            batch: Arc::new(Mutex::new(Batch {
+                sender,
+                buffer,
+                rows: 0,
+            })),
+            batch_rows: DEFAULT_BATCH_ROWS,
+            http: reqwest::Client::new(),
+            query_url: query_url_from_conf(conf),
         }
     }
 
-    /// Insert a tick into QuestDB (async compatible).
+    /// Base URL of the QuestDB HTTP API used for reads, e.g. `http://localhost:9000`.
+    /// Defaults to the `addr` of the ingress config.
+    pub fn with_query_url(mut self, url: &str) -> Self {
+        self.query_url = url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Flush after this many buffered rows (1 writes every tick immediately).
+    pub fn with_batch_rows(mut self, rows: usize) -> Self {
+        self.batch_rows = rows.max(1);
+        self
+    }
+
+    /// Buffer a tick, flushing if the batch is full (async compatible).
     pub async fn insert_tick(&self, tick: &TickData) -> Result<()> {
-        let mut sender = self.sender.lock().await;
-        let mut buffer = sender.new_buffer();
-        buffer
-            .table("memecoin_ticks")?
-            .symbol("symbol", &tick.symbol)?
-            .column_f64("price", tick.price)?
-            .column_f64("volume", tick.volume)?
-            .at_now()?;
-        // Flush to QuestDB
-        sender.flush(&mut buffer)?;
+        let mut batch = self.batch.lock().await;
+        batch.buffer.set_marker()?;
+        let row = batch
+            .buffer
+            .table("memecoin_ticks")
+            .and_then(|b| b.symbol("symbol", &tick.symbol))
+            .and_then(|b| b.symbol("source", &tick.source))
+            .and_then(|b| b.column_f64("price", tick.price))
+            .and_then(|b| b.column_f64("volume", tick.volume))
+            .and_then(|b| b.at(TimestampMicros::new(tick.timestamp * 1000)));
+        if let Err(e) = row {
+            // Drop the half-written row so the rest of the batch stays valid
+            batch.buffer.rewind_to_marker()?;
+            return Err(e.into());
+        }
+        batch.rows += 1;
+
+        if batch.rows >= self.batch_rows {
+            self.flush_batch(&mut batch)?;
+        }
         Ok(())
     }
+
+    /// Write all buffered rows to QuestDB, returning how many were sent.
+    pub async fn flush(&self) -> Result<usize> {
+        let mut batch = self.batch.lock().await;
+        self.flush_batch(&mut batch)
+    }
+
+    /// Rows buffered but not yet written.
+    pub async fn pending(&self) -> usize {
+        self.batch.lock().await.rows
+    }
+
+    /// Send recorded ticks for `markets` with `from <= timestamp < to` (epoch
+    /// millis, `to` open-ended if None) into `tx` in timestamp order, tagged
+    /// the same way the WebSocket feed does. Returns how many ticks were sent.
+    pub async fn stream_ticks(
+        &self,
+        markets: &[String],
+        from: i64,
+        to: Option<i64>,
+        tx: &UnboundedSender<TickData>,
+    ) -> Result<usize> {
+        // Ticks are stored under the feed's symbol, either the pair or its base
+        let symbols: Vec<String> = markets
+            .iter()
+            .flat_map(|market| [market.as_str(), market.split('/').next().unwrap_or(market)])
+            .map(sql_string)
+            .collect();
+        let mut filter = format!(
+            "symbol IN ({}) AND timestamp >= {}",
+            symbols.join(", "),
+            from * 1000
+        );
+        if let Some(to) = to {
+            filter.push_str(&format!(" AND timestamp < {}", to * 1000));
+        }
+
+        let mut sent = 0;
+        loop {
+            let query = format!(
+                "SELECT symbol, source, price, volume, cast(timestamp AS LONG) FROM memecoin_ticks \
+                 WHERE {} ORDER BY timestamp LIMIT {},{}",
+                filter,
+                sent,
+                sent + REPLAY_PAGE_ROWS
+            );
+            let response: Value = self
+                .http
+                .get(format!("{}/exec", self.query_url))
+                .query(&[("query", query.as_str())])
+                .send()
+                .await?
+                .json()
+                .await?;
+
+            let ticks = parse_ticks(&response)?;
+            let page_len = ticks.len();
+            for mut tick in ticks {
+                if let Some(market) = tick.find_market(markets) {
+                    tick.market = market.to_string();
+                }
+                if tx.send(tick).is_err() {
+                    return Ok(sent); // Pipeline shut down
+                }
+                sent += 1;
+            }
+            if page_len < REPLAY_PAGE_ROWS {
+                return Ok(sent);
+            }
+        }
+    }
+
+    fn flush_batch(&self, batch: &mut Batch) -> Result<usize> {
+        if batch.rows == 0 {
+            return Ok(0);
+        }
+        let Batch { sender, buffer, rows } = batch;
+        match sender.flush(buffer) {
+            Ok(()) => Ok(std::mem::take(rows)),
+            Err(e) => {
+                // Keep the rows for the next attempt, but don't grow without bound
+                if *rows >= self.batch_rows * MAX_PENDING_BATCHES {
+                    tracing::warn!("QuestDB unreachable, dropping {} buffered ticks", rows);
+                    buffer.clear();
+                    *rows = 0;
+                }
+                Err(e.into())
+            }
+        }
+    }
+}
+
+/// `http://host:port` from an ingress config like `http::addr=host:port;`
+fn query_url_from_conf(conf: &str) -> String {
+    let (scheme, params) = conf.split_once("::").unwrap_or(("http", conf));
+    let scheme = if scheme.ends_with('s') { "https" } else { "http" };
+    let addr = params
+        .split(';')
+        .find_map(|param| param.trim().strip_prefix("addr="))
+        .unwrap_or("localhost:9000");
+    format!("{}://{}", scheme, addr)
+}
+
+/// Quote a value as a SQL string literal
+fn sql_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Read ticks from a `/exec` response for the replay query's columns
+fn parse_ticks(response: &Value) -> Result<Vec<TickData>> {
+    if let Some(error) = response.get("error").and_then(Value::as_str) {
+        return Err(anyhow!("QuestDB query failed: {}", error));
+    }
+    let rows = response
+        .get("dataset")
+        .and_then(Value::as_array)
+        .ok_or_else(|| anyhow!("QuestDB response has no dataset"))?;
+
+    rows.iter()
+        .map(|row| {
+            let field = |i: usize| row.get(i).ok_or_else(|| anyhow!("Short tick row: {}", row));
+            Ok(TickData {
+                symbol: field(0)?.as_str().unwrap_or_default().to_string(),
+                source: field(1)?.as_str().unwrap_or_default().to_string(),
+                price: field(2)?.as_f64().ok_or_else(|| anyhow!("Bad price in {}", row))?,
+                volume: field(3)?.as_f64().unwrap_or(0.0),
+                timestamp: field(4)?.as_i64().ok_or_else(|| anyhow!("Bad timestamp in {}", row))? / 1000,
+                market: String::new(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tick(timestamp: i64) -> TickData {
+        TickData {
+            symbol: "BONK".to_string(),
+            price: 0.00002,
+            volume: 10.0,
+            timestamp,
+            source: "test".to_string(),
+            market: "BONK/SOL".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ticks_are_buffered_until_flush() {
+        // Nothing listens here; only flushing touches the network
+        let client = QuestDBClient::new("http::addr=127.0.0.1:1;retry_timeout=0;").with_batch_rows(10);
+
+        client.insert_tick(&tick(1_700_000_000_000)).await.unwrap();
+        client.insert_tick(&tick(1_700_000_000_001)).await.unwrap();
+        assert_eq!(client.pending().await, 2);
+
+        // A failed flush keeps the rows for the next attempt
+        assert!(client.flush().await.is_err());
+        assert_eq!(client.pending().await, 2);
+    }
+
+    #[test]
+    fn test_query_url_from_conf() {
+        assert_eq!(query_url_from_conf("http::addr=localhost:9000;"), "http://localhost:9000");
+        assert_eq!(
+            query_url_from_conf("https::addr=db.example.com:443;username=a;password=b;"),
+            "https://db.example.com:443"
+        );
+    }
+
+    #[test]
+    fn test_parse_replay_ticks() {
+        let response = json!({
+            "query": "SELECT ...",
+            "columns": [
+                { "name": "symbol", "type": "SYMBOL" },
+                { "name": "source", "type": "SYMBOL" },
+                { "name": "price", "type": "DOUBLE" },
+                { "name": "volume", "type": "DOUBLE" },
+                { "name": "cast", "type": "LONG" }
+            ],
+            "dataset": [
+                ["BONK", "jupiter_ws", 0.00002, 150.0, 1_700_000_000_000_000i64],
+                ["BONK", "jupiter_ws", 0.000021, null, 1_700_000_000_250_000i64]
+            ],
+            "count": 2
+        });
+
+        let ticks = parse_ticks(&response).unwrap();
+        assert_eq!(ticks.len(), 2);
+        assert_eq!(ticks[0].timestamp, 1_700_000_000_000);
+        assert_eq!(ticks[1].timestamp, 1_700_000_000_250);
+        assert_eq!(ticks[1].volume, 0.0);
+        assert_eq!(ticks[0].source, "jupiter_ws");
+
+        let error = json!({ "query": "SELECT ...", "error": "table does not exist", "position": 0 });
+        assert!(parse_ticks(&error).is_err());
+        assert_eq!(sql_string("it's"), "'it''s'");
+    }
 }
@@ -0,0 +1,161 @@
+//! Market regime filter
+//!
+//! The stored patterns mostly come from ordinary conditions, so a signal that
+//! fires in a dead chop or in a blow-off move deserves less capital, or none.
+//! [`RegimeDetector`] buckets a window's per-tick return volatility into a
+//! [`Regime`] and maps each one to a [`RegimeAction`].
+
+use crate::types::SignalRejection;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Regime {
+    Calm,
+    Normal,
+    Volatile,
+}
+
+impl Regime {
+    /// Short label for metrics and logs
+    pub fn label(&self) -> &'static str {
+        match self {
+            Regime::Calm => "calm",
+            Regime::Normal => "normal",
+            Regime::Volatile => "volatile",
+        }
+    }
+}
+
+impl fmt::Display for Regime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// What to do with signals that pass the gate in a given regime
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegimeAction {
+    Trade,
+    /// Trade at the detector's reduced size
+    Reduce,
+    StandDown,
+}
+
+impl FromStr for RegimeAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "trade" => Ok(RegimeAction::Trade),
+            "reduce" => Ok(RegimeAction::Reduce),
+            "stand-down" | "stand_down" => Ok(RegimeAction::StandDown),
+            other => Err(anyhow!(
+                "unknown regime action '{}' (expected trade, reduce or stand-down)",
+                other
+            )),
+        }
+    }
+}
+
+/// Classifies volatility into regimes and decides how to trade each
+#[derive(Clone, Debug)]
+pub struct RegimeDetector {
+    /// Volatility at or below this is calm (0 = never calm)
+    pub calm_below: f64,
+    /// Volatility above this is volatile (0 = never volatile)
+    pub volatile_above: f64,
+    pub calm: RegimeAction,
+    pub normal: RegimeAction,
+    pub volatile: RegimeAction,
+    /// Position size multiplier for `RegimeAction::Reduce`
+    pub reduced_size: f64,
+}
+
+impl Default for RegimeDetector {
+    fn default() -> Self {
+        Self {
+            calm_below: 0.0005,
+            volatile_above: 0.02,
+            calm: RegimeAction::StandDown,
+            normal: RegimeAction::Trade,
+            volatile: RegimeAction::Reduce,
+            reduced_size: 0.5,
+        }
+    }
+}
+
+impl RegimeDetector {
+    pub fn classify(&self, volatility: f64) -> Regime {
+        if self.volatile_above > 0.0 && volatility > self.volatile_above {
+            Regime::Volatile
+        } else if self.calm_below > 0.0 && volatility <= self.calm_below {
+            Regime::Calm
+        } else {
+            Regime::Normal
+        }
+    }
+
+    pub fn action(&self, regime: Regime) -> RegimeAction {
+        match regime {
+            Regime::Calm => self.calm,
+            Regime::Normal => self.normal,
+            Regime::Volatile => self.volatile,
+        }
+    }
+
+    /// Size factor to trade a signal with at `volatility`, or the rejection
+    /// if the regime says to stand down
+    pub fn check(&self, volatility: f64) -> Result<f64, SignalRejection> {
+        let regime = self.classify(volatility);
+        match self.action(regime) {
+            RegimeAction::Trade => Ok(1.0),
+            RegimeAction::Reduce => Ok(self.reduced_size.clamp(0.0, 1.0)),
+            RegimeAction::StandDown => Err(SignalRejection::RegimeStandDown { regime, volatility }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_buckets() {
+        let detector = RegimeDetector::default();
+        assert_eq!(detector.classify(0.0), Regime::Calm);
+        assert_eq!(detector.classify(0.0005), Regime::Calm);
+        assert_eq!(detector.classify(0.01), Regime::Normal);
+        assert_eq!(detector.classify(0.02), Regime::Normal);
+        assert_eq!(detector.classify(0.03), Regime::Volatile);
+
+        let off = RegimeDetector {
+            calm_below: 0.0,
+            volatile_above: 0.0,
+            ..detector
+        };
+        assert_eq!(off.classify(0.0), Regime::Normal);
+        assert_eq!(off.classify(1.0), Regime::Normal);
+    }
+
+    #[test]
+    fn test_regime_actions() {
+        let detector = RegimeDetector::default();
+        assert_eq!(
+            detector.check(0.0001),
+            Err(SignalRejection::RegimeStandDown {
+                regime: Regime::Calm,
+                volatility: 0.0001
+            })
+        );
+        assert_eq!(detector.check(0.01), Ok(1.0));
+        assert_eq!(detector.check(0.03), Ok(0.5));
+
+        assert_eq!("stand-down".parse::<RegimeAction>().unwrap(), RegimeAction::StandDown);
+        assert_eq!("Reduce".parse::<RegimeAction>().unwrap(), RegimeAction::Reduce);
+        assert!("hold".parse::<RegimeAction>().is_err());
+    }
+}
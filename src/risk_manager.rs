@@ -1,9 +1,12 @@
-use crate::types::{Signal, TickData};
+use crate::types::{Candle, Signal, TickData};
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tracing::{info, warn};
+use tracing::{error, info, warn};
 
 /// Risk configuration with position limits and stop-loss rules
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -16,6 +19,8 @@ pub struct RiskConfig {
     // Stop-loss rules
     pub hard_stop_loss_pct: f64,           // Hard stop-loss % (e.g., 0.05 = 5%)
     pub trailing_stop_loss_pct: f64,       // Trailing stop % from peak
+    #[serde(default)]
+    pub trailing_schedule: Vec<(f64, f64)>, // (peak profit multiple, trail %): tighter trail once a position is up that much
     pub portfolio_stop_loss_pct: f64,      // Daily portfolio stop-loss %
 
     // Diversification limits
@@ -31,9 +36,73 @@ pub struct RiskConfig {
     pub max_weekly_drawdown_pct: f64,      // Max weekly drawdown
     pub cooldown_after_loss_streak: usize, // Number of losses before cooldown
     pub cooldown_duration_minutes: u64,    // Cooldown duration
+    pub halt_after_loss_streak: usize,     // Consecutive losses that halt trading until resumed
 
     // Kelly Criterion settings
     pub kelly_fraction: f64,               // Fraction of Kelly to use (0.25 = quarter Kelly)
+
+    // Position sizing
+    #[serde(default)]
+    pub sizing: SizingMethod,
+
+    // Price staleness
+    #[serde(default = "default_max_price_age_ms")]
+    pub max_price_age_ms: u64,             // Prices older than this can't be trusted for stops (0 = no limit)
+    #[serde(default)]
+    pub stale_price_action: StalePriceAction, // What to do with a position whose price went stale
+}
+
+fn default_max_price_age_ms() -> u64 {
+    10_000
+}
+
+impl RiskConfig {
+    /// Trailing stop % for a position whose peak is `peak_mult` times its entry:
+    /// the highest `trailing_schedule` rung reached, else `trailing_stop_loss_pct`
+    pub fn trailing_pct_for(&self, peak_mult: f64) -> f64 {
+        self.trailing_schedule
+            .iter()
+            .filter(|(profit_mult, _)| peak_mult >= *profit_mult)
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .map_or(self.trailing_stop_loss_pct, |(_, trail_pct)| *trail_pct)
+    }
+}
+
+/// How `calculate_position_size` turns a signal into a USD size
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum SizingMethod {
+    /// Fractional Kelly on signal confidence, scaled down in high volatility
+    #[default]
+    Kelly,
+    /// Risk a fixed USD amount per trade with the stop `atr_mult` ATRs away:
+    /// size = risk_per_trade_usd / (atr_mult * ATR%)
+    AtrTarget {
+        risk_per_trade_usd: f64,
+        atr_mult: f64,
+    },
+}
+
+/// What `update_positions` does with a position whose latest price is
+/// older than `max_price_age_ms`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StalePriceAction {
+    /// Skip its stops and flag it `price_stale` until a fresh price arrives
+    #[default]
+    Hold,
+    /// Close it with reason `stale_price`; its stops are blind
+    Exit,
+}
+
+impl FromStr for StalePriceAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "hold" => Ok(StalePriceAction::Hold),
+            "exit" => Ok(StalePriceAction::Exit),
+            other => Err(anyhow!("unknown stale price action '{}' (expected hold or exit)", other)),
+        }
+    }
 }
 
 impl Default for RiskConfig {
@@ -44,6 +113,7 @@ impl Default for RiskConfig {
             max_leverage: 1.0,
             hard_stop_loss_pct: 0.05,
             trailing_stop_loss_pct: 0.03,
+            trailing_schedule: Vec::new(),
             portfolio_stop_loss_pct: 0.15,
             max_correlated_positions: 3,
             max_total_positions: 5,
@@ -53,23 +123,31 @@ impl Default for RiskConfig {
             max_weekly_drawdown_pct: 0.25,
             cooldown_after_loss_streak: 3,
             cooldown_duration_minutes: 60,
+            halt_after_loss_streak: 5,
             kelly_fraction: 0.25,
+            sizing: SizingMethod::Kelly,
+            max_price_age_ms: default_max_price_age_ms(),
+            stale_price_action: StalePriceAction::Hold,
         }
     }
 }
 
 /// Position tracking
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct Position {
     pub symbol: String,
     pub entry_price: f64,
     pub current_price: f64,
     pub size: f64,              // Position size in USD
+    #[serde(skip)]
     pub entry_time: Instant,
     pub peak_price: f64,        // For trailing stop
     pub trailing_stop: f64,     // Current trailing stop level
     pub unrealized_pnl: f64,
     pub unrealized_pnl_pct: f64,
+    #[serde(skip)]
+    pub price_updated: Instant, // When `current_price` was observed
+    pub price_stale: bool,      // Price older than `max_price_age_ms`; stops aren't evaluated
 }
 
 impl Position {
@@ -84,6 +162,8 @@ impl Position {
             trailing_stop: entry_price * 0.97, // Initial 3% trailing stop
             unrealized_pnl: 0.0,
             unrealized_pnl_pct: 0.0,
+            price_updated: Instant::now(),
+            price_stale: false,
         }
     }
 
@@ -192,6 +272,7 @@ pub enum RiskError {
     InsufficientCapital,
     HardStopTriggered,
     TrailingStopTriggered,
+    TradingHalted,
 }
 
 impl std::fmt::Display for RiskError {
@@ -205,6 +286,7 @@ impl std::fmt::Display for RiskError {
             RiskError::InsufficientCapital => write!(f, "Insufficient capital available"),
             RiskError::HardStopTriggered => write!(f, "Hard stop-loss triggered"),
             RiskError::TrailingStopTriggered => write!(f, "Trailing stop-loss triggered"),
+            RiskError::TradingHalted => write!(f, "Trading halted"),
         }
     }
 }
@@ -217,6 +299,11 @@ pub struct RiskManager {
     pub portfolio: Portfolio,
     pub positions: HashMap<String, Position>,
     pub volatility_cache: HashMap<String, f64>,
+    /// Latest ATR per symbol as a fraction of price
+    pub atr_cache: HashMap<String, f64>,
+    /// Kill switch: while set, every trade is rejected until `resume()`
+    trading_halted: Arc<AtomicBool>,
+    halt_reason: Option<String>,
 }
 
 impl RiskManager {
@@ -227,15 +314,78 @@ impl RiskManager {
             portfolio: Portfolio::new(starting_capital),
             positions: HashMap::new(),
             volatility_cache: HashMap::new(),
+            atr_cache: HashMap::new(),
+            trading_halted: Arc::new(AtomicBool::new(false)),
+            halt_reason: None,
+        }
+    }
+
+    /// Stop all trading until `resume()` is called. Repeat calls keep the first reason.
+    pub fn halt(&mut self, reason: &str) {
+        if self.trading_halted.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        error!("🛑 TRADING HALTED: {}", reason);
+        self.halt_reason = Some(reason.to_string());
+    }
+
+    /// Re-enable trading after a halt
+    pub fn resume(&mut self) {
+        if self.trading_halted.swap(false, Ordering::SeqCst) {
+            info!("Trading resumed (was halted: {})", self.halt_reason.as_deref().unwrap_or("unknown"));
         }
+        self.halt_reason = None;
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.trading_halted.load(Ordering::SeqCst)
     }
 
-    /// Calculate optimal position size using Kelly Criterion with volatility scaling
+    /// Shared flag for checking the kill switch without taking the manager lock
+    pub fn halt_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.trading_halted)
+    }
+
+    /// Calculate position size with the configured `SizingMethod`.
+    /// `atr_pct` is the symbol's ATR as a fraction of price; ATR sizing falls
+    /// back to Kelly when it isn't known yet.
     pub fn calculate_position_size(
         &self,
         signal: &Signal,
         estimated_volatility: f64,
+        atr_pct: Option<f64>,
     ) -> Result<f64> {
+        let base_size = match (&self.config.sizing, atr_pct) {
+            (SizingMethod::AtrTarget { risk_per_trade_usd, atr_mult }, Some(atr_pct)) if atr_pct > 0.0 => {
+                let size = risk_per_trade_usd / (atr_mult * atr_pct);
+                info!(
+                    "Position sizing: ATR={:.2}%, Stop={:.2}%, Risk=${:.2}, Base=${:.2}",
+                    atr_pct * 100.0, atr_mult * atr_pct * 100.0, risk_per_trade_usd, size
+                );
+                size
+            }
+            (SizingMethod::AtrTarget { .. }, _) => {
+                warn!("No ATR available, falling back to Kelly sizing");
+                self.kelly_size(signal, estimated_volatility)
+            }
+            (SizingMethod::Kelly, _) => self.kelly_size(signal, estimated_volatility),
+        };
+        // The market regime can call for trading smaller
+        let base_size = base_size * signal.size_factor.clamp(0.0, 1.0);
+
+        // Apply hard limits
+        let available = self.portfolio.available_capital;
+        let max_pct_size = available * self.config.max_position_pct_portfolio;
+        let max_abs_size = self.config.max_position_size_usd;
+
+        let final_size = base_size.min(max_pct_size).min(max_abs_size);
+        info!("Position size: Base=${:.2}, Final=${:.2}", base_size, final_size);
+
+        Ok(final_size)
+    }
+
+    /// Kelly Criterion with volatility scaling, before hard limits
+    fn kelly_size(&self, signal: &Signal, estimated_volatility: f64) -> f64 {
         let win_rate = signal.confidence as f64;
 
         // For simplicity, assume win/loss ratio is 1.5:1 (adjust based on backtesting)
@@ -257,21 +407,14 @@ impl RiskManager {
         };
 
         // Calculate base size
-        let available = self.portfolio.available_capital;
-        let base_size = available * fractional_kelly * vol_scalar;
-
-        // Apply hard limits
-        let max_pct_size = available * self.config.max_position_pct_portfolio;
-        let max_abs_size = self.config.max_position_size_usd;
-
-        let final_size = base_size.min(max_pct_size).min(max_abs_size);
+        let base_size = self.portfolio.available_capital * fractional_kelly * vol_scalar;
 
         info!(
-            "Position sizing: Kelly={:.3}, Vol_scalar={:.3}, Base=${:.2}, Final=${:.2}",
-            fractional_kelly, vol_scalar, base_size, final_size
+            "Position sizing: Kelly={:.3}, Vol_scalar={:.3}, Base=${:.2}",
+            fractional_kelly, vol_scalar, base_size
         );
 
-        Ok(final_size)
+        base_size
     }
 
     /// Validate if a trade should be allowed
@@ -281,6 +424,13 @@ impl RiskManager {
         size: f64,
         estimated_volatility: f64,
     ) -> Result<(), RiskError> {
+        // Kill switch overrides everything else
+        if self.is_halted() {
+            warn!("Trade rejected: trading halted ({})",
+                  self.halt_reason.as_deref().unwrap_or("unknown"));
+            return Err(RiskError::TradingHalted);
+        }
+
         // Check position count limit
         if self.positions.len() >= self.config.max_total_positions {
             warn!("Trade rejected: max positions reached ({}/{})",
@@ -341,8 +491,37 @@ impl RiskManager {
         Ok(())
     }
 
-    /// Open a new position
+    /// Project what opening a position of `size` USD would do to the
+    /// portfolio, without changing any state.
+    pub fn preview_open(&self, signal: &Signal, size: f64) -> OpenPreview {
+        let position_value: f64 = self.positions.values()
+            .map(|p| p.current_price * (p.size / p.entry_price))
+            .sum();
+        let exposure_usd = position_value + size;
+        let max_loss_usd = size * self.config.hard_stop_loss_pct;
+
+        OpenPreview {
+            size_usd: size,
+            confidence: signal.confidence,
+            exposure_usd,
+            exposure_pct: exposure_usd / self.portfolio.current_capital,
+            remaining_capital: self.portfolio.available_capital - size,
+            num_positions: self.positions.len() + 1,
+            max_loss_usd,
+            daily_pnl_pct_at_stop: (self.portfolio.daily_pnl - max_loss_usd) / self.portfolio.day_start_capital,
+        }
+    }
+
+    /// Whether a stop-out of the previewed position would breach the daily drawdown limit
+    pub fn breaches_daily_drawdown(&self, preview: &OpenPreview) -> bool {
+        preview.daily_pnl_pct_at_stop < -self.config.max_daily_drawdown_pct
+    }
+
+    /// Open a new position; fails if `symbol` already has one
     pub fn open_position(&mut self, symbol: String, entry_price: f64, size: f64) -> Result<()> {
+        if self.positions.contains_key(&symbol) {
+            return Err(anyhow!("Position already open: {}", symbol));
+        }
         let position = Position::new(symbol.clone(), entry_price, size);
 
         // Update portfolio
@@ -356,14 +535,48 @@ impl RiskManager {
         Ok(())
     }
 
-    /// Update all positions with current prices and check stops
-    pub fn update_positions(&mut self, current_prices: &HashMap<String, f64>) -> Vec<(String, String)> {
+    /// Update all positions with current prices (and when each was observed)
+    /// and check stops
+    ///
+    /// A position whose latest price is older than `max_price_age_ms` is
+    /// flagged `price_stale` and skips its stops, or is returned with reason
+    /// `stale_price` under `StalePriceAction::Exit`.
+    pub fn update_positions(&mut self, current_prices: &HashMap<String, (f64, Instant)>) -> Vec<(String, String)> {
         let mut stops_triggered = Vec::new();
+        let now = Instant::now();
+        let max_age = (self.config.max_price_age_ms > 0).then(|| Duration::from_millis(self.config.max_price_age_ms));
 
         for (symbol, position) in self.positions.iter_mut() {
-            if let Some(&price) = current_prices.get(symbol) {
+            let quote = current_prices.get(symbol);
+            // A price older than the one already held never replaces it
+            if let Some(&(price, observed_at)) = quote.filter(|(_, at)| *at >= position.price_updated) {
                 position.update_price(price);
-                position.update_trailing_stop(self.config.trailing_stop_loss_pct);
+                position.price_updated = observed_at;
+            }
+
+            let age = now.saturating_duration_since(position.price_updated);
+            let stale = max_age.is_some_and(|max_age| age > max_age);
+            if stale != position.price_stale {
+                position.price_stale = stale;
+                if stale {
+                    warn!("{}: price is {:.1?} old (limit {:?}), stops can't be trusted",
+                          symbol, age, max_age.unwrap_or_default());
+                } else {
+                    info!("{}: price is fresh again", symbol);
+                }
+            }
+            if stale {
+                if self.config.stale_price_action == StalePriceAction::Exit {
+                    stops_triggered.push((symbol.clone(), "stale_price".to_string()));
+                }
+                continue;
+            }
+
+            if quote.is_some() {
+                let price = position.current_price;
+                // Rung is picked from the peak, so a pullback never loosens the stop
+                let trailing_pct = self.config.trailing_pct_for(position.peak_price / position.entry_price);
+                position.update_trailing_stop(trailing_pct);
 
                 // Check hard stop-loss
                 if position.unrealized_pnl_pct < -self.config.hard_stop_loss_pct {
@@ -418,6 +631,18 @@ impl RiskManager {
             self.portfolio.peak_capital = self.portfolio.current_capital;
         }
 
+        // Trip the kill switch on limits that need a human to look
+        let daily_dd = self.portfolio.daily_pnl_pct();
+        if daily_dd < -self.config.max_daily_drawdown_pct {
+            self.halt(&format!(
+                "daily drawdown {:.2}% exceeds limit {:.2}%",
+                daily_dd * 100.0,
+                self.config.max_daily_drawdown_pct * 100.0
+            ));
+        } else if self.portfolio.consecutive_losses >= self.config.halt_after_loss_streak {
+            self.halt(&format!("{} consecutive losses", self.portfolio.consecutive_losses));
+        }
+
         info!(
             "Closed position: {} at ${:.4}, P&L=${:.2} ({:.2}%), Reason: {}",
             symbol, exit_price, pnl, pnl_pct * 100.0, reason
@@ -426,6 +651,15 @@ impl RiskManager {
         Ok(pnl)
     }
 
+    /// Drop a position that left the wallet without a known exit price,
+    /// returning its capital without booking a P&L or a trade
+    pub fn forget_position(&mut self, symbol: &str) -> Option<Position> {
+        let position = self.positions.remove(symbol)?;
+        self.portfolio.available_capital += position.size;
+        warn!("Forgot position: {} (no exit price, no P&L booked)", symbol);
+        Some(position)
+    }
+
     /// Get current portfolio metrics
     pub fn get_metrics(&self) -> RiskMetrics {
         let total_position_value: f64 = self.positions.values()
@@ -468,6 +702,8 @@ impl RiskManager {
             sharpe_estimate,
             consecutive_losses: self.portfolio.consecutive_losses,
             consecutive_wins: self.portfolio.consecutive_wins,
+            trading_halted: self.is_halted(),
+            halt_reason: self.halt_reason.clone(),
         }
     }
 
@@ -496,6 +732,31 @@ impl RiskManager {
 
         volatility
     }
+
+    /// Average True Range over the last `vol_lookback_periods` candles, as a
+    /// fraction of the latest close. None until there are two candles.
+    pub fn calculate_atr(&mut self, symbol: &str, candles: &[Candle]) -> Option<f64> {
+        let last_close = candles.last()?.close;
+        if candles.len() < 2 || last_close <= 0.0 {
+            return None;
+        }
+
+        // True range needs the previous close, so the first candle only seeds it
+        let true_ranges: Vec<f64> = candles
+            .windows(2)
+            .map(|pair| {
+                let (prev_close, candle) = (pair[0].close, &pair[1]);
+                (candle.high - candle.low)
+                    .max((candle.high - prev_close).abs())
+                    .max((candle.low - prev_close).abs())
+            })
+            .collect();
+        let recent = &true_ranges[true_ranges.len().saturating_sub(self.config.vol_lookback_periods)..];
+        let atr_pct = recent.iter().sum::<f64>() / recent.len() as f64 / last_close;
+
+        self.atr_cache.insert(symbol.to_string(), atr_pct);
+        Some(atr_pct)
+    }
 }
 
 /// Risk metrics for monitoring
@@ -517,4 +778,224 @@ pub struct RiskMetrics {
     pub sharpe_estimate: f64,
     pub consecutive_losses: usize,
     pub consecutive_wins: usize,
+    pub trading_halted: bool,
+    pub halt_reason: Option<String>,
+}
+
+/// Projected portfolio state if a position were opened, from `preview_open`
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenPreview {
+    pub size_usd: f64,
+    pub confidence: f32,
+    pub exposure_usd: f64,           // Value of all positions including this one
+    pub exposure_pct: f64,           // Exposure as a fraction of total capital
+    pub remaining_capital: f64,      // Available capital after the fill
+    pub num_positions: usize,
+    pub max_loss_usd: f64,           // Loss if this position hits the hard stop
+    pub daily_pnl_pct_at_stop: f64,  // Daily P&L if it does
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(high: f64, low: f64, close: f64) -> Candle {
+        Candle { open: close, high, low, close }
+    }
+
+    #[test]
+    fn test_trailing_schedule_tightens_with_profit() {
+        let config = RiskConfig {
+            hard_stop_loss_pct: 0.5,
+            trailing_stop_loss_pct: 0.15,
+            trailing_schedule: vec![(3.0, 0.08), (2.0, 0.10)],
+            ..RiskConfig::default()
+        };
+        assert_eq!(config.trailing_pct_for(1.5), 0.15);
+        assert_eq!(config.trailing_pct_for(2.5), 0.10);
+        assert_eq!(config.trailing_pct_for(4.0), 0.08);
+
+        let mut rm = RiskManager::new(config, 10_000.0);
+        rm.open_position("BONK".to_string(), 1.0, 100.0).unwrap();
+        let prices = |price: f64| HashMap::from([("BONK".to_string(), (price, Instant::now()))]);
+
+        // 15% trail under 2x
+        assert!(rm.update_positions(&prices(1.5)).is_empty());
+        assert!((rm.positions["BONK"].trailing_stop - 1.275).abs() < 1e-9);
+
+        // 8% trail once the peak passes 3x, and it stays there on the pullback
+        assert!(rm.update_positions(&prices(4.0)).is_empty());
+        assert!((rm.positions["BONK"].trailing_stop - 3.68).abs() < 1e-9);
+        let stops = rm.update_positions(&prices(3.6));
+        assert_eq!(stops, vec![("BONK".to_string(), "trailing_stop".to_string())]);
+        assert!((rm.positions["BONK"].trailing_stop - 3.68).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stale_prices_skip_stops_or_exit() {
+        let config = RiskConfig {
+            max_price_age_ms: 1_000,
+            ..RiskConfig::default()
+        };
+        let mut rm = RiskManager::new(config, 10_000.0);
+        rm.open_position("BONK".to_string(), 1.0, 100.0).unwrap();
+        let old = Instant::now();
+        rm.positions.get_mut("BONK").unwrap().price_updated = old - Duration::from_secs(5);
+
+        // Through the hard stop, but seen 3s ago: held and flagged, not stopped out
+        let prices = HashMap::from([("BONK".to_string(), (0.5, old - Duration::from_secs(3)))]);
+        assert!(rm.update_positions(&prices).is_empty());
+        assert!(rm.positions["BONK"].price_stale);
+        assert_eq!(rm.positions["BONK"].current_price, 0.5);
+
+        // A fresh price clears the flag and the stops run again
+        let prices = HashMap::from([("BONK".to_string(), (0.9, Instant::now()))]);
+        let stops = rm.update_positions(&prices);
+        assert_eq!(stops, vec![("BONK".to_string(), "hard_stop".to_string())]);
+        assert!(!rm.positions["BONK"].price_stale);
+
+        // An older price never overwrites a newer one
+        let prices = HashMap::from([("BONK".to_string(), (2.0, old - Duration::from_secs(1)))]);
+        rm.update_positions(&prices);
+        assert_eq!(rm.positions["BONK"].current_price, 0.9);
+
+        // No price at all for too long exits under `Exit`
+        rm.config.stale_price_action = StalePriceAction::Exit;
+        rm.positions.get_mut("BONK").unwrap().price_updated = old - Duration::from_secs(2);
+        let stops = rm.update_positions(&HashMap::new());
+        assert_eq!(stops, vec![("BONK".to_string(), "stale_price".to_string())]);
+
+        assert_eq!("EXIT".parse::<StalePriceAction>().unwrap(), StalePriceAction::Exit);
+        assert!("close".parse::<StalePriceAction>().is_err());
+    }
+
+    #[test]
+    fn test_halt_rejects_trades_until_resumed() {
+        let mut rm = RiskManager::new(RiskConfig::default(), 1_000.0);
+        let signal = Signal::new("BONK".to_string(), 0.9, 0.1);
+        assert!(rm.validate_trade(&signal, 100.0, 0.02).is_ok());
+
+        rm.halt("manual");
+        rm.halt("second reason");
+        assert!(rm.is_halted());
+        assert!(rm.halt_flag().load(Ordering::SeqCst));
+        assert_eq!(rm.get_metrics().halt_reason.as_deref(), Some("manual"));
+        assert!(matches!(rm.validate_trade(&signal, 100.0, 0.02), Err(RiskError::TradingHalted)));
+
+        rm.resume();
+        assert!(!rm.is_halted());
+        assert_eq!(rm.get_metrics().halt_reason, None);
+        assert!(rm.validate_trade(&signal, 100.0, 0.02).is_ok());
+    }
+
+    #[test]
+    fn test_daily_drawdown_trips_halt() {
+        let mut rm = RiskManager::new(RiskConfig::default(), 1_000.0);
+        rm.open_position("BONK".to_string(), 1.0, 200.0).unwrap();
+
+        // -$160 on $1,000 is past the 15% daily limit
+        rm.close_position("BONK", 0.2, "hard_stop").unwrap();
+        assert!(rm.is_halted());
+        assert!(rm.get_metrics().halt_reason.unwrap().starts_with("daily drawdown"));
+    }
+
+    #[test]
+    fn test_forget_position_returns_capital_without_a_trade() {
+        let mut rm = RiskManager::new(RiskConfig::default(), 1_000.0);
+        rm.open_position("BONK".to_string(), 1.0, 200.0).unwrap();
+
+        assert!(rm.forget_position("BONK").is_some());
+        assert!(rm.forget_position("BONK").is_none());
+        assert!(rm.positions.is_empty());
+        assert_eq!(rm.portfolio.available_capital, 1_000.0);
+        assert_eq!(rm.portfolio.total_trades, 0);
+    }
+
+    #[test]
+    fn test_open_position_rejects_a_held_symbol() {
+        let mut rm = RiskManager::new(RiskConfig::default(), 1_000.0);
+        rm.open_position("BONK".to_string(), 1.0, 200.0).unwrap();
+
+        assert!(rm.open_position("BONK".to_string(), 2.0, 100.0).is_err());
+        assert_eq!(rm.positions["BONK"].entry_price, 1.0);
+        assert_eq!(rm.portfolio.available_capital, 800.0);
+    }
+
+    #[test]
+    fn test_loss_streak_trips_halt_and_only_resume_clears_it() {
+        let config = RiskConfig {
+            halt_after_loss_streak: 2,
+            ..RiskConfig::default()
+        };
+        let mut rm = RiskManager::new(config, 10_000.0);
+        let signal = Signal::new("BONK".to_string(), 0.9, 0.1);
+
+        rm.open_position("BONK".to_string(), 1.0, 100.0).unwrap();
+        rm.close_position("BONK", 0.99, "trailing_stop").unwrap();
+        assert!(!rm.is_halted());
+        rm.open_position("BONK".to_string(), 1.0, 100.0).unwrap();
+        rm.close_position("BONK", 0.99, "trailing_stop").unwrap();
+        assert!(rm.is_halted());
+        assert_eq!(rm.get_metrics().halt_reason.as_deref(), Some("2 consecutive losses"));
+
+        // A win afterwards doesn't lift it
+        rm.open_position("BONK".to_string(), 1.0, 100.0).unwrap();
+        rm.close_position("BONK", 1.5, "take_profit").unwrap();
+        assert!(rm.is_halted());
+        assert!(matches!(rm.validate_trade(&signal, 100.0, 0.02), Err(RiskError::TradingHalted)));
+
+        rm.resume();
+        assert!(rm.validate_trade(&signal, 100.0, 0.02).is_ok());
+    }
+
+    #[test]
+    fn test_atr_target_sizing() {
+        let config = RiskConfig {
+            sizing: SizingMethod::AtrTarget { risk_per_trade_usd: 10.0, atr_mult: 2.0 },
+            ..RiskConfig::default()
+        };
+        let mut rm = RiskManager::new(config, 10_000.0);
+
+        // True ranges of 0.1 (one via a gap from the previous close) on a close of 1.0
+        let candles = [candle(1.0, 0.9, 1.0), candle(1.05, 0.95, 1.0), candle(1.1, 1.05, 1.0)];
+        let atr_pct = rm.calculate_atr("BONK", &candles).unwrap();
+        assert!((atr_pct - 0.1).abs() < 1e-9);
+        assert_eq!(rm.atr_cache.get("BONK"), Some(&atr_pct));
+
+        // $10 at risk with a 20% stop
+        let signal = Signal::new("BONK".to_string(), 0.9, 0.1);
+        let size = rm.calculate_position_size(&signal, 0.02, Some(atr_pct)).unwrap();
+        assert!((size - 50.0).abs() < 1e-9);
+
+        // Without an ATR it sizes like Kelly, still under the hard limits
+        let kelly = rm.calculate_position_size(&signal, 0.02, None).unwrap();
+        assert!(kelly > 0.0 && kelly <= rm.config.max_position_size_usd);
+        assert_eq!(rm.calculate_atr("BONK", &candles[..1]), None);
+    }
+
+    #[test]
+    fn test_preview_open_leaves_state_untouched() {
+        let config = RiskConfig {
+            hard_stop_loss_pct: 0.10,
+            max_daily_drawdown_pct: 0.02,
+            ..RiskConfig::default()
+        };
+        let mut rm = RiskManager::new(config, 1_000.0);
+        rm.open_position("BONK".to_string(), 1.0, 100.0).unwrap();
+        rm.update_positions(&HashMap::from([("BONK".to_string(), (1.5, Instant::now()))]));
+
+        let signal = Signal::new("BONK".to_string(), 0.8, 0.1);
+        let preview = rm.preview_open(&signal, 200.0);
+        assert!((preview.exposure_usd - 350.0).abs() < 1e-9);
+        assert!((preview.exposure_pct - 0.35).abs() < 1e-9);
+        assert!((preview.remaining_capital - 700.0).abs() < 1e-9);
+        assert!((preview.max_loss_usd - 20.0).abs() < 1e-9);
+        assert!((preview.daily_pnl_pct_at_stop + 0.02).abs() < 1e-9);
+        assert_eq!(preview.num_positions, 2);
+        assert!(!rm.breaches_daily_drawdown(&preview));
+        assert!(rm.breaches_daily_drawdown(&rm.preview_open(&signal, 250.0)));
+
+        assert_eq!(rm.positions.len(), 1);
+        assert!((rm.portfolio.available_capital - 900.0).abs() < 1e-9);
+    }
 }
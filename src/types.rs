@@ -1,4 +1,8 @@
-use serde::Deserialize;
+use crate::regime::Regime;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use thiserror::Error;
 
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -6,6 +10,146 @@ pub struct TickData {
     pub symbol: String,
     pub price: f64,
     pub volume: f64,
+    /// Milliseconds since the epoch; stamped on receipt if the feed omits it
+    #[serde(default, alias = "ts")]
+    pub timestamp: i64,
+    /// Feed the tick came from, set on ingestion
+    #[serde(default)]
+    pub source: String,
+    /// Subscribed market the tick belongs to (e.g. `BONK/SOL`), set on ingestion
+    #[serde(default)]
+    pub market: String,
+}
+
+/// Why a tick was dropped before reaching storage or features
+#[derive(Clone, Debug, PartialEq, Error)]
+pub enum TickRejection {
+    #[error("symbol {got} doesn't match subscribed market {expected}")]
+    WrongMarket { expected: String, got: String },
+    #[error("timestamp {timestamp} is before the previous tick at {last}")]
+    OutOfOrder { timestamp: i64, last: i64 },
+}
+
+impl TickRejection {
+    /// Short label for metrics
+    pub fn label(&self) -> &'static str {
+        match self {
+            TickRejection::WrongMarket { .. } => "wrong_market",
+            TickRejection::OutOfOrder { .. } => "out_of_order",
+        }
+    }
+}
+
+impl TickData {
+    /// Check the tick is for `expected_market` (e.g. `BONK/SOL`).
+    /// The full pair or just its base symbol is accepted, ignoring case.
+    pub fn validate(&self, expected_market: &str) -> Result<(), TickRejection> {
+        let base = expected_market.split('/').next().unwrap_or(expected_market);
+        if self.symbol.eq_ignore_ascii_case(expected_market) || self.symbol.eq_ignore_ascii_case(base) {
+            Ok(())
+        } else {
+            Err(TickRejection::WrongMarket {
+                expected: expected_market.to_string(),
+                got: self.symbol.clone(),
+            })
+        }
+    }
+
+    /// The first of `markets` this tick's symbol belongs to, used to tag it on ingestion
+    pub fn find_market<'a>(&self, markets: &'a [String]) -> Option<&'a str> {
+        markets.iter().map(String::as_str).find(|market| self.validate(market).is_ok())
+    }
+
+    /// Check the tick was tagged with one of `markets` and its symbol matches that market
+    pub fn check_market(&self, markets: &[String]) -> Result<(), TickRejection> {
+        if markets.contains(&self.market) {
+            self.validate(&self.market)
+        } else {
+            Err(TickRejection::WrongMarket {
+                expected: markets.join(", "),
+                got: self.symbol.clone(),
+            })
+        }
+    }
+
+    /// Check the tick doesn't go back in time from the last accepted one.
+    /// Equal timestamps are allowed; feeds batch several trades per millisecond.
+    pub fn check_order(&self, last_timestamp: Option<i64>) -> Result<(), TickRejection> {
+        match last_timestamp {
+            Some(last) if self.timestamp < last => Err(TickRejection::OutOfOrder {
+                timestamp: self.timestamp,
+                last,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// When the tick was observed, mapped onto the monotonic clock from its timestamp.
+    /// A timestamp in the future is treated as now.
+    pub fn observed_at(&self, now: Instant, now_ms: i64) -> Instant {
+        let age_ms = u64::try_from(now_ms.saturating_sub(self.timestamp)).unwrap_or(0);
+        now.checked_sub(Duration::from_millis(age_ms)).unwrap_or(now)
+    }
+}
+
+/// OHLC bar over some fixed interval
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candle {
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+/// Rolls one market's ticks into fixed-interval candles by tick timestamp,
+/// keeping the most recent `capacity` closed ones
+#[derive(Clone, Debug)]
+pub struct CandleSeries {
+    interval_ms: i64,
+    capacity: usize,
+    /// Bucket start and bar still being built
+    current: Option<(i64, Candle)>,
+    closed: VecDeque<Candle>,
+}
+
+impl CandleSeries {
+    pub fn new(interval: Duration, capacity: usize) -> Self {
+        Self {
+            interval_ms: (interval.as_millis() as i64).max(1),
+            capacity,
+            current: None,
+            closed: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Add a tick; returns true when it started a new interval and so closed the previous bar
+    pub fn push(&mut self, price: f64, timestamp_ms: i64) -> bool {
+        let bucket = timestamp_ms - timestamp_ms.rem_euclid(self.interval_ms);
+        match &mut self.current {
+            Some((start, bar)) if *start == bucket => {
+                bar.high = bar.high.max(price);
+                bar.low = bar.low.min(price);
+                bar.close = price;
+                false
+            }
+            current => {
+                let finished = current.replace((bucket, Candle { open: price, high: price, low: price, close: price }));
+                let Some((_, bar)) = finished else {
+                    return false;
+                };
+                if self.closed.len() == self.capacity {
+                    self.closed.pop_front();
+                }
+                self.closed.push_back(bar);
+                true
+            }
+        }
+    }
+
+    /// Closed candles, oldest first
+    pub fn candles(&self) -> Vec<Candle> {
+        self.closed.iter().cloned().collect()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -25,21 +169,360 @@ impl PatternMetadata {
     }
 }
 
+/// A stored pattern returned by a similarity search
 #[derive(Clone, Debug)]
+pub struct SimilarPattern {
+    pub metadata: PatternMetadata,
+    pub score: f32,
+}
+
+/// Shape of [`Signal`] written today. Bump it when a field changes meaning or
+/// is removed; new fields only need `#[serde(default)]`.
+pub const SIGNAL_SCHEMA_VERSION: u32 = 1;
+
+/// A model prediction for one market
+///
+/// Persisted and passed around as JSON, so it has to keep reading older
+/// shapes: anything added after the first version defaults when missing, a
+/// missing `version` means the signal predates versioning (0), and fields
+/// from newer versions are ignored.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Signal {
+    #[serde(default)]
+    pub version: u32,
+    /// Market the signal was produced for
+    #[serde(default)]
+    pub market: String,
     pub confidence: f32,
+    /// Autoencoder reconstruction error the signal was derived from
+    #[serde(default)]
+    pub anomaly_score: f32,
+    /// Multiplier on the position size, below 1.0 when the market regime
+    /// calls for trading smaller
+    #[serde(default = "full_size")]
+    pub size_factor: f64,
+}
+
+fn full_size() -> f64 {
+    1.0
+}
+
+impl Signal {
+    pub fn new(market: String, confidence: f32, anomaly_score: f32) -> Self {
+        Signal {
+            version: SIGNAL_SCHEMA_VERSION,
+            market,
+            confidence,
+            anomaly_score,
+            size_factor: full_size(),
+        }
+    }
+}
+
+/// Why a signal was kept from reaching execution
+#[derive(Clone, Debug, PartialEq, Error)]
+pub enum SignalRejection {
+    #[error("confidence {confidence:.3} not above {min:.3}")]
+    LowConfidence { confidence: f32, min: f32 },
+    #[error("only {count} similar patterns, need {min}")]
+    TooFewPatterns { count: usize, min: usize },
+    #[error("volatility {volatility:.4} above {max:.4}")]
+    HighVolatility { volatility: f64, max: f64 },
+    #[error("market anomalous, trading suppressed for {remaining_secs}s")]
+    AnomalyCooldown { remaining_secs: u64 },
+    #[error("{regime} regime (volatility {volatility:.4}), standing down")]
+    RegimeStandDown { regime: Regime, volatility: f64 },
+}
+
+impl SignalRejection {
+    /// Short label for metrics
+    pub fn label(&self) -> &'static str {
+        match self {
+            SignalRejection::LowConfidence { .. } => "low_confidence",
+            SignalRejection::TooFewPatterns { .. } => "too_few_patterns",
+            SignalRejection::HighVolatility { .. } => "high_volatility",
+            SignalRejection::AnomalyCooldown { .. } => "anomaly_cooldown",
+            SignalRejection::RegimeStandDown { .. } => "regime_stand_down",
+        }
+    }
+}
+
+/// Thresholds a signal has to clear before it is traded
+#[derive(Clone, Debug)]
+pub struct SignalGate {
+    pub min_confidence: f32,
+    /// Similar historical patterns needed to back the signal
+    pub min_patterns: usize,
+    /// Highest per-tick return volatility to trade into (0 = no limit)
+    pub max_volatility: f64,
+}
+
+impl SignalGate {
+    pub fn check(&self, signal: &Signal, patterns: usize, volatility: f64) -> Result<(), SignalRejection> {
+        if signal.confidence <= self.min_confidence {
+            return Err(SignalRejection::LowConfidence {
+                confidence: signal.confidence,
+                min: self.min_confidence,
+            });
+        }
+        if patterns < self.min_patterns {
+            return Err(SignalRejection::TooFewPatterns {
+                count: patterns,
+                min: self.min_patterns,
+            });
+        }
+        if self.max_volatility > 0.0 && volatility > self.max_volatility {
+            return Err(SignalRejection::HighVolatility {
+                volatility,
+                max: self.max_volatility,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Suppresses trading in a market for `cooldown` after its anomaly score
+/// exceeds `threshold`; a reconstruction error spike usually means the regime
+/// changed and the stored patterns no longer apply.
+#[derive(Clone, Debug)]
+pub struct AnomalyGuard {
+    /// Score above which a market is anomalous (0 = off)
+    pub threshold: f32,
+    pub cooldown: Duration,
+    /// Per market: when suppression ends and the score that triggered it
+    suppressed: HashMap<String, (Instant, f32)>,
+}
+
+/// A market currently suppressed by the `AnomalyGuard`
+#[derive(Clone, Debug, Serialize)]
+pub struct AnomalousMarket {
+    pub market: String,
+    pub anomaly_score: f32,
+    pub remaining_secs: u64,
+}
+
+impl AnomalyGuard {
+    pub fn new(threshold: f32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            suppressed: HashMap::new(),
+        }
+    }
+
+    /// Record `score` for `market`, starting (or extending) its cooldown if it is
+    /// above the threshold. Returns whether the market became anomalous.
+    pub fn observe(&mut self, market: &str, score: f32, now: Instant) -> bool {
+        if self.threshold <= 0.0 || score <= self.threshold {
+            return false;
+        }
+        let already = self.remaining(market, now).is_some();
+        self.suppressed.insert(market.to_string(), (now + self.cooldown, score));
+        !already
+    }
+
+    /// Time left on `market`'s cooldown, if it is in one
+    pub fn remaining(&self, market: &str, now: Instant) -> Option<Duration> {
+        self.suppressed
+            .get(market)
+            .map(|(until, _)| until.saturating_duration_since(now))
+            .filter(|left| !left.is_zero())
+    }
+
+    pub fn check(&self, market: &str, now: Instant) -> Result<(), SignalRejection> {
+        match self.remaining(market, now) {
+            Some(left) => Err(SignalRejection::AnomalyCooldown {
+                remaining_secs: left.as_secs().max(1),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Markets still in their cooldown
+    pub fn anomalous(&self, now: Instant) -> Vec<AnomalousMarket> {
+        let mut markets: Vec<_> = self
+            .suppressed
+            .iter()
+            .filter_map(|(market, (_, score))| {
+                self.remaining(market, now).map(|left| AnomalousMarket {
+                    market: market.clone(),
+                    anomaly_score: *score,
+                    remaining_secs: left.as_secs(),
+                })
+            })
+            .collect();
+        markets.sort_by(|a, b| a.market.cmp(&b.market));
+        markets
+    }
 }
 
-pub fn analyze_pattern(_similar: &Vec<(Vec<f32>, f32)>, _score: f32) -> Signal {
-    // Compute confidence based on average similarity scores and anomaly score.
-    let avg_sim: f32 = if !_similar.is_empty() {
-        _similar.iter().map(|(_, s)| *s).sum::<f32>() / _similar.len() as f32
+pub fn analyze_pattern(similar: &[SimilarPattern], score: f32) -> Signal {
+    // Compute confidence from the similarity of the matches, weighting each
+    // match by its own score so close matches dominate weak ones.
+    let total_sim: f32 = similar.iter().map(|m| m.score.max(0.0)).sum();
+    let avg_sim: f32 = if total_sim > 0.0 {
+        similar.iter().map(|m| m.score.max(0.0).powi(2)).sum::<f32>() / total_sim
     } else {
         0.0
     };
     // Higher anomaly score -> lower confidence, so invert score (assuming normalized <=1)
-    let anomaly_factor = (1.0_f32 - _score).max(0.0);
+    let anomaly_factor = (1.0_f32 - score).max(0.0);
     let confidence = (avg_sim * anomaly_factor).clamp(0.0, 1.0);
-    crate::metrics::SIGNALS_EMITTED.inc_by((confidence * 1_000_000.0) as u64);
-    Signal { confidence }
+    Signal::new(String::new(), confidence, score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tick(symbol: &str, timestamp: i64) -> TickData {
+        TickData {
+            symbol: symbol.to_string(),
+            price: 1.0,
+            volume: 1.0,
+            timestamp,
+            source: "test".to_string(),
+            market: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_validate_market() {
+        assert!(tick("BONK/SOL", 1).validate("BONK/SOL").is_ok());
+        assert!(tick("bonk", 1).validate("BONK/SOL").is_ok());
+        assert_eq!(tick("WIF", 1).validate("BONK/SOL").unwrap_err().label(), "wrong_market");
+    }
+
+    #[test]
+    fn test_candle_series_closes_bars_per_interval() {
+        let mut series = CandleSeries::new(Duration::from_secs(60), 2);
+        assert!(!series.push(1.0, 0));
+        assert!(!series.push(1.5, 30_000));
+        assert!(!series.push(0.8, 59_999));
+        assert!(series.candles().is_empty());
+
+        assert!(series.push(1.2, 60_000));
+        assert_eq!(series.candles(), [Candle { open: 1.0, high: 1.5, low: 0.8, close: 0.8 }]);
+
+        // A gap still closes just the one bar that was open; only `capacity` are kept
+        assert!(series.push(2.0, 300_000));
+        assert!(series.push(3.0, 360_000));
+        let candles = series.candles();
+        assert_eq!(candles.len(), 2);
+        assert_eq!(candles[0].close, 1.2);
+        assert_eq!(candles[1].close, 2.0);
+    }
+
+    #[test]
+    fn test_observed_at_uses_tick_timestamp() {
+        let now = Instant::now() + Duration::from_secs(60);
+        assert_eq!(tick("BONK", 10_000).observed_at(now, 12_500), now - Duration::from_millis(2_500));
+        assert_eq!(tick("BONK", 13_000).observed_at(now, 12_500), now);
+    }
+
+    #[test]
+    fn test_tag_market() {
+        let markets = vec!["BONK/SOL".to_string(), "WIF/SOL".to_string()];
+        let mut wif = tick("wif", 1);
+        assert_eq!(wif.find_market(&markets), Some("WIF/SOL"));
+        assert_eq!(tick("POPCAT", 1).find_market(&markets), None);
+
+        // Untagged ticks are rejected even when the symbol would match
+        assert_eq!(wif.check_market(&markets).unwrap_err().label(), "wrong_market");
+        wif.market = "WIF/SOL".to_string();
+        assert!(wif.check_market(&markets).is_ok());
+        wif.market = "BONK/SOL".to_string();
+        assert!(wif.check_market(&markets).is_err());
+    }
+
+    #[test]
+    fn test_check_order() {
+        assert!(tick("BONK", 100).check_order(None).is_ok());
+        assert!(tick("BONK", 100).check_order(Some(100)).is_ok());
+        assert_eq!(
+            tick("BONK", 99).check_order(Some(100)),
+            Err(TickRejection::OutOfOrder { timestamp: 99, last: 100 })
+        );
+    }
+
+    #[test]
+    fn test_signal_gate() {
+        let gate = SignalGate {
+            min_confidence: 0.8,
+            min_patterns: 2,
+            max_volatility: 0.05,
+        };
+        let strong = Signal::new("BONK/SOL".to_string(), 0.9, 0.1);
+
+        assert!(gate.check(&strong, 3, 0.01).is_ok());
+        assert_eq!(gate.check(&Signal { confidence: 0.8, ..strong.clone() }, 3, 0.01).unwrap_err().label(), "low_confidence");
+        assert_eq!(
+            gate.check(&strong, 1, 0.01),
+            Err(SignalRejection::TooFewPatterns { count: 1, min: 2 })
+        );
+        assert_eq!(gate.check(&strong, 3, 0.2).unwrap_err().label(), "high_volatility");
+
+        let no_limit = SignalGate { max_volatility: 0.0, ..gate };
+        assert!(no_limit.check(&strong, 3, 0.2).is_ok());
+    }
+
+    #[test]
+    fn test_anomaly_guard() {
+        let mut guard = AnomalyGuard::new(0.5, Duration::from_secs(60));
+        let start = Instant::now();
+
+        assert!(!guard.observe("BONK/SOL", 0.2, start));
+        assert!(guard.check("BONK/SOL", start).is_ok());
+
+        assert!(guard.observe("BONK/SOL", 0.9, start));
+        // A second spike extends the cooldown without re-reporting it
+        assert!(!guard.observe("BONK/SOL", 0.7, start + Duration::from_secs(30)));
+        assert_eq!(
+            guard.check("BONK/SOL", start + Duration::from_secs(60)),
+            Err(SignalRejection::AnomalyCooldown { remaining_secs: 30 })
+        );
+        assert!(guard.check("WIF/SOL", start).is_ok());
+        assert_eq!(guard.anomalous(start + Duration::from_secs(60))[0].anomaly_score, 0.7);
+
+        assert!(guard.check("BONK/SOL", start + Duration::from_secs(90)).is_ok());
+        assert!(guard.anomalous(start + Duration::from_secs(90)).is_empty());
+
+        let mut off = AnomalyGuard::new(0.0, Duration::from_secs(60));
+        assert!(!off.observe("BONK/SOL", 10.0, start));
+    }
+
+    #[test]
+    fn test_tick_timestamp_defaults() {
+        let tick: TickData = serde_json::from_str(r#"{"symbol":"BONK","price":1.0,"volume":2.0}"#).unwrap();
+        assert_eq!(tick.timestamp, 0);
+        assert!(tick.source.is_empty());
+
+        let tick: TickData =
+            serde_json::from_str(r#"{"symbol":"BONK","price":1.0,"volume":2.0,"ts":1700000000000}"#).unwrap();
+        assert_eq!(tick.timestamp, 1_700_000_000_000);
+    }
+
+    #[test]
+    fn test_signal_reads_older_and_newer_shapes() {
+        // Before markets and anomaly scores were recorded
+        let old: Signal = serde_json::from_str(r#"{"confidence":0.8}"#).unwrap();
+        assert_eq!(old.version, 0);
+        assert!(old.market.is_empty());
+        assert_eq!(old.confidence, 0.8);
+        assert_eq!(old.anomaly_score, 0.0);
+        assert_eq!(old.size_factor, 1.0);
+
+        // A later version's extra fields are skipped
+        let newer: Signal = serde_json::from_str(
+            r#"{"version":2,"market":"BONK/SOL","confidence":0.7,"anomaly_score":0.2,"predicted_return":0.05}"#,
+        )
+        .unwrap();
+        assert_eq!(newer.version, 2);
+        assert_eq!(newer.market, "BONK/SOL");
+
+        let current = Signal::new("WIF/SOL".to_string(), 0.9, 0.1);
+        let round_trip: Signal = serde_json::from_str(&serde_json::to_string(&current).unwrap()).unwrap();
+        assert_eq!(round_trip.version, SIGNAL_SCHEMA_VERSION);
+        assert_eq!(round_trip.market, "WIF/SOL");
+    }
 }
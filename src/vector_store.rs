@@ -1,17 +1,59 @@
-use crate::types::PatternMetadata;
-use anyhow::Result;
+use crate::types::{PatternMetadata, SimilarPattern};
+use anyhow::{anyhow, Result};
 use qdrant_client::{
-    qdrant::{PointStruct, SearchPointsBuilder, UpsertPointsBuilder},
+    qdrant::{
+        Condition, CountPointsBuilder, CreateCollectionBuilder, DeletePointsBuilder, Distance,
+        Filter, PointStruct, Range, SearchPointsBuilder, UpsertPointsBuilder,
+        VectorParamsBuilder,
+    },
     Qdrant,
 };
 use serde_json::Value;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
 use uuid::Uuid;
 
+/// Similarity metric used to score matches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DistanceMetric {
+    /// Cosine similarity in [-1, 1], independent of embedding magnitude
+    Cosine,
+    /// Raw dot product; unbounded, so thresholds depend on the embedding scale
+    Dot,
+}
+
+impl DistanceMetric {
+    fn to_qdrant(self) -> Distance {
+        match self {
+            DistanceMetric::Cosine => Distance::Cosine,
+            DistanceMetric::Dot => Distance::Dot,
+        }
+    }
+}
+
+impl FromStr for DistanceMetric {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "cosine" => Ok(DistanceMetric::Cosine),
+            "dot" => Ok(DistanceMetric::Dot),
+            other => Err(anyhow!("unknown distance metric '{}' (expected cosine or dot)", other)),
+        }
+    }
+}
+
 /// Client for vector storage and similarity search (e.g., Qdrant).
 #[derive(Clone)]
 pub struct VectorStore {
     client: Qdrant,
     collection: String,
+    metric: DistanceMetric,
+    /// Skip inserts whose nearest neighbour scores within this of a perfect match
+    dedup_epsilon: Option<f32>,
+    /// Set once the collection is known to exist
+    collection_ready: Arc<OnceCell<()>>,
 }
 
 impl VectorStore {
@@ -21,46 +63,211 @@ impl VectorStore {
         Ok(Self {
             client,
             collection: "memecoin_patterns".into(),
+            metric: DistanceMetric::Cosine,
+            dedup_epsilon: None,
+            collection_ready: Arc::new(OnceCell::new()),
         })
     }
 
-    /// Insert an embedding with associated metadata.
-    pub async fn insert_pattern(
+    /// Score matches with `metric`. Only applies when this client creates the
+    /// collection; an existing collection keeps the metric it was created with.
+    pub fn with_metric(mut self, metric: DistanceMetric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// Skip inserting a pattern when the nearest stored vector scores at least
+    /// `1.0 - epsilon`, so repeated ticks don't pile up near-duplicates.
+    /// Only applies under the cosine metric, where 1.0 is an identical
+    /// direction; dot scores have no fixed top, so the extra search is skipped.
+    pub fn with_dedup(mut self, epsilon: f32) -> Self {
+        self.dedup_epsilon = Some(epsilon);
+        self
+    }
+
+    /// Create the collection on first use, sized to the embedding dimension.
+    async fn ensure_collection(&self, dim: usize) -> Result<()> {
+        self.collection_ready
+            .get_or_try_init(|| async {
+                if !self.client.collection_exists(&self.collection).await? {
+                    self.client
+                        .create_collection(
+                            CreateCollectionBuilder::new(&self.collection).vectors_config(
+                                VectorParamsBuilder::new(dim as u64, self.metric.to_qdrant()),
+                            ),
+                        )
+                        .await?;
+                    tracing::info!(
+                        "Created Qdrant collection {} ({} dims, {:?})",
+                        self.collection,
+                        dim,
+                        self.metric
+                    );
+                }
+                Ok::<_, anyhow::Error>(())
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Insert several embeddings in one upsert, returning how many were stored.
+    /// With dedup enabled, patterns close to a stored vector or to an earlier
+    /// one in the same batch are dropped.
+    pub async fn insert_patterns_batch(
         &self,
-        embedding: &[f32],
-        metadata: &PatternMetadata,
-    ) -> Result<()> {
-        let payload: serde_json::Map<String, Value> = serde_json::json!({
-            "symbol": metadata.symbol,
-            "price": metadata.price,
-            "volume": metadata.volume,
-        })
-        .as_object()
-        .cloned()
-        .unwrap_or_default();
-        let point = PointStruct::new(Uuid::new_v4().to_string(), embedding.to_vec(), payload);
-        let upsert = UpsertPointsBuilder::new(&self.collection, vec![point]).build();
-        self.client.upsert_points(upsert).await?;
+        patterns: &[(Vec<f32>, PatternMetadata)],
+    ) -> Result<usize> {
+        let Some((first, _)) = patterns.first() else {
+            return Ok(0);
+        };
+        self.ensure_collection(first.len()).await?;
+
+        let timestamp = chrono::Utc::now().timestamp();
+        let dedup_epsilon = self.dedup_epsilon.filter(|_| self.metric == DistanceMetric::Cosine);
+        let mut accepted: Vec<&[f32]> = Vec::new();
+        let mut points = Vec::with_capacity(patterns.len());
+        for (embedding, metadata) in patterns {
+            if let Some(epsilon) = dedup_epsilon {
+                let min_score = 1.0 - epsilon;
+                let in_batch = accepted
+                    .iter()
+                    .any(|other| self.score(other, embedding) >= min_score);
+                if in_batch
+                    || !self
+                        .find_similar_above(embedding, 1, min_score)
+                        .await?
+                        .is_empty()
+                {
+                    continue;
+                }
+                accepted.push(embedding);
+            }
+
+            let payload: serde_json::Map<String, Value> = serde_json::json!({
+                "symbol": metadata.symbol,
+                "price": metadata.price,
+                "volume": metadata.volume,
+                "timestamp": timestamp,
+            })
+            .as_object()
+            .cloned()
+            .unwrap_or_default();
+            points.push(PointStruct::new(
+                Uuid::new_v4().to_string(),
+                embedding.clone(),
+                payload,
+            ));
+        }
+
+        let inserted = points.len();
+        if inserted > 0 {
+            let upsert = UpsertPointsBuilder::new(&self.collection, points).build();
+            self.client.upsert_points(upsert).await?;
+        }
+        Ok(inserted)
+    }
+
+    /// Number of stored patterns.
+    pub async fn count(&self) -> Result<u64> {
+        let response = self
+            .client
+            .count(CountPointsBuilder::new(&self.collection).exact(true))
+            .await?;
+        Ok(response.result.map(|r| r.count).unwrap_or(0))
+    }
+
+    /// Delete patterns inserted before `ts` (unix seconds).
+    /// Points stored without a timestamp can't be aged, so they go too.
+    pub async fn prune_older_than(&self, ts: i64) -> Result<()> {
+        let older = Filter::should([
+            Condition::range(
+                "timestamp",
+                Range {
+                    lt: Some(ts as f64),
+                    ..Default::default()
+                },
+            ),
+            Condition::is_empty("timestamp"),
+        ]);
+        self.client
+            .delete_points(DeletePointsBuilder::new(&self.collection).points(older))
+            .await?;
         Ok(())
     }
 
+    /// Similarity of two embeddings under the configured metric.
+    fn score(&self, a: &[f32], b: &[f32]) -> f32 {
+        let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+        match self.metric {
+            DistanceMetric::Dot => dot,
+            DistanceMetric::Cosine => {
+                let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+                let denom = norm(a) * norm(b);
+                if denom > 0.0 {
+                    dot / denom
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+
     /// Find similar embeddings to the query.
     pub async fn find_similar(
         &self,
         embedding: &[f32],
         limit: usize,
-    ) -> Result<Vec<(Vec<f32>, f32)>> {
+    ) -> Result<Vec<SimilarPattern>> {
+        self.search(SearchPointsBuilder::new(
+            &self.collection,
+            embedding.to_vec(),
+            limit as u64,
+        ))
+        .await
+    }
+
+    /// Find up to `limit` embeddings scoring at least `min_score`.
+    /// In a sparse store the top-k are often noise; this drops them.
+    pub async fn find_similar_above(
+        &self,
+        embedding: &[f32],
+        limit: usize,
+        min_score: f32,
+    ) -> Result<Vec<SimilarPattern>> {
+        self.search(
+            SearchPointsBuilder::new(&self.collection, embedding.to_vec(), limit as u64)
+                .score_threshold(min_score),
+        )
+        .await
+    }
+
+    async fn search(&self, search: SearchPointsBuilder) -> Result<Vec<SimilarPattern>> {
         let result = self
             .client
-            .search_points(
-                SearchPointsBuilder::new(&self.collection, embedding.to_vec(), limit as u64)
-                    .with_payload(true),
-            )
+            .search_points(search.with_payload(true))
             .await?;
         Ok(result
             .result
             .into_iter()
-            .map(|p| (Vec::new(), p.score))
+            .map(|p| {
+                let text = |key: &str| {
+                    p.payload
+                        .get(key)
+                        .and_then(|v| v.as_str())
+                        .cloned()
+                        .unwrap_or_default()
+                };
+                let number =
+                    |key: &str| p.payload.get(key).and_then(|v| v.as_double()).unwrap_or(0.0);
+                SimilarPattern {
+                    metadata: PatternMetadata {
+                        symbol: text("symbol"),
+                        price: number("price"),
+                        volume: number("volume"),
+                    },
+                    score: p.score,
+                }
+            })
             .collect())
     }
 }
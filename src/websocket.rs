@@ -2,38 +2,101 @@ use anyhow::Result;
 use futures_util::sink::SinkExt;
 use futures_util::stream::StreamExt;
 use serde_json::json;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{info, warn};
 
 use crate::metrics::TICKS_RECEIVED;
 
 use crate::types::TickData;
 
-/// Connects to the given WebSocket URL & market, streaming TickData over the channel.
-#[tracing::instrument(name = "websocket_stream", skip(tx, url, market))]
+/// Initial delay before reconnecting after a disconnect.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// Upper bound for the reconnect delay.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// `TickData::source` for ticks from this feed.
+pub const SOURCE: &str = "jupiter_ws";
+
+/// Connects to the given WebSocket URL and subscribes to every market on that one
+/// connection, streaming TickData tagged with its market over the channel.
+///
+/// Reconnects with exponential backoff and re-subscribes whenever the connection
+/// drops. `healthy` is true while a subscribed connection is open. Returns only
+/// once the tick receiver has been dropped.
+#[tracing::instrument(name = "websocket_stream", skip(tx, url, markets, healthy))]
 pub async fn stream_jupiter_websocket(
     url: String,
-    market: String,
+    markets: Vec<String>,
     tx: UnboundedSender<TickData>,
+    healthy: Arc<AtomicBool>,
+) -> Result<()> {
+    let label = markets.join(", ");
+    let mut backoff = INITIAL_BACKOFF;
+    loop {
+        match run_session(&url, &markets, &tx, &healthy).await {
+            Ok(()) => warn!("WebSocket stream for {} closed by server", label),
+            Err(e) => warn!("WebSocket stream for {} disconnected: {}", label, e),
+        }
+        let was_subscribed = healthy.swap(false, Ordering::SeqCst);
+
+        if tx.is_closed() {
+            info!("Tick receiver dropped, stopping WebSocket ingestion");
+            return Ok(());
+        }
+
+        // A session that got as far as subscribing resets the backoff
+        if was_subscribed {
+            backoff = INITIAL_BACKOFF;
+        }
+
+        info!("Reconnecting to {} in {:?}", url, backoff);
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+/// Run a single connect → subscribe → read session until the stream ends.
+async fn run_session(
+    url: &str,
+    markets: &[String],
+    tx: &UnboundedSender<TickData>,
+    healthy: &AtomicBool,
 ) -> Result<()> {
-    let (ws_stream, _) = connect_async(&url).await?;
+    let (ws_stream, _) = connect_async(url).await?;
     let (mut write, mut read) = ws_stream.split();
 
-    // Subscribe to memecoin ticker
-    let subscribe = json!({
-        "op": "subscribe",
-        "channel": "ticker",
-        "market": market,
-    });
-    write.send(Message::Text(subscribe.to_string())).await?;
+    // Subscribe to each memecoin ticker
+    for market in markets {
+        let subscribe = json!({
+            "op": "subscribe",
+            "channel": "ticker",
+            "market": market,
+        });
+        write.send(Message::Text(subscribe.to_string())).await?;
+        info!("Subscribed to {} ticker on {}", market, url);
+    }
+    healthy.store(true, Ordering::SeqCst);
 
     // Read loop
     while let Some(msg) = read.next().await {
         let msg = msg?;
         if let Message::Text(text) = msg {
-            if let Ok(tick) = serde_json::from_str::<TickData>(&text) {
+            if let Ok(mut tick) = serde_json::from_str::<TickData>(&text) {
                 TICKS_RECEIVED.inc();
-                let _ = tx.send(tick);
+                if tick.timestamp == 0 {
+                    tick.timestamp = chrono::Utc::now().timestamp_millis();
+                }
+                tick.source = SOURCE.to_string();
+                // Left untagged if it matches none, so validation drops it
+                if let Some(market) = tick.find_market(markets) {
+                    tick.market = market.to_string();
+                }
+                if tx.send(tick).is_err() {
+                    return Ok(());
+                }
             }
         }
     }